@@ -5,6 +5,8 @@ struct AdvancedOptionsView: View {
     @State private var imageCacheStats = CacheStatistics(count: 0, sizeMB: 0.0)
     @State private var searchCacheStats = CacheStatistics(count: 0, sizeMB: 0.0)
     @State private var cardNamesCacheStats = CacheStatistics(count: 0, sizeMB: 0.0)
+    @State private var cacheRefreshStatus = "Unknown"
+    @State private var cacheFreshness: CacheFreshnessInfo?
     @State private var isUpdatingCardNames = false
     @State private var errorMessage: String?
     @State private var successMessage: String?
@@ -65,7 +67,10 @@ struct AdvancedOptionsView: View {
                             stats: [
                                 ("Card names", "\(cardNamesCacheStats.count) items"),
                                 ("Database size estimate", String(format: "%.1f MB", cardNamesCacheStats.sizeMB)),
-                                ("Status", cardNamesCacheStats.count > 0 ? "Loaded" : "Not loaded")
+                                ("Status", cardNamesCacheStats.count > 0 ? "Loaded" : "Not loaded"),
+                                ("Card names updated", freshnessLabel(cacheFreshness?.cardNamesUpdatedAt, stale: cacheFreshness?.cardNamesStale ?? true)),
+                                ("Set codes updated", freshnessLabel(cacheFreshness?.setCodesUpdatedAt, stale: cacheFreshness?.setCodesStale ?? true)),
+                                ("Automatic refresh", cacheRefreshStatus)
                             ],
                             locationPath: ProxyGenerator.getCardNamesCachePath() ?? "Unknown",
                             color: .green,
@@ -113,6 +118,16 @@ struct AdvancedOptionsView: View {
         imageCacheStats = ProxyGenerator.getImageCacheStats()
         searchCacheStats = ProxyGenerator.getSearchCacheStats()
         cardNamesCacheStats = ProxyGenerator.getCardNamesCacheStats()
+        cacheRefreshStatus = ProxyGenerator.getCacheRefreshStatus()
+        cacheFreshness = ProxyGenerator.getCacheFreshness()
+    }
+    
+    private func freshnessLabel(_ updatedAt: Date?, stale: Bool) -> String {
+        guard let updatedAt = updatedAt else {
+            return "Never"
+        }
+        let date = updatedAt.formatted(date: .abbreviated, time: .shortened)
+        return stale ? "\(date) (outdated)" : date
     }
     
     private func clearImageCache() {
@@ -140,14 +155,14 @@ struct AdvancedOptionsView: View {
         
         // Run on background queue since this is a network operation
         DispatchQueue.global(qos: .userInitiated).async {
-            let result = ProxyGenerator.updateCardNames()
+            let result = ProxyGenerator.updateCardNames().flatMap { ProxyGenerator.updateSetCodes() }
             
             DispatchQueue.main.async {
                 isUpdatingCardNames = false
                 
                 switch result {
                 case .success:
-                    successMessage = "Card names database updated successfully"
+                    successMessage = "Card names and set codes updated successfully"
                     errorMessage = nil
                     loadCacheStatistics()
                     
@@ -157,7 +172,7 @@ struct AdvancedOptionsView: View {
                     }
                     
                 case .failure(let error):
-                    errorMessage = "Failed to update card names: \(error.localizedDescription)"
+                    errorMessage = "Failed to update card names and set codes: \(error.localizedDescription)"
                     successMessage = nil
                 }
             }
@@ -416,6 +416,20 @@ struct PrintSelectionModal: View {
                         .font(.caption)
                         .foregroundColor(.secondary)
                     
+                    // Rules text is the same for every printing, take it from the selected one
+                    if let details = availablePrintings.first(where: { $0.isSamePrinting(as: currentCard) }) ?? availablePrintings.first,
+                       let typeLine = details.typeLine {
+                        Text([typeLine, details.manaCost].compactMap { $0 }.joined(separator: "  "))
+                            .font(.caption)
+                            .fontWeight(.medium)
+                        if let oracleText = details.oracleText {
+                            Text(oracleText)
+                                .font(.caption)
+                                .foregroundColor(.secondary)
+                                .lineLimit(6)
+                        }
+                    }
+                    
                     Text("\(availablePrintings.count) printings available")
                         .font(.caption)
                         .foregroundColor(.blue)
@@ -432,7 +446,7 @@ struct PrintSelectionModal: View {
                         ForEach(Array(availablePrintings.enumerated()), id: \.offset) { index, printing in
                             PrintingThumbnailView(
                                 printing: printing,
-                                isSelected: printing.set == currentCard.set && printing.language == currentCard.language,
+                                isSelected: printing.isSamePrinting(as: currentCard),
                                 onTap: {
                                     print("🎯 [PrintSelectionModal] onTap triggered for: \(printing.set) (\(printing.language))")
                                     onPrintingSelected(printing)
@@ -751,9 +765,10 @@ struct DynamicGridCardView: View {
         let entry = decklistEntries[decklistEntryIndex]
         print("🔄 [DynamicGridCardView] Before update: '\(entry.name)' set='\(entry.set ?? "nil")', lang='\(entry.language ?? "nil")'")
 
-        // Update the source entry with new set/language (desktop pattern)
+        // Update the source entry with new printing id/set/language (desktop pattern)
         decklistEntries[decklistEntryIndex].set = newPrinting.set
         decklistEntries[decklistEntryIndex].language = newPrinting.language
+        decklistEntries[decklistEntryIndex].scryfallId = newPrinting.scryfallId
 
         print("🔄 [DynamicGridCardView] After update: set='\(decklistEntries[decklistEntryIndex].set ?? "nil")', lang='\(decklistEntries[decklistEntryIndex].language ?? "nil")'")
 
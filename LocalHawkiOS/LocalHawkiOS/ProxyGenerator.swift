@@ -14,6 +14,14 @@ struct CacheStatistics {
     let sizeMB: Double
 }
 
+/// When card names and set codes were last fetched (nil if not cached)
+struct CacheFreshnessInfo {
+    let cardNamesUpdatedAt: Date?
+    let cardNamesStale: Bool
+    let setCodesUpdatedAt: Date?
+    let setCodesStale: Bool
+}
+
 // MARK: - Print Selection Data Models
 
 enum DoubleFaceMode: Int32, CaseIterable {
@@ -37,15 +45,17 @@ struct DecklistEntryData {
     var language: String? // Make mutable for print selection updates
     let faceMode: DoubleFaceMode
     let sourceLineNumber: Int32?
+    var scryfallId: String? // Pinned printing, takes precedence over set and language
     
     init(multiple: Int32, name: String, set: String? = nil, language: String? = nil, 
-         faceMode: DoubleFaceMode = .bothSides, sourceLineNumber: Int32? = nil) {
+         faceMode: DoubleFaceMode = .bothSides, sourceLineNumber: Int32? = nil, scryfallId: String? = nil) {
         self.multiple = multiple
         self.name = name
         self.set = set
         self.language = language
         self.faceMode = faceMode
         self.sourceLineNumber = sourceLineNumber
+        self.scryfallId = scryfallId
     }
 }
 
@@ -64,8 +74,12 @@ struct CardPrintingData: Equatable {
     let backSideURL: String?
     let backSideType: BackSideType    // NEW: Type of back side
     let backSideName: String?         // NEW: Back face name or meld result name
+    let scryfallId: String?           // Unique id of the printing (nil for cards cached without one)
+    var manaCost: String? = nil       // Only filled in for search results
+    var typeLine: String? = nil
+    var oracleText: String? = nil
 
-    init(name: String, set: String, language: String, borderCropURL: String, backSideURL: String? = nil, backSideType: BackSideType = .none, backSideName: String? = nil) {
+    init(name: String, set: String, language: String, borderCropURL: String, backSideURL: String? = nil, backSideType: BackSideType = .none, backSideName: String? = nil, scryfallId: String? = nil) {
         self.name = name
         self.set = set
         self.language = language
@@ -73,6 +87,15 @@ struct CardPrintingData: Equatable {
         self.backSideURL = backSideURL
         self.backSideType = backSideType
         self.backSideName = backSideName
+        self.scryfallId = scryfallId
+    }
+
+    /// Same printing: compares Scryfall ids when both are known, set and language otherwise
+    func isSamePrinting(as other: CardPrintingData) -> Bool {
+        if let id = scryfallId, let otherId = other.scryfallId {
+            return id == otherId
+        }
+        return set == other.set && language == other.language
     }
 }
 
@@ -162,9 +185,11 @@ enum ProxyGeneratorError: Error, LocalizedError {
 
 /// Image cache change notification from Rust
 struct ImageCacheChangeNotification {
-    let changeType: UInt8 // 1=ImageCached, 2=ImageRemoved
-    let imageUrl: String
+    let changeType: UInt8 // 1=ImageCached, 2=ImageRemoved, 3=LoadFinished, 4=LoadAborted
+    let imageUrl: String // Load name for LoadFinished/LoadAborted
     let timestamp: UInt64
+    let completed: Int // Images loaded (LoadFinished/LoadAborted only)
+    let total: Int
 }
 
 class ProxyGenerator {
@@ -239,7 +264,9 @@ class ProxyGenerator {
             let change = ImageCacheChangeNotification(
                 changeType: cChange.change_type,
                 imageUrl: imageUrl,
-                timestamp: cChange.timestamp
+                timestamp: cChange.timestamp,
+                completed: Int(cChange.completed),
+                total: Int(cChange.total)
             )
             changes.append(change)
         }
@@ -275,7 +302,7 @@ class ProxyGenerator {
                 
                 // Notify all listeners
                 for change in changes {
-                    print("🖼️ [ProxyGenerator] Image cache change: \(change.changeType == 1 ? "CACHED" : "REMOVED") - \(change.imageUrl)")
+                    print("🖼️ [ProxyGenerator] Image cache change: \(change.changeType == 1 ? "CACHED" : change.changeType == 2 ? "REMOVED" : "LOAD \(change.completed)/\(change.total)") - \(change.imageUrl)")
                     for (_, callback) in imageCacheListeners {
                         callback(change)
                     }
@@ -462,6 +489,44 @@ class ProxyGenerator {
         return .success(())
     }
     
+    /// Update set codes from Scryfall API
+    static func updateSetCodes() -> Result<Void, ProxyGeneratorError> {
+        print("🔄 [ProxyGenerator] Updating set codes from Scryfall API...")
+        let result = localhawk_update_set_codes()
+        guard result == 0 else {
+            print("❌ [ProxyGenerator] Failed to update set codes with code: \(result)")
+            return .failure(convertErrorCode(result))
+        }
+        print("✅ [ProxyGenerator] Set codes updated successfully")
+        return .success(())
+    }
+    
+    /// Get when card names and set codes were last fetched and whether they are stale
+    static func getCacheFreshness() -> CacheFreshnessInfo {
+        let freshness = localhawk_get_cache_freshness()
+        func date(_ unixTime: Int64) -> Date? {
+            unixTime > 0 ? Date(timeIntervalSince1970: TimeInterval(unixTime)) : nil
+        }
+        return CacheFreshnessInfo(
+            cardNamesUpdatedAt: date(freshness.card_names_updated_at),
+            cardNamesStale: freshness.card_names_stale != 0,
+            setCodesUpdatedAt: date(freshness.set_codes_updated_at),
+            setCodesStale: freshness.set_codes_stale != 0
+        )
+    }
+    
+    /// Get the status of the automatic background refresh of card names and set codes
+    static func getCacheRefreshStatus() -> String {
+        var state: Int32 = 0
+        var message: UnsafeMutablePointer<CChar>? = nil
+        let result = localhawk_get_cache_refresh_status(&state, &message)
+        guard result == 0, let message = message else {
+            return "Unknown"
+        }
+        defer { localhawk_free_string(message) }
+        return String(cString: message)
+    }
+    
     /// Save all in-memory caches to disk
     static func saveCaches() -> Result<Void, ProxyGeneratorError> {
         print("💾 [ProxyGenerator] Saving caches to disk...")
@@ -649,7 +714,8 @@ class ProxyGenerator {
                     borderCropURL: borderCropURL,
                     backSideURL: backBorderCropURL,
                     backSideType: .none,  // This is from CResolvedCard, not enhanced CardPrinting
-                    backSideName: nil     // This is from CResolvedCard, not enhanced CardPrinting
+                    backSideName: nil,    // This is from CResolvedCard, not enhanced CardPrinting
+                    scryfallId: cCard.scryfall_id != nil ? String(cString: cCard.scryfall_id) : nil
                 )
                 
                 let resolvedCard = ResolvedCard(
@@ -795,16 +861,22 @@ class ProxyGenerator {
                 }
             }()
             let backSideName = card.back_name != nil ? String(cString: card.back_name) : nil
+            let scryfallId = card.scryfall_id != nil ? String(cString: card.scryfall_id) : nil
 
-            cards.append(CardPrintingData(
+            var printing = CardPrintingData(
                 name: name,
                 set: set,
                 language: language,
                 borderCropURL: borderCropURL,
                 backSideURL: backSideURL,
                 backSideType: backSideType,
-                backSideName: backSideName
-            ))
+                backSideName: backSideName,
+                scryfallId: scryfallId
+            )
+            printing.manaCost = card.mana_cost != nil ? String(cString: card.mana_cost) : nil
+            printing.typeLine = card.type_line != nil ? String(cString: card.type_line) : nil
+            printing.oracleText = card.oracle_text != nil ? String(cString: card.oracle_text) : nil
+            cards.append(printing)
         }
         
         return .success(CardSearchResultData(cards: cards))
@@ -845,7 +917,8 @@ class ProxyGenerator {
                 set: createCString(entry.set),
                 language: createCString(entry.language),
                 face_mode: entry.faceMode.rawValue,
-                source_line_number: entry.sourceLineNumber ?? -1
+                source_line_number: entry.sourceLineNumber ?? -1,
+                scryfall_id: createCString(entry.scryfallId)
             )
             cEntries.append(cEntry)
         }
@@ -1036,6 +1109,7 @@ class ProxyGenerator {
             let nameCString = strdup(entry.name)
             let setCString = entry.set.flatMap { strdup($0) }
             let languageCString = entry.language.flatMap { strdup($0) }
+            let scryfallIdCString = entry.scryfallId.flatMap { strdup($0) }
             
             // Keep track for cleanup
             if let nameCString = nameCString { 
@@ -1047,6 +1121,9 @@ class ProxyGenerator {
             if let languageCString = languageCString { 
                 allocatedStrings.append(languageCString) 
             }
+            if let scryfallIdCString = scryfallIdCString {
+                allocatedStrings.append(scryfallIdCString)
+            }
             
             let cEntry = DecklistEntry(
                 multiple: entry.multiple,
@@ -1054,7 +1131,8 @@ class ProxyGenerator {
                 set: setCString,
                 language: languageCString,
                 face_mode: entry.faceMode.rawValue,
-                source_line_number: entry.sourceLineNumber ?? -1
+                source_line_number: entry.sourceLineNumber ?? -1,
+                scryfall_id: scryfallIdCString
             )
             
             cEntries.append(cEntry)
@@ -1273,7 +1351,8 @@ class ProxyGenerator {
                 set: createCString(entry.set),
                 language: createCString(entry.language),
                 face_mode: Int32(entry.faceMode.rawValue),
-                source_line_number: entry.sourceLineNumber.map { Int32($0) } ?? -1
+                source_line_number: entry.sourceLineNumber.map { Int32($0) } ?? -1,
+                scryfall_id: createCString(entry.scryfallId)
             )
             cEntries.append(cEntry)
         }
@@ -1318,7 +1397,8 @@ class ProxyGenerator {
                 borderCropURL: String(cString: cCard.border_crop_url),
                 backSideURL: cCard.back_border_crop_url != nil ? String(cString: cCard.back_border_crop_url!) : nil,
                 backSideType: backSideType,
-                backSideName: backSideName
+                backSideName: backSideName,
+                scryfallId: cCard.scryfall_id != nil ? String(cString: cCard.scryfall_id!) : nil
             )
 
             let resolvedCard = ResolvedCard(
@@ -1,5 +1,24 @@
 use clap::{Parser, Subcommand};
-use localhawk_core::{PdfOptions, ProxyGenerator, get_image_cache};
+use localhawk_core::{
+    BackFaceFallback, CacheRefreshStatus, Collection, CopyOrder, DeckSection, DecklistEntry,
+    DoubleFaceMode, Format, FreshnessPolicy, OverwritePolicy, PdfOptions, ProxyGenerator,
+    SortOrder, calibrated_scale_correction, check_legality, clear_decklist_history, command_hook,
+    count_cached_images, estimate_prefetch_time, existing_pdfs, export_image_cache_to, export_logs,
+    force_update_card_lookup, force_update_set_codes, format_eta, format_normalized_decklist,
+    format_price_total, generate_calibration_page, get_cache_dates, get_card_name_cache_info,
+    get_decklist_history, get_decklist_history_path, get_default_printing, get_download_rate_limit,
+    get_freshness_policy, get_image_cache, get_image_cache_contents,
+    get_minimal_scryfall_languages, get_or_fetch_default_printing_id, get_pinned_decks,
+    get_preferred_language, get_set_codes_cache, get_settings, get_usage_stats,
+    get_usage_stats_path, import_image_cache_from, init_logging, is_scryfall_unavailable,
+    parse_deck_name, pin_deck, plan_prefetch, prefetch_images, record_recent_decklist,
+    reset_usage_stats, run_diagnostics, set_back_face_fallback, set_basic_land_sets,
+    set_cache_directory, set_default_printing, set_display_name, set_download_rate_limit,
+    set_favorite_printings, set_freshness_policy, set_image_cache_eviction_policy,
+    set_preferred_language, set_read_only_caches, set_ui_language, unpin_deck, update_settings,
+    wait_for_cache_refresh, write_pdfs,
+};
+use std::io::{IsTerminal, Write};
 use std::path::PathBuf;
 
 #[derive(Parser)]
@@ -8,35 +27,282 @@ use std::path::PathBuf;
 struct Cli {
     #[command(subcommand)]
     command: Commands,
+    /// Refresh card names and set codes when older than this many days
+    #[arg(long, global = true, default_value = "7")]
+    max_cache_age_days: i64,
+    /// Never refresh stale card names and set codes automatically
+    #[arg(long, global = true)]
+    no_auto_refresh: bool,
+    /// Cap image downloads at this many KB/s, e.g. to keep a video call usable (overrides the
+    /// settings)
+    #[arg(long, global = true, value_name = "KB_PER_SEC")]
+    max_download_rate: Option<u64>,
+    /// Also write logs to daily files in the cache directory (always on if enabled in the settings)
+    #[arg(long, global = true)]
+    log_file: bool,
+    /// Keep the image, search and card name caches in this directory (overrides the settings)
+    #[arg(long, global = true, value_name = "DIR")]
+    cache_dir: Option<PathBuf>,
+    /// Only read the caches: save nothing and keep new downloads in memory, e.g. for scripted
+    /// runs while the GUI is open (implies --no-auto-refresh)
+    #[arg(long, global = true)]
+    read_only_cache: bool,
 }
 
 #[derive(Subcommand)]
 enum Commands {
     /// Search for cards
     Search {
-        /// Card name to search for
-        name: String,
+        /// Card name to search for (all printings of that card)
+        #[arg(required_unless_present = "query")]
+        name: Option<String>,
+        /// Raw Scryfall search syntax instead of a name, e.g. "t:goblin e:dom lang:ja"
+        #[arg(long, conflicts_with = "name")]
+        query: Option<String>,
     },
     /// Generate a PDF from a list of card names
     Generate {
         /// Card names (one per line or comma-separated)
         #[arg(short, long)]
         cards: Vec<String>,
-        /// Output PDF file path
-        #[arg(short, long, default_value = "proxies.pdf")]
-        output: PathBuf,
+        /// Output PDF file path (default: named after a leading "Deck: NAME" line, or proxies.pdf)
+        #[arg(short, long, conflicts_with_all = ["output_dir", "name_template"])]
+        output: Option<PathBuf>,
+        /// Directory to write the PDF to, named by --name-template (default: current directory)
+        #[arg(long, value_name = "DIR")]
+        output_dir: Option<PathBuf>,
+        /// File name without extension: {deck} is the deck name, {date} today's date and {part}
+        /// the file number with --pages-per-file (default: from the settings, "{deck}")
+        #[arg(long, value_name = "TEMPLATE")]
+        name_template: Option<String>,
+        /// Replace an existing file with the same name
+        #[arg(long, conflicts_with = "timestamp")]
+        overwrite: bool,
+        /// Keep an existing file with the same name, adding the time to the new file's name
+        #[arg(long)]
+        timestamp: bool,
+        /// Generate the PDF again even when the existing file was made from the same cards and
+        /// options (it is kept otherwise; asked on a terminal)
+        #[arg(long)]
+        regenerate: bool,
+        /// Write one PDF per this many pages, for printers that can't take large files
+        #[arg(long, value_name = "PAGES")]
+        pages_per_file: Option<u32>,
         /// Number of cards per row (default: 3)
         #[arg(long, default_value = "3")]
         cards_per_row: u32,
         /// Number of cards per column (default: 3)
         #[arg(long, default_value = "3")]
         cards_per_column: u32,
+        /// Warn about cards that are banned or not legal in this format (e.g. modern, commander)
+        #[arg(long)]
+        format: Option<Format>,
+        /// Collection export (CSV or plain list); only cards missing from it are generated
+        #[arg(long)]
+        collection: Option<PathBuf>,
+        /// Write PDF/A-1b with an embedded sRGB color profile (for printers that reject the PDF)
+        #[arg(long)]
+        pdf_a: bool,
+        /// Print the name, mana cost, type line, rules text and power/toughness of each card in
+        /// a plain frame instead of its image, without downloading any images
+        #[arg(long)]
+        text_only: bool,
+        /// Fill the empty slots on the last page with these cards, taking turns
+        /// (comma-separated, e.g. "Plains,Island")
+        #[arg(long, value_name = "CARDS", value_delimiter = ',')]
+        fill_with: Vec<String>,
+        /// Order of the cards in the PDF: entered, alphabetical, type or lands-last
+        #[arg(long, value_name = "ORDER", default_value = "entered")]
+        sort: SortOrder,
+        /// Where the copies of a card go: adjacent, or interleaved to print one copy of every
+        /// card before the next copies
+        #[arg(long, value_name = "ORDER", default_value = "adjacent")]
+        copy_order: CopyOrder,
+        /// What to print for back faces without an image, e.g. an unresolved meld result:
+        /// front, skip or placeholder (default: from the settings)
+        #[arg(long, value_name = "FALLBACK")]
+        missing_back: Option<BackFaceFallback>,
+        /// Use the printing scryfall.com shows for each card instead of the first search
+        /// result (default: from the settings)
+        #[arg(long)]
+        default_printing: bool,
+        /// Prefer printings in this language (Scryfall code, e.g. "ja") for cards without a
+        /// language, falling back to English (default: from the settings)
+        #[arg(long, value_name = "LANG")]
+        language: Option<String>,
+        /// Spread basic lands without a set across the different arts of these sets
+        /// (comma-separated set codes, e.g. "unf,zen"; default: from the settings)
+        #[arg(long, value_name = "SETS", value_delimiter = ',')]
+        basic_land_sets: Vec<String>,
+        /// Command to run once the PDF is written, e.g. "lp -d office"; `{}` stands for the
+        /// PDF path, which is appended when the command doesn't contain it
+        #[arg(long, value_name = "COMMAND")]
+        exec: Option<String>,
+        /// List the image URLs of every page instead of downloading images and writing the PDF
+        #[arg(long)]
+        dry_run: bool,
+        /// Also write the resolved cards as a decklist naming each exact printing, e.g.
+        /// "4 Lightning Bolt (2XM) 129 [en]", to share or reuse later ("-" for stdout)
+        #[arg(long, value_name = "FILE")]
+        export_decklist: Option<PathBuf>,
+        /// Keep the deck's images in the image cache under any eviction policy, pinned under the
+        /// name of its leading "Deck: NAME" line (see `cache pinned`)
+        #[arg(long)]
+        pin: bool,
+        /// Download large amounts of images and generate long PDFs without asking first
+        /// (only asked on a terminal)
+        #[arg(short, long)]
+        yes: bool,
+    },
+    /// Write the image cache to a tar archive, to copy it to another machine
+    ExportCache {
+        /// Archive file to write
+        output: PathBuf,
+    },
+    /// Add the images of an archive written by export-cache to the image cache
+    ImportCache {
+        /// Archive file to read
+        input: PathBuf,
+    },
+    /// Show what the image cache holds
+    Cache {
+        #[command(subcommand)]
+        command: CacheCommand,
+    },
+    /// Write all log files into a single file, to attach to a bug report
+    ExportLogs {
+        /// Text file to write
+        output: PathBuf,
+    },
+    /// Show the usage statistics kept on this computer (cards generated, cache hit rates)
+    Stats {
+        /// Start collecting usage statistics
+        #[arg(long, conflicts_with = "disable")]
+        enable: bool,
+        /// Stop collecting usage statistics
+        #[arg(long)]
+        disable: bool,
+        /// Start the statistics over
+        #[arg(long)]
+        reset: bool,
+    },
+    /// List the recently used decklists, newest first
+    History {
+        /// Print the decklist with this number in the list instead, e.g. to load it in the GUI
+        #[arg(long, value_name = "NUMBER", conflicts_with = "clear")]
+        show: Option<usize>,
+        /// Forget all recently used decklists
+        #[arg(long)]
+        clear: bool,
+    },
+    /// Fetch fresh card names and set codes from Scryfall, e.g. from a cron job
+    Update {
+        /// Only report whether the cached data is older than --max-cache-age-days, exiting
+        /// with status 1 if it is (or isn't cached at all)
+        #[arg(long)]
+        check: bool,
+    },
+    /// Check the cache directory, the cached data and the connection to Scryfall, printing a
+    /// report to paste into a bug report (exits with status 1 if a check fails)
+    Doctor,
+    /// Download the images of a decklist ahead of time, e.g. overnight before an event without a
+    /// connection
+    ///
+    /// Searches and downloads stay within the rate limits. Images already cached are skipped,
+    /// so running it again after an interruption resumes where it stopped.
+    Prefetch {
+        /// Decklist file, in any format the decklist box of the GUI takes
+        #[arg(long, value_name = "FILE")]
+        decklist: PathBuf,
+        /// Also download every other printing of each card, to pick another printing offline
+        #[arg(long)]
+        alternatives: bool,
+    },
+    /// Write a calibration page, or store the correction for the length measured on it
+    ///
+    /// Print the page at 100%, measure its ruler and run `calibrate --measured <MM>` so that
+    /// printed cards come out at their real size.
+    Calibrate {
+        /// Calibration page to write
+        #[arg(short, long, default_value = "calibration.pdf")]
+        output: PathBuf,
+        /// Length of the ruler on the printed calibration page, in mm
+        #[arg(long, conflicts_with = "reset")]
+        measured: Option<f32>,
+        /// Print cards without any scale correction again
+        #[arg(long)]
+        reset: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum CacheCommand {
+    /// List the URLs of the cached images
+    List {
+        /// List the card printings the cached images show instead, marking the cards that
+        /// print without a connection
+        #[arg(long)]
+        by_card: bool,
+    },
+    /// List the decks whose images are pinned in the image cache
+    Pinned,
+    /// Let the images of a pinned deck be evicted again
+    Unpin {
+        /// Name of the deck, as listed by `cache pinned`
+        name: String,
     },
 }
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    env_logger::init();
+    let cli = Cli::parse();
+    let settings = get_settings();
+    // Before logging starts, since the log files live in the cache directory
+    set_cache_directory(cli.cache_dir.clone().or(settings.cache_directory.clone()));
+    // Quiet unless RUST_LOG asks for more: results go to stdout, logs to stderr
+    init_logging("warn", cli.log_file || settings.log_to_file);
+    set_ui_language(settings.ui_language);
+    set_back_face_fallback(settings.back_face_fallback);
+    set_default_printing(settings.default_printing);
+    set_preferred_language(settings.preferred_language.clone());
+    set_basic_land_sets(settings.basic_land_sets.clone());
+    set_favorite_printings(settings.favorite_printings.clone());
+    set_download_rate_limit(cli.max_download_rate.or(settings.max_download_rate_kb));
+    set_image_cache_eviction_policy(settings.image_cache_eviction);
+    set_read_only_caches(cli.read_only_cache);
+    set_freshness_policy(FreshnessPolicy {
+        // Refreshing rewrites the card names and set codes
+        auto_refresh: !cli.no_auto_refresh && !cli.read_only_cache,
+        ..FreshnessPolicy::from_days(cli.max_cache_age_days)
+    });
+
+    // Updating replaces card names and set codes, so there is no point in loading them first
+    if let Commands::Update { check } = cli.command {
+        update_caches(check).await;
+        return Ok(());
+    }
+
+    // The history is read from disk, no caches needed
+    if let Commands::History { show, clear } = cli.command {
+        show_history(show, clear);
+        return Ok(());
+    }
+
+    // Cache commands read the image and search caches, not the card names and set codes
+    if let Commands::Cache { command } = &cli.command {
+        run_cache_command(command);
+        return Ok(());
+    }
+
+    // Diagnostics check the caches on disk, so a broken cache mustn't stop them from running
+    if let Commands::Doctor = cli.command {
+        let report = run_diagnostics().await;
+        print!("{}", report);
+        if report.has_failures() {
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
 
     // Initialize caches at startup
     if let Err(e) = localhawk_core::initialize_caches().await {
@@ -44,27 +310,41 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         std::process::exit(1);
     }
 
-    let cli = Cli::parse();
     let mut generator = ProxyGenerator::new()?;
 
     match cli.command {
-        Commands::Search { name } => {
-            println!("Searching for '{}'...", name);
+        Commands::Search { name, query } => {
+            let results = if let Some(query) = query {
+                println!("Searching Scryfall for '{}'...", query);
+                ProxyGenerator::search_with_query(&query).await
+            } else {
+                let name = name.unwrap_or_default();
+                println!("Searching for '{}'...", name);
+                ProxyGenerator::search_card(&name).await
+            };
 
-            match ProxyGenerator::search_card(&name).await {
+            match results {
                 Ok(results) => {
                     println!("Found {} cards:", results.total_found);
                     for (i, card) in results.cards.iter().enumerate().take(10) {
+                        let price_info = card
+                            .price_label()
+                            .map(|price| format!(" [{}]", price))
+                            .unwrap_or_default();
                         println!(
-                            "  {}. {} ({}) - {}",
+                            "  {}. {} ({}) - {}{}",
                             i + 1,
                             card.name,
-                            card.set,
-                            card.language
+                            set_display_name(&card.set),
+                            card.language,
+                            price_info
                         );
                     }
-                    if results.cards.len() > 10 {
-                        println!("  ... and {} more", results.cards.len() - 10);
+                    if results.total_found > 10 {
+                        println!("  ... and {} more", results.total_found - 10);
+                    }
+                    if results.has_more {
+                        println!("  (Scryfall has more matches than were fetched)");
                     }
                 }
                 Err(e) => {
@@ -76,23 +356,148 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         Commands::Generate {
             cards,
             output,
+            output_dir,
+            name_template,
+            overwrite,
+            timestamp,
+            regenerate,
+            pages_per_file,
             cards_per_row,
             cards_per_column,
+            format,
+            collection,
+            pdf_a,
+            text_only,
+            fill_with,
+            sort,
+            copy_order,
+            missing_back,
+            default_printing,
+            language,
+            basic_land_sets,
+            exec,
+            dry_run,
+            export_decklist,
+            pin,
+            yes,
         } => {
+            if let Some(fallback) = missing_back {
+                set_back_face_fallback(fallback);
+            }
+            if default_printing {
+                set_default_printing(true);
+            }
+            if let Some(language) = language {
+                let language = language.to_lowercase();
+                if !get_minimal_scryfall_languages().contains(&language) {
+                    eprintln!(
+                        "Unknown language '{}', use a Scryfall code like ja",
+                        language
+                    );
+                    std::process::exit(1);
+                }
+                set_preferred_language(Some(language));
+            }
+            if !basic_land_sets.is_empty() {
+                set_basic_land_sets(basic_land_sets);
+            }
+
             if cards.is_empty() {
                 eprintln!("No cards specified. Use --cards to specify card names.");
                 std::process::exit(1);
             }
 
-            println!("Generating PDF with {} cards...", cards.len());
+            // A leading "Deck: NAME" line names the deck instead of a card
+            let deck_name = parse_deck_name(&cards.join("\n"));
+            if !cli.read_only_cache {
+                if let Err(e) = record_recent_decklist(&cards.join("\n")) {
+                    eprintln!("Failed to save decklist history: {}", e);
+                }
+            }
+            let card_names = match deck_name {
+                Some(_) => &cards[1..],
+                None => &cards[..],
+            };
+
+            let mut output_options = settings.output.clone();
+            if let Some(name_template) = name_template {
+                output_options.file_name_template = name_template;
+            }
+            if overwrite {
+                output_options.overwrite = OverwritePolicy::Overwrite;
+            } else if timestamp {
+                output_options.overwrite = OverwritePolicy::Timestamp;
+            }
+            if pages_per_file.is_some() {
+                output_options.pages_per_file = pages_per_file;
+            }
+            let (output_dir, output_options) = match output {
+                Some(output) => output_options.for_path(&output),
+                None => (
+                    output_dir.unwrap_or_else(|| PathBuf::from(".")),
+                    output_options,
+                ),
+            };
+
+            let mut entries: Vec<DecklistEntry> = card_names
+                .iter()
+                .map(|card_name| DecklistEntry::from_name(card_name))
+                .collect();
+
+            // Skip the cards that are already owned
+            if let Some(path) = collection {
+                match Collection::load_from(&path) {
+                    Ok(collection) => {
+                        let requested = entries.len();
+                        entries = collection.missing_entries(&entries);
+                        println!(
+                            "Collection has {} of {} cards, generating the missing ones",
+                            requested - entries.len(),
+                            requested
+                        );
+                    }
+                    Err(e) => {
+                        eprintln!("Failed to load collection '{}': {}", path.display(), e);
+                        std::process::exit(1);
+                    }
+                }
+                if entries.is_empty() {
+                    println!("All cards are already in the collection, nothing to generate.");
+                    std::process::exit(0);
+                }
+            }
+
+            println!("Generating PDF with {} cards...", entries.len());
 
             // Search and add each card
-            for card_name in cards {
+            for entry in entries {
+                let card_name = entry.name.clone();
                 println!("Searching for '{}'...", card_name);
                 match ProxyGenerator::search_card(&card_name).await {
                     Ok(results) => {
-                        if let Some(card) = results.cards.first() {
-                            generator.add_card(card.clone(), 1);
+                        // The default printing is English, so a preferred language wins over it
+                        let english = get_preferred_language().is_none_or(|lang| lang == "en");
+                        let default_id = if get_default_printing() && english {
+                            get_or_fetch_default_printing_id(&card_name)
+                                .await
+                                .ok()
+                                .flatten()
+                        } else {
+                            None
+                        };
+                        let card = default_id
+                            .and_then(|id| {
+                                results
+                                    .cards
+                                    .iter()
+                                    .find(|card| card.scryfall_id.as_deref() == Some(id.as_str()))
+                            })
+                            .or_else(|| {
+                                ProxyGenerator::select_printing_for_entry(&results.cards, &entry)
+                                    .map(|index| &results.cards[index])
+                            });
+                        if let Some(card) = card {
+                            generator.add_card(card.clone(), entry.multiple.get());
                             println!("  Added: {} ({})", card.name, card.set);
                         } else {
                             eprintln!("  No results found for '{}'", card_name);
@@ -108,38 +513,345 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 eprintln!("No valid cards found. Cannot generate PDF.");
                 std::process::exit(1);
             }
+            println!("{}", format_price_total(generator.get_cards()));
+
+            if let Some(format) = format {
+                let issues = check_legality(generator.get_cards(), format);
+                if issues.is_empty() {
+                    println!("All cards are legal in {}", format);
+                } else {
+                    eprintln!(
+                        "Warning: {} cards are not legal in {}:",
+                        issues.len(),
+                        format
+                    );
+                    for issue in issues {
+                        eprintln!("  {}", issue);
+                    }
+                }
+            }
 
             // Generate PDF
             let options = PdfOptions {
                 cards_per_row,
                 cards_per_column,
+                pdf_a,
+                text_only,
+                filler_cards: fill_with,
+                sort_order: sort,
+                copy_order,
+                title: deck_name.clone(),
                 ..Default::default()
             };
 
-            println!("Generating PDF...");
-            match generator
-                .generate_pdf(options, |current, total| {
-                    println!("Progress: {}/{}", current, total);
-                })
-                .await
-            {
-                Ok(pdf_data) => {
-                    std::fs::write(&output, pdf_data)?;
-                    println!("PDF saved to: {}", output.display());
-                    let cache = get_image_cache();
-                    let cache_guard = cache.read().unwrap();
-                    println!(
-                        "Cache size: {} images ({} MB)",
-                        cache_guard.len(),
-                        cache_guard.size_bytes() / (1024 * 1024)
+            for (card, _) in generator.get_cards() {
+                if let Some(warning) = card.missing_back_face_warning(&options.double_face_mode) {
+                    eprintln!("Warning: {}", warning);
+                }
+            }
+
+            if let Some(path) = export_decklist {
+                let cards: Vec<_> = generator
+                    .get_cards()
+                    .iter()
+                    .map(|(card, quantity)| {
+                        let face_mode = options.double_face_mode.clone();
+                        (DeckSection::Main, card.clone(), *quantity, face_mode)
+                    })
+                    .collect();
+                let decklist = format_normalized_decklist(&cards);
+                if path.as_os_str() == "-" {
+                    println!("{}", decklist);
+                } else if let Err(e) = std::fs::write(&path, decklist + "\n") {
+                    eprintln!("Failed to write decklist '{}': {}", path.display(), e);
+                    std::process::exit(1);
+                } else {
+                    println!("Decklist saved to: {}", path.display());
+                }
+            }
+
+            let mut card_groups: Vec<Vec<_>> = vec![
+                generator
+                    .get_cards()
+                    .iter()
+                    .map(|(card, quantity)| {
+                        (card.clone(), *quantity, options.double_face_mode.clone())
+                    })
+                    .collect(),
+            ];
+            for cards in &mut card_groups {
+                options.sort_order.sort_cards(cards);
+                *cards = options.copy_order.arrange(std::mem::take(cards));
+            }
+            if !options.filler_cards.is_empty() {
+                let fillers = ProxyGenerator::resolve_filler_cards(&options.filler_cards).await;
+                if fillers.len() < options.filler_cards.len() {
+                    eprintln!("Warning: Some filler cards were not found");
+                }
+                ProxyGenerator::fill_last_page(&mut card_groups, &fillers, &options);
+            }
+            if pin {
+                match &deck_name {
+                    Some(deck_name) => {
+                        let urls =
+                            ProxyGenerator::expand_cards_to_image_urls(&card_groups.concat());
+                        match pin_deck(deck_name, urls) {
+                            Ok(()) => println!("Pinned the images of '{}'", deck_name),
+                            Err(e) => eprintln!("Failed to pin '{}': {}", deck_name, e),
+                        }
+                    }
+                    None => eprintln!(
+                        "Warning: --pin needs a leading \"Deck: NAME\" line to name the deck"
+                    ),
+                }
+            }
+
+            let print_estimate = ProxyGenerator::estimate_print(&card_groups, &options);
+            println!("{}", print_estimate.describe());
+            if dry_run {
+                let pages = ProxyGenerator::plan_pages_from_card_groups(&card_groups, &options);
+                let image_count: usize = pages.iter().map(Vec::len).sum();
+                println!(
+                    "Dry run: {} images on {} pages ({} per page)",
+                    image_count,
+                    pages.len(),
+                    options.cards_per_page()
+                );
+                let mut number = 0;
+                for (page_index, page) in pages.iter().enumerate() {
+                    println!("Page {}:", page_index + 1);
+                    for url in page {
+                        number += 1;
+                        println!("  {:>3}. {}", number, url);
+                    }
+                }
+            } else {
+                let interactive = std::io::stdin().is_terminal();
+                let hooks: Vec<_> = exec.into_iter().map(command_hook).collect();
+
+                // The same deck generated before with the same options gives the same PDFs
+                let deck_hash =
+                    ProxyGenerator::deck_hash(&card_groups, &options, output_options.split_pages());
+                let existing = existing_pdfs(
+                    &output_dir,
+                    deck_name.as_deref(),
+                    &output_options,
+                    output_options.parts(print_estimate.pages),
+                    &deck_hash,
+                )
+                .filter(|_| !regenerate);
+                if let Some(paths) = existing {
+                    println!("Unchanged since it was last generated:");
+                    for path in &paths {
+                        println!("  {}", path.display());
+                    }
+                    if !interactive || yes || !confirm("Generate it again?") {
+                        for path in &paths {
+                            if let Err(e) = hooks.iter().try_for_each(|hook| hook(path)) {
+                                eprintln!("Failed to run --exec command: {}", e);
+                                std::process::exit(1);
+                            }
+                            println!("PDF kept: {}", path.display());
+                        }
+                        std::process::exit(0);
+                    }
+                }
+
+                let estimate = if options.text_only {
+                    Default::default()
+                } else {
+                    ProxyGenerator::estimate_downloads_for_cards(card_groups.iter().flatten())
+                };
+                if estimate.images > 0 {
+                    println!("{}", estimate.describe());
+                }
+                if print_estimate.is_large() {
+                    eprintln!(
+                        "Warning: the PDF will have {} pages, check the decklist",
+                        print_estimate.pages
                     );
                 }
-                Err(e) => {
-                    eprintln!("PDF generation failed: {}", e);
+                let large = estimate.is_large() || print_estimate.is_large();
+                if large && interactive && !yes && !confirm("Continue?") {
+                    println!("Cancelled, nothing was downloaded.");
+                    std::process::exit(0);
+                }
+
+                println!("Generating PDF...");
+                let generation = ProxyGenerator::generate_pdfs_from_card_groups(
+                    &card_groups,
+                    options,
+                    output_options.split_pages(),
+                    |current, total| {
+                        println!("Progress: {}/{}", current, total);
+                    },
+                )
+                .await;
+                match generation {
+                    Ok(pdfs) => {
+                        let written = write_pdfs(
+                            &pdfs,
+                            &output_dir,
+                            deck_name.as_deref(),
+                            &output_options,
+                            &hooks,
+                        );
+                        match written {
+                            Ok(paths) => {
+                                for path in paths {
+                                    println!("PDF saved to: {}", path.display());
+                                }
+                            }
+                            Err(e) => {
+                                eprintln!("Failed to save PDF or run --exec command: {}", e);
+                                std::process::exit(1);
+                            }
+                        }
+                        let cache = get_image_cache();
+                        let cache_guard = cache.read().unwrap();
+                        println!(
+                            "Cache size: {} images ({} MB)",
+                            cache_guard.len(),
+                            cache_guard.size_bytes() / (1024 * 1024)
+                        );
+                    }
+                    Err(e) => {
+                        eprintln!("PDF generation failed: {}", e);
+                        std::process::exit(1);
+                    }
+                }
+            }
+        }
+        Commands::ExportCache { output } => match export_image_cache_to(&output) {
+            Ok(count) => println!("Exported {} cached images to {}", count, output.display()),
+            Err(e) => {
+                eprintln!("Cache export failed: {}", e);
+                std::process::exit(1);
+            }
+        },
+        Commands::ExportLogs { output } => match export_logs(&output) {
+            Ok(count) => println!("Exported {} log files to {}", count, output.display()),
+            Err(e) => {
+                eprintln!("Log export failed: {}", e);
+                std::process::exit(1);
+            }
+        },
+        Commands::Stats {
+            enable,
+            disable,
+            reset,
+        } => {
+            if enable || disable {
+                let saved = update_settings(|settings| settings.collect_usage_stats = enable);
+                if let Err(e) = saved {
+                    eprintln!("Failed to save settings: {}", e);
+                    std::process::exit(1);
+                }
+            }
+            if reset {
+                if let Err(e) = reset_usage_stats() {
+                    eprintln!("Failed to reset usage statistics: {}", e);
                     std::process::exit(1);
                 }
             }
+
+            for line in get_usage_stats().summary() {
+                println!("{}", line);
+            }
+            println!("Stored in {}", get_usage_stats_path());
+            if !get_settings().collect_usage_stats {
+                println!("Collecting is off; enable it with `localhawk-cli stats --enable`");
+            }
         }
+        Commands::Prefetch {
+            decklist,
+            alternatives,
+        } => {
+            if cli.read_only_cache {
+                eprintln!("Prefetching needs to save the image cache, drop --read-only-cache");
+                std::process::exit(1);
+            }
+            prefetch(&decklist, alternatives).await;
+        }
+        Commands::Calibrate {
+            output,
+            measured,
+            reset,
+        } => {
+            let current = get_settings().pdf_options.scale_correction;
+            let correction = match measured {
+                Some(measured) => match calibrated_scale_correction(current, measured) {
+                    Ok(correction) => Some(correction),
+                    Err(e) => {
+                        eprintln!("Calibration failed: {}", e);
+                        std::process::exit(1);
+                    }
+                },
+                None if reset => Some(1.0),
+                None => None,
+            };
+
+            match correction {
+                Some(correction) => {
+                    let saved = update_settings(|settings| {
+                        settings.pdf_options.scale_correction = correction
+                    });
+                    if let Err(e) = saved {
+                        eprintln!("Failed to save settings: {}", e);
+                        std::process::exit(1);
+                    }
+                    println!("Scale correction set to {:.4}", correction);
+                }
+                None => {
+                    let page = generate_calibration_page(&get_settings().pdf_options);
+                    let written = page.and_then(|pdf| Ok(std::fs::write(&output, pdf)?));
+                    if let Err(e) = written {
+                        eprintln!("Failed to write calibration page: {}", e);
+                        std::process::exit(1);
+                    }
+                    println!(
+                        "Calibration page saved to {} (scale correction {:.4})",
+                        output.display(),
+                        current
+                    );
+                    println!(
+                        "Print it at 100%, then run `localhawk-cli calibrate --measured <MM>` with the length of its ruler"
+                    );
+                }
+            }
+        }
+        // Handled before the caches are initialized
+        Commands::Update { .. }
+        | Commands::Doctor
+        | Commands::History { .. }
+        | Commands::Cache { .. } => unreachable!(),
+        Commands::ImportCache { input } => match import_image_cache_from(&input) {
+            Ok(summary) => {
+                println!(
+                    "Imported {} images ({} already cached)",
+                    summary.imported, summary.already_cached
+                );
+                if summary.corrupt > 0 {
+                    eprintln!(
+                        "Warning: Skipped {} images that failed their checksum",
+                        summary.corrupt
+                    );
+                }
+            }
+            Err(e) => {
+                eprintln!("Cache import failed: {}", e);
+                std::process::exit(1);
+            }
+        },
+    }
+
+    // Let an automatic refresh of stale card data finish before the runtime shuts down
+    if let CacheRefreshStatus::Failed(e) = wait_for_cache_refresh().await {
+        eprintln!("Warning: Failed to refresh card names/set codes: {}", e);
+    }
+
+    if is_scryfall_unavailable() {
+        eprintln!("Warning: Scryfall unavailable, using cached data");
     }
 
     // Save caches before exiting
@@ -149,3 +861,255 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+/// Ask a yes/no question on the terminal, defaulting to no
+fn confirm(question: &str) -> bool {
+    print!("{} [y/N] ", question);
+    std::io::stdout().flush().ok();
+    let mut answer = String::new();
+    if std::io::stdin().read_line(&mut answer).is_err() {
+        return false;
+    }
+    matches!(answer.trim().to_lowercase().as_str(), "y" | "yes")
+}
+
+/// Download the images of the decklist in `path` that aren't cached yet, printing an ETA
+async fn prefetch(path: &std::path::Path, alternatives: bool) {
+    let decklist = match std::fs::read_to_string(path) {
+        Ok(decklist) => decklist,
+        Err(e) => {
+            eprintln!("Failed to read decklist '{}': {}", path.display(), e);
+            std::process::exit(1);
+        }
+    };
+    // Both faces, so double-faced cards print offline in any face mode
+    let resolved = ProxyGenerator::parse_and_resolve_decklist_with_report(
+        &decklist,
+        DoubleFaceMode::BothSides,
+    )
+    .await;
+    let (entries, report) = match resolved {
+        Ok(resolved) => resolved,
+        Err(e) => {
+            eprintln!("Failed to parse decklist: {}", e);
+            std::process::exit(1);
+        }
+    };
+    for (line_number, issue) in &report.issues {
+        eprintln!("Line {}: {}", line_number + 1, issue.annotation());
+    }
+    if entries.is_empty() {
+        eprintln!("No cards found in '{}'", path.display());
+        std::process::exit(1);
+    }
+
+    println!("Searching {} cards...", entries.len());
+    let plan = plan_prefetch(&entries, alternatives).await;
+    for error in &plan.errors {
+        eprintln!("  {}", error);
+    }
+    println!("{} images already cached", plan.cached);
+    if plan.urls.is_empty() {
+        println!("Nothing to download, the deck prints without a connection.");
+        return;
+    }
+    println!("{}", plan.estimate().describe());
+    if let Some(eta) = estimate_prefetch_time(plan.urls.len(), get_download_rate_limit()) {
+        println!(
+            "At the download rate limit this takes about {}",
+            format_eta(eta)
+        );
+    }
+
+    let progress = prefetch_images(&plan.urls, |progress| {
+        let done = progress.downloaded + progress.errors.len();
+        let eta = progress
+            .eta()
+            .map(|eta| format!(", about {} left", format_eta(eta)))
+            .unwrap_or_default();
+        println!("Progress: {}/{}{}", done, progress.total, eta);
+    })
+    .await;
+
+    println!(
+        "Downloaded {} images in {}",
+        progress.downloaded,
+        format_eta(progress.elapsed)
+    );
+    if !progress.errors.is_empty() {
+        eprintln!(
+            "Warning: {} images failed, run prefetch again to retry them:",
+            progress.errors.len()
+        );
+        for error in &progress.errors {
+            eprintln!("  {}", error);
+        }
+    }
+}
+
+/// List the cached images or the pinned decks, or unpin a deck
+fn run_cache_command(command: &CacheCommand) {
+    match command {
+        CacheCommand::List { by_card } => list_cache(*by_card),
+        CacheCommand::Pinned => list_pinned_decks(),
+        CacheCommand::Unpin { name } => match unpin_deck(name) {
+            Ok(true) => println!("Unpinned the images of '{}'", name),
+            Ok(false) => {
+                eprintln!("No pinned deck named '{}'", name);
+                std::process::exit(1);
+            }
+            Err(e) => {
+                eprintln!("Failed to unpin '{}': {}", name, e);
+                std::process::exit(1);
+            }
+        },
+    }
+}
+
+/// List the pinned decks with how many of their images are in the image cache
+fn list_pinned_decks() {
+    let pinned_decks = get_pinned_decks();
+    if pinned_decks.decks.is_empty() {
+        println!("No decks pinned, pin one with generate --pin");
+        return;
+    }
+
+    for (name, urls) in &pinned_decks.decks {
+        let cached = count_cached_images(urls.iter().map(String::as_str));
+        println!("{}: {} of {} images cached", name, cached, urls.len());
+    }
+}
+
+fn list_cache(by_card: bool) {
+    if !by_card {
+        let cache = get_image_cache();
+        let cache_guard = cache.read().unwrap();
+        let mut urls: Vec<&String> = cache_guard.iter().map(|(url, _)| url).collect();
+        urls.sort();
+        for url in &urls {
+            println!("{}", url);
+        }
+        println!(
+            "{} images ({} MB)",
+            urls.len(),
+            cache_guard.size_bytes() / (1024 * 1024)
+        );
+        return;
+    }
+
+    let contents = get_image_cache_contents();
+    for printing in &contents.printings {
+        let marker = if printing.is_complete() { "✓" } else { " " };
+        println!("{} {}", marker, printing);
+    }
+    println!(
+        "{} printings of {} cards cached, {} cards with all images (✓)",
+        contents.printings.len(),
+        contents.cards().len(),
+        contents.offline_ready_cards().len()
+    );
+    if contents.unknown_images > 0 {
+        println!(
+            "{} more images belong to cards whose searches are no longer cached",
+            contents.unknown_images
+        );
+    }
+}
+
+/// List the recently used decklists, print one of them or clear them
+fn show_history(show: Option<usize>, clear: bool) {
+    if clear {
+        if let Err(e) = clear_decklist_history() {
+            eprintln!("Failed to clear decklist history: {}", e);
+            std::process::exit(1);
+        }
+        println!("Decklist history cleared");
+        return;
+    }
+
+    let history = get_decklist_history();
+    if let Some(number) = show {
+        match number
+            .checked_sub(1)
+            .and_then(|index| history.decklists.get(index))
+        {
+            Some(recent) => println!("{}", recent.decklist),
+            None => {
+                eprintln!(
+                    "No decklist number {} (the history has {})",
+                    number,
+                    history.decklists.len()
+                );
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    if history.decklists.is_empty() {
+        println!("No decklists used yet");
+    }
+    for (index, recent) in history.decklists.iter().enumerate() {
+        println!(
+            "{:>3}  {}  {}",
+            index + 1,
+            recent.parsed_at_label(),
+            recent.name
+        );
+    }
+    println!("Stored in {}", get_decklist_history_path());
+}
+
+/// Report how old the cached card names and set codes are, then fetch fresh ones unless
+/// `check_only`
+async fn update_caches(check_only: bool) {
+    let (card_names_at, set_codes_at) = match get_cache_dates() {
+        Ok(dates) => dates,
+        Err(e) => {
+            eprintln!("Failed to read the caches: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let policy = get_freshness_policy();
+    let mut stale = false;
+    for (label, cached_at) in [("Card names", card_names_at), ("Set codes", set_codes_at)] {
+        match cached_at {
+            Some(cached_at) if policy.is_stale(cached_at) => {
+                stale = true;
+                println!("{}: fetched {}, stale", label, cached_at.date());
+            }
+            Some(cached_at) => println!("{}: fetched {}, up to date", label, cached_at.date()),
+            None => {
+                stale = true;
+                println!("{}: not cached", label);
+            }
+        }
+    }
+    if check_only {
+        if stale {
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    println!("Updating card names...");
+    if let Err(e) = force_update_card_lookup().await {
+        eprintln!("Failed to update card names: {}", e);
+        std::process::exit(1);
+    }
+    let count = get_card_name_cache_info().map_or(0, |(_, count)| count);
+    println!("Updated {} card names", count);
+
+    println!("Updating set codes...");
+    if let Err(e) = force_update_set_codes().await {
+        eprintln!("Failed to update set codes: {}", e);
+        std::process::exit(1);
+    }
+    let count = get_set_codes_cache()
+        .read()
+        .unwrap()
+        .as_ref()
+        .map_or(0, |codes| codes.len());
+    println!("Updated {} set codes", count);
+}
@@ -0,0 +1,269 @@
+//! Benchmarks of the hot paths of proxy generation
+//!
+//! Run with `cargo bench -p localhawk-core` (or `-- <filter>` for a single group). All inputs
+//! are generated here, so no network access or populated caches are needed.
+
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use localhawk_core::cache::create_image_cache_with_config;
+use localhawk_core::pdf::{IMAGE_HEIGHT, IMAGE_WIDTH};
+use localhawk_core::{
+    CardNameLookup, PdfOptions, generate_pdf, get_minimal_scryfall_languages, parse_decklist,
+};
+use printpdf::image_crate::{DynamicImage, ImageFormat, Rgb, RgbImage, load_from_memory};
+use std::collections::HashSet;
+use std::hint::black_box;
+use std::io::Cursor;
+
+/// Roughly the number of distinct card names on Scryfall
+const CATALOG_SIZE: usize = 30_000;
+const DECKLIST_LINES: usize = 1_000;
+const CACHED_IMAGES: usize = 1_000;
+
+const NAME_PREFIXES: [&str; 25] = [
+    "Lightning",
+    "Shadow",
+    "Ancient",
+    "Crystal",
+    "Ember",
+    "Frost",
+    "Gilded",
+    "Hollow",
+    "Iron",
+    "Jade",
+    "Kindred",
+    "Lunar",
+    "Mystic",
+    "Night",
+    "Obsidian",
+    "Primal",
+    "Quiet",
+    "Radiant",
+    "Storm",
+    "Thorn",
+    "Umbral",
+    "Verdant",
+    "Wild",
+    "Ashen",
+    "Blood",
+];
+const NAME_MIDDLES: [&str; 30] = [
+    "Bolt",
+    "Walker",
+    "Sentinel",
+    "Drake",
+    "Oracle",
+    "Titan",
+    "Serpent",
+    "Knight",
+    "Shaman",
+    "Golem",
+    "Wurm",
+    "Sphinx",
+    "Angel",
+    "Demon",
+    "Elemental",
+    "Spirit",
+    "Hydra",
+    "Phoenix",
+    "Vampire",
+    "Zombie",
+    "Giant",
+    "Goblin",
+    "Elf",
+    "Merfolk",
+    "Rogue",
+    "Wizard",
+    "Cleric",
+    "Warrior",
+    "Beast",
+    "Horror",
+];
+const NAME_SUFFIXES: [&str; 40] = [
+    "of the Vale",
+    "of Dawn",
+    "of Dusk",
+    "of the Deep",
+    "of Ruin",
+    "of the Wilds",
+    "of Embers",
+    "of the Coast",
+    "of the Forge",
+    "of Echoes",
+    "Reborn",
+    "Ascendant",
+    "Unbound",
+    "Eternal",
+    "Forgotten",
+    "Triumphant",
+    "Exiled",
+    "Awakened",
+    "Corrupted",
+    "Sanctified",
+    "of the Spire",
+    "of the Mire",
+    "of the Pass",
+    "of Storms",
+    "of Tides",
+    "of Ash",
+    "of Bones",
+    "of Whispers",
+    "of the Hunt",
+    "of the Grave",
+    "the Fearless",
+    "the Cunning",
+    "the Patient",
+    "the Bold",
+    "the Wise",
+    "the Cruel",
+    "the Mighty",
+    "the Lost",
+    "the Silent",
+    "the Radiant",
+];
+
+/// Deterministic, realistic-looking card names (uppercase, as stored in the name cache)
+fn catalog_names(count: usize) -> Vec<String> {
+    (0..count)
+        .map(|i| {
+            let prefix = NAME_PREFIXES[i % NAME_PREFIXES.len()];
+            let middle = NAME_MIDDLES[(i / NAME_PREFIXES.len()) % NAME_MIDDLES.len()];
+            let suffix = NAME_SUFFIXES
+                [(i / (NAME_PREFIXES.len() * NAME_MIDDLES.len())) % NAME_SUFFIXES.len()];
+            format!("{} {} {}", prefix, middle, suffix).to_uppercase()
+        })
+        .collect()
+}
+
+/// A card-sized JPEG with some structure, so decoding does real work
+fn card_jpeg(seed: u32) -> Vec<u8> {
+    let image = RgbImage::from_fn(IMAGE_WIDTH, IMAGE_HEIGHT, |x, y| {
+        Rgb([
+            ((x + seed) % 256) as u8,
+            ((y * 3 + seed) % 256) as u8,
+            ((x * y + seed) % 256) as u8,
+        ])
+    });
+    let mut bytes = Vec::new();
+    DynamicImage::ImageRgb8(image)
+        .write_to(&mut Cursor::new(&mut bytes), ImageFormat::Jpeg)
+        .unwrap();
+    bytes
+}
+
+fn bench_image_decode(c: &mut Criterion) {
+    let jpeg = card_jpeg(0);
+    let mut group = c.benchmark_group("image_decode");
+    group.throughput(Throughput::Bytes(jpeg.len() as u64));
+    group.bench_function("card_jpeg", |b| {
+        b.iter(|| load_from_memory(black_box(&jpeg)).unwrap())
+    });
+    group.finish();
+}
+
+fn bench_pdf_assembly(c: &mut Criterion) {
+    let images: Vec<DynamicImage> = (0..18)
+        .map(|seed| load_from_memory(&card_jpeg(seed)).unwrap())
+        .collect();
+
+    let mut group = c.benchmark_group("pdf_assembly");
+    group.sample_size(10);
+    for pages in [1, 2] {
+        let page_images = &images[..pages * 9];
+        group.throughput(Throughput::Elements(page_images.len() as u64));
+        group.bench_with_input(
+            BenchmarkId::new("pages", pages),
+            page_images,
+            |b, images| {
+                b.iter(|| generate_pdf(images.iter().cloned(), PdfOptions::default()).unwrap())
+            },
+        );
+    }
+    group.finish();
+}
+
+fn bench_cache_lookup(c: &mut Criterion) {
+    let cache_dir =
+        std::env::temp_dir().join(format!("localhawk-bench-cache-{}", std::process::id()));
+    let mut cache =
+        create_image_cache_with_config(Some(cache_dir.clone()), 1024 * 1024 * 1024).unwrap();
+    let jpeg = card_jpeg(0);
+    let urls: Vec<String> = (0..CACHED_IMAGES)
+        .map(|i| format!("https://cards.scryfall.io/border_crop/front/{}.jpg", i))
+        .collect();
+    for url in &urls {
+        cache.insert(url.clone(), jpeg.clone()).unwrap();
+    }
+
+    let mut group = c.benchmark_group("cache_lookup");
+    group.bench_function("hit", |b| {
+        let mut i = 0;
+        b.iter(|| {
+            i = (i + 7) % urls.len();
+            cache.get(black_box(&urls[i])).unwrap()
+        })
+    });
+    let missing = "https://cards.scryfall.io/border_crop/front/missing.jpg".to_string();
+    group.bench_function("miss", |b| b.iter(|| cache.get(black_box(&missing))));
+    group.finish();
+
+    let _ = std::fs::remove_dir_all(cache_dir);
+}
+
+fn bench_decklist_parse(c: &mut Criterion) {
+    let names = catalog_names(DECKLIST_LINES);
+    let set_codes: HashSet<String> = ["m21", "znr", "bro", "emn", "7ed"]
+        .into_iter()
+        .map(String::from)
+        .collect();
+    let languages = get_minimal_scryfall_languages();
+    let decklist: String = names
+        .iter()
+        .enumerate()
+        .map(|(i, name)| {
+            // Mix plain lines, set and language annotations and comments
+            let quantity = 1 + (i / 4) % 4;
+            let name = name.to_lowercase();
+            match i % 4 {
+                0 => format!("{} {}\n", quantity, name),
+                1 => format!("{}x {} [M21]\n", quantity, name),
+                2 => format!("{} {} (bro) [ja]\n", quantity, name),
+                _ => format!("// {}\n", name),
+            }
+        })
+        .collect();
+
+    let mut group = c.benchmark_group("decklist_parse");
+    group.throughput(Throughput::Elements(DECKLIST_LINES as u64));
+    group.bench_function("1k_lines", |b| {
+        b.iter(|| parse_decklist(black_box(&decklist), &languages, &set_codes))
+    });
+    group.finish();
+}
+
+fn bench_fuzzy_lookup(c: &mut Criterion) {
+    let names = catalog_names(CATALOG_SIZE);
+    let lookup = CardNameLookup::from_card_names(&names);
+    let queries = [
+        ("exact", names[CATALOG_SIZE / 2].clone()),
+        ("typo", names[CATALOG_SIZE / 3].replacen('E', "A", 1)),
+        ("partial", "STORM SERPENT".to_string()),
+    ];
+
+    let mut group = c.benchmark_group("fuzzy_lookup");
+    for (kind, query) in &queries {
+        group.bench_with_input(BenchmarkId::new("catalog", kind), query, |b, query| {
+            b.iter(|| lookup.find(black_box(query)))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_image_decode,
+    bench_pdf_assembly,
+    bench_cache_lookup,
+    bench_decklist_parse,
+    bench_fuzzy_lookup
+);
+criterion_main!(benches);
@@ -22,6 +22,7 @@ typedef enum {
 /**
  * Initialize the proxy generator caches.
  * Must be called before any other FFI functions.
+ * Image downloads keep to the max_download_rate_kb bandwidth cap of the settings.
  * 
  * @return LOCALHAWK_SUCCESS on success, negative error code on failure
  * 
@@ -31,6 +32,45 @@ typedef enum {
  */
 int32_t localhawk_initialize(void);
 
+/**
+ * Initialize the proxy generator caches in a chosen directory, e.g. on a larger drive.
+ * Use instead of localhawk_initialize.
+ * 
+ * @param cache_dir Directory for the image, search and card name caches
+ *                  (NULL uses the settings, else the platform cache directory)
+ * @return LOCALHAWK_SUCCESS on success, negative error code on failure
+ * 
+ * Memory Management:
+ * - The path is copied, the caller keeps ownership
+ * - No cleanup required
+ */
+int32_t localhawk_initialize_with_cache_dir(const char* cache_dir);
+
+/**
+ * Options for localhawk_initialize_with_options.
+ */
+typedef struct {
+    const char* cache_dir;      // Cache directory, e.g. in an App Group container
+                                // (NULL uses the settings, else the platform cache directory)
+    int32_t max_image_cache_mb; // Size limit of the image cache (0 keeps the default of 1000 MB)
+    int32_t network_policy;     // 0 = normal, 1 = constrained: stale card names and set codes
+                                // are not refreshed automatically, e.g. in Low Data Mode
+} LocalHawkInitOptions;
+
+/**
+ * Initialize the proxy generator caches with options.
+ * Use instead of localhawk_initialize.
+ * 
+ * @param options Initialization options (NULL initializes like localhawk_initialize)
+ * @return LOCALHAWK_SUCCESS on success, LOCALHAWK_INVALID_INPUT for a negative cache size,
+ *         an unknown network policy or a path that isn't UTF-8, negative error code on failure
+ * 
+ * Memory Management:
+ * - The options are copied, the caller keeps ownership
+ * - No cleanup required
+ */
+int32_t localhawk_initialize_with_options(const LocalHawkInitOptions* options);
+
 /**
  * Generate PDF from decklist text.
  * 
@@ -62,6 +102,53 @@ int32_t localhawk_generate_pdf_from_decklist(
  */
 void localhawk_free_buffer(uint8_t* buffer);
 
+/**
+ * Result of localhawk_share_generate_pdf.
+ */
+typedef struct {
+    int32_t needs_full_app; // 0 = PDF written, 1 = cards not cached, 2 = images not cached,
+                            // 3 = out of time: open the decklist in the full app instead
+    char* pdf_path;         // Path of the written PDF (NULL if the full app is needed)
+    char* missing_cards;    // Newline-separated names of the cards to fetch, or NULL
+} LocalHawkShareResult;
+
+/**
+ * Generate a PDF from the caches alone, for the share extension.
+ * Nothing is downloaded and no initialization is needed; anything not cached, or running
+ * past the time budget, is reported in needs_full_app instead.
+ * 
+ * @param decklist_cstr Null-terminated C string containing the decklist
+ * @param cache_dir Cache directory shared with the app, e.g. in an App Group container
+ *                  (NULL uses the settings, else the platform cache directory)
+ * @param output_dir Directory to write the PDF to
+ * @param time_budget_ms Time budget in milliseconds (must be positive)
+ * @param result_out Pointer to a LocalHawkShareResult that will receive the result
+ * @return LOCALHAWK_SUCCESS if result_out was filled, LOCALHAWK_PARSE_FAILED if the decklist
+ *         has no cards, negative error code on failure
+ * 
+ * Memory Management:
+ * - Strings in the result are allocated by this function
+ * - Caller must call localhawk_free_share_result to free them
+ */
+int32_t localhawk_share_generate_pdf(
+    const char* decklist_cstr,
+    const char* cache_dir,
+    const char* output_dir,
+    int32_t time_budget_ms,
+    LocalHawkShareResult* result_out
+);
+
+/**
+ * Free the strings of a result from localhawk_share_generate_pdf.
+ * 
+ * @param result Result filled by localhawk_share_generate_pdf
+ * 
+ * Memory Management:
+ * - Safe to call with NULL pointer (no-op)
+ * - The struct itself is owned by the caller, only its strings are freed
+ */
+void localhawk_free_share_result(LocalHawkShareResult* result);
+
 /**
  * Get error message for the given error code.
  * 
@@ -151,6 +238,124 @@ int32_t localhawk_clear_image_cache(void);
  */
 int32_t localhawk_update_card_names(void);
 
+/**
+ * Update set codes from Scryfall API.
+ * This is a blocking operation that may take several seconds.
+ * 
+ * @return LOCALHAWK_SUCCESS on success, negative error code on failure
+ * 
+ * Memory Management:
+ * - No memory is allocated by this function
+ * - No cleanup required
+ */
+int32_t localhawk_update_set_codes(void);
+
+/**
+ * When card names and set codes were last fetched
+ */
+typedef struct {
+    int64_t card_names_updated_at; // Unix time in seconds, 0 if not cached
+    int32_t card_names_stale;      // 1 if older than the freshness policy allows or not cached
+    int64_t set_codes_updated_at;  // Unix time in seconds, 0 if not cached
+    int32_t set_codes_stale;       // 1 if older than the freshness policy allows or not cached
+} CacheFreshness;
+
+/**
+ * Get when card names and set codes were last fetched and whether they are stale.
+ * Staleness follows the policy set with localhawk_set_freshness_policy.
+ * 
+ * @return CacheFreshness structure; caches that can't be read count as not cached
+ * 
+ * Memory Management:
+ * - Returns struct by value (no memory allocation)
+ * - No cleanup required
+ */
+CacheFreshness localhawk_get_cache_freshness(void);
+
+/**
+ * Get the status of the automatic background refresh of card names and set codes.
+ * Stale data is refreshed in the background by localhawk_initialize.
+ * 
+ * @param state_out Receives 0=idle, 1=refreshing, 2=completed, 3=failed
+ * @param message_out Receives a display string (may be NULL if not needed)
+ * @return LOCALHAWK_SUCCESS on success, negative error code on failure
+ * 
+ * Memory Management:
+ * - If message_out is non-NULL, the string is allocated by this function
+ * - Caller must call localhawk_free_string to free the message
+ */
+int32_t localhawk_get_cache_refresh_status(int32_t* state_out, char** message_out);
+
+/**
+ * Run the self-diagnostics: cache directories writable, cache files readable,
+ * card name index builds, Scryfall reachable. Attach the report to bug reports.
+ * Takes a few seconds; call after localhawk_initialize, off the main thread.
+ * 
+ * @param report_out Receives the report as JSON:
+ *        {"version", "platform", "cache_directory",
+ *         "checks": [{"name", "status": "ok"|"warning"|"failed", "details"}]}
+ * @return LOCALHAWK_SUCCESS on success (even if checks failed), negative error code on failure
+ * 
+ * Memory Management:
+ * - The report string is allocated by this function
+ * - Caller must call localhawk_free_string to free the report
+ */
+int32_t localhawk_run_diagnostics(char** report_out);
+
+/**
+ * Configure when card names and set codes are refreshed automatically.
+ * Call before localhawk_initialize for the policy to apply at startup.
+ * 
+ * @param auto_refresh Non-zero to refresh stale data in the background
+ * @param max_age_days Age after which cached data is considered stale (must be > 0)
+ * @return LOCALHAWK_SUCCESS on success, LOCALHAWK_INVALID_INPUT for non-positive ages
+ * 
+ * Memory Management:
+ * - No memory is allocated by this function
+ * - No cleanup required
+ */
+int32_t localhawk_set_freshness_policy(int32_t auto_refresh, int32_t max_age_days);
+
+/**
+ * Configure what is printed for back faces whose image is unavailable,
+ * e.g. a meld card whose meld result could not be resolved.
+ * 
+ * @param fallback 0 = print the front instead, 1 = skip the back face, 2 = print a placeholder back
+ * @return LOCALHAWK_SUCCESS on success, LOCALHAWK_INVALID_INPUT for unknown values
+ * 
+ * Memory Management:
+ * - No memory is allocated by this function
+ * - No cleanup required
+ */
+int32_t localhawk_set_back_face_fallback(int32_t fallback);
+
+/**
+ * Prefer printings in a language for decklist lines that don't name one.
+ * Cards without a printing in that language fall back to English.
+ * 
+ * @param language Scryfall language code, e.g. "ja" (NULL or "" prefers English)
+ * @return LOCALHAWK_SUCCESS on success, LOCALHAWK_INVALID_INPUT for invalid UTF-8
+ * 
+ * Memory Management:
+ * - The language string is copied, the caller keeps ownership
+ * - No cleanup required
+ */
+int32_t localhawk_set_preferred_language(const char* language);
+
+/**
+ * Configure the network timeouts used by all following requests.
+ * Downloads on a dead network then fail instead of hanging background loading.
+ * 
+ * @param connect_timeout_ms Time allowed to establish a connection (must be > 0)
+ * @param read_timeout_ms Time allowed between received bytes of a response (must be > 0)
+ * @return LOCALHAWK_SUCCESS on success, LOCALHAWK_INVALID_INPUT for non-positive timeouts
+ * 
+ * Memory Management:
+ * - No memory is allocated by this function
+ * - No cleanup required
+ */
+int32_t localhawk_set_network_timeouts(int32_t connect_timeout_ms, int32_t read_timeout_ms);
+
 /**
  * Save all in-memory caches to disk.
  * This saves image cache and search results cache without shutting down.
@@ -163,6 +368,43 @@ int32_t localhawk_update_card_names(void);
  */
 int32_t localhawk_save_caches(void);
 
+/**
+ * Export the image cache to a tar archive, e.g. to seed the cache on another device.
+ * 
+ * @param path_cstr Null-terminated C string with the path of the archive to write
+ * @return LOCALHAWK_SUCCESS on success, negative error code on failure
+ * 
+ * Memory Management:
+ * - No memory is allocated by this function
+ */
+int32_t localhawk_export_image_cache(const char* path_cstr);
+
+/**
+ * Import an archive written by localhawk_export_image_cache into the image cache.
+ * Images whose bytes don't match their checksum are skipped; the cache is saved afterwards.
+ * 
+ * @param path_cstr Null-terminated C string with the path of the archive to read
+ * @param imported_out Pointer that receives the number of images added (may be NULL)
+ * @return LOCALHAWK_SUCCESS on success, LOCALHAWK_PARSE_FAILED if the archive is invalid
+ * 
+ * Memory Management:
+ * - No memory is allocated by this function
+ */
+int32_t localhawk_import_image_cache(const char* path_cstr, size_t* imported_out);
+
+/**
+ * Write all log files, oldest first, into a single file to attach to a bug report.
+ * Log files are only written when file logging is on in the settings.
+ * 
+ * @param path_cstr Null-terminated C string with the path of the file to write
+ * @param exported_out Pointer that receives the number of log files included (may be NULL)
+ * @return LOCALHAWK_SUCCESS on success, negative error code on failure
+ * 
+ * Memory Management:
+ * - No memory is allocated by this function
+ */
+int32_t localhawk_export_logs(const char* path_cstr, size_t* exported_out);
+
 /**
  * Get the image cache directory path.
  * 
@@ -225,6 +467,7 @@ typedef struct {
     char* language;             // Language code (NULL if not specified)
     int32_t face_mode;          // DoubleFaceMode: 0=FrontOnly, 1=BackOnly, 2=BothSides
     int32_t source_line_number; // Source line number (-1 if not specified)
+    char* scryfall_id;          // Pinned printing (NULL to select by set and language)
 } DecklistEntry;
 
 /**
@@ -247,6 +490,10 @@ typedef struct {
     char* back_side;      // Back face/meld result image URL (NULL if no back side)
     BackSideType back_type; // Type of back side (none, DFC, meld)
     char* back_name;      // Back face name or meld result name (NULL if no back side)
+    char* scryfall_id;    // Unique id of the printing (NULL for cards cached without one)
+    char* mana_cost;      // Mana cost, e.g. "{1}{W}{U}" (NULL if unknown or none)
+    char* type_line;      // Type line (NULL if unknown)
+    char* oracle_text;    // Rules text, faces separated by a "//" line (NULL if unknown or none)
 } CardPrinting;
 
 /**
@@ -255,6 +502,7 @@ typedef struct {
 typedef struct {
     CardPrinting* cards; // Array of card printings
     size_t count;        // Number of cards in array
+    int32_t has_more;    // 1 if Scryfall has more printings than were fetched, else 0
 } CardSearchResult;
 
 /**
@@ -342,6 +590,56 @@ int32_t localhawk_generate_pdf_from_entries(
     size_t* output_size
 );
 
+/**
+ * A rendered page image (PNG bytes).
+ */
+typedef struct {
+    uint8_t* data;
+    size_t size;
+} LocalHawkPageImage;
+
+/**
+ * Array of rendered page images, in page order.
+ */
+typedef struct {
+    LocalHawkPageImage* pages;
+    size_t count;
+} LocalHawkPageImageArray;
+
+/**
+ * Render an array of DecklistEntry structures into one PNG per page,
+ * laid out exactly like the PDF from localhawk_generate_pdf_from_entries.
+ * Useful for page previews and for sharing sheets without a PDF viewer.
+ * 
+ * @param entries Array of DecklistEntry structures
+ * @param entry_count Number of entries in the array
+ * @param dpi Resolution of the page images (10 to 600)
+ * @param output_pages Pointer that will receive the allocated page array
+ * @return LOCALHAWK_SUCCESS on success, negative error code on failure
+ * 
+ * Memory Management:
+ * - The page array and all page buffers are allocated by this function
+ * - Caller must call localhawk_free_page_images to free the memory
+ * - If function fails, no memory is allocated
+ */
+int32_t localhawk_render_pages_from_entries(
+    const DecklistEntry* entries,
+    size_t entry_count,
+    float dpi,
+    LocalHawkPageImageArray** output_pages
+);
+
+/**
+ * Free page images returned by localhawk_render_pages_from_entries.
+ * 
+ * @param pages Page array returned by localhawk_render_pages_from_entries
+ * 
+ * Memory Management:
+ * - Frees the array and every page buffer
+ * - Safe to call with NULL pointer (no-op)
+ */
+void localhawk_free_page_images(LocalHawkPageImageArray* pages);
+
 /**
  * Expand a single resolved card to its image URLs using Rust logic.
  * This ensures 100% consistency with PDF generation.
@@ -375,9 +673,35 @@ int localhawk_expand_single_card(
 );
 
 /**
- * Free memory allocated by localhawk_expand_single_card.
+ * Plan the pages of a PDF without downloading any image (dry run).
+ * Resolves the entries like localhawk_generate_pdf_from_entries and returns the image URLs
+ * it would print, in print order; every 9 consecutive URLs fill one page.
+ *
+ * @param entries Array of DecklistEntry structures
+ * @param entry_count Number of entries in the array
+ * @param out_urls Output: Array of image URL strings
+ * @param out_count Output: Number of URLs in the array
+ * @param out_page_count Output: Number of pages the PDF would have
+ * @return LOCALHAWK_SUCCESS on success, LOCALHAWK_PARSE_FAILED if entries can't be resolved,
+ *         negative error code on failure
  *
- * @param urls Array of image URL strings returned by localhawk_expand_single_card
+ * Memory Management:
+ * - The output array and all strings are allocated by this function
+ * - Caller must call localhawk_free_image_urls to free the memory
+ * - If function fails, no memory is allocated
+ */
+int localhawk_plan_pages_from_entries(
+    const DecklistEntry* entries,
+    size_t entry_count,
+    char*** out_urls,
+    size_t* out_count,
+    size_t* out_page_count
+);
+
+/**
+ * Free memory allocated by localhawk_expand_single_card or localhawk_plan_pages_from_entries.
+ *
+ * @param urls Array of image URL strings returned by either function
  * @param count Number of URLs in the array
  *
  * Memory Management:
@@ -405,6 +729,26 @@ int32_t localhawk_get_cached_image_bytes(
     size_t* output_size
 );
 
+/**
+ * Get a downscaled copy (300 pixels wide JPEG) of a cached image, for grids of thumbnails.
+ * The thumbnail is made on first access and cached next to the image cache.
+ * 
+ * @param image_url_cstr Null-terminated C string containing the image URL
+ * @param output_buffer Pointer to buffer pointer (will be allocated by this function)
+ * @param output_size Pointer to size_t that will receive the buffer size
+ * @return LOCALHAWK_SUCCESS if image is cached, LOCALHAWK_PARSE_FAILED if not cached, negative error code on failure
+ * 
+ * Memory Management:
+ * - The output buffer is allocated by this function using malloc
+ * - Caller must call localhawk_free_buffer to free the memory
+ * - If function fails or image not cached, no memory is allocated
+ */
+int32_t localhawk_get_cached_thumbnail_bytes(
+    const char* image_url_cstr,
+    uint8_t** output_buffer,
+    size_t* output_size
+);
+
 /**
  * Check if an image is cached without retrieving the bytes.
  * 
@@ -443,6 +787,7 @@ typedef struct {
     LocalHawkDoubleFaceMode face_mode; // Face mode for this card
     BackSideType back_type;         // Type of back side (none, DFC, meld)
     char* back_name;                // Back face name or meld result name (NULL if no back side)
+    char* scryfall_id;              // Unique id of the printing (NULL for cards cached without one)
 } LocalHawkResolvedCard;
 
 /**
@@ -572,6 +917,8 @@ int32_t localhawk_start_background_loading(
  * - No memory is allocated by this function
  * - Progress structure is filled by value
  * - Call periodically to get latest progress updates
+ * - Once a finished task's final progress (phase LOCALHAWK_LOADING_COMPLETED) has been
+ *   returned, the handle is forgotten and has_progress is set to 0 from then on
  */
 int32_t localhawk_get_background_progress(
     BackgroundLoadHandleId handle_id,
@@ -581,6 +928,8 @@ int32_t localhawk_get_background_progress(
 
 /**
  * Cancel background loading task.
+ * Running loads stop after their current download and report a
+ * LoadAborted image cache notification with the number of images loaded.
  * 
  * @param handle_id Handle ID of the task to cancel
  * @return LOCALHAWK_SUCCESS on success, negative error code on failure
@@ -618,9 +967,11 @@ typedef void (*LocalHawkDispatchNotifyFn)(const void* source_ptr, const char* ke
  * Image cache change notification structure
  */
 typedef struct {
-    uint8_t change_type;    // 1=ImageCached, 2=ImageRemoved
-    char* image_url;        // Null-terminated C string (caller must free)
+    uint8_t change_type;    // 1=ImageCached, 2=ImageRemoved, 3=LoadFinished, 4=LoadAborted
+    char* image_url;        // Null-terminated C string (caller must free); load name for types 3 and 4
     uint64_t timestamp;     // Unix timestamp in milliseconds
+    size_t completed;       // Images loaded by the background load (types 3 and 4)
+    size_t total;           // Images the background load set out to load (types 3 and 4)
 } LocalHawkImageCacheNotification;
 
 /**
@@ -0,0 +1,965 @@
+use super::{
+    BackgroundLoadProgress, LoadingPhase, select_card_from_printings, spread_basic_land_copies,
+};
+use crate::globals::{get_or_fetch_image_bytes, get_or_fetch_search_results};
+use crate::scryfall::models::Card;
+use crate::{DecklistEntry, DoubleFaceMode, ProxyError};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::{Arc, Mutex, OnceLock};
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
+use tokio::sync::{OnceCell, Semaphore};
+use tokio::task::{JoinHandle, JoinSet};
+use tokio_util::sync::CancellationToken;
+use tracing::{Instrument, debug, info, info_span, warn};
+
+pub struct BackgroundLoadHandle {
+    handle: JoinHandle<Result<(), ProxyError>>,
+    progress_rx: tokio::sync::mpsc::UnboundedReceiver<BackgroundLoadProgress>,
+    selection_tx: UnboundedSender<(usize, Card)>,
+    priority_tx: UnboundedSender<Vec<String>>,
+    cancel_token: CancellationToken,
+}
+
+impl BackgroundLoadHandle {
+    /// Get latest progress (non-blocking)
+    pub fn try_get_progress(&mut self) -> Option<BackgroundLoadProgress> {
+        // Drain all available progress messages and return the latest one
+        let mut latest_progress = None;
+        while let Ok(progress) = self.progress_rx.try_recv() {
+            latest_progress = Some(progress);
+        }
+        latest_progress
+    }
+
+    /// Load the images of `card` for the entry at `entry_index` (an index into the entries the
+    /// load was started with) before everything else
+    ///
+    /// Call this when the user picks another printing, so that bandwidth goes to the images
+    /// that will be printed; the printing it replaces is only loaded as an alternative.
+    pub fn select_printing(&self, entry_index: usize, card: Card) {
+        if self.selection_tx.send((entry_index, card)).is_err() {
+            debug!(
+                entry_index,
+                "Background loading finished, ignoring printing selection"
+            );
+        }
+    }
+
+    /// Download the images at `urls` before any other job, e.g. printings shown side by side
+    ///
+    /// Images that are already loaded or downloading aren't downloaded again. Returns false if
+    /// loading has finished, so the caller has to fetch the images itself.
+    pub fn prioritize_images(&self, urls: Vec<String>) -> bool {
+        self.priority_tx.send(urls).is_ok()
+    }
+
+    /// Cancel background loading
+    pub fn cancel(&self) {
+        self.cancel_token.cancel();
+    }
+
+    /// Wait for completion
+    pub async fn wait_for_completion(self) -> Result<(), ProxyError> {
+        self.handle
+            .await
+            .map_err(|e| ProxyError::Cache(format!("Task join error: {}", e)))?
+    }
+
+    /// Check if finished (non-blocking)
+    pub fn is_finished(&self) -> bool {
+        self.handle.is_finished()
+    }
+}
+
+/// Limits for downloading images in the background
+///
+/// Image downloads go to Scryfall's image CDN, which is not subject to the API rate limit
+/// that card searches go through, so they are bounded by their own pools instead.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BackgroundLoadConfig {
+    /// Images downloaded at the same time across all hosts
+    pub max_concurrent_downloads: usize,
+    /// Images downloaded at the same time from a single host
+    pub max_downloads_per_host: usize,
+    /// Selected-printing downloads started before a waiting alternative gets its turn
+    pub selected_per_alternative: usize,
+}
+
+impl Default for BackgroundLoadConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrent_downloads: 6,
+            max_downloads_per_host: 4,
+            selected_per_alternative: 3,
+        }
+    }
+}
+
+/// Start background image loading for resolved decklist entries
+pub fn start_background_image_loading(entries: Vec<DecklistEntry>) -> BackgroundLoadHandle {
+    start_background_image_loading_with_config(entries, BackgroundLoadConfig::default())
+}
+
+/// Start background image loading with custom download limits
+pub fn start_background_image_loading_with_config(
+    entries: Vec<DecklistEntry>,
+    config: BackgroundLoadConfig,
+) -> BackgroundLoadHandle {
+    spawn_background_load(entries, config, None)
+}
+
+/// The background load of the current decklist, replaced when another decklist is loaded
+///
+/// Starting a load cancels the running one, and the new load waits for it to stop before it
+/// searches anything. Parsing a decklist again while its images are loading thus neither
+/// downloads them twice nor mixes the progress of both loads.
+#[derive(Default)]
+pub struct BackgroundLoader {
+    current: Option<BackgroundLoadHandle>,
+    generation: u64,
+}
+
+impl BackgroundLoader {
+    /// Start loading the images of `entries` in place of the current load
+    ///
+    /// Returns the new load's generation, see [`BackgroundLoader::generation`].
+    pub fn start(&mut self, entries: Vec<DecklistEntry>) -> u64 {
+        self.start_with_config(entries, BackgroundLoadConfig::default())
+    }
+
+    /// Start loading with custom download limits in place of the current load
+    pub fn start_with_config(
+        &mut self,
+        entries: Vec<DecklistEntry>,
+        config: BackgroundLoadConfig,
+    ) -> u64 {
+        let previous = self.current.take();
+        if let Some(previous) = previous.as_ref().filter(|handle| !handle.is_finished()) {
+            debug!(
+                generation = self.generation,
+                "Replacing the running background load"
+            );
+            previous.cancel();
+        }
+        self.current = Some(spawn_background_load(entries, config, previous));
+        self.generation += 1;
+        self.generation
+    }
+
+    /// Cancel the current load, e.g. when a decklist is parsed again
+    ///
+    /// The load is kept until the next one starts, which waits for it to stop.
+    pub fn cancel(&self) {
+        if let Some(handle) = &self.current {
+            handle.cancel();
+        }
+    }
+
+    /// Handle of the current load, also once it has finished
+    pub fn handle(&self) -> Option<&BackgroundLoadHandle> {
+        self.current.as_ref()
+    }
+
+    pub fn handle_mut(&mut self) -> Option<&mut BackgroundLoadHandle> {
+        self.current.as_mut()
+    }
+
+    /// Whether the current load is still running
+    pub fn is_running(&self) -> bool {
+        self.current
+            .as_ref()
+            .is_some_and(|handle| !handle.is_finished())
+    }
+
+    /// Number of loads started so far, identifying the current one
+    ///
+    /// Progress polling can stop once the load it was started for is no longer current.
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+}
+
+/// Spawn a background load, which first waits for the cancelled `previous` load to stop
+fn spawn_background_load(
+    entries: Vec<DecklistEntry>,
+    config: BackgroundLoadConfig,
+    previous: Option<BackgroundLoadHandle>,
+) -> BackgroundLoadHandle {
+    let (progress_tx, progress_rx) = tokio::sync::mpsc::unbounded_channel();
+    let (selection_tx, selection_rx) = tokio::sync::mpsc::unbounded_channel();
+    let (priority_tx, priority_rx) = tokio::sync::mpsc::unbounded_channel();
+    let cancel_token = CancellationToken::new();
+    let cancel_clone = cancel_token.clone();
+
+    debug!(
+        entries = entries.len(),
+        ?config,
+        "Starting background image loading"
+    );
+
+    // Everything logged while loading (searches and downloads) belongs to this span
+    let span = info_span!("background_loading", entries = entries.len());
+    let handle = tokio::spawn(
+        async move {
+            // Its downloads in flight finish or stop before any of this load start
+            if let Some(previous) = previous {
+                if let Err(e) = previous.wait_for_completion().await {
+                    debug!(error = %e, "Previous background load ended with an error");
+                }
+            }
+            load_background_images_impl(
+                entries,
+                config,
+                progress_tx,
+                selection_rx,
+                priority_rx,
+                cancel_clone,
+            )
+            .await
+        }
+        .instrument(span),
+    );
+
+    BackgroundLoadHandle {
+        handle,
+        progress_rx,
+        selection_tx,
+        priority_tx,
+        cancel_token,
+    }
+}
+
+/// Images to download for one searched decklist entry
+struct SearchOutcome {
+    entry_index: usize,
+    /// Images of the selected printing, or None if no printing matched
+    selected_urls: Option<Vec<String>>,
+    /// Front images of all other printings
+    alternative_urls: Vec<String>,
+    error: Option<String>,
+}
+
+enum ImageJob {
+    /// All images of an entry's selected printing, counted as one loaded entry
+    Selected {
+        entry_index: usize,
+        urls: Vec<String>,
+    },
+    /// Front image of an alternative printing
+    Alternative(String),
+}
+
+/// Pending downloads, handing out selected printings first without starving alternatives
+struct JobQueue {
+    /// Images the front end is waiting for, see `BackgroundLoadHandle::prioritize_images`
+    prioritized: VecDeque<String>,
+    selected: VecDeque<(usize, Vec<String>)>,
+    alternatives: VecDeque<String>,
+    selected_per_alternative: usize,
+    selected_streak: usize,
+}
+
+impl JobQueue {
+    fn new(selected_per_alternative: usize) -> Self {
+        Self {
+            prioritized: VecDeque::new(),
+            selected: VecDeque::new(),
+            alternatives: VecDeque::new(),
+            selected_per_alternative: selected_per_alternative.max(1),
+            selected_streak: 0,
+        }
+    }
+
+    fn next(&mut self) -> Option<ImageJob> {
+        if let Some(url) = self.prioritized.pop_front() {
+            return Some(ImageJob::Alternative(url));
+        }
+
+        let alternatives_turn =
+            self.selected.is_empty() || self.selected_streak >= self.selected_per_alternative;
+        if alternatives_turn && !self.alternatives.is_empty() {
+            self.selected_streak = 0;
+            return self.alternatives.pop_front().map(ImageJob::Alternative);
+        }
+
+        let (entry_index, urls) = self.selected.pop_front()?;
+        self.selected_streak += 1;
+        Some(ImageJob::Selected { entry_index, urls })
+    }
+
+    /// Load `urls` as the selected printing of an entry before any other job
+    ///
+    /// A still pending job for the printing they replace becomes alternatives; images queued as
+    /// alternatives move up, other images already queued or loaded aren't downloaded again.
+    fn reselect(&mut self, entry_index: usize, urls: Vec<String>, queued: &mut HashSet<String>) {
+        let pending = self
+            .selected
+            .iter()
+            .position(|(queued_entry, _)| *queued_entry == entry_index);
+        if let Some((_, replaced)) = pending.and_then(|position| self.selected.remove(position)) {
+            self.alternatives.extend(replaced);
+        }
+
+        let urls = urls
+            .into_iter()
+            .filter(
+                |url| match self.alternatives.iter().position(|queued| queued == url) {
+                    Some(position) => {
+                        self.alternatives.remove(position);
+                        true
+                    }
+                    None => queued.insert(url.clone()),
+                },
+            )
+            .collect();
+        self.selected.push_front((entry_index, urls));
+    }
+
+    /// Load `urls` before any other job, returning how many of them weren't queued yet
+    ///
+    /// Images queued as alternatives move up; images already loaded, downloading or queued as
+    /// a selected printing stay where they are.
+    fn prioritize(&mut self, urls: Vec<String>, queued: &mut HashSet<String>) -> usize {
+        let mut added = 0;
+        for url in urls.into_iter().rev() {
+            if let Some(position) = self.alternatives.iter().position(|queued| *queued == url) {
+                self.alternatives.remove(position);
+            } else if queued.insert(url.clone()) {
+                added += 1;
+            } else {
+                continue;
+            }
+            self.prioritized.push_front(url);
+        }
+        added
+    }
+
+    fn is_empty(&self) -> bool {
+        self.prioritized.is_empty() && self.selected.is_empty() && self.alternatives.is_empty()
+    }
+}
+
+/// One download pool per image host
+struct HostPools {
+    permits_per_host: usize,
+    pools: Mutex<HashMap<String, Arc<Semaphore>>>,
+}
+
+impl HostPools {
+    fn new(permits_per_host: usize) -> Self {
+        Self {
+            permits_per_host: permits_per_host.max(1),
+            pools: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn pool_for(&self, url: &str) -> Arc<Semaphore> {
+        self.pools
+            .lock()
+            .unwrap()
+            .entry(url_host(url).to_string())
+            .or_insert_with(|| Arc::new(Semaphore::new(self.permits_per_host)))
+            .clone()
+    }
+}
+
+fn url_host(url: &str) -> &str {
+    let without_scheme = url.split_once("://").map_or(url, |(_, rest)| rest);
+    without_scheme.split('/').next().unwrap_or(without_scheme)
+}
+
+/// Outcome of a download that every background load asking for the same URL shares
+type SharedDownload = Arc<OnceCell<Result<(), String>>>;
+
+// Downloads currently running in any background load, keyed by URL
+static IN_FLIGHT_DOWNLOADS: OnceLock<Mutex<HashMap<String, SharedDownload>>> = OnceLock::new();
+
+fn get_in_flight_downloads() -> &'static Mutex<HashMap<String, SharedDownload>> {
+    IN_FLIGHT_DOWNLOADS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Removes a download from the in-flight set once its starter finishes or is cancelled
+struct InFlightGuard {
+    url: String,
+    download: SharedDownload,
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        let mut in_flight = get_in_flight_downloads().lock().unwrap();
+        if in_flight
+            .get(&self.url)
+            .is_some_and(|download| Arc::ptr_eq(download, &self.download))
+        {
+            in_flight.remove(&self.url);
+        }
+    }
+}
+
+/// Download an image, joining a download of the same URL that another background load started
+async fn fetch_image(pools: &HostPools, url: &str) -> Result<(), String> {
+    let (download, _guard) = {
+        let mut in_flight = get_in_flight_downloads().lock().unwrap();
+        match in_flight.get(url) {
+            Some(download) => {
+                debug!(url, "Joining in-flight download");
+                (download.clone(), None)
+            }
+            None => {
+                let download = SharedDownload::default();
+                in_flight.insert(url.to_string(), download.clone());
+                let guard = InFlightGuard {
+                    url: url.to_string(),
+                    download: download.clone(),
+                };
+                (download, Some(guard))
+            }
+        }
+    };
+
+    download
+        .get_or_init(|| download_image(pools, url))
+        .await
+        .clone()
+}
+
+async fn download_image(pools: &HostPools, url: &str) -> Result<(), String> {
+    let pool = pools.pool_for(url);
+    let _permit = pool
+        .acquire()
+        .await
+        .map_err(|e| format!("Download pool closed for {}: {}", url, e))?;
+
+    debug!(url, "Caching image");
+    get_or_fetch_image_bytes(url)
+        .await
+        .map(|_| ())
+        .map_err(|e| format!("Failed to cache {}: {}", url, e))
+}
+
+/// Download a job's images, returning the entry of a selected printing and any errors
+async fn run_job(job: ImageJob, pools: Arc<HostPools>) -> (Option<usize>, Vec<String>) {
+    match job {
+        ImageJob::Selected { entry_index, urls } => {
+            let mut errors = Vec::new();
+            for url in urls {
+                if let Err(error) = fetch_image(&pools, &url).await {
+                    errors.push(error);
+                }
+            }
+            (Some(entry_index), errors)
+        }
+        ImageJob::Alternative(url) => {
+            let errors = fetch_image(&pools, &url).await.err().into_iter().collect();
+            (None, errors)
+        }
+    }
+}
+
+/// Search each entry in turn (through the rate-limited API) and report which images it needs
+async fn search_entries(
+    entries: Vec<DecklistEntry>,
+    outcome_tx: UnboundedSender<SearchOutcome>,
+    cancel_token: CancellationToken,
+) {
+    for (entry_idx, entry) in entries.iter().enumerate() {
+        if cancel_token.is_cancelled() {
+            debug!(
+                entry = entry_idx,
+                "Background loading cancelled while searching"
+            );
+            return;
+        }
+
+        debug!(
+            entry = entry_idx + 1,
+            of = entries.len(),
+            name = %entry.name,
+            set = ?entry.set,
+            lang = ?entry.lang,
+            face_mode = ?entry.face_mode,
+            "Searching entry"
+        );
+
+        let outcome = match get_or_fetch_search_results(&entry.name).await {
+            Ok(search_result) => {
+                // Select printing based on entry's set/lang hints
+                // Basic lands spread across several arts need all of them
+                let selected_indices: Vec<usize> =
+                    match spread_basic_land_copies(&search_result.cards, entry) {
+                        Some(spread) => spread.into_iter().map(|(index, _)| index).collect(),
+                        None => select_card_from_printings(&search_result.cards, entry)
+                            .into_iter()
+                            .collect(),
+                    };
+                let alternative_urls = search_result
+                    .cards
+                    .iter()
+                    .enumerate()
+                    .filter(|(card_idx, _)| !selected_indices.contains(card_idx))
+                    .map(|(_, card)| card.border_crop.clone())
+                    .collect();
+
+                if !selected_indices.is_empty() {
+                    let mut selected_urls = Vec::new();
+                    for &selected_index in &selected_indices {
+                        let selected_card = &search_result.cards[selected_index];
+                        debug!(
+                            printing = selected_index + 1,
+                            of = search_result.cards.len(),
+                            name = %selected_card.name,
+                            set = %selected_card.set,
+                            lang = %selected_card.language,
+                            "Selected printing"
+                        );
+                        selected_urls.extend(get_image_urls_for_face_mode(
+                            selected_card,
+                            &entry.face_mode,
+                        ));
+                    }
+                    SearchOutcome {
+                        entry_index: entry_idx,
+                        selected_urls: Some(selected_urls),
+                        alternative_urls,
+                        error: None,
+                    }
+                } else {
+                    SearchOutcome {
+                        entry_index: entry_idx,
+                        selected_urls: None,
+                        alternative_urls,
+                        error: Some(format!("No suitable printing found for '{}'", entry.name)),
+                    }
+                }
+            }
+            Err(e) => SearchOutcome {
+                entry_index: entry_idx,
+                selected_urls: None,
+                alternative_urls: Vec::new(),
+                error: Some(format!("Search failed for '{}': {}", entry.name, e)),
+            },
+        };
+
+        if outcome_tx.send(outcome).is_err() {
+            return;
+        }
+    }
+}
+
+async fn load_background_images_impl(
+    entries: Vec<DecklistEntry>,
+    config: BackgroundLoadConfig,
+    progress_tx: UnboundedSender<BackgroundLoadProgress>,
+    mut selection_rx: UnboundedReceiver<(usize, Card)>,
+    mut priority_rx: UnboundedReceiver<Vec<String>>,
+    cancel_token: CancellationToken,
+) -> Result<(), ProxyError> {
+    // Lifecycle events carry a `lifecycle` field: started, then completed or cancelled
+    info!(
+        lifecycle = "started",
+        entries = entries.len(),
+        "Background loading started"
+    );
+
+    let mut progress = BackgroundLoadProgress {
+        phase: LoadingPhase::Selected,
+        current_entry: 0,
+        total_entries: entries.len(),
+        selected_loaded: 0,
+        alternatives_loaded: 0,
+        total_alternatives: 0,
+        errors: Vec::new(),
+    };
+    send_progress(&progress_tx, progress.clone());
+
+    // Printings picked by the user replace the selected ones, see `select_printing`
+    let face_modes: Vec<DoubleFaceMode> = entries
+        .iter()
+        .map(|entry| entry.face_mode.clone())
+        .collect();
+    let mut reselected: HashMap<usize, Vec<String>> = HashMap::new();
+    let mut selections_closed = false;
+    let mut priorities_closed = false;
+
+    // Searches run one after another alongside the downloads, feeding the job queue
+    let (outcome_tx, mut outcome_rx) = tokio::sync::mpsc::unbounded_channel();
+    let search_handle =
+        tokio::spawn(search_entries(entries, outcome_tx, cancel_token.clone()).in_current_span());
+
+    let pools = Arc::new(HostPools::new(config.max_downloads_per_host));
+    let max_concurrent_downloads = config.max_concurrent_downloads.max(1);
+    let mut queue = JobQueue::new(config.selected_per_alternative);
+    let mut downloads = JoinSet::new();
+    let mut selected_in_flight = 0;
+    let mut searches_done = false;
+    // Every URL is downloaded once per load, even if several entries or printings share it
+    let mut queued_urls = HashSet::new();
+    let mut loaded_entries = HashSet::new();
+
+    loop {
+        while downloads.len() < max_concurrent_downloads {
+            let Some(job) = queue.next() else {
+                break;
+            };
+            if matches!(job, ImageJob::Selected { .. }) {
+                selected_in_flight += 1;
+            }
+            downloads.spawn(run_job(job, pools.clone()));
+        }
+
+        if searches_done && queue.is_empty() && downloads.is_empty() {
+            break;
+        }
+
+        tokio::select! {
+            _ = cancel_token.cancelled() => {
+                info!(
+                    lifecycle = "cancelled",
+                    selected = progress.selected_loaded,
+                    alternatives = progress.alternatives_loaded,
+                    "Background loading cancelled"
+                );
+                search_handle.abort();
+                downloads.abort_all();
+                return Ok(());
+            }
+            outcome = outcome_rx.recv(), if !searches_done => match outcome {
+                Some(outcome) => {
+                    progress.current_entry += 1;
+                    if let Some(error) = outcome.error {
+                        warn!("{}", error);
+                        progress.errors.push(error);
+                    }
+                    let mut selected_urls = outcome.selected_urls;
+                    let mut alternative_urls = outcome.alternative_urls;
+                    // Picked before the search finished: the default printing is an alternative
+                    if let Some(urls) = reselected.remove(&outcome.entry_index) {
+                        alternative_urls.extend(selected_urls.replace(urls).unwrap_or_default());
+                    }
+                    if let Some(urls) = selected_urls {
+                        let urls = urls
+                            .into_iter()
+                            .filter(|url| queued_urls.insert(url.clone()))
+                            .collect();
+                        queue.selected.push_back((outcome.entry_index, urls));
+                    }
+                    let alternatives: Vec<String> = alternative_urls
+                        .into_iter()
+                        .filter(|url| queued_urls.insert(url.clone()))
+                        .collect();
+                    progress.total_alternatives += alternatives.len();
+                    queue.alternatives.extend(alternatives);
+                }
+                None => searches_done = true,
+            },
+            selection = selection_rx.recv(), if !selections_closed => match selection {
+                Some((entry_index, card)) => match face_modes.get(entry_index) {
+                    Some(face_mode) => {
+                        debug!(entry = entry_index, name = %card.name, set = %card.set, "Printing selected");
+                        let urls = get_image_urls_for_face_mode(&card, face_mode);
+                        if entry_index < progress.current_entry {
+                            queue.reselect(entry_index, urls, &mut queued_urls);
+                            let alternatives_in_flight = downloads.len() - selected_in_flight;
+                            progress.total_alternatives = progress.alternatives_loaded
+                                + alternatives_in_flight
+                                + queue.alternatives.len();
+                        } else {
+                            reselected.insert(entry_index, urls);
+                        }
+                    }
+                    None => debug!(entry = entry_index, "Printing selected for an entry that isn't loaded"),
+                },
+                None => selections_closed = true,
+            },
+            urls = priority_rx.recv(), if !priorities_closed => match urls {
+                Some(urls) => {
+                    debug!(images = urls.len(), "Images prioritized");
+                    // Prioritized images count as alternatives
+                    progress.total_alternatives += queue.prioritize(urls, &mut queued_urls);
+                }
+                None => priorities_closed = true,
+            },
+            Some(finished) = downloads.join_next(), if !downloads.is_empty() => {
+                match finished {
+                    Ok((selected_entry, errors)) => {
+                        if let Some(entry_index) = selected_entry {
+                            selected_in_flight -= 1;
+                            // A printing picked after the entry loaded doesn't count it twice
+                            if loaded_entries.insert(entry_index) {
+                                progress.selected_loaded += 1;
+                            }
+                        } else {
+                            progress.alternatives_loaded += 1;
+                        }
+                        for error in &errors {
+                            warn!("{}", error);
+                        }
+                        progress.errors.extend(errors);
+                    }
+                    Err(e) => {
+                        let error_msg = format!("Image download task failed: {}", e);
+                        warn!("{}", error_msg);
+                        progress.errors.push(error_msg);
+                    }
+                }
+            }
+        }
+
+        // Stay in the selected phase until every selected printing has been loaded
+        progress.phase = if searches_done && queue.selected.is_empty() && selected_in_flight == 0 {
+            LoadingPhase::Alternatives
+        } else {
+            LoadingPhase::Selected
+        };
+        send_progress(&progress_tx, progress.clone());
+    }
+
+    info!(
+        lifecycle = "completed",
+        selected = progress.selected_loaded,
+        alternatives = progress.alternatives_loaded,
+        errors = progress.errors.len(),
+        "Background loading completed"
+    );
+
+    // Final progress
+    progress.phase = LoadingPhase::Completed;
+    send_progress(&progress_tx, progress);
+
+    Ok(())
+}
+
+fn send_progress(tx: &UnboundedSender<BackgroundLoadProgress>, progress: BackgroundLoadProgress) {
+    if tx.send(progress).is_err() {
+        // Receiver dropped, ignore
+        debug!("Progress receiver dropped, stopping progress updates");
+    }
+}
+
+/// Get image URLs for a card based on face mode
+/// This mirrors the logic from the GUI's image URL handling
+fn get_image_urls_for_face_mode(
+    card: &crate::scryfall::models::Card,
+    face_mode: &DoubleFaceMode,
+) -> Vec<String> {
+    card.get_images_for_face_mode(face_mode)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scryfall::models::{BackSide, Card};
+    use tracing::field::{Field, Visit};
+    use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+
+    /// Records the `lifecycle` field of every event
+    #[derive(Clone, Default)]
+    struct LifecycleRecorder(Arc<Mutex<Vec<String>>>);
+
+    impl Visit for LifecycleRecorder {
+        fn record_str(&mut self, field: &Field, value: &str) {
+            if field.name() == "lifecycle" {
+                self.0.lock().unwrap().push(value.to_string());
+            }
+        }
+
+        fn record_debug(&mut self, _field: &Field, _value: &dyn std::fmt::Debug) {}
+    }
+
+    impl<S: tracing::Subscriber> Layer<S> for LifecycleRecorder {
+        fn on_event(&self, event: &tracing::Event<'_>, _ctx: Context<'_, S>) {
+            event.record(&mut self.clone());
+        }
+    }
+
+    #[test]
+    fn test_job_queue_interleaves_alternatives() {
+        let mut queue = JobQueue::new(2);
+        queue
+            .selected
+            .extend((0..4).map(|i| (i, vec![format!("selected{}", i)])));
+        queue
+            .alternatives
+            .extend((0..3).map(|i| format!("alternative{}", i)));
+
+        let order: Vec<String> = std::iter::from_fn(|| queue.next())
+            .map(|job| match job {
+                ImageJob::Selected { urls, .. } => urls[0].clone(),
+                ImageJob::Alternative(url) => url,
+            })
+            .collect();
+        assert_eq!(
+            order,
+            vec![
+                "selected0",
+                "selected1",
+                "alternative0",
+                "selected2",
+                "selected3",
+                "alternative1",
+                "alternative2",
+            ]
+        );
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn test_job_queue_reselect() {
+        let mut queue = JobQueue::new(2);
+        let mut queued: HashSet<String> = ["default1", "default2", "other", "loaded"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        queue.selected.push_back((0, vec!["first".to_string()]));
+        queue.selected.push_back((1, vec!["default1".to_string()]));
+        queue.selected.push_back((2, vec!["default2".to_string()]));
+        queue.alternatives.push_back("other".to_string());
+
+        // Entry 1's pending default printing becomes an alternative, the picked one moves up
+        queue.reselect(1, vec!["other".to_string()], &mut queued);
+        // Entry 2's picked printing is already loaded, its new back face isn't
+        queue.reselect(
+            2,
+            vec!["loaded".to_string(), "new back".to_string()],
+            &mut queued,
+        );
+
+        assert_eq!(
+            queue.selected,
+            VecDeque::from([
+                (2, vec!["new back".to_string()]),
+                (1, vec!["other".to_string()]),
+                (0, vec!["first".to_string()]),
+            ])
+        );
+        assert_eq!(
+            queue.alternatives,
+            VecDeque::from(["default1".to_string(), "default2".to_string()])
+        );
+        assert!(queued.contains("new back"));
+    }
+
+    #[test]
+    fn test_job_queue_prioritize() {
+        let mut queue = JobQueue::new(2);
+        let mut queued: HashSet<String> = ["selected", "alternative", "loaded"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        queue.selected.push_back((0, vec!["selected".to_string()]));
+        queue.alternatives.push_back("alternative".to_string());
+
+        let urls = ["new", "alternative", "loaded", "selected"].map(String::from);
+        assert_eq!(queue.prioritize(urls.to_vec(), &mut queued), 1);
+
+        let order: Vec<String> = std::iter::from_fn(|| queue.next())
+            .map(|job| match job {
+                ImageJob::Selected { urls, .. } => urls[0].clone(),
+                ImageJob::Alternative(url) => url,
+            })
+            .collect();
+        assert_eq!(order, vec!["new", "alternative", "selected"]);
+        assert!(queued.contains("new"));
+    }
+
+    #[tokio::test]
+    async fn test_in_flight_downloads_are_coalesced() {
+        let url = "https://example.invalid/coalesced.jpg";
+        let pools = HostPools::new(1);
+
+        // Pretend another background load is already downloading this URL
+        let download = SharedDownload::default();
+        get_in_flight_downloads()
+            .lock()
+            .unwrap()
+            .insert(url.to_string(), download.clone());
+        download.set(Ok(())).unwrap();
+
+        // The shared outcome is used instead of starting a second (failing) download
+        assert_eq!(fetch_image(&pools, url).await, Ok(()));
+        get_in_flight_downloads().lock().unwrap().remove(url);
+    }
+
+    #[tokio::test]
+    async fn test_lifecycle_events() {
+        // The current-thread test runtime runs the loading task on this thread
+        let recorder = LifecycleRecorder::default();
+        let _guard =
+            tracing::subscriber::set_default(tracing_subscriber::registry().with(recorder.clone()));
+
+        start_background_image_loading(Vec::new())
+            .wait_for_completion()
+            .await
+            .unwrap();
+
+        assert_eq!(*recorder.0.lock().unwrap(), vec!["started", "completed"]);
+    }
+
+    #[tokio::test]
+    async fn test_background_loader_replaces_load() {
+        let mut loader = BackgroundLoader::default();
+        assert!(!loader.is_running());
+        assert_eq!(loader.start(Vec::new()), 1);
+        let first_token = loader.handle().unwrap().cancel_token.clone();
+
+        // The first load is cancelled and awaited by the second one
+        assert_eq!(loader.start(Vec::new()), 2);
+        assert!(first_token.is_cancelled());
+        assert_eq!(loader.generation(), 2);
+
+        loader.cancel();
+        let second = loader.current.take().unwrap();
+        assert!(second.cancel_token.is_cancelled());
+        second.wait_for_completion().await.unwrap();
+    }
+
+    #[test]
+    fn test_url_host() {
+        assert_eq!(
+            url_host("https://cards.scryfall.io/border_crop/front/a/b.jpg"),
+            "cards.scryfall.io"
+        );
+        assert_eq!(
+            url_host("svgs.scryfall.io/sets/bro.svg"),
+            "svgs.scryfall.io"
+        );
+    }
+
+    #[test]
+    fn test_get_image_urls_for_face_mode() {
+        let card = Card {
+            name: "Test Card".to_string(),
+            set: "TST".to_string(),
+            language: "en".to_string(),
+            border_crop: "front_url".to_string(),
+            back_side: Some(BackSide::DfcBack {
+                image_url: "back_url".to_string(),
+                name: "Test Card Back".to_string(),
+            }),
+            frame: None,
+            digital: false,
+            collector_number: None,
+            prices: None,
+            legalities: Default::default(),
+            layout: None,
+            scryfall_id: None,
+            oracle_id: None,
+            mana_cost: None,
+            type_line: None,
+            oracle_text: None,
+            power_toughness: None,
+        };
+
+        // Test FrontOnly
+        let urls = get_image_urls_for_face_mode(&card, &DoubleFaceMode::FrontOnly);
+        assert_eq!(urls, vec!["front_url"]);
+
+        // Test BackOnly
+        let urls = get_image_urls_for_face_mode(&card, &DoubleFaceMode::BackOnly);
+        assert_eq!(urls, vec!["back_url"]);
+
+        // Test BothSides
+        let urls = get_image_urls_for_face_mode(&card, &DoubleFaceMode::BothSides);
+        assert_eq!(urls, vec!["front_url", "back_url"]);
+    }
+}
@@ -0,0 +1,590 @@
+// The async loader prefetches images for the desktop frontends; the iOS library loads through
+// ios_api and only shares the printing selection
+#[cfg(feature = "background-loading")]
+mod loader;
+
+use crate::DecklistEntry;
+
+#[cfg(feature = "background-loading")]
+pub use loader::{
+    BackgroundLoadConfig, BackgroundLoadHandle, BackgroundLoader, start_background_image_loading,
+    start_background_image_loading_with_config,
+};
+
+#[derive(Debug, Clone)]
+pub struct BackgroundLoadProgress {
+    pub phase: LoadingPhase,
+    pub current_entry: usize,
+    pub total_entries: usize,
+    pub selected_loaded: usize,
+    pub alternatives_loaded: usize,
+    pub total_alternatives: usize,
+    pub errors: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LoadingPhase {
+    Selected,     // Loading selected printings (based on set/lang hints)
+    Alternatives, // Loading alternative printings
+    Completed,    // All done
+}
+
+/// Select the best card from available printings based on DecklistEntry preferences
+/// This mirrors the logic from the GUI's select_card_from_printings function
+///
+/// A pinned Scryfall id wins when one of the printings has it; set and language are only
+/// matched otherwise, since several printings can share both. Among those, the printing with
+/// the entry's collector number comes first, if there is one. Entries that name a printing
+/// exactly (see [`DecklistEntry::is_exact_printing`]) only match that printing. Entries
+/// without a language prefer the preferred language (see
+/// [`crate::globals::get_preferred_language`]), then English. Entries without a set prefer a
+/// favorite printing (see [`crate::globals::is_favorite_printing`]) over both.
+pub(crate) fn select_card_from_printings(
+    available_printings: &[crate::scryfall::models::Card],
+    entry: &DecklistEntry,
+) -> Option<usize> {
+    let preferred_language = crate::globals::get_preferred_language();
+    select_card_preferring_language(
+        available_printings,
+        entry,
+        preferred_language.as_deref(),
+        crate::globals::is_favorite_printing,
+    )
+}
+
+fn select_card_preferring_language(
+    available_printings: &[crate::scryfall::models::Card],
+    entry: &DecklistEntry,
+    preferred_language: Option<&str>,
+    is_favorite: impl Fn(&crate::scryfall::models::Card) -> bool,
+) -> Option<usize> {
+    let pinned = entry.scryfall_id.as_ref().and_then(|id| {
+        available_printings
+            .iter()
+            .position(|card| card.scryfall_id.as_ref() == Some(id))
+    });
+    if pinned.is_some() {
+        return pinned;
+    }
+    if entry.is_exact_printing() {
+        return available_printings
+            .iter()
+            .position(|card| entry.is_printed_as(card));
+    }
+
+    let matches = |card: &crate::scryfall::models::Card| {
+        // First check if the card name matches what we're looking for
+        let name_matches = card.name.to_lowercase() == entry.name.to_lowercase()
+            || card.face_index(&entry.name).is_some();
+
+        // Try to match both set and language if specified
+        let set_matches = if let Some(ref entry_set) = entry.set {
+            card.set.to_lowercase() == entry_set.to_lowercase()
+        } else {
+            true // No set preference, any set is fine
+        };
+
+        let lang_matches = if let Some(ref entry_lang) = entry.lang {
+            card.language.to_lowercase() == entry_lang.to_lowercase()
+        } else {
+            true // No language preference, any language is fine
+        };
+
+        name_matches && set_matches && lang_matches
+    };
+
+    let numbered = entry.collector_number.as_ref().and_then(|number| {
+        available_printings
+            .iter()
+            .position(|card| matches(card) && card.collector_number.as_ref() == Some(number))
+    });
+    let favorite = entry.set.is_none().then(|| {
+        available_printings
+            .iter()
+            .position(|card| matches(card) && is_favorite(card))
+    });
+    let in_language = |language: &str| {
+        available_printings
+            .iter()
+            .position(|card| matches(card) && card.language.eq_ignore_ascii_case(language))
+    };
+    let preferred = match preferred_language {
+        Some(language) if entry.lang.is_none() => {
+            in_language(language).or_else(|| in_language("en"))
+        }
+        _ => None,
+    };
+    numbered
+        .or(favorite.flatten())
+        .or(preferred)
+        .or_else(|| available_printings.iter().position(matches))
+}
+
+/// Basic lands whose copies can be spread across different arts, see
+/// [`spread_basic_land_copies`]
+const BASIC_LAND_NAMES: [&str; 5] = ["plains", "island", "swamp", "mountain", "forest"];
+
+/// Copies per printing (index into `available_printings`, copies) spreading a basic land's
+/// copies across its different arts in the basic land sets (see
+/// [`crate::globals::get_basic_land_sets`])
+///
+/// None for other cards, for basics with a set or a pinned printing, and when the sets have no
+/// printing of the basic; those use the printing [`select_card_from_printings`] picks.
+pub(crate) fn spread_basic_land_copies(
+    available_printings: &[crate::scryfall::models::Card],
+    entry: &DecklistEntry,
+) -> Option<Vec<(usize, u32)>> {
+    let sets = crate::globals::get_basic_land_sets();
+    let preferred_language = crate::globals::get_preferred_language();
+    spread_basic_land_copies_in_sets(
+        available_printings,
+        entry,
+        &sets,
+        preferred_language.as_deref(),
+    )
+}
+
+fn spread_basic_land_copies_in_sets(
+    available_printings: &[crate::scryfall::models::Card],
+    entry: &DecklistEntry,
+    sets: &[String],
+    preferred_language: Option<&str>,
+) -> Option<Vec<(usize, u32)>> {
+    let is_basic = BASIC_LAND_NAMES
+        .iter()
+        .any(|name| entry.name.eq_ignore_ascii_case(name));
+    if !is_basic || sets.is_empty() || entry.set.is_some() || entry.scryfall_id.is_some() {
+        return None;
+    }
+
+    // One printing per art: the first of each collector number, in the entry's language
+    let in_language = |language: &str| {
+        let mut arts: Vec<usize> = Vec::new();
+        for (index, card) in available_printings.iter().enumerate() {
+            let candidate = !card.digital
+                && card.name.eq_ignore_ascii_case(&entry.name)
+                && card.language.eq_ignore_ascii_case(language)
+                && sets.iter().any(|set| card.set.eq_ignore_ascii_case(set));
+            let new_art = arts.iter().all(|&art| {
+                let other = &available_printings[art];
+                other.set != card.set || other.collector_number != card.collector_number
+            });
+            if candidate && new_art {
+                arts.push(index);
+            }
+        }
+        arts
+    };
+    // Entries without a language fall back to English like the printing selection does
+    let mut arts = in_language(entry.lang.as_deref().or(preferred_language).unwrap_or("en"));
+    if arts.is_empty() && entry.lang.is_none() {
+        arts = in_language("en");
+    }
+    if arts.is_empty() {
+        return None;
+    }
+
+    // Shuffled with a seed from the card name rather than the clock, so the estimate, the
+    // preview and the PDF (and its deck hash) all get the same arts
+    let mut seed = entry
+        .name
+        .to_lowercase()
+        .bytes()
+        .fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+        });
+    for last in (1..arts.len()).rev() {
+        // xorshift64
+        seed ^= seed << 13;
+        seed ^= seed >> 7;
+        seed ^= seed << 17;
+        arts.swap(last, (seed % (last as u64 + 1)) as usize);
+    }
+
+    // As even as possible, the earlier arts taking the extra copies
+    let copies = entry.multiple.get();
+    arts.truncate(copies as usize);
+    let per_art = copies / arts.len() as u32;
+    let extra = copies as usize % arts.len();
+    Some(
+        arts.into_iter()
+            .enumerate()
+            .map(|(position, art)| (art, per_art + u32::from(position < extra)))
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DoubleFaceMode;
+    use crate::scryfall::models::Card;
+    use std::num::NonZeroU32;
+
+    #[test]
+    fn test_select_card_from_printings_with_set_preference() {
+        let cards = vec![
+            Card {
+                name: "Lightning Bolt".to_string(),
+                set: "LEA".to_string(),
+                language: "en".to_string(),
+                border_crop: "url1".to_string(),
+                back_side: None,
+                frame: None,
+                digital: false,
+                collector_number: None,
+                prices: None,
+                legalities: Default::default(),
+                layout: None,
+                scryfall_id: None,
+                oracle_id: None,
+                mana_cost: None,
+                type_line: None,
+                oracle_text: None,
+                power_toughness: None,
+            },
+            Card {
+                name: "Lightning Bolt".to_string(),
+                set: "VMA".to_string(),
+                language: "en".to_string(),
+                border_crop: "url2".to_string(),
+                back_side: None,
+                frame: None,
+                digital: false,
+                collector_number: None,
+                prices: None,
+                legalities: Default::default(),
+                layout: None,
+                scryfall_id: None,
+                oracle_id: None,
+                mana_cost: None,
+                type_line: None,
+                oracle_text: None,
+                power_toughness: None,
+            },
+        ];
+
+        let entry = DecklistEntry {
+            multiple: NonZeroU32::MIN,
+            name: "Lightning Bolt".to_string(),
+            set: Some("VMA".to_string()),
+            lang: None,
+            face_mode: DoubleFaceMode::BothSides,
+            source_line_number: None,
+            section: crate::DeckSection::Main,
+            foil: false,
+            tags: Vec::new(),
+            scryfall_id: None,
+            collector_number: None,
+            annotation: None,
+        };
+
+        let result = select_card_from_printings(&cards, &entry);
+        assert_eq!(result, Some(1)); // Should select VMA printing
+    }
+
+    #[test]
+    fn test_select_card_from_printings_prefers_pinned_id() {
+        // Two printings sharing set and language, told apart only by their ids
+        let printing = |id: &str, collector_number: &str| Card {
+            name: "Plains".to_string(),
+            set: "znr".to_string(),
+            language: "en".to_string(),
+            border_crop: format!("https://example.com/{}.jpg", id),
+            back_side: None,
+            frame: None,
+            digital: false,
+            collector_number: Some(collector_number.to_string()),
+            prices: None,
+            legalities: Default::default(),
+            layout: None,
+            scryfall_id: Some(id.to_string()),
+            oracle_id: Some("plains-oracle".to_string()),
+            mana_cost: None,
+            type_line: None,
+            oracle_text: None,
+            power_toughness: None,
+        };
+        let cards = vec![printing("id-266", "266"), printing("id-267", "267")];
+
+        let mut entry = DecklistEntry::new(NonZeroU32::MIN, "Plains", Some("znr"), Some("en"));
+        assert_eq!(select_card_from_printings(&cards, &entry), Some(0));
+
+        entry.scryfall_id = Some("id-267".to_string());
+        assert_eq!(select_card_from_printings(&cards, &entry), Some(1));
+
+        // An id not among the printings falls back to set and language
+        entry.scryfall_id = Some("id-unknown".to_string());
+        assert_eq!(select_card_from_printings(&cards, &entry), Some(0));
+    }
+
+    #[test]
+    fn test_select_card_from_printings_by_face_name() {
+        let printing = |set: &str| Card {
+            name: "kabira takedown // kabira plateau".to_string(),
+            set: set.to_string(),
+            language: "en".to_string(),
+            border_crop: format!("https://example.com/{}.jpg", set),
+            back_side: None,
+            frame: None,
+            digital: false,
+            collector_number: None,
+            prices: None,
+            legalities: Default::default(),
+            layout: None,
+            scryfall_id: None,
+            oracle_id: None,
+            mana_cost: None,
+            type_line: None,
+            oracle_text: None,
+            power_toughness: None,
+        };
+        let cards = vec![printing("znr"), printing("pznr")];
+
+        // A back face name the lookup didn't resolve picks the card and prints its back
+        let entry = DecklistEntry::new(NonZeroU32::MIN, "Kabira Plateau", Some("pznr"), None);
+        assert_eq!(select_card_from_printings(&cards, &entry), Some(1));
+        assert_eq!(entry.face_mode_for(&cards[1]), DoubleFaceMode::BackOnly);
+
+        let entry = DecklistEntry::new(NonZeroU32::MIN, "Kabira Takedown", None, None);
+        assert_eq!(select_card_from_printings(&cards, &entry), Some(0));
+        assert_eq!(entry.face_mode_for(&cards[0]), DoubleFaceMode::BothSides);
+    }
+
+    #[test]
+    fn test_select_card_from_printings_exact_printing() {
+        let printing = |collector_number: &str, language: &str| Card {
+            name: "Plains".to_string(),
+            set: "znr".to_string(),
+            language: language.to_string(),
+            border_crop: format!("https://example.com/{}.jpg", collector_number),
+            back_side: None,
+            frame: None,
+            digital: false,
+            collector_number: Some(collector_number.to_string()),
+            prices: None,
+            legalities: Default::default(),
+            layout: None,
+            scryfall_id: None,
+            oracle_id: None,
+            mana_cost: None,
+            type_line: None,
+            oracle_text: None,
+            power_toughness: None,
+        };
+        let cards = vec![printing("266", "en"), printing("267", "en")];
+
+        // Set and number without a language still fall back to the set
+        let mut entry = DecklistEntry::new(NonZeroU32::MIN, "plains", Some("ZNR"), None);
+        entry.collector_number = Some("267".to_string());
+        assert!(!entry.is_exact_printing());
+        assert_eq!(select_card_from_printings(&cards, &entry), Some(1));
+        entry.collector_number = Some("999".to_string());
+        assert_eq!(select_card_from_printings(&cards, &entry), Some(0));
+
+        // With the language the line names one printing, and no other will do
+        entry.lang = Some("en".to_string());
+        assert!(entry.is_exact_printing());
+        assert_eq!(select_card_from_printings(&cards, &entry), None);
+        entry.collector_number = Some("267".to_string());
+        assert_eq!(select_card_from_printings(&cards, &entry), Some(1));
+        entry.lang = Some("ja".to_string());
+        assert_eq!(select_card_from_printings(&cards, &entry), None);
+    }
+
+    #[test]
+    fn test_select_card_preferring_language() {
+        let printing = |set: &str, language: &str| Card {
+            name: "counterspell".to_string(),
+            set: set.to_string(),
+            language: language.to_string(),
+            border_crop: format!("https://example.com/{}-{}.jpg", set, language),
+            back_side: None,
+            frame: None,
+            digital: false,
+            collector_number: None,
+            prices: None,
+            legalities: Default::default(),
+            layout: None,
+            scryfall_id: None,
+            oracle_id: None,
+            mana_cost: None,
+            type_line: None,
+            oracle_text: None,
+            power_toughness: None,
+        };
+        let cards = vec![
+            printing("mh2", "de"),
+            printing("mh2", "en"),
+            printing("tmp", "ja"),
+        ];
+        let entry = DecklistEntry::new(NonZeroU32::MIN, "counterspell", None, None);
+
+        // Without a preferred language the first printing wins, whatever its language
+        assert_eq!(
+            select_card_preferring_language(&cards, &entry, None, |_| false),
+            Some(0)
+        );
+        assert_eq!(
+            select_card_preferring_language(&cards, &entry, Some("ja"), |_| false),
+            Some(2)
+        );
+        // Without a printing in the preferred language, English comes next
+        assert_eq!(
+            select_card_preferring_language(&cards, &entry, Some("fr"), |_| false),
+            Some(1)
+        );
+
+        // The language and set of the line win over the preference
+        let german = DecklistEntry::new(NonZeroU32::MIN, "counterspell", None, Some("de"));
+        assert_eq!(
+            select_card_preferring_language(&cards, &german, Some("ja"), |_| false),
+            Some(0)
+        );
+        let modern_horizons =
+            DecklistEntry::new(NonZeroU32::MIN, "counterspell", Some("mh2"), None);
+        assert_eq!(
+            select_card_preferring_language(&cards, &modern_horizons, Some("ja"), |_| false),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn test_spread_basic_land_copies() {
+        let printing = |name: &str, set: &str, number: &str, language: &str| Card {
+            name: name.to_string(),
+            set: set.to_string(),
+            language: language.to_string(),
+            border_crop: format!("https://example.com/{}-{}-{}.jpg", set, number, language),
+            back_side: None,
+            frame: None,
+            digital: false,
+            collector_number: Some(number.to_string()),
+            prices: None,
+            legalities: Default::default(),
+            layout: None,
+            scryfall_id: None,
+            oracle_id: None,
+            mana_cost: None,
+            type_line: None,
+            oracle_text: None,
+            power_toughness: None,
+        };
+        let cards = vec![
+            printing("Island", "m21", "264", "en"),
+            printing("Island", "zen", "234", "en"),
+            printing("Island", "zen", "235", "en"),
+            printing("Island", "zen", "235", "en"),
+            printing("Island", "unf", "236", "en"),
+            printing("Island", "zen", "234", "ja"),
+        ];
+        let sets = vec!["zen".to_string(), "UNF".to_string()];
+        let island =
+            |copies| DecklistEntry::new(NonZeroU32::new(copies).unwrap(), "island", None, None);
+
+        // Seven copies over the three arts of the sets, the duplicate art left out
+        let spread = spread_basic_land_copies_in_sets(&cards, &island(7), &sets, None).unwrap();
+        let mut arts: Vec<usize> = spread.iter().map(|(index, _)| *index).collect();
+        arts.sort();
+        assert_eq!(arts, vec![1, 2, 4]);
+        let mut copies: Vec<u32> = spread.iter().map(|(_, copies)| *copies).collect();
+        copies.sort();
+        assert_eq!(copies, vec![2, 2, 3]);
+        assert_eq!(copies.iter().sum::<u32>(), 7);
+        // The same entry always gets the same arts
+        assert_eq!(
+            spread_basic_land_copies_in_sets(&cards, &island(7), &sets, None),
+            Some(spread)
+        );
+
+        // Fewer copies than arts use one art each
+        let spread = spread_basic_land_copies_in_sets(&cards, &island(2), &sets, None).unwrap();
+        assert_eq!(spread.len(), 2);
+        assert!(spread.iter().all(|(_, copies)| *copies == 1));
+
+        // The preferred language, if the sets have it
+        assert_eq!(
+            spread_basic_land_copies_in_sets(&cards, &island(3), &sets, Some("ja")),
+            Some(vec![(5, 3)])
+        );
+        assert_eq!(
+            spread_basic_land_copies_in_sets(&cards, &island(1), &sets, Some("de"))
+                .map(|spread| spread.len()),
+            Some(1)
+        );
+
+        // Not for entries with a set, for other cards or without basic land sets
+        let zendikar = DecklistEntry::new(NonZeroU32::new(4).unwrap(), "island", Some("zen"), None);
+        assert_eq!(
+            spread_basic_land_copies_in_sets(&cards, &zendikar, &sets, None),
+            None
+        );
+        let bolt = DecklistEntry::new(NonZeroU32::new(4).unwrap(), "lightning bolt", None, None);
+        assert_eq!(
+            spread_basic_land_copies_in_sets(&cards, &bolt, &sets, None),
+            None
+        );
+        assert_eq!(
+            spread_basic_land_copies_in_sets(&cards, &island(4), &[], None),
+            None
+        );
+        let m19 = vec!["m19".to_string()];
+        assert_eq!(
+            spread_basic_land_copies_in_sets(&cards, &island(4), &m19, None),
+            None
+        );
+    }
+
+    #[test]
+    fn test_select_card_preferring_favorites() {
+        let printing = |set: &str, language: &str| Card {
+            name: "counterspell".to_string(),
+            set: set.to_string(),
+            language: language.to_string(),
+            border_crop: format!("https://example.com/{}-{}.jpg", set, language),
+            back_side: None,
+            frame: None,
+            digital: false,
+            collector_number: None,
+            prices: None,
+            legalities: Default::default(),
+            layout: None,
+            scryfall_id: None,
+            oracle_id: None,
+            mana_cost: None,
+            type_line: None,
+            oracle_text: None,
+            power_toughness: None,
+        };
+        let cards = vec![
+            printing("mh2", "en"),
+            printing("tmp", "en"),
+            printing("tmp", "ja"),
+        ];
+        let favorite_tempest = |card: &Card| card.set == "tmp";
+
+        // A favorite beats the first printing and the preferred language
+        let entry = DecklistEntry::new(NonZeroU32::MIN, "counterspell", None, None);
+        assert_eq!(
+            select_card_preferring_language(&cards, &entry, None, favorite_tempest),
+            Some(1)
+        );
+        assert_eq!(
+            select_card_preferring_language(&cards, &entry, Some("de"), favorite_tempest),
+            Some(1)
+        );
+
+        // The language still has to match, and a set hint wins over favorites
+        let japanese = DecklistEntry::new(NonZeroU32::MIN, "counterspell", None, Some("ja"));
+        assert_eq!(
+            select_card_preferring_language(&cards, &japanese, None, favorite_tempest),
+            Some(2)
+        );
+        let modern_horizons =
+            DecklistEntry::new(NonZeroU32::MIN, "counterspell", Some("mh2"), None);
+        assert_eq!(
+            select_card_preferring_language(&cards, &modern_horizons, None, favorite_tempest),
+            Some(0)
+        );
+    }
+}
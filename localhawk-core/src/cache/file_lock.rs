@@ -0,0 +1,111 @@
+//! Lock files that let one process at a time save a cache shared by several processes
+//!
+//! The GUI, the CLI and the server all use the same cache directory. Each holds the lock file
+//! of a cache while saving it, so two processes shutting down together don't write the same
+//! files at once. A process that crashes while saving leaves its lock file behind; once that is
+//! older than [`STALE_LOCK_AGE`] the next process breaks it.
+
+use crate::error::ProxyError;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tracing::{debug, warn};
+
+/// Lock files older than this were left behind by a crashed process (saving the image
+/// cache metadata takes seconds, not minutes)
+pub const STALE_LOCK_AGE: Duration = Duration::from_secs(120);
+const LOCK_RETRY_INTERVAL: Duration = Duration::from_millis(50);
+
+/// A held lock file, removed again when dropped
+#[derive(Debug)]
+pub struct FileLock {
+    path: PathBuf,
+}
+
+impl FileLock {
+    /// Create the lock file at `path`, waiting while another process holds it
+    pub fn acquire(path: PathBuf) -> Result<Self, ProxyError> {
+        loop {
+            if let Some(lock) = Self::try_acquire(&path)? {
+                return Ok(lock);
+            }
+            if is_stale(&path) {
+                warn!(lock = %path.display(), "Breaking stale cache lock");
+                // Another process may have broken it first
+                if let Err(e) = fs::remove_file(&path) {
+                    debug!(lock = %path.display(), error = %e, "Stale cache lock already gone");
+                }
+                continue;
+            }
+            std::thread::sleep(LOCK_RETRY_INTERVAL);
+        }
+    }
+
+    /// Create the lock file at `path` unless another process holds it
+    pub fn try_acquire(path: &Path) -> Result<Option<Self>, ProxyError> {
+        match OpenOptions::new().write(true).create_new(true).open(path) {
+            Ok(mut file) => {
+                // For whoever finds a lock file left behind
+                writeln!(file, "{}", std::process::id()).map_err(ProxyError::Io)?;
+                debug!(lock = %path.display(), "Acquired cache lock");
+                Ok(Some(FileLock {
+                    path: path.to_path_buf(),
+                }))
+            }
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => Ok(None),
+            Err(e) => Err(ProxyError::Io(e)),
+        }
+    }
+}
+
+impl Drop for FileLock {
+    fn drop(&mut self) {
+        if let Err(e) = fs::remove_file(&self.path) {
+            warn!(lock = %self.path.display(), error = %e, "Failed to release cache lock");
+        }
+    }
+}
+
+/// Whether the lock file at `path` is older than [`STALE_LOCK_AGE`]
+fn is_stale(path: &Path) -> bool {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|modified| modified.elapsed().ok())
+        .is_some_and(|age| age > STALE_LOCK_AGE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use std::fs::File;
+    use std::time::SystemTime;
+
+    #[test]
+    fn test_file_lock() {
+        let dir = env::temp_dir().join(format!("localhawk-lock-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("cache.lock");
+        let _ = fs::remove_file(&path);
+
+        let lock = FileLock::acquire(path.clone()).unwrap();
+        assert!(path.exists());
+        assert!(FileLock::try_acquire(&path).unwrap().is_none());
+        drop(lock);
+        assert!(!path.exists());
+
+        // A lock left behind by a crashed process is broken once it is stale
+        File::create(&path)
+            .unwrap()
+            .set_modified(SystemTime::now() - STALE_LOCK_AGE * 2)
+            .unwrap();
+        assert!(is_stale(&path));
+        let lock = FileLock::acquire(path.clone()).unwrap();
+        assert!(!is_stale(&path));
+        drop(lock);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -2,18 +2,22 @@
 //!
 //! This storage strategy stores binary data as separate files on disk with
 //! a JSON metadata file containing references and timestamps.
+//! It backs the image cache, so inserts and evictions are published as image
+//! [cache events](crate::cache_events).
 
-use super::lru_cache::{CacheEntry, StorageStrategy};
+use super::lru_cache::{CacheEntry, HttpValidators, StorageStrategy};
+use crate::cache_events::{CacheEvent, publish_cache_event};
 use crate::error::ProxyError;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::PathBuf;
 use time::OffsetDateTime;
 use tracing::{debug, info, warn};
 
 const METADATA_FILENAME: &str = "cache_metadata.json";
+const LOCK_FILENAME: &str = "cache_metadata.lock";
 
 /// Metadata stored on disk for file-based cache entries
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -23,6 +27,12 @@ struct DiskFileEntry {
     pub created_at: OffsetDateTime,
     pub last_accessed: OffsetDateTime,
     pub size_bytes: u64,
+    /// ETag / Last-Modified of the download, for conditional revalidation
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub validators: Option<HttpValidators>,
+    /// Times the file was used, for the least frequently used eviction policy
+    #[serde(default)]
+    pub access_count: u64,
 }
 
 /// Metadata file format for file-based storage
@@ -39,6 +49,8 @@ pub struct FileStorage {
     metadata_file: PathBuf,
     file_extension: String,
     size_estimate: u64,
+    /// Whether inserts and evictions are published as cache events
+    publish_events: bool,
 }
 
 impl FileStorage {
@@ -66,9 +78,16 @@ impl FileStorage {
             metadata_file,
             file_extension,
             size_estimate,
+            publish_events: true,
         })
     }
 
+    /// Don't publish cache events, for files derived from images rather than images
+    pub fn without_cache_events(mut self) -> Self {
+        self.publish_events = false;
+        self
+    }
+
     /// Generate a filename from a key using SHA256 hash
     fn key_to_filename(&self, key: &str) -> String {
         let mut hasher = Sha256::new();
@@ -82,6 +101,89 @@ impl FileStorage {
         let filename = self.key_to_filename(key);
         self.cache_dir.join(filename)
     }
+
+    /// Read the data file of `key` without loading the cache, None if it isn't cached
+    ///
+    /// For processes that only need a few entries, e.g. the iOS share extension, which can't
+    /// afford to hold the whole image cache in memory.
+    pub fn read_file(&self, key: &str) -> Option<Vec<u8>> {
+        fs::read(self.get_file_path(key)).ok()
+    }
+
+    /// Parse the metadata without reading any data file, counting the entries whose data file
+    /// is on disk and those whose file is missing
+    pub fn check_metadata(&self) -> Result<(usize, usize), ProxyError> {
+        if !self.metadata_file.exists() {
+            return Ok((0, 0));
+        }
+
+        let metadata_content = fs::read_to_string(&self.metadata_file).map_err(ProxyError::Io)?;
+        let metadata: DiskFileMetadata =
+            serde_json::from_str(&metadata_content).map_err(ProxyError::Json)?;
+        let present = metadata
+            .entries
+            .values()
+            .filter(|disk_entry| self.cache_dir.join(&disk_entry.filename).exists())
+            .count();
+        Ok((present, metadata.entries.len() - present))
+    }
+
+    /// Write the data file of every entry, then the metadata of the entries and of `kept`
+    /// (entries whose data files are already on disk)
+    fn write_entries(
+        &self,
+        entries: &HashMap<String, CacheEntry<Vec<u8>>>,
+        kept: HashMap<String, DiskFileEntry>,
+    ) -> Result<(), ProxyError> {
+        let mut total_size: u64 = kept.values().map(|entry| entry.size_bytes).sum();
+        let mut disk_entries = kept;
+
+        // Save each entry to its own file and build metadata
+        for (key, cache_entry) in entries {
+            let filename = self.key_to_filename(key);
+            let file_path = self.cache_dir.join(&filename);
+
+            // Write the data file
+            fs::write(&file_path, &cache_entry.value).map_err(ProxyError::Io)?;
+
+            let size_bytes = cache_entry.value.len() as u64;
+            total_size += size_bytes;
+
+            let disk_entry = DiskFileEntry {
+                key: key.clone(),
+                filename,
+                created_at: cache_entry.created_at,
+                last_accessed: cache_entry.last_accessed,
+                size_bytes,
+                validators: cache_entry.validators.clone(),
+                access_count: cache_entry.access_count,
+            };
+
+            disk_entries.insert(key.clone(), disk_entry);
+        }
+
+        // Save metadata file
+        let metadata = DiskFileMetadata {
+            entries: disk_entries,
+            total_size_bytes: total_size,
+            last_updated: OffsetDateTime::now_utc(),
+        };
+
+        // Write to a temporary file first, so a crash mid-save never truncates the metadata
+        let json = serde_json::to_string_pretty(&metadata).map_err(ProxyError::Json)?;
+        let temp_file = self.metadata_file.with_extension("json.tmp");
+        fs::write(&temp_file, json).map_err(ProxyError::Io)?;
+        fs::rename(&temp_file, &self.metadata_file).map_err(ProxyError::Io)?;
+
+        debug!(
+            entries = metadata.entries.len(),
+            total_size_kb = total_size / 1024,
+            cache_dir = %self.cache_dir.display(),
+            "Saved file cache metadata"
+        );
+
+        Ok(())
+    }
 }
 
 impl StorageStrategy<String, Vec<u8>> for FileStorage {
@@ -122,6 +224,8 @@ impl StorageStrategy<String, Vec<u8>> for FileStorage {
                         value: data,
                         created_at: disk_entry.created_at,
                         last_accessed: disk_entry.last_accessed,
+                        validators: disk_entry.validators,
+                        access_count: disk_entry.access_count,
                     };
                     entries.insert(key, cache_entry);
                     loaded_count += 1;
@@ -149,49 +253,39 @@ impl StorageStrategy<String, Vec<u8>> for FileStorage {
     }
 
     fn save(&self, entries: &HashMap<String, CacheEntry<Vec<u8>>>) -> Result<(), ProxyError> {
-        let mut disk_entries = HashMap::new();
-        let mut total_size = 0u64;
-
-        // Save each entry to its own file and build metadata
-        for (key, cache_entry) in entries {
-            let filename = self.key_to_filename(key);
-            let file_path = self.cache_dir.join(&filename);
-
-            // Write the data file
-            fs::write(&file_path, &cache_entry.value).map_err(ProxyError::Io)?;
-
-            let size_bytes = cache_entry.value.len() as u64;
-            total_size += size_bytes;
-
-            let disk_entry = DiskFileEntry {
-                key: key.clone(),
-                filename,
-                created_at: cache_entry.created_at,
-                last_accessed: cache_entry.last_accessed,
-                size_bytes,
-            };
+        self.write_entries(entries, HashMap::new())
+    }
 
-            disk_entries.insert(key.clone(), disk_entry);
+    fn save_merged(
+        &self,
+        entries: &HashMap<String, CacheEntry<Vec<u8>>>,
+        removed: &HashSet<String>,
+    ) -> Result<(), ProxyError> {
+        // Images another process cached since ours were loaded are only in the metadata on disk
+        let on_disk = fs::read_to_string(&self.metadata_file)
+            .ok()
+            .and_then(|content| serde_json::from_str::<DiskFileMetadata>(&content).ok())
+            .map(|metadata| metadata.entries)
+            .unwrap_or_default();
+        let kept: HashMap<String, DiskFileEntry> = on_disk
+            .into_iter()
+            .filter(|(key, disk_entry)| {
+                !entries.contains_key(key)
+                    && !removed.contains(key)
+                    && self.cache_dir.join(&disk_entry.filename).exists()
+            })
+            .collect();
+        if !kept.is_empty() {
+            debug!(
+                kept = kept.len(),
+                "Keeping file cache entries saved by another process"
+            );
         }
+        self.write_entries(entries, kept)
+    }
 
-        // Save metadata file
-        let metadata = DiskFileMetadata {
-            entries: disk_entries,
-            total_size_bytes: total_size,
-            last_updated: OffsetDateTime::now_utc(),
-        };
-
-        let json = serde_json::to_string_pretty(&metadata).map_err(ProxyError::Json)?;
-        fs::write(&self.metadata_file, json).map_err(ProxyError::Io)?;
-
-        debug!(
-            entries = entries.len(),
-            total_size_kb = total_size / 1024,
-            cache_dir = %self.cache_dir.display(),
-            "Saved file cache metadata"
-        );
-
-        Ok(())
+    fn lock_path(&self) -> Option<PathBuf> {
+        Some(self.cache_dir.join(LOCK_FILENAME))
     }
 
     fn estimate_size(&self, _key: &String, _value: &Vec<u8>) -> u64 {
@@ -203,7 +297,16 @@ impl StorageStrategy<String, Vec<u8>> for FileStorage {
         self.size_estimate
     }
 
+    fn entry_inserted(&self, key: &String, _value: &Vec<u8>) {
+        if self.publish_events {
+            publish_cache_event(CacheEvent::ImageCached(key.clone()));
+        }
+    }
+
     fn evict_entry(&self, key: &String, _value: &Vec<u8>) -> Result<(), ProxyError> {
+        if self.publish_events {
+            publish_cache_event(CacheEvent::ImageEvicted(key.clone()));
+        }
         let file_path = self.get_file_path(key);
 
         if file_path.exists() {
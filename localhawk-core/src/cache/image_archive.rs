@@ -0,0 +1,297 @@
+//! Export and import of the image cache as a tar archive
+//!
+//! The archive holds a JSON manifest mapping image URLs to the SHA256 checksum of their
+//! bytes, followed by one file per distinct image named after that checksum. Images
+//! shared by several URLs are stored once, and imports verify every image against its
+//! checksum, so a warmed cache can be moved between machines without trusting the copy.
+
+use super::LruImageCache;
+use crate::error::ProxyError;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::io::{Read, Write};
+use time::OffsetDateTime;
+use tracing::{info, warn};
+
+const MANIFEST_PATH: &str = "manifest.json";
+const IMAGES_DIR: &str = "images";
+const ARCHIVE_VERSION: u32 = 1;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ArchiveManifest {
+    version: u32,
+    created_at: OffsetDateTime,
+    entries: Vec<ArchiveEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ArchiveEntry {
+    url: String,
+    sha256: String,
+}
+
+/// Outcome of importing an image cache archive
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ImageArchiveImport {
+    /// Images added to the cache
+    pub imported: usize,
+    /// Images skipped because the cache already had them
+    pub already_cached: usize,
+    /// Images dropped because their bytes didn't match the checksum in the manifest
+    pub corrupt: usize,
+}
+
+fn checksum(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+/// Write every cached image to `writer` as a tar archive; returns the number of URLs exported
+pub fn export_image_cache<W: Write>(cache: &LruImageCache, writer: W) -> Result<usize, ProxyError> {
+    let mut images: BTreeMap<String, &Vec<u8>> = BTreeMap::new();
+    let mut entries = Vec::new();
+    for (url, entry) in cache.iter() {
+        let sha256 = checksum(&entry.value);
+        images.insert(sha256.clone(), &entry.value);
+        entries.push(ArchiveEntry {
+            url: url.clone(),
+            sha256,
+        });
+    }
+    entries.sort_by(|a, b| a.url.cmp(&b.url));
+
+    let now = OffsetDateTime::now_utc();
+    let manifest = ArchiveManifest {
+        version: ARCHIVE_VERSION,
+        created_at: now,
+        entries,
+    };
+    let manifest_json = serde_json::to_vec_pretty(&manifest).map_err(ProxyError::Json)?;
+
+    // The manifest goes first so imports can match images to URLs while streaming
+    let mut builder = tar::Builder::new(writer);
+    append_file(&mut builder, MANIFEST_PATH, &manifest_json, now)?;
+    for (sha256, bytes) in &images {
+        append_file(
+            &mut builder,
+            &format!("{}/{}.jpg", IMAGES_DIR, sha256),
+            bytes,
+            now,
+        )?;
+    }
+    builder.into_inner().map_err(ProxyError::Io)?;
+
+    info!(
+        urls = manifest.entries.len(),
+        images = images.len(),
+        "Exported image cache archive"
+    );
+    Ok(manifest.entries.len())
+}
+
+fn append_file<W: Write>(
+    builder: &mut tar::Builder<W>,
+    path: &str,
+    bytes: &[u8],
+    modified: OffsetDateTime,
+) -> Result<(), ProxyError> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(bytes.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(modified.unix_timestamp().max(0) as u64);
+    header.set_cksum();
+    builder
+        .append_data(&mut header, path, bytes)
+        .map_err(ProxyError::Io)
+}
+
+/// Add the images of an archive written by `export_image_cache` to the cache
+///
+/// Images already in the cache are kept as they are. The caller is responsible for
+/// saving the cache afterwards.
+pub fn import_image_cache<R: Read>(
+    cache: &mut LruImageCache,
+    reader: R,
+) -> Result<ImageArchiveImport, ProxyError> {
+    let mut archive = tar::Archive::new(reader);
+    let mut files = archive.entries().map_err(ProxyError::Io)?;
+
+    let manifest: ArchiveManifest = match files.next() {
+        Some(file) => {
+            let mut file = file.map_err(ProxyError::Io)?;
+            let is_manifest = file
+                .path()
+                .is_ok_and(|path| path.to_str() == Some(MANIFEST_PATH));
+            if !is_manifest {
+                return Err(ProxyError::Cache(
+                    "Not an image cache archive: manifest missing".to_string(),
+                ));
+            }
+            serde_json::from_reader(&mut file).map_err(ProxyError::Json)?
+        }
+        None => {
+            return Err(ProxyError::Cache(
+                "Image cache archive is empty".to_string(),
+            ));
+        }
+    };
+    if manifest.version != ARCHIVE_VERSION {
+        return Err(ProxyError::Cache(format!(
+            "Unsupported image cache archive version {}",
+            manifest.version
+        )));
+    }
+
+    let mut urls_by_checksum: HashMap<String, Vec<String>> = HashMap::new();
+    for entry in manifest.entries {
+        urls_by_checksum
+            .entry(entry.sha256)
+            .or_default()
+            .push(entry.url);
+    }
+
+    let mut summary = ImageArchiveImport::default();
+    for file in files {
+        let mut file = file.map_err(ProxyError::Io)?;
+        let sha256 = file
+            .path()
+            .ok()
+            .and_then(|path| Some(path.file_stem()?.to_str()?.to_string()));
+        let Some((sha256, urls)) = sha256.and_then(|sha256| urls_by_checksum.remove_entry(&sha256))
+        else {
+            continue;
+        };
+
+        let mut bytes = Vec::new();
+        file.read_to_end(&mut bytes).map_err(ProxyError::Io)?;
+        if checksum(&bytes) != sha256 {
+            warn!(sha256 = %sha256, urls = urls.len(), "Skipping image with mismatching checksum");
+            summary.corrupt += urls.len();
+            continue;
+        }
+
+        for url in urls {
+            if cache.contains(&url) {
+                summary.already_cached += 1;
+            } else {
+                cache.insert(url, bytes.clone())?;
+                summary.imported += 1;
+            }
+        }
+    }
+
+    info!(
+        imported = summary.imported,
+        already_cached = summary.already_cached,
+        corrupt = summary.corrupt,
+        "Imported image cache archive"
+    );
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::create_image_cache_with_config;
+    use std::env;
+
+    fn test_cache(name: &str) -> LruImageCache {
+        let dir = env::temp_dir().join(format!(
+            "localhawk-archive-test-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let mut cache = create_image_cache_with_config(Some(dir), 100 * 1024 * 1024).unwrap();
+        cache.clear().unwrap();
+        cache
+    }
+
+    #[test]
+    fn test_export_then_import_round_trip() {
+        let mut source = test_cache("source");
+        source
+            .insert("https://example.com/bolt.jpg".to_string(), vec![1, 2, 3])
+            .unwrap();
+        // Two URLs with identical bytes are stored once in the archive
+        source
+            .insert("https://example.com/bolt-2.jpg".to_string(), vec![1, 2, 3])
+            .unwrap();
+        source
+            .insert("https://example.com/shock.jpg".to_string(), vec![4, 5])
+            .unwrap();
+
+        let mut archive = Vec::new();
+        assert_eq!(export_image_cache(&source, &mut archive).unwrap(), 3);
+
+        let mut target = test_cache("target");
+        target
+            .insert("https://example.com/shock.jpg".to_string(), vec![4, 5])
+            .unwrap();
+        let summary = import_image_cache(&mut target, archive.as_slice()).unwrap();
+        assert_eq!(
+            summary,
+            ImageArchiveImport {
+                imported: 2,
+                already_cached: 1,
+                corrupt: 0,
+            }
+        );
+        assert_eq!(
+            target.get(&"https://example.com/bolt-2.jpg".to_string()),
+            Some(vec![1, 2, 3])
+        );
+
+        source.clear().unwrap();
+        target.clear().unwrap();
+    }
+
+    #[test]
+    fn test_import_skips_corrupt_images() {
+        let now = OffsetDateTime::now_utc();
+        let manifest = ArchiveManifest {
+            version: ARCHIVE_VERSION,
+            created_at: now,
+            entries: vec![ArchiveEntry {
+                url: "https://example.com/bolt.jpg".to_string(),
+                sha256: checksum(&[1, 2, 3]),
+            }],
+        };
+        let mut builder = tar::Builder::new(Vec::new());
+        append_file(
+            &mut builder,
+            MANIFEST_PATH,
+            &serde_json::to_vec(&manifest).unwrap(),
+            now,
+        )
+        .unwrap();
+        append_file(
+            &mut builder,
+            &format!("{}/{}.jpg", IMAGES_DIR, checksum(&[1, 2, 3])),
+            &[9, 9, 9],
+            now,
+        )
+        .unwrap();
+        let archive = builder.into_inner().unwrap();
+
+        let mut cache = test_cache("corrupt");
+        let summary = import_image_cache(&mut cache, archive.as_slice()).unwrap();
+        assert_eq!(summary.corrupt, 1);
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_import_rejects_other_archives() {
+        let mut builder = tar::Builder::new(Vec::new());
+        append_file(
+            &mut builder,
+            "notes.txt",
+            b"hello",
+            OffsetDateTime::now_utc(),
+        )
+        .unwrap();
+        let archive = builder.into_inner().unwrap();
+
+        let mut cache = test_cache("other");
+        assert!(import_image_cache(&mut cache, archive.as_slice()).is_err());
+    }
+}
@@ -4,19 +4,43 @@
 //! The cache supports both entry count limits and total size limits, with automatic eviction of
 //! least recently used items when limits are exceeded.
 
+use super::file_lock::FileLock;
 use crate::error::ProxyError;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::hash::Hash;
-use time::OffsetDateTime;
+use std::path::PathBuf;
+use time::{Duration, OffsetDateTime};
 use tracing::{debug, info, warn};
 
+/// HTTP validators of a downloaded response, sent along when the cached copy is revalidated
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HttpValidators {
+    /// `ETag` response header, sent back as `If-None-Match`
+    pub etag: Option<String>,
+    /// `Last-Modified` response header, sent back as `If-Modified-Since`
+    pub last_modified: Option<String>,
+}
+
+impl HttpValidators {
+    /// Whether the server sent neither validator, so the response can't be revalidated
+    pub fn is_empty(&self) -> bool {
+        self.etag.is_none() && self.last_modified.is_none()
+    }
+}
+
 /// A cache entry with access tracking for LRU eviction
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CacheEntry<V> {
     pub value: V,
     pub created_at: OffsetDateTime,
     pub last_accessed: OffsetDateTime,
+    /// Validators of the HTTP response the value came from, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub validators: Option<HttpValidators>,
+    /// Times the value was used, for [`EvictionPolicy::LeastFrequentlyUsed`]
+    #[serde(default)]
+    pub access_count: u64,
 }
 
 impl<V> CacheEntry<V> {
@@ -26,11 +50,47 @@ impl<V> CacheEntry<V> {
             value,
             created_at: now,
             last_accessed: now,
+            validators: None,
+            access_count: 0,
         }
     }
 
     pub fn touch(&mut self) {
         self.last_accessed = OffsetDateTime::now_utc();
+        self.access_count = self.access_count.saturating_add(1);
+    }
+}
+
+/// Which entries are evicted first when a cache is over its limits
+///
+/// Pinned entries (see [`LruCache::pin`]) are never evicted, whatever the policy.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EvictionPolicy {
+    /// The entries used longest ago
+    #[default]
+    LeastRecentlyUsed,
+    /// The entries used least often, and of those the ones used longest ago, so images
+    /// printed again and again outlast printings that were only browsed
+    LeastFrequentlyUsed,
+}
+
+impl EvictionPolicy {
+    pub fn all() -> Vec<EvictionPolicy> {
+        vec![
+            EvictionPolicy::LeastRecentlyUsed,
+            EvictionPolicy::LeastFrequentlyUsed,
+        ]
+    }
+}
+
+impl std::fmt::Display for EvictionPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let label = match self {
+            EvictionPolicy::LeastRecentlyUsed => "Least recently used",
+            EvictionPolicy::LeastFrequentlyUsed => "Least frequently used",
+        };
+        write!(f, "{}", label)
     }
 }
 
@@ -46,12 +106,36 @@ where
     /// Save all cache entries to persistent storage
     fn save(&self, entries: &HashMap<K, CacheEntry<V>>) -> Result<(), ProxyError>;
 
+    /// Save all cache entries, keeping those another process saved since they were loaded
+    /// (except the `removed` ones), so processes sharing the storage don't drop each other's
+    /// entries
+    ///
+    /// Storages only one process uses save `entries` as they are, which is the default.
+    fn save_merged(
+        &self,
+        entries: &HashMap<K, CacheEntry<V>>,
+        _removed: &HashSet<K>,
+    ) -> Result<(), ProxyError> {
+        self.save(entries)
+    }
+
+    /// Lock file held while saving, so processes sharing the storage save one at a time
+    /// (None = no locking)
+    fn lock_path(&self) -> Option<PathBuf> {
+        None
+    }
+
     /// Estimate the size in bytes of a cache entry (key + value + metadata)
+    ///
+    /// Called once per inserted or loaded entry; the cache remembers the result.
     fn estimate_size(&self, key: &K, value: &V) -> u64;
 
     /// Get the fixed size estimate per entry for O(1) size calculations
     fn get_size_estimate(&self) -> u64;
 
+    /// Called when an entry is inserted into the cache, before it is saved
+    fn entry_inserted(&self, _key: &K, _value: &V) {}
+
     /// Called when an entry is evicted from the cache (for cleanup)
     fn evict_entry(&self, key: &K, value: &V) -> Result<(), ProxyError>;
 
@@ -68,6 +152,14 @@ pub struct CacheConfig {
     pub max_size_bytes: Option<u64>,
     /// Whether to save to disk on every insert (vs only on shutdown)
     pub eager_persistence: bool,
+    /// Entries created longer ago than this are dropped on access and compaction (None = never)
+    pub max_age: Option<Duration>,
+    /// Compact and save to storage after this many inserts (None = only on explicit saves)
+    pub save_interval: Option<usize>,
+    /// Entries with HTTP validators older than this are revalidated before use (None = never)
+    pub revalidate_after: Option<Duration>,
+    /// Which entries make room when the limits are exceeded
+    pub eviction_policy: EvictionPolicy,
 }
 
 impl Default for CacheConfig {
@@ -76,6 +168,10 @@ impl Default for CacheConfig {
             max_entries: Some(1000), // Reasonable default
             max_size_bytes: None,
             eager_persistence: false,
+            max_age: None,
+            save_interval: None,
+            revalidate_after: None,
+            eviction_policy: EvictionPolicy::LeastRecentlyUsed,
         }
     }
 }
@@ -88,8 +184,19 @@ where
     S: StorageStrategy<K, V>,
 {
     entries: HashMap<K, CacheEntry<V>>,
+    /// Estimated size of every entry, so the total stays exact across inserts and evictions
+    entry_sizes: HashMap<K, u64>,
+    total_size_bytes: u64,
+    /// Inserts since the last save, for `CacheConfig::save_interval`
+    unsaved_inserts: usize,
     storage: S,
     config: CacheConfig,
+    /// Pin counts of keys that must not be evicted to make room, see [`LruCache::pin`]
+    pinned: HashMap<K, usize>,
+    /// Keys removed since the cache was loaded, which saving must not bring back from storage
+    removed: HashSet<K>,
+    /// Never write to or delete from storage, see [`LruCache::set_read_only`]
+    read_only: bool,
 }
 
 impl<K, V, S> LruCache<K, V, S>
@@ -102,8 +209,14 @@ where
     pub fn new(storage: S, config: CacheConfig) -> Result<Self, ProxyError> {
         let mut cache = Self {
             entries: HashMap::new(),
+            entry_sizes: HashMap::new(),
+            total_size_bytes: 0,
+            unsaved_inserts: 0,
             storage,
             config,
+            pinned: HashMap::new(),
+            removed: HashSet::new(),
+            read_only: false,
         };
 
         // Load existing data from storage
@@ -114,6 +227,21 @@ where
 
     /// Get a value from the cache, updating its access time
     pub fn get(&mut self, key: &K) -> Option<V> {
+        if self
+            .entries
+            .get(key)
+            .is_some_and(|entry| self.is_expired(entry))
+        {
+            debug!(strategy = %self.storage.strategy_name(), "Cache entry EXPIRED");
+            let evicted = self
+                .remove_entry(key)
+                .map(|entry| self.evict_from_storage(key, &entry.value));
+            if let Some(Err(e)) = evicted {
+                warn!(strategy = %self.storage.strategy_name(), error = %e, "Failed to evict expired entry");
+            }
+            return None;
+        }
+
         if let Some(entry) = self.entries.get_mut(key) {
             entry.touch();
             debug!(strategy = %self.storage.strategy_name(), "Cache HIT");
@@ -124,14 +252,50 @@ where
         }
     }
 
+    /// Get a value from the cache even if it expired, updating its access time
+    ///
+    /// Also returns whether the value expired. Unlike [`LruCache::get`] this keeps expired
+    /// entries, so callers can refresh them and still fall back to them if that fails.
+    pub fn get_allowing_expired(&mut self, key: &K) -> Option<(V, bool)> {
+        let expired = self
+            .entries
+            .get(key)
+            .is_some_and(|entry| self.is_expired(entry));
+        let entry = self.entries.get_mut(key)?;
+        entry.touch();
+        Some((entry.value.clone(), expired))
+    }
+
     /// Insert a value into the cache, potentially evicting old entries
     pub fn insert(&mut self, key: K, value: V) -> Result<(), ProxyError> {
+        self.insert_entry(key, CacheEntry::new(value))
+    }
+
+    /// Insert a downloaded value together with the validators of its HTTP response
+    pub fn insert_with_validators(
+        &mut self,
+        key: K,
+        value: V,
+        validators: HttpValidators,
+    ) -> Result<(), ProxyError> {
+        let mut entry = CacheEntry::new(value);
+        entry.validators = Some(validators);
+        self.insert_entry(key, entry)
+    }
+
+    fn insert_entry(&mut self, key: K, entry: CacheEntry<V>) -> Result<(), ProxyError> {
+        let size = self.storage.estimate_size(&key, &entry.value);
+
         // Check if we need to make space first
-        self.ensure_space_for_new_entry(&key, &value)?;
+        self.ensure_space_for_new_entry(&key, size)?;
 
-        // Insert the new entry
-        let entry = CacheEntry::new(value.clone());
+        // Insert the new entry, replacing any previous value
+        self.remove_entry(&key);
+        self.removed.remove(&key);
+        self.storage.entry_inserted(&key, &entry.value);
         self.entries.insert(key.clone(), entry);
+        self.entry_sizes.insert(key, size);
+        self.total_size_bytes += size;
 
         debug!(
             strategy = %self.storage.strategy_name(),
@@ -142,14 +306,55 @@ where
         // Save to storage if eager persistence is enabled
         if self.config.eager_persistence {
             self.save_to_storage()?;
+        } else if let Some(save_interval) = self.config.save_interval {
+            self.unsaved_inserts += 1;
+            if self.unsaved_inserts >= save_interval {
+                self.compact_and_save()?;
+            }
         }
 
         Ok(())
     }
 
-    /// Check if the cache contains a key
+    /// Check if the cache contains a key that hasn't expired
     pub fn contains(&self, key: &K) -> bool {
-        self.entries.contains_key(key)
+        self.entries
+            .get(key)
+            .is_some_and(|entry| !self.is_expired(entry))
+    }
+
+    /// Validators to revalidate an entry with, if it is older than `CacheConfig::revalidate_after`
+    ///
+    /// Entries stored without validators are never revalidated, since that would mean
+    /// downloading them again in full.
+    pub fn validators_if_stale(&self, key: &K) -> Option<HttpValidators> {
+        let revalidate_after = self.config.revalidate_after?;
+        let entry = self.entries.get(key)?;
+        if OffsetDateTime::now_utc() - entry.created_at <= revalidate_after {
+            return None;
+        }
+        entry
+            .validators
+            .clone()
+            .filter(|validators| !validators.is_empty())
+    }
+
+    /// Mark an entry as fresh again after the server confirmed it is unchanged
+    pub fn mark_revalidated(&mut self, key: &K) -> bool {
+        match self.entries.get_mut(key) {
+            Some(entry) => {
+                entry.created_at = OffsetDateTime::now_utc();
+                entry.touch();
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn is_expired(&self, entry: &CacheEntry<V>) -> bool {
+        self.config
+            .max_age
+            .is_some_and(|max_age| OffsetDateTime::now_utc() - entry.created_at > max_age)
     }
 
     /// Get the number of entries in the cache
@@ -162,15 +367,74 @@ where
         self.entries.is_empty()
     }
 
+    /// Iterate over all entries without updating their access times
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &CacheEntry<V>)> {
+        self.entries.iter()
+    }
+
     /// Get the total estimated size of the cache in bytes
     pub fn size_bytes(&self) -> u64 {
-        (self.entries.len() as u64) * self.storage.get_size_estimate()
+        self.total_size_bytes
+    }
+
+    /// Remove an entry and its size from the cache without notifying the storage
+    fn remove_entry(&mut self, key: &K) -> Option<CacheEntry<V>> {
+        let entry = self.entries.remove(key)?;
+        self.removed.insert(key.clone());
+        let size = self.entry_sizes.remove(key).unwrap_or_default();
+        self.total_size_bytes = self.total_size_bytes.saturating_sub(size);
+        Some(entry)
     }
 
-    /// Force evict a specific entry
+    /// Recompute the size of every entry, e.g. after loading them from storage
+    fn recompute_sizes(&mut self) {
+        self.entry_sizes = self
+            .entries
+            .iter()
+            .map(|(key, entry)| (key.clone(), self.storage.estimate_size(key, &entry.value)))
+            .collect();
+        self.total_size_bytes = self.entry_sizes.values().sum();
+    }
+
+    /// Keep `keys` from being evicted or expired until they are unpinned, e.g. the images of
+    /// a PDF that is being generated
+    ///
+    /// Pins nest: a key stays pinned until every pin is released. Keys don't have to be cached
+    /// yet. While pinned entries can't be evicted the cache may exceed its limits; the next
+    /// compaction after unpinning evicts the excess.
+    pub fn pin<'a>(&mut self, keys: impl IntoIterator<Item = &'a K>)
+    where
+        K: 'a,
+    {
+        for key in keys {
+            *self.pinned.entry(key.clone()).or_default() += 1;
+        }
+    }
+
+    /// Release one pin of each of `keys`
+    pub fn unpin<'a>(&mut self, keys: impl IntoIterator<Item = &'a K>)
+    where
+        K: 'a,
+    {
+        for key in keys {
+            if let Some(count) = self.pinned.get_mut(key) {
+                *count -= 1;
+                if *count == 0 {
+                    self.pinned.remove(key);
+                }
+            }
+        }
+    }
+
+    /// Whether `key` is protected from eviction
+    pub fn is_pinned(&self, key: &K) -> bool {
+        self.pinned.contains_key(key)
+    }
+
+    /// Force evict a specific entry, even if it's pinned
     pub fn evict(&mut self, key: &K) -> Result<bool, ProxyError> {
-        if let Some(entry) = self.entries.remove(key) {
-            self.storage.evict_entry(key, &entry.value)?;
+        if let Some(entry) = self.remove_entry(key) {
+            self.evict_from_storage(key, &entry.value)?;
             debug!(strategy = %self.storage.strategy_name(), "Force evicted cache entry");
 
             if self.config.eager_persistence {
@@ -183,23 +447,66 @@ where
         }
     }
 
+    /// Evict the entries created before `cutoff`, skipping pinned ones
+    ///
+    /// Returns the number of entries removed.
+    pub fn evict_created_before(&mut self, cutoff: OffsetDateTime) -> Result<usize, ProxyError> {
+        let outdated: Vec<K> = self
+            .entries
+            .iter()
+            .filter(|(key, entry)| entry.created_at < cutoff && !self.is_pinned(key))
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in &outdated {
+            if let Some(entry) = self.remove_entry(key) {
+                self.evict_from_storage(key, &entry.value)?;
+            }
+        }
+
+        if !outdated.is_empty() {
+            info!(
+                strategy = %self.storage.strategy_name(),
+                removed = outdated.len(),
+                cutoff = %cutoff,
+                "Evicted outdated cache entries"
+            );
+            if self.config.eager_persistence {
+                self.save_to_storage()?;
+            }
+        }
+        Ok(outdated.len())
+    }
+
     /// Clear all entries from the cache
     pub fn clear(&mut self) -> Result<(), ProxyError> {
         // Notify storage of all evictions
         for (key, entry) in &self.entries {
-            self.storage.evict_entry(key, &entry.value)?;
+            self.evict_from_storage(key, &entry.value)?;
         }
 
-        self.entries.clear();
-        self.save_to_storage()?;
+        self.removed
+            .extend(self.entries.drain().map(|(key, _)| key));
+        self.entry_sizes.clear();
+        self.total_size_bytes = 0;
+        // Clearing drops the entries other processes saved as well
+        if !self.read_only {
+            let _lock = self.lock_storage()?;
+            self.storage.save(&self.entries)?;
+        }
 
         info!(strategy = %self.storage.strategy_name(), "Cleared all cache entries");
         Ok(())
     }
 
-    /// Save the current cache state to storage
+    /// Save the current cache state to storage, merged with what other processes saved there
+    /// meanwhile (see [`StorageStrategy::save_merged`])
     pub fn save_to_storage(&self) -> Result<(), ProxyError> {
-        self.storage.save(&self.entries)?;
+        if self.read_only {
+            debug!(strategy = %self.storage.strategy_name(), "Read-only cache, not saving");
+            return Ok(());
+        }
+        let _lock = self.lock_storage()?;
+        self.storage.save_merged(&self.entries, &self.removed)?;
         debug!(
             strategy = %self.storage.strategy_name(),
             entries = self.entries.len(),
@@ -208,11 +515,96 @@ where
         Ok(())
     }
 
+    /// Drop expired entries, then evict least recently used ones until the limits hold again
+    ///
+    /// Returns the number of entries removed.
+    pub fn compact(&mut self) -> Result<usize, ProxyError> {
+        let entries_before = self.entries.len();
+
+        let expired: Vec<K> = self
+            .entries
+            .iter()
+            .filter(|(key, entry)| self.is_expired(entry) && !self.is_pinned(key))
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in expired {
+            if let Some(entry) = self.remove_entry(&key) {
+                self.evict_from_storage(&key, &entry.value)?;
+            }
+        }
+
+        // Limits can be exceeded after they were lowered or the entries were loaded from disk
+        let excess_entries = self.config.max_entries.map_or(0, |max_entries| {
+            self.entries.len().saturating_sub(max_entries)
+        });
+        let excess_bytes = self
+            .config
+            .max_size_bytes
+            .map_or(0, |max_size| self.total_size_bytes.saturating_sub(max_size));
+        if excess_entries > 0 || excess_bytes > 0 {
+            self.evict_lru_entries(excess_entries, excess_bytes)?;
+        }
+
+        let removed = entries_before - self.entries.len();
+        if removed > 0 {
+            info!(
+                strategy = %self.storage.strategy_name(),
+                removed = removed,
+                entries = self.entries.len(),
+                "Compacted cache"
+            );
+        }
+        Ok(removed)
+    }
+
+    /// Compact the cache, then save what is left to storage
+    pub fn compact_and_save(&mut self) -> Result<(), ProxyError> {
+        self.compact()?;
+        self.save_to_storage()?;
+        self.unsaved_inserts = 0;
+        Ok(())
+    }
+
+    /// Only read the storage: saves are skipped and evicted entries stay in storage
+    ///
+    /// For short-lived processes that use a cache another process (e.g. the GUI) keeps up to
+    /// date; they neither wait for its lock nor rewrite its files.
+    pub fn set_read_only(&mut self, read_only: bool) {
+        self.read_only = read_only;
+    }
+
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    /// Change which entries are evicted from now on
+    pub fn set_eviction_policy(&mut self, eviction_policy: EvictionPolicy) {
+        self.config.eviction_policy = eviction_policy;
+    }
+
+    pub fn eviction_policy(&self) -> EvictionPolicy {
+        self.config.eviction_policy
+    }
+
+    /// Tell the storage about an evicted entry, unless the cache is read-only
+    fn evict_from_storage(&self, key: &K, value: &V) -> Result<(), ProxyError> {
+        if self.read_only {
+            return Ok(());
+        }
+        self.storage.evict_entry(key, value)
+    }
+
+    /// Hold the lock file of the storage, if it has one
+    fn lock_storage(&self) -> Result<Option<FileLock>, ProxyError> {
+        self.storage.lock_path().map(FileLock::acquire).transpose()
+    }
+
     /// Load cache state from storage
     fn load_from_storage(&mut self) -> Result<(), ProxyError> {
         match self.storage.load() {
             Ok(entries) => {
                 self.entries = entries;
+                self.recompute_sizes();
                 info!(
                     strategy = %self.storage.strategy_name(),
                     entries = self.entries.len(),
@@ -227,15 +619,18 @@ where
                     "Failed to load cache from storage, starting empty"
                 );
                 self.entries.clear();
+                self.recompute_sizes();
                 Ok(())
             }
         }
     }
 
     /// Ensure there's space for a new entry, evicting old ones if necessary
-    fn ensure_space_for_new_entry(&mut self, new_key: &K, new_value: &V) -> Result<(), ProxyError> {
-        let new_entry_size = self.storage.estimate_size(new_key, new_value);
-
+    fn ensure_space_for_new_entry(
+        &mut self,
+        new_key: &K,
+        new_entry_size: u64,
+    ) -> Result<(), ProxyError> {
         // Check entry count limit
         if let Some(max_entries) = self.config.max_entries {
             if self.entries.len() >= max_entries && !self.entries.contains_key(new_key) {
@@ -245,7 +640,9 @@ where
 
         // Check size limit
         if let Some(max_size) = self.config.max_size_bytes {
-            let current_size = self.size_bytes();
+            // An entry that is replaced doesn't count against the budget
+            let replaced_size = self.entry_sizes.get(new_key).copied().unwrap_or_default();
+            let current_size = self.size_bytes() - replaced_size;
             if current_size + new_entry_size > max_size {
                 let size_to_free = (current_size + new_entry_size) - max_size;
                 self.evict_lru_entries(0, size_to_free)?;
@@ -255,16 +652,21 @@ where
         Ok(())
     }
 
-    /// Evict least recently used entries to free up space
+    /// Evict entries to free up space in the order of the eviction policy, skipping pinned ones
     fn evict_lru_entries(&mut self, min_count: usize, min_size: u64) -> Result<(), ProxyError> {
-        // Sort entries by last access time (oldest first)
+        // Sort entries by use (least used first), then by last access time (oldest first)
+        let by_frequency = self.config.eviction_policy == EvictionPolicy::LeastFrequentlyUsed;
         let mut entries_by_access: Vec<_> = self
             .entries
             .iter()
-            .map(|(key, entry)| (key.clone(), entry.last_accessed))
+            .filter(|(key, _)| !self.is_pinned(key))
+            .map(|(key, entry)| {
+                let uses = if by_frequency { entry.access_count } else { 0 };
+                (key.clone(), (uses, entry.last_accessed))
+            })
             .collect();
 
-        entries_by_access.sort_by_key(|(_, last_accessed)| *last_accessed);
+        entries_by_access.sort_by_key(|(_, order)| *order);
 
         let mut evicted_count = 0;
         let mut size_freed = 0u64;
@@ -275,8 +677,8 @@ where
                 break;
             }
 
-            if let Some(entry) = self.entries.get(&key) {
-                size_freed += self.storage.estimate_size(&key, &entry.value);
+            if let Some(size) = self.entry_sizes.get(&key) {
+                size_freed += size;
                 keys_to_remove.push(key);
                 evicted_count += 1;
             }
@@ -284,17 +686,18 @@ where
 
         // Actually remove the entries
         for key in keys_to_remove {
-            if let Some(entry) = self.entries.remove(&key) {
-                self.storage.evict_entry(&key, &entry.value)?;
+            if let Some(entry) = self.remove_entry(&key) {
+                self.evict_from_storage(&key, &entry.value)?;
             }
         }
 
         if evicted_count > 0 {
             info!(
                 strategy = %self.storage.strategy_name(),
+                policy = ?self.config.eviction_policy,
                 evicted_count = evicted_count,
                 size_freed_kb = size_freed / 1024,
-                "Evicted LRU entries"
+                "Evicted cache entries"
             );
         }
 
@@ -406,6 +809,10 @@ mod tests {
             max_entries: Some(2),
             max_size_bytes: None,
             eager_persistence: false,
+            max_age: None,
+            save_interval: None,
+            revalidate_after: None,
+            eviction_policy: EvictionPolicy::LeastRecentlyUsed,
         };
         let mut cache = LruCache::new(storage, config).unwrap();
 
@@ -431,6 +838,204 @@ mod tests {
         assert!(cache.contains(&"key3".to_string()));
     }
 
+    #[test]
+    fn test_lfu_eviction() {
+        let storage = MockStorage::new();
+        let config = CacheConfig {
+            max_entries: Some(2),
+            eviction_policy: EvictionPolicy::LeastFrequentlyUsed,
+            ..Default::default()
+        };
+        let mut cache = LruCache::new(storage, config).unwrap();
+        let key1 = "key1".to_string();
+        let key2 = "key2".to_string();
+
+        cache.insert(key1.clone(), "value1".to_string()).unwrap();
+        cache.insert(key2.clone(), "value2".to_string()).unwrap();
+        cache.get(&key1);
+        cache.get(&key1);
+        cache.get(&key2);
+
+        // key2 is the most recently used, but used less often than key1
+        cache
+            .insert("key3".to_string(), "value3".to_string())
+            .unwrap();
+        assert!(cache.contains(&key1));
+        assert!(!cache.contains(&key2));
+
+        // Back to least recently used: key1 was used longest ago
+        cache.set_eviction_policy(EvictionPolicy::LeastRecentlyUsed);
+        assert_eq!(cache.eviction_policy(), EvictionPolicy::LeastRecentlyUsed);
+        cache
+            .insert("key4".to_string(), "value4".to_string())
+            .unwrap();
+        assert!(!cache.contains(&key1));
+        assert!(cache.contains(&"key3".to_string()));
+    }
+
+    #[test]
+    fn test_pinned_entries_are_not_evicted() {
+        let storage = MockStorage::new();
+        let config = CacheConfig {
+            max_entries: Some(2),
+            ..Default::default()
+        };
+        let mut cache = LruCache::new(storage, config).unwrap();
+        let key1 = "key1".to_string();
+
+        cache.pin([&key1]);
+        cache.pin([&key1]);
+        cache.insert(key1.clone(), "value1".to_string()).unwrap();
+        cache
+            .insert("key2".to_string(), "value2".to_string())
+            .unwrap();
+
+        // key1 is least recently used, but pinned
+        cache
+            .insert("key3".to_string(), "value3".to_string())
+            .unwrap();
+        assert!(cache.contains(&key1));
+        assert!(!cache.contains(&"key2".to_string()));
+
+        // Still pinned once
+        cache.unpin([&key1]);
+        cache
+            .insert("key4".to_string(), "value4".to_string())
+            .unwrap();
+        assert!(cache.contains(&key1));
+
+        cache.unpin([&key1]);
+        assert!(!cache.is_pinned(&key1));
+        cache
+            .insert("key5".to_string(), "value5".to_string())
+            .unwrap();
+        assert!(!cache.contains(&key1));
+    }
+
+    #[test]
+    fn test_expired_entries_are_dropped() {
+        let storage = MockStorage::new();
+        let evict_calls = storage.evict_calls.clone();
+        let config = CacheConfig {
+            max_age: Some(Duration::days(30)),
+            ..Default::default()
+        };
+        let mut cache = LruCache::new(storage, config).unwrap();
+
+        for key in ["old1", "old2", "fresh"] {
+            cache.insert(key.to_string(), "value".to_string()).unwrap();
+        }
+        for key in ["old1", "old2"] {
+            cache.entries.get_mut(key).unwrap().created_at -= Duration::days(31);
+        }
+
+        // Expired entries read as misses, and compaction removes the rest of them
+        assert!(!cache.contains(&"old1".to_string()));
+        assert_eq!(cache.get(&"old1".to_string()), None);
+        assert_eq!(cache.compact().unwrap(), 1);
+        assert_eq!(cache.len(), 1);
+        assert_eq!(cache.size_bytes(), ("fresh".len() + "value".len()) as u64);
+        assert_eq!(evict_calls.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_stale_entries_are_revalidated() {
+        let storage = MockStorage::new();
+        let config = CacheConfig {
+            revalidate_after: Some(Duration::days(30)),
+            ..Default::default()
+        };
+        let mut cache = LruCache::new(storage, config).unwrap();
+
+        let validators = HttpValidators {
+            etag: Some("\"abc123\"".to_string()),
+            last_modified: None,
+        };
+        cache
+            .insert_with_validators("image".to_string(), "v1".to_string(), validators.clone())
+            .unwrap();
+        cache
+            .insert("unvalidated".to_string(), "v1".to_string())
+            .unwrap();
+        assert_eq!(cache.validators_if_stale(&"image".to_string()), None);
+
+        for key in ["image", "unvalidated"] {
+            cache.entries.get_mut(key).unwrap().created_at -= Duration::days(31);
+        }
+        // Stale entries stay usable; only those with validators can be revalidated
+        assert_eq!(cache.get(&"image".to_string()), Some("v1".to_string()));
+        assert_eq!(
+            cache.validators_if_stale(&"image".to_string()),
+            Some(validators)
+        );
+        assert_eq!(cache.validators_if_stale(&"unvalidated".to_string()), None);
+
+        // A 304 response makes the entry fresh again
+        assert!(cache.mark_revalidated(&"image".to_string()));
+        assert_eq!(cache.validators_if_stale(&"image".to_string()), None);
+    }
+
+    #[test]
+    fn test_compaction_enforces_limits_and_saves_periodically() {
+        let storage = MockStorage::new();
+        let save_calls = storage.save_calls.clone();
+        let config = CacheConfig {
+            max_entries: Some(10),
+            max_size_bytes: None,
+            eager_persistence: false,
+            max_age: None,
+            save_interval: Some(3),
+            revalidate_after: None,
+            eviction_policy: EvictionPolicy::LeastRecentlyUsed,
+        };
+        let mut cache = LruCache::new(storage, config).unwrap();
+
+        for i in 0..4 {
+            cache
+                .insert(format!("key{}", i), "value".to_string())
+                .unwrap();
+        }
+        // Saved once, after the third insert
+        assert_eq!(save_calls.lock().unwrap().len(), 1);
+        assert_eq!(save_calls.lock().unwrap()[0].len(), 3);
+
+        // A lowered limit takes effect on the next compaction, keeping recently used entries
+        cache.get(&"key0".to_string());
+        cache.config.max_entries = Some(2);
+        cache.compact_and_save().unwrap();
+        assert_eq!(cache.len(), 2);
+        assert!(cache.contains(&"key0".to_string()));
+        assert!(cache.contains(&"key3".to_string()));
+        assert_eq!(save_calls.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_read_only_cache_never_writes() {
+        let storage = MockStorage::new();
+        let save_calls = storage.save_calls.clone();
+        let evict_calls = storage.evict_calls.clone();
+        let config = CacheConfig {
+            max_entries: Some(2),
+            save_interval: Some(1),
+            ..Default::default()
+        };
+        let mut cache = LruCache::new(storage, config).unwrap();
+        cache.set_read_only(true);
+
+        for i in 0..3 {
+            cache
+                .insert(format!("key{}", i), "value".to_string())
+                .unwrap();
+        }
+        cache.compact_and_save().unwrap();
+        cache.clear().unwrap();
+
+        // Entries come and go in memory only
+        assert!(cache.is_empty());
+        assert!(save_calls.lock().unwrap().is_empty());
+        assert!(evict_calls.lock().unwrap().is_empty());
+    }
+
     #[test]
     fn test_cache_stats() {
         let storage = MockStorage::new();
@@ -1,11 +1,14 @@
 //! Image cache implementation using the generic LRU framework
 
-use super::{CacheConfig, FileStorage, LruCache};
+use super::{CacheConfig, EvictionPolicy, FileStorage, LruCache};
 use crate::error::ProxyError;
 use std::path::PathBuf;
+use time::Duration;
 
 const MAGIC_CARD_SIZE_ESTIMATE: u64 = 956 * 1024; // 480x680 pixels * 3 bytes ≈ 956 KB
 const DEFAULT_MAX_SIZE_MB: u64 = 1000;
+/// Cached images are checked for changes with a conditional request after this many days
+const IMAGE_REVALIDATE_AFTER_DAYS: i64 = 30;
 
 /// Image cache type alias
 pub type LruImageCache = LruCache<String, Vec<u8>, FileStorage>;
@@ -32,6 +35,10 @@ pub fn create_image_cache_with_config(
         max_entries: None, // No entry limit, only size limit
         max_size_bytes: Some(max_size_bytes),
         eager_persistence: false, // Save only on shutdown for performance
+        max_age: None,
+        save_interval: None,
+        revalidate_after: Some(Duration::days(IMAGE_REVALIDATE_AFTER_DAYS)),
+        eviction_policy: EvictionPolicy::LeastRecentlyUsed,
     };
 
     LruCache::new(storage, config)
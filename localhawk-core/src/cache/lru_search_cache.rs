@@ -1,43 +1,83 @@
 //! Search results cache implementation using the generic LRU framework
 
 use super::search_json_storage::SearchJsonStorage;
-use super::{CacheConfig, LruCache};
+use super::{CacheConfig, EvictionPolicy, LruCache};
 use crate::error::ProxyError;
 use crate::scryfall::CardSearchResult;
 use std::path::PathBuf;
+use time::Duration;
 
 const SEARCH_RESULT_SIZE_ESTIMATE: u64 = 50 * 1024; // 50 KB per cached search
 const DEFAULT_MAX_SEARCHES: usize = 1000; // Reasonable limit for search results
+/// Default memory budget for cached searches, measured by their serialized size
+pub const DEFAULT_SEARCH_CACHE_MAX_BYTES: u64 = 50 * 1024 * 1024;
+const SEARCH_RESULT_MAX_AGE_DAYS: i64 = 30;
+const SEARCH_CACHE_SAVE_INTERVAL: usize = 25; // New searches between saves to disk
+const DEFAULT_MAX_QUERIES: usize = 200;
+const QUERY_CACHE_MAX_BYTES: u64 = 20 * 1024 * 1024;
+const QUERY_RESULT_MAX_AGE_DAYS: i64 = 1; // Broad queries pick up new cards quickly
 
 /// Search results cache type alias
 pub type LruSearchCache = LruCache<String, CardSearchResult, SearchJsonStorage>;
 
 /// Create a new search results cache with sensible defaults
+///
+/// The memory budget comes from the `search_cache_max_mb` setting.
 pub fn create_search_cache() -> Result<LruSearchCache, ProxyError> {
     let cache_file = PathBuf::from(crate::get_search_cache_path());
+    let max_size_bytes = crate::get_settings()
+        .search_cache_max_mb
+        .map_or(DEFAULT_SEARCH_CACHE_MAX_BYTES, |mb| mb * 1024 * 1024);
 
-    let storage = SearchJsonStorage::new(cache_file, SEARCH_RESULT_SIZE_ESTIMATE)?;
-
-    let config = CacheConfig {
-        max_entries: Some(DEFAULT_MAX_SEARCHES),
-        max_size_bytes: Some(DEFAULT_MAX_SEARCHES as u64 * SEARCH_RESULT_SIZE_ESTIMATE), // ~50MB max
-        eager_persistence: false, // Save only on shutdown for performance
-    };
-
-    LruCache::new(storage, config)
+    create_search_cache_with_limits(cache_file, DEFAULT_MAX_SEARCHES, max_size_bytes)
 }
 
 /// Create a new search results cache with custom configuration
 pub fn create_search_cache_with_config(
     cache_file: PathBuf,
     max_searches: usize,
+) -> Result<LruSearchCache, ProxyError> {
+    create_search_cache_with_limits(cache_file, max_searches, DEFAULT_SEARCH_CACHE_MAX_BYTES)
+}
+
+/// Create a new search results cache that evicts the least recently used searches once
+/// either limit is exceeded
+pub fn create_search_cache_with_limits(
+    cache_file: PathBuf,
+    max_searches: usize,
+    max_size_bytes: u64,
 ) -> Result<LruSearchCache, ProxyError> {
     let storage = SearchJsonStorage::new(cache_file, SEARCH_RESULT_SIZE_ESTIMATE)?;
 
     let config = CacheConfig {
         max_entries: Some(max_searches),
-        max_size_bytes: Some(max_searches as u64 * SEARCH_RESULT_SIZE_ESTIMATE),
+        max_size_bytes: Some(max_size_bytes),
+        eager_persistence: false, // Save on shutdown and every few new searches
+        // New printings show up in search results, so old searches are eventually redone
+        max_age: Some(Duration::days(SEARCH_RESULT_MAX_AGE_DAYS)),
+        save_interval: Some(SEARCH_CACHE_SAVE_INTERVAL),
+        revalidate_after: None,
+        eviction_policy: EvictionPolicy::LeastRecentlyUsed,
+    };
+
+    LruCache::new(storage, config)
+}
+
+/// Create the cache for raw Scryfall queries, kept apart from the card name searches
+///
+/// Query results cover whatever matches at the time, so they expire much sooner.
+pub fn create_query_cache() -> Result<LruSearchCache, ProxyError> {
+    let cache_file = PathBuf::from(crate::get_query_cache_path());
+    let storage = SearchJsonStorage::new(cache_file, SEARCH_RESULT_SIZE_ESTIMATE)?;
+
+    let config = CacheConfig {
+        max_entries: Some(DEFAULT_MAX_QUERIES),
+        max_size_bytes: Some(QUERY_CACHE_MAX_BYTES),
         eager_persistence: false,
+        max_age: Some(Duration::days(QUERY_RESULT_MAX_AGE_DAYS)),
+        save_interval: Some(SEARCH_CACHE_SAVE_INTERVAL),
+        revalidate_after: None,
+        eviction_policy: EvictionPolicy::LeastRecentlyUsed,
     };
 
     LruCache::new(storage, config)
@@ -57,12 +97,25 @@ mod tests {
                 language: "en".to_string(),
                 border_crop: format!("https://example.com/image{}.jpg", i),
                 back_side: None,
+                frame: None,
+                digital: false,
+                collector_number: None,
+                prices: None,
+                legalities: Default::default(),
+                layout: None,
+                scryfall_id: None,
+                oracle_id: None,
+                mana_cost: None,
+                type_line: None,
+                oracle_text: None,
+                power_toughness: None,
             })
             .collect();
 
         CardSearchResult {
             cards,
             total_found: count,
+            has_more: false,
         }
     }
 
@@ -127,6 +180,41 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_search_cache_byte_budget() {
+        let temp_file =
+            env::temp_dir().join(format!("localhawk-budget-test-{}.json", std::process::id()));
+
+        let small = create_test_search_result("Card 1", 1);
+        let large = create_test_search_result("Card 2", 200);
+        let small_size = serde_json::to_vec(&small).unwrap().len() as u64;
+        let large_size = serde_json::to_vec(&large).unwrap().len() as u64;
+
+        // Room for the large search plus a couple of small ones, never for two large ones
+        let budget = large_size + 2 * (small_size + 16);
+        let mut cache = create_search_cache_with_limits(temp_file.clone(), 100, budget).unwrap();
+        cache.clear().unwrap();
+
+        cache.insert("card1".to_string(), small.clone()).unwrap();
+        cache.insert("card2".to_string(), large.clone()).unwrap();
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.size_bytes(), 5 + small_size + 5 + large_size);
+
+        // Keep card2 recently used, so the next large search evicts card1 and then card2
+        cache.get(&"card2".to_string());
+        cache.insert("card3".to_string(), large.clone()).unwrap();
+        assert!(!cache.contains(&"card1".to_string()));
+        assert!(!cache.contains(&"card2".to_string()));
+        assert!(cache.contains(&"card3".to_string()));
+        assert_eq!(cache.size_bytes(), 5 + large_size);
+
+        // Clean up
+        cache.clear().unwrap();
+        if temp_file.exists() {
+            std::fs::remove_file(temp_file).ok();
+        }
+    }
+
     #[test]
     fn test_search_cache_persistence() {
         let temp_file = env::temp_dir().join(format!(
@@ -5,7 +5,7 @@
 
 #[cfg(test)]
 mod comprehensive_lru_tests {
-    use super::super::lru_cache::{CacheConfig, CacheEntry, LruCache};
+    use super::super::lru_cache::{CacheConfig, CacheEntry, EvictionPolicy, LruCache};
     use super::super::vector_storage::VectorStorage;
     use std::collections::HashMap;
 
@@ -17,6 +17,10 @@ mod comprehensive_lru_tests {
             max_entries,
             max_size_bytes: max_size,
             eager_persistence: false,
+            max_age: None,
+            save_interval: None,
+            revalidate_after: None,
+            eviction_policy: EvictionPolicy::LeastRecentlyUsed,
         };
         LruCache::new(storage, config).unwrap()
     }
@@ -119,6 +123,10 @@ mod comprehensive_lru_tests {
             max_entries: Some(2),
             max_size_bytes: None,
             eager_persistence: false,
+            max_age: None,
+            save_interval: None,
+            revalidate_after: None,
+            eviction_policy: EvictionPolicy::LeastRecentlyUsed,
         };
         let mut cache = LruCache::new(storage.clone(), config).unwrap();
 
@@ -170,6 +178,29 @@ mod comprehensive_lru_tests {
         assert!(!evicted);
     }
 
+    #[test]
+    fn test_evict_created_before() {
+        let storage = VectorStorage::<String, String>::new();
+        let mut cache = LruCache::new(storage.clone(), CacheConfig::default()).unwrap();
+
+        cache.insert("old".to_string(), "1".to_string()).unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(2));
+        let cutoff = time::OffsetDateTime::now_utc();
+        std::thread::sleep(std::time::Duration::from_millis(2));
+        cache.insert("new".to_string(), "2".to_string()).unwrap();
+
+        assert_eq!(cache.evict_created_before(cutoff).unwrap(), 1);
+        assert!(!cache.contains(&"old".to_string()));
+        assert!(cache.contains(&"new".to_string()));
+        assert_eq!(
+            storage.get_evicted_entries(),
+            vec![("old".to_string(), "1".to_string())]
+        );
+
+        // Nothing left that old
+        assert_eq!(cache.evict_created_before(cutoff).unwrap(), 0);
+    }
+
     #[test]
     fn test_cache_clear() {
         let storage = VectorStorage::<String, String>::new();
@@ -262,6 +293,10 @@ mod comprehensive_lru_tests {
             max_entries: Some(2),
             max_size_bytes: None,
             eager_persistence: true, // Force save on every insert
+            max_age: None,
+            save_interval: None,
+            revalidate_after: None,
+            eviction_policy: EvictionPolicy::LeastRecentlyUsed,
         };
 
         let mut cache = LruCache::new(storage, config).unwrap();
@@ -280,6 +315,10 @@ mod comprehensive_lru_tests {
             max_entries: Some(1),
             max_size_bytes: None,
             eager_persistence: false,
+            max_age: None,
+            save_interval: None,
+            revalidate_after: None,
+            eviction_policy: EvictionPolicy::LeastRecentlyUsed,
         };
 
         let mut cache = LruCache::new(storage, config).unwrap();
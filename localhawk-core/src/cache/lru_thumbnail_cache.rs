@@ -0,0 +1,125 @@
+//! Thumbnail cache: downscaled copies of cached images for preview grids
+//!
+//! The preview grid and the print selection show card images at about 200 pixels wide, so
+//! decoding the full-size images there wastes memory. Thumbnails are derived from the image
+//! cache on first access and kept in a `thumbnails` directory inside the image cache directory.
+
+use super::{CacheConfig, EvictionPolicy, FileStorage, LruCache};
+use crate::error::ProxyError;
+use printpdf::image_crate::{self, DynamicImage, ImageOutputFormat};
+use std::path::PathBuf;
+
+/// Width thumbnails are scaled down to, enough for the preview grid on high-DPI screens
+pub const THUMBNAIL_WIDTH: u32 = 300;
+const THUMBNAIL_JPEG_QUALITY: u8 = 85;
+const THUMBNAIL_SIZE_ESTIMATE: u64 = 40 * 1024; // 300x425 pixels as JPEG ≈ 40 KB
+const DEFAULT_MAX_SIZE_MB: u64 = 100;
+const THUMBNAILS_DIRECTORY: &str = "thumbnails";
+
+/// Thumbnail cache type alias, keyed by the URL of the full-size image
+pub type LruThumbnailCache = LruCache<String, Vec<u8>, FileStorage>;
+
+/// Create the thumbnail cache inside the image cache directory
+pub fn create_thumbnail_cache() -> Result<LruThumbnailCache, ProxyError> {
+    create_thumbnail_cache_with_config(None, DEFAULT_MAX_SIZE_MB * 1024 * 1024)
+}
+
+/// Create a thumbnail cache in `image_cache_dir` (None = the image cache directory)
+pub fn create_thumbnail_cache_with_config(
+    image_cache_dir: Option<PathBuf>,
+    max_size_bytes: u64,
+) -> Result<LruThumbnailCache, ProxyError> {
+    let image_cache_dir =
+        image_cache_dir.unwrap_or_else(|| PathBuf::from(crate::get_cache_directory_path()));
+
+    // Thumbnails aren't images of their own, so front ends aren't told about them
+    let storage = FileStorage::new(
+        image_cache_dir.join(THUMBNAILS_DIRECTORY),
+        "jpg".to_string(),
+        THUMBNAIL_SIZE_ESTIMATE,
+    )?
+    .without_cache_events();
+
+    let config = CacheConfig {
+        max_entries: None, // No entry limit, only size limit
+        max_size_bytes: Some(max_size_bytes),
+        eager_persistence: false, // Save only on shutdown for performance
+        max_age: None,
+        save_interval: None,
+        revalidate_after: None,
+        eviction_policy: EvictionPolicy::LeastRecentlyUsed,
+    };
+
+    LruCache::new(storage, config)
+}
+
+/// Scale an encoded image down to [`THUMBNAIL_WIDTH`] and encode it as JPEG
+///
+/// Images that are already that small are re-encoded at their size.
+pub fn make_thumbnail(image_bytes: &[u8]) -> Result<Vec<u8>, ProxyError> {
+    let image = image_crate::load_from_memory(image_bytes)
+        .map_err(|e| ProxyError::Cache(format!("Failed to decode image: {}", e)))?;
+    let thumbnail = if image.width() > THUMBNAIL_WIDTH {
+        // Keeps the aspect ratio; the height is left unbounded
+        image.thumbnail(THUMBNAIL_WIDTH, u32::MAX)
+    } else {
+        image
+    };
+
+    // JPEG has no alpha channel
+    let mut bytes = Vec::new();
+    DynamicImage::ImageRgb8(thumbnail.to_rgb8())
+        .write_to(
+            &mut std::io::Cursor::new(&mut bytes),
+            ImageOutputFormat::Jpeg(THUMBNAIL_JPEG_QUALITY),
+        )
+        .map_err(|e| ProxyError::Cache(format!("Failed to encode thumbnail: {}", e)))?;
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use printpdf::image_crate::{ImageFormat, RgbImage};
+    use std::env;
+
+    fn card_image(width: u32, height: u32) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        DynamicImage::ImageRgb8(RgbImage::new(width, height))
+            .write_to(&mut std::io::Cursor::new(&mut bytes), ImageFormat::Jpeg)
+            .unwrap();
+        bytes
+    }
+
+    #[test]
+    fn test_make_thumbnail() {
+        let thumbnail = make_thumbnail(&card_image(480, 680)).unwrap();
+        let thumbnail = image_crate::load_from_memory(&thumbnail).unwrap();
+        assert_eq!(thumbnail.width(), THUMBNAIL_WIDTH);
+        assert_eq!(thumbnail.height(), 425);
+
+        // Small images are not scaled up
+        let small = make_thumbnail(&card_image(100, 140)).unwrap();
+        assert_eq!(image_crate::load_from_memory(&small).unwrap().width(), 100);
+
+        assert!(make_thumbnail(b"not an image").is_err());
+    }
+
+    #[test]
+    fn test_thumbnail_cache_directory() {
+        let temp_dir =
+            env::temp_dir().join(format!("localhawk-thumbnail-test-{}", std::process::id()));
+        let mut cache =
+            create_thumbnail_cache_with_config(Some(temp_dir.clone()), 1024 * 1024).unwrap();
+
+        let url = "https://example.com/thumbnail.jpg".to_string();
+        cache.insert(url.clone(), vec![1, 2, 3]).unwrap();
+        cache.save_to_storage().unwrap();
+        assert!(temp_dir.join(THUMBNAILS_DIRECTORY).is_dir());
+        assert_eq!(cache.get(&url), Some(vec![1, 2, 3]));
+
+        // Clean up
+        cache.clear().unwrap();
+        std::fs::remove_dir_all(temp_dir).ok();
+    }
+}
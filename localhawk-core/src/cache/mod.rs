@@ -1,8 +1,11 @@
 // LRU cache framework modules
+pub mod file_lock;
 pub mod file_storage;
+pub mod image_archive;
 pub mod lru_cache;
 pub mod lru_image_cache;
 pub mod lru_search_cache;
+pub mod lru_thumbnail_cache;
 pub mod search_json_storage;
 pub mod vector_storage;
 
@@ -11,10 +14,20 @@ pub mod vector_storage;
 pub mod lru_tests;
 
 // Re-export the main types for convenience
+pub use file_lock::FileLock;
 pub use file_storage::FileStorage;
-pub use lru_cache::{CacheConfig, CacheEntry, CacheStats, LruCache, StorageStrategy};
+pub use image_archive::{ImageArchiveImport, export_image_cache, import_image_cache};
+pub use lru_cache::{
+    CacheConfig, CacheEntry, CacheStats, EvictionPolicy, HttpValidators, LruCache, StorageStrategy,
+};
 pub use lru_image_cache::{LruImageCache, create_image_cache, create_image_cache_with_config};
-pub use lru_search_cache::{LruSearchCache, create_search_cache, create_search_cache_with_config};
+pub use lru_search_cache::{
+    DEFAULT_SEARCH_CACHE_MAX_BYTES, LruSearchCache, create_query_cache, create_search_cache,
+    create_search_cache_with_config, create_search_cache_with_limits,
+};
+pub use lru_thumbnail_cache::{
+    LruThumbnailCache, THUMBNAIL_WIDTH, create_thumbnail_cache, make_thumbnail,
+};
 pub use search_json_storage::SearchJsonStorage;
 pub use vector_storage::VectorStorage;
 
@@ -7,7 +7,7 @@ use super::lru_cache::{CacheEntry, StorageStrategy};
 use crate::error::ProxyError;
 use crate::scryfall::CardSearchResult;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::PathBuf;
 use time::OffsetDateTime;
@@ -96,8 +96,11 @@ impl StorageStrategy<String, CardSearchResult> for SearchJsonStorage {
             },
         };
 
-        let json = serde_json::to_string_pretty(&cache_data).map_err(ProxyError::Json)?;
-        fs::write(&self.cache_file, json).map_err(ProxyError::Io)?;
+        // Write to a temporary file first, so a crash mid-save never truncates the cache
+        let json = serde_json::to_vec(&cache_data).map_err(ProxyError::Json)?;
+        let temp_file = self.cache_file.with_extension("json.tmp");
+        fs::write(&temp_file, json).map_err(ProxyError::Io)?;
+        fs::rename(&temp_file, &self.cache_file).map_err(ProxyError::Io)?;
 
         debug!(
             entries = entries.len(),
@@ -108,10 +111,33 @@ impl StorageStrategy<String, CardSearchResult> for SearchJsonStorage {
         Ok(())
     }
 
-    fn estimate_size(&self, _key: &String, _value: &CardSearchResult) -> u64 {
-        // Use the provided size estimate
-        // For more precision, we could serialize and measure, but that's expensive
-        self.size_estimate
+    fn save_merged(
+        &self,
+        entries: &HashMap<String, CacheEntry<CardSearchResult>>,
+        removed: &HashSet<String>,
+    ) -> Result<(), ProxyError> {
+        // Searches another process cached since ours were loaded; ours win for the same key
+        let mut merged = self.load().unwrap_or_default();
+        merged.retain(|key, _| !removed.contains(key));
+        merged.extend(
+            entries
+                .iter()
+                .map(|(key, entry)| (key.clone(), entry.clone())),
+        );
+        self.save(&merged)
+    }
+
+    fn lock_path(&self) -> Option<PathBuf> {
+        Some(self.cache_file.with_extension("lock"))
+    }
+
+    fn estimate_size(&self, key: &String, value: &CardSearchResult) -> u64 {
+        // Searches for cards with hundreds of printings are orders of magnitude larger than
+        // the average, so measure the serialized result; the cache only does this once per entry
+        match serde_json::to_vec(value) {
+            Ok(json) => (key.len() + json.len()) as u64,
+            Err(_) => self.size_estimate,
+        }
     }
 
     fn get_size_estimate(&self) -> u64 {
@@ -144,12 +170,25 @@ mod tests {
                 language: "en".to_string(),
                 border_crop: format!("https://example.com/image{}.jpg", i),
                 back_side: None,
+                frame: None,
+                digital: false,
+                collector_number: None,
+                prices: None,
+                legalities: Default::default(),
+                layout: None,
+                scryfall_id: None,
+                oracle_id: None,
+                mana_cost: None,
+                type_line: None,
+                oracle_text: None,
+                power_toughness: None,
             })
             .collect();
 
         CardSearchResult {
             cards,
             total_found: count,
+            has_more: false,
         }
     }
 
@@ -189,13 +228,58 @@ mod tests {
         }
     }
 
+    #[test]
+    #[ignore] // File system dependent test - see CLAUDE.md testing requirements
+    fn test_search_json_save_keeps_other_processes_entries() {
+        use crate::cache::{CacheConfig, LruCache};
+
+        let storage = create_test_storage();
+        let cache_file = storage.cache_file.clone();
+        fs::remove_file(&cache_file).ok();
+        let open = || {
+            let storage = SearchJsonStorage::new(cache_file.clone(), 1024).unwrap();
+            LruCache::new(storage, CacheConfig::default()).unwrap()
+        };
+
+        // Two processes load the same cache, then each saves what it searched
+        let mut gui = open();
+        let mut cli = open();
+        gui.insert(
+            "counterspell".to_string(),
+            create_test_search_result("Counterspell", 2),
+        )
+        .unwrap();
+        cli.insert("island".to_string(), create_test_search_result("Island", 3))
+            .unwrap();
+        gui.save_to_storage().unwrap();
+        cli.save_to_storage().unwrap();
+
+        let saved = storage.load().unwrap();
+        assert!(saved.contains_key("counterspell"));
+        assert!(saved.contains_key("island"));
+
+        // Entries a process evicted stay gone
+        assert!(gui.evict(&"counterspell".to_string()).unwrap());
+        gui.save_to_storage().unwrap();
+        let saved = storage.load().unwrap();
+        assert!(!saved.contains_key("counterspell"));
+        assert!(saved.contains_key("island"));
+
+        fs::remove_file(&cache_file).ok();
+    }
+
     #[test]
     fn test_search_json_size_estimation() {
         let storage = create_test_storage();
         let test_data = create_test_search_result("Test Card", 3);
 
         let size = storage.estimate_size(&"test".to_string(), &test_data);
-        assert_eq!(size, 1024); // Should be the provided estimate
+        let json_len = serde_json::to_vec(&test_data).unwrap().len() as u64;
+        assert_eq!(size, 4 + json_len); // Key plus serialized result
+
+        // Results with more printings take proportionally more space
+        let large_data = create_test_search_result("Test Card", 300);
+        assert!(storage.estimate_size(&"test".to_string(), &large_data) > 50 * size);
     }
 
     #[test]
@@ -0,0 +1,207 @@
+//! Which cards the images in the image cache show
+//!
+//! The image cache is keyed by image URL only. The cached search results know the printing
+//! behind each URL, so they serve as the reverse index. Images whose search results have
+//! expired or were evicted can't be named anymore and are only counted.
+
+use crate::scryfall::models::Card;
+use crate::{BackFaceFallback, DoubleFaceMode};
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+
+/// A printing with at least one image in the image cache
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CachedPrinting {
+    pub name: String,
+    pub set: String,
+    pub collector_number: Option<String>,
+    pub language: String,
+    /// Images of this printing in the cache
+    pub cached_images: usize,
+    /// Images this printing has: the front, and the back of a double-faced card
+    pub images: usize,
+}
+
+impl CachedPrinting {
+    /// Whether all images of the printing are cached, so it prints without a connection
+    pub fn is_complete(&self) -> bool {
+        self.cached_images >= self.images
+    }
+}
+
+/// e.g. "Delver of Secrets (ISD) 51 [en], 1 of 2 images"
+impl fmt::Display for CachedPrinting {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({})", self.name, self.set.to_uppercase())?;
+        if let Some(collector_number) = &self.collector_number {
+            write!(f, " {}", collector_number)?;
+        }
+        write!(f, " [{}]", self.language)?;
+        if !self.is_complete() {
+            write!(f, ", {} of {} images", self.cached_images, self.images)?;
+        }
+        Ok(())
+    }
+}
+
+/// The image cache by card, see [`image_cache_contents`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ImageCacheContents {
+    /// Printings with cached images, ordered by name, set and collector number
+    pub printings: Vec<CachedPrinting>,
+    /// Cached images that no cached search result names
+    pub unknown_images: usize,
+}
+
+impl ImageCacheContents {
+    /// Cards with cached images, in name order
+    pub fn cards(&self) -> Vec<&str> {
+        self.card_names(|_| true)
+    }
+
+    /// Cards with at least one printing whose images are all cached, in name order
+    pub fn offline_ready_cards(&self) -> Vec<&str> {
+        self.card_names(CachedPrinting::is_complete)
+    }
+
+    fn card_names(&self, filter: impl Fn(&CachedPrinting) -> bool) -> Vec<&str> {
+        let mut names: Vec<&str> = self
+            .printings
+            .iter()
+            .filter(|printing| filter(printing))
+            .map(|printing| printing.name.as_str())
+            .collect();
+        names.dedup();
+        names
+    }
+}
+
+/// Map the cached image URLs back to the printings among `cards` they belong to
+///
+/// `cards` are the printings of the cached search results; a printing found in several
+/// searches is listed once.
+pub fn image_cache_contents<'a>(
+    cards: impl IntoIterator<Item = &'a Card>,
+    cached_urls: &HashSet<String>,
+) -> ImageCacheContents {
+    let mut printings = BTreeMap::new();
+    let mut known_urls = HashSet::new();
+    for card in cards {
+        let urls = card.get_images_for_face_mode_with_fallback(
+            &DoubleFaceMode::BothSides,
+            BackFaceFallback::Skip,
+        );
+        let cached: Vec<&String> = urls.iter().filter_map(|url| cached_urls.get(url)).collect();
+        if cached.is_empty() {
+            continue;
+        }
+        let cached_images = cached.len();
+        known_urls.extend(cached);
+
+        // Numeric collector numbers sort by value, so 9 comes before 10
+        let collector_number = card.collector_number.clone();
+        let number_key = collector_number.as_deref().map(|number| {
+            let digits: String = number.chars().take_while(char::is_ascii_digit).collect();
+            (
+                digits.parse::<u32>().unwrap_or(u32::MAX),
+                number.to_string(),
+            )
+        });
+        let key = (
+            card.name.to_lowercase(),
+            card.set.clone(),
+            number_key,
+            card.language.clone(),
+        );
+        printings.entry(key).or_insert_with(|| CachedPrinting {
+            name: card.name.clone(),
+            set: card.set.clone(),
+            collector_number,
+            language: card.language.clone(),
+            cached_images,
+            images: urls.len(),
+        });
+    }
+
+    ImageCacheContents {
+        printings: printings.into_values().collect(),
+        unknown_images: cached_urls.len() - known_urls.len(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scryfall::models::BackSide;
+
+    fn card(name: &str, set: &str, collector_number: &str) -> Card {
+        Card {
+            name: name.to_string(),
+            set: set.to_string(),
+            language: "en".to_string(),
+            border_crop: format!("https://example.com/{}/{}.jpg", set, collector_number),
+            back_side: None,
+            frame: None,
+            digital: false,
+            collector_number: Some(collector_number.to_string()),
+            prices: None,
+            legalities: BTreeMap::new(),
+            layout: None,
+            scryfall_id: None,
+            oracle_id: None,
+            mana_cost: None,
+            type_line: None,
+            oracle_text: None,
+            power_toughness: None,
+        }
+    }
+
+    #[test]
+    fn test_image_cache_contents() {
+        let bolt_2xm = card("Lightning Bolt", "2xm", "129");
+        let bolt_sta = card("Lightning Bolt", "sta", "42");
+        let mut delver = card("Delver of Secrets", "isd", "51");
+        delver.back_side = Some(BackSide::DfcBack {
+            image_url: "https://example.com/isd/51-back.jpg".to_string(),
+            name: "Insectile Aberration".to_string(),
+        });
+        let island_10 = card("Island", "unf", "10");
+        let island_9 = card("Island", "unf", "9");
+
+        let cached_urls: HashSet<String> = [
+            bolt_2xm.border_crop.as_str(),
+            delver.border_crop.as_str(),
+            island_10.border_crop.as_str(),
+            island_9.border_crop.as_str(),
+            "https://example.com/evicted-search.jpg",
+        ]
+        .into_iter()
+        .map(String::from)
+        .collect();
+        // The same printing can be in several cached searches
+        let cards = [
+            &bolt_2xm, &bolt_sta, &delver, &island_10, &island_9, &bolt_2xm,
+        ];
+        let contents = image_cache_contents(cards, &cached_urls);
+
+        let listed: Vec<String> = contents.printings.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            listed,
+            vec![
+                "Delver of Secrets (ISD) 51 [en], 1 of 2 images",
+                "Island (UNF) 9 [en]",
+                "Island (UNF) 10 [en]",
+                "Lightning Bolt (2XM) 129 [en]",
+            ]
+        );
+        assert_eq!(contents.unknown_images, 1);
+        assert_eq!(
+            contents.cards(),
+            vec!["Delver of Secrets", "Island", "Lightning Bolt"]
+        );
+        assert_eq!(
+            contents.offline_ready_cards(),
+            vec!["Island", "Lightning Bolt"]
+        );
+    }
+}
@@ -0,0 +1,63 @@
+//! Events about images landing in or leaving the image cache
+//!
+//! The image cache storage publishes an event whenever it stores or evicts an image, so front
+//! ends can refresh the thumbnails they show as soon as the image is there instead of polling.
+//! The desktop GUI subscribes directly; on iOS the FFI layer forwards the events to its
+//! notification queue for Swift.
+
+use std::sync::LazyLock;
+use tokio::sync::broadcast;
+
+/// Events kept for subscribers that fall behind; older ones are dropped (see RecvError::Lagged)
+const CHANNEL_CAPACITY: usize = 1024;
+
+/// A change to the image cache
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CacheEvent {
+    /// The image at this URL was stored in the image cache
+    ImageCached(String),
+    /// The image at this URL was evicted from the image cache
+    ImageEvicted(String),
+}
+
+static CACHE_EVENTS: LazyLock<broadcast::Sender<CacheEvent>> =
+    LazyLock::new(|| broadcast::channel(CHANNEL_CAPACITY).0);
+
+/// Receive the image cache events published from now on
+pub fn subscribe_cache_events() -> broadcast::Receiver<CacheEvent> {
+    CACHE_EVENTS.subscribe()
+}
+
+/// Publish an event to all current subscribers
+pub(crate) fn publish_cache_event(event: CacheEvent) {
+    // Nobody listening (e.g. the CLI) is not an error
+    let _ = CACHE_EVENTS.send(event);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache_events() {
+        let mut events = subscribe_cache_events();
+        let url = "https://example.com/cache-events-test.jpg".to_string();
+
+        publish_cache_event(CacheEvent::ImageCached(url.clone()));
+        publish_cache_event(CacheEvent::ImageEvicted(url.clone()));
+
+        // Other tests running in parallel publish to the same channel
+        let received: Vec<CacheEvent> = std::iter::from_fn(|| events.try_recv().ok())
+            .filter(|event| {
+                matches!(event, CacheEvent::ImageCached(u) | CacheEvent::ImageEvicted(u) if *u == url)
+            })
+            .collect();
+        assert_eq!(
+            received,
+            vec![
+                CacheEvent::ImageCached(url.clone()),
+                CacheEvent::ImageEvicted(url)
+            ]
+        );
+    }
+}
@@ -4,6 +4,7 @@
 //! It can be used by both async (desktop) and sync (iOS) implementations.
 
 use crate::{
+    i18n::{Text, tr, tr_with},
     lookup::CardNameLookup,
     scryfall::models::{ScryfallCardNames, ScryfallSetCodes},
 };
@@ -68,8 +69,109 @@ pub fn log_cache_miss(reason: &str, cache_type: &str) {
     );
 }
 
-/// Default cache expiry time for card names (in hours)
-pub const CARD_NAMES_CACHE_HOURS: i64 = 24;
+/// Default maximum age before card names and set codes are refreshed automatically (in days)
+pub const DEFAULT_REFRESH_MAX_AGE_DAYS: i64 = 7;
 
-/// Default cache expiry time for set codes (in hours) 
-pub const SET_CODES_CACHE_HOURS: i64 = 24;
\ No newline at end of file
+/// Policy deciding when cached card names and set codes are stale enough to refresh
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FreshnessPolicy {
+    /// Refresh stale data in the background at startup
+    pub auto_refresh: bool,
+    /// Maximum age of cached data before it is considered stale (in hours)
+    pub max_age_hours: i64,
+}
+
+impl Default for FreshnessPolicy {
+    fn default() -> Self {
+        Self::from_days(DEFAULT_REFRESH_MAX_AGE_DAYS)
+    }
+}
+
+impl FreshnessPolicy {
+    pub fn from_days(days: i64) -> Self {
+        Self {
+            auto_refresh: true,
+            max_age_hours: days * 24,
+        }
+    }
+
+    /// Whether data cached at `cached_at` is older than this policy allows
+    pub fn is_stale(&self, cached_at: OffsetDateTime) -> bool {
+        is_cache_expired(cached_at, self.max_age_hours)
+    }
+
+    /// Whether data cached at `cached_at` should be refreshed automatically
+    pub fn needs_refresh(&self, cached_at: OffsetDateTime) -> bool {
+        self.auto_refresh && self.is_stale(cached_at)
+    }
+}
+
+/// Status of the automatic background refresh of card names and set codes
+#[derive(Debug, Clone, PartialEq)]
+pub enum CacheRefreshStatus {
+    /// No refresh was needed or started
+    Idle,
+    /// Refresh in progress for the flagged caches
+    Refreshing { card_names: bool, set_codes: bool },
+    /// Refresh finished successfully
+    Completed { refreshed_at: OffsetDateTime },
+    /// Refresh finished with errors (stale data is still in use)
+    Failed(String),
+}
+
+impl CacheRefreshStatus {
+    pub fn is_refreshing(&self) -> bool {
+        matches!(self, CacheRefreshStatus::Refreshing { .. })
+    }
+}
+
+impl std::fmt::Display for CacheRefreshStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CacheRefreshStatus::Idle => write!(f, "{}", tr(Text::UpToDate)),
+            CacheRefreshStatus::Refreshing {
+                card_names,
+                set_codes,
+            } => match (card_names, set_codes) {
+                (true, true) => write!(f, "{}", tr(Text::RefreshingCardNamesAndSetCodes)),
+                (true, false) => write!(f, "{}", tr(Text::RefreshingCardNames)),
+                _ => write!(f, "{}", tr(Text::RefreshingSetCodes)),
+            },
+            CacheRefreshStatus::Completed { .. } => {
+                write!(f, "{}", tr(Text::RefreshedSuccessfully))
+            }
+            CacheRefreshStatus::Failed(error) => {
+                write!(f, "{}", tr_with(Text::RefreshFailed, &[error]))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::Duration;
+
+    #[test]
+    fn test_freshness_policy_staleness() {
+        let policy = FreshnessPolicy::default();
+        let now = OffsetDateTime::now_utc();
+
+        assert!(!policy.is_stale(now - Duration::days(1)));
+        assert!(!policy.needs_refresh(now - Duration::days(6)));
+        assert!(policy.is_stale(now - Duration::days(8)));
+        assert!(policy.needs_refresh(now - Duration::days(8)));
+    }
+
+    #[test]
+    fn test_freshness_policy_auto_refresh_disabled() {
+        let policy = FreshnessPolicy {
+            auto_refresh: false,
+            ..FreshnessPolicy::from_days(1)
+        };
+        let cached_at = OffsetDateTime::now_utc() - Duration::days(30);
+
+        assert!(policy.is_stale(cached_at));
+        assert!(!policy.needs_refresh(cached_at));
+    }
+}
@@ -1,19 +1,28 @@
 use crate::error::ProxyError;
+use crate::lookup::CardNameLookup;
 use crate::scryfall::{ScryfallCardNames, ScryfallClient};
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
-use time::{Duration, OffsetDateTime};
+use time::OffsetDateTime;
 use tracing::{debug, info, warn};
 
-const CACHE_DURATION_DAYS: i64 = 1;
-
 #[derive(Serialize, Deserialize, Debug)]
 struct CachedCardNames {
     data: ScryfallCardNames,
     cached_at: OffsetDateTime,
 }
 
+/// Fuzzy index of the cached card names, saved next to them so startup doesn't rebuild it
+/// (`L` is the index, or a reference to it when saving)
+#[derive(Serialize, Deserialize)]
+struct CachedCardNameIndex<L> {
+    /// Scryfall's date and the number of the card names the index was built from
+    date: Option<OffsetDateTime>,
+    card_count: usize,
+    lookup: L,
+}
+
 #[derive(Debug)]
 pub struct CardNameCache {
     cache_file_path: PathBuf,
@@ -54,7 +63,10 @@ impl CardNameCache {
                     info!("Disk cache is valid, using cached data");
                     return Ok(cached.data);
                 } else {
-                    warn!(max_age_days = CACHE_DURATION_DAYS, "Disk cache expired");
+                    warn!(
+                        max_age_hours = crate::globals::get_freshness_policy().max_age_hours,
+                        "Disk cache expired"
+                    );
                 }
             } else {
                 info!("No valid disk cache found");
@@ -65,7 +77,16 @@ impl CardNameCache {
 
         // Cache miss or expired - fetch from API
         info!("Fetching fresh card names from Scryfall API");
-        let card_names = client.get_card_names().await?;
+        let card_names = match client.get_card_names().await {
+            Ok(card_names) => card_names,
+            // Expired card names beat none while Scryfall is down
+            Err(e) if e.is_scryfall_unavailable() && !force_update => {
+                let cached = self.load_from_cache().map_err(|_| e)?;
+                warn!("Scryfall unavailable, using expired card names from disk cache");
+                return Ok(cached.data);
+            }
+            Err(e) => return Err(e),
+        };
 
         // Save to cache
         self.save_to_cache(&card_names)?;
@@ -118,7 +139,7 @@ impl CardNameCache {
         fs::write(&self.cache_file_path, content)
             .map_err(|e| ProxyError::Cache(format!("Failed to write cache file: {}", e)))?;
 
-        log::info!(
+        info!(
             "Saved card names to cache: {}",
             self.cache_file_path.display()
         );
@@ -126,8 +147,14 @@ impl CardNameCache {
     }
 
     fn is_cache_valid(&self, cached: &CachedCardNames) -> bool {
-        let age = OffsetDateTime::now_utc() - cached.cached_at;
-        age < Duration::days(CACHE_DURATION_DAYS)
+        !crate::globals::get_freshness_policy().is_stale(cached.cached_at)
+    }
+
+    /// Load card names from disk regardless of their age, together with when they were cached
+    pub fn load_cached_card_names(&self) -> Option<(ScryfallCardNames, OffsetDateTime)> {
+        self.load_from_cache()
+            .ok()
+            .map(|cached| (cached.data, cached.cached_at))
     }
 
     pub fn get_cache_info(&self) -> Option<(OffsetDateTime, usize)> {
@@ -137,13 +164,68 @@ impl CardNameCache {
     }
 
     pub fn clear_cache(&self) -> Result<(), ProxyError> {
-        if self.cache_file_path.exists() {
-            fs::remove_file(&self.cache_file_path)
-                .map_err(|e| ProxyError::Cache(format!("Failed to remove cache file: {}", e)))?;
+        for path in [&self.cache_file_path, &self.index_file_path()] {
+            if path.exists() {
+                fs::remove_file(path).map_err(|e| {
+                    ProxyError::Cache(format!("Failed to remove cache file: {}", e))
+                })?;
+            }
         }
         Ok(())
     }
 
+    /// The saved fuzzy index, next to the card names
+    fn index_file_path(&self) -> PathBuf {
+        self.cache_file_path.with_file_name("card_name_index.json")
+    }
+
+    /// Load the saved fuzzy index of `card_names`; None if there is none or it was built from
+    /// other card names
+    pub fn load_lookup(&self, card_names: &ScryfallCardNames) -> Option<CardNameLookup> {
+        let content = fs::read_to_string(self.index_file_path()).ok()?;
+        let cached: CachedCardNameIndex<CardNameLookup> = match serde_json::from_str(&content) {
+            Ok(cached) => cached,
+            Err(e) => {
+                warn!(error = %e, "Failed to parse saved card name index");
+                return None;
+            }
+        };
+        if cached.date != card_names.date || cached.card_count != card_names.names.len() {
+            debug!("Saved card name index is out of date");
+            return None;
+        }
+        info!(
+            card_count = cached.card_count,
+            "Loaded card name index from disk cache"
+        );
+        Some(cached.lookup)
+    }
+
+    /// Save the fuzzy index of `card_names`, see [`CardNameCache::load_lookup`]
+    pub fn save_lookup(
+        &self,
+        card_names: &ScryfallCardNames,
+        lookup: &CardNameLookup,
+    ) -> Result<(), ProxyError> {
+        let cached = CachedCardNameIndex {
+            date: card_names.date,
+            card_count: card_names.names.len(),
+            lookup,
+        };
+
+        let content = serde_json::to_string(&cached).map_err(|e| {
+            ProxyError::Cache(format!("Failed to serialize card name index: {}", e))
+        })?;
+        fs::write(self.index_file_path(), content)
+            .map_err(|e| ProxyError::Cache(format!("Failed to write card name index: {}", e)))?;
+
+        debug!(
+            index_file = %self.index_file_path().display(),
+            "Saved card name index"
+        );
+        Ok(())
+    }
+
     pub fn get_cache_path(&self) -> &PathBuf {
         &self.cache_file_path
     }
@@ -0,0 +1,270 @@
+use crate::decklist::{DecklistEntry, parse_line};
+use crate::error::ProxyError;
+use crate::scryfall::models::get_minimal_scryfall_languages;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::num::NonZeroU32;
+use std::path::Path;
+
+// Column headers used for card names and quantities by common collection exports
+// (Moxfield, Deckbox, ManaBox, Archidekt, ...), compared case-insensitively
+const NAME_HEADERS: [&str; 3] = ["name", "card name", "card"];
+const QUANTITY_HEADERS: [&str; 5] = ["count", "quantity", "qty", "amount", "copies"];
+
+/// Owned card quantities, used to print proxies only for the cards that are missing
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Collection {
+    quantities: HashMap<String, u32>,
+}
+
+impl Collection {
+    /// Parse a collection export: either CSV with a header row naming the card name and
+    /// quantity columns, or plain decklist-style lines like "4 Lightning Bolt"
+    pub fn parse(text: &str) -> Collection {
+        let mut lines = text.lines().map(str::trim).filter(|line| !line.is_empty());
+        let Some(first_line) = lines.next() else {
+            return Collection::default();
+        };
+
+        match csv_columns(first_line) {
+            Some((name_column, quantity_column)) => {
+                Self::parse_csv(lines, name_column, quantity_column)
+            }
+            None => Self::parse_plain(std::iter::once(first_line).chain(lines)),
+        }
+    }
+
+    /// Load and parse a collection export from a file
+    pub fn load_from(path: &Path) -> Result<Collection, ProxyError> {
+        let text = fs::read_to_string(path)?;
+        let collection = Self::parse(&text);
+        tracing::debug!(
+            "Loaded collection from {}: {} distinct cards, {} total",
+            path.display(),
+            collection.len(),
+            collection.total_cards()
+        );
+        Ok(collection)
+    }
+
+    fn parse_csv<'a>(
+        lines: impl Iterator<Item = &'a str>,
+        name_column: usize,
+        quantity_column: Option<usize>,
+    ) -> Collection {
+        let mut collection = Collection::default();
+        for line in lines {
+            let fields = split_csv_line(line);
+            let Some(name) = fields.get(name_column) else {
+                continue;
+            };
+            let quantity = match quantity_column {
+                Some(column) => fields
+                    .get(column)
+                    .and_then(|quantity| quantity.trim().parse().ok())
+                    .unwrap_or(0),
+                None => 1,
+            };
+            collection.add(name, quantity);
+        }
+        collection
+    }
+
+    fn parse_plain<'a>(lines: impl Iterator<Item = &'a str>) -> Collection {
+        let languages = get_minimal_scryfall_languages();
+        let set_codes = HashSet::new();
+
+        let mut collection = Collection::default();
+        for line in lines {
+            if let Some(entry) = parse_line(line, &languages, &set_codes) {
+                collection.add(&entry.name, entry.multiple.get());
+            }
+        }
+        collection
+    }
+
+    /// Add owned copies of a card
+    pub fn add(&mut self, name: &str, quantity: u32) {
+        let name = normalize_name(name);
+        if name.is_empty() || quantity == 0 {
+            return;
+        }
+        *self.quantities.entry(name).or_insert(0) += quantity;
+    }
+
+    /// Owned copies of a card, matching either the full name or the front face name
+    pub fn quantity(&self, name: &str) -> u32 {
+        self.quantities
+            .get(&lookup_key(&self.quantities, name))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Number of distinct cards in the collection
+    pub fn len(&self) -> usize {
+        self.quantities.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.quantities.is_empty()
+    }
+
+    /// Total number of owned cards
+    pub fn total_cards(&self) -> u32 {
+        self.quantities.values().sum()
+    }
+
+    /// Subtract owned copies from decklist entries, keeping only what still has to be proxied
+    ///
+    /// Owned copies are used up in decklist order, so a card listed in several entries
+    /// (e.g. main deck and sideboard) is only counted against the collection once.
+    pub fn missing_entries(&self, entries: &[DecklistEntry]) -> Vec<DecklistEntry> {
+        let mut remaining = self.quantities.clone();
+
+        entries
+            .iter()
+            .filter_map(|entry| {
+                let key = lookup_key(&remaining, &entry.name);
+                let owned = remaining.get_mut(&key);
+                let needed = entry.multiple.get();
+
+                let missing = match owned {
+                    Some(owned) => {
+                        let used = (*owned).min(needed);
+                        *owned -= used;
+                        needed - used
+                    }
+                    None => needed,
+                };
+
+                NonZeroU32::new(missing).map(|multiple| DecklistEntry {
+                    multiple,
+                    ..entry.clone()
+                })
+            })
+            .collect()
+    }
+}
+
+fn normalize_name(name: &str) -> String {
+    name.trim().to_lowercase()
+}
+
+/// Key under which a card is stored: the full name, or the front face of a "front // back" name
+fn lookup_key(quantities: &HashMap<String, u32>, name: &str) -> String {
+    let name = normalize_name(name);
+    if quantities.contains_key(&name) {
+        return name;
+    }
+    match name.split_once(" // ") {
+        Some((front, _)) if quantities.contains_key(front) => front.to_string(),
+        _ => name,
+    }
+}
+
+/// Find the name and quantity columns if `header` is a CSV header row
+fn csv_columns(header: &str) -> Option<(usize, Option<usize>)> {
+    if !header.contains(',') {
+        return None;
+    }
+    let columns: Vec<String> = split_csv_line(header)
+        .iter()
+        .map(|column| normalize_name(column))
+        .collect();
+    let find = |names: &[&str]| {
+        columns
+            .iter()
+            .position(|column| names.contains(&column.as_str()))
+    };
+
+    let name_column = find(&NAME_HEADERS)?;
+    Some((name_column, find(&QUANTITY_HEADERS)))
+}
+
+/// Split a CSV line on commas, honoring double-quoted fields (with "" as an escaped quote)
+fn split_csv_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => fields.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_csv_export() {
+        let csv = "Count,Tradelist Count,Name,Edition\n\
+                   2,0,\"Atraxa, Praetors' Voice\",Commander 2016\n\
+                   4,1,Lightning Bolt,Magic 2010\n\
+                   1,0,Lightning Bolt,Masters 25\n\
+                   1,0,\"Delver of Secrets // Insectile Aberration\",Innistrad";
+        let collection = Collection::parse(csv);
+
+        assert_eq!(collection.len(), 3);
+        assert_eq!(collection.quantity("atraxa, praetors' voice"), 2);
+        assert_eq!(collection.quantity("Lightning Bolt"), 5);
+        assert_eq!(collection.total_cards(), 8);
+    }
+
+    #[test]
+    fn test_parse_plain_list() {
+        let collection =
+            Collection::parse("4 Lightning Bolt\n// binder\n1 Counterspell [7ED]\nIsland");
+        assert_eq!(collection.quantity("lightning bolt"), 4);
+        assert_eq!(collection.quantity("counterspell"), 1);
+        assert_eq!(collection.quantity("island"), 1);
+        assert_eq!(collection.quantity("brainstorm"), 0);
+    }
+
+    #[test]
+    fn test_missing_entries() {
+        let collection = Collection::parse("name,quantity\nlightning bolt,3\ndelver of secrets,1");
+        let entries = vec![
+            (4, "lightning bolt"),
+            (2, "lightning bolt"),
+            (1, "delver of secrets // insectile aberration"),
+            (2, "counterspell"),
+        ]
+        .map(|(copies, name)| {
+            DecklistEntry::from_multiple_name(NonZeroU32::new(copies).unwrap(), name)
+        });
+
+        let missing: Vec<(String, u32)> = collection
+            .missing_entries(&entries)
+            .into_iter()
+            .map(|entry| (entry.name, entry.multiple.get()))
+            .collect();
+        assert_eq!(
+            missing,
+            vec![
+                ("lightning bolt".to_string(), 1),
+                ("lightning bolt".to_string(), 2),
+                ("counterspell".to_string(), 2),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_split_csv_line_handles_quotes() {
+        assert_eq!(
+            split_csv_line(r#"1,"Borrowing 100,000 Arrows","say ""hi""""#),
+            vec!["1", "Borrowing 100,000 Arrows", "say \"hi\""]
+        );
+    }
+}
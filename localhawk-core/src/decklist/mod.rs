@@ -1,20 +1,174 @@
 use crate::DoubleFaceMode;
+use crate::lookup::normalize_name_separators;
+use crate::scryfall::models::Card;
 use lazy_static::lazy_static;
 use regex::{Match, Regex};
 use std::collections::HashSet;
+use std::num::NonZeroU32;
+use std::ops::RangeInclusive;
+
+mod report;
+
+/// Most printings a collector number range like "235-240" expands to; longer ranges are kept
+/// as a single collector number, which no printing has
+pub const MAX_COLLECTOR_NUMBER_RANGE: u32 = 50;
+
+pub use report::{
+    DownloadEstimate, ESTIMATED_IMAGE_BYTES, ESTIMATED_INK_ML_PER_IMAGE, LARGE_DOWNLOAD_BYTES,
+    LARGE_PRINT_PAGES, LineIssue, PrintEstimate, ResolutionReport,
+};
+
+/// Section of a decklist an entry belongs to, set by header lines like "Sideboard" or "Commander"
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DeckSection {
+    #[default]
+    Main,
+    Sideboard,
+    Commander,
+    Companion,
+    Tokens,
+}
+
+impl DeckSection {
+    /// Recognize a section header line (case-insensitive, ignoring a trailing colon or count
+    /// such as "Commander (1)" or "Sideboard [15]")
+    pub fn from_header(line: &str) -> Option<DeckSection> {
+        let header = line.split(['(', '[']).next().unwrap_or_default();
+        let header = header.trim().trim_end_matches(':').trim().to_lowercase();
+        match header.as_str() {
+            "deck" | "decklist" | "main" | "mainboard" => Some(DeckSection::Main),
+            "sideboard" => Some(DeckSection::Sideboard),
+            "commander" | "commanders" => Some(DeckSection::Commander),
+            "companion" | "companions" => Some(DeckSection::Companion),
+            "token" | "tokens" => Some(DeckSection::Tokens),
+            _ => None,
+        }
+    }
+
+    /// Header line that starts this section, as recognized by [`DeckSection::from_header`]
+    pub fn header(&self) -> &'static str {
+        match self {
+            DeckSection::Main => "Deck",
+            DeckSection::Sideboard => "Sideboard",
+            DeckSection::Commander => "Commander",
+            DeckSection::Companion => "Companion",
+            DeckSection::Tokens => "Tokens",
+        }
+    }
+
+    /// Cards that start the game outside the library (commanders and companions)
+    pub fn is_command_zone(&self) -> bool {
+        matches!(self, DeckSection::Commander | DeckSection::Companion)
+    }
+}
+
+/// Export format of a pasted decklist, used to pick the parsing rules for its lines
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DecklistFormat {
+    /// MTG Arena export: "4 Lightning Bolt (STA) 42"
+    Arena,
+    /// MTGO text export, with sideboard cards prefixed by "SB:"
+    Mtgo,
+    /// Moxfield text export: Arena-style lines with foil markers like "*F*"
+    Moxfield,
+    /// mtgdecks.net list with a tab-separated price column
+    Mtgdecks,
+    /// Plain lines like "4 Lightning Bolt [sta]"
+    #[default]
+    Plain,
+}
+
+impl DecklistFormat {
+    /// Guess the export format from the decklist's card lines
+    pub fn detect(decklist: &str) -> DecklistFormat {
+        lazy_static! {
+            static ref PRICE_COLUMN: Regex = Regex::new(r"\t\s*(\$\s*[\d.,]+|-+)\s*$").unwrap();
+            static ref SET_AND_NUMBER: Regex =
+                Regex::new(r"\([\dA-Za-z]{2,6}\)\s+[^\s*]+").unwrap();
+            static ref FOIL_MARKER: Regex = Regex::new(r"\s\*[A-Z]\*(\s|$)").unwrap();
+        }
+
+        let mut has_prices = false;
+        let mut has_sideboard_prefix = false;
+        let mut has_set_and_number = false;
+        let mut has_moxfield_marker = false;
+        for line in decklist.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with("//") || line.starts_with('#') {
+                continue;
+            }
+            if line == "SIDEBOARD:" {
+                // Moxfield writes its section headers in capitals
+                has_moxfield_marker = true;
+                continue;
+            }
+            has_prices |= PRICE_COLUMN.is_match(line);
+            has_sideboard_prefix |= strip_sideboard_prefix(line).is_some();
+            has_set_and_number |= SET_AND_NUMBER.is_match(line);
+            has_moxfield_marker |= FOIL_MARKER.is_match(line);
+        }
+
+        if has_prices {
+            DecklistFormat::Mtgdecks
+        } else if has_sideboard_prefix {
+            DecklistFormat::Mtgo
+        } else if has_moxfield_marker {
+            DecklistFormat::Moxfield
+        } else if has_set_and_number {
+            DecklistFormat::Arena
+        } else {
+            DecklistFormat::Plain
+        }
+    }
+
+    /// Human-readable name for display
+    pub fn name(&self) -> &'static str {
+        match self {
+            DecklistFormat::Arena => "MTG Arena",
+            DecklistFormat::Mtgo => "MTGO",
+            DecklistFormat::Moxfield => "Moxfield",
+            DecklistFormat::Mtgdecks => "mtgdecks",
+            DecklistFormat::Plain => "Plain",
+        }
+    }
+}
+
+impl std::fmt::Display for DecklistFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// The rest of an MTGO "SB: 2 Pithing Needle" line, if it has the sideboard prefix
+fn strip_sideboard_prefix(line: &str) -> Option<&str> {
+    let prefix = line.get(..3)?;
+    prefix
+        .eq_ignore_ascii_case("sb:")
+        .then(|| line[3..].trim_start())
+}
 
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct DecklistEntry {
-    pub multiple: i32,
+    pub multiple: NonZeroU32, // Lines without a positive count are rejected while parsing
     pub name: String,
     pub set: Option<String>,
     pub lang: Option<String>,
     pub face_mode: DoubleFaceMode,         // Fully resolved face mode
     pub source_line_number: Option<usize>, // Which line in the original decklist this came from (0-indexed), at present only used for printing
+    pub section: DeckSection,              // Set from the most recent section header
+    pub foil: bool, // Marked foil or etched ("*F*", "*E*") by the exporting tool
+    pub tags: Vec<String>, // Tags ("#ramp") and categories ("[Ramp]") from the exporting tool
+    pub scryfall_id: Option<String>, // Pinned printing, takes precedence over set and lang
+    pub collector_number: Option<String>, // Picks one of several printings of the set
+    pub annotation: Option<String>, // Comment ("// lands") grouping this entry, if comments are kept
 }
 
 impl DecklistEntry {
-    pub fn new(multiple: i32, name: &str, set: Option<&str>, lang: Option<&str>) -> DecklistEntry {
+    pub fn new(
+        multiple: NonZeroU32,
+        name: &str,
+        set: Option<&str>,
+        lang: Option<&str>,
+    ) -> DecklistEntry {
         DecklistEntry {
             multiple,
             name: name.to_string(),
@@ -22,21 +176,33 @@ impl DecklistEntry {
             lang: lang.map(String::from),
             face_mode: DoubleFaceMode::BothSides, // Default to both sides for basic parsing
             source_line_number: None,
+            section: DeckSection::Main,
+            foil: false,
+            tags: Vec::new(),
+            scryfall_id: None,
+            collector_number: None,
+            annotation: None,
         }
     }
 
     pub fn from_name(n: &str) -> DecklistEntry {
         DecklistEntry {
-            multiple: 1,
+            multiple: NonZeroU32::MIN,
             name: n.to_string(),
             set: None,
             lang: None,
             face_mode: DoubleFaceMode::BothSides, // Default to both sides
             source_line_number: None,
+            section: DeckSection::Main,
+            foil: false,
+            tags: Vec::new(),
+            scryfall_id: None,
+            collector_number: None,
+            annotation: None,
         }
     }
 
-    pub fn from_multiple_name(m: i32, n: &str) -> DecklistEntry {
+    pub fn from_multiple_name(m: NonZeroU32, n: &str) -> DecklistEntry {
         DecklistEntry {
             multiple: m,
             name: n.to_string(),
@@ -44,6 +210,84 @@ impl DecklistEntry {
             lang: None,
             face_mode: DoubleFaceMode::BothSides, // Default to both sides
             source_line_number: None,
+            section: DeckSection::Main,
+            foil: false,
+            tags: Vec::new(),
+            scryfall_id: None,
+            collector_number: None,
+            annotation: None,
+        }
+    }
+
+    /// Whether the line names one printing by set, collector number and language, like the
+    /// lines of [`format_normalized_line`](crate::format_normalized_line)
+    pub fn is_exact_printing(&self) -> bool {
+        self.set.is_some() && self.collector_number.is_some() && self.lang.is_some()
+    }
+
+    /// Whether `card` is the printing an exact entry (see [`DecklistEntry::is_exact_printing`])
+    /// names; always false for other entries
+    pub fn is_printed_as(&self, card: &Card) -> bool {
+        let same = |expected: &Option<String>, actual: Option<&String>| {
+            expected
+                .as_ref()
+                .zip(actual)
+                .is_some_and(|(expected, actual)| expected.eq_ignore_ascii_case(actual))
+        };
+        card.name.eq_ignore_ascii_case(&self.name)
+            && same(&self.set, Some(&card.set))
+            && same(&self.collector_number, card.collector_number.as_ref())
+            && same(&self.lang, Some(&card.language))
+    }
+
+    /// Collector numbers of a range like "235-240" in a line like "1 Plains (UNF) 235-240",
+    /// which asks for each printing in the range, e.g. a cycle of full-art lands
+    ///
+    /// Only ranges of plain numbers within a named set count, so numbers like "ISD-61" (from
+    /// The List) stay a single collector number.
+    pub fn collector_number_range(&self) -> Option<RangeInclusive<u32>> {
+        let (first, last) = self.collector_number.as_deref()?.split_once('-')?;
+        let number = |digits: &str| {
+            digits
+                .parse::<u32>()
+                .ok()
+                .filter(|_| digits.chars().all(|c| c.is_ascii_digit()))
+        };
+        let (first, last) = (number(first)?, number(last)?);
+        (self.set.is_some() && first < last && last - first < MAX_COLLECTOR_NUMBER_RANGE)
+            .then_some(first..=last)
+    }
+
+    /// One entry per collector number of a range (see
+    /// [`DecklistEntry::collector_number_range`]), each with the copies of this entry; any other
+    /// entry is kept as it is
+    pub fn expand_collector_number_range(self) -> Vec<DecklistEntry> {
+        match self.collector_number_range() {
+            Some(range) => range
+                .map(|number| DecklistEntry {
+                    collector_number: Some(number.to_string()),
+                    ..self.clone()
+                })
+                .collect(),
+            None => vec![self],
+        }
+    }
+
+    /// Label of the group the entry is printed in: its comment annotation, else the header of
+    /// its section outside the main deck
+    pub fn group_label(&self) -> Option<String> {
+        self.annotation.clone().or_else(|| {
+            (self.section != DeckSection::Main).then(|| self.section.header().to_string())
+        })
+    }
+
+    /// How to print `card` for this entry: the back face only if the entry names the card's
+    /// back face, e.g. a line "Kabira Plateau" the name lookup didn't resolve
+    pub fn face_mode_for(&self, card: &Card) -> DoubleFaceMode {
+        if card.face_index(&self.name) == Some(1) {
+            DoubleFaceMode::BackOnly
+        } else {
+            self.face_mode.clone()
         }
     }
 }
@@ -60,10 +304,66 @@ impl ParsedDecklistLine<'_> {
     }
 }
 
-fn parse_multiple(group: Option<Match>) -> i32 {
-    match group {
-        Some(m) => m.as_str().parse().ok().unwrap_or(1),
-        None => 1,
+/// Foil marker, tags and categories that deck building tools append after the card
+#[derive(Debug, Default, PartialEq, Eq)]
+struct LineSuffixes {
+    foil: bool,
+    tags: Vec<String>,
+}
+
+/// Split trailing "*F*" foil markers, "#tag" tags, Archidekt "[Category]" categories and
+/// "^label^" labels off a line, so they don't end up in the card name or set
+fn split_suffixes(line: &str) -> (&str, LineSuffixes) {
+    lazy_static! {
+        static ref MARKER: Regex = Regex::new(r"\s\*([A-Za-z]+)\*$").unwrap();
+        static ref TAG: Regex = Regex::new(r"\s#!?([^\s#]+)$").unwrap();
+        static ref CATEGORY: Regex = Regex::new(r"\s\[([^\[\]]+)\]$").unwrap();
+        static ref LABEL: Regex = Regex::new(r"\s\^[^\^]*\^$").unwrap();
+        // A category only follows a set and collector number; otherwise "[xyz]" is a set
+        static ref SET_AND_NUMBER: Regex =
+            Regex::new(r"\([\dA-Za-z]{2,6}\)\s+[^\s*]+$").unwrap();
+    }
+
+    let mut rest = line.trim_end();
+    let mut suffixes = LineSuffixes::default();
+    // Tags are found last to first; collect them reversed and flip at the end
+    let mut tags = Vec::new();
+    loop {
+        if let Some(marker) = MARKER.captures(rest) {
+            let kind = marker[1].to_lowercase();
+            suffixes.foil |= matches!(kind.as_str(), "f" | "e" | "foil" | "etched");
+            rest = rest[..marker.get(0).unwrap().start()].trim_end();
+        } else if let Some(tag) = TAG.captures(rest) {
+            tags.push(tag[1].to_string());
+            rest = rest[..tag.get(0).unwrap().start()].trim_end();
+        } else if let Some(label) = LABEL.find(rest) {
+            rest = rest[..label.start()].trim_end();
+        } else if let Some(category) = CATEGORY
+            .captures(rest)
+            .filter(|c| SET_AND_NUMBER.is_match(&rest[..c.get(0).unwrap().start()]))
+        {
+            // Archidekt writes "[Commander{top}]"; the braces only control its layout
+            let categories = category[1].split(',').rev().map(|c| {
+                let name = c.split('{').next().unwrap_or_default();
+                name.trim().to_string()
+            });
+            tags.extend(categories.filter(|c| !c.is_empty()));
+            rest = rest[..category.get(0).unwrap().start()].trim_end();
+        } else {
+            break;
+        }
+    }
+    tags.reverse();
+    suffixes.tags = tags;
+    (rest, suffixes)
+}
+
+/// Copies given by the count at the start of a line, one without a count; None for a count
+/// of zero or one too large, which makes the line not a card line
+fn parse_multiple(group: Option<Match>) -> Option<NonZeroU32> {
+    match group.filter(|m| !m.as_str().is_empty()) {
+        Some(m) => m.as_str().parse().ok(),
+        None => Some(NonZeroU32::MIN),
     }
 }
 
@@ -94,6 +394,89 @@ pub fn parse_line(
     line: &str,
     languages: &HashSet<String>,
     set_codes: &HashSet<String>,
+) -> Option<DecklistEntry> {
+    parse_line_with_format(line, DecklistFormat::Plain, languages, set_codes)
+}
+
+/// Parse a line using the rules of the given export format
+///
+/// Arena and Moxfield lines always name a set in parentheses, followed by a collector
+/// number. mtgdecks lines end in a price column. In every format, foil markers, tags and
+/// categories after the card are captured in `foil` and `tags`; a language code in brackets
+/// after the collector number, as written by [`format_normalized_line`], is the language.
+///
+/// [`format_normalized_line`]: crate::format::format_normalized_line
+pub fn parse_line_with_format(
+    line: &str,
+    format: DecklistFormat,
+    languages: &HashSet<String>,
+    set_codes: &HashSet<String>,
+) -> Option<DecklistEntry> {
+    // Prices come last on mtgdecks lines, after the card and any suffixes
+    let line = match format {
+        DecklistFormat::Mtgdecks => line.split('\t').next().unwrap_or_default(),
+        _ => line,
+    };
+    let (line, suffixes) = split_suffixes(line);
+    let mut entry = parse_line_without_suffixes(line, format, languages, set_codes)?;
+    entry.foil = suffixes.foil;
+    entry.tags = suffixes.tags;
+    let language = entry
+        .tags
+        .last()
+        .map(|tag| tag.to_lowercase())
+        .filter(|tag| entry.lang.is_none() && languages.contains(tag));
+    if language.is_some() {
+        entry.tags.pop();
+        entry.lang = language;
+    }
+    Some(entry)
+}
+
+fn parse_line_without_suffixes(
+    line: &str,
+    format: DecklistFormat,
+    languages: &HashSet<String>,
+    set_codes: &HashSet<String>,
+) -> Option<DecklistEntry> {
+    lazy_static! {
+        static ref REARENA: Regex =
+            Regex::new(r"^\s*(\d+)x?\s+(.+?)\s+\(([\dA-Za-z]{2,6})\)(\s.*)?$").unwrap();
+    }
+
+    match format {
+        DecklistFormat::Arena | DecklistFormat::Moxfield => {
+            if let Some(captures) = REARENA.captures(line.trim()) {
+                let name = normalize_name_separators(&captures[2]);
+                tracing::debug!(
+                    "Parsed {} line '{}' -> name: '{}', set: {}",
+                    format,
+                    line.trim(),
+                    name,
+                    &captures[3]
+                );
+                let mut entry = DecklistEntry::new(
+                    parse_multiple(captures.get(1))?,
+                    &name,
+                    Some(&captures[3].to_lowercase()),
+                    None,
+                );
+                entry.collector_number = captures
+                    .get(4)
+                    .and_then(|rest| rest.as_str().split_whitespace().next())
+                    .map(String::from);
+                return Some(entry);
+            }
+        }
+        DecklistFormat::Mtgo | DecklistFormat::Mtgdecks | DecklistFormat::Plain => {}
+    }
+    parse_generic_line(line, languages, set_codes)
+}
+
+fn parse_generic_line(
+    line: &str,
+    languages: &HashSet<String>,
+    set_codes: &HashSet<String>,
 ) -> Option<DecklistEntry> {
     let trimmed = line.trim();
 
@@ -109,20 +492,18 @@ pub fn parse_line(
 
     match REMNS.captures(line) {
         Some(mns) => {
-            let multiple = parse_multiple(mns.get(1));
-            let name = mns.get(2)?.as_str().trim().to_string();
+            let multiple = parse_multiple(mns.get(1))?;
+            let name = normalize_name_separators(mns.get(2)?.as_str());
             let set_or_lang = mns.get(3);
             let (set, lang) = parse_set_and_lang(set_or_lang, languages, set_codes);
-            log::debug!(
+            tracing::debug!(
                 "Parsed decklist line '{}' -> name: '{}', set: {:?}, lang: {:?}",
                 line.trim(),
                 name,
                 set,
                 lang
             );
-            let name_lowercase = name.to_lowercase();
-            let non_entries = ["deck", "decklist", "sideboard"];
-            if non_entries.iter().any(|s| **s == name_lowercase) {
+            if DeckSection::from_header(&name).is_some() {
                 None
             } else {
                 Some(DecklistEntry {
@@ -132,6 +513,12 @@ pub fn parse_line(
                     lang,
                     face_mode: DoubleFaceMode::BothSides, // Default for basic parsing
                     source_line_number: None,             // Will be set by caller if needed
+                    section: DeckSection::Main,           // Will be set by caller if needed
+                    foil: false,
+                    tags: Vec::new(),
+                    scryfall_id: None,
+                    collector_number: None,
+                    annotation: None,
                 })
             }
         }
@@ -139,11 +526,104 @@ pub fn parse_line(
     }
 }
 
+/// Name of the deck, given by a leading "Deck: NAME" or "// NAME" line
+pub fn parse_deck_name(decklist: &str) -> Option<String> {
+    let first_line = decklist
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())?;
+    deck_name_from_line(first_line).map(str::to_string)
+}
+
+fn deck_name_from_line(line: &str) -> Option<&str> {
+    let name = match line.strip_prefix("//") {
+        Some(comment) => comment,
+        None => line
+            .get(..5)
+            .filter(|prefix| prefix.eq_ignore_ascii_case("deck:"))
+            .map(|_| &line[5..])?,
+    };
+    let name = name.trim();
+    // Some exports write section headers as comments, e.g. "// Sideboard"
+    (!name.is_empty() && DeckSection::from_header(name).is_none()).then_some(name)
+}
+
+/// Text of a comment line like "// lands" or "# ramp", None for other lines and for section
+/// headers written as comments
+pub fn comment_annotation(line: &str) -> Option<&str> {
+    let line = line.trim();
+    if !line.starts_with("//") && !line.starts_with('#') {
+        return None;
+    }
+    let text = line.trim_start_matches(['/', '#']).trim();
+    (!text.is_empty() && DeckSection::from_header(text).is_none()).then_some(text)
+}
+
+/// Attach each comment line to the entries after it as their annotation, so the decklist's
+/// own grouping ("// lands", "// removal") can be shown and kept when sorting
+///
+/// Section headers end the group of the comment before them; a leading deck name
+/// (see [`parse_deck_name`]) doesn't start one.
+pub fn attach_comment_annotations(lines: &mut [ParsedDecklistLine]) {
+    let mut annotation: Option<String> = None;
+    for (index, parsed) in lines.iter_mut().enumerate() {
+        if let Some(entry) = &mut parsed.entry {
+            entry.annotation = annotation.clone();
+        } else if index == 0 && deck_name_from_line(parsed.line).is_some() {
+            continue;
+        } else if let Some(text) = comment_annotation(parsed.line) {
+            annotation = Some(text.to_string());
+        } else if is_ignored_line(parsed.line) {
+            // Section headers, also when written as comments like "// Sideboard"
+            annotation = None;
+        }
+    }
+}
+
+/// Lines that don't describe cards on purpose: blank lines, comments, section headers and
+/// "Deck: NAME" lines
+fn is_ignored_line(line: &str) -> bool {
+    let line = line.trim();
+    line.is_empty()
+        || line.starts_with("//")
+        || line.starts_with('#')
+        || DeckSection::from_header(line).is_some()
+        || deck_name_from_line(line).is_some()
+}
+
+/// File name for a deck's PDF, with characters that aren't allowed in file names replaced
+pub fn deck_file_name(deck_name: &str) -> Option<String> {
+    let name: String = deck_name
+        .chars()
+        .map(|c| match c {
+            '<' | '>' | ':' | '"' | '/' | '\\' | '|' | '?' | '*' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    let name = name.trim_matches(|c: char| c == '.' || c.is_whitespace());
+    (!name.is_empty()).then(|| format!("{}.pdf", name))
+}
+
+/// Parse a decklist, detecting its export format first (see [`DecklistFormat::detect`])
 pub fn parse_decklist<'a>(
     decklist: &'a str,
     languages: &HashSet<String>,
     set_codes: &HashSet<String>,
 ) -> Vec<ParsedDecklistLine<'a>> {
+    let format = DecklistFormat::detect(decklist);
+    tracing::debug!("Detected decklist format: {}", format);
+    parse_decklist_with_format(decklist, format, languages, set_codes)
+}
+
+pub fn parse_decklist_with_format<'a>(
+    decklist: &'a str,
+    format: DecklistFormat,
+    languages: &HashSet<String>,
+    set_codes: &HashSet<String>,
+) -> Vec<ParsedDecklistLine<'a>> {
+    let mut section = DeckSection::Main;
+    let mut first_line = true;
     decklist
         .lines()
         .enumerate() // Track line numbers (0-indexed)
@@ -151,11 +631,29 @@ pub fn parse_decklist<'a>(
         .filter_map(|(line_num, s)| {
             if s.is_empty() {
                 None // Skip empty lines but preserve line numbering
+            } else if std::mem::take(&mut first_line) && deck_name_from_line(s).is_some() {
+                Some(ParsedDecklistLine {
+                    line: s,
+                    entry: None,
+                })
             } else {
-                let mut entry = parse_line(s, languages, set_codes);
-                // Set the source line number if we successfully parsed the line
+                if let Some(header) = DeckSection::from_header(s) {
+                    section = header;
+                }
+                // MTGO marks each sideboard card instead of using a header
+                let sideboard_card = match format {
+                    DecklistFormat::Mtgo => strip_sideboard_prefix(s),
+                    _ => None,
+                };
+                let card = sideboard_card.unwrap_or(s);
+                let mut entry = parse_line_with_format(card, format, languages, set_codes);
+                // Set the source line number and section if we successfully parsed the line
                 if let Some(ref mut e) = entry {
                     e.source_line_number = Some(line_num);
+                    e.section = match sideboard_card {
+                        Some(_) => DeckSection::Sideboard,
+                        None => section,
+                    };
                 }
                 Some(ParsedDecklistLine { line: s, entry })
             }
@@ -168,6 +666,10 @@ mod tests {
     use super::*;
     use crate::scryfall::models::get_minimal_scryfall_languages;
 
+    fn copies(n: u32) -> NonZeroU32 {
+        NonZeroU32::new(n).unwrap()
+    }
+
     fn parse_line_default(s: &str) -> Option<DecklistEntry> {
         let minimal = get_minimal_scryfall_languages();
         let set_codes = std::collections::HashSet::new(); // Empty for tests
@@ -192,7 +694,17 @@ mod tests {
     fn number_name() {
         assert_eq!(
             parse_line_default("2\tplains").unwrap(),
-            DecklistEntry::from_multiple_name(2, "plains")
+            DecklistEntry::from_multiple_name(copies(2), "plains")
+        );
+    }
+
+    #[test]
+    fn zero_or_huge_counts_are_not_card_lines() {
+        assert_eq!(parse_line_default("0 Island"), None);
+        assert_eq!(parse_line_default("99999999999 Island"), None);
+        assert_eq!(
+            parse_line_default("Island").map(|entry| entry.multiple),
+            Some(NonZeroU32::MIN)
         );
     }
 
@@ -200,7 +712,7 @@ mod tests {
     fn shatter() {
         assert_eq!(
             parse_line_default("1 shatter [mrd]").unwrap(),
-            DecklistEntry::new(1, "shatter", Some("mrd"), None)
+            DecklistEntry::new(copies(1), "shatter", Some("mrd"), None)
         );
     }
 
@@ -208,7 +720,7 @@ mod tests {
     fn number_name_set() {
         assert_eq!(
             parse_line_default("17 long card's name [IPA]").unwrap(),
-            DecklistEntry::new(17, "long card's name", Some("ipa"), None)
+            DecklistEntry::new(copies(17), "long card's name", Some("ipa"), None)
         );
     }
 
@@ -216,7 +728,7 @@ mod tests {
     fn name_set() {
         assert_eq!(
             parse_line_default("long card's name [ipa]").unwrap(),
-            DecklistEntry::new(1, "long card's name", Some("ipa"), None)
+            DecklistEntry::new(copies(1), "long card's name", Some("ipa"), None)
         );
     }
 
@@ -224,7 +736,7 @@ mod tests {
     fn name_with_tab() {
         assert_eq!(
             parse_line_default("Incubation/Incongruity   \t\t---").unwrap(),
-            DecklistEntry::from_multiple_name(1, "Incubation/Incongruity")
+            DecklistEntry::from_multiple_name(copies(1), "Incubation // Incongruity")
         );
     }
 
@@ -232,7 +744,7 @@ mod tests {
     fn japanese_printing() {
         assert_eq!(
             parse_line_default("memory lapse [ja]").unwrap(),
-            DecklistEntry::new(1, "memory lapse", None, Some("ja")) // Only lang="ja", not set="ja"
+            DecklistEntry::new(copies(1), "memory lapse", None, Some("ja")) // Only lang="ja", not set="ja"
         );
     }
 
@@ -249,67 +761,103 @@ mod tests {
             ParsedDecklistLine {
                 line: "4  Beanstalk Giant   \t\t$0.25",
                 entry: Some(DecklistEntry {
-                    multiple: 4,
+                    multiple: copies(4),
                     name: "Beanstalk Giant".to_string(),
                     set: None,
                     lang: None,
                     face_mode: DoubleFaceMode::BothSides,
                     source_line_number: Some(0),
+                    section: DeckSection::Main,
+                    foil: false,
+                    tags: Vec::new(),
+                    scryfall_id: None,
+                    collector_number: None,
+                    annotation: None,
                 }),
             },
             ParsedDecklistLine {
                 line: "4  Lovestruck Beast   \t\t$1.5",
                 entry: Some(DecklistEntry {
-                    multiple: 4,
+                    multiple: copies(4),
                     name: "Lovestruck Beast".to_string(),
                     set: None,
                     lang: None,
                     face_mode: DoubleFaceMode::BothSides,
                     source_line_number: Some(1),
+                    section: DeckSection::Main,
+                    foil: false,
+                    tags: Vec::new(),
+                    scryfall_id: None,
+                    collector_number: None,
+                    annotation: None,
                 }),
             },
             ParsedDecklistLine {
                 line: "Artifact [5]",
                 entry: Some(DecklistEntry {
-                    multiple: 1,
+                    multiple: copies(1),
                     name: "Artifact".to_string(),
                     set: None,
                     lang: None,
                     face_mode: DoubleFaceMode::BothSides,
                     source_line_number: Some(2),
+                    section: DeckSection::Main,
+                    foil: false,
+                    tags: Vec::new(),
+                    scryfall_id: None,
+                    collector_number: None,
+                    annotation: None,
                 }),
             },
             ParsedDecklistLine {
                 line: "1  The Great Henge   \t\t$25",
                 entry: Some(DecklistEntry {
-                    multiple: 1,
+                    multiple: copies(1),
                     name: "The Great Henge".to_string(),
                     set: None,
                     lang: None,
                     face_mode: DoubleFaceMode::BothSides,
                     source_line_number: Some(3),
+                    section: DeckSection::Main,
+                    foil: false,
+                    tags: Vec::new(),
+                    scryfall_id: None,
+                    collector_number: None,
+                    annotation: None,
                 }),
             },
             ParsedDecklistLine {
                 line: "Instant [1]",
                 entry: Some(DecklistEntry {
-                    multiple: 1,
+                    multiple: copies(1),
                     name: "Instant".to_string(),
                     set: None,
                     lang: None,
                     face_mode: DoubleFaceMode::BothSides,
                     source_line_number: Some(4),
+                    section: DeckSection::Main,
+                    foil: false,
+                    tags: Vec::new(),
+                    scryfall_id: None,
+                    collector_number: None,
+                    annotation: None,
                 }),
             },
             ParsedDecklistLine {
                 line: "1  Incubation/Incongruity   \t\t---",
                 entry: Some(DecklistEntry {
-                    multiple: 1,
-                    name: "Incubation/Incongruity".to_string(),
+                    multiple: copies(1),
+                    name: "Incubation // Incongruity".to_string(),
                     set: None,
                     lang: None,
                     face_mode: DoubleFaceMode::BothSides,
                     source_line_number: Some(5),
+                    section: DeckSection::Main,
+                    foil: false,
+                    tags: Vec::new(),
+                    scryfall_id: None,
+                    collector_number: None,
+                    annotation: None,
                 }),
             },
         ];
@@ -332,23 +880,35 @@ mod tests {
             ParsedDecklistLine {
                 line: "1 Bedeck // Bedazzle (RNA) 221",
                 entry: Some(DecklistEntry {
-                    multiple: 1,
+                    multiple: copies(1),
                     name: "Bedeck // Bedazzle".to_string(),
                     set: Some("rna".to_string()),
                     lang: None,
                     face_mode: DoubleFaceMode::BothSides,
                     source_line_number: Some(1),
+                    section: DeckSection::Main,
+                    foil: false,
+                    tags: Vec::new(),
+                    scryfall_id: None,
+                    collector_number: Some("221".to_string()),
+                    annotation: None,
                 }),
             },
             ParsedDecklistLine {
                 line: "1 Spawn of Mayhem (RNA) 85",
                 entry: Some(DecklistEntry {
-                    multiple: 1,
+                    multiple: copies(1),
                     name: "Spawn of Mayhem".to_string(),
                     set: Some("rna".to_string()),
                     lang: None,
                     face_mode: DoubleFaceMode::BothSides,
                     source_line_number: Some(2),
+                    section: DeckSection::Main,
+                    foil: false,
+                    tags: Vec::new(),
+                    scryfall_id: None,
+                    collector_number: Some("85".to_string()),
+                    annotation: None,
                 }),
             },
         ];
@@ -388,23 +948,35 @@ mod tests {
             ParsedDecklistLine {
                 line: "1 Defiant Strike (M21) 15",
                 entry: Some(DecklistEntry {
-                    multiple: 1,
+                    multiple: copies(1),
                     name: "Defiant Strike".to_string(),
                     set: Some("m21".to_string()),
                     lang: None,
                     face_mode: DoubleFaceMode::BothSides,
                     source_line_number: Some(1),
+                    section: DeckSection::Main,
+                    foil: false,
+                    tags: Vec::new(),
+                    scryfall_id: None,
+                    collector_number: Some("15".to_string()),
+                    annotation: None,
                 }),
             },
             ParsedDecklistLine {
                 line: "24 Plains (ANB) 115",
                 entry: Some(DecklistEntry {
-                    multiple: 24,
+                    multiple: copies(24),
                     name: "Plains".to_string(),
                     set: Some("anb".to_string()),
                     lang: None,
                     face_mode: DoubleFaceMode::BothSides,
                     source_line_number: Some(2),
+                    section: DeckSection::Main,
+                    foil: false,
+                    tags: Vec::new(),
+                    scryfall_id: None,
+                    collector_number: Some("115".to_string()),
+                    annotation: None,
                 }),
             },
             ParsedDecklistLine {
@@ -414,12 +986,18 @@ mod tests {
             ParsedDecklistLine {
                 line: "2 Faerie Guidemother (ELD) 11",
                 entry: Some(DecklistEntry {
-                    multiple: 2,
+                    multiple: copies(2),
                     name: "Faerie Guidemother".to_string(),
                     set: Some("eld".to_string()),
                     lang: None,
                     face_mode: DoubleFaceMode::BothSides,
                     source_line_number: Some(5),
+                    section: DeckSection::Sideboard,
+                    foil: false,
+                    tags: Vec::new(),
+                    scryfall_id: None,
+                    collector_number: Some("11".to_string()),
+                    annotation: None,
                 }),
             },
         ];
@@ -445,36 +1023,71 @@ mod tests {
         let test_cases = vec![
             (
                 "1 Lightning Bolt [BRO]",
-                Some(DecklistEntry::new(1, "Lightning Bolt", Some("bro"), None)),
+                Some(DecklistEntry::new(
+                    copies(1),
+                    "Lightning Bolt",
+                    Some("bro"),
+                    None,
+                )),
             ),
             (
                 "2 Cut // Ribbons [PLST]",
-                Some(DecklistEntry::new(2, "Cut // Ribbons", Some("plst"), None)),
+                Some(DecklistEntry::new(
+                    copies(2),
+                    "Cut // Ribbons",
+                    Some("plst"),
+                    None,
+                )),
             ),
             (
                 "3 Kabira Takedown [PAKH]",
-                Some(DecklistEntry::new(3, "Kabira Takedown", Some("pakh"), None)),
+                Some(DecklistEntry::new(
+                    copies(3),
+                    "Kabira Takedown",
+                    Some("pakh"),
+                    None,
+                )),
             ),
             (
                 "4 Memory Lapse [JA]",
-                Some(DecklistEntry::new(4, "Memory Lapse", None, Some("ja"))),
+                Some(DecklistEntry::new(
+                    copies(4),
+                    "Memory Lapse",
+                    None,
+                    Some("ja"),
+                )),
             ),
             (
                 "1 Brainstorm [FR]",
-                Some(DecklistEntry::new(1, "Brainstorm", None, Some("fr"))),
+                Some(DecklistEntry::new(
+                    copies(1),
+                    "Brainstorm",
+                    None,
+                    Some("fr"),
+                )),
             ),
             (
                 "2 Giant Growth [DE]",
-                Some(DecklistEntry::new(2, "Giant Growth", None, Some("de"))),
+                Some(DecklistEntry::new(
+                    copies(2),
+                    "Giant Growth",
+                    None,
+                    Some("de"),
+                )),
             ),
             (
                 "1 Black Lotus [H2R]",
-                Some(DecklistEntry::new(1, "Black Lotus", Some("h2r"), None)),
+                Some(DecklistEntry::new(
+                    copies(1),
+                    "Black Lotus",
+                    Some("h2r"),
+                    None,
+                )),
             ),
             (
                 "3 Ancestral Recall [PMPS08]",
                 Some(DecklistEntry::new(
-                    3,
+                    copies(3),
                     "Ancestral Recall",
                     Some("pmps08"),
                     None,
@@ -482,11 +1095,16 @@ mod tests {
             ),
             (
                 "1 Time Walk [30A]",
-                Some(DecklistEntry::new(1, "Time Walk", Some("30a"), None)),
+                Some(DecklistEntry::new(
+                    copies(1),
+                    "Time Walk",
+                    Some("30a"),
+                    None,
+                )),
             ),
             (
                 "5 Counterspell",
-                Some(DecklistEntry::new(5, "Counterspell", None, None)),
+                Some(DecklistEntry::new(copies(5), "Counterspell", None, None)),
             ), // No set/lang
         ];
 
@@ -517,4 +1135,242 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn command_zone_sections() {
+        let decklist = "Commander (1)\n1 Atraxa, Praetors' Voice\n\nCompanion:\n1 Lurrus of the Dream-Den\n\nDeck\n1 Sol Ring\nSideboard [1]\n1 Pithing Needle\n\nTokens:\n2 Soldier";
+        let parsed = parse_decklist_default(decklist);
+        let entries: Vec<DecklistEntry> = parsed.iter().filter_map(|p| p.as_entry()).collect();
+
+        let sections: Vec<(&str, DeckSection)> = entries
+            .iter()
+            .map(|e| (e.name.as_str(), e.section))
+            .collect();
+        assert_eq!(
+            sections,
+            vec![
+                ("Atraxa, Praetors' Voice", DeckSection::Commander),
+                ("Lurrus of the Dream-Den", DeckSection::Companion),
+                ("Sol Ring", DeckSection::Main),
+                ("Pithing Needle", DeckSection::Sideboard),
+                ("Soldier", DeckSection::Tokens),
+            ]
+        );
+        assert!(entries[0].section.is_command_zone());
+        assert!(entries[1].section.is_command_zone());
+        assert!(!entries[2].section.is_command_zone());
+    }
+
+    #[test]
+    fn comment_annotations() {
+        let decklist = "// Burn\n\n// creatures\n4 Goblin Guide\n# burn spells\n4 Lightning Bolt\n\n4 Lava Spike\n// Sideboard\n2 Smash to Smithereens";
+        let mut parsed = parse_decklist_default(decklist);
+        attach_comment_annotations(&mut parsed);
+
+        let annotations: Vec<(String, Option<String>)> = parsed
+            .iter()
+            .filter_map(|p| p.as_entry())
+            .map(|e| (e.name, e.annotation))
+            .collect();
+        assert_eq!(
+            annotations,
+            vec![
+                ("Goblin Guide".to_string(), Some("creatures".to_string())),
+                (
+                    "Lightning Bolt".to_string(),
+                    Some("burn spells".to_string())
+                ),
+                ("Lava Spike".to_string(), Some("burn spells".to_string())),
+                ("Smash to Smithereens".to_string(), None),
+            ]
+        );
+        assert_eq!(comment_annotation("//"), None);
+        assert_eq!(comment_annotation("4 Lava Spike"), None);
+    }
+
+    #[test]
+    fn detect_formats() {
+        let cases = [
+            (
+                "Deck\n4 Lightning Bolt (STA) 42\n\nSideboard\n2 Negate (M20) 69",
+                DecklistFormat::Arena,
+            ),
+            (
+                "4 Lightning Bolt\n20 Mountain\nSB: 2 Smash to Smithereens",
+                DecklistFormat::Mtgo,
+            ),
+            (
+                "1 Sol Ring (C21) 263 *F*\n1 Laboratory Maniac (PLST) ISD-61",
+                DecklistFormat::Moxfield,
+            ),
+            (
+                "4  Beanstalk Giant   \t\t$0.25\n1  Opt \t\t---",
+                DecklistFormat::Mtgdecks,
+            ),
+            (
+                "4 lightning bolt [sta]\n// lands\n20 mountain",
+                DecklistFormat::Plain,
+            ),
+            ("", DecklistFormat::Plain),
+        ];
+        for (decklist, expected) in cases {
+            assert_eq!(
+                DecklistFormat::detect(decklist),
+                expected,
+                "for {:?}",
+                decklist
+            );
+        }
+    }
+
+    #[test]
+    fn mtgo_sideboard_prefix() {
+        let decklist = "4 Lightning Bolt\n20 Mountain\nSB: 2 Smash to Smithereens\nsb:1 Pyroblast";
+        let entries: Vec<(String, u32, DeckSection)> = parse_decklist_default(decklist)
+            .iter()
+            .filter_map(|p| p.as_entry())
+            .map(|e| (e.name, e.multiple.get(), e.section))
+            .collect();
+        assert_eq!(
+            entries,
+            vec![
+                ("Lightning Bolt".to_string(), 4, DeckSection::Main),
+                ("Mountain".to_string(), 20, DeckSection::Main),
+                (
+                    "Smash to Smithereens".to_string(),
+                    2,
+                    DeckSection::Sideboard
+                ),
+                ("Pyroblast".to_string(), 1, DeckSection::Sideboard),
+            ]
+        );
+    }
+
+    #[test]
+    fn moxfield_collector_numbers_and_markers() {
+        let decklist =
+            "1 Laboratory Maniac (PLST) ISD-61 *F*\n1 Opt (PH) 59\n\nSIDEBOARD:\n1 Sol Ring";
+        let parsed = parse_decklist_default(decklist);
+        let entries: Vec<DecklistEntry> = parsed.iter().filter_map(|p| p.as_entry()).collect();
+
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].name, "Laboratory Maniac");
+        assert_eq!(entries[0].set, Some("plst".to_string()));
+        // The code in parentheses is always a set, even if it looks like a language
+        assert_eq!(entries[1].set, Some("ph".to_string()));
+        assert_eq!(entries[1].lang, None);
+        assert_eq!(entries[2].name, "Sol Ring");
+        assert_eq!(entries[2].section, DeckSection::Sideboard);
+    }
+
+    #[test]
+    fn collector_number_ranges() {
+        let decklist = "1 Plains (UNF) 235-240\n2 Island (UNF) 236 *F*\n1 Opt (PLST) XLN-65";
+        let entries: Vec<DecklistEntry> = parse_decklist_default(decklist)
+            .iter()
+            .filter_map(|p| p.as_entry())
+            .flat_map(DecklistEntry::expand_collector_number_range)
+            .collect();
+
+        assert_eq!(entries.len(), 8);
+        let plains: Vec<&str> = entries[..6]
+            .iter()
+            .map(|entry| entry.collector_number.as_deref().unwrap())
+            .collect();
+        assert_eq!(plains, vec!["235", "236", "237", "238", "239", "240"]);
+        assert!(entries[..6].iter().all(|entry| {
+            entry.name == "Plains"
+                && entry.set.as_deref() == Some("unf")
+                && entry.multiple == NonZeroU32::MIN
+                && entry.source_line_number == Some(0)
+        }));
+        assert_eq!(entries[6].multiple, copies(2));
+        assert_eq!(entries[6].collector_number, Some("236".to_string()));
+        assert_eq!(entries[7].collector_number, Some("XLN-65".to_string()));
+
+        // Reversed, huge and setless ranges are not expanded
+        for number in ["240-235", "1-1000"] {
+            let mut entry = DecklistEntry::from_name("Plains");
+            entry.set = Some("unf".to_string());
+            entry.collector_number = Some(number.to_string());
+            assert_eq!(entry.clone().expand_collector_number_range(), vec![entry]);
+        }
+        let mut setless = DecklistEntry::from_name("Plains");
+        setless.collector_number = Some("235-240".to_string());
+        assert_eq!(setless.collector_number_range(), None);
+    }
+
+    #[test]
+    fn foil_markers_and_tags() {
+        let entry = parse_line_default("1 Sol Ring (C21) 263 *F* #ramp #!Staples").unwrap();
+        assert_eq!(entry.name, "Sol Ring");
+        assert_eq!(entry.set, Some("c21".to_string()));
+        assert!(entry.foil);
+        assert_eq!(entry.tags, vec!["ramp", "Staples"]);
+
+        let entry = parse_line_default("1 shatter [mrd] #removal").unwrap();
+        assert_eq!(entry.name, "shatter");
+        assert_eq!(entry.set, Some("mrd".to_string()));
+        assert!(!entry.foil);
+        assert_eq!(entry.tags, vec!["removal"]);
+
+        let entry = parse_line_default("4 Lightning Bolt *E*").unwrap();
+        assert_eq!(entry.name, "Lightning Bolt");
+        assert_eq!(entry.set, None);
+        assert!(entry.foil);
+    }
+
+    #[test]
+    fn archidekt_categories_and_labels() {
+        let minimal = get_minimal_scryfall_languages();
+        let set_codes = std::collections::HashSet::new();
+        let entry = parse_line_with_format(
+            "1x Sol Ring (c21) 263 [Ramp,Commander{top}] ^Have,#37d67a^",
+            DecklistFormat::Arena,
+            &minimal,
+            &set_codes,
+        )
+        .unwrap();
+        assert_eq!(entry.multiple, NonZeroU32::MIN);
+        assert_eq!(entry.name, "Sol Ring");
+        assert_eq!(entry.set, Some("c21".to_string()));
+        assert_eq!(entry.tags, vec!["Ramp", "Commander"]);
+
+        // Without a set and collector number in front, brackets still name the set
+        assert_eq!(
+            split_suffixes("1 shatter [mrd]"),
+            ("1 shatter [mrd]", LineSuffixes::default())
+        );
+    }
+
+    #[test]
+    fn deck_name_header() {
+        let decklist =
+            "\nDeck: Mono Red Burn\n4 Lightning Bolt\n// Sideboard\n2 Smash to Smithereens";
+        assert_eq!(parse_deck_name(decklist), Some("Mono Red Burn".to_string()));
+        let parsed = parse_decklist_default(decklist);
+        assert_eq!(parsed[0].entry, None);
+        assert_eq!(
+            parsed.iter().filter_map(|line| line.entry.as_ref()).count(),
+            2
+        );
+
+        assert_eq!(
+            parse_deck_name("// Izzet Phoenix\n4 Arclight Phoenix"),
+            Some("Izzet Phoenix".to_string())
+        );
+        // Section headers and plain card lists have no name
+        assert_eq!(parse_deck_name("Deck\n4 Lightning Bolt"), None);
+        assert_eq!(parse_deck_name("// Commander\n1 Atraxa"), None);
+        assert_eq!(parse_deck_name("4 Lightning Bolt\n// Burn"), None);
+    }
+
+    #[test]
+    fn deck_file_names() {
+        assert_eq!(
+            deck_file_name("Mono Red: Burn?"),
+            Some("Mono Red_ Burn_.pdf".to_string())
+        );
+        assert_eq!(deck_file_name(" .. "), None);
+    }
 }
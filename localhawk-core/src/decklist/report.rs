@@ -0,0 +1,257 @@
+use super::{DecklistEntry, is_ignored_line};
+use crate::i18n::{Text, tr_with};
+use crate::tokens::TokenSuggestion;
+use std::collections::{BTreeMap, HashSet};
+
+/// Why a decklist line didn't turn into a card
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LineIssue {
+    /// The line isn't a card line the parser understands
+    Skipped { line: String },
+    /// No card has this name; `suggestion` is the closest name there is, if any
+    Unresolved {
+        name: String,
+        suggestion: Option<String>,
+    },
+}
+
+impl LineIssue {
+    /// Inline annotation for the line, e.g.
+    /// "✗ could not resolve 'Lighning Bolt' – did you mean lightning bolt?"
+    pub fn annotation(&self) -> String {
+        match self {
+            LineIssue::Skipped { line } => tr_with(Text::LineSkipped, &[line]),
+            LineIssue::Unresolved {
+                name,
+                suggestion: Some(suggestion),
+            } => tr_with(Text::CouldNotResolveSuggestion, &[name, suggestion]),
+            LineIssue::Unresolved {
+                name,
+                suggestion: None,
+            } => tr_with(Text::CouldNotResolve, &[name]),
+        }
+    }
+}
+
+/// Rough size of a Scryfall border_crop image, for estimating downloads
+pub const ESTIMATED_IMAGE_BYTES: u64 = 150 * 1024;
+
+/// Downloads above this size are confirmed first, for metered connections
+pub const LARGE_DOWNLOAD_BYTES: u64 = 50 * 1024 * 1024;
+
+/// Images a deck still has to download and their estimated size
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DownloadEstimate {
+    pub images: usize,
+    pub bytes: u64,
+}
+
+impl DownloadEstimate {
+    pub fn for_images(images: usize) -> Self {
+        Self {
+            images,
+            bytes: images as u64 * ESTIMATED_IMAGE_BYTES,
+        }
+    }
+
+    /// Whether the user should confirm the download first
+    pub fn is_large(&self) -> bool {
+        self.bytes > LARGE_DOWNLOAD_BYTES
+    }
+
+    /// Estimated size in whole MB, rounded up
+    pub fn megabytes(&self) -> u64 {
+        self.bytes.div_ceil(1024 * 1024)
+    }
+
+    /// e.g. "~45 MB of images will be downloaded (300 images)"
+    pub fn describe(&self) -> String {
+        tr_with(Text::DownloadEstimate, &[&self.megabytes(), &self.images])
+    }
+}
+
+/// PDFs above this many pages are confirmed first, e.g. after a malformed decklist
+pub const LARGE_PRINT_PAGES: usize = 20;
+
+/// Rough ink for printing one card image in full color on an inkjet printer
+pub const ESTIMATED_INK_ML_PER_IMAGE: f64 = 0.04;
+
+/// Pages, sheets and ink a PDF takes to print
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PrintEstimate {
+    pub pages: usize,
+    /// Card images on the pages, back faces included
+    pub images: usize,
+    /// Images of back faces, printed as cards of their own
+    pub back_faces: usize,
+}
+
+impl PrintEstimate {
+    /// Sheets of paper when printing one-sided
+    pub fn sheets(&self) -> usize {
+        self.pages
+    }
+
+    /// Sheets of paper when printing double-sided
+    pub fn duplex_sheets(&self) -> usize {
+        self.pages.div_ceil(2)
+    }
+
+    /// Estimated ink in ml
+    pub fn ink_ml(&self) -> f64 {
+        self.images as f64 * ESTIMATED_INK_ML_PER_IMAGE
+    }
+
+    /// Whether the user should confirm generating the PDF first
+    pub fn is_large(&self) -> bool {
+        self.pages > LARGE_PRINT_PAGES
+    }
+
+    /// e.g. "12 pages (12 sheets, 6 double-sided) with 100 cards, 8 of them back faces,
+    /// ~4.0 ml of ink"
+    pub fn describe(&self) -> String {
+        tr_with(
+            Text::PrintEstimate,
+            &[
+                &self.pages,
+                &self.sheets(),
+                &self.duplex_sheets(),
+                &self.images,
+                &self.back_faces,
+                &format!("{:.1}", self.ink_ml()),
+            ],
+        )
+    }
+}
+
+/// Decklist lines that didn't turn into cards, by line number (0-based, like
+/// [`DecklistEntry::source_line_number`]), and what the deck will download
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ResolutionReport {
+    pub issues: BTreeMap<usize, LineIssue>,
+    /// None until the printings were looked up, see `ProxyGenerator::estimate_downloads`
+    pub download: Option<DownloadEstimate>,
+    /// Tokens the cards create, offered as additions to the decklist; empty until looked up,
+    /// see `ProxyGenerator::suggest_tokens`
+    pub tokens: Vec<TokenSuggestion>,
+}
+
+impl ResolutionReport {
+    pub fn is_empty(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// Record that no card has the name of `entry`
+    pub fn add_unresolved(&mut self, entry: &DecklistEntry, suggestion: Option<String>) {
+        if let Some(line_number) = entry.source_line_number {
+            self.issues.insert(
+                line_number,
+                LineIssue::Unresolved {
+                    name: entry.name.clone(),
+                    suggestion,
+                },
+            );
+        }
+    }
+
+    /// Record the lines of `decklist` that none of `entries` came from, leaving out blank
+    /// lines, comments, section headers and the deck name
+    pub fn add_skipped_lines(&mut self, decklist: &str, entries: &[DecklistEntry]) {
+        let parsed: HashSet<usize> = entries
+            .iter()
+            .filter(|entry| !entry.name.is_empty())
+            .filter_map(|entry| entry.source_line_number)
+            .collect();
+        for (line_number, line) in decklist.lines().enumerate() {
+            if !parsed.contains(&line_number) && !is_ignored_line(line) {
+                self.issues.insert(
+                    line_number,
+                    LineIssue::Skipped {
+                        line: line.trim().to_string(),
+                    },
+                );
+            }
+        }
+    }
+
+    /// Annotation for a line of the decklist, None if the line is fine
+    pub fn annotation(&self, line_number: usize) -> Option<String> {
+        self.issues.get(&line_number).map(LineIssue::annotation)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decklist::parse_decklist;
+    use crate::scryfall::models::get_minimal_scryfall_languages;
+
+    #[test]
+    fn test_report_skipped_and_unresolved_lines() {
+        let decklist =
+            "Deck: Burn\n4 Lightning Bolt\n\n// Creatures\n4 [sta]\nSideboard\n2 Lighning Blot";
+        let entries: Vec<DecklistEntry> =
+            parse_decklist(decklist, &get_minimal_scryfall_languages(), &HashSet::new())
+                .iter()
+                .filter_map(|line| line.as_entry())
+                .collect();
+
+        let mut report = ResolutionReport::default();
+        report.add_unresolved(&entries[2], Some("lightning bolt".to_string()));
+        report.add_skipped_lines(decklist, &entries);
+
+        assert_eq!(
+            report.issues.keys().copied().collect::<Vec<_>>(),
+            vec![4, 6]
+        );
+        assert_eq!(
+            report.issues[&4],
+            LineIssue::Skipped {
+                line: "4 [sta]".to_string()
+            }
+        );
+        assert_eq!(
+            report.annotation(6).unwrap(),
+            "✗ could not resolve 'Lighning Blot' – did you mean lightning bolt?"
+        );
+        assert_eq!(report.annotation(1), None);
+    }
+
+    #[test]
+    fn test_download_estimate() {
+        let estimate = DownloadEstimate::for_images(300);
+        assert_eq!(estimate.megabytes(), 44);
+        assert!(!estimate.is_large());
+        assert_eq!(
+            estimate.describe(),
+            "~44 MB of images will be downloaded (300 images)"
+        );
+
+        assert!(DownloadEstimate::for_images(400).is_large());
+        assert_eq!(DownloadEstimate::for_images(0).megabytes(), 0);
+    }
+
+    #[test]
+    fn test_print_estimate() {
+        let estimate = PrintEstimate {
+            pages: 5,
+            images: 41,
+            back_faces: 3,
+        };
+        assert_eq!(estimate.sheets(), 5);
+        assert_eq!(estimate.duplex_sheets(), 3);
+        assert!(!estimate.is_large());
+        assert_eq!(
+            estimate.describe(),
+            "5 pages (5 sheets, 3 double-sided) with 41 cards, 3 of them back faces, \
+             ~1.6 ml of ink"
+        );
+
+        let malformed = PrintEstimate {
+            pages: 40,
+            images: 360,
+            back_faces: 0,
+        };
+        assert!(malformed.is_large());
+    }
+}
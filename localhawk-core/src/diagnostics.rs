@@ -0,0 +1,362 @@
+//! Self-diagnostics to attach to bug reports
+//!
+//! Most problems users report come from their machine rather than the code: a cache directory
+//! that can't be written, a cache file that no longer parses, card names that were never
+//! downloaded or a Scryfall that can't be reached. [`run_diagnostics`] checks each of them and
+//! returns a report the frontends print (CLI `doctor`), show (GUI) or pass on as JSON (FFI).
+
+use crate::cache::{FileStorage, SearchJsonStorage, StorageStrategy};
+use crate::globals::{
+    get_cache_directory_path, get_card_names_cache_path, get_freshness_policy,
+    get_log_directory_path, get_query_cache_path, get_scryfall_client, get_search_cache_path,
+    get_set_codes_cache_path, get_settings_path,
+};
+use crate::settings::Settings;
+use crate::{CardNameCache, CardNameLookup, SetCodesCache};
+use serde::Serialize;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::time::Instant;
+use time::OffsetDateTime;
+
+/// A small API call whose answer shows Scryfall is up
+const SCRYFALL_HEALTH_URI: &str = "https://api.scryfall.com/sets/lea";
+const PROBE_FILE_NAME: &str = ".localhawk-write-test";
+
+/// Outcome of a single check
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Ok,
+    /// Works, but not as well as it could (e.g. stale or missing data that is fetched later)
+    Warning,
+    Failed,
+}
+
+impl fmt::Display for CheckStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            CheckStatus::Ok => "OK",
+            CheckStatus::Warning => "WARN",
+            CheckStatus::Failed => "FAIL",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DiagnosticCheck {
+    pub name: &'static str,
+    pub status: CheckStatus,
+    pub details: String,
+}
+
+impl DiagnosticCheck {
+    fn new(name: &'static str, status: CheckStatus, details: impl Into<String>) -> Self {
+        DiagnosticCheck {
+            name,
+            status,
+            details: details.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DiagnosticsReport {
+    pub version: &'static str,
+    pub platform: String,
+    pub cache_directory: String,
+    pub checks: Vec<DiagnosticCheck>,
+}
+
+impl DiagnosticsReport {
+    /// Whether any check failed outright
+    pub fn has_failures(&self) -> bool {
+        self.checks
+            .iter()
+            .any(|check| check.status == CheckStatus::Failed)
+    }
+}
+
+impl fmt::Display for DiagnosticsReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "LocalHawk {} on {}", self.version, self.platform)?;
+        writeln!(f, "Cache directory: {}", self.cache_directory)?;
+        for check in &self.checks {
+            writeln!(f, "[{:<4}] {}: {}", check.status, check.name, check.details)?;
+        }
+        Ok(())
+    }
+}
+
+/// Check the cache directories and files, the card name index and the connection to Scryfall
+///
+/// Only reads the caches (apart from a probe file to test writing), so it is safe to run
+/// while another process uses them. Must be called from within a tokio runtime.
+pub async fn run_diagnostics() -> DiagnosticsReport {
+    let cache_directory = get_cache_directory_path();
+    let mut checks = vec![
+        check_directory_writable("Cache directory", Path::new(&cache_directory)),
+        check_directory_writable("Log directory", Path::new(&get_log_directory_path())),
+        check_settings(Path::new(&get_settings_path())),
+        check_image_cache(Path::new(&cache_directory)),
+        check_search_cache("Search results cache", Path::new(&get_search_cache_path())),
+        check_search_cache("Query results cache", Path::new(&get_query_cache_path())),
+    ];
+    // Building the index takes seconds, too long to block the async runtime
+    match tokio::task::spawn_blocking(check_card_names).await {
+        Ok(card_name_checks) => checks.extend(card_name_checks),
+        Err(e) => checks.push(DiagnosticCheck::new(
+            "Card name index",
+            CheckStatus::Failed,
+            e.to_string(),
+        )),
+    }
+    checks.push(check_set_codes());
+    checks.push(check_scryfall().await);
+
+    DiagnosticsReport {
+        version: env!("CARGO_PKG_VERSION"),
+        platform: format!("{}/{}", std::env::consts::OS, std::env::consts::ARCH),
+        cache_directory,
+        checks,
+    }
+}
+
+fn check_directory_writable(name: &'static str, directory: &Path) -> DiagnosticCheck {
+    let probe = directory.join(PROBE_FILE_NAME);
+    let written = fs::create_dir_all(directory)
+        .and_then(|()| fs::write(&probe, b"localhawk"))
+        .and_then(|()| fs::remove_file(&probe));
+    match written {
+        Ok(()) => DiagnosticCheck::new(
+            name,
+            CheckStatus::Ok,
+            format!("{} is writable", directory.display()),
+        ),
+        Err(e) => DiagnosticCheck::new(
+            name,
+            CheckStatus::Failed,
+            format!("{} is not writable: {}", directory.display(), e),
+        ),
+    }
+}
+
+fn check_settings(path: &Path) -> DiagnosticCheck {
+    const NAME: &str = "Settings";
+    if !path.exists() {
+        return DiagnosticCheck::new(NAME, CheckStatus::Ok, "none saved, using defaults");
+    }
+    match Settings::try_load_from(path) {
+        Ok(_) => DiagnosticCheck::new(NAME, CheckStatus::Ok, format!("{} loads", path.display())),
+        Err(e) => DiagnosticCheck::new(
+            NAME,
+            CheckStatus::Failed,
+            format!("{}, using defaults instead", e),
+        ),
+    }
+}
+
+fn check_image_cache(cache_directory: &Path) -> DiagnosticCheck {
+    const NAME: &str = "Image cache";
+    // Only the metadata: the images themselves may take a gigabyte
+    let counts = FileStorage::new(cache_directory.to_path_buf(), "jpg".to_string(), 0)
+        .and_then(|storage| storage.check_metadata());
+    match counts {
+        Ok((present, 0)) => {
+            DiagnosticCheck::new(NAME, CheckStatus::Ok, format!("{} images", present))
+        }
+        Ok((present, missing)) => DiagnosticCheck::new(
+            NAME,
+            CheckStatus::Warning,
+            format!("{} images, {} image files missing", present, missing),
+        ),
+        Err(e) => DiagnosticCheck::new(
+            NAME,
+            CheckStatus::Failed,
+            format!("{}, the cache starts empty", e),
+        ),
+    }
+}
+
+fn check_search_cache(name: &'static str, cache_file: &Path) -> DiagnosticCheck {
+    match SearchJsonStorage::new(cache_file.to_path_buf(), 0).and_then(|storage| storage.load()) {
+        Ok(entries) => DiagnosticCheck::new(
+            name,
+            CheckStatus::Ok,
+            format!("{} cached searches", entries.len()),
+        ),
+        Err(e) => DiagnosticCheck::new(
+            name,
+            CheckStatus::Failed,
+            format!("{}, the cache starts empty", e),
+        ),
+    }
+}
+
+/// The card names cache and the fuzzy index built from it
+fn check_card_names() -> [DiagnosticCheck; 2] {
+    const NAME: &str = "Card names";
+    const INDEX_NAME: &str = "Card name index";
+    let cached = CardNameCache::new()
+        .ok()
+        .and_then(|cache| cache.load_cached_card_names());
+    let Some((card_names, cached_at)) = cached else {
+        return [
+            missing_cache_check(NAME, Path::new(&get_card_names_cache_path())),
+            DiagnosticCheck::new(INDEX_NAME, CheckStatus::Warning, "no card names to index"),
+        ];
+    };
+
+    let names_check =
+        cached_data_check(NAME, format!("{} names", card_names.names.len()), cached_at);
+    let start = Instant::now();
+    let lookup = CardNameLookup::from_card_names(&card_names.names);
+    let elapsed_ms = start.elapsed().as_millis();
+    // A name straight from the cache must be found again
+    let unfound_name = card_names
+        .names
+        .first()
+        .filter(|name| lookup.find(&name.to_uppercase()).is_none());
+    let index_check = match unfound_name {
+        None => DiagnosticCheck::new(
+            INDEX_NAME,
+            CheckStatus::Ok,
+            format!("built in {} ms", elapsed_ms),
+        ),
+        Some(name) => DiagnosticCheck::new(
+            INDEX_NAME,
+            CheckStatus::Failed,
+            format!("'{}' is not found in the index", name),
+        ),
+    };
+    [names_check, index_check]
+}
+
+fn check_set_codes() -> DiagnosticCheck {
+    const NAME: &str = "Set codes";
+    let cached = SetCodesCache::new()
+        .ok()
+        .and_then(|cache| cache.load_cached_set_codes());
+    match cached {
+        Some((set_codes, cached_at)) => {
+            cached_data_check(NAME, format!("{} sets", set_codes.codes.len()), cached_at)
+        }
+        None => missing_cache_check(NAME, Path::new(&get_set_codes_cache_path())),
+    }
+}
+
+/// Cached card names or set codes, a warning once they are older than the freshness policy
+fn cached_data_check(
+    name: &'static str,
+    summary: String,
+    cached_at: OffsetDateTime,
+) -> DiagnosticCheck {
+    let status = if get_freshness_policy().is_stale(cached_at) {
+        CheckStatus::Warning
+    } else {
+        CheckStatus::Ok
+    };
+    DiagnosticCheck::new(
+        name,
+        status,
+        format!("{}, fetched {}", summary, cached_at.date()),
+    )
+}
+
+/// Card names or set codes that couldn't be loaded: fetched on next start if there is no file,
+/// broken if there is one
+fn missing_cache_check(name: &'static str, cache_file: &Path) -> DiagnosticCheck {
+    if cache_file.exists() {
+        DiagnosticCheck::new(
+            name,
+            CheckStatus::Failed,
+            format!("{} can't be read", cache_file.display()),
+        )
+    } else {
+        DiagnosticCheck::new(name, CheckStatus::Warning, "not downloaded yet")
+    }
+}
+
+async fn check_scryfall() -> DiagnosticCheck {
+    const NAME: &str = "Scryfall";
+    let start = Instant::now();
+    match get_scryfall_client().call(SCRYFALL_HEALTH_URI).await {
+        Ok(response) if response.status().is_success() => DiagnosticCheck::new(
+            NAME,
+            CheckStatus::Ok,
+            format!("reachable in {} ms", start.elapsed().as_millis()),
+        ),
+        Ok(response) => DiagnosticCheck::new(
+            NAME,
+            CheckStatus::Failed,
+            format!("answered {}", response.status()),
+        ),
+        Err(e) => DiagnosticCheck::new(NAME, CheckStatus::Failed, e.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    #[test]
+    fn test_cache_file_checks() {
+        let dir = env::temp_dir().join(format!("localhawk-doctor-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+
+        let check = check_directory_writable("Cache directory", &dir);
+        assert_eq!(check.status, CheckStatus::Ok);
+        assert!(!dir.join(PROBE_FILE_NAME).exists());
+
+        // A file where the directory should be
+        let file = dir.join("file");
+        fs::write(&file, b"").unwrap();
+        let check = check_directory_writable("Cache directory", &file.join("cache"));
+        assert_eq!(check.status, CheckStatus::Failed);
+
+        let cache_file = dir.join("search_results_cache.json");
+        assert_eq!(
+            check_search_cache("Search results cache", &cache_file).status,
+            CheckStatus::Ok
+        );
+        fs::write(&cache_file, b"{ not json").unwrap();
+        assert_eq!(
+            check_search_cache("Search results cache", &cache_file).status,
+            CheckStatus::Failed
+        );
+
+        assert_eq!(
+            missing_cache_check("Card names", &dir.join("card_names.json")).status,
+            CheckStatus::Warning
+        );
+        assert_eq!(
+            missing_cache_check("Search results cache", &cache_file).status,
+            CheckStatus::Failed
+        );
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_report_display() {
+        let report = DiagnosticsReport {
+            version: "1.2.3",
+            platform: "linux/x86_64".to_string(),
+            cache_directory: "/tmp/localhawk".to_string(),
+            checks: vec![
+                DiagnosticCheck::new("Settings", CheckStatus::Ok, "none saved, using defaults"),
+                DiagnosticCheck::new("Scryfall", CheckStatus::Failed, "answered 503"),
+            ],
+        };
+        assert!(report.has_failures());
+        assert_eq!(
+            report.to_string(),
+            "LocalHawk 1.2.3 on linux/x86_64\n\
+             Cache directory: /tmp/localhawk\n\
+             [OK  ] Settings: none saved, using defaults\n\
+             [FAIL] Scryfall: answered 503\n"
+        );
+    }
+}
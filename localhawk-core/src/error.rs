@@ -1,3 +1,4 @@
+use crate::i18n::{Text, tr_with};
 use std::fmt;
 
 #[derive(Debug)]
@@ -11,21 +12,32 @@ pub enum ProxyError {
     Cache(String),
     InvalidCard(String),
     Io(std::io::Error),
+    /// Scryfall answered with a maintenance or overload page instead of its API
+    ScryfallUnavailable(String),
+}
+
+impl ProxyError {
+    /// Whether this error means Scryfall is down, so cached data is the best there is
+    pub fn is_scryfall_unavailable(&self) -> bool {
+        matches!(self, ProxyError::ScryfallUnavailable(_))
+    }
 }
 
 impl fmt::Display for ProxyError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            ProxyError::Network(e) => write!(f, "Network error: {}", e),
+        let message = match self {
+            ProxyError::Network(e) => tr_with(Text::NetworkError, &[e]),
             #[cfg(feature = "ios")]
-            ProxyError::NetworkUreq(e) => write!(f, "Network error: {}", e),
-            ProxyError::Json(e) => write!(f, "JSON parsing error: {}", e),
-            ProxyError::Serialization(e) => write!(f, "Serialization error: {}", e),
-            ProxyError::Pdf(e) => write!(f, "PDF generation error: {}", e),
-            ProxyError::Cache(e) => write!(f, "Cache error: {}", e),
-            ProxyError::InvalidCard(e) => write!(f, "Invalid card: {}", e),
-            ProxyError::Io(e) => write!(f, "IO error: {}", e),
-        }
+            ProxyError::NetworkUreq(e) => tr_with(Text::NetworkError, &[e]),
+            ProxyError::Json(e) => tr_with(Text::JsonError, &[e]),
+            ProxyError::Serialization(e) => tr_with(Text::SerializationError, &[e]),
+            ProxyError::Pdf(e) => tr_with(Text::PdfError, &[e]),
+            ProxyError::Cache(e) => tr_with(Text::CacheError, &[e]),
+            ProxyError::InvalidCard(e) => tr_with(Text::InvalidCardError, &[e]),
+            ProxyError::Io(e) => tr_with(Text::IoError, &[e]),
+            ProxyError::ScryfallUnavailable(e) => tr_with(Text::ScryfallUnavailableError, &[e]),
+        };
+        f.write_str(&message)
     }
 }
 
@@ -1,16 +1,25 @@
 use std::ffi::{CStr, CString};
+use std::num::NonZeroU32;
 use std::os::raw::{c_char, c_int, c_void};
 use std::ptr;
-use std::collections::VecDeque;
-use std::sync::{Mutex, LazyLock};
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, LazyLock, Mutex};
+
+use tokio::sync::broadcast::error::RecvError;
 
 use crate::{
-    DoubleFaceMode, PdfOptions,
-    get_card_names_cache_path, get_card_names_cache_size,
+    BackFaceFallback, CacheEvent, CacheRefreshStatus, DoubleFaceMode, FreshnessPolicy, PdfOptions,
+    get_cache_refresh_status, get_card_names_cache_path, get_card_names_cache_size,
     get_image_cache_info, get_image_cache_path, get_search_cache_path,
-    get_search_results_cache_info, 
-    ios_api::ProxyGenerator,
-    globals::initialize_caches_sync,
+    get_search_results_cache_info, set_back_face_fallback, set_freshness_policy,
+    set_preferred_language,
+    LoadingPhase,
+    ios_api::{CachedPdfOutcome, ProxyGenerator, SyncBackgroundLoadProgress},
+    globals::{
+        force_update_card_lookup_sync, force_update_set_codes_sync, get_cache_dates_sync,
+        get_freshness_policy, initialize_caches_sync,
+    },
 };
 
 /// iOS-specific sync FFI implementation
@@ -32,9 +41,11 @@ static GLOBAL_IMAGE_DISPATCH_SOURCE: LazyLock<Mutex<Option<(ThreadSafePtr, Dispa
 /// Image cache change notification for rich payload delivery
 #[derive(Clone, Debug)]
 struct ImageCacheNotification {
-    change_type: u8, // 1=ImageCached, 2=ImageRemoved
-    image_url: String,
+    change_type: u8, // 1=ImageCached, 2=ImageRemoved, 3=LoadFinished, 4=LoadAborted
+    image_url: String, // Image URL, or the name of the background load for types 3 and 4
     timestamp: u64,
+    completed: usize, // Images loaded by the background load (types 3 and 4)
+    total: usize,     // Images the background load set out to load (types 3 and 4)
 }
 
 /// Global queue of image cache change notifications
@@ -63,14 +74,17 @@ pub struct CDeclistEntry {
     pub language: *mut c_char,
     pub face_mode: i32,
     pub source_line_number: i32,
+    pub scryfall_id: *mut c_char, // Pinned printing (NULL to select by set and language)
 }
 
 /// C-compatible image cache change notification structure
 #[repr(C)]
 pub struct CImageCacheNotification {
-    pub change_type: u8, // 1=ImageCached, 2=ImageRemoved
+    pub change_type: u8, // 1=ImageCached, 2=ImageRemoved, 3=LoadFinished, 4=LoadAborted
     pub image_url: *mut c_char,
     pub timestamp: u64,
+    pub completed: usize,
+    pub total: usize,
 }
 
 /// C-compatible array of image cache change notifications
@@ -80,6 +94,20 @@ pub struct CImageCacheChangeArray {
     pub count: usize,
 }
 
+/// C-compatible encoded page image (PNG)
+#[repr(C)]
+pub struct CPageImage {
+    pub data: *mut u8,
+    pub size: usize,
+}
+
+/// C-compatible array of rendered page images
+#[repr(C)]
+pub struct CPageImageArray {
+    pub pages: *mut CPageImage,
+    pub count: usize,
+}
+
 /// C-compatible DoubleFaceMode enum
 #[repr(C)]
 pub enum CDoubleFaceMode {
@@ -110,6 +138,7 @@ pub struct CResolvedCard {
     pub face_mode: CDoubleFaceMode,
     pub back_type: u32, // BackSideType: 0=NONE, 1=DFC, 2=MELD
     pub back_name: *mut c_char, // null if no back side
+    pub scryfall_id: *mut c_char, // null for cards cached without an id
 }
 
 /// C-compatible array of resolved cards
@@ -119,6 +148,95 @@ pub struct CResolvedCardArray {
     pub count: usize,
 }
 
+/// Convert C decklist entries to Rust entries
+///
+/// # Safety
+/// `entries` must point to `count` valid entries whose strings are null or null-terminated
+unsafe fn convert_entries_from_c_format(
+    entries: *const CDeclistEntry,
+    count: usize,
+) -> Result<Vec<crate::decklist::DecklistEntry>, FFIError> {
+    let mut rust_entries = Vec::with_capacity(count);
+    for i in 0..count {
+        let c_entry = unsafe { &*entries.add(i) };
+
+        // Convert C strings to Rust strings
+        let name = if c_entry.name.is_null() {
+            return Err(FFIError::InvalidInput);
+        } else {
+            match unsafe { CStr::from_ptr(c_entry.name) }.to_str() {
+                Ok(s) => s.to_string(),
+                Err(_) => return Err(FFIError::InvalidInput),
+            }
+        };
+
+        let set = if c_entry.set.is_null() {
+            None
+        } else {
+            match unsafe { CStr::from_ptr(c_entry.set) }.to_str() {
+                Ok(s) => Some(s.to_string()),
+                Err(_) => return Err(FFIError::InvalidInput),
+            }
+        };
+
+        let lang = if c_entry.language.is_null() {
+            None
+        } else {
+            match unsafe { CStr::from_ptr(c_entry.language) }.to_str() {
+                Ok(s) => Some(s.to_string()),
+                Err(_) => return Err(FFIError::InvalidInput),
+            }
+        };
+
+        let face_mode = match c_entry.face_mode {
+            0 => DoubleFaceMode::FrontOnly,
+            1 => DoubleFaceMode::BackOnly,
+            2 => DoubleFaceMode::BothSides,
+            _ => DoubleFaceMode::BothSides,
+        };
+
+        let source_line_number = if c_entry.source_line_number >= 0 {
+            Some(c_entry.source_line_number as usize)
+        } else {
+            None
+        };
+
+        let scryfall_id = if c_entry.scryfall_id.is_null() {
+            None
+        } else {
+            match unsafe { CStr::from_ptr(c_entry.scryfall_id) }.to_str() {
+                Ok(s) => Some(s.to_string()),
+                Err(_) => return Err(FFIError::InvalidInput),
+            }
+        };
+
+        rust_entries.push(crate::DecklistEntry {
+            multiple: c_entry_quantity(c_entry.multiple)?,
+            name,
+            set,
+            lang,
+            face_mode,
+            source_line_number,
+            section: crate::DeckSection::Main,
+            foil: false,
+            tags: Vec::new(),
+            scryfall_id,
+            collector_number: None,
+            annotation: None,
+        });
+    }
+
+    Ok(rust_entries)
+}
+
+/// Copies of a C decklist entry; zero or negative counts are rejected
+fn c_entry_quantity(multiple: i32) -> Result<NonZeroU32, FFIError> {
+    u32::try_from(multiple)
+        .ok()
+        .and_then(NonZeroU32::new)
+        .ok_or(FFIError::InvalidInput)
+}
+
 /// Helper function to convert DecklistEntry vector to C-compatible format
 fn convert_entries_to_c_format(entries: &[crate::decklist::DecklistEntry]) -> Result<(*mut CDeclistEntry, usize), FFIError> {
     let count = entries.len();
@@ -136,6 +254,9 @@ fn convert_entries_to_c_format(entries: &[crate::decklist::DecklistEntry]) -> Re
         let language_cstr = entry.lang.as_ref()
             .map(|s| CString::new(s.clone()).map_err(|_| FFIError::InvalidInput))
             .transpose()?;
+        let scryfall_id_cstr = entry.scryfall_id.as_ref()
+            .map(|s| CString::new(s.clone()).map_err(|_| FFIError::InvalidInput))
+            .transpose()?;
 
         let face_mode_int = match entry.face_mode {
             DoubleFaceMode::FrontOnly => 0,
@@ -144,12 +265,13 @@ fn convert_entries_to_c_format(entries: &[crate::decklist::DecklistEntry]) -> Re
         };
 
         let c_entry = CDeclistEntry {
-            multiple: entry.multiple as i32,
+            multiple: entry.multiple.get() as i32,
             name: name_cstr.into_raw(),
             set: set_cstr.map_or(ptr::null_mut(), |s| s.into_raw()),
             language: language_cstr.map_or(ptr::null_mut(), |s| s.into_raw()),
             face_mode: face_mode_int,
             source_line_number: -1, // iOS doesn't track source line numbers
+            scryfall_id: scryfall_id_cstr.map_or(ptr::null_mut(), |s| s.into_raw()),
         };
 
         c_entries.push(c_entry);
@@ -167,6 +289,9 @@ fn card_to_c_resolved_card(card: &crate::scryfall::models::Card, quantity: u32,
     let set_cstr = CString::new(card.set.clone()).map_err(|_| FFIError::InvalidInput)?;
     let lang_cstr = CString::new(card.language.clone()).map_err(|_| FFIError::InvalidInput)?;
     let border_crop_cstr = CString::new(card.border_crop.clone()).map_err(|_| FFIError::InvalidInput)?;
+    let scryfall_id_cstr = card.scryfall_id.as_ref()
+        .map(|id| CString::new(id.clone()).map_err(|_| FFIError::InvalidInput))
+        .transpose()?;
     
     let (back_border_crop_ptr, back_type, back_name_ptr) = if let Some(back_side) = &card.back_side {
         match back_side {
@@ -195,6 +320,7 @@ fn card_to_c_resolved_card(card: &crate::scryfall::models::Card, quantity: u32,
         face_mode: face_mode.into(),
         back_type,
         back_name: back_name_ptr,
+        scryfall_id: scryfall_id_cstr.map_or(ptr::null_mut(), |s| s.into_raw()),
     })
 }
 
@@ -227,6 +353,9 @@ pub extern "C" fn localhawk_free_resolved_cards(resolved_cards: *mut CResolvedCa
             if !card.back_name.is_null() {
                 drop(CString::from_raw(card.back_name));
             }
+            if !card.scryfall_id.is_null() {
+                drop(CString::from_raw(card.scryfall_id));
+            }
         }
         // Free the cards array itself
         drop(Vec::from_raw_parts(resolved_cards, count, count));
@@ -237,34 +366,92 @@ pub extern "C" fn localhawk_free_resolved_cards(resolved_cards: *mut CResolvedCa
 /// Must be called before any other FFI functions
 #[unsafe(no_mangle)]
 pub extern "C" fn localhawk_initialize() -> c_int {
-    // Initialize logging with appropriate level for build type
-    #[cfg(debug_assertions)]
-    {
-        if let Err(_) = env_logger::Builder::from_default_env()
-            .filter_level(log::LevelFilter::Debug)
-            .try_init() {
-            // Already initialized, that's fine
+    localhawk_initialize_with_options(ptr::null())
+}
+
+/// Initialize the proxy generator caches in `cache_dir` instead of the platform cache directory
+/// NULL uses the cache directory of the settings, if any
+#[unsafe(no_mangle)]
+pub extern "C" fn localhawk_initialize_with_cache_dir(cache_dir: *const c_char) -> c_int {
+    let options = CInitOptions {
+        cache_dir,
+        ..CInitOptions::default()
+    };
+    localhawk_initialize_with_options(&options)
+}
+
+/// Don't refresh stale card names and set codes automatically, e.g. in Low Data Mode
+pub const NETWORK_POLICY_CONSTRAINED: c_int = 1;
+
+/// C-compatible initialization options (matches LocalHawkInitOptions in the header)
+#[repr(C)]
+pub struct CInitOptions {
+    pub cache_dir: *const c_char,  // NULL uses the settings, else the platform cache directory
+    pub max_image_cache_mb: c_int, // 0 keeps the default size
+    pub network_policy: c_int,     // 0 = normal, 1 = constrained (no automatic refresh)
+}
+
+impl Default for CInitOptions {
+    fn default() -> Self {
+        CInitOptions {
+            cache_dir: ptr::null(),
+            max_image_cache_mb: 0,
+            network_policy: 0,
         }
     }
+}
 
-    #[cfg(not(debug_assertions))]
+/// Initialize the proxy generator caches with `options`, e.g. in an App Group container
+/// NULL options initialize like localhawk_initialize
+#[unsafe(no_mangle)]
+pub extern "C" fn localhawk_initialize_with_options(options: *const CInitOptions) -> c_int {
+    let default_options = CInitOptions::default();
+    let options = if options.is_null() {
+        &default_options
+    } else {
+        unsafe { &*options }
+    };
+    if options.max_image_cache_mb < 0
+        || !(0..=NETWORK_POLICY_CONSTRAINED).contains(&options.network_policy)
     {
-        if let Err(_) = env_logger::Builder::from_default_env()
-            .filter_level(log::LevelFilter::Warn)  // Only warnings and errors in release
-            .try_init() {
-            // Already initialized, that's fine
+        return FFIError::InvalidInput as c_int;
+    }
+
+    let settings = crate::get_settings();
+    let cache_dir = if options.cache_dir.is_null() {
+        settings.cache_directory
+    } else {
+        match unsafe { CStr::from_ptr(options.cache_dir) }.to_str() {
+            Ok(s) => Some(std::path::PathBuf::from(s)),
+            Err(_) => return FFIError::InvalidInput as c_int,
         }
+    };
+    // Before logging starts, since the log files live in the cache directory
+    crate::set_cache_directory(cache_dir);
+    start_cache_event_forwarding();
+    crate::set_image_cache_max_size_mb(Some(options.max_image_cache_mb as u64).filter(|&mb| mb > 0));
+    if options.network_policy == NETWORK_POLICY_CONSTRAINED {
+        set_freshness_policy(FreshnessPolicy {
+            auto_refresh: false,
+            ..get_freshness_policy()
+        });
     }
+
+    // Initialize logging with appropriate level for build type (only warnings and errors in release)
+    let default_filter = if cfg!(debug_assertions) { "debug" } else { "warn" };
+    crate::logging::init_logging(default_filter, settings.log_to_file);
+    // Image downloads keep to the bandwidth cap of the settings, as on the desktop
+    crate::set_download_rate_limit(settings.max_download_rate_kb);
     
     // iOS sync version - use shared initialization logic
     // May block on network for essential data, but ensures app is ready to work
     match initialize_caches_sync() {
         Ok(_) => {
-            log::info!("iOS cache initialization successful");
+            tracing::info!("iOS cache initialization successful");
             FFIError::Success as c_int
         },
         Err(e) => {
-            log::error!("iOS cache initialization failed: {:?}", e);
+            tracing::error!("iOS cache initialization failed: {:?}", e);
             FFIError::InitializationFailed as c_int
         }
     }
@@ -357,12 +544,12 @@ pub extern "C" fn localhawk_generate_pdf_from_decklist(
         PdfOptions::default(),
         |current, total| {
             // Simple progress callback
-            log::debug!("PDF generation progress: {}/{}", current, total);
+            tracing::debug!("PDF generation progress: {}/{}", current, total);
         },
     ) {
         Ok(data) => data,
         Err(e) => {
-            log::error!("PDF generation failed: {:?}", e);
+            tracing::error!("PDF generation failed: {:?}", e);
             return match e {
                 crate::ProxyError::InvalidCard(_) => FFIError::ParseFailed as c_int,
                 _ => FFIError::PdfGenerationFailed as c_int,
@@ -402,6 +589,110 @@ pub extern "C" fn localhawk_free_buffer(buffer: *mut u8) {
     }
 }
 
+/// Result of localhawk_share_generate_pdf, freed with localhawk_free_share_result
+#[repr(C)]
+pub struct CShareResult {
+    /// 0 if the PDF was written, else why the full app is needed (see NeedsFullApp)
+    pub needs_full_app: c_int,
+    /// Path of the written PDF, NULL if the full app is needed
+    pub pdf_path: *mut c_char,
+    /// Newline-separated names of the cards the full app has to fetch, or NULL
+    pub missing_cards: *mut c_char,
+}
+
+/// Print a decklist from the caches alone within `time_budget_ms`, for the share extension
+/// NULL cache_dir uses the cache directory from the settings
+#[unsafe(no_mangle)]
+pub extern "C" fn localhawk_share_generate_pdf(
+    decklist_cstr: *const c_char,
+    cache_dir: *const c_char,
+    output_dir: *const c_char,
+    time_budget_ms: c_int,
+    result_out: *mut CShareResult,
+) -> c_int {
+    if decklist_cstr.is_null() || output_dir.is_null() || result_out.is_null() {
+        return FFIError::NullPointer as c_int;
+    }
+    if time_budget_ms <= 0 {
+        return FFIError::InvalidInput as c_int;
+    }
+    let (decklist, output_dir) = match (
+        unsafe { CStr::from_ptr(decklist_cstr) }.to_str(),
+        unsafe { CStr::from_ptr(output_dir) }.to_str(),
+    ) {
+        (Ok(decklist), Ok(output_dir)) => (decklist, output_dir),
+        _ => return FFIError::InvalidInput as c_int,
+    };
+    let cache_dir = if cache_dir.is_null() {
+        crate::get_settings().cache_directory
+    } else {
+        match unsafe { CStr::from_ptr(cache_dir) }.to_str() {
+            Ok(s) => Some(std::path::PathBuf::from(s)),
+            Err(_) => return FFIError::InvalidInput as c_int,
+        }
+    };
+    crate::set_cache_directory(cache_dir);
+
+    let outcome = ProxyGenerator::generate_pdf_from_cache_sync(
+        decklist,
+        std::path::Path::new(output_dir),
+        std::time::Duration::from_millis(time_budget_ms as u64),
+    );
+    let result = match outcome {
+        Ok(CachedPdfOutcome::Written(path)) => CShareResult {
+            needs_full_app: 0,
+            pdf_path: match CString::new(path.to_string_lossy().into_owned()) {
+                Ok(path) => path.into_raw(),
+                Err(_) => return FFIError::PdfGenerationFailed as c_int,
+            },
+            missing_cards: ptr::null_mut(),
+        },
+        Ok(CachedPdfOutcome::NeedsFullApp { reason, missing }) => CShareResult {
+            needs_full_app: reason as c_int,
+            pdf_path: ptr::null_mut(),
+            missing_cards: if missing.is_empty() {
+                ptr::null_mut()
+            } else {
+                CString::new(missing.join("\n"))
+                    .map(CString::into_raw)
+                    .unwrap_or(ptr::null_mut())
+            },
+        },
+        Err(crate::ProxyError::InvalidCard(e)) => {
+            tracing::warn!("Share extension could not parse the decklist: {}", e);
+            return FFIError::ParseFailed as c_int;
+        }
+        Err(e) => {
+            tracing::error!("Share extension PDF generation failed: {:?}", e);
+            return FFIError::PdfGenerationFailed as c_int;
+        }
+    };
+
+    unsafe {
+        *result_out = result;
+    }
+    FFIError::Success as c_int
+}
+
+/// Free the strings of a result from localhawk_share_generate_pdf
+#[unsafe(no_mangle)]
+pub extern "C" fn localhawk_free_share_result(result: *mut CShareResult) {
+    if result.is_null() {
+        return;
+    }
+    unsafe {
+        let result = &mut *result;
+        if !result.pdf_path.is_null() {
+            drop(CString::from_raw(result.pdf_path));
+            result.pdf_path = ptr::null_mut();
+        }
+        if !result.missing_cards.is_null() {
+            drop(CString::from_raw(result.missing_cards));
+            result.missing_cards = ptr::null_mut();
+        }
+    }
+}
+
 /// Get error message for the last error (simple version)
 /// Returns a static string describing the error code
 #[unsafe(no_mangle)]
@@ -483,14 +774,198 @@ pub extern "C" fn localhawk_clear_image_cache() -> c_int {
 }
 
 /// Update card names database from Scryfall API (iOS sync version)
-#[unsafe(no_mangle)] 
+/// Blocks on the network, so call it off the main thread
+#[unsafe(no_mangle)]
 pub extern "C" fn localhawk_update_card_names() -> c_int {
-    // For iOS, this would require network requests
-    // For now, return success (initialization handles basic setup)
+    match force_update_card_lookup_sync() {
+        Ok(()) => FFIError::Success as c_int,
+        Err(e) => {
+            tracing::error!("Failed to update card names: {}", e);
+            FFIError::InitializationFailed as c_int
+        }
+    }
+}
+
+/// Update set codes from Scryfall API (iOS sync version)
+/// Blocks on the network, so call it off the main thread
+#[unsafe(no_mangle)]
+pub extern "C" fn localhawk_update_set_codes() -> c_int {
+    match force_update_set_codes_sync() {
+        Ok(()) => FFIError::Success as c_int,
+        Err(e) => {
+            tracing::error!("Failed to update set codes: {}", e);
+            FFIError::InitializationFailed as c_int
+        }
+    }
+}
+
+/// When card names and set codes were last fetched, for showing database freshness
+#[repr(C)]
+pub struct CacheFreshness {
+    pub card_names_updated_at: i64, // Unix time in seconds, 0 if not cached
+    pub card_names_stale: c_int,    // 1 if older than the freshness policy allows or not cached
+    pub set_codes_updated_at: i64,  // Unix time in seconds, 0 if not cached
+    pub set_codes_stale: c_int,     // 1 if older than the freshness policy allows or not cached
+}
+
+/// Get when card names and set codes were last fetched and whether they are stale
+/// Reads the caches from disk; caches that can't be read count as not cached
+#[unsafe(no_mangle)]
+pub extern "C" fn localhawk_get_cache_freshness() -> CacheFreshness {
+    let (card_names, set_codes) = get_cache_dates_sync().unwrap_or_default();
+    let policy = get_freshness_policy();
+    let updated_at = |cached_at: Option<time::OffsetDateTime>| {
+        cached_at.map_or(0, |cached_at| cached_at.unix_timestamp())
+    };
+    let stale = |cached_at: Option<time::OffsetDateTime>| {
+        cached_at.is_none_or(|cached_at| policy.is_stale(cached_at)) as c_int
+    };
+
+    CacheFreshness {
+        card_names_updated_at: updated_at(card_names),
+        card_names_stale: stale(card_names),
+        set_codes_updated_at: updated_at(set_codes),
+        set_codes_stale: stale(set_codes),
+    }
+}
+
+/// Get the status of the automatic background refresh of card names and set codes
+/// `state_out` receives 0 = idle, 1 = refreshing, 2 = completed, 3 = failed
+/// `message_out` (nullable) receives a display string that must be freed with localhawk_free_string
+#[unsafe(no_mangle)]
+pub extern "C" fn localhawk_get_cache_refresh_status(
+    state_out: *mut c_int,
+    message_out: *mut *mut c_char,
+) -> c_int {
+    if state_out.is_null() {
+        return FFIError::NullPointer as c_int;
+    }
+
+    let status = get_cache_refresh_status();
+    let state = match status {
+        CacheRefreshStatus::Idle => 0,
+        CacheRefreshStatus::Refreshing { .. } => 1,
+        CacheRefreshStatus::Completed { .. } => 2,
+        CacheRefreshStatus::Failed(_) => 3,
+    };
+
+    unsafe {
+        *state_out = state;
+    }
+
+    if !message_out.is_null() {
+        let message = match CString::new(status.to_string()) {
+            Ok(c_string) => c_string.into_raw(),
+            Err(_) => return FFIError::OutOfMemory as c_int,
+        };
+        unsafe {
+            *message_out = message;
+        }
+    }
+
+    FFIError::Success as c_int
+}
+
+/// Run the self-diagnostics and get their report as JSON, to attach to bug reports
+/// `report_out` receives {"version", "platform", "cache_directory", "checks": [{"name",
+/// "status" ("ok", "warning" or "failed"), "details"}]}, which must be freed with
+/// localhawk_free_string; failed checks are part of the report, not an error
+#[unsafe(no_mangle)]
+pub extern "C" fn localhawk_run_diagnostics(report_out: *mut *mut c_char) -> c_int {
+    if report_out.is_null() {
+        return FFIError::NullPointer as c_int;
+    }
+
+    let rt = match get_ffi_runtime() {
+        Some(rt) => rt,
+        None => return FFIError::InitializationFailed as c_int, // Must call localhawk_initialize first
+    };
+
+    let report = rt.block_on(crate::diagnostics::run_diagnostics());
+    let json = match serde_json::to_string(&report) {
+        Ok(json) => json,
+        Err(_) => return FFIError::InvalidInput as c_int,
+    };
+    let report = match CString::new(json) {
+        Ok(c_string) => c_string.into_raw(),
+        Err(_) => return FFIError::OutOfMemory as c_int,
+    };
+    unsafe {
+        *report_out = report;
+    }
+
+    FFIError::Success as c_int
+}
+
+/// Configure when card names and set codes are refreshed automatically
+/// Call before localhawk_initialize for the policy to apply at startup
+#[unsafe(no_mangle)]
+pub extern "C" fn localhawk_set_freshness_policy(
+    auto_refresh: c_int,
+    max_age_days: c_int,
+) -> c_int {
+    if max_age_days <= 0 {
+        return FFIError::InvalidInput as c_int;
+    }
+
+    set_freshness_policy(FreshnessPolicy {
+        auto_refresh: auto_refresh != 0,
+        ..FreshnessPolicy::from_days(max_age_days as i64)
+    });
+    FFIError::Success as c_int
+}
+
+/// Configure what is printed for back faces whose image is unavailable
+/// 0 = front, 1 = skip, 2 = placeholder back
+#[unsafe(no_mangle)]
+pub extern "C" fn localhawk_set_back_face_fallback(fallback: c_int) -> c_int {
+    let fallback = match fallback {
+        0 => BackFaceFallback::Front,
+        1 => BackFaceFallback::Skip,
+        2 => BackFaceFallback::Placeholder,
+        _ => return FFIError::InvalidInput as c_int,
+    };
+
+    set_back_face_fallback(fallback);
+    FFIError::Success as c_int
+}
+
+/// Prefer printings in a language (Scryfall code, e.g. "ja") for cards without a language
+/// NULL or an empty string prefers English again
+#[unsafe(no_mangle)]
+pub extern "C" fn localhawk_set_preferred_language(language: *const c_char) -> c_int {
+    if language.is_null() {
+        set_preferred_language(None);
+        return FFIError::Success as c_int;
+    }
+
+    let language = match unsafe { CStr::from_ptr(language) }.to_str() {
+        Ok(s) => s.trim(),
+        Err(_) => return FFIError::InvalidInput as c_int,
+    };
+    set_preferred_language((!language.is_empty()).then(|| language.to_string()));
     FFIError::Success as c_int
 }
 
 
+/// Configure the network timeouts of all following requests
+/// A dead network then fails downloads instead of hanging background loading
+#[unsafe(no_mangle)]
+pub extern "C" fn localhawk_set_network_timeouts(
+    connect_timeout_ms: c_int,
+    read_timeout_ms: c_int,
+) -> c_int {
+    if connect_timeout_ms <= 0 || read_timeout_ms <= 0 {
+        return FFIError::InvalidInput as c_int;
+    }
+
+    crate::http_client::set_http_timeouts(crate::http_client::HttpTimeouts {
+        connect: std::time::Duration::from_millis(connect_timeout_ms as u64),
+        read: std::time::Duration::from_millis(read_timeout_ms as u64),
+    });
+    FFIError::Success as c_int
+}
+
 /// Get image cache path
 /// Returns a newly allocated C string that must be freed with localhawk_free_string
 #[unsafe(no_mangle)]
@@ -550,6 +1025,10 @@ pub struct CCardPrinting {
     pub back_side: *mut c_char, // Back face/meld result image URL (NULL if no back side)
     pub back_type: u32, // BackSideType enum value (0=none, 1=DFC, 2=meld)
     pub back_name: *mut c_char, // Back face name or meld result name (NULL if no back side)
+    pub scryfall_id: *mut c_char, // Unique id of the printing (NULL for cards cached without one)
+    pub mana_cost: *mut c_char, // NULL if unknown or the card has none
+    pub type_line: *mut c_char, // NULL if unknown
+    pub oracle_text: *mut c_char, // NULL if unknown or the card has none
 }
 
 /// C-compatible card search result
@@ -557,6 +1036,7 @@ pub struct CCardPrinting {
 pub struct CCardSearchResult {
     pub cards: *mut CCardPrinting,
     pub count: usize,
+    pub has_more: c_int, // 1 if Scryfall has more printings than were fetched
 }
 
 // NOTE: Removed duplicate/commented function with illegal async call
@@ -722,6 +1202,9 @@ pub extern "C" fn localhawk_free_decklist_entries(entries: *mut CDeclistEntry, c
                 if !(*entry).language.is_null() {
                     let _ = CString::from_raw((*entry).language);
                 }
+                if !(*entry).scryfall_id.is_null() {
+                    let _ = CString::from_raw((*entry).scryfall_id);
+                }
             }
             libc::free(entries as *mut libc::c_void);
         }
@@ -755,6 +1238,18 @@ pub extern "C" fn localhawk_free_card_search_result(result: *mut CCardSearchResu
                     if !(*card).back_name.is_null() {
                         let _ = CString::from_raw((*card).back_name);
                     }
+                    if !(*card).scryfall_id.is_null() {
+                        let _ = CString::from_raw((*card).scryfall_id);
+                    }
+                    if !(*card).mana_cost.is_null() {
+                        let _ = CString::from_raw((*card).mana_cost);
+                    }
+                    if !(*card).type_line.is_null() {
+                        let _ = CString::from_raw((*card).type_line);
+                    }
+                    if !(*card).oracle_text.is_null() {
+                        let _ = CString::from_raw((*card).oracle_text);
+                    }
                 }
                 libc::free(result_ref.cards as *mut libc::c_void);
             }
@@ -805,10 +1300,14 @@ pub extern "C" fn localhawk_get_cached_image_bytes(
     }
 }
 
-/// Check if an image is cached without retrieving the bytes
+/// Get a downscaled copy of a cached image for preview grids
 #[unsafe(no_mangle)]
-pub extern "C" fn localhawk_is_image_cached(image_url_cstr: *const c_char) -> c_int {
-    if image_url_cstr.is_null() {
+pub extern "C" fn localhawk_get_cached_thumbnail_bytes(
+    image_url_cstr: *const c_char,
+    output_buffer: *mut *mut u8,
+    output_size: *mut usize,
+) -> c_int {
+    if image_url_cstr.is_null() || output_buffer.is_null() || output_size.is_null() {
         return FFIError::NullPointer as c_int;
     }
 
@@ -817,15 +1316,52 @@ pub extern "C" fn localhawk_is_image_cached(image_url_cstr: *const c_char) -> c_
         Err(_) => return FFIError::InvalidInput as c_int,
     };
 
-    if crate::get_cached_image_bytes(image_url).is_some() {
-        1 // TRUE - image is cached
-    } else {
-        0 // FALSE - image is not cached
-    }
-}
+    match crate::get_cached_thumbnail_bytes(image_url) {
+        Some(bytes) => {
+            let buffer = unsafe { libc::malloc(bytes.len()) as *mut u8 };
+            if buffer.is_null() {
+                return FFIError::OutOfMemory as c_int;
+            }
 
-// ============================================================================
-// Background Loading FFI Extensions
+            unsafe {
+                std::ptr::copy_nonoverlapping(bytes.as_ptr(), buffer, bytes.len());
+                *output_buffer = buffer;
+                *output_size = bytes.len();
+            }
+
+            FFIError::Success as c_int
+        }
+        None => {
+            unsafe {
+                *output_buffer = std::ptr::null_mut();
+                *output_size = 0;
+            }
+            FFIError::ParseFailed as c_int // Using ParseFailed to indicate "not found"
+        }
+    }
+}
+
+/// Check if an image is cached without retrieving the bytes
+#[unsafe(no_mangle)]
+pub extern "C" fn localhawk_is_image_cached(image_url_cstr: *const c_char) -> c_int {
+    if image_url_cstr.is_null() {
+        return FFIError::NullPointer as c_int;
+    }
+
+    let image_url = match unsafe { CStr::from_ptr(image_url_cstr) }.to_str() {
+        Ok(s) => s,
+        Err(_) => return FFIError::InvalidInput as c_int,
+    };
+
+    if crate::get_cached_image_bytes(image_url).is_some() {
+        1 // TRUE - image is cached
+    } else {
+        0 // FALSE - image is not cached
+    }
+}
+
+// ============================================================================
+// Background Loading FFI Extensions
 // ============================================================================
 
 // TODO: Migrate to sync - Parse decklist and start background image loading (fire and forget)
@@ -867,21 +1403,21 @@ pub extern "C" fn localhawk_parse_and_start_background_loading(
         if !entries.is_empty() {
             let entries_clone = entries.clone();
             let entry_count = entries.len();
-            println!("About to spawn background loading task for {} entries", entry_count);
+            tracing::debug!("About to spawn background loading task for {} entries", entry_count);
             
             // Spawn the task and give it a moment to start
             let handle = tokio::spawn(async move {
-                println!("Background loading task started for {} entries", entry_count);
+                tracing::debug!("Background loading task started for {} entries", entry_count);
                 let _handle = crate::start_background_image_loading(entries_clone);
-                println!("Background loading task completed for {} entries", entry_count);
+                tracing::debug!("Background loading task completed for {} entries", entry_count);
             });
             
             // Give the spawned task a moment to start before returning
             // This ensures the task actually begins execution
             tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
-            println!("tokio::spawn called successfully, gave task time to start");
+            tracing::debug!("tokio::spawn called successfully, gave task time to start");
         } else {
-            println!("No entries to load in background");
+            tracing::debug!("No entries to load in background");
         }
         
         Ok::<Vec<crate::DecklistEntry>, crate::ProxyError>(entries)
@@ -912,212 +1448,6 @@ pub struct CBackgroundLoadProgress {
     pub error_count: usize,
 }
 
-// TODO: Migrate to sync - Background loading infrastructure
-/*
-/// Opaque handle to background loading task
-pub type BackgroundLoadHandleId = usize;
-
-use std::collections::HashMap;
-use std::sync::{Arc, Mutex};
-
-lazy_static::lazy_static! {
-    static ref BACKGROUND_HANDLES: Arc<Mutex<HashMap<BackgroundLoadHandleId, BackgroundLoadHandle>>> =
-        Arc::new(Mutex::new(HashMap::new()));
-    static ref HANDLE_COUNTER: Arc<Mutex<BackgroundLoadHandleId>> = Arc::new(Mutex::new(0));
-}
-*/
-
-// TODO: Migrate to sync - Start background image loading for decklist entries
-// Returns a handle ID for tracking progress and cancellation
-/*
-#[unsafe(no_mangle)]
-pub extern "C" fn localhawk_start_background_loading(
-    entries: *const CDeclistEntry,
-    count: usize,
-    handle_id: *mut BackgroundLoadHandleId,
-) -> c_int {
-    if entries.is_null() || handle_id.is_null() {
-        return FFIError::NullPointer as c_int;
-    }
-
-    if count == 0 {
-        return FFIError::InvalidInput as c_int;
-    }
-
-    // Convert C entries to Rust entries
-    let rust_entries = unsafe {
-        let mut result = Vec::with_capacity(count);
-        for i in 0..count {
-            let entry = entries.add(i);
-            let name = CStr::from_ptr((*entry).name).to_string_lossy().to_string();
-            let set = if (*entry).set.is_null() {
-                None
-            } else {
-                Some(CStr::from_ptr((*entry).set).to_string_lossy().to_string())
-            };
-            let language = if (*entry).language.is_null() {
-                None
-            } else {
-                Some(
-                    CStr::from_ptr((*entry).language)
-                        .to_string_lossy()
-                        .to_string(),
-                )
-            };
-            let face_mode = match (*entry).face_mode {
-                0 => DoubleFaceMode::FrontOnly,
-                1 => DoubleFaceMode::BackOnly,
-                2 => DoubleFaceMode::BothSides,
-                _ => DoubleFaceMode::BothSides, // Default fallback
-            };
-            let source_line_number = if (*entry).source_line_number >= 0 {
-                Some((*entry).source_line_number as usize)
-            } else {
-                None
-            };
-
-            result.push(crate::DecklistEntry {
-                multiple: (*entry).multiple,
-                name,
-                set,
-                lang: language,
-                face_mode,
-                source_line_number,
-            });
-        }
-        result
-    };
-
-    // Use the FFI runtime instead of creating a temporary one
-    let rt = match get_ffi_runtime() {
-        Some(rt) => rt,
-        None => return FFIError::InitializationFailed as c_int, // Must call localhawk_initialize first
-    };
-
-    // Convert to a decklist string for the high-level function
-    let decklist_string = rust_entries
-        .iter()
-        .map(|entry| {
-            let mut line = format!("{} {}", entry.multiple, entry.name);
-            if let Some(ref set) = entry.set {
-                line.push_str(&format!(" [{}]", set));
-            }
-            if let Some(ref lang) = entry.lang {
-                line.push_str(&format!(" [{}]", lang));
-            }
-            line
-        })
-        .collect::<Vec<_>>()
-        .join("\n");
-
-    // Use the new high-level function that parses and starts background loading
-    let global_face_mode = match rust_entries.first() {
-        Some(entry) => entry.face_mode.clone(),
-        None => crate::DoubleFaceMode::BothSides,
-    };
-
-    // Start background loading directly using the sync approach (fire-and-forget)
-    let entries_clone = rust_entries.clone();
-    std::thread::spawn(move || {
-        let _handle = crate::start_background_image_loading(entries_clone);
-    });
-
-    // Since we're using fire-and-forget, just return a dummy handle ID
-    unsafe {
-        *handle_id = 1; // Always return 1 since we don't track handles anymore
-    }
-
-    FFIError::Success as c_int
-}
-*/
-
-// TODO: Migrate to sync - Get progress for a background loading task
-// Returns latest progress if available, or indicates if task is finished
-/*
-#[unsafe(no_mangle)]
-pub extern "C" fn localhawk_get_background_progress(
-    handle_id: BackgroundLoadHandleId,
-    progress: *mut CBackgroundLoadProgress,
-    has_progress: *mut c_int,
-) -> c_int {
-    if progress.is_null() || has_progress.is_null() {
-        return FFIError::NullPointer as c_int;
-    }
-
-    let mut handles = BACKGROUND_HANDLES.lock().unwrap();
-    if let Some(handle) = handles.get_mut(&handle_id) {
-        if let Some(rust_progress) = handle.try_get_progress() {
-            unsafe {
-                *progress = CBackgroundLoadProgress {
-                    phase: match rust_progress.phase {
-                        LoadingPhase::Selected => CLoadingPhase::Selected,
-                        LoadingPhase::Alternatives => CLoadingPhase::Alternatives,
-                        LoadingPhase::Completed => CLoadingPhase::Completed,
-                    },
-                    current_entry: rust_progress.current_entry,
-                    total_entries: rust_progress.total_entries,
-                    selected_loaded: rust_progress.selected_loaded,
-                    alternatives_loaded: rust_progress.alternatives_loaded,
-                    total_alternatives: rust_progress.total_alternatives,
-                    error_count: rust_progress.errors.len(),
-                };
-                *has_progress = 1; // TRUE
-            }
-        } else {
-            unsafe {
-                *has_progress = 0; // FALSE - no new progress
-            }
-        }
-
-        // Clean up finished tasks
-        if handle.is_finished() {
-            handles.remove(&handle_id);
-        }
-
-        FFIError::Success as c_int
-    } else {
-        // Handle not found (probably finished and cleaned up)
-        unsafe {
-            *has_progress = 0;
-        }
-        FFIError::InvalidInput as c_int
-    }
-}
-
-/// Cancel background loading task
-#[unsafe(no_mangle)]
-pub extern "C" fn localhawk_cancel_background_loading(handle_id: BackgroundLoadHandleId) -> c_int {
-    let mut handles = BACKGROUND_HANDLES.lock().unwrap();
-    if let Some(handle) = handles.get(&handle_id) {
-        handle.cancel();
-        handles.remove(&handle_id); // Remove immediately after cancellation
-        FFIError::Success as c_int
-    } else {
-        FFIError::InvalidInput as c_int
-    }
-}
-*/
-
-// TODO: Migrate to sync - Check if background loading task is finished
-/*
-#[unsafe(no_mangle)]
-pub extern "C" fn localhawk_is_background_loading_finished(
-    handle_id: BackgroundLoadHandleId,
-) -> c_int {
-    let mut handles = BACKGROUND_HANDLES.lock().unwrap();
-    if let Some(handle) = handles.get(&handle_id) {
-        if handle.is_finished() {
-            handles.remove(&handle_id); // Clean up finished task
-            1 // TRUE - finished
-        } else {
-            0 // FALSE - still running
-        }
-    } else {
-        1 // TRUE - not found means it's finished (and cleaned up)
-    }
-}
-*/
-
 // TODO: Migrate to sync - Generate PDF from an array of DecklistEntry structures
 // This allows PDF generation with modified entries (e.g., after print selection)
 /*
@@ -1182,13 +1512,24 @@ pub extern "C" fn localhawk_generate_pdf_from_entries(
             Some(c_entry.source_line_number as usize)
         };
 
+        let multiple = match c_entry_quantity(c_entry.multiple) {
+            Ok(multiple) => multiple,
+            Err(e) => return e as c_int,
+        };
+
         rust_entries.push(crate::DecklistEntry {
-            multiple: c_entry.multiple,
+            multiple,
             name,
             set,
             lang,
             face_mode,
             source_line_number,
+            section: crate::DeckSection::Main,
+            foil: false,
+            tags: Vec::new(),
+            scryfall_id: None,
+            collector_number: None,
+            annotation: None,
         });
     }
 
@@ -1202,7 +1543,7 @@ pub extern "C" fn localhawk_generate_pdf_from_entries(
     let pdf_data = match rt.block_on(async {
         let pdf_options = PdfOptions::default();
         ProxyGenerator::generate_pdf_from_entries(&rust_entries, pdf_options, |current, total| {
-            log::debug!("PDF generation progress: {}/{}", current, total);
+            tracing::debug!("PDF generation progress: {}/{}", current, total);
         })
         .await
     }) {
@@ -1268,9 +1609,19 @@ pub extern "C" fn localhawk_get_resolved_cards_for_entries(
                 2 => DoubleFaceMode::BothSides,
                 _ => DoubleFaceMode::BothSides,
             };
+            let scryfall_id = if c_entry.scryfall_id.is_null() {
+                None
+            } else {
+                Some(CStr::from_ptr(c_entry.scryfall_id).to_string_lossy().to_string())
+            };
+
+            let multiple = match c_entry_quantity(c_entry.multiple) {
+                Ok(multiple) => multiple,
+                Err(e) => return e as c_int,
+            };
 
             rust_entries.push(crate::decklist::DecklistEntry {
-                multiple: c_entry.multiple,
+                multiple,
                 name,
                 set,
                 lang: language,
@@ -1280,6 +1631,12 @@ pub extern "C" fn localhawk_get_resolved_cards_for_entries(
                 } else { 
                     None 
                 },
+                section: crate::DeckSection::Main,
+                foil: false,
+                tags: Vec::new(),
+                scryfall_id,
+                collector_number: None,
+                annotation: None,
             });
         }
     }
@@ -1288,7 +1645,7 @@ pub extern "C" fn localhawk_get_resolved_cards_for_entries(
     let resolved_cards = match crate::ios_api::ProxyGenerator::resolve_decklist_entries_to_cards_sync(&rust_entries) {
         Ok(cards) => cards,
         Err(e) => {
-            println!("❌ FFI: Failed to resolve entries to cards: {:?}", e);
+            tracing::warn!("FFI: Failed to resolve entries to cards: {:?}", e);
             return FFIError::ParseFailed as c_int;
         }
     };
@@ -1299,7 +1656,7 @@ pub extern "C" fn localhawk_get_resolved_cards_for_entries(
         match card_to_c_resolved_card(card, *quantity, face_mode) {
             Ok(c_card) => c_cards.push(c_card),
             Err(e) => {
-                println!("❌ FFI: Failed to convert resolved card to C format: {:?}", e);
+                tracing::warn!("FFI: Failed to convert resolved card to C format: {:?}", e);
                 return e as c_int;
             }
         }
@@ -1319,7 +1676,7 @@ pub extern "C" fn localhawk_get_resolved_cards_for_entries(
         *resolved_cards_count_out = count;
     }
 
-    println!("✅ FFI: Returning {} resolved cards for default selection mapping", count);
+    tracing::debug!("FFI: Returning {} resolved cards for default selection mapping", count);
     FFIError::Success as c_int
 }
 
@@ -1352,13 +1709,13 @@ pub extern "C" fn localhawk_parse_and_start_background_loading(
     let entries = match crate::ios_api::ProxyGenerator::parse_and_resolve_decklist_sync(decklist, face_mode) {
         Ok(entries) => entries,
         Err(e) => {
-            log::error!("Failed to parse decklist: {:?}", e);
+            tracing::error!("Failed to parse decklist: {:?}", e);
             return FFIError::ParseFailed as c_int;
         }
     };
 
     if entries.is_empty() {
-        log::info!("No entries parsed from decklist");
+        tracing::info!("No entries parsed from decklist");
         unsafe {
             *entries_out = ptr::null_mut();
             *entries_count_out = 0;
@@ -1369,15 +1726,15 @@ pub extern "C" fn localhawk_parse_and_start_background_loading(
     // Phase 1: Selected printings are already loaded by resolve_decklist_entries_to_cards_sync above
     // Phase 2: Load all printings in background thread for print selection modal
     let entries_for_bg = entries.clone();
-    println!("🔧 FFI: About to spawn alternative printings loading thread for {} entries", entries_for_bg.len());
+    tracing::debug!("FFI: About to spawn alternative printings loading thread for {} entries", entries_for_bg.len());
     std::thread::spawn(move || {
-        println!("🧵 FFI: Alternative printings loading thread started for {} entries", entries_for_bg.len());
+        tracing::debug!("FFI: Alternative printings loading thread started for {} entries", entries_for_bg.len());
         match crate::ios_api::ProxyGenerator::load_alternative_printings_sync(&entries_for_bg) {
             Ok(count) => {
-                println!("✅ FFI: Alternative printings loading completed successfully, {} images processed", count);
+                tracing::debug!("FFI: Alternative printings loading completed successfully, {} images processed", count);
             }
             Err(e) => {
-                println!("❌ FFI: Alternative printings loading failed: {:?}", e);
+                tracing::warn!("FFI: Alternative printings loading failed: {:?}", e);
             }
         }
     });
@@ -1389,66 +1746,131 @@ pub extern "C" fn localhawk_parse_and_start_background_loading(
                 *entries_out = c_entries_ptr;
                 *entries_count_out = count;
             }
-            println!("✅ FFI: Returning {} parsed entries to UI, background loading started", count);
+            tracing::debug!("FFI: Returning {} parsed entries to UI, background loading started", count);
             FFIError::Success as c_int
         }
         Err(e) => {
-            println!("❌ FFI: Failed to convert entries to C format: {:?}", e);
+            tracing::warn!("FFI: Failed to convert entries to C format: {:?}", e);
             FFIError::OutOfMemory as c_int
         }
     }
 }
 
-/// Dummy implementation - Start background image loading for decklist entries (non-functional)
+/// Background loads started through localhawk_start_background_loading, by handle ID
+static BACKGROUND_LOADS: LazyLock<Mutex<HashMap<usize, Arc<SyncBackgroundLoadProgress>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+static NEXT_BACKGROUND_HANDLE: AtomicUsize = AtomicUsize::new(1);
+
+/// Start background image loading for decklist entries in a background thread
 #[unsafe(no_mangle)]
 pub extern "C" fn localhawk_start_background_loading(
-    _entries: *const CDeclistEntry,
-    _count: usize,
+    entries: *const CDeclistEntry,
+    count: usize,
     handle_id: *mut usize,
 ) -> c_int {
-    if handle_id.is_null() {
+    if entries.is_null() || handle_id.is_null() {
         return FFIError::NullPointer as c_int;
     }
-    
-    // Return a dummy handle ID
+
+    if count == 0 {
+        return FFIError::InvalidInput as c_int;
+    }
+
+    let rust_entries = match unsafe { convert_entries_from_c_format(entries, count) } {
+        Ok(rust_entries) => rust_entries,
+        Err(e) => return e as c_int,
+    };
+
+    let progress = Arc::new(SyncBackgroundLoadProgress::new(rust_entries.len()));
+    let id = NEXT_BACKGROUND_HANDLE.fetch_add(1, Ordering::SeqCst);
+    match BACKGROUND_LOADS.lock() {
+        Ok(mut loads) => {
+            loads.insert(id, progress.clone());
+        }
+        Err(e) => {
+            tracing::error!("Failed to lock background load registry: {}", e);
+            return FFIError::InitializationFailed as c_int;
+        }
+    }
+
+    std::thread::spawn(move || {
+        ProxyGenerator::load_background_images_sync(&rust_entries, &progress);
+    });
+
     unsafe {
-        *handle_id = 1;
+        *handle_id = id;
     }
-    
+
     FFIError::Success as c_int
 }
 
-/// Dummy implementation - Get progress for background loading (non-functional)
+fn find_background_load(handle_id: usize) -> Option<Arc<SyncBackgroundLoadProgress>> {
+    BACKGROUND_LOADS.lock().ok()?.get(&handle_id).cloned()
+}
+
+/// Get progress for background loading
+/// Finished loads are forgotten once their final progress has been reported
 #[unsafe(no_mangle)]
 pub extern "C" fn localhawk_get_background_progress(
-    _handle_id: usize,
-    _progress: *mut CBackgroundLoadProgress,
+    handle_id: usize,
+    progress: *mut CBackgroundLoadProgress,
     has_progress: *mut c_int,
 ) -> c_int {
-    if has_progress.is_null() {
+    if progress.is_null() || has_progress.is_null() {
         return FFIError::NullPointer as c_int;
     }
-    
-    // Always return "no progress available"
+
+    let Some(load) = find_background_load(handle_id) else {
+        unsafe {
+            *has_progress = 0;
+        }
+        return FFIError::Success as c_int;
+    };
+
     unsafe {
-        *has_progress = 0;
+        *progress = CBackgroundLoadProgress {
+            phase: match load.phase() {
+                LoadingPhase::Selected => CLoadingPhase::Selected,
+                LoadingPhase::Alternatives => CLoadingPhase::Alternatives,
+                LoadingPhase::Completed => CLoadingPhase::Completed,
+            },
+            current_entry: load.current_entry.load(Ordering::SeqCst),
+            total_entries: load.total_entries,
+            selected_loaded: load.selected_loaded.load(Ordering::SeqCst),
+            alternatives_loaded: load.alternatives_loaded.load(Ordering::SeqCst),
+            total_alternatives: load.total_alternatives.load(Ordering::SeqCst),
+            error_count: load.error_count.load(Ordering::SeqCst),
+        };
+        *has_progress = 1;
     }
-    
+
+    if load.is_finished() {
+        if let Ok(mut loads) = BACKGROUND_LOADS.lock() {
+            loads.remove(&handle_id);
+        }
+    }
+
     FFIError::Success as c_int
 }
 
-/// Dummy implementation - Cancel background loading (non-functional)
+/// Cancel background loading
+/// Unknown handles (e.g. loads started by parsing a decklist) abort every running background load
 #[unsafe(no_mangle)]
-pub extern "C" fn localhawk_cancel_background_loading(_handle_id: usize) -> c_int {
-    // Always return success (nothing to cancel)
+pub extern "C" fn localhawk_cancel_background_loading(handle_id: usize) -> c_int {
+    match find_background_load(handle_id) {
+        Some(load) => load.cancel(),
+        None => crate::ios_api::abort_background_loading_sync(),
+    }
     FFIError::Success as c_int
 }
 
-/// Dummy implementation - Check if background loading is finished (non-functional)
+/// Check if background loading is finished (1 = finished, 0 = still running)
 #[unsafe(no_mangle)]
-pub extern "C" fn localhawk_is_background_loading_finished(_handle_id: usize) -> c_int {
-    // Always return "finished" (1 = TRUE)
-    1
+pub extern "C" fn localhawk_is_background_loading_finished(handle_id: usize) -> c_int {
+    match find_background_load(handle_id) {
+        Some(load) if !load.is_finished() => 0,
+        _ => 1,
+    }
 }
 
 /// Save all in-memory caches to disk
@@ -1461,6 +1883,87 @@ pub extern "C" fn localhawk_save_caches() -> c_int {
     }
 }
 
+/// Export the image cache to a tar archive at the given path
+#[unsafe(no_mangle)]
+pub extern "C" fn localhawk_export_image_cache(path_cstr: *const c_char) -> c_int {
+    if path_cstr.is_null() {
+        return FFIError::NullPointer as c_int;
+    }
+    let path = match unsafe { CStr::from_ptr(path_cstr) }.to_str() {
+        Ok(path) => std::path::PathBuf::from(path),
+        Err(_) => return FFIError::InvalidInput as c_int,
+    };
+
+    match crate::globals::export_image_cache_to(&path) {
+        Ok(count) => {
+            tracing::info!("Exported {} cached images to {}", count, path.display());
+            FFIError::Success as c_int
+        }
+        Err(e) => {
+            tracing::error!("Failed to export image cache: {}", e);
+            FFIError::InitializationFailed as c_int
+        }
+    }
+}
+
+/// Import a tar archive written by localhawk_export_image_cache into the image cache
+/// `imported_out` (nullable) receives the number of images added to the cache
+#[unsafe(no_mangle)]
+pub extern "C" fn localhawk_import_image_cache(
+    path_cstr: *const c_char,
+    imported_out: *mut usize,
+) -> c_int {
+    if path_cstr.is_null() {
+        return FFIError::NullPointer as c_int;
+    }
+    let path = match unsafe { CStr::from_ptr(path_cstr) }.to_str() {
+        Ok(path) => std::path::PathBuf::from(path),
+        Err(_) => return FFIError::InvalidInput as c_int,
+    };
+
+    match crate::globals::import_image_cache_from(&path) {
+        Ok(summary) => {
+            if !imported_out.is_null() {
+                unsafe { *imported_out = summary.imported };
+            }
+            FFIError::Success as c_int
+        }
+        Err(e) => {
+            tracing::error!("Failed to import image cache: {}", e);
+            FFIError::ParseFailed as c_int
+        }
+    }
+}
+
+/// Write all log files into a single file at the given path, to attach to a bug report
+/// `exported_out` (nullable) receives the number of log files included
+#[unsafe(no_mangle)]
+pub extern "C" fn localhawk_export_logs(
+    path_cstr: *const c_char,
+    exported_out: *mut usize,
+) -> c_int {
+    if path_cstr.is_null() {
+        return FFIError::NullPointer as c_int;
+    }
+    let path = match unsafe { CStr::from_ptr(path_cstr) }.to_str() {
+        Ok(path) => std::path::PathBuf::from(path),
+        Err(_) => return FFIError::InvalidInput as c_int,
+    };
+
+    match crate::logging::export_logs(&path) {
+        Ok(count) => {
+            if !exported_out.is_null() {
+                unsafe { *exported_out = count };
+            }
+            FFIError::Success as c_int
+        }
+        Err(e) => {
+            tracing::error!("Failed to export logs: {}", e);
+            FFIError::InitializationFailed as c_int
+        }
+    }
+}
+
 // ============================================================================
 // Restored Essential FFI Functions (Sync iOS Versions)
 // ============================================================================
@@ -1514,6 +2017,12 @@ pub extern "C" fn localhawk_parse_and_resolve_decklist(
             .flatten()
             .map(|s| s.into_raw())
             .unwrap_or(std::ptr::null_mut());
+        let scryfall_id = entry
+            .scryfall_id
+            .map(|s| CString::new(s).ok())
+            .flatten()
+            .map(|s| s.into_raw())
+            .unwrap_or(std::ptr::null_mut());
         let face_mode_int = match entry.face_mode {
             DoubleFaceMode::FrontOnly => 0,
             DoubleFaceMode::BackOnly => 1,
@@ -1521,12 +2030,13 @@ pub extern "C" fn localhawk_parse_and_resolve_decklist(
         };
 
         c_entries.push(CDeclistEntry {
-            multiple: entry.multiple,
+            multiple: entry.multiple.get() as i32,
             name,
             set,
             language,
             face_mode: face_mode_int,
             source_line_number: entry.source_line_number.map(|n| n as i32).unwrap_or(-1),
+            scryfall_id,
         });
     }
 
@@ -1551,6 +2061,11 @@ pub extern "C" fn localhawk_parse_and_resolve_decklist(
                     let _ = CString::from_raw(entry.language);
                 }
             }
+            if !entry.scryfall_id.is_null() {
+                unsafe {
+                    let _ = CString::from_raw(entry.scryfall_id);
+                }
+            }
         }
         return FFIError::OutOfMemory as c_int;
     }
@@ -1585,6 +2100,7 @@ pub extern "C" fn localhawk_search_card_printings(
         Ok(result) => result,
         Err(_) => return FFIError::ParseFailed as c_int,
     };
+    let has_more = search_result.has_more;
 
     // Convert cards to C structures
     let mut c_cards = Vec::with_capacity(search_result.cards.len());
@@ -1605,6 +2121,26 @@ pub extern "C" fn localhawk_search_card_printings(
             Ok(s) => s.into_raw(),
             Err(_) => return FFIError::OutOfMemory as c_int,
         };
+        let scryfall_id = match card.scryfall_id.map(CString::new) {
+            Some(Ok(s)) => s.into_raw(),
+            Some(Err(_)) => return FFIError::OutOfMemory as c_int,
+            None => std::ptr::null_mut(),
+        };
+        let mana_cost = match card.mana_cost.map(CString::new) {
+            Some(Ok(s)) => s.into_raw(),
+            Some(Err(_)) => return FFIError::OutOfMemory as c_int,
+            None => std::ptr::null_mut(),
+        };
+        let type_line = match card.type_line.map(CString::new) {
+            Some(Ok(s)) => s.into_raw(),
+            Some(Err(_)) => return FFIError::OutOfMemory as c_int,
+            None => std::ptr::null_mut(),
+        };
+        let oracle_text = match card.oracle_text.map(CString::new) {
+            Some(Ok(s)) => s.into_raw(),
+            Some(Err(_)) => return FFIError::OutOfMemory as c_int,
+            None => std::ptr::null_mut(),
+        };
         let (back_side, back_type, back_name) = match card.back_side {
             Some(back) => {
                 match back {
@@ -1647,6 +2183,10 @@ pub extern "C" fn localhawk_search_card_printings(
             back_side,
             back_type,
             back_name,
+            scryfall_id,
+            mana_cost,
+            type_line,
+            oracle_text,
         });
     }
 
@@ -1662,6 +2202,10 @@ pub extern "C" fn localhawk_search_card_printings(
                 if !card.border_crop.is_null() { let _ = CString::from_raw(card.border_crop); }
                 if !card.back_side.is_null() { let _ = CString::from_raw(card.back_side); }
                 if !card.back_name.is_null() { let _ = CString::from_raw(card.back_name); }
+                if !card.scryfall_id.is_null() { let _ = CString::from_raw(card.scryfall_id); }
+                if !card.mana_cost.is_null() { let _ = CString::from_raw(card.mana_cost); }
+                if !card.type_line.is_null() { let _ = CString::from_raw(card.type_line); }
+                if !card.oracle_text.is_null() { let _ = CString::from_raw(card.oracle_text); }
             }
         }
         return FFIError::OutOfMemory as c_int;
@@ -1681,6 +2225,10 @@ pub extern "C" fn localhawk_search_card_printings(
                 if !card.border_crop.is_null() { let _ = CString::from_raw(card.border_crop); }
                 if !card.back_side.is_null() { let _ = CString::from_raw(card.back_side); }
                 if !card.back_name.is_null() { let _ = CString::from_raw(card.back_name); }
+                if !card.scryfall_id.is_null() { let _ = CString::from_raw(card.scryfall_id); }
+                if !card.mana_cost.is_null() { let _ = CString::from_raw(card.mana_cost); }
+                if !card.type_line.is_null() { let _ = CString::from_raw(card.type_line); }
+                if !card.oracle_text.is_null() { let _ = CString::from_raw(card.oracle_text); }
             }
         }
         return FFIError::OutOfMemory as c_int;
@@ -1692,6 +2240,7 @@ pub extern "C" fn localhawk_search_card_printings(
         *result_ptr = CCardSearchResult {
             cards: cards_ptr,
             count: c_cards.len(),
+            has_more: has_more as c_int,
         };
         *output_result = result_ptr;
     }
@@ -1716,60 +2265,10 @@ pub extern "C" fn localhawk_generate_pdf_from_entries(
     }
 
     // Convert C structures to Rust DecklistEntry structures
-    let mut rust_entries = Vec::new();
-    for i in 0..entry_count {
-        let c_entry = unsafe { &*entries.add(i) };
-
-        // Convert C strings to Rust strings
-        let name = if c_entry.name.is_null() {
-            return FFIError::InvalidInput as c_int;
-        } else {
-            match unsafe { CStr::from_ptr(c_entry.name) }.to_str() {
-                Ok(s) => s.to_string(),
-                Err(_) => return FFIError::InvalidInput as c_int,
-            }
-        };
-
-        let set = if c_entry.set.is_null() {
-            None
-        } else {
-            match unsafe { CStr::from_ptr(c_entry.set) }.to_str() {
-                Ok(s) => Some(s.to_string()),
-                Err(_) => return FFIError::InvalidInput as c_int,
-            }
-        };
-
-        let lang = if c_entry.language.is_null() {
-            None
-        } else {
-            match unsafe { CStr::from_ptr(c_entry.language) }.to_str() {
-                Ok(s) => Some(s.to_string()),
-                Err(_) => return FFIError::InvalidInput as c_int,
-            }
-        };
-
-        let face_mode = match c_entry.face_mode {
-            0 => DoubleFaceMode::FrontOnly,
-            1 => DoubleFaceMode::BackOnly,
-            2 => DoubleFaceMode::BothSides,
-            _ => DoubleFaceMode::BothSides,
-        };
-
-        let source_line_number = if c_entry.source_line_number >= 0 {
-            Some(c_entry.source_line_number as usize)
-        } else {
-            None
-        };
-
-        rust_entries.push(crate::DecklistEntry {
-            multiple: c_entry.multiple,
-            name,
-            set,
-            lang,
-            face_mode,
-            source_line_number,
-        });
-    }
+    let rust_entries = match unsafe { convert_entries_from_c_format(entries, entry_count) } {
+        Ok(rust_entries) => rust_entries,
+        Err(e) => return e as c_int,
+    };
 
     // Generate PDF using iOS sync API
     let pdf_data = match crate::ios_api::ProxyGenerator::generate_pdf_from_entries_sync(&rust_entries, crate::pdf::PdfOptions::default(), |_current, _total| {
@@ -1795,6 +2294,95 @@ pub extern "C" fn localhawk_generate_pdf_from_entries(
     FFIError::Success as c_int
 }
 
+/// Render an array of DecklistEntry structures into one PNG per page (sync iOS version)
+#[unsafe(no_mangle)]
+pub extern "C" fn localhawk_render_pages_from_entries(
+    entries: *const CDeclistEntry,
+    entry_count: usize,
+    dpi: f32,
+    output_pages: *mut *mut CPageImageArray,
+) -> c_int {
+    if entries.is_null() || output_pages.is_null() {
+        return FFIError::NullPointer as c_int;
+    }
+
+    let render_options = crate::pdf::RenderOptions {
+        dpi,
+        format: crate::pdf::PageImageFormat::Png,
+    };
+    if entry_count == 0 || render_options.validate().is_err() {
+        return FFIError::InvalidInput as c_int;
+    }
+
+    let rust_entries = match unsafe { convert_entries_from_c_format(entries, entry_count) } {
+        Ok(rust_entries) => rust_entries,
+        Err(e) => return e as c_int,
+    };
+
+    let pages = match crate::ios_api::ProxyGenerator::render_pages_from_entries_sync(
+        &rust_entries,
+        crate::pdf::PdfOptions::default(),
+        render_options,
+        |_current, _total| {},
+    ) {
+        Ok(pages) => pages,
+        Err(e) => {
+            tracing::error!("Failed to render pages: {}", e);
+            return FFIError::PdfGenerationFailed as c_int;
+        }
+    };
+
+    let count = pages.len();
+    let array_ptr = unsafe { libc::malloc(std::mem::size_of::<CPageImageArray>()) as *mut CPageImageArray };
+    let pages_ptr = unsafe { libc::malloc(count.max(1) * std::mem::size_of::<CPageImage>()) as *mut CPageImage };
+    if array_ptr.is_null() || pages_ptr.is_null() {
+        unsafe {
+            libc::free(array_ptr as *mut c_void);
+            libc::free(pages_ptr as *mut c_void);
+        }
+        return FFIError::OutOfMemory as c_int;
+    }
+
+    for (i, page) in pages.iter().enumerate() {
+        let data = unsafe { libc::malloc(page.len()) as *mut u8 };
+        if data.is_null() {
+            unsafe {
+                *array_ptr = CPageImageArray { pages: pages_ptr, count: i };
+            }
+            localhawk_free_page_images(array_ptr);
+            return FFIError::OutOfMemory as c_int;
+        }
+        unsafe {
+            std::ptr::copy_nonoverlapping(page.as_ptr(), data, page.len());
+            *pages_ptr.add(i) = CPageImage { data, size: page.len() };
+        }
+    }
+
+    unsafe {
+        *array_ptr = CPageImageArray { pages: pages_ptr, count };
+        *output_pages = array_ptr;
+    }
+
+    FFIError::Success as c_int
+}
+
+/// Free page images returned by localhawk_render_pages_from_entries
+#[unsafe(no_mangle)]
+pub extern "C" fn localhawk_free_page_images(array_ptr: *mut CPageImageArray) {
+    if array_ptr.is_null() {
+        return;
+    }
+
+    unsafe {
+        let array = &*array_ptr;
+        for i in 0..array.count {
+            libc::free((*array.pages.add(i)).data as *mut c_void);
+        }
+        libc::free(array.pages as *mut c_void);
+        libc::free(array_ptr as *mut c_void);
+    }
+}
+
 //==============================================================================
 // Image Cache Dispatch Source Notification Functions
 //==============================================================================
@@ -1809,16 +2397,16 @@ pub extern "C" fn localhawk_register_image_cache_dispatch_source(
         return FFIError::NullPointer as c_int;
     }
 
-    log::info!("📡 FFI: Registering global image cache dispatch source");
+    tracing::info!("📡 FFI: Registering global image cache dispatch source");
 
     match GLOBAL_IMAGE_DISPATCH_SOURCE.lock() {
         Ok(mut source) => {
             *source = Some((ThreadSafePtr(source_ptr), notify_fn));
-            log::info!("✅ FFI: Registered global image cache dispatch source");
+            tracing::info!("✅ FFI: Registered global image cache dispatch source");
             FFIError::Success as c_int
         }
         Err(e) => {
-            log::error!("Failed to lock global image cache dispatch source: {}", e);
+            tracing::error!("Failed to lock global image cache dispatch source: {}", e);
             FFIError::InitializationFailed as c_int
         }
     }
@@ -1827,21 +2415,21 @@ pub extern "C" fn localhawk_register_image_cache_dispatch_source(
 /// Unregister the global image cache dispatch source
 #[unsafe(no_mangle)]
 pub extern "C" fn localhawk_unregister_image_cache_dispatch_source() -> c_int {
-    log::info!("📡 FFI: Unregistering global image cache dispatch source");
+    tracing::info!("📡 FFI: Unregistering global image cache dispatch source");
 
     match GLOBAL_IMAGE_DISPATCH_SOURCE.lock() {
         Ok(mut source) => {
             let was_registered = source.is_some();
             *source = None;
             if was_registered {
-                log::info!("✅ FFI: Unregistered global image cache dispatch source");
+                tracing::info!("✅ FFI: Unregistered global image cache dispatch source");
             } else {
-                log::warn!("⚠️ FFI: No global image cache dispatch source was registered");
+                tracing::warn!("⚠️ FFI: No global image cache dispatch source was registered");
             }
             FFIError::Success as c_int
         }
         Err(e) => {
-            log::error!("Failed to lock global image cache dispatch source: {}", e);
+            tracing::error!("Failed to lock global image cache dispatch source: {}", e);
             FFIError::InitializationFailed as c_int
         }
     }
@@ -1857,7 +2445,7 @@ pub extern "C" fn localhawk_get_queued_image_cache_changes() -> *mut CImageCache
             }
 
             let count = queue.len();
-            log::info!("📤 FFI: Returning {} queued image cache change notifications", count);
+            tracing::info!("📤 FFI: Returning {} queued image cache change notifications", count);
 
             // Allocate array for C structs
             let changes_ptr = unsafe {
@@ -1865,7 +2453,7 @@ pub extern "C" fn localhawk_get_queued_image_cache_changes() -> *mut CImageCache
             };
             
             if changes_ptr.is_null() {
-                log::error!("Failed to allocate memory for image cache change notifications");
+                tracing::error!("Failed to allocate memory for image cache change notifications");
                 return ptr::null_mut();
             }
 
@@ -1874,7 +2462,7 @@ pub extern "C" fn localhawk_get_queued_image_cache_changes() -> *mut CImageCache
                 let image_url_cstr = match CString::new(change.image_url.as_str()) {
                     Ok(s) => s.into_raw(),
                     Err(_) => {
-                        log::error!("Failed to convert image URL to CString");
+                        tracing::error!("Failed to convert image URL to CString");
                         continue;
                     }
                 };
@@ -1884,6 +2472,8 @@ pub extern "C" fn localhawk_get_queued_image_cache_changes() -> *mut CImageCache
                         change_type: change.change_type,
                         image_url: image_url_cstr,
                         timestamp: change.timestamp,
+                        completed: change.completed,
+                        total: change.total,
                     };
                 }
             }
@@ -1891,7 +2481,7 @@ pub extern "C" fn localhawk_get_queued_image_cache_changes() -> *mut CImageCache
             // Allocate and populate CImageCacheChangeArray
             let array_ptr = unsafe { libc::malloc(std::mem::size_of::<CImageCacheChangeArray>()) as *mut CImageCacheChangeArray };
             if array_ptr.is_null() {
-                log::error!("Failed to allocate memory for CImageCacheChangeArray");
+                tracing::error!("Failed to allocate memory for CImageCacheChangeArray");
                 unsafe { libc::free(changes_ptr as *mut c_void); }
                 return ptr::null_mut();
             }
@@ -1906,7 +2496,7 @@ pub extern "C" fn localhawk_get_queued_image_cache_changes() -> *mut CImageCache
             array_ptr
         }
         Err(e) => {
-            log::error!("Failed to lock image cache change queue: {}", e);
+            tracing::error!("Failed to lock image cache change queue: {}", e);
             ptr::null_mut()
         }
     }
@@ -1938,32 +2528,76 @@ pub extern "C" fn localhawk_free_image_cache_change_array(array_ptr: *mut CImage
         libc::free(array_ptr as *mut c_void);
     }
     
-    log::debug!("📤 FFI: Freed image cache change array memory");
+    tracing::debug!("📤 FFI: Freed image cache change array memory");
 }
 
-/// Queue an image cache change notification (called from background loading threads)
-pub(crate) fn queue_image_cache_notification(
+/// Forward the core image cache events to the notification queue, once per process
+fn start_cache_event_forwarding() {
+    static STARTED: std::sync::Once = std::sync::Once::new();
+    STARTED.call_once(|| {
+        // Subscribe before returning so no image cached during initialization is missed
+        let mut events = crate::subscribe_cache_events();
+        std::thread::spawn(move || loop {
+            let (change_type, image_url) = match events.blocking_recv() {
+                Ok(CacheEvent::ImageCached(url)) => (1, url), // 1 = ImageCached
+                Ok(CacheEvent::ImageEvicted(url)) => (2, url), // 2 = ImageRemoved
+                Err(RecvError::Lagged(skipped)) => {
+                    tracing::warn!("Dropped {} image cache notifications", skipped);
+                    continue;
+                }
+                Err(RecvError::Closed) => break,
+            };
+            queue_image_cache_notification(change_type, &image_url);
+            notify_image_cache_dispatch_source();
+        });
+    });
+}
+
+/// Queue an image cache change notification
+fn queue_image_cache_notification(
     change_type: u8,
     image_url: &str,
 ) {
-    let timestamp = std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .unwrap_or_default()
-        .as_millis() as u64;
-
-    let change = ImageCacheNotification {
+    push_notification(ImageCacheNotification {
         change_type,
         image_url: image_url.to_string(),
-        timestamp,
-    };
+        timestamp: notification_timestamp(),
+        completed: 0,
+        total: 0,
+    });
+}
+
+/// Queue the outcome of a background load, with how many of its images were loaded
+pub(crate) fn queue_background_load_notification(
+    load_name: &str,
+    aborted: bool,
+    completed: usize,
+    total: usize,
+) {
+    push_notification(ImageCacheNotification {
+        change_type: if aborted { 4 } else { 3 }, // 3 = LoadFinished, 4 = LoadAborted
+        image_url: load_name.to_string(),
+        timestamp: notification_timestamp(),
+        completed,
+        total,
+    });
+}
+
+fn notification_timestamp() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
 
+fn push_notification(change: ImageCacheNotification) {
     match IMAGE_CHANGE_QUEUE.lock() {
         Ok(mut queue) => {
+            tracing::debug!("📥 FFI: Queued image cache change notification for '{}'. Queue size: {}", change.image_url, queue.len() + 1);
             queue.push_back(change);
-            log::debug!("📥 FFI: Queued image cache change notification for URL '{}'. Queue size: {}", image_url, queue.len());
         }
         Err(e) => {
-            log::error!("Failed to lock image cache change queue: {}", e);
+            tracing::error!("Failed to lock image cache change queue: {}", e);
         }
     }
 }
@@ -1973,21 +2607,21 @@ pub(crate) fn notify_image_cache_dispatch_source() {
     match GLOBAL_IMAGE_DISPATCH_SOURCE.lock() {
         Ok(source) => {
             if let Some((source_ptr, notify_fn)) = source.as_ref() {
-                log::info!("🔔 FFI: Notifying global image cache dispatch source of state change");
+                tracing::info!("🔔 FFI: Notifying global image cache dispatch source of state change");
                 let key_cstr = match CString::new("__GLOBAL_IMAGE_CACHE__") {
                     Ok(s) => s,
                     Err(_) => {
-                        log::error!("Failed to create global key CString");
+                        tracing::error!("Failed to create global key CString");
                         return;
                     }
                 };
                 
-                log::debug!("📲 FFI: Calling image cache notification function");
+                tracing::debug!("📲 FFI: Calling image cache notification function");
                 notify_fn(source_ptr.0, key_cstr.as_ptr());
             }
         }
         Err(e) => {
-            log::error!("Failed to lock global image cache dispatch source for notification: {}", e);
+            tracing::error!("Failed to lock global image cache dispatch source for notification: {}", e);
         }
     }
 }
@@ -2059,7 +2693,7 @@ pub extern "C" fn localhawk_expand_single_card(
     };
 
     // Log before using the values
-    println!("🔍 [FFI] expand_single_card: '{}' qty={} face_mode={:?}",
+    tracing::debug!("[FFI] expand_single_card: '{}' qty={} face_mode={:?}",
              name_str, quantity, face_mode_enum);
 
     let card = crate::scryfall::models::Card {
@@ -2068,6 +2702,18 @@ pub extern "C" fn localhawk_expand_single_card(
         language: language_str,
         border_crop: border_crop_str,
         back_side,
+        frame: None,
+        digital: false,
+        collector_number: None,
+        prices: None,
+        legalities: Default::default(),
+        layout: None,
+        scryfall_id: None,
+        oracle_id: None,
+        mana_cost: None,
+        type_line: None,
+        oracle_text: None,
+        power_toughness: None,
     };
 
     // Use the existing expansion logic
@@ -2075,14 +2721,67 @@ pub extern "C" fn localhawk_expand_single_card(
     let image_urls = crate::ProxyGenerator::expand_cards_to_image_urls(&cards);
 
     // Log the result
-    println!("🔍 [FFI] expand_single_card result: {} URLs", image_urls.len());
+    tracing::debug!("[FFI] expand_single_card result: {} URLs", image_urls.len());
     for (i, url) in image_urls.iter().enumerate() {
-        println!("  [{}] {}", i, url);
+        tracing::debug!("  [{}] {}", i, url);
     }
 
+    unsafe { write_image_urls(&image_urls, out_urls, out_count) }
+}
+
+/// Plan the pages of a PDF for an array of DecklistEntry structures without downloading
+/// any image (dry run): the image URLs in print order and the number of pages they fill
+#[unsafe(no_mangle)]
+pub extern "C" fn localhawk_plan_pages_from_entries(
+    entries: *const CDeclistEntry,
+    entry_count: usize,
+    out_urls: *mut *mut *mut c_char,
+    out_count: *mut usize,
+    out_page_count: *mut usize,
+) -> c_int {
+    if entries.is_null() || out_urls.is_null() || out_count.is_null() || out_page_count.is_null() {
+        return FFIError::NullPointer as c_int;
+    }
+
+    if entry_count == 0 {
+        return FFIError::InvalidInput as c_int;
+    }
+
+    let rust_entries = match unsafe { convert_entries_from_c_format(entries, entry_count) } {
+        Ok(rust_entries) => rust_entries,
+        Err(e) => return e as c_int,
+    };
+
+    let pages = match crate::ios_api::ProxyGenerator::plan_pages_from_entries_sync(
+        &rust_entries,
+        &crate::pdf::PdfOptions::default(),
+    ) {
+        Ok(pages) => pages,
+        Err(e) => {
+            tracing::error!("Failed to plan pages: {}", e);
+            return FFIError::ParseFailed as c_int;
+        }
+    };
+
+    let image_urls: Vec<String> = pages.iter().flatten().cloned().collect();
+    let result = unsafe { write_image_urls(&image_urls, out_urls, out_count) };
+    if result == FFIError::Success as c_int {
+        unsafe {
+            *out_page_count = pages.len();
+        }
+    }
+    result
+}
+
+/// Hand image URLs to C as an array of C strings, freed with localhawk_free_image_urls
+unsafe fn write_image_urls(
+    image_urls: &[String],
+    out_urls: *mut *mut *mut c_char,
+    out_count: *mut usize,
+) -> c_int {
     // Convert image URLs to C strings
     let mut c_urls = Vec::new();
-    for url in &image_urls {
+    for url in image_urls {
         match CString::new(url.as_str()) {
             Ok(c_str) => c_urls.push(c_str.into_raw()),
             Err(_) => {
@@ -2107,7 +2806,8 @@ pub extern "C" fn localhawk_expand_single_card(
     FFIError::Success as c_int
 }
 
-/// Free the image URLs array returned by localhawk_expand_single_card
+/// Free the image URLs array returned by localhawk_expand_single_card or
+/// localhawk_plan_pages_from_entries
 #[unsafe(no_mangle)]
 pub extern "C" fn localhawk_free_image_urls(urls: *mut *mut c_char, count: usize) {
     if !urls.is_null() {
@@ -1,18 +1,48 @@
 use crate::DoubleFaceMode;
-use crate::decklist::DecklistEntry;
+use crate::decklist::{DeckSection, DecklistEntry, ResolutionReport, comment_annotation};
+use crate::globals::set_display_name;
+use crate::i18n::{Text, tr, tr_with};
+use crate::scryfall::models::Card;
+use std::collections::HashSet;
 
 /// Build aligned text output: start with original decklist, replace successfully parsed lines
 /// Uses current parsed_cards state (which may have updated printings)
 pub fn build_aligned_parsed_output(input_text: &str, parsed_cards: &[DecklistEntry]) -> String {
+    build_aligned_parsed_output_with_prices(input_text, parsed_cards, &[])
+}
+
+/// Build aligned text output like [`build_aligned_parsed_output`], adding a price to each line
+/// `prices` is aligned with `parsed_cards`; entries without a known price show none
+pub fn build_aligned_parsed_output_with_prices(
+    input_text: &str,
+    parsed_cards: &[DecklistEntry],
+    prices: &[Option<String>],
+) -> String {
+    build_aligned_parsed_output_with_report(
+        input_text,
+        parsed_cards,
+        prices,
+        &ResolutionReport::default(),
+    )
+}
+
+/// Build aligned text output like [`build_aligned_parsed_output_with_prices`], replacing the
+/// lines in `report` with their annotation (starting with "✗")
+pub fn build_aligned_parsed_output_with_report(
+    input_text: &str,
+    parsed_cards: &[DecklistEntry],
+    prices: &[Option<String>],
+    report: &ResolutionReport,
+) -> String {
     let input_lines: Vec<&str> = input_text.lines().collect();
     let mut output_lines: Vec<String> = input_lines.iter().map(|line| line.to_string()).collect();
 
     // Replace lines where we successfully parsed something
-    for entry in parsed_cards {
+    for (entry_index, entry) in parsed_cards.iter().enumerate() {
         if let Some(line_num) = entry.source_line_number {
             if line_num < output_lines.len() {
                 let set_info = if let Some(set) = &entry.set {
-                    format!(" • Set: {}", set.to_uppercase())
+                    format!(" • Set: {}", set_display_name(set))
                 } else {
                     String::new()
                 };
@@ -26,22 +56,51 @@ pub fn build_aligned_parsed_output(input_text: &str, parsed_cards: &[DecklistEnt
                     DoubleFaceMode::BackOnly => " • Face: Back only".to_string(),
                     DoubleFaceMode::BothSides => " • Face: Both sides".to_string(),
                 };
+                let marks_info = format_marks(entry);
+                let price_info = match prices.get(entry_index) {
+                    Some(Some(price)) => format!(" • Price: {}", price),
+                    _ => String::new(),
+                };
 
                 output_lines[line_num] = format!(
-                    "✓ {}x {}{}{}{}",
-                    entry.multiple, entry.name, set_info, lang_info, face_info
+                    "✓ {}x {}{}{}{}{}{}",
+                    entry.multiple,
+                    entry.name,
+                    set_info,
+                    lang_info,
+                    face_info,
+                    marks_info,
+                    price_info
                 );
             }
         }
     }
 
+    // Comments that group entries (see `attach_comment_annotations`) become group headers
+    let annotations: HashSet<&str> = parsed_cards
+        .iter()
+        .filter_map(|entry| entry.annotation.as_deref())
+        .collect();
+    for (input_line, output_line) in input_lines.iter().zip(output_lines.iter_mut()) {
+        if let Some(text) = comment_annotation(input_line).filter(|text| annotations.contains(text))
+        {
+            *output_line = format!("── {} ──", text);
+        }
+    }
+
+    for (line_num, output_line) in output_lines.iter_mut().enumerate() {
+        if let Some(annotation) = report.annotation(line_num) {
+            *output_line = annotation;
+        }
+    }
+
     output_lines.join("\n")
 }
 
 /// Format a single decklist entry for display
 pub fn format_decklist_entry(entry: &DecklistEntry) -> String {
     let set_info = if let Some(set) = &entry.set {
-        format!(" • Set: {}", set.to_uppercase())
+        format!(" • Set: {}", set_display_name(set))
     } else {
         String::new()
     };
@@ -57,23 +116,302 @@ pub fn format_decklist_entry(entry: &DecklistEntry) -> String {
     };
 
     format!(
-        "{}x {}{}{}{}",
-        entry.multiple, entry.name, set_info, lang_info, face_info
+        "{}x {}{}{}{}{}",
+        entry.multiple,
+        entry.name,
+        set_info,
+        lang_info,
+        face_info,
+        format_marks(entry)
     )
 }
 
+/// Foil marker and tags carried over from the exporting tool
+fn format_marks(entry: &DecklistEntry) -> String {
+    let mut marks = String::new();
+    if entry.foil {
+        marks.push_str(" • Foil");
+    }
+    if !entry.tags.is_empty() {
+        marks.push_str(&format!(" • Tags: {}", entry.tags.join(", ")));
+    }
+    marks
+}
+
 /// Format multiple entries as a summary
 pub fn format_entries_summary(entries: &[DecklistEntry]) -> String {
     if entries.is_empty() {
-        return "No cards".to_string();
+        return tr(Text::NoCards).to_string();
     }
 
-    let total_cards: u32 = entries.iter().map(|e| e.multiple as u32).sum();
+    let total_cards: u32 = entries.iter().map(|e| e.multiple.get()).sum();
     let unique_cards = entries.len();
 
-    if unique_cards == 1 {
-        format!("{} cards (1 unique)", total_cards)
+    tr_with(Text::CardsSummary, &[&total_cards, &unique_cards])
+}
+
+/// Format a resolved card as a line naming its exact printing, e.g.
+/// "4 Lightning Bolt (2XM) 129 [en]", which parses back to the same printing
+///
+/// Cards printed back face only are named by their back face, so they resolve to it again.
+pub fn format_normalized_line(card: &Card, copies: u32, face_mode: &DoubleFaceMode) -> String {
+    let name = match face_mode {
+        DoubleFaceMode::BackOnly if !card.is_meld_card() => {
+            card.back_side_name().unwrap_or(&card.name)
+        }
+        _ => &card.name,
+    };
+    let number = card
+        .collector_number
+        .as_ref()
+        .map(|number| format!(" {}", number))
+        .unwrap_or_default();
+    format!(
+        "{} {} ({}){} [{}]",
+        copies,
+        name,
+        card.set.to_uppercase(),
+        number,
+        card.language
+    )
+}
+
+/// Format resolved cards as a decklist of [`format_normalized_line`] lines, to share the deck
+/// or load the same printings again later
+///
+/// Commanders and companions come first and the sideboard and tokens last, each under its
+/// header; the main deck only gets a "Deck" header when there are other sections.
+pub fn format_normalized_decklist(cards: &[(DeckSection, Card, u32, DoubleFaceMode)]) -> String {
+    let sections = [
+        DeckSection::Commander,
+        DeckSection::Companion,
+        DeckSection::Main,
+        DeckSection::Sideboard,
+        DeckSection::Tokens,
+    ];
+    let only_main = cards
+        .iter()
+        .all(|(section, _, _, _)| *section == DeckSection::Main);
+
+    let mut blocks = Vec::new();
+    for section in sections {
+        let mut lines: Vec<String> = cards
+            .iter()
+            .filter(|(card_section, _, _, _)| *card_section == section)
+            .map(|(_, card, copies, face_mode)| format_normalized_line(card, *copies, face_mode))
+            .collect();
+        if lines.is_empty() {
+            continue;
+        }
+        if !only_main {
+            lines.insert(0, section.header().to_string());
+        }
+        blocks.push(lines.join("\n"));
+    }
+    blocks.join("\n\n")
+}
+
+/// Format the estimated cost of the real cards, counting USD prices only
+pub fn format_price_total(cards: &[(Card, u32)]) -> String {
+    let mut total = 0.0;
+    let mut unpriced = 0;
+    for (card, quantity) in cards {
+        match card.prices.as_ref().and_then(|prices| prices.usd_value()) {
+            Some(price) => total += price * *quantity as f64,
+            None => unpriced += quantity,
+        }
+    }
+
+    let total = format!("{:.2}", total);
+    if unpriced == 0 {
+        tr_with(Text::EstimatedPrice, &[&total])
     } else {
-        format!("{} cards ({} unique)", total_cards, unique_cards)
+        tr_with(Text::EstimatedPriceWithUnpriced, &[&total, &unpriced])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scryfall::models::CardPrices;
+    use std::num::NonZeroU32;
+
+    fn card_with_usd(name: &str, usd: Option<&str>) -> Card {
+        Card {
+            name: name.to_string(),
+            set: "m21".to_string(),
+            language: "en".to_string(),
+            border_crop: format!("https://example.com/{}.jpg", name),
+            back_side: None,
+            frame: None,
+            digital: false,
+            collector_number: None,
+            prices: usd.map(|usd| CardPrices {
+                usd: Some(usd.to_string()),
+                ..Default::default()
+            }),
+            legalities: Default::default(),
+            layout: None,
+            scryfall_id: None,
+            oracle_id: None,
+            mana_cost: None,
+            type_line: None,
+            oracle_text: None,
+            power_toughness: None,
+        }
+    }
+
+    #[test]
+    fn test_aligned_output_with_prices() {
+        let mut bolt = DecklistEntry::new(
+            NonZeroU32::new(4).unwrap(),
+            "lightning bolt",
+            Some("m21"),
+            None,
+        );
+        bolt.source_line_number = Some(0);
+        let mut island = DecklistEntry::from_name("island");
+        island.source_line_number = Some(2);
+
+        let output = build_aligned_parsed_output_with_prices(
+            "4 bolt\n// comment\nisland",
+            &[bolt, island],
+            &[Some("$1.25".to_string()), None],
+        );
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(
+            lines[0],
+            "✓ 4x lightning bolt • Set: M21 • Face: Both sides • Price: $1.25"
+        );
+        assert_eq!(lines[1], "// comment");
+        assert_eq!(lines[2], "✓ 1x island • Face: Both sides");
+    }
+
+    #[test]
+    fn test_aligned_output_with_group_headers() {
+        let mut bolt = DecklistEntry::from_name("lightning bolt");
+        bolt.source_line_number = Some(1);
+        bolt.annotation = Some("burn".to_string());
+
+        let output = build_aligned_parsed_output("// burn\nlightning bolt\n// unused", &[bolt]);
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines[0], "── burn ──");
+        assert_eq!(lines[2], "// unused");
+    }
+
+    #[test]
+    fn test_aligned_output_with_report() {
+        let mut bolt = DecklistEntry::from_name("lighning blot");
+        bolt.source_line_number = Some(0);
+        let mut report = ResolutionReport::default();
+        report.add_unresolved(&bolt, None);
+
+        let output =
+            build_aligned_parsed_output_with_report("lighning blot", &[bolt], &[], &report);
+        assert_eq!(output, "✗ could not resolve 'lighning blot'");
+    }
+
+    #[test]
+    fn test_format_entry_with_foil_and_tags() {
+        let mut entry = DecklistEntry::new(NonZeroU32::MIN, "sol ring", Some("c21"), None);
+        entry.foil = true;
+        entry.tags = vec!["ramp".to_string(), "staples".to_string()];
+        assert_eq!(
+            format_decklist_entry(&entry),
+            "1x sol ring • Set: C21 • Face: Both sides • Foil • Tags: ramp, staples"
+        );
+    }
+
+    #[test]
+    fn test_normalized_decklist_parses_back() {
+        let mut bolt = card_with_usd("Lightning Bolt", None);
+        bolt.set = "2xm".to_string();
+        bolt.collector_number = Some("129".to_string());
+        let mut commander = card_with_usd("Kaalia of the Vast", None);
+        commander.set = "cmd".to_string();
+        commander.language = "ja".to_string();
+        commander.collector_number = Some("197".to_string());
+        let mut pathway = card_with_usd("Branchloft Pathway // Boulderloft Pathway", None);
+        pathway.set = "znr".to_string();
+        pathway.collector_number = Some("261".to_string());
+        pathway.back_side = Some(crate::scryfall::models::BackSide::DfcBack {
+            image_url: "https://example.com/back.jpg".to_string(),
+            name: "Boulderloft Pathway".to_string(),
+        });
+
+        let decklist = format_normalized_decklist(&[
+            (DeckSection::Main, bolt, 4, DoubleFaceMode::BothSides),
+            (DeckSection::Main, pathway, 1, DoubleFaceMode::BackOnly),
+            (
+                DeckSection::Commander,
+                commander,
+                1,
+                DoubleFaceMode::BothSides,
+            ),
+        ]);
+        assert_eq!(
+            decklist,
+            "Commander\n1 Kaalia of the Vast (CMD) 197 [ja]\n\n\
+             Deck\n4 Lightning Bolt (2XM) 129 [en]\n1 Boulderloft Pathway (ZNR) 261 [en]"
+        );
+
+        let languages = crate::scryfall::models::get_minimal_scryfall_languages();
+        let entries: Vec<DecklistEntry> =
+            crate::decklist::parse_decklist(&decklist, &languages, &Default::default())
+                .iter()
+                .filter_map(|line| line.as_entry())
+                .collect();
+        let summary: Vec<(&str, Option<&str>, Option<&str>, Option<&str>, DeckSection)> = entries
+            .iter()
+            .map(|entry| {
+                (
+                    entry.name.as_str(),
+                    entry.set.as_deref(),
+                    entry.collector_number.as_deref(),
+                    entry.lang.as_deref(),
+                    entry.section,
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (
+                    "Kaalia of the Vast",
+                    Some("cmd"),
+                    Some("197"),
+                    Some("ja"),
+                    DeckSection::Commander
+                ),
+                (
+                    "Lightning Bolt",
+                    Some("2xm"),
+                    Some("129"),
+                    Some("en"),
+                    DeckSection::Main
+                ),
+                (
+                    "Boulderloft Pathway",
+                    Some("znr"),
+                    Some("261"),
+                    Some("en"),
+                    DeckSection::Main
+                ),
+            ]
+        );
+        assert!(entries.iter().all(|entry| entry.tags.is_empty()));
+    }
+
+    #[test]
+    fn test_price_total() {
+        let cards = vec![
+            (card_with_usd("bolt", Some("1.25")), 4),
+            (card_with_usd("island", None), 10),
+        ];
+        assert_eq!(
+            format_price_total(&cards),
+            "Estimated price: $5.00 (10 cards without a USD price)"
+        );
+        assert_eq!(format_price_total(&cards[..1]), "Estimated price: $5.00");
     }
 }
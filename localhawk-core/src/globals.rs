@@ -1,11 +1,38 @@
-use crate::cache::{LruImageCache, LruSearchCache, create_image_cache, create_search_cache};
+use crate::cache::{
+    EvictionPolicy, HttpValidators, ImageArchiveImport, LruCache, LruImageCache, LruSearchCache,
+    LruThumbnailCache, StorageStrategy, create_image_cache, create_image_cache_with_config,
+    create_query_cache, create_search_cache, create_thumbnail_cache, export_image_cache,
+    import_image_cache, make_thumbnail,
+};
+use crate::cache_contents::{ImageCacheContents, image_cache_contents};
+use crate::cache_logic::{
+    CacheRefreshStatus, FreshnessPolicy, process_card_names_into_lookup,
+    process_set_codes_into_hashset,
+};
+use crate::history::DecklistHistory;
+use crate::pdf::placeholder_back_image_bytes;
+use crate::pinned_decks::PinnedDecks;
+use crate::scryfall::ScryfallCardNames;
+use crate::scryfall::client::ConditionalImage;
+use crate::scryfall::models::{PLACEHOLDER_BACK_URL, SetInfo};
+use crate::set_codes_cache::newest_new_set_release;
+use crate::set_icon_cache::SetIconCache;
+use crate::settings::Settings;
+use crate::single_flight::SingleFlight;
+use crate::stats::UsageStats;
 use crate::{
-    CardNameCache, CardNameLookup, NameLookupResult, ProxyError, ScryfallClient, SetCodesCache,
+    BackFaceFallback, Card, CardNameCache, CardNameLookup, DecklistEntry, NameLookupResult,
+    ProxyError, ScryfallClient, SetCodesCache,
 };
 use directories::ProjectDirs;
-use std::collections::HashSet;
-use std::sync::{Arc, OnceLock, RwLock};
-use tracing::{debug, info, error};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Condvar, Mutex, OnceLock, RwLock};
+use time::OffsetDateTime;
+use tokio::sync::watch;
+use tracing::{debug, error, info, warn};
 
 // Memory size estimation constants for cache statistics
 
@@ -16,11 +43,51 @@ const FUZZY_INDEX_OVERHEAD_FACTOR: u64 = 4; // Fuzzy index adds 4x overhead for
 // Global singletons - initialized once, shared everywhere
 static SCRYFALL_CLIENT: OnceLock<ScryfallClient> = OnceLock::new();
 static IMAGE_CACHE: OnceLock<Arc<RwLock<LruImageCache>>> = OnceLock::new();
+static THUMBNAIL_CACHE: OnceLock<Arc<RwLock<LruThumbnailCache>>> = OnceLock::new();
 static CARD_LOOKUP: OnceLock<Arc<RwLock<Option<CardNameLookup>>>> = OnceLock::new();
 static SEARCH_RESULTS_CACHE: OnceLock<Arc<RwLock<LruSearchCache>>> = OnceLock::new();
+static QUERY_RESULTS_CACHE: OnceLock<Arc<RwLock<LruSearchCache>>> = OnceLock::new();
 static SET_CODES_CACHE: OnceLock<Arc<RwLock<Option<HashSet<String>>>>> = OnceLock::new();
+static SET_ICON_URIS: OnceLock<Arc<RwLock<BTreeMap<String, String>>>> = OnceLock::new();
+static SET_INFOS: OnceLock<Arc<RwLock<BTreeMap<String, SetInfo>>>> = OnceLock::new();
+static SET_ICONS: OnceLock<Arc<RwLock<HashMap<String, Vec<u8>>>>> = OnceLock::new();
+static IMAGE_FETCHES: OnceLock<SingleFlight<Vec<u8>>> = OnceLock::new();
+static SEARCH_FETCHES: OnceLock<SingleFlight<crate::scryfall::CardSearchResult>> = OnceLock::new();
+static QUERY_FETCHES: OnceLock<SingleFlight<crate::scryfall::CardSearchResult>> = OnceLock::new();
 static CARD_NAME_CACHE_INFO: OnceLock<Arc<RwLock<Option<(time::OffsetDateTime, usize)>>>> =
     OnceLock::new();
+static FRESHNESS_POLICY: OnceLock<Arc<RwLock<FreshnessPolicy>>> = OnceLock::new();
+static BACK_FACE_FALLBACK: OnceLock<Arc<RwLock<BackFaceFallback>>> = OnceLock::new();
+static DEFAULT_PRINTING: OnceLock<Arc<RwLock<bool>>> = OnceLock::new();
+static COMMENT_ANNOTATIONS: OnceLock<Arc<RwLock<bool>>> = OnceLock::new();
+static PREFERRED_LANGUAGE: OnceLock<Arc<RwLock<Option<String>>>> = OnceLock::new();
+/// Set codes whose arts the copies of basic lands are spread across
+static BASIC_LAND_SETS: OnceLock<Arc<RwLock<Vec<String>>>> = OnceLock::new();
+/// Scryfall ids of the user's favorite printings, keyed by oracle id
+static FAVORITE_PRINTINGS: OnceLock<Arc<RwLock<BTreeMap<String, Vec<String>>>>> = OnceLock::new();
+/// Cache directory chosen by the user, None for the platform cache directory
+static CACHE_DIRECTORY: OnceLock<Arc<RwLock<Option<PathBuf>>>> = OnceLock::new();
+/// Size limit of the image cache in MB, None for the default
+static IMAGE_CACHE_MAX_SIZE_MB: OnceLock<Arc<RwLock<Option<u64>>>> = OnceLock::new();
+/// Which images the image cache evicts when it is full
+static IMAGE_CACHE_EVICTION: OnceLock<Arc<RwLock<EvictionPolicy>>> = OnceLock::new();
+/// Whether this process only reads the caches, see [`set_read_only_caches`]
+static READ_ONLY_CACHES: OnceLock<Arc<RwLock<bool>>> = OnceLock::new();
+/// Whether Scryfall answered its last API call with a maintenance page
+static SCRYFALL_UNAVAILABLE: OnceLock<Arc<RwLock<bool>>> = OnceLock::new();
+/// Download rate limit in KB/s, None for unlimited
+static DOWNLOAD_RATE_LIMIT: OnceLock<Arc<RwLock<Option<u64>>>> = OnceLock::new();
+/// Scryfall id of the default printing by card name, None for names Scryfall doesn't know
+static DEFAULT_PRINTING_IDS: OnceLock<Arc<RwLock<HashMap<String, Option<String>>>>> =
+    OnceLock::new();
+static CACHE_REFRESH_STATUS: OnceLock<watch::Sender<CacheRefreshStatus>> = OnceLock::new();
+/// Whether the card name lookup is being built in the background, see [`wait_for_card_lookup`]
+static CARD_LOOKUP_BUILDING: OnceLock<watch::Sender<bool>> = OnceLock::new();
+/// Notified when the card name lookup built in the background is installed, see
+/// [`wait_for_card_lookup_sync`]
+static CARD_LOOKUP_BUILT: (Mutex<()>, Condvar) = (Mutex::new(()), Condvar::new());
+static SETTINGS: OnceLock<Arc<RwLock<Settings>>> = OnceLock::new();
+static USAGE_STATS: OnceLock<Arc<RwLock<UsageStats>>> = OnceLock::new();
 
 pub fn get_scryfall_client() -> &'static ScryfallClient {
     SCRYFALL_CLIENT.get_or_init(|| ScryfallClient::new().expect("Failed to create ScryfallClient"))
@@ -42,8 +109,12 @@ pub fn initialize_caches_sync() -> Result<(), ProxyError> {
     let _search_cache = get_search_results_cache();
     info!("Search results cache initialized");
 
+    // Load card names and set codes from disk even if stale, so startup never waits on a refresh
+    let card_names_cached_at = load_stale_card_lookup_sync()?;
+    let set_codes_cached_at = load_stale_set_codes_sync()?;
+
     // Ensure card name lookup is available (essential for fuzzy matching)
-    // This will fetch from network if disk cache is missing/expired
+    // This will fetch from network if disk cache is missing
     match ProxyGenerator::ensure_card_lookup_initialized_sync() {
         Ok(_) => info!("Card name lookup initialized"),
         Err(e) => {
@@ -53,7 +124,7 @@ pub fn initialize_caches_sync() -> Result<(), ProxyError> {
     }
 
     // Ensure set codes are available (essential for decklist parsing)
-    // This will fetch from network if disk cache is missing/expired  
+    // This will fetch from network if disk cache is missing
     match ProxyGenerator::ensure_set_codes_initialized_sync() {
         Ok(_) => info!("Set codes initialized"),
         Err(e) => {
@@ -62,27 +133,261 @@ pub fn initialize_caches_sync() -> Result<(), ProxyError> {
         }
     }
 
+    let policy = get_freshness_policy();
+    let refresh_card_names = card_names_cached_at.is_some_and(|at| policy.needs_refresh(at));
+    let refresh_set_codes = set_codes_cached_at.is_some_and(|at| policy.needs_refresh(at));
+    if refresh_card_names || refresh_set_codes {
+        start_background_cache_refresh_sync(refresh_card_names, refresh_set_codes);
+    }
+
     info!("Cache initialization complete - all essential data available");
     Ok(())
 }
 
+/// Load card names from disk regardless of age and build their lookup in the background
+/// (iOS sync version)
+/// Returns when the data was cached, or None if nothing was loaded
+#[cfg(feature = "ios")]
+fn load_stale_card_lookup_sync() -> Result<Option<OffsetDateTime>, ProxyError> {
+    if is_card_lookup_ready() || is_card_lookup_building() {
+        return Ok(None);
+    }
+
+    let Some((card_names, cached_at)) = crate::ios_cache::load_stale_card_names_sync()? else {
+        return Ok(None);
+    };
+
+    *get_card_name_cache_info_ref().write().unwrap() =
+        card_names.date.map(|date| (date, card_names.names.len()));
+    get_card_lookup_building_sender().send_replace(true);
+    std::thread::spawn(move || finish_deferred_card_lookup(&card_names));
+    Ok(Some(cached_at))
+}
+
+/// Install set codes from disk regardless of age (iOS sync version)
+/// Returns when the data was cached, or None if nothing was loaded
+#[cfg(feature = "ios")]
+fn load_stale_set_codes_sync() -> Result<Option<OffsetDateTime>, ProxyError> {
+    let set_codes_ref = get_set_codes_cache();
+    if set_codes_ref.read().unwrap().is_some() {
+        return Ok(None);
+    }
+
+    let Some((codes_set, cached_at)) = crate::ios_cache::load_stale_set_codes_sync()? else {
+        return Ok(None);
+    };
+
+    *set_codes_ref.write().unwrap() = Some(codes_set);
+    Ok(Some(cached_at))
+}
+
+/// Refresh stale card names and/or set codes on a background thread (iOS sync version)
+#[cfg(feature = "ios")]
+fn start_background_cache_refresh_sync(card_names: bool, set_codes: bool) {
+    info!(
+        card_names,
+        set_codes, "Starting background refresh of stale caches"
+    );
+    set_cache_refresh_status(CacheRefreshStatus::Refreshing {
+        card_names,
+        set_codes,
+    });
+
+    std::thread::spawn(move || {
+        let mut errors = Vec::new();
+
+        if let Some(Err(e)) = card_names.then(force_update_card_lookup_sync) {
+            errors.push(format!("card names: {}", e));
+        }
+        if let Some(Err(e)) = set_codes.then(force_update_set_codes_sync) {
+            errors.push(format!("set codes: {}", e));
+        }
+
+        finish_background_cache_refresh(errors);
+    });
+}
+
+/// Fetch fresh card names from the API and install their lookup (iOS sync version)
+#[cfg(feature = "ios")]
+pub fn force_update_card_lookup_sync() -> Result<(), ProxyError> {
+    info!("Force updating CardNameLookup from Scryfall API");
+    let client = crate::http_client::UreqHttpClient::new()?;
+    let (lookup, cache_info) = crate::ios_cache::refresh_card_lookup_sync(&client)?;
+    *get_card_lookup().write().unwrap() = Some(lookup);
+    *get_card_name_cache_info_ref().write().unwrap() = cache_info;
+    Ok(())
+}
+
+/// Fetch fresh set codes from the API and install them (iOS sync version)
+#[cfg(feature = "ios")]
+pub fn force_update_set_codes_sync() -> Result<(), ProxyError> {
+    info!("Force updating set codes from Scryfall API");
+    let client = crate::http_client::UreqHttpClient::new()?;
+    let codes_set = crate::ios_cache::refresh_set_codes_sync(&client)?;
+    *get_set_codes_cache().write().unwrap() = Some(codes_set);
+    Ok(())
+}
+
+/// When the card names and set codes on disk were fetched, None for data that isn't cached
+/// (iOS sync version)
+#[cfg(feature = "ios")]
+pub fn get_cache_dates_sync() -> Result<(Option<OffsetDateTime>, Option<OffsetDateTime>), ProxyError>
+{
+    let card_names = crate::ios_cache::load_stale_card_names_sync()?;
+    let set_codes = crate::ios_cache::load_stale_set_codes_sync()?;
+    Ok((
+        card_names.map(|(_, cached_at)| cached_at),
+        set_codes.map(|(_, cached_at)| cached_at),
+    ))
+}
+
 pub fn get_image_cache() -> &'static Arc<RwLock<LruImageCache>> {
     IMAGE_CACHE.get_or_init(|| {
-        Arc::new(RwLock::new(
-            create_image_cache().expect("Failed to initialize LRU image cache"),
-        ))
+        let cache = match get_image_cache_max_size_mb() {
+            Some(max_size_mb) => create_image_cache_with_config(None, max_size_mb * 1024 * 1024),
+            None => create_image_cache(),
+        };
+        let mut cache = read_only_if_enabled(cache.expect("Failed to initialize LRU image cache"));
+        cache.set_eviction_policy(get_image_cache_eviction_policy());
+        cache.pin(get_pinned_decks().urls());
+        Arc::new(RwLock::new(cache))
     })
 }
 
+/// Thumbnails of cached images, created on first use (only the GUI shows them)
+pub fn get_thumbnail_cache() -> &'static Arc<RwLock<LruThumbnailCache>> {
+    THUMBNAIL_CACHE.get_or_init(|| {
+        Arc::new(RwLock::new(read_only_if_enabled(
+            create_thumbnail_cache().expect("Failed to initialize thumbnail cache"),
+        )))
+    })
+}
+
+/// Size limit of the image cache set with [`set_image_cache_max_size_mb`], None for the default
+pub fn get_image_cache_max_size_mb() -> Option<u64> {
+    let max_size_ref = IMAGE_CACHE_MAX_SIZE_MB.get_or_init(|| Arc::new(RwLock::new(None)));
+    *max_size_ref.read().unwrap()
+}
+
+/// Limit the image cache to `max_size_mb` (None = the default of 1000 MB), e.g. on phones
+///
+/// Call before the caches are first used.
+pub fn set_image_cache_max_size_mb(max_size_mb: Option<u64>) {
+    let max_size_ref = IMAGE_CACHE_MAX_SIZE_MB.get_or_init(|| Arc::new(RwLock::new(None)));
+    *max_size_ref.write().unwrap() = max_size_mb.filter(|&max_size_mb| max_size_mb > 0);
+}
+
+/// Eviction policy of the image cache set with [`set_image_cache_eviction_policy`]
+pub fn get_image_cache_eviction_policy() -> EvictionPolicy {
+    let policy_ref =
+        IMAGE_CACHE_EVICTION.get_or_init(|| Arc::new(RwLock::new(EvictionPolicy::default())));
+    *policy_ref.read().unwrap()
+}
+
+/// Choose which images the image cache evicts when it is full; applies right away
+pub fn set_image_cache_eviction_policy(policy: EvictionPolicy) {
+    let policy_ref =
+        IMAGE_CACHE_EVICTION.get_or_init(|| Arc::new(RwLock::new(EvictionPolicy::default())));
+    *policy_ref.write().unwrap() = policy;
+    if let Some(cache) = IMAGE_CACHE.get() {
+        cache.write().unwrap().set_eviction_policy(policy);
+    }
+}
+
 pub fn get_card_lookup() -> &'static Arc<RwLock<Option<CardNameLookup>>> {
     CARD_LOOKUP.get_or_init(|| Arc::new(RwLock::new(None)))
 }
 
+fn get_card_lookup_building_sender() -> &'static watch::Sender<bool> {
+    CARD_LOOKUP_BUILDING.get_or_init(|| watch::channel(false).0)
+}
+
+/// Whether fuzzy card name matching is available (false while the lookup is still being built)
+pub fn is_card_lookup_ready() -> bool {
+    get_card_lookup().read().unwrap().is_some()
+}
+
+fn is_card_lookup_building() -> bool {
+    *get_card_lookup_building_sender().borrow()
+}
+
+/// Wait until the card name lookup being built in the background is installed (returns
+/// immediately if none is being built)
+pub async fn wait_for_card_lookup() {
+    let mut building_rx = get_card_lookup_building_sender().subscribe();
+    loop {
+        let building = *building_rx.borrow_and_update();
+        if !building || building_rx.changed().await.is_err() {
+            return;
+        }
+    }
+}
+
+/// Blocking version of `wait_for_card_lookup` (iOS sync version)
+#[cfg(feature = "ios")]
+pub fn wait_for_card_lookup_sync() {
+    let (lock, built) = &CARD_LOOKUP_BUILT;
+    let guard = lock.lock().unwrap();
+    let _guard = built
+        .wait_while(guard, |_| is_card_lookup_building())
+        .unwrap();
+}
+
+/// Load or build the fuzzy index for card names loaded at startup and install it, unless
+/// fresher names were installed in the meantime
+///
+/// Building the index for all card names takes a while, so startup hands this to another
+/// thread instead of waiting for it.
+fn finish_deferred_card_lookup(card_names: &ScryfallCardNames) {
+    let lookup = load_or_build_card_lookup(card_names);
+    {
+        let mut lookup_guard = get_card_lookup().write().unwrap();
+        if lookup_guard.is_none() {
+            *lookup_guard = Some(lookup);
+        }
+    }
+    get_card_lookup_building_sender().send_replace(false);
+    // Under the lock, so a blocking waiter either sees the flag cleared or is notified
+    let _guard = CARD_LOOKUP_BUILT.0.lock().unwrap();
+    CARD_LOOKUP_BUILT.1.notify_all();
+}
+
+/// The fuzzy index of `card_names`: the one saved next to them if it was built from these
+/// names, otherwise built now and saved for the next startup
+fn load_or_build_card_lookup(card_names: &ScryfallCardNames) -> CardNameLookup {
+    let cache = CardNameCache::new().ok();
+    if let Some(lookup) = cache
+        .as_ref()
+        .and_then(|cache| cache.load_lookup(card_names))
+    {
+        return lookup;
+    }
+
+    let lookup = process_card_names_into_lookup(card_names);
+    let saved = match cache {
+        Some(cache) if !get_read_only_caches() => cache.save_lookup(card_names, &lookup),
+        _ => Ok(()),
+    };
+    if let Err(e) = saved {
+        warn!(error = %e, "Failed to save card name index");
+    }
+    lookup
+}
+
 pub fn get_search_results_cache() -> &'static Arc<RwLock<LruSearchCache>> {
     SEARCH_RESULTS_CACHE.get_or_init(|| {
-        Arc::new(RwLock::new(
+        Arc::new(RwLock::new(read_only_if_enabled(
             create_search_cache().expect("Failed to initialize LRU search results cache"),
-        ))
+        )))
+    })
+}
+
+/// Cache of raw Scryfall query results, separate from the card name searches
+pub fn get_query_results_cache() -> &'static Arc<RwLock<LruSearchCache>> {
+    QUERY_RESULTS_CACHE.get_or_init(|| {
+        Arc::new(RwLock::new(read_only_if_enabled(
+            create_query_cache().expect("Failed to initialize LRU query results cache"),
+        )))
     })
 }
 
@@ -90,11 +395,413 @@ pub fn get_set_codes_cache() -> &'static Arc<RwLock<Option<HashSet<String>>>> {
     SET_CODES_CACHE.get_or_init(|| Arc::new(RwLock::new(None)))
 }
 
+fn get_set_icon_uris() -> &'static Arc<RwLock<BTreeMap<String, String>>> {
+    SET_ICON_URIS.get_or_init(|| Arc::new(RwLock::new(BTreeMap::new())))
+}
+
+fn get_set_infos() -> &'static Arc<RwLock<BTreeMap<String, SetInfo>>> {
+    SET_INFOS.get_or_init(|| Arc::new(RwLock::new(BTreeMap::new())))
+}
+
+/// Name, release date and type of a set, if known from the loaded set codes
+pub fn get_set_info(set_code: &str) -> Option<SetInfo> {
+    get_set_infos()
+        .read()
+        .unwrap()
+        .get(&set_code.to_lowercase())
+        .cloned()
+}
+
+/// All known sets by lowercase code, newest first
+pub fn get_sets() -> Vec<(String, SetInfo)> {
+    let mut sets: Vec<(String, SetInfo)> = get_set_infos()
+        .read()
+        .unwrap()
+        .iter()
+        .map(|(code, info)| (code.clone(), info.clone()))
+        .collect();
+    // Dates are YYYY-MM-DD, so they sort as text; sets without one go last
+    sets.sort_by(|(_, left), (_, right)| right.released_at.cmp(&left.released_at));
+    sets
+}
+
+/// Set code with the set's name for display, e.g. "RNA – Ravnica Allegiance"; just the code
+/// while the name is unknown
+pub fn set_display_name(set_code: &str) -> String {
+    match get_set_info(set_code) {
+        Some(info) => format!("{} – {}", set_code.to_uppercase(), info.name),
+        None => set_code.to_uppercase(),
+    }
+}
+
+fn get_set_icons() -> &'static Arc<RwLock<HashMap<String, Vec<u8>>>> {
+    SET_ICONS.get_or_init(|| Arc::new(RwLock::new(HashMap::new())))
+}
+
 pub fn get_card_name_cache_info_ref() -> &'static Arc<RwLock<Option<(time::OffsetDateTime, usize)>>>
 {
     CARD_NAME_CACHE_INFO.get_or_init(|| Arc::new(RwLock::new(None)))
 }
 
+fn get_cache_refresh_status_sender() -> &'static watch::Sender<CacheRefreshStatus> {
+    CACHE_REFRESH_STATUS.get_or_init(|| watch::channel(CacheRefreshStatus::Idle).0)
+}
+
+/// Get the policy deciding when card names and set codes are refreshed automatically
+pub fn get_freshness_policy() -> FreshnessPolicy {
+    let policy_ref =
+        FRESHNESS_POLICY.get_or_init(|| Arc::new(RwLock::new(FreshnessPolicy::default())));
+    *policy_ref.read().unwrap()
+}
+
+/// Replace the freshness policy (takes effect on the next staleness check)
+pub fn set_freshness_policy(policy: FreshnessPolicy) {
+    let policy_ref =
+        FRESHNESS_POLICY.get_or_init(|| Arc::new(RwLock::new(FreshnessPolicy::default())));
+    *policy_ref.write().unwrap() = policy;
+}
+
+/// Get what is printed for back faces whose image is unavailable
+pub fn get_back_face_fallback() -> BackFaceFallback {
+    let fallback_ref =
+        BACK_FACE_FALLBACK.get_or_init(|| Arc::new(RwLock::new(BackFaceFallback::default())));
+    *fallback_ref.read().unwrap()
+}
+
+/// Replace what is printed for back faces whose image is unavailable
+pub fn set_back_face_fallback(fallback: BackFaceFallback) {
+    let fallback_ref =
+        BACK_FACE_FALLBACK.get_or_init(|| Arc::new(RwLock::new(BackFaceFallback::default())));
+    *fallback_ref.write().unwrap() = fallback;
+}
+
+/// Whether cards without a set or language resolve to the printing scryfall.com shows
+pub fn get_default_printing() -> bool {
+    let default_printing_ref = DEFAULT_PRINTING.get_or_init(|| Arc::new(RwLock::new(false)));
+    *default_printing_ref.read().unwrap()
+}
+
+/// Choose whether cards without a set or language resolve to the printing scryfall.com shows
+pub fn set_default_printing(enabled: bool) {
+    let default_printing_ref = DEFAULT_PRINTING.get_or_init(|| Arc::new(RwLock::new(false)));
+    *default_printing_ref.write().unwrap() = enabled;
+}
+
+/// Whether comment lines like "// lands" group the decklist entries after them
+pub fn get_comment_annotations() -> bool {
+    let annotations_ref = COMMENT_ANNOTATIONS.get_or_init(|| Arc::new(RwLock::new(false)));
+    *annotations_ref.read().unwrap()
+}
+
+/// Choose whether comment lines like "// lands" group the decklist entries after them
+pub fn set_comment_annotations(enabled: bool) {
+    let annotations_ref = COMMENT_ANNOTATIONS.get_or_init(|| Arc::new(RwLock::new(false)));
+    *annotations_ref.write().unwrap() = enabled;
+}
+
+/// Language whose printings are preferred for cards without a language (None = English)
+pub fn get_preferred_language() -> Option<String> {
+    let language_ref = PREFERRED_LANGUAGE.get_or_init(|| Arc::new(RwLock::new(None)));
+    language_ref.read().unwrap().clone()
+}
+
+/// Prefer printings in `language` (a Scryfall code like "ja") for cards without a language;
+/// cards without a printing in that language fall back to English
+pub fn set_preferred_language(language: Option<String>) {
+    let language_ref = PREFERRED_LANGUAGE.get_or_init(|| Arc::new(RwLock::new(None)));
+    *language_ref.write().unwrap() = language.map(|language| language.to_lowercase());
+}
+
+/// Set codes whose arts the copies of basic lands without a set are spread across (empty =
+/// all copies use one printing)
+pub fn get_basic_land_sets() -> Vec<String> {
+    let sets_ref = BASIC_LAND_SETS.get_or_init(|| Arc::new(RwLock::new(Vec::new())));
+    sets_ref.read().unwrap().clone()
+}
+
+/// Spread the copies of basic lands without a set across their different arts in these sets,
+/// e.g. ["unf"] or ["zen", "bfz"]; empty turns it off
+pub fn set_basic_land_sets(sets: Vec<String>) {
+    let sets_ref = BASIC_LAND_SETS.get_or_init(|| Arc::new(RwLock::new(Vec::new())));
+    *sets_ref.write().unwrap() = sets
+        .iter()
+        .map(|set| set.trim().to_lowercase())
+        .filter(|set| !set.is_empty())
+        .collect();
+}
+
+fn get_favorite_printings_ref() -> &'static Arc<RwLock<BTreeMap<String, Vec<String>>>> {
+    FAVORITE_PRINTINGS.get_or_init(|| Arc::new(RwLock::new(BTreeMap::new())))
+}
+
+/// Scryfall ids of the favorite printings, keyed by the oracle id of their card
+pub fn get_favorite_printings() -> BTreeMap<String, Vec<String>> {
+    get_favorite_printings_ref().read().unwrap().clone()
+}
+
+/// Replace the favorite printings, e.g. with the ones saved in the settings
+pub fn set_favorite_printings(favorites: BTreeMap<String, Vec<String>>) {
+    *get_favorite_printings_ref().write().unwrap() = favorites;
+}
+
+/// Whether `card` is one of the favorite printings
+pub fn is_favorite_printing(card: &Card) -> bool {
+    let (Some(oracle_id), Some(scryfall_id)) = (&card.oracle_id, &card.scryfall_id) else {
+        return false;
+    };
+    get_favorite_printings_ref()
+        .read()
+        .unwrap()
+        .get(oracle_id)
+        .is_some_and(|ids| ids.contains(scryfall_id))
+}
+
+/// Add `card` to the favorite printings, or remove it if it is one already, returning all
+/// favorites for saving in the settings
+///
+/// Printings without an oracle id or Scryfall id can't be favorites and change nothing.
+pub fn toggle_favorite_printing(card: &Card) -> BTreeMap<String, Vec<String>> {
+    let mut favorites = get_favorite_printings_ref().write().unwrap();
+    if let (Some(oracle_id), Some(scryfall_id)) = (&card.oracle_id, &card.scryfall_id) {
+        let ids = favorites.entry(oracle_id.clone()).or_default();
+        match ids.iter().position(|id| id == scryfall_id) {
+            Some(position) => {
+                ids.remove(position);
+            }
+            None => ids.push(scryfall_id.clone()),
+        }
+        if ids.is_empty() {
+            favorites.remove(oracle_id);
+        }
+    }
+    favorites.clone()
+}
+
+/// Whether any printing of the card named `name` is a favorite (searching its printings if
+/// there are favorites at all)
+pub async fn has_favorite_printing(name: &str) -> bool {
+    if get_favorite_printings_ref().read().unwrap().is_empty() {
+        return false;
+    }
+    get_or_fetch_search_results(name)
+        .await
+        .is_ok_and(|results| results.cards.iter().any(is_favorite_printing))
+}
+
+pub fn get_download_rate_limit() -> Option<u64> {
+    let limit_ref = DOWNLOAD_RATE_LIMIT.get_or_init(|| Arc::new(RwLock::new(None)));
+    *limit_ref.read().unwrap()
+}
+
+/// Cap the bandwidth of image downloads at `kb_per_second` (None = unlimited), so background
+/// loading doesn't saturate the connection
+pub fn set_download_rate_limit(kb_per_second: Option<u64>) {
+    let limit_ref = DOWNLOAD_RATE_LIMIT.get_or_init(|| Arc::new(RwLock::new(None)));
+    *limit_ref.write().unwrap() = kb_per_second.filter(|&limit| limit > 0);
+}
+
+/// Whether Scryfall is down for maintenance (or overloaded), so cached data is used where
+/// there is any; frontends show this as a status
+pub fn is_scryfall_unavailable() -> bool {
+    let unavailable_ref = SCRYFALL_UNAVAILABLE.get_or_init(|| Arc::new(RwLock::new(false)));
+    *unavailable_ref.read().unwrap()
+}
+
+pub(crate) fn set_scryfall_unavailable(unavailable: bool) {
+    let unavailable_ref = SCRYFALL_UNAVAILABLE.get_or_init(|| Arc::new(RwLock::new(false)));
+    let was_unavailable = std::mem::replace(&mut *unavailable_ref.write().unwrap(), unavailable);
+    if was_unavailable && !unavailable {
+        info!("Scryfall is available again");
+    }
+}
+
+/// Fall back to `cached` data when fetching failed while Scryfall is unavailable
+///
+/// The global status is checked rather than the error, since callers waiting for a shared
+/// fetch only get its error as a message.
+fn or_cached_if_unavailable<T>(
+    fetched: Result<T, ProxyError>,
+    cached: Option<T>,
+) -> Result<T, ProxyError> {
+    match (fetched, cached) {
+        (Err(e), Some(cached)) if is_scryfall_unavailable() => {
+            warn!(error = %e, "Scryfall unavailable, using cached data");
+            Ok(cached)
+        }
+        (fetched, _) => fetched,
+    }
+}
+
+/// Get the Scryfall id of the printing scryfall.com shows for a card, asking Scryfall once per
+/// name and session
+pub async fn get_or_fetch_default_printing_id(name: &str) -> Result<Option<String>, ProxyError> {
+    let ids_ref = DEFAULT_PRINTING_IDS.get_or_init(|| Arc::new(RwLock::new(HashMap::new())));
+    let key = name.to_lowercase();
+    if let Some(id) = ids_ref.read().unwrap().get(&key) {
+        return Ok(id.clone());
+    }
+
+    let id = get_scryfall_client().get_default_printing_id(name).await?;
+    debug!(card_name = %name, id = ?id, "Fetched default printing");
+    ids_ref.write().unwrap().insert(key, id.clone());
+    Ok(id)
+}
+
+fn get_settings_ref() -> &'static Arc<RwLock<Settings>> {
+    SETTINGS.get_or_init(|| {
+        let settings = Settings::load_from(&PathBuf::from(get_settings_path()));
+        Arc::new(RwLock::new(settings))
+    })
+}
+
+/// Get a snapshot of the user settings (loaded from disk on first access)
+pub fn get_settings() -> Settings {
+    get_settings_ref().read().unwrap().clone()
+}
+
+/// Modify the user settings and persist them to disk immediately
+pub fn update_settings<F>(update: F) -> Result<(), ProxyError>
+where
+    F: FnOnce(&mut Settings),
+{
+    let mut settings = get_settings_ref().write().unwrap();
+    update(&mut settings);
+    settings.save_to(&PathBuf::from(get_settings_path()))
+}
+
+fn get_usage_stats_ref() -> &'static Arc<RwLock<UsageStats>> {
+    USAGE_STATS.get_or_init(|| {
+        let stats = UsageStats::load_from(&PathBuf::from(get_usage_stats_path()));
+        Arc::new(RwLock::new(stats))
+    })
+}
+
+/// Get a snapshot of the local usage statistics (loaded from disk on first access)
+pub fn get_usage_stats() -> UsageStats {
+    get_usage_stats_ref().read().unwrap().clone()
+}
+
+/// The most recently parsed decklists, newest first
+///
+/// Read from disk on every call, so the GUI and the CLI see each other's decklists.
+pub fn get_decklist_history() -> DecklistHistory {
+    DecklistHistory::load_from(&PathBuf::from(get_decklist_history_path()))
+}
+
+/// Add a just parsed decklist to the history (see [`DecklistHistory::record`]) and persist it
+pub fn record_recent_decklist(decklist: &str) -> Result<(), ProxyError> {
+    let mut history = get_decklist_history();
+    history.record(decklist);
+    history.save_to(&PathBuf::from(get_decklist_history_path()))
+}
+
+/// Forget all recently parsed decklists
+pub fn clear_decklist_history() -> Result<(), ProxyError> {
+    DecklistHistory::default().save_to(&PathBuf::from(get_decklist_history_path()))
+}
+
+/// Decks whose images are pinned in the image cache
+///
+/// Read from disk on every call, so the GUI and the CLI see each other's decks.
+pub fn get_pinned_decks() -> PinnedDecks {
+    PinnedDecks::load_from(&PathBuf::from(get_pinned_decks_path()))
+}
+
+/// Keep the images at `urls` in the image cache under any eviction policy, as the deck `name`
+///
+/// Pinning a deck again replaces its images, e.g. after changing printings.
+pub fn pin_deck(name: &str, urls: Vec<String>) -> Result<(), ProxyError> {
+    let mut pinned_decks = get_pinned_decks();
+    let previous = pinned_decks.pin(name, urls);
+    if let Some(cache) = IMAGE_CACHE.get() {
+        let mut cache_guard = cache.write().unwrap();
+        cache_guard.unpin(&previous);
+        cache_guard.pin(&pinned_decks.decks[name]);
+    }
+    pinned_decks.save_to(&PathBuf::from(get_pinned_decks_path()))
+}
+
+/// Let the images of the deck `name` be evicted again; false if it wasn't pinned
+pub fn unpin_deck(name: &str) -> Result<bool, ProxyError> {
+    let mut pinned_decks = get_pinned_decks();
+    let Some(previous) = pinned_decks.unpin(name) else {
+        return Ok(false);
+    };
+    if let Some(cache) = IMAGE_CACHE.get() {
+        cache.write().unwrap().unpin(&previous);
+    }
+    pinned_decks.save_to(&PathBuf::from(get_pinned_decks_path()))?;
+    Ok(true)
+}
+
+/// Start the usage statistics over and persist the empty statistics
+pub fn reset_usage_stats() -> Result<(), ProxyError> {
+    let mut stats = get_usage_stats_ref().write().unwrap();
+    *stats = UsageStats::new();
+    stats.save_to(&PathBuf::from(get_usage_stats_path()))
+}
+
+/// Update the usage statistics in memory, if collecting them is enabled in the settings
+pub(crate) fn record_usage<F>(update: F)
+where
+    F: FnOnce(&mut UsageStats),
+{
+    if get_settings_ref().read().unwrap().collect_usage_stats {
+        update(&mut get_usage_stats_ref().write().unwrap());
+    }
+}
+
+/// Persist the usage statistics, if any were loaded or recorded this session
+pub fn save_usage_stats() -> Result<(), ProxyError> {
+    if get_read_only_caches() {
+        return Ok(());
+    }
+    match USAGE_STATS.get() {
+        Some(stats) => stats
+            .read()
+            .unwrap()
+            .save_to(&PathBuf::from(get_usage_stats_path())),
+        None => Ok(()),
+    }
+}
+
+/// Current status of the automatic background refresh
+pub fn get_cache_refresh_status() -> CacheRefreshStatus {
+    get_cache_refresh_status_sender().borrow().clone()
+}
+
+/// Subscribe to status changes of the automatic background refresh
+pub fn subscribe_cache_refresh_status() -> watch::Receiver<CacheRefreshStatus> {
+    get_cache_refresh_status_sender().subscribe()
+}
+
+fn set_cache_refresh_status(status: CacheRefreshStatus) {
+    get_cache_refresh_status_sender().send_replace(status);
+}
+
+fn finish_background_cache_refresh(errors: Vec<String>) {
+    if errors.is_empty() {
+        info!("Background cache refresh complete");
+        set_cache_refresh_status(CacheRefreshStatus::Completed {
+            refreshed_at: OffsetDateTime::now_utc(),
+        });
+    } else {
+        warn!(errors = ?errors, "Background cache refresh failed, keeping stale data");
+        set_cache_refresh_status(CacheRefreshStatus::Failed(errors.join("; ")));
+    }
+}
+
+/// Wait until a running background refresh has finished (returns immediately if none is running)
+pub async fn wait_for_cache_refresh() -> CacheRefreshStatus {
+    let mut status_rx = subscribe_cache_refresh_status();
+    loop {
+        let status = status_rx.borrow_and_update().clone();
+        if !status.is_refreshing() || status_rx.changed().await.is_err() {
+            return status;
+        }
+    }
+}
+
 // Eager initialization function - call at application startup
 pub async fn initialize_caches() -> Result<(), ProxyError> {
     // Initialize image cache (loads from disk)
@@ -105,17 +812,109 @@ pub async fn initialize_caches() -> Result<(), ProxyError> {
     let _search_cache = get_search_results_cache();
     info!("Search results cache initialized at startup");
 
-    // Initialize card name lookup from disk at startup
-    ensure_card_lookup_initialized().await?;
+    // Load card names and set codes from disk even if stale, so startup never waits on a refresh
+    let card_names_cached_at = load_stale_card_lookup()?;
+    let set_codes_cached_at = load_stale_set_codes()?;
 
-    // Initialize set codes from disk at startup
+    // Anything still missing (first run or unreadable disk cache) has to be fetched now; the
+    // lookup for card names found on disk is still being built and isn't waited for
+    ensure_card_lookup_initialized().await?;
     ensure_set_codes_initialized().await?;
 
+    let policy = get_freshness_policy();
+    let refresh_card_names = card_names_cached_at.is_some_and(|at| policy.needs_refresh(at));
+    let refresh_set_codes = set_codes_cached_at.is_some_and(|at| policy.needs_refresh(at));
+    if refresh_card_names || refresh_set_codes {
+        start_background_cache_refresh(refresh_card_names, refresh_set_codes);
+    }
+
     Ok(())
 }
 
+/// Load card names from disk regardless of age and build their lookup in the background
+/// Returns when the data was cached, or None if nothing was loaded
+fn load_stale_card_lookup() -> Result<Option<OffsetDateTime>, ProxyError> {
+    if is_card_lookup_ready() || is_card_lookup_building() {
+        return Ok(None);
+    }
+
+    let cache = CardNameCache::new()?;
+    let Some((card_names, cached_at)) = cache.load_cached_card_names() else {
+        return Ok(None);
+    };
+
+    let cache_info_ref = get_card_name_cache_info_ref();
+    *cache_info_ref.write().unwrap() = card_names.date.map(|date| (date, card_names.names.len()));
+
+    get_card_lookup_building_sender().send_replace(true);
+    tokio::task::spawn_blocking(move || finish_deferred_card_lookup(&card_names));
+
+    Ok(Some(cached_at))
+}
+
+/// Install set codes from disk regardless of age
+/// Returns when the data was cached, or None if nothing was loaded
+fn load_stale_set_codes() -> Result<Option<OffsetDateTime>, ProxyError> {
+    let set_codes_ref = get_set_codes_cache();
+    if set_codes_ref.read().unwrap().is_some() {
+        return Ok(None);
+    }
+
+    let cache = SetCodesCache::new()?;
+    let Some((set_codes, cached_at)) = cache.load_cached_set_codes() else {
+        return Ok(None);
+    };
+
+    *set_codes_ref.write().unwrap() = Some(process_set_codes_into_hashset(&set_codes));
+    *get_set_icon_uris().write().unwrap() = set_codes.icon_uris;
+    *get_set_infos().write().unwrap() = set_codes.sets;
+    Ok(Some(cached_at))
+}
+
+/// When the card names and set codes on disk were fetched (None for data that isn't cached)
+pub fn get_cache_dates() -> Result<(Option<OffsetDateTime>, Option<OffsetDateTime>), ProxyError> {
+    let card_names = CardNameCache::new()?.load_cached_card_names();
+    let set_codes = SetCodesCache::new()?.load_cached_set_codes();
+    Ok((
+        card_names.map(|(_, cached_at)| cached_at),
+        set_codes.map(|(_, cached_at)| cached_at),
+    ))
+}
+
+/// Refresh stale card names and/or set codes without blocking the caller
+/// Must be called from within a tokio runtime
+fn start_background_cache_refresh(card_names: bool, set_codes: bool) {
+    info!(
+        card_names,
+        set_codes, "Starting background refresh of stale caches"
+    );
+    set_cache_refresh_status(CacheRefreshStatus::Refreshing {
+        card_names,
+        set_codes,
+    });
+
+    tokio::spawn(async move {
+        let mut errors = Vec::new();
+
+        if card_names {
+            let result = force_update_card_lookup().await;
+            errors.extend(result.err().map(|e| format!("card names: {}", e)));
+        }
+        if set_codes {
+            let result = force_update_set_codes().await;
+            errors.extend(result.err().map(|e| format!("set codes: {}", e)));
+        }
+
+        finish_background_cache_refresh(errors);
+    });
+}
+
 // Save all in-memory caches to disk (without shutdown)
 pub fn save_caches() -> Result<(), ProxyError> {
+    if get_read_only_caches() {
+        info!("Caches are read-only, not saving them");
+        return Ok(());
+    }
     info!("Saving all in-memory caches to disk");
 
     // Save image cache metadata
@@ -126,17 +925,32 @@ pub fn save_caches() -> Result<(), ProxyError> {
         debug!("Image cache saved to disk");
     }
 
-    // Save search results cache
+    // Save thumbnail cache, only if thumbnails were shown (it is created on first use)
+    if let Some(thumbnail_cache) = THUMBNAIL_CACHE.get() {
+        thumbnail_cache.read().unwrap().save_to_storage()?;
+        debug!("Thumbnail cache saved to disk");
+    }
+
+    // Save search results cache, dropping expired and excess searches first
     {
         let search_cache = get_search_results_cache();
-        let cache_guard = search_cache.read().unwrap();
-        cache_guard.save_to_storage()?;
+        let mut cache_guard = search_cache.write().unwrap();
+        cache_guard.compact_and_save()?;
         debug!("Search results cache saved to disk");
     }
 
+    // Save query results cache, only if a query was run (it is created on first use)
+    if let Some(query_cache) = QUERY_RESULTS_CACHE.get() {
+        let mut cache_guard = query_cache.write().unwrap();
+        cache_guard.compact_and_save()?;
+        debug!("Query results cache saved to disk");
+    }
+
     // Card names and set codes caches save immediately when updated from API
     // (no need to save - they only change when force-updated and save immediately)
 
+    save_usage_stats()?;
+
     info!("All in-memory caches saved to disk successfully");
     Ok(())
 }
@@ -155,10 +969,8 @@ pub async fn shutdown_caches() -> Result<(), ProxyError> {
 // Convenience functions - these now only check if already initialized
 pub async fn ensure_card_lookup_initialized() -> Result<(), ProxyError> {
     let lookup_ref = get_card_lookup();
-    let needs_init = {
-        let lookup = lookup_ref.read().unwrap();
-        lookup.is_none()
-    };
+    // Names found on disk at startup are indexed in the background already
+    let needs_init = lookup_ref.read().unwrap().is_none() && !is_card_lookup_building();
 
     if needs_init {
         info!("Initializing CardNameLookup from disk cache");
@@ -167,19 +979,7 @@ pub async fn ensure_card_lookup_initialized() -> Result<(), ProxyError> {
 
         // This will log disk cache operations internally
         let card_names = cache.get_card_names(client, false).await?;
-
-        info!(
-            card_count = card_names.names.len(),
-            "Building fuzzy matching index from card names"
-        );
-        let start = std::time::Instant::now();
-        let lookup = CardNameLookup::from_card_names(&card_names.names);
-        let duration = start.elapsed();
-
-        info!(
-            duration_ms = duration.as_millis(),
-            "CardNameLookup fuzzy index construction complete"
-        );
+        let lookup = load_or_build_card_lookup(&card_names);
 
         let mut lookup_guard = lookup_ref.write().unwrap();
         *lookup_guard = Some(lookup);
@@ -200,19 +1000,7 @@ pub async fn force_update_card_lookup() -> Result<(), ProxyError> {
 
     // This will log the forced API fetch internally
     let card_names = cache.get_card_names(client, true).await?;
-
-    info!(
-        card_count = card_names.names.len(),
-        "Building new fuzzy matching index from fresh data"
-    );
-    let start = std::time::Instant::now();
-    let lookup = CardNameLookup::from_card_names(&card_names.names);
-    let duration = start.elapsed();
-
-    info!(
-        duration_ms = duration.as_millis(),
-        "Force update: CardNameLookup fuzzy index construction complete"
-    );
+    let lookup = load_or_build_card_lookup(&card_names);
 
     let lookup_ref = get_card_lookup();
     let mut lookup_guard = lookup_ref.write().unwrap();
@@ -239,6 +1027,16 @@ pub fn find_card_name(name: &str) -> Option<NameLookupResult> {
     result
 }
 
+/// Card names for what was typed so far, best first (empty while the lookup isn't ready)
+pub fn suggest_card_names(query: &str, limit: usize) -> Vec<NameLookupResult> {
+    let lookup_ref = get_card_lookup();
+    let lookup = lookup_ref.read().unwrap();
+    lookup
+        .as_ref()
+        .map(|lookup| lookup.suggest(query, limit))
+        .unwrap_or_default()
+}
+
 pub async fn ensure_set_codes_initialized() -> Result<(), ProxyError> {
     let set_codes_ref = get_set_codes_cache();
     let needs_init = {
@@ -257,6 +1055,8 @@ pub async fn ensure_set_codes_initialized() -> Result<(), ProxyError> {
             set_code_count = set_codes.codes.len(),
             "Loaded set codes into memory"
         );
+        *get_set_icon_uris().write().unwrap() = set_codes.icon_uris;
+        *get_set_infos().write().unwrap() = set_codes.sets;
 
         // Convert to HashSet for fast lookups
         let codes_set: HashSet<String> = set_codes.codes.into_iter().collect();
@@ -275,6 +1075,10 @@ pub async fn force_update_set_codes() -> Result<(), ProxyError> {
     info!("Force updating set codes from Scryfall API");
     let client = get_scryfall_client();
     let cache = SetCodesCache::new()?;
+    // Compared with the fresh set codes to notice newly released sets
+    let previous_set_codes = cache
+        .load_cached_set_codes()
+        .map(|(set_codes, _)| set_codes);
 
     // This will log the forced API fetch internally
     let set_codes = cache.get_set_codes(client, true).await?;
@@ -283,6 +1087,16 @@ pub async fn force_update_set_codes() -> Result<(), ProxyError> {
         set_code_count = set_codes.codes.len(),
         "Force update: Fresh set codes loaded from API"
     );
+    let new_set_release =
+        previous_set_codes.and_then(|previous| newest_new_set_release(&previous, &set_codes));
+    if let Some(released_at) = new_set_release {
+        // Outdated searches are still better than failing the update
+        if let Err(e) = invalidate_search_results_before(released_at) {
+            warn!(error = %e, "Failed to invalidate searches cached before the new set");
+        }
+    }
+    *get_set_icon_uris().write().unwrap() = set_codes.icon_uris;
+    *get_set_infos().write().unwrap() = set_codes.sets;
 
     // Convert to HashSet for fast lookups
     let codes_set: HashSet<String> = set_codes.codes.into_iter().collect();
@@ -297,33 +1111,150 @@ pub async fn force_update_set_codes() -> Result<(), ProxyError> {
     Ok(())
 }
 
+/// Drop the searches cached before a new set's release, so its cards and printings show up
+fn invalidate_search_results_before(released_at: OffsetDateTime) -> Result<(), ProxyError> {
+    let searches = get_search_results_cache()
+        .write()
+        .unwrap()
+        .evict_created_before(released_at)?;
+    // The query cache is created on first use
+    let queries = match QUERY_RESULTS_CACHE.get() {
+        Some(query_cache) => query_cache
+            .write()
+            .unwrap()
+            .evict_created_before(released_at)?,
+        None => 0,
+    };
+    info!(
+        %released_at,
+        searches, queries, "New set released, invalidated older cached searches"
+    );
+    Ok(())
+}
+
 pub async fn get_or_fetch_image_bytes(url: &str) -> Result<Vec<u8>, ProxyError> {
     let cache = get_image_cache();
-    let client = get_scryfall_client();
 
     // Try to get from cache first (note: this needs mutable access for LRU tracking)
-    let cached_bytes = {
+    let (cached_bytes, stale_validators) = {
         let mut cache_guard = cache.write().unwrap();
-        cache_guard.get(&url.to_string())
+        let key = url.to_string();
+        (cache_guard.get(&key), cache_guard.validators_if_stale(&key))
+    };
+    let cache_hit = cached_bytes.is_some();
+    record_usage(|stats| stats.record_image_lookup(cache_hit));
+
+    // Concurrent requests for the same image (e.g. preview and background loading)
+    // share a single download
+    let fetches = IMAGE_FETCHES.get_or_init(SingleFlight::new);
+    match (cached_bytes, stale_validators) {
+        (Some(bytes), None) => Ok(bytes),
+        (Some(bytes), Some(validators)) => {
+            fetches
+                .run(url, || async {
+                    Ok(revalidate_image_bytes(url, bytes, validators).await)
+                })
+                .await
+        }
+        (None, _) => fetches.run(url, || fetch_and_cache_image_bytes(url)).await,
+    }
+}
+
+/// Check a stale cached image for changes, keeping the cached bytes if that fails
+async fn revalidate_image_bytes(
+    url: &str,
+    cached_bytes: Vec<u8>,
+    validators: HttpValidators,
+) -> Vec<u8> {
+    let response = get_scryfall_client()
+        .get_image_bytes_if_modified(url, &validators)
+        .await;
+    let mut cache_guard = get_image_cache().write().unwrap();
+    match response {
+        Ok(ConditionalImage::NotModified) => {
+            debug!(url = %url, "Cached image is still current");
+            cache_guard.mark_revalidated(&url.to_string());
+            cached_bytes
+        }
+        Ok(ConditionalImage::Modified { bytes, validators }) => {
+            debug!(url = %url, "Cached image changed, replacing it");
+            evict_thumbnail(url);
+            let inserted =
+                cache_guard.insert_with_validators(url.to_string(), bytes.clone(), validators);
+            if let Err(e) = inserted {
+                warn!(url = %url, error = %e, "Failed to cache changed image");
+            }
+            bytes
+        }
+        Err(e) => {
+            warn!(url = %url, error = %e, "Failed to revalidate cached image, using it anyway");
+            cached_bytes
+        }
+    }
+}
+
+async fn fetch_and_cache_image_bytes(url: &str) -> Result<Vec<u8>, ProxyError> {
+    debug!(url = %url, "Image cache MISS, fetching from network");
+
+    // Fetch raw bytes and cache them (the placeholder back is generated locally)
+    let (raw_bytes, validators) = if url == PLACEHOLDER_BACK_URL {
+        (placeholder_back_image_bytes()?, HttpValidators::default())
+    } else {
+        get_scryfall_client()
+            .get_image_bytes_with_validators(url)
+            .await?
     };
 
-    match cached_bytes {
-        Some(bytes) => Ok(bytes),
-        None => {
-            debug!(url = %url, "Image cache MISS, fetching from network");
+    // Insert raw bytes into cache (this handles disk persistence and LRU eviction)
+    {
+        let mut cache_guard = get_image_cache().write().unwrap();
+        cache_guard.insert_with_validators(url.to_string(), raw_bytes.clone(), validators)?;
+    }
 
-            // Fetch raw bytes and cache them
-            let raw_bytes = client.get_image_bytes(url).await?;
+    Ok(raw_bytes)
+}
 
-            // Insert raw bytes into cache (this handles disk persistence and LRU eviction)
-            {
-                let mut cache_guard = cache.write().unwrap();
-                cache_guard.insert(url.to_string(), raw_bytes.clone())?;
-            }
+/// Get the set symbol SVG of a set, from memory, the disk cache or Scryfall
+pub async fn get_or_fetch_set_icon(set_code: &str) -> Result<Vec<u8>, ProxyError> {
+    let set_code = set_code.to_lowercase();
+    if let Some(icon) = get_cached_set_icon(&set_code) {
+        return Ok(icon);
+    }
 
-            Ok(raw_bytes)
+    let cache = SetIconCache::new()?;
+    let icon = match cache.load(&set_code) {
+        Some(icon) => icon,
+        None => {
+            let uri = get_set_icon_uris()
+                .read()
+                .unwrap()
+                .get(&set_code)
+                .cloned()
+                .ok_or_else(|| {
+                    ProxyError::Cache(format!("No set icon known for '{}'", set_code))
+                })?;
+            debug!(set_code = %set_code, "Set icon cache MISS, fetching from network");
+
+            let icon = get_scryfall_client().get_image_bytes(&uri).await?;
+            cache.save(&set_code, &icon)?;
+            icon
         }
-    }
+    };
+
+    get_set_icons()
+        .write()
+        .unwrap()
+        .insert(set_code, icon.clone());
+    Ok(icon)
+}
+
+/// Get the set symbol SVG of a set if it was already loaded into memory
+pub fn get_cached_set_icon(set_code: &str) -> Option<Vec<u8>> {
+    get_set_icons()
+        .read()
+        .unwrap()
+        .get(&set_code.to_lowercase())
+        .cloned()
 }
 
 /// Get or fetch image and convert to DynamicImage (for PDF generation)
@@ -361,6 +1292,70 @@ pub fn get_image_cache_info() -> (usize, f64) {
     (count, size_mb)
 }
 
+/// List the cards whose images are in the image cache, by the cached search results
+pub fn get_image_cache_contents() -> ImageCacheContents {
+    let cached_urls: HashSet<String> = {
+        let cache = get_image_cache();
+        let cache_guard = cache.read().unwrap();
+        cache_guard.iter().map(|(url, _)| url.clone()).collect()
+    };
+
+    let searches = get_search_results_cache().read().unwrap();
+    // Raw queries are only loaded once a query is run, not for listing the images
+    let queries = QUERY_RESULTS_CACHE
+        .get()
+        .map(|queries| queries.read().unwrap());
+    let cards = searches
+        .iter()
+        .chain(queries.iter().flat_map(|queries| queries.iter()))
+        .flat_map(|(_, entry)| &entry.value.cards);
+    image_cache_contents(cards, &cached_urls)
+}
+
+/// Image URLs pinned in the image cache for as long as this is alive, see [`pin_images`]
+#[derive(Debug)]
+pub struct ImagePin {
+    urls: Vec<String>,
+}
+
+impl Drop for ImagePin {
+    fn drop(&mut self) {
+        if let Ok(mut cache_guard) = get_image_cache().write() {
+            cache_guard.unpin(&self.urls);
+        }
+    }
+}
+
+/// Keep the images at `urls` from being evicted while a preview or PDF generation uses them
+///
+/// Hold on to the returned pin for the duration of the work; dropping it unpins the images.
+pub fn pin_images(urls: Vec<String>) -> ImagePin {
+    get_image_cache().write().unwrap().pin(&urls);
+    ImagePin { urls }
+}
+
+/// Export the image cache to a tar archive at `path`, e.g. to seed another device
+pub fn export_image_cache_to(path: &Path) -> Result<usize, ProxyError> {
+    let mut writer = BufWriter::new(File::create(path).map_err(ProxyError::Io)?);
+    let exported = {
+        let cache = get_image_cache();
+        let cache_guard = cache.read().unwrap();
+        export_image_cache(&cache_guard, &mut writer)?
+    };
+    writer.flush().map_err(ProxyError::Io)?;
+    Ok(exported)
+}
+
+/// Import a tar archive written by `export_image_cache_to` and save the image cache
+pub fn import_image_cache_from(path: &Path) -> Result<ImageArchiveImport, ProxyError> {
+    let reader = BufReader::new(File::open(path).map_err(ProxyError::Io)?);
+    let cache = get_image_cache();
+    let mut cache_guard = cache.write().unwrap();
+    let summary = import_image_cache(&mut cache_guard, reader)?;
+    cache_guard.save_to_storage()?;
+    Ok(summary)
+}
+
 /// Get raw image bytes from cache for GUI display (returns None if not cached)
 pub fn get_cached_image_bytes(url: &str) -> Option<Vec<u8>> {
     let cache = get_image_cache();
@@ -368,7 +1363,55 @@ pub fn get_cached_image_bytes(url: &str) -> Option<Vec<u8>> {
     cache_guard.get(&url.to_string())
 }
 
-/// Get search results cache statistics (count and estimated size in MB)
+/// Get a downscaled copy of a cached image for preview grids (returns None if not cached)
+///
+/// The thumbnail is made from the cached image on first access and kept in the thumbnail
+/// cache. If that fails, the full-size image is returned instead.
+pub fn get_cached_thumbnail_bytes(url: &str) -> Option<Vec<u8>> {
+    let key = url.to_string();
+    if let Some(thumbnail) = get_thumbnail_cache().write().unwrap().get(&key) {
+        return Some(thumbnail);
+    }
+
+    let image_bytes = get_cached_image_bytes(url)?;
+    match make_thumbnail(&image_bytes) {
+        Ok(thumbnail) => {
+            let inserted = get_thumbnail_cache()
+                .write()
+                .unwrap()
+                .insert(key, thumbnail.clone());
+            if let Err(e) = inserted {
+                warn!(url = %url, error = %e, "Failed to cache thumbnail");
+            }
+            Some(thumbnail)
+        }
+        Err(e) => {
+            warn!(url = %url, error = %e, "Failed to make thumbnail, showing the full image");
+            Some(image_bytes)
+        }
+    }
+}
+
+/// Drop the thumbnail of an image that changed, so the next access makes a new one
+fn evict_thumbnail(url: &str) {
+    let Some(thumbnail_cache) = THUMBNAIL_CACHE.get() else {
+        return;
+    };
+    if let Err(e) = thumbnail_cache.write().unwrap().evict(&url.to_string()) {
+        warn!(url = %url, error = %e, "Failed to evict thumbnail");
+    }
+}
+
+/// Count how many of the given image URLs are cached, without touching their access times
+pub fn count_cached_images<'a>(urls: impl IntoIterator<Item = &'a str>) -> usize {
+    let cache = get_image_cache();
+    let cache_guard = cache.read().unwrap();
+    urls.into_iter()
+        .filter(|url| cache_guard.contains(&url.to_string()))
+        .count()
+}
+
+/// Get search results cache statistics (count and serialized size in MB)
 pub fn get_search_results_cache_info() -> (usize, f64) {
     let cache = get_search_results_cache();
     let cache_guard = cache.read().unwrap();
@@ -380,27 +1423,82 @@ pub fn get_search_results_cache_info() -> (usize, f64) {
 pub async fn get_or_fetch_search_results(
     card_name: &str,
 ) -> Result<crate::scryfall::CardSearchResult, ProxyError> {
-    let client = get_scryfall_client();
     let cache = get_search_results_cache();
 
-    // Check cache first (separate scope to release lock)
+    // Check cache first (separate scope to release lock); expired results are kept in case
+    // Scryfall is unavailable
     let cached_result = {
         let mut cache_guard = cache.write().unwrap();
-        cache_guard.get(&card_name.to_lowercase())
+        cache_guard.get_allowing_expired(&card_name.to_lowercase())
+    };
+    let cache_hit = matches!(cached_result, Some((_, false)));
+    record_usage(|stats| stats.record_search_lookup(cache_hit));
+
+    let expired_result = match cached_result {
+        Some((result, false)) => {
+            debug!(card_name = %card_name, "Search results cache HIT");
+            return Ok(result);
+        }
+        Some((result, true)) => Some(result),
+        None => None,
     };
 
-    if let Some(result) = cached_result {
-        debug!(card_name = %card_name, "Search results cache HIT");
-        return Ok(result);
+    // Cache miss - fetch from API, sharing the request with concurrent searches for the same name
+    let fetches = SEARCH_FETCHES.get_or_init(SingleFlight::new);
+    let fetched = fetches
+        .run(&card_name.to_lowercase(), || {
+            fetch_and_cache_search_results(card_name)
+        })
+        .await;
+    or_cached_if_unavailable(fetched, expired_result)
+}
+
+/// Find the printing a normalized decklist line names exactly (see
+/// [`DecklistEntry::is_exact_printing`]), asking Scryfall for it when the printings search
+/// didn't return it (e.g. most printings in other languages)
+///
+/// A printing fetched this way is added to the cached search results of the card, so every
+/// frontend can select it from there.
+pub async fn get_or_fetch_exact_printing(
+    entry: &DecklistEntry,
+) -> Result<Option<Card>, ProxyError> {
+    let (Some(set), Some(number), Some(lang)) = (&entry.set, &entry.collector_number, &entry.lang)
+    else {
+        return Ok(None);
+    };
+
+    let mut search_results = get_or_fetch_search_results(&entry.name).await?;
+    if let Some(card) = search_results
+        .cards
+        .iter()
+        .find(|card| entry.is_printed_as(card))
+    {
+        return Ok(Some(card.clone()));
     }
 
-    // Cache miss - fetch from API
+    let card = get_scryfall_client().get_printing(set, number, lang).await;
+    // Without Scryfall the printing falls back to the cached ones
+    let card = or_cached_if_unavailable(card, Some(None))?
+        .filter(|card| card.name.eq_ignore_ascii_case(&entry.name));
+    if let Some(card) = &card {
+        debug!(card_name = %entry.name, "Fetched exact printing {} {} [{}]", set, number, lang);
+        search_results.cards.push(card.clone());
+        search_results.total_found += 1;
+        let mut cache_guard = get_search_results_cache().write().unwrap();
+        cache_guard.insert(entry.name.to_lowercase(), search_results)?;
+    }
+    Ok(card)
+}
+
+async fn fetch_and_cache_search_results(
+    card_name: &str,
+) -> Result<crate::scryfall::CardSearchResult, ProxyError> {
     debug!(card_name = %card_name, "Search results cache MISS, fetching from API");
-    let search_results = client.search_card(card_name).await?;
+    let search_results = get_scryfall_client().search_card(card_name).await?;
 
     // Insert into cache (separate scope to release lock)
     {
-        let mut cache_guard = cache.write().unwrap();
+        let mut cache_guard = get_search_results_cache().write().unwrap();
         cache_guard.insert(card_name.to_lowercase(), search_results.clone())?;
         debug!(
             card_name = %card_name,
@@ -412,15 +1510,101 @@ pub async fn get_or_fetch_search_results(
     Ok(search_results)
 }
 
-/// Get the actual cache directory path
+/// Search Scryfall with a raw query (e.g. "t:goblin e:dom lang:ja"), using cached results
+pub async fn get_or_fetch_query_results(
+    query: &str,
+) -> Result<crate::scryfall::CardSearchResult, ProxyError> {
+    // Queries are kept verbatim apart from surrounding whitespace: regexes are case-sensitive
+    let query = query.trim();
+    let cached_result = {
+        let mut cache_guard = get_query_results_cache().write().unwrap();
+        cache_guard.get_allowing_expired(&query.to_string())
+    };
+
+    let expired_result = match cached_result {
+        Some((result, false)) => {
+            debug!(query = %query, "Query results cache HIT");
+            return Ok(result);
+        }
+        Some((result, true)) => Some(result),
+        None => None,
+    };
+
+    let fetches = QUERY_FETCHES.get_or_init(SingleFlight::new);
+    let fetched = fetches
+        .run(query, || fetch_and_cache_query_results(query))
+        .await;
+    or_cached_if_unavailable(fetched, expired_result)
+}
+
+async fn fetch_and_cache_query_results(
+    query: &str,
+) -> Result<crate::scryfall::CardSearchResult, ProxyError> {
+    debug!(query = %query, "Query results cache MISS, fetching from API");
+    let search_results = get_scryfall_client().search_with_query(query).await?;
+
+    {
+        let mut cache_guard = get_query_results_cache().write().unwrap();
+        cache_guard.insert(query.to_string(), search_results.clone())?;
+        debug!(
+            query = %query,
+            results_count = search_results.cards.len(),
+            "Query results cached"
+        );
+    }
+
+    Ok(search_results)
+}
+
+/// Whether this process only reads the caches
+pub fn get_read_only_caches() -> bool {
+    let read_only_ref = READ_ONLY_CACHES.get_or_init(|| Arc::new(RwLock::new(false)));
+    *read_only_ref.read().unwrap()
+}
+
+/// Only read the caches: nothing is saved, evicted images stay on disk and downloads are kept
+/// in memory, so short-lived processes (e.g. scripted CLI runs while the GUI is open) start
+/// and exit quickly without touching the files the other process maintains
+///
+/// Call before the caches are first used.
+pub fn set_read_only_caches(enabled: bool) {
+    let read_only_ref = READ_ONLY_CACHES.get_or_init(|| Arc::new(RwLock::new(false)));
+    *read_only_ref.write().unwrap() = enabled;
+}
+
+/// Make `cache` read-only if the caches are, see [`set_read_only_caches`]
+fn read_only_if_enabled<K, V, S>(mut cache: LruCache<K, V, S>) -> LruCache<K, V, S>
+where
+    K: std::hash::Hash + Eq + Clone,
+    V: Clone,
+    S: StorageStrategy<K, V>,
+{
+    cache.set_read_only(get_read_only_caches());
+    cache
+}
+
+/// Get the actual cache directory path: the one set with [`set_cache_directory`], else the
+/// platform cache directory
 pub fn get_cache_directory_path() -> String {
-    let cache_dir = ProjectDirs::from("", "", "localhawk")
-        .map(|proj_dirs| proj_dirs.cache_dir().to_path_buf())
-        .unwrap_or_else(|| std::env::temp_dir().join("localhawk-cache"));
+    let directory_ref = CACHE_DIRECTORY.get_or_init(|| Arc::new(RwLock::new(None)));
+    let cache_dir = directory_ref.read().unwrap().clone().unwrap_or_else(|| {
+        ProjectDirs::from("", "", "localhawk")
+            .map(|proj_dirs| proj_dirs.cache_dir().to_path_buf())
+            .unwrap_or_else(|| std::env::temp_dir().join("localhawk-cache"))
+    });
 
     cache_dir.to_string_lossy().to_string()
 }
 
+/// Keep all caches (and the log files) in `directory` instead of the platform cache directory,
+/// e.g. on a larger drive (None = platform cache directory)
+///
+/// Call before initializing the caches and logging; caches already loaded keep their files.
+pub fn set_cache_directory(directory: Option<PathBuf>) {
+    let directory_ref = CACHE_DIRECTORY.get_or_init(|| Arc::new(RwLock::new(None)));
+    *directory_ref.write().unwrap() = directory;
+}
+
 /// Get the image cache directory path
 pub fn get_image_cache_path() -> String {
     format!("{}/", get_cache_directory_path())
@@ -431,6 +1615,16 @@ pub fn get_search_cache_path() -> String {
     format!("{}/search_results_cache.json", get_cache_directory_path())
 }
 
+/// Get the directory of the log files written when file logging is on
+pub fn get_log_directory_path() -> String {
+    format!("{}/logs", get_cache_directory_path())
+}
+
+/// Get the raw query results cache file path
+pub fn get_query_cache_path() -> String {
+    format!("{}/query_results_cache.json", get_cache_directory_path())
+}
+
 /// Get the card names cache file path
 pub fn get_card_names_cache_path() -> String {
     format!("{}/card_names.json", get_cache_directory_path())
@@ -441,6 +1635,49 @@ pub fn get_set_codes_cache_path() -> String {
     format!("{}/set_codes.json", get_cache_directory_path())
 }
 
+/// Get the set symbol cache directory path
+pub fn get_set_icons_cache_path() -> String {
+    format!("{}/set_icons", get_cache_directory_path())
+}
+
+/// Get the user settings file path (config directory, not cache, so clearing caches keeps settings)
+pub fn get_settings_path() -> String {
+    get_config_directory_path()
+        .join("settings.json")
+        .to_string_lossy()
+        .to_string()
+}
+
+/// Get the usage statistics file path (next to the settings)
+pub fn get_usage_stats_path() -> String {
+    get_config_directory_path()
+        .join("usage_stats.json")
+        .to_string_lossy()
+        .to_string()
+}
+
+/// Get the pinned decks file path (next to the settings)
+pub fn get_pinned_decks_path() -> String {
+    get_config_directory_path()
+        .join("pinned_decks.json")
+        .to_string_lossy()
+        .to_string()
+}
+
+/// Get the decklist history file path (next to the settings)
+pub fn get_decklist_history_path() -> String {
+    get_config_directory_path()
+        .join("decklist_history.json")
+        .to_string_lossy()
+        .to_string()
+}
+
+fn get_config_directory_path() -> PathBuf {
+    ProjectDirs::from("", "", "localhawk")
+        .map(|proj_dirs| proj_dirs.config_dir().to_path_buf())
+        .unwrap_or_else(|| std::env::temp_dir().join("localhawk-config"))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
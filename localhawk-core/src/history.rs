@@ -0,0 +1,164 @@
+use crate::decklist::parse_deck_name;
+use crate::error::ProxyError;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use time::OffsetDateTime;
+use tracing::{debug, warn};
+
+/// Decklists kept in the history; parsing another one drops the oldest
+pub const MAX_RECENT_DECKLISTS: usize = 20;
+
+/// A decklist parsed on this computer, kept to load it again later
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecentDecklist {
+    /// Deck name from a "Deck: NAME" line, else the first line of the decklist
+    pub name: String,
+    /// The decklist as it was entered
+    pub decklist: String,
+    /// When it was last parsed (UTC)
+    pub parsed_at: OffsetDateTime,
+}
+
+impl RecentDecklist {
+    /// When it was last parsed, e.g. "2024-05-01 18:30 UTC"
+    pub fn parsed_at_label(&self) -> String {
+        format!(
+            "{} {:02}:{:02} UTC",
+            self.parsed_at.date(),
+            self.parsed_at.hour(),
+            self.parsed_at.minute()
+        )
+    }
+}
+
+/// e.g. "Mono Red (2024-05-01 18:30 UTC)", for menus listing the history
+impl fmt::Display for RecentDecklist {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({})", self.name, self.parsed_at_label())
+    }
+}
+
+/// The most recently parsed decklists, newest first
+///
+/// Stored next to the settings, so clearing the caches keeps it. Like the settings, missing
+/// fields fall back to their defaults so files written by older versions keep loading.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DecklistHistory {
+    pub decklists: Vec<RecentDecklist>,
+}
+
+impl DecklistHistory {
+    /// Add a just parsed decklist at the front
+    ///
+    /// Parsing the same decklist again moves it to the front instead of adding it twice.
+    /// Blank decklists aren't kept.
+    pub fn record(&mut self, decklist: &str) {
+        let decklist = decklist.trim();
+        let Some(first_line) = decklist
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty())
+        else {
+            return;
+        };
+        let name = parse_deck_name(decklist).unwrap_or_else(|| first_line.to_string());
+
+        self.decklists.retain(|recent| recent.decklist != decklist);
+        self.decklists.insert(
+            0,
+            RecentDecklist {
+                name,
+                decklist: decklist.to_string(),
+                parsed_at: OffsetDateTime::now_utc(),
+            },
+        );
+        self.decklists.truncate(MAX_RECENT_DECKLISTS);
+    }
+
+    /// Load the history from a JSON file, starting empty if the file is missing or unreadable
+    pub fn load_from(path: &Path) -> Self {
+        if !path.exists() {
+            return DecklistHistory::default();
+        }
+
+        let loaded = fs::read_to_string(path)
+            .map_err(|e| ProxyError::Cache(format!("Failed to read decklist history: {}", e)))
+            .and_then(|content| {
+                serde_json::from_str(&content).map_err(|e| {
+                    ProxyError::Serialization(format!("Failed to parse decklist history: {}", e))
+                })
+            });
+        match loaded {
+            Ok(history) => history,
+            Err(e) => {
+                warn!(path = %path.display(), error = %e, "Failed to load decklist history, starting empty");
+                DecklistHistory::default()
+            }
+        }
+    }
+
+    /// Save the history to a JSON file, creating the parent directory if needed
+    pub fn save_to(&self, path: &Path) -> Result<(), ProxyError> {
+        if let Some(parent_dir) = path.parent() {
+            fs::create_dir_all(parent_dir).map_err(|e| {
+                ProxyError::Cache(format!("Failed to create history directory: {}", e))
+            })?;
+        }
+
+        let content = serde_json::to_string_pretty(self).map_err(|e| {
+            ProxyError::Serialization(format!("Failed to serialize decklist history: {}", e))
+        })?;
+        fs::write(path, content)
+            .map_err(|e| ProxyError::Cache(format!("Failed to write decklist history: {}", e)))?;
+
+        debug!(path = %path.display(), "Saved decklist history to disk");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decklist_history() {
+        let mut history = DecklistHistory::default();
+        history.record("Deck: Mono Red\n4 Lightning Bolt\n");
+        history.record("  \n1 Counterspell\n1 Memory Lapse");
+        history.record("\n\n");
+        assert_eq!(history.decklists.len(), 2);
+        assert_eq!(history.decklists[0].name, "1 Counterspell");
+        assert_eq!(
+            history.decklists[0].decklist,
+            "1 Counterspell\n1 Memory Lapse"
+        );
+        assert_eq!(history.decklists[1].name, "Mono Red");
+
+        // Parsing a decklist again moves it to the front
+        history.record("Deck: Mono Red\n4 Lightning Bolt");
+        assert_eq!(history.decklists.len(), 2);
+        assert_eq!(history.decklists[0].name, "Mono Red");
+
+        for number in 0..MAX_RECENT_DECKLISTS {
+            history.record(&format!("{} Island", number + 1));
+        }
+        assert_eq!(history.decklists.len(), MAX_RECENT_DECKLISTS);
+        assert_eq!(
+            history.decklists[0].name,
+            format!("{} Island", MAX_RECENT_DECKLISTS)
+        );
+
+        let path = std::env::temp_dir()
+            .join(format!("localhawk-history-test-{}", std::process::id()))
+            .join("decklist_history.json");
+        history.save_to(&path).unwrap();
+        assert_eq!(DecklistHistory::load_from(&path), history);
+
+        if let Some(parent) = path.parent() {
+            std::fs::remove_dir_all(parent).ok();
+        }
+    }
+}
@@ -0,0 +1,137 @@
+//! Hooks run after a generated PDF has been written, e.g. to send it to a printer,
+//! and printing through the system spooler
+
+use crate::error::ProxyError;
+use std::path::Path;
+use std::process::{Command, ExitStatus};
+use tracing::info;
+
+/// Placeholder in hook commands that is replaced by the path of the written PDF
+pub const OUTPUT_PLACEHOLDER: &str = "{}";
+
+/// Called with the path of every PDF written by `save_pdf`
+pub type PostGenerationHook = Box<dyn Fn(&Path) -> Result<(), ProxyError> + Send + Sync>;
+
+/// Write a generated PDF to `path`, then run the hooks in order
+///
+/// The first failing hook stops the remaining ones; the PDF stays written either way.
+pub fn save_pdf(pdf: &[u8], path: &Path, hooks: &[PostGenerationHook]) -> Result<(), ProxyError> {
+    std::fs::write(path, pdf)?;
+    for hook in hooks {
+        hook(path)?;
+    }
+    Ok(())
+}
+
+/// Hook that runs a shell command line with the PDF path
+///
+/// The path replaces every `{}` in the command, or is appended as the last argument
+/// when there is none, e.g. `lp -d office` or `open -a Preview {}`.
+pub fn command_hook(command: impl Into<String>) -> PostGenerationHook {
+    let command = command.into();
+    Box::new(move |output| run_command(&command, output))
+}
+
+/// Run a hook command line for `output` and wait for it to finish
+pub fn run_command(command: &str, output: &Path) -> Result<(), ProxyError> {
+    info!(command = %command, output = %output.display(), "Running post-generation command");
+    let status = shell_command(command, output)
+        .env("LOCALHAWK_OUTPUT", output)
+        .status()?;
+    check_status(command, status)
+}
+
+/// Send a PDF to the default printer through the system print spooler
+#[cfg(not(windows))]
+pub fn print_file(path: &Path) -> Result<(), ProxyError> {
+    // CUPS' lp is available on macOS and practically every Linux desktop
+    run_command("lp", path)
+}
+
+/// Send a PDF to the default printer through the application registered for printing PDFs
+#[cfg(windows)]
+pub fn print_file(path: &Path) -> Result<(), ProxyError> {
+    let quoted = format!("'{}'", path.display().to_string().replace('\'', "''"));
+    let status = Command::new("powershell")
+        .args([
+            "-NoProfile",
+            "-Command",
+            "Start-Process",
+            "-Verb",
+            "Print",
+            "-FilePath",
+        ])
+        .arg(quoted)
+        .status()?;
+    check_status("Start-Process -Verb Print", status)
+}
+
+fn check_status(command: &str, status: ExitStatus) -> Result<(), ProxyError> {
+    if status.success() {
+        Ok(())
+    } else {
+        Err(ProxyError::Io(std::io::Error::other(format!(
+            "Command '{}' failed ({})",
+            command, status
+        ))))
+    }
+}
+
+// The path is passed as a positional parameter rather than pasted into the command line,
+// so paths with spaces or quotes can't change what the shell runs
+#[cfg(not(windows))]
+fn shell_command(command: &str, output: &Path) -> Command {
+    let script = if command.contains(OUTPUT_PLACEHOLDER) {
+        command.replace(OUTPUT_PLACEHOLDER, "\"$1\"")
+    } else {
+        format!("{} \"$1\"", command)
+    };
+    let mut shell = Command::new("sh");
+    shell.arg("-c").arg(script).arg("localhawk").arg(output);
+    shell
+}
+
+#[cfg(windows)]
+fn shell_command(command: &str, output: &Path) -> Command {
+    let quoted = format!("\"{}\"", output.display());
+    let script = if command.contains(OUTPUT_PLACEHOLDER) {
+        command.replace(OUTPUT_PLACEHOLDER, &quoted)
+    } else {
+        format!("{} {}", command, quoted)
+    };
+    let mut shell = Command::new("cmd");
+    shell.arg("/C").arg(script);
+    shell
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::env;
+
+    #[test]
+    fn test_save_pdf_runs_hooks_with_output_path() {
+        let dir = env::temp_dir().join(format!("localhawk-hooks-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let output = dir.join("my proxies.pdf");
+        let copy = dir.join("copy.pdf");
+
+        let hooks = vec![
+            command_hook("test -f"),
+            command_hook(format!("cp {{}} '{}'", copy.display())),
+        ];
+        save_pdf(b"%PDF-1.3", &output, &hooks).unwrap();
+        assert_eq!(std::fs::read(&copy).unwrap(), b"%PDF-1.3");
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_failing_command_is_an_error() {
+        let output = env::temp_dir().join(format!(
+            "localhawk-hooks-missing-{}.pdf",
+            std::process::id()
+        ));
+        assert!(run_command("test -f", &output).is_err());
+    }
+}
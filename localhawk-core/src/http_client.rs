@@ -4,10 +4,11 @@
 #[cfg(feature = "ios")]
 use crate::{
     error::ProxyError,
-    scryfall::models::{Card, CardSearchResult, ScryfallCardNames, ScryfallSearchAnswer, ScryfallSetCodes, ScryfallSetsResponse},
+    scryfall::api::MAX_PRINTING_PAGES,
+    scryfall::models::{Card, CardSearchResult, ScryfallCardNames, ScryfallSearchAnswer, ScryfallSetCodes, ScryfallSetsResponse, SetInfo},
 };
 #[cfg(feature = "ios")]
-use log::debug;
+use tracing::{debug, warn};
 #[cfg(feature = "ios")]
 use std::sync::Mutex;
 #[cfg(feature = "ios")]
@@ -24,6 +25,38 @@ const SCRYFALL_COOLDOWN: Duration = Duration::from_millis(100);
 lazy_static::lazy_static! {
     static ref LAST_SCRYFALL_CALL: Mutex<Instant> =
         Mutex::new(Instant::now() - SCRYFALL_COOLDOWN);
+    static ref HTTP_TIMEOUTS: Mutex<HttpTimeouts> = Mutex::new(HttpTimeouts::default());
+}
+
+/// Timeouts applied to every request of the sync client, so a dead network fails instead of hanging
+#[cfg(feature = "ios")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HttpTimeouts {
+    /// Time allowed for establishing a connection
+    pub connect: Duration,
+    /// Time allowed between received bytes of a response
+    pub read: Duration,
+}
+
+#[cfg(feature = "ios")]
+impl Default for HttpTimeouts {
+    fn default() -> Self {
+        HttpTimeouts {
+            connect: Duration::from_secs(10),
+            read: Duration::from_secs(30),
+        }
+    }
+}
+
+/// Set the timeouts used by sync clients created from now on
+#[cfg(feature = "ios")]
+pub fn set_http_timeouts(timeouts: HttpTimeouts) {
+    *HTTP_TIMEOUTS.lock().unwrap() = timeouts;
+}
+
+#[cfg(feature = "ios")]
+pub fn get_http_timeouts() -> HttpTimeouts {
+    *HTTP_TIMEOUTS.lock().unwrap()
 }
 
 /// Trait for sync HTTP operations (iOS only)
@@ -44,8 +77,11 @@ pub struct UreqHttpClient {
 #[cfg(feature = "ios")]
 impl UreqHttpClient {
     pub fn new() -> Result<Self, ProxyError> {
+        let timeouts = get_http_timeouts();
         let agent = ureq::AgentBuilder::new()
             .user_agent(USER_AGENT)
+            .timeout_connect(timeouts.connect)
+            .timeout_read(timeouts.read)
             .build();
 
         Ok(UreqHttpClient { agent })
@@ -88,7 +124,7 @@ impl UreqHttpClient {
             "https://api.scryfall.com/cards/search?q=name:\"{}\"&unique=prints",
             encoded_name
         );
-        log::debug!("Searching for meld result with URI: {}", uri);
+        debug!("Searching for meld result with URI: {}", uri);
 
         let response = self.call_with_rate_limit(&uri)?;
         let answer: crate::scryfall::models::ScryfallSearchAnswer = response
@@ -103,7 +139,7 @@ impl UreqHttpClient {
         }
 
         // Debug: Log all search results for the meld result
-        log::debug!(
+        debug!(
             "Meld search for '{}' returned {} cards:",
             meld_result_name,
             answer.data.len()
@@ -114,7 +150,7 @@ impl UreqHttpClient {
         for (i, card_data) in answer.data.iter().enumerate() {
             match crate::scryfall::models::Card::from_scryfall_object(&card_data) {
                 Ok(card) => {
-                    log::debug!(
+                    debug!(
                         "  [{}] '{}' (set: {}) - URL: {}",
                         i,
                         card.name,
@@ -124,7 +160,7 @@ impl UreqHttpClient {
                     all_meld_cards.push(card);
                 }
                 Err(e) => {
-                    log::debug!("Failed to parse meld result card: {}", e);
+                    debug!("Failed to parse meld result card: {}", e);
                     continue;
                 }
             }
@@ -144,7 +180,7 @@ impl UreqHttpClient {
                 ProxyError::InvalidCard("No meld result card available".to_string())
             })?;
 
-        log::debug!(
+        debug!(
             "Selected meld result '{}' (set: {}) for original card set '{}'",
             meld_card.name,
             meld_card.set,
@@ -174,6 +210,16 @@ impl HttpClient for UreqHttpClient {
             .into_json()
             .map_err(|e| ProxyError::Serialization(format!("Failed to parse sets: {}", e)))?;
 
+        let icon_uris = sets_response
+            .data
+            .iter()
+            .filter_map(|set| Some((set.code.to_lowercase(), set.icon_svg_uri.clone()?)))
+            .collect();
+        let sets = sets_response
+            .data
+            .iter()
+            .map(|set| (set.code.to_lowercase(), SetInfo::from(set)))
+            .collect();
         let codes = sets_response
             .data
             .into_iter()
@@ -183,6 +229,8 @@ impl HttpClient for UreqHttpClient {
         let mut set_codes = ScryfallSetCodes {
             date: Some(time::OffsetDateTime::now_utc()),
             codes,
+            icon_uris,
+            sets,
         };
 
         // Simple post processing - just sort set codes
@@ -198,22 +246,34 @@ impl HttpClient for UreqHttpClient {
             encoded_name
         );
 
-        debug!("Searching Scryfall with URI: {}", uri);
-        let response = self.call_with_rate_limit(&uri)?;
-
-        let answer: ScryfallSearchAnswer = response
-            .into_json()
-            .map_err(|e| ProxyError::Serialization(format!("Failed to parse search results: {}", e)))?;
-
+        // Follow the result pages like scryfall/api.rs, up to the same limit
+        let mut uri = uri;
         let mut cards = Vec::new();
+        let mut has_more = false;
+        for page in 1..=MAX_PRINTING_PAGES {
+            debug!("Searching Scryfall with URI: {}", uri);
+            let response = self.call_with_rate_limit(&uri)?;
+
+            let answer: ScryfallSearchAnswer = response
+                .into_json()
+                .map_err(|e| ProxyError::Serialization(format!("Failed to parse search results: {}", e)))?;
+
+            for card_data in answer.data {
+                match Card::from_scryfall_object(&card_data) {
+                    Ok(card) => cards.push(card),
+                    Err(e) => {
+                        debug!("Skipping card due to conversion error: {}", e);
+                        continue;
+                    }
+                }
+            }
 
-        for card_data in answer.data {
-            match Card::from_scryfall_object(&card_data) {
-                Ok(card) => cards.push(card),
-                Err(e) => {
-                    debug!("Skipping card due to conversion error: {}", e);
-                    continue;
+            match answer.next_page {
+                Some(next_page) if answer.has_more => {
+                    has_more = page == MAX_PRINTING_PAGES;
+                    uri = next_page;
                 }
+                _ => break,
             }
         }
 
@@ -233,7 +293,7 @@ impl HttpClient for UreqHttpClient {
             }) = &mut card.back_side
             {
                 if meld_result_image_url.is_empty() {
-                    log::debug!(
+                    debug!(
                         "Resolving meld result '{}' for card '{}'",
                         meld_result_name,
                         card.name
@@ -242,7 +302,7 @@ impl HttpClient for UreqHttpClient {
                     // Search for the meld result card, prioritizing same set
                     match self.search_meld_result(&meld_result_name, &card.set) {
                         Ok(meld_card) => {
-                            log::debug!(
+                            debug!(
                                 "Found meld result '{}' (set: {}) for card '{}' (set: {})",
                                 meld_card.name,
                                 meld_card.set,
@@ -252,7 +312,7 @@ impl HttpClient for UreqHttpClient {
                             *meld_result_image_url = meld_card.border_crop.clone();
                         }
                         Err(e) => {
-                            log::warn!(
+                            warn!(
                                 "Failed to resolve meld result '{}' for card '{}': {}",
                                 meld_result_name,
                                 card.name,
@@ -267,15 +327,27 @@ impl HttpClient for UreqHttpClient {
         Ok(CardSearchResult {
             cards: processed_cards.clone(),
             total_found: processed_cards.len(),
+            has_more,
         })
     }
 
     fn get_image_bytes(&self, url: &str) -> Result<Vec<u8>, ProxyError> {
+        use std::io::Read;
+
         let response = self.call_with_rate_limit(url)?;
         
+        // Read chunk by chunk, keeping to the download rate limit like the async client
+        let mut reader = response.into_reader();
         let mut bytes = Vec::new();
-        std::io::copy(&mut response.into_reader(), &mut bytes)
-            .map_err(|e| ProxyError::Io(e))?;
+        let mut chunk = [0u8; 16 * 1024];
+        loop {
+            let read = reader.read(&mut chunk).map_err(ProxyError::Io)?;
+            if read == 0 {
+                break;
+            }
+            crate::scryfall::throttle::throttle_download_blocking(read);
+            bytes.extend_from_slice(&chunk[..read]);
+        }
         
         Ok(bytes)
     }
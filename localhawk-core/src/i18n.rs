@@ -0,0 +1,647 @@
+//! Translations of user-facing texts shown by the frontends and generated in core
+//!
+//! Texts are looked up by [`Text`] key in the current [`UiLanguage`], which frontends set
+//! from the settings at startup. Placeholders (`{}`) are filled in order by [`tr_with`].
+
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+static UI_LANGUAGE: AtomicU8 = AtomicU8::new(UiLanguage::English as u8);
+
+/// Language of the user interface
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum UiLanguage {
+    #[default]
+    English = 0,
+    Japanese = 1,
+}
+
+impl UiLanguage {
+    pub fn all() -> Vec<UiLanguage> {
+        vec![UiLanguage::English, UiLanguage::Japanese]
+    }
+}
+
+impl std::fmt::Display for UiLanguage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Each language is listed under its own name
+        match self {
+            UiLanguage::English => write!(f, "English"),
+            UiLanguage::Japanese => write!(f, "日本語"),
+        }
+    }
+}
+
+/// Set the language used by [`tr`] and [`tr_with`]
+pub fn set_ui_language(language: UiLanguage) {
+    UI_LANGUAGE.store(language as u8, Ordering::Relaxed);
+}
+
+/// The language used by [`tr`] and [`tr_with`]
+pub fn ui_language() -> UiLanguage {
+    match UI_LANGUAGE.load(Ordering::Relaxed) {
+        1 => UiLanguage::Japanese,
+        _ => UiLanguage::English,
+    }
+}
+
+/// Translatable texts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Text {
+    // Errors
+    NetworkError,
+    JsonError,
+    SerializationError,
+    PdfError,
+    CacheError,
+    InvalidCardError,
+    IoError,
+    ScryfallUnavailableError,
+    ScryfallUnavailableUsingCache,
+
+    // Background refresh of card names and set codes
+    UpToDate,
+    RefreshingCardNamesAndSetCodes,
+    RefreshingCardNames,
+    RefreshingSetCodes,
+    RefreshedSuccessfully,
+    RefreshFailed,
+
+    // Summaries of parsed decklists
+    NoCards,
+    CardsSummary,
+    EstimatedPrice,
+    EstimatedPriceWithUnpriced,
+    CardBanned,
+    CardNotLegal,
+    CardRestricted,
+    CardLegal,
+    BackFaceUsingFront,
+    BackFaceFrontOnly,
+    BackFaceSkipped,
+    BackFacePlaceholder,
+
+    // Frontend labels and messages
+    DecklistParser,
+    PasteDecklist,
+    RecentDecklists,
+    PdfFromDecklist,
+    Preview,
+    PdfFromPreview,
+    CopyDecklist,
+    DecklistCopied,
+    Print,
+    FaceMode,
+    MissingBackFace,
+    DefaultPrinting,
+    CommentGroups,
+    PreferredLanguage,
+    MaxDownloadRate,
+    InterfaceLanguage,
+    PageSize,
+    Rows,
+    Columns,
+    ImageQuality,
+    ParsedCards,
+    ResolvedCardsHint,
+    ResolvedCardsPlaceholder,
+    AdvancedOptions,
+    ErrorLabel,
+    EnterDecklistFirst,
+    ParseDecklistFirst,
+    NoCardsToPreview,
+    GridPreviewBuilt,
+    GridPreviewFailed,
+    GeneratingPdf,
+    GeneratingPdfForPrinting,
+    PdfGenerated,
+    PdfSize,
+    PdfImagesProgress,
+    SelectedImagesProgress,
+    AlternativeImagesProgress,
+    PageImagesReady,
+    PdfGeneratedOpeningSaveDialog,
+    PdfGenerationFailed,
+    PdfSaved,
+    NoPdfToSave,
+    SaveCancelled,
+    SendingToPrinter,
+    SentToPrinter,
+    PrintingFailed,
+    CardNamesUpdated,
+    CardNameUpdateFailed,
+    ScryfallSearch,
+    SearchQueryPlaceholder,
+    Search,
+    Searching,
+    SearchResultsFound,
+    MorePrintingsOnScryfall,
+    ComparePrintings,
+    ComparePrintingsHint,
+    UseThisPrinting,
+    BackToPrintings,
+    FaceFront,
+    FaceBack,
+    FaceBoth,
+    AddToDecklist,
+    WarningLabel,
+    ShowDetails,
+    HideDetails,
+    DismissAll,
+    Retry,
+    TaskTimedOut,
+    ImagesFailedToLoad,
+    DecklistParseFailed,
+    NoPrintingsFound,
+    CardDataRefreshFailed,
+    Diagnostics,
+    WriteLogFiles,
+    ExportLogs,
+    LogsExported,
+    LogExportFailed,
+    RunDiagnostics,
+    RunningDiagnostics,
+    CopyReport,
+    DiagnosticsCopied,
+    ShowCachedCards,
+    HideCachedCards,
+    CachedCardsSummary,
+    UnknownCachedImages,
+    ImageCacheEviction,
+    PinDeck,
+    PinDeckNeedsName,
+    PinDeckFailed,
+    PinnedDeckImages,
+    Unpin,
+    UsageStatistics,
+    CollectUsageStats,
+    ResetUsageStats,
+    UsageStatsResetFailed,
+    StatsSince,
+    StatsPdfsGenerated,
+    StatsCardsGenerated,
+    StatsAveragePdfTime,
+    StatsImageCacheHitRate,
+    StatsSearchCacheHitRate,
+    PdfPartsSaved,
+    FileName,
+    FileNameTemplateHelp,
+    ExistingFiles,
+    PagesPerFile,
+    AllPages,
+    ScaleCorrection,
+    CalibrationPage,
+    MeasuredRuler,
+    ApplyCalibration,
+    ResetCalibration,
+    CalibrationPageSaved,
+    CalibrationFailed,
+    FillerCards,
+    FillerCardsPlaceholder,
+    ApplyFillerCards,
+    BasicLandSets,
+    BasicLandSetsPlaceholder,
+    CardOrder,
+    Copies,
+    Sections,
+    LineSkipped,
+    CouldNotResolve,
+    CouldNotResolveSuggestion,
+    SuggestedToken,
+    DownloadEstimate,
+    ConfirmLargeDownload,
+    Download,
+    Cancel,
+    DownloadCancelled,
+    PrintEstimate,
+    ConfirmLargePrint,
+    GenerateAnyway,
+    GenerationCancelled,
+}
+
+/// Text for `key` in `language`
+pub fn translate(language: UiLanguage, key: Text) -> &'static str {
+    let (english, japanese) = match key {
+        Text::NetworkError => ("Network error: {}", "ネットワークエラー: {}"),
+        Text::JsonError => ("JSON parsing error: {}", "JSON解析エラー: {}"),
+        Text::SerializationError => ("Serialization error: {}", "シリアライズエラー: {}"),
+        Text::PdfError => ("PDF generation error: {}", "PDF生成エラー: {}"),
+        Text::CacheError => ("Cache error: {}", "キャッシュエラー: {}"),
+        Text::InvalidCardError => ("Invalid card: {}", "無効なカード: {}"),
+        Text::IoError => ("IO error: {}", "入出力エラー: {}"),
+        Text::ScryfallUnavailableError => (
+            "Scryfall is unavailable: {}",
+            "Scryfallは現在利用できません: {}",
+        ),
+        Text::ScryfallUnavailableUsingCache => (
+            "Scryfall unavailable, using cached data",
+            "Scryfallが利用できないため、キャッシュのデータを使用しています",
+        ),
+
+        Text::UpToDate => ("Up to date", "最新です"),
+        Text::RefreshingCardNamesAndSetCodes => (
+            "Refreshing card names and set codes...",
+            "カード名とセットコードを更新中...",
+        ),
+        Text::RefreshingCardNames => ("Refreshing card names...", "カード名を更新中..."),
+        Text::RefreshingSetCodes => ("Refreshing set codes...", "セットコードを更新中..."),
+        Text::RefreshedSuccessfully => ("Refreshed successfully", "更新しました"),
+        Text::RefreshFailed => ("Refresh failed: {}", "更新に失敗しました: {}"),
+
+        Text::NoCards => ("No cards", "カードなし"),
+        Text::CardsSummary => ("{} cards ({} unique)", "{}枚（{}種類）"),
+        Text::EstimatedPrice => ("Estimated price: ${}", "推定価格: ${}"),
+        Text::EstimatedPriceWithUnpriced => (
+            "Estimated price: ${} ({} cards without a USD price)",
+            "推定価格: ${}（USD価格のないカード{}枚）",
+        ),
+        Text::CardBanned => ("{}: banned", "{}: 禁止"),
+        Text::CardNotLegal => ("{}: not legal", "{}: 使用不可"),
+        Text::CardRestricted => (
+            "{}: restricted ({} copies, at most 1 allowed)",
+            "{}: 制限（{}枚、1枚まで）",
+        ),
+        Text::CardLegal => ("{}: legal", "{}: 使用可"),
+        Text::BackFaceUsingFront => (
+            "{}: back face unavailable, printing the front instead",
+            "{}: 裏面の画像がないため、表面を印刷します",
+        ),
+        Text::BackFaceFrontOnly => (
+            "{}: back face unavailable, printing only the front",
+            "{}: 裏面の画像がないため、表面のみ印刷します",
+        ),
+        Text::BackFaceSkipped => (
+            "{}: back face unavailable, not printed",
+            "{}: 裏面の画像がないため、印刷しません",
+        ),
+        Text::BackFacePlaceholder => (
+            "{}: back face unavailable, printing a placeholder back",
+            "{}: 裏面の画像がないため、代わりの裏面を印刷します",
+        ),
+
+        Text::DecklistParser => ("Decklist Parser:", "デッキリスト:"),
+        Text::PasteDecklist => (
+            "Paste your decklist below (supports various formats):",
+            "デッキリストを貼り付けてください（各種形式に対応）:",
+        ),
+        Text::RecentDecklists => ("Recent", "最近のデッキ"),
+        Text::PdfFromDecklist => ("PDF from Decklist", "デッキからPDF"),
+        Text::Preview => ("Preview", "プレビュー"),
+        Text::PdfFromPreview => ("PDF from Preview", "プレビューからPDF"),
+        Text::CopyDecklist => ("Copy Decklist", "デッキリストをコピー"),
+        Text::DecklistCopied => (
+            "Copied the decklist ({} cards) with the selected printings to the clipboard",
+            "選択した版のデッキリスト（{}枚）をクリップボードにコピーしました",
+        ),
+        Text::Print => ("Print...", "印刷..."),
+        Text::FaceMode => ("Face Mode:", "両面カード:"),
+        Text::MissingBackFace => ("Missing back:", "裏面がない場合:"),
+        Text::DefaultPrinting => ("Scryfall's default printing", "Scryfallの標準の版"),
+        Text::CommentGroups => ("Comments group cards", "コメントでカードをグループ化"),
+        Text::PreferredLanguage => ("Card language:", "カードの言語:"),
+        Text::MaxDownloadRate => ("Max download KB/s:", "最大ダウンロード速度 (KB/s):"),
+        Text::InterfaceLanguage => ("Interface language:", "表示言語:"),
+        Text::PageSize => ("Page Size:", "用紙サイズ:"),
+        Text::Rows => ("Rows:", "行:"),
+        Text::Columns => ("Columns:", "列:"),
+        Text::ImageQuality => ("Image Quality:", "画質:"),
+        Text::ParsedCards => ("Parsed Cards:", "読み込んだカード:"),
+        Text::ResolvedCardsHint => (
+            "Resolved cards will appear here after parsing:",
+            "読み込み後、ここにカードが表示されます:",
+        ),
+        Text::ResolvedCardsPlaceholder => (
+            "Resolved cards will appear here after parsing...",
+            "読み込み後、ここにカードが表示されます...",
+        ),
+        Text::AdvancedOptions => ("Advanced Options", "詳細設定"),
+        Text::ErrorLabel => ("Error:", "エラー:"),
+        Text::EnterDecklistFirst => (
+            "Please enter a decklist first!",
+            "先にデッキリストを入力してください。",
+        ),
+        Text::ParseDecklistFirst => (
+            "Please parse a decklist first!",
+            "先にデッキリストを読み込んでください。",
+        ),
+        Text::NoCardsToPreview => (
+            "No cards parsed to build preview",
+            "プレビューするカードがありません",
+        ),
+        Text::GridPreviewBuilt => (
+            "Grid preview built with {} pages",
+            "{}ページのプレビューを作成しました",
+        ),
+        Text::GridPreviewFailed => (
+            "Failed to build grid preview",
+            "プレビューを作成できませんでした",
+        ),
+        Text::GeneratingPdf => (
+            "Generating PDF and opening save dialog...",
+            "PDFを生成し、保存ダイアログを開きます...",
+        ),
+        Text::GeneratingPdfForPrinting => (
+            "Generating PDF for printing...",
+            "印刷用のPDFを生成しています...",
+        ),
+        Text::PdfGenerated => ("PDF Generated!", "PDFを生成しました"),
+        Text::PdfSize => ("Size: {} KB", "サイズ: {} KB"),
+        Text::PdfImagesProgress => ("Images: {}/{}", "画像: {}/{}"),
+        Text::SelectedImagesProgress => {
+            ("Selected printings: {}/{} entries", "選択した版: {}/{}件")
+        }
+        Text::AlternativeImagesProgress => ("Other printings: {}/{} images", "その他の版: {}/{}枚"),
+        Text::PageImagesReady => ("Images ready {}/{}", "画像の準備 {}/{}"),
+        Text::PdfGeneratedOpeningSaveDialog => (
+            "PDF generated successfully! {} bytes - Opening save dialog...",
+            "PDFを生成しました（{}バイト）。保存先を選んでください...",
+        ),
+        Text::PdfGenerationFailed => ("PDF generation failed!", "PDFの生成に失敗しました"),
+        Text::PdfSaved => ("PDF saved successfully to: {}", "PDFを保存しました: {}"),
+        Text::NoPdfToSave => ("No PDF data to save!", "保存するPDFがありません"),
+        Text::SaveCancelled => ("Save cancelled.", "保存をキャンセルしました。"),
+        Text::SendingToPrinter => (
+            "PDF generated - sending it to the printer...",
+            "PDFを生成しました。プリンターに送信しています...",
+        ),
+        Text::SentToPrinter => ("Sent {} to the printer.", "{}をプリンターに送信しました。"),
+        Text::PrintingFailed => ("Printing failed!", "印刷に失敗しました"),
+        Text::CardNamesUpdated => ("Card names updated successfully!", "カード名を更新しました"),
+        Text::CardNameUpdateFailed => ("Card name update failed!", "カード名の更新に失敗しました"),
+        Text::ScryfallSearch => ("Scryfall Search", "Scryfall検索"),
+        Text::SearchQueryPlaceholder => (
+            "Scryfall syntax, e.g. t:goblin e:dom lang:ja",
+            "Scryfallの検索構文（例: t:goblin e:dom lang:ja）",
+        ),
+        Text::Search => ("Search", "検索"),
+        Text::Searching => ("Searching...", "検索中..."),
+        Text::SearchResultsFound => (
+            "{} cards found, showing {}:",
+            "{}枚見つかりました（{}枚を表示）:",
+        ),
+        Text::MorePrintingsOnScryfall => (
+            "(Scryfall has more printings than are shown)",
+            "（Scryfallには表示しきれない印刷版があります）",
+        ),
+        Text::ComparePrintings => ("Compare", "比較"),
+        Text::ComparePrintingsHint => (
+            "Click two printings to compare them side by side",
+            "比較する印刷版を2つクリックしてください",
+        ),
+        Text::UseThisPrinting => ("Use this printing", "この印刷版を使う"),
+        Text::BackToPrintings => ("Back to all printings", "印刷版の一覧に戻る"),
+        Text::FaceFront => ("Front", "表"),
+        Text::FaceBack => ("Back", "裏"),
+        Text::FaceBoth => ("Both", "両面"),
+        Text::AddToDecklist => ("Add", "追加"),
+        Text::WarningLabel => ("Warning:", "警告:"),
+        Text::ShowDetails => ("Details", "詳細"),
+        Text::HideDetails => ("Hide details", "詳細を隠す"),
+        Text::DismissAll => ("Dismiss all", "すべて閉じる"),
+        Text::Retry => ("Retry", "再試行"),
+        Text::TaskTimedOut => (
+            "No answer after {} seconds, check the connection and retry",
+            "{}秒待っても応答がありません。接続を確認して再試行してください",
+        ),
+        Text::ImagesFailedToLoad => (
+            "{} image(s) failed to load",
+            "{}件の画像を読み込めませんでした",
+        ),
+        Text::DecklistParseFailed => (
+            "Failed to parse the decklist",
+            "デッキリストを読み込めませんでした",
+        ),
+        Text::NoPrintingsFound => ("No printings found for {}", "{}の版が見つかりませんでした"),
+        Text::CardDataRefreshFailed => (
+            "Automatic card data refresh failed",
+            "カードデータの自動更新に失敗しました",
+        ),
+        Text::Diagnostics => ("Diagnostics", "診断"),
+        Text::WriteLogFiles => (
+            "Write log files (applies after restart)",
+            "ログファイルを書き込む（再起動後に有効）",
+        ),
+        Text::ExportLogs => ("Export Logs...", "ログを書き出す..."),
+        Text::LogsExported => (
+            "Exported {} log file(s) to {}",
+            "{}件のログファイルを{}に書き出しました",
+        ),
+        Text::LogExportFailed => ("Log export failed", "ログを書き出せませんでした"),
+        Text::RunDiagnostics => ("Run Diagnostics", "診断を実行"),
+        Text::RunningDiagnostics => ("Running diagnostics...", "診断を実行中..."),
+        Text::CopyReport => ("Copy Report", "レポートをコピー"),
+        Text::DiagnosticsCopied => (
+            "Diagnostics report copied to the clipboard",
+            "診断レポートをクリップボードにコピーしました",
+        ),
+        Text::ShowCachedCards => ("Show Cached Cards", "キャッシュ済みのカードを表示"),
+        Text::HideCachedCards => ("Hide Cached Cards", "キャッシュ済みのカードを隠す"),
+        Text::CachedCardsSummary => (
+            "{} printings of {} cards, {} cards with all images (✓)",
+            "{}種類の版・{}枚のカード、すべての画像があるカード{}枚 (✓)",
+        ),
+        Text::UnknownCachedImages => (
+            "{} more images belong to cards whose searches are no longer cached",
+            "ほかに検索結果がキャッシュにないカードの画像{}枚",
+        ),
+        Text::ImageCacheEviction => ("When full, evict:", "満杯のとき削除:"),
+        Text::PinDeck => (
+            "Keep This Deck's Images Cached",
+            "このデッキの画像をキャッシュに保持",
+        ),
+        Text::PinDeckNeedsName => (
+            "Name the deck with a leading \"Deck: NAME\" line to pin its images",
+            "画像を保持するには先頭の「Deck: 名前」行でデッキに名前を付けてください",
+        ),
+        Text::PinDeckFailed => (
+            "Failed to pin the deck",
+            "デッキの画像を保持できませんでした",
+        ),
+        Text::PinnedDeckImages => (
+            "• {}: {} of {} images cached",
+            "• {}: {}/{}枚の画像がキャッシュ済み",
+        ),
+        Text::Unpin => ("Unpin", "保持を解除"),
+        Text::UsageStatistics => ("Usage Statistics", "利用統計"),
+        Text::CollectUsageStats => (
+            "Collect usage statistics (kept on this computer only)",
+            "利用統計を記録する（このコンピューター内にのみ保存）",
+        ),
+        Text::ResetUsageStats => ("Reset Statistics", "統計をリセット"),
+        Text::UsageStatsResetFailed => (
+            "Failed to reset usage statistics",
+            "利用統計をリセットできませんでした",
+        ),
+        Text::StatsSince => ("Collecting since: {}", "記録開始: {}"),
+        Text::StatsPdfsGenerated => ("PDFs generated: {}", "生成したPDF: {}"),
+        Text::StatsCardsGenerated => ("Cards generated: {}", "生成したカード: {}"),
+        Text::StatsAveragePdfTime => ("Average PDF time: {}", "PDFの平均生成時間: {}"),
+        Text::StatsImageCacheHitRate => (
+            "Image cache hit rate: {} ({} hits, {} misses)",
+            "画像キャッシュのヒット率: {}（ヒット{}件、ミス{}件）",
+        ),
+        Text::StatsSearchCacheHitRate => (
+            "Search cache hit rate: {} ({} hits, {} misses)",
+            "検索キャッシュのヒット率: {}（ヒット{}件、ミス{}件）",
+        ),
+        Text::PdfPartsSaved => ("Saved {} PDFs to {}", "{}個のPDFを{}に保存しました"),
+        Text::FileName => ("File name:", "ファイル名:"),
+        Text::FileNameTemplateHelp => (
+            "{deck}: deck name, {date}: today's date, {part}: file number when splitting",
+            "{deck}: デッキ名、{date}: 今日の日付、{part}: 分割時のファイル番号",
+        ),
+        Text::ExistingFiles => ("Existing files:", "既存のファイル:"),
+        Text::PagesPerFile => ("Pages per file:", "ファイルあたりのページ数:"),
+        Text::AllPages => ("all", "全部"),
+        Text::ScaleCorrection => ("Scale correction: {}", "倍率補正: {}"),
+        Text::CalibrationPage => ("Calibration page...", "調整用ページ..."),
+        Text::MeasuredRuler => ("Measured ruler (mm)", "定規の実測値 (mm)"),
+        Text::ApplyCalibration => ("Apply", "適用"),
+        Text::ResetCalibration => ("Reset", "リセット"),
+        Text::CalibrationPageSaved => (
+            "Calibration page saved to {}; print it at 100% and measure its ruler",
+            "調整用ページを{}に保存しました。100%で印刷して定規を測ってください",
+        ),
+        Text::CalibrationFailed => ("Calibration failed", "倍率を調整できませんでした"),
+        Text::FillerCards => ("Fill last page with:", "最後のページの空きを埋めるカード:"),
+        Text::FillerCardsPlaceholder => ("e.g. Plains, Island", "例: Plains, Island"),
+        Text::ApplyFillerCards => ("Apply", "適用"),
+        Text::BasicLandSets => ("Basic land art from sets:", "基本土地のイラストのセット:"),
+        Text::BasicLandSetsPlaceholder => ("e.g. UNF, ZEN", "例: UNF, ZEN"),
+        Text::CardOrder => ("Card order:", "カードの順番:"),
+        Text::Copies => ("Copies:", "コピー:"),
+        Text::Sections => ("Sections:", "セクション:"),
+        Text::LineSkipped => (
+            "✗ skipped '{}': not a card line",
+            "✗ '{}'を飛ばしました: カードの行ではありません",
+        ),
+        Text::CouldNotResolve => ("✗ could not resolve '{}'", "✗ '{}'が見つかりませんでした"),
+        Text::CouldNotResolveSuggestion => (
+            "✗ could not resolve '{}' – did you mean {}?",
+            "✗ '{}'が見つかりませんでした – {}のことですか？",
+        ),
+        Text::SuggestedToken => ("Suggested token for {}: {}", "{}のトークン候補: {}"),
+        Text::DownloadEstimate => (
+            "~{} MB of images will be downloaded ({} images)",
+            "約{} MBの画像をダウンロードします（{}枚）",
+        ),
+        Text::ConfirmLargeDownload => (
+            "~{} MB of images will be downloaded ({} images). Download them now?",
+            "約{} MBの画像をダウンロードします（{}枚）。今ダウンロードしますか？",
+        ),
+        Text::Download => ("Download", "ダウンロード"),
+        Text::Cancel => ("Cancel", "キャンセル"),
+        Text::DownloadCancelled => (
+            "Download cancelled. Parse again to load the images.",
+            "ダウンロードをキャンセルしました。画像を読み込むにはもう一度解析してください。",
+        ),
+        Text::PrintEstimate => (
+            "{} pages ({} sheets, {} double-sided) with {} cards, {} of them back faces, ~{} ml of ink",
+            "{}ページ（用紙{}枚、両面印刷なら{}枚）、カード{}枚（うち裏面{}枚）、インク約{} ml",
+        ),
+        Text::ConfirmLargePrint => (
+            "The PDF will have {} pages. Check the decklist; generate it anyway?",
+            "PDFは{}ページになります。デッキリストを確認してください。このまま作成しますか？",
+        ),
+        Text::GenerateAnyway => ("Generate anyway", "このまま作成"),
+        Text::GenerationCancelled => (
+            "PDF generation cancelled.",
+            "PDFの作成をキャンセルしました。",
+        ),
+    };
+    match language {
+        UiLanguage::English => english,
+        UiLanguage::Japanese => japanese,
+    }
+}
+
+/// Text for `key` in the current language
+pub fn tr(key: Text) -> &'static str {
+    translate(ui_language(), key)
+}
+
+/// Text for `key` in the current language, with its placeholders filled from `args`
+pub fn tr_with(key: Text, args: &[&dyn Display]) -> String {
+    fill_placeholders(tr(key), args)
+}
+
+fn fill_placeholders(template: &str, args: &[&dyn Display]) -> String {
+    let mut parts = template.split("{}");
+    let mut filled = parts.next().unwrap_or_default().to_string();
+    for (index, part) in parts.enumerate() {
+        if let Some(arg) = args.get(index) {
+            filled.push_str(&arg.to_string());
+        }
+        filled.push_str(part);
+    }
+    filled
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fill_placeholders() {
+        let template = translate(UiLanguage::Japanese, Text::CardsSummary);
+        assert_eq!(fill_placeholders(template, &[&60, &23]), "60枚（23種類）");
+        let template = translate(UiLanguage::English, Text::CardsSummary);
+        assert_eq!(
+            fill_placeholders(template, &[&60, &23]),
+            "60 cards (23 unique)"
+        );
+        // Missing arguments leave the placeholder empty
+        assert_eq!(fill_placeholders("{} and {}", &[&"bolt"]), "bolt and ");
+    }
+
+    #[test]
+    fn test_translations_keep_placeholders() {
+        let keys = [
+            Text::NetworkError,
+            Text::ScryfallUnavailableError,
+            Text::RefreshFailed,
+            Text::CardsSummary,
+            Text::EstimatedPriceWithUnpriced,
+            Text::CardRestricted,
+            Text::GridPreviewBuilt,
+            Text::PdfGeneratedOpeningSaveDialog,
+            Text::PdfSaved,
+            Text::SentToPrinter,
+            Text::SearchResultsFound,
+            Text::ImagesFailedToLoad,
+            Text::NoPrintingsFound,
+            Text::LogsExported,
+            Text::CachedCardsSummary,
+            Text::UnknownCachedImages,
+            Text::PinnedDeckImages,
+            Text::StatsSince,
+            Text::StatsPdfsGenerated,
+            Text::StatsCardsGenerated,
+            Text::StatsAveragePdfTime,
+            Text::StatsImageCacheHitRate,
+            Text::StatsSearchCacheHitRate,
+            Text::PdfPartsSaved,
+            Text::ScaleCorrection,
+            Text::CalibrationPageSaved,
+            Text::LineSkipped,
+            Text::CouldNotResolve,
+            Text::CouldNotResolveSuggestion,
+            Text::SuggestedToken,
+            Text::DownloadEstimate,
+            Text::ConfirmLargeDownload,
+            Text::PrintEstimate,
+            Text::ConfirmLargePrint,
+            Text::DecklistCopied,
+            Text::TaskTimedOut,
+        ];
+        for key in keys {
+            assert_eq!(
+                translate(UiLanguage::English, key).matches("{}").count(),
+                translate(UiLanguage::Japanese, key).matches("{}").count(),
+                "{:?}",
+                key
+            );
+        }
+    }
+}
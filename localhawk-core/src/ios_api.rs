@@ -8,10 +8,135 @@ use crate::{
     globals::{get_image_cache, get_search_results_cache, get_set_codes_cache},
     http_client::{HttpClient, UreqHttpClient},
     lookup::NameMatchMode,
-    scryfall::models::{Card, CardSearchResult},
+    pdf::placeholder_back_image_bytes,
+    scryfall::models::{Card, CardSearchResult, PLACEHOLDER_BACK_URL},
     DoubleFaceMode,
 };
 
+#[cfg(feature = "ios")]
+use crate::background_loading::{LoadingPhase, select_card_from_printings};
+#[cfg(feature = "ios")]
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicU8, AtomicUsize, Ordering};
+
+/// Bumped to abort the background loading threads that are running
+#[cfg(feature = "ios")]
+static BACKGROUND_LOAD_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Abort flag of one background loading thread, checked between downloads
+#[cfg(feature = "ios")]
+#[derive(Debug, Clone, Copy)]
+pub struct BackgroundLoadAbort {
+    generation: u64,
+}
+
+#[cfg(feature = "ios")]
+impl BackgroundLoadAbort {
+    /// Flag for a load starting now, raised by the next abort_background_loading_sync
+    pub fn current() -> Self {
+        BackgroundLoadAbort {
+            generation: BACKGROUND_LOAD_GENERATION.load(Ordering::SeqCst),
+        }
+    }
+
+    pub fn is_aborted(&self) -> bool {
+        BACKGROUND_LOAD_GENERATION.load(Ordering::SeqCst) != self.generation
+    }
+}
+
+/// Abort all running background loading threads after their current download
+#[cfg(feature = "ios")]
+pub fn abort_background_loading_sync() {
+    BACKGROUND_LOAD_GENERATION.fetch_add(1, Ordering::SeqCst);
+}
+
+/// Progress of a background loading thread, shared with the FFI through atomic counters
+#[cfg(feature = "ios")]
+#[derive(Debug)]
+pub struct SyncBackgroundLoadProgress {
+    phase: AtomicU8,
+    pub current_entry: AtomicUsize,
+    pub total_entries: usize,
+    pub selected_loaded: AtomicUsize,
+    pub alternatives_loaded: AtomicUsize,
+    pub total_alternatives: AtomicUsize,
+    pub error_count: AtomicUsize,
+    cancelled: AtomicBool,
+    abort: BackgroundLoadAbort,
+}
+
+#[cfg(feature = "ios")]
+impl SyncBackgroundLoadProgress {
+    pub fn new(total_entries: usize) -> Self {
+        SyncBackgroundLoadProgress {
+            phase: AtomicU8::new(0),
+            current_entry: AtomicUsize::new(0),
+            total_entries,
+            selected_loaded: AtomicUsize::new(0),
+            alternatives_loaded: AtomicUsize::new(0),
+            total_alternatives: AtomicUsize::new(0),
+            error_count: AtomicUsize::new(0),
+            cancelled: AtomicBool::new(false),
+            abort: BackgroundLoadAbort::current(),
+        }
+    }
+
+    pub fn phase(&self) -> LoadingPhase {
+        match self.phase.load(Ordering::SeqCst) {
+            0 => LoadingPhase::Selected,
+            1 => LoadingPhase::Alternatives,
+            _ => LoadingPhase::Completed,
+        }
+    }
+
+    fn set_phase(&self, phase: LoadingPhase) {
+        let value = match phase {
+            LoadingPhase::Selected => 0,
+            LoadingPhase::Alternatives => 1,
+            LoadingPhase::Completed => 2,
+        };
+        self.phase.store(value, Ordering::SeqCst);
+    }
+
+    /// Stop this load after its current download
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst) || self.abort.is_aborted()
+    }
+
+    /// Whether the load completed or stopped after being cancelled
+    pub fn is_finished(&self) -> bool {
+        self.phase() == LoadingPhase::Completed
+    }
+
+    fn record_error(&self, message: String) {
+        tracing::warn!("{}", message);
+        self.error_count.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+/// Report how far a background loading thread got when it stopped
+#[cfg(feature = "ios")]
+fn report_background_load_finished(
+    name: &str,
+    abort: &BackgroundLoadAbort,
+    completed: usize,
+    total: usize,
+) {
+    let aborted = abort.is_aborted();
+    tracing::info!(
+        "Background loading '{}' {}: {}/{} images",
+        name,
+        if aborted { "aborted" } else { "finished" },
+        completed,
+        total
+    );
+    crate::ffi::queue_background_load_notification(name, aborted, completed, total);
+    crate::ffi::notify_image_cache_dispatch_source();
+}
+
 /// iOS sync API implementation
 #[cfg(feature = "ios")]
 pub struct ProxyGenerator;
@@ -32,33 +157,17 @@ impl ProxyGenerator {
         let mut card_list = Vec::new();
 
         for entry in entries {
-            log::debug!("Searching for card: '{}'", entry.name);
+            tracing::debug!("Searching for card: '{}'", entry.name);
             match Self::search_card_sync(&entry.name) {
                 Ok(search_result) => {
                     // Use the same card selection logic as the main ProxyGenerator
-                    let selected_card = search_result
-                        .cards
-                        .iter()
-                        .position(|c| {
-                            let name_matches = c.name.to_lowercase() == entry.name.to_lowercase();
-                            let set_matches = if let Some(ref entry_set) = entry.set {
-                                c.set.to_lowercase() == entry_set.to_lowercase()
-                            } else {
-                                true
-                            };
-                            let lang_matches = if let Some(ref entry_lang) = entry.lang {
-                                c.language.to_lowercase() == entry_lang.to_lowercase()
-                            } else {
-                                true
-                            };
-                            name_matches && set_matches && lang_matches
-                        })
+                    let selected_card = select_card_from_printings(&search_result.cards, entry)
                         .and_then(|idx| search_result.cards.get(idx))
                         .or_else(|| search_result.cards.first())
                         .cloned();
 
                     if let Some(card) = selected_card {
-                        card_list.push((card, entry.multiple as u32, entry.face_mode.clone()));
+                        card_list.push((card, entry.multiple.get(), entry.face_mode.clone()));
                     }
                 }
                 Err(_) => {
@@ -69,31 +178,37 @@ impl ProxyGenerator {
 
         // Automatically start background loading for resolved cards (like desktop)
         if !card_list.is_empty() {
-            println!("🚀 iOS: Auto-starting background loading for {} resolved cards", card_list.len());
+            tracing::debug!("iOS: Auto-starting background loading for {} resolved cards", card_list.len());
             
             // Start background loading in separate thread (fire and forget, like desktop)
             let cards_clone = card_list.clone();
+            let abort = BackgroundLoadAbort::current();
             std::thread::spawn(move || {
-                println!("🧵 iOS: Background loading thread started for {} resolved cards", cards_clone.len());
-                
-                for (card, quantity, face_mode) in &cards_clone {
-                    // Cache images for each copy of the card
-                    for _ in 0..*quantity {
-                        let urls = card.get_images_for_face_mode(face_mode);
-                        for url in urls {
-                            match get_or_fetch_image_bytes_sync(&url) {
-                                Ok(_) => {
-                                    println!("✅ iOS: Cached resolved image: {}", url);
-                                }
-                                Err(e) => {
-                                    println!("❌ iOS: Failed to cache resolved image {}: {:?}", url, e);
-                                }
-                            }
+                tracing::debug!("iOS: Background loading thread started for {} resolved cards", cards_clone.len());
+
+                // Every copy shares its images, so each card's images are only fetched once
+                let urls: Vec<String> = cards_clone
+                    .iter()
+                    .flat_map(|(card, _, face_mode)| card.get_images_for_face_mode(face_mode))
+                    .collect();
+                let mut completed = 0;
+
+                for url in &urls {
+                    if abort.is_aborted() {
+                        break;
+                    }
+                    match get_or_fetch_image_bytes_sync(url) {
+                        Ok(_) => {
+                            completed += 1;
+                            tracing::debug!("iOS: Cached resolved image: {}", url);
+                        }
+                        Err(e) => {
+                            tracing::warn!("iOS: Failed to cache resolved image {}: {:?}", url, e);
                         }
                     }
                 }
-                
-                println!("✅ iOS: Background loading completed for resolved cards");
+
+                report_background_load_finished("selected", &abort, completed, urls.len());
             });
         }
 
@@ -110,6 +225,9 @@ impl ProxyGenerator {
         use crate::decklist::parse_decklist;
         use crate::globals::find_card_name;
 
+        // Card names loaded at startup may still be indexed in the background
+        crate::globals::wait_for_card_lookup_sync();
+
         // Get required data for parsing
         let scryfall_languages = get_minimal_scryfall_languages();
         let set_codes = {
@@ -119,20 +237,23 @@ impl ProxyGenerator {
         };
         
         // Parse the decklist text using shared logic
-        let parsed_lines = parse_decklist(decklist_text, &scryfall_languages, &set_codes);
+        let mut parsed_lines = parse_decklist(decklist_text, &scryfall_languages, &set_codes);
+        if crate::globals::get_comment_annotations() {
+            crate::decklist::attach_comment_annotations(&mut parsed_lines);
+        }
         let parsed_entries: Vec<_> = parsed_lines.into_iter().filter_map(|line| line.as_entry()).collect();
         
         let mut resolved_entries = Vec::new();
         
         for mut entry in parsed_entries {
-            log::debug!(
+            tracing::debug!(
                 "📝 iOS Parse: Processing '{}' [set: {:?}, lang: {:?}]",
                 entry.name, entry.set, entry.lang
             );
             
             // Use shared business logic for name resolution (EXACTLY like desktop)
             if let Some(lookup_result) = find_card_name(&entry.name) {
-                log::debug!(
+                tracing::debug!(
                     "🔍 iOS Parse: Name resolved '{}' -> '{}' (keeping set: {:?}, lang: {:?})",
                     entry.name, lookup_result.name, entry.set, entry.lang
                 );
@@ -147,11 +268,11 @@ impl ProxyGenerator {
                     }
                 };
             } else {
-                log::debug!("🔍 iOS Parse: No name resolution for '{}'", entry.name);
+                tracing::debug!("🔍 iOS Parse: No name resolution for '{}'", entry.name);
                 entry.face_mode = global_face_mode.clone(); // No match: use global setting
             }
             
-            log::debug!(
+            tracing::debug!(
                 "✅ iOS Parse: Final entry '{}' [set: {:?}, lang: {:?}, face_mode: {:?}]",
                 entry.name, entry.set, entry.lang, entry.face_mode
             );
@@ -176,13 +297,17 @@ impl ProxyGenerator {
         };
         
         if let Some(bytes) = cached_bytes {
-            log::debug!("Image cache HIT for URL: {}", url);
+            tracing::debug!("Image cache HIT for URL: {}", url);
             return Ok(bytes);
         }
         
         // Cache miss - fetch from API using sync client
-        log::debug!("Image cache MISS for URL: {}, fetching...", url);
-        let image_bytes = client.get_image_bytes(url)?;
+        tracing::debug!("Image cache MISS for URL: {}, fetching...", url);
+        let image_bytes = if url == PLACEHOLDER_BACK_URL {
+            placeholder_back_image_bytes()?
+        } else {
+            client.get_image_bytes(url)?
+        };
         
         // Store in cache
         {
@@ -190,13 +315,6 @@ impl ProxyGenerator {
             let _ = cache_guard.insert(url.to_string(), image_bytes.clone());
         }
         
-        // Notify that image was cached
-        #[cfg(feature = "ios")]
-        {
-            crate::ffi::queue_image_cache_notification(1, url); // 1 = ImageCached
-            crate::ffi::notify_image_cache_dispatch_source();
-        }
-        
         Ok(image_bytes)
     }
     
@@ -209,42 +327,79 @@ impl ProxyGenerator {
     where
         F: FnMut(usize, usize),
     {
-        println!("🦀 [Rust] generate_pdf_from_entries_sync received {} entries:", entries.len());
+        tracing::debug!("[Rust] generate_pdf_from_entries_sync received {} entries:", entries.len());
         for (i, entry) in entries.iter().enumerate() {
             let set_str = entry.set.as_deref().unwrap_or("any");
             let lang_str = entry.lang.as_deref().unwrap_or("any");
-            println!("  [{}] '{}' ({}) [{}] x{} face={:?}", i, entry.name, set_str, lang_str, entry.multiple, entry.face_mode);
+            tracing::debug!("  [{}] '{}' ({}) [{}] x{} face={:?}", i, entry.name, set_str, lang_str, entry.multiple, entry.face_mode);
         }
 
         // Convert entries to cards using sync API
         let cards = Self::resolve_decklist_entries_to_cards_sync(entries)?;
 
-        println!("🦀 [Rust] resolved to {} cards:", cards.len());
+        tracing::debug!("[Rust] resolved to {} cards:", cards.len());
         for (i, (card, qty, face_mode)) in cards.iter().enumerate() {
-            println!("  [{}] '{}' ({}) [{}] x{} face={:?}", i, card.name, card.set, card.language, qty, face_mode);
+            tracing::debug!("  [{}] '{}' ({}) [{}] x{} face={:?}", i, card.name, card.set, card.language, qty, face_mode);
         }
 
         // Use existing PDF generation logic (mostly pure, just needs sync image fetching)
         Self::generate_pdf_from_cards_with_face_modes_sync(&cards, options, progress_callback)
     }
+
+    /// iOS sync version of render_pages_from_card_groups, for page previews and sharing
+    pub fn render_pages_from_entries_sync<F>(
+        entries: &[DecklistEntry],
+        options: crate::pdf::PdfOptions,
+        render_options: crate::pdf::RenderOptions,
+        progress_callback: F,
+    ) -> Result<Vec<Vec<u8>>, ProxyError>
+    where
+        F: FnMut(usize, usize),
+    {
+        let cards = Self::resolve_decklist_entries_to_cards_sync(entries)?;
+        let images = Self::fetch_card_images_sync(&cards, progress_callback)?;
+        crate::pdf::render_pages(images.into_iter(), options, render_options)
+    }
+    
+    /// iOS sync version of plan_pages_from_card_groups: the image URLs of every page,
+    /// in print order, without downloading any image
+    pub fn plan_pages_from_entries_sync(
+        entries: &[DecklistEntry],
+        options: &crate::pdf::PdfOptions,
+    ) -> Result<Vec<Vec<String>>, ProxyError> {
+        let cards = Self::resolve_decklist_entries_to_cards_sync(entries)?;
+        Ok(crate::ProxyGenerator::plan_pages_from_card_groups(&[cards], options))
+    }
     
     /// iOS sync version of generate_pdf_from_cards_with_face_modes
     pub fn generate_pdf_from_cards_with_face_modes_sync<F>(
         cards: &[(Card, u32, DoubleFaceMode)],
         options: crate::pdf::PdfOptions,
-        mut progress_callback: F,
+        progress_callback: F,
     ) -> Result<Vec<u8>, ProxyError>
     where
         F: FnMut(usize, usize),
     {
-        use crate::pdf::generate_pdf;
-        
+        let images = Self::fetch_card_images_sync(cards, progress_callback)?;
+
+        // Generate PDF using shared logic
+        crate::pdf::generate_pdf(images.into_iter(), options)
+    }
+
+    /// Download and decode the image of every card face to print, in print order
+    fn fetch_card_images_sync<F>(
+        cards: &[(Card, u32, DoubleFaceMode)],
+        mut progress_callback: F,
+    ) -> Result<Vec<image::DynamicImage>, ProxyError>
+    where
+        F: FnMut(usize, usize),
+    {
         // Expand cards to image URLs using shared logic from main ProxyGenerator
         let image_urls = crate::ProxyGenerator::expand_cards_to_image_urls(cards);
 
-        println!("🦀 [Rust] PDF expansion generated {} image URLs:", image_urls.len());
+        tracing::debug!("[Rust] PDF expansion generated {} image URLs:", image_urls.len());
         for (i, url) in image_urls.iter().enumerate() {
-            println!("  [{}] {}", i, url);
+            tracing::debug!("  [{}] {}", i, url);
         }
 
         let total_images = image_urls.len();
@@ -262,9 +417,8 @@ impl ProxyGenerator {
             images.push(image);
             progress_callback(i + 1, total_images);
         }
-        
-        // Generate PDF using shared logic
-        generate_pdf(images.into_iter(), options)
+
+        Ok(images)
     }
     
     /// iOS sync version of clear_cache
@@ -283,6 +437,9 @@ impl ProxyGenerator {
     
     /// iOS sync version to ensure card lookup is initialized  
     pub fn ensure_card_lookup_initialized_sync() -> Result<(), ProxyError> {
+        // Names found on disk at startup may be indexed already
+        crate::globals::wait_for_card_lookup_sync();
+
         let lookup_ref = crate::globals::get_card_lookup();
         let needs_init = {
             let lookup = lookup_ref.read().unwrap();
@@ -290,7 +447,7 @@ impl ProxyGenerator {
         };
 
         if needs_init {
-            log::info!("Initializing CardNameLookup using sync iOS cache");
+            tracing::info!("Initializing CardNameLookup using sync iOS cache");
             let client = UreqHttpClient::new()?;
             
             // Use the new sync cache implementation with pure business logic
@@ -317,7 +474,7 @@ impl ProxyGenerator {
         };
         
         if needs_init {
-            log::info!("Initializing set codes using sync iOS cache");
+            tracing::info!("Initializing set codes using sync iOS cache");
             let client = UreqHttpClient::new()?;
             
             // Use the new sync cache implementation with pure business logic
@@ -328,7 +485,7 @@ impl ProxyGenerator {
                 *cache_guard = Some(codes_set);
             }
 
-            log::info!("Set codes initialization complete");
+            tracing::info!("Set codes initialization complete");
         }
         
         Ok(())
@@ -348,12 +505,12 @@ pub fn get_or_fetch_search_results_sync(name: &str) -> Result<CardSearchResult,
     };
     
     if let Some(result) = cached_result {
-        log::debug!("Search cache HIT for name: {}", name);
+        tracing::debug!("Search cache HIT for name: {}", name);
         return Ok(result);
     }
     
     // Cache miss - fetch from API using sync client
-    log::debug!("Search cache MISS for name: {}, fetching...", name);
+    tracing::debug!("Search cache MISS for name: {}, fetching...", name);
     let search_result = client.search_card(name)?;
     
     // Store in cache
@@ -378,13 +535,17 @@ pub fn get_or_fetch_image_bytes_sync(url: &str) -> Result<Vec<u8>, ProxyError> {
     };
     
     if let Some(bytes) = cached_bytes {
-        log::debug!("Image cache HIT for URL: {}", url);
+        tracing::debug!("Image cache HIT for URL: {}", url);
         return Ok(bytes);
     }
     
     // Cache miss - fetch from API using sync client
-    log::debug!("Image cache MISS for URL: {}, fetching...", url);
-    let image_bytes = client.get_image_bytes(url)?;
+    tracing::debug!("Image cache MISS for URL: {}, fetching...", url);
+    let image_bytes = if url == PLACEHOLDER_BACK_URL {
+        placeholder_back_image_bytes()?
+    } else {
+        client.get_image_bytes(url)?
+    };
     
     // Store in cache
     {
@@ -392,57 +553,284 @@ pub fn get_or_fetch_image_bytes_sync(url: &str) -> Result<Vec<u8>, ProxyError> {
         let _ = cache_guard.insert(url.to_string(), image_bytes.clone());
     }
     
-    // Notify that image was cached
-    #[cfg(feature = "ios")]
-    {
-        crate::ffi::queue_image_cache_notification(1, url); // 1 = ImageCached
-        crate::ffi::notify_image_cache_dispatch_source();
-    }
-    
     Ok(image_bytes)
 }
 
 impl ProxyGenerator {
+    /// Load selected printings, then all alternatives, of decklist entries (iOS sync version of
+    /// start_background_image_loading), updating the shared progress as it goes
+    pub fn load_background_images_sync(
+        entries: &[DecklistEntry],
+        progress: &SyncBackgroundLoadProgress,
+    ) {
+        // Phase 1: selected printings, remembering each entry's search results for phase 2
+        let mut search_results = Vec::with_capacity(entries.len());
+        for entry in entries {
+            if progress.is_cancelled() {
+                break;
+            }
+
+            match get_or_fetch_search_results_sync(&entry.name) {
+                Ok(search_result) => {
+                    let selected_index = select_card_from_printings(&search_result.cards, entry);
+                    match selected_index {
+                        Some(index) => {
+                            let card = &search_result.cards[index];
+                            for url in card.get_images_for_face_mode(&entry.face_mode) {
+                                if let Err(e) = get_or_fetch_image_bytes_sync(&url) {
+                                    progress.record_error(format!("Failed to cache {}: {}", url, e));
+                                }
+                            }
+                            progress.selected_loaded.fetch_add(1, Ordering::SeqCst);
+                        }
+                        None => progress.record_error(format!(
+                            "No suitable printing found for '{}'",
+                            entry.name
+                        )),
+                    }
+
+                    let alternatives = search_result.cards.len() - usize::from(selected_index.is_some());
+                    progress.total_alternatives.fetch_add(alternatives, Ordering::SeqCst);
+                    search_results.push((search_result, selected_index));
+                }
+                Err(e) => progress.record_error(format!("Search failed for '{}': {}", entry.name, e)),
+            }
+            progress.current_entry.fetch_add(1, Ordering::SeqCst);
+        }
+
+        // Phase 2: front images of all other printings for the print selection
+        progress.set_phase(LoadingPhase::Alternatives);
+        'entries: for (search_result, selected_index) in &search_results {
+            for (card_idx, card) in search_result.cards.iter().enumerate() {
+                if Some(card_idx) == *selected_index {
+                    continue;
+                }
+                if progress.is_cancelled() {
+                    break 'entries;
+                }
+
+                if let Err(e) = get_or_fetch_image_bytes_sync(&card.border_crop) {
+                    progress.record_error(format!(
+                        "Failed to cache alternative {}: {}",
+                        card.border_crop, e
+                    ));
+                }
+                progress.alternatives_loaded.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+
+        tracing::info!(
+            "Background loading {}: {} selected, {}/{} alternatives, {} errors",
+            if progress.is_cancelled() { "cancelled" } else { "completed" },
+            progress.selected_loaded.load(Ordering::SeqCst),
+            progress.alternatives_loaded.load(Ordering::SeqCst),
+            progress.total_alternatives.load(Ordering::SeqCst),
+            progress.error_count.load(Ordering::SeqCst)
+        );
+        progress.set_phase(LoadingPhase::Completed);
+    }
+
     /// Load all printings for all entries (iOS sync version of Phase 2)
     /// This should be called after parsing to populate the print selection modal with cached images
     pub fn load_alternative_printings_sync(entries: &[DecklistEntry]) -> Result<usize, ProxyError> {
+        let abort = BackgroundLoadAbort::current();
         let mut images_loaded = 0;
+        let mut images_found = 0;
         
-        println!("🔄 [iOS API] Starting all printings loading for {} entries", entries.len());
+        tracing::debug!("[iOS API] Starting all printings loading for {} entries", entries.len());
         
         for (entry_idx, entry) in entries.iter().enumerate() {
-            println!("🔍 [iOS API] Loading all printings for entry {}/{}: '{}'", 
+            if abort.is_aborted() {
+                break;
+            }
+            tracing::debug!("[iOS API] Loading all printings for entry {}/{}: '{}'", 
                 entry_idx + 1, entries.len(), entry.name);
             
             // Search for all available printings
             match Self::search_card_sync(&entry.name) {
                 Ok(search_result) => {
-                    println!("  Found {} total printings", search_result.cards.len());
+                    tracing::debug!("  Found {} total printings", search_result.cards.len());
+                    images_found += search_result.cards.len();
                     
                     // Load all printings (cache will handle duplicates efficiently)
                     for card in &search_result.cards {
+                        if abort.is_aborted() {
+                            break;
+                        }
                         // Load front image for each printing
                         match get_or_fetch_image_bytes_sync(&card.border_crop) {
                             Ok(_) => {
                                 images_loaded += 1;
-                                println!("  ✅ Cached printing: '{}' ({}) [{}]", 
+                                tracing::debug!("  Cached printing: '{}' ({}) [{}]", 
                                     card.name, card.set.to_uppercase(), card.language);
                             }
                             Err(e) => {
-                                println!("  ❌ Failed to cache printing '{}' ({}): {}", 
+                                tracing::warn!("  Failed to cache printing '{}' ({}): {}", 
                                     card.name, card.set.to_uppercase(), e);
                             }
                         }
                     }
                 }
                 Err(e) => {
-                    println!("  ❌ Failed to search for printings of '{}': {}", entry.name, e);
+                    tracing::warn!("  Failed to search for printings of '{}': {}", entry.name, e);
                 }
             }
         }
         
-        println!("✅ [iOS API] All printings loading complete: {} images processed", images_loaded);
+        tracing::debug!("[iOS API] All printings loading complete: {} images processed", images_loaded);
+        report_background_load_finished("alternatives", &abort, images_loaded, images_found);
         Ok(images_loaded)
     }
 }
 
+
+/// Why a decklist shared to the share extension has to be opened in the full app
+#[cfg(feature = "ios")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NeedsFullApp {
+    /// Some cards were never looked up, so their printings aren't cached
+    CardsNotCached = 1,
+    /// Some images to print aren't cached
+    ImagesNotCached = 2,
+    /// Printing from the caches took longer than the time budget
+    OutOfTime = 3,
+}
+
+/// Outcome of ProxyGenerator::generate_pdf_from_cache_sync
+#[cfg(feature = "ios")]
+#[derive(Debug)]
+pub enum CachedPdfOutcome {
+    /// The PDF was written to this path
+    Written(std::path::PathBuf),
+    /// The full app has to fetch data first; `missing` names the cards it needs
+    NeedsFullApp {
+        reason: NeedsFullApp,
+        missing: Vec<String>,
+    },
+}
+
+#[cfg(feature = "ios")]
+impl ProxyGenerator {
+    /// Print a decklist from the caches alone, for the share extension
+    ///
+    /// Nothing is downloaded and no background loading is started. Names are resolved with the
+    /// card name lookup only if it is already built (else they are taken as typed), printings
+    /// come from the search results cache, and images are read from their cache files one by
+    /// one instead of loading the image cache, so memory stays within an extension's limit.
+    /// Anything missing, or running past `time_budget`, hands the decklist to the full app.
+    pub fn generate_pdf_from_cache_sync(
+        decklist_text: &str,
+        output_directory: &std::path::Path,
+        time_budget: std::time::Duration,
+    ) -> Result<CachedPdfOutcome, ProxyError> {
+        use crate::cache::{FileStorage, SearchJsonStorage, StorageStrategy};
+        use crate::decklist::{parse_deck_name, parse_decklist};
+        use crate::globals::{find_card_name, get_cache_directory_path, get_search_cache_path, is_card_lookup_ready};
+        use crate::scryfall::models::get_minimal_scryfall_languages;
+        use std::collections::HashMap;
+        use std::path::PathBuf;
+        use std::time::Instant;
+
+        let deadline = Instant::now() + time_budget;
+        let out_of_time = || CachedPdfOutcome::NeedsFullApp {
+            reason: NeedsFullApp::OutOfTime,
+            missing: Vec::new(),
+        };
+
+        // Set codes from disk are cheap, unlike the card name lookup
+        let set_codes = match get_set_codes_cache().read().unwrap().clone() {
+            Some(set_codes) => set_codes,
+            None => crate::ios_cache::load_stale_set_codes_sync()?
+                .map(|(set_codes, _)| set_codes)
+                .unwrap_or_default(),
+        };
+        let entries: Vec<DecklistEntry> = parse_decklist(decklist_text, &get_minimal_scryfall_languages(), &set_codes)
+            .into_iter()
+            .filter_map(|line| line.as_entry())
+            .collect();
+        if entries.is_empty() {
+            return Err(ProxyError::InvalidCard("No cards in the decklist".to_string()));
+        }
+
+        // Resolve every entry from the cached search results (the desktop keys them lowercase)
+        let search_results = SearchJsonStorage::new(PathBuf::from(get_search_cache_path()), 0)?.load()?;
+        let mut cards = Vec::new();
+        let mut missing = Vec::new();
+        for mut entry in entries {
+            entry.face_mode = DoubleFaceMode::BothSides;
+            if let Some(lookup_result) = is_card_lookup_ready().then(|| find_card_name(&entry.name)).flatten() {
+                if lookup_result.hit == NameMatchMode::Part(1) {
+                    entry.face_mode = DoubleFaceMode::BackOnly;
+                }
+                entry.name = lookup_result.name;
+            }
+
+            let printings = [entry.name.to_lowercase(), entry.name.clone()]
+                .iter()
+                .find_map(|key| search_results.get(key))
+                .map(|cached| &cached.value.cards);
+            let card = printings.and_then(|printings| {
+                select_card_from_printings(printings, &entry)
+                    .and_then(|idx| printings.get(idx))
+                    .or_else(|| printings.first())
+                    .cloned()
+            });
+            match card {
+                Some(card) => cards.push((card, entry.multiple.get(), entry.face_mode.clone())),
+                None => missing.push(entry.name.clone()),
+            }
+        }
+        if !missing.is_empty() {
+            return Ok(CachedPdfOutcome::NeedsFullApp {
+                reason: NeedsFullApp::CardsNotCached,
+                missing,
+            });
+        }
+
+        // Read and decode each image once, however many copies are printed
+        let storage = FileStorage::new(PathBuf::from(get_cache_directory_path()), "jpg".to_string(), 0)?;
+        let mut images: HashMap<String, image::DynamicImage> = HashMap::new();
+        for (card, _, face_mode) in &cards {
+            for url in card.get_images_for_face_mode(face_mode) {
+                if Instant::now() > deadline {
+                    return Ok(out_of_time());
+                }
+                if images.contains_key(&url) {
+                    continue;
+                }
+                let bytes = if url == PLACEHOLDER_BACK_URL {
+                    Some(placeholder_back_image_bytes()?)
+                } else {
+                    storage.read_file(&url)
+                };
+                let Some(bytes) = bytes else {
+                    missing.push(card.name.clone());
+                    break;
+                };
+                let image = image::load_from_memory(&bytes)
+                    .map_err(|e| ProxyError::InvalidCard(format!("Failed to decode image: {}", e)))?;
+                images.insert(url, image);
+            }
+        }
+        if !missing.is_empty() {
+            return Ok(CachedPdfOutcome::NeedsFullApp {
+                reason: NeedsFullApp::ImagesNotCached,
+                missing,
+            });
+        }
+
+        let pages = crate::ProxyGenerator::expand_cards_to_image_urls(&cards)
+            .into_iter()
+            .map(|url| images[&url].clone());
+        let pdf = crate::pdf::generate_pdf(pages, crate::pdf::PdfOptions::default())?;
+        if Instant::now() > deadline {
+            return Ok(out_of_time());
+        }
+
+        let deck_name = parse_deck_name(decklist_text);
+        let path = crate::OutputOptions::default().output_path(output_directory, deck_name.as_deref(), 1, 1);
+        std::fs::write(&path, pdf)?;
+        tracing::info!("Share extension wrote {} from the caches", path.display());
+        Ok(CachedPdfOutcome::Written(path))
+    }
+}
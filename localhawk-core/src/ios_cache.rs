@@ -7,17 +7,15 @@
 use crate::{
     cache_logic::{
         process_card_names_into_lookup, process_set_codes_into_hashset,
-        is_cache_expired, log_cache_hit, log_cache_miss,
-        CARD_NAMES_CACHE_HOURS, SET_CODES_CACHE_HOURS
+        log_cache_hit, log_cache_miss,
     },
     error::ProxyError,
+    globals::get_freshness_policy,
     http_client::{HttpClient, UreqHttpClient},
     lookup::CardNameLookup,
     scryfall::models::{ScryfallCardNames, ScryfallSetCodes},
 };
 #[cfg(feature = "ios")]
-use directories::ProjectDirs;
-#[cfg(feature = "ios")]
 use serde::{Deserialize, Serialize};
 #[cfg(feature = "ios")]
 use std::{collections::HashSet, fs, path::PathBuf};
@@ -49,9 +47,7 @@ pub struct CardNameCacheSync {
 #[cfg(feature = "ios")]
 impl CardNameCacheSync {
     pub fn new() -> Result<Self, ProxyError> {
-        let cache_dir = ProjectDirs::from("", "", "localhawk")
-            .map(|proj_dirs| proj_dirs.cache_dir().to_path_buf())
-            .unwrap_or_else(|| std::env::temp_dir().join("localhawk-cache"));
+        let cache_dir = PathBuf::from(crate::get_cache_directory_path());
 
         // Create cache directory if it doesn't exist
         if !cache_dir.exists() {
@@ -75,7 +71,7 @@ impl CardNameCacheSync {
             debug!(cache_file = %self.cache_file_path.display(), "Checking disk cache");
 
             if let Ok(cached) = self.load_from_cache() {
-                if !is_cache_expired(cached.cached_at, CARD_NAMES_CACHE_HOURS) {
+                if !get_freshness_policy().is_stale(cached.cached_at) {
                     log_cache_hit(cached.cached_at, cached.data.names.len(), "card_names");
                     return Ok(cached.data);
                 } else {
@@ -103,6 +99,13 @@ impl CardNameCacheSync {
         Ok(card_names)
     }
 
+    /// Load card names from disk regardless of their age, together with when they were cached
+    pub fn load_cached_card_names(&self) -> Option<(ScryfallCardNames, OffsetDateTime)> {
+        self.load_from_cache()
+            .ok()
+            .map(|cached| (cached.data, cached.cached_at))
+    }
+
     fn load_from_cache(&self) -> Result<CachedCardNames, ProxyError> {
         if !self.cache_file_path.exists() {
             debug!(
@@ -161,9 +164,7 @@ pub struct SetCodesCacheSync {
 #[cfg(feature = "ios")]
 impl SetCodesCacheSync {
     pub fn new() -> Result<Self, ProxyError> {
-        let cache_dir = ProjectDirs::from("", "", "localhawk")
-            .map(|proj_dirs| proj_dirs.cache_dir().to_path_buf())
-            .unwrap_or_else(|| std::env::temp_dir().join("localhawk-cache"));
+        let cache_dir = PathBuf::from(crate::get_cache_directory_path());
 
         // Create cache directory if it doesn't exist
         if !cache_dir.exists() {
@@ -187,7 +188,7 @@ impl SetCodesCacheSync {
             debug!(cache_file = %self.cache_file_path.display(), "Checking disk cache");
 
             if let Ok(cached) = self.load_from_cache() {
-                if !is_cache_expired(cached.cached_at, SET_CODES_CACHE_HOURS) {
+                if !get_freshness_policy().is_stale(cached.cached_at) {
                     log_cache_hit(cached.cached_at, cached.data.codes.len(), "set_codes");
                     return Ok(cached.data);
                 } else {
@@ -215,6 +216,13 @@ impl SetCodesCacheSync {
         Ok(set_codes)
     }
 
+    /// Load set codes from disk regardless of their age, together with when they were cached
+    pub fn load_cached_set_codes(&self) -> Option<(ScryfallSetCodes, OffsetDateTime)> {
+        self.load_from_cache()
+            .ok()
+            .map(|cached| (cached.data, cached.cached_at))
+    }
+
     fn load_from_cache(&self) -> Result<CachedSetCodes, ProxyError> {
         if !self.cache_file_path.exists() {
             debug!(
@@ -287,4 +295,47 @@ pub fn initialize_set_codes_sync(client: &UreqHttpClient) -> Result<HashSet<Stri
     let codes_set = process_set_codes_into_hashset(&set_codes);
     
     Ok(codes_set)
-}
\ No newline at end of file
+}
+
+/// Load card name lookup from disk regardless of age (no network access)
+/// Returns the lookup, its cache info, and when the data was cached
+#[cfg(feature = "ios")]
+pub fn load_stale_card_names_sync()
+-> Result<Option<(ScryfallCardNames, OffsetDateTime)>, ProxyError> {
+    let cache = CardNameCacheSync::new()?;
+    Ok(cache.load_cached_card_names())
+}
+
+/// Load set codes from disk regardless of age (no network access)
+/// Returns the set codes and when they were cached
+#[cfg(feature = "ios")]
+pub fn load_stale_set_codes_sync() -> Result<Option<(HashSet<String>, OffsetDateTime)>, ProxyError>
+{
+    let cache = SetCodesCacheSync::new()?;
+    Ok(cache
+        .load_cached_set_codes()
+        .map(|(set_codes, cached_at)| (process_set_codes_into_hashset(&set_codes), cached_at)))
+}
+
+/// Fetch fresh card names from the API and build a new lookup
+#[cfg(feature = "ios")]
+pub fn refresh_card_lookup_sync(
+    client: &UreqHttpClient,
+) -> Result<(CardNameLookup, Option<(OffsetDateTime, usize)>), ProxyError> {
+    let cache = CardNameCacheSync::new()?;
+    let card_names = cache.get_card_names_sync(client, true)?;
+
+    let lookup = process_card_names_into_lookup(&card_names);
+    let cache_info = card_names.date.map(|date| (date, card_names.names.len()));
+
+    Ok((lookup, cache_info))
+}
+
+/// Fetch fresh set codes from the API
+#[cfg(feature = "ios")]
+pub fn refresh_set_codes_sync(client: &UreqHttpClient) -> Result<HashSet<String>, ProxyError> {
+    let cache = SetCodesCacheSync::new()?;
+    let set_codes = cache.get_set_codes_sync(client, true)?;
+
+    Ok(process_set_codes_into_hashset(&set_codes))
+}
@@ -1,14 +1,28 @@
-use crate::decklist::DecklistEntry;
+use crate::DoubleFaceMode;
+use crate::decklist::{DeckSection, DecklistEntry};
 use crate::pagination::PaginatedGrid;
+use crate::pdf::PdfOptions;
 use crate::scryfall::models::Card;
+use crate::sorting::CopyOrder;
+use std::num::NonZeroU32;
 
 /// Represents a position in a grid layout
 #[derive(Debug, Clone)]
 pub struct GridPosition {
     pub page: usize,             // Which page this position is on
-    pub position_in_page: usize, // Position within the page grid (0-8 for 3x3)
+    pub position_in_page: usize, // Position within the page grid (0-8 for 3x3, 0-15 for 4x4)
     pub entry_index: usize,      // Which decklist entry this belongs to
     pub copy_number: usize,      // Which copy of that entry (0-based)
+    pub printing_index: usize,   // Which printing this copy uses (index into available_printings)
+    pub image_index: usize,      // Which image of that printing for the face mode (0 = first)
+}
+
+/// A divider row labeling the group of cards after it (see [`PdfOptions::group_dividers`])
+#[derive(Debug, Clone, PartialEq)]
+pub struct GridDivider {
+    pub page: usize,
+    pub position_in_page: usize, // First slot of the row it takes
+    pub label: String,
 }
 
 /// Contains all information about a decklist entry for grid preview
@@ -18,6 +32,17 @@ pub struct PreviewEntry {
     pub available_printings: Vec<Card>,
     pub selected_printing: Option<usize>, // Index into available_printings
     pub grid_positions: Vec<GridPosition>,
+    /// Copies per printing as (index into available_printings, copies) when the copies are
+    /// split across several printings; empty means all copies use the selected printing
+    pub printing_split: Vec<(usize, u32)>,
+    /// Fills empty slots on the last page instead of coming from the decklist; its copies are
+    /// set by [`GridPreview::layout`]
+    pub is_filler: bool,
+    /// Copies of a filler entry, which may be none when the last page is full
+    pub filler_copies: u32,
+    /// Scryfall has more printings than `available_printings` (see
+    /// [`CardSearchResult::has_more`](crate::CardSearchResult::has_more))
+    pub has_more_printings: bool,
 }
 
 impl PreviewEntry {
@@ -27,6 +52,21 @@ impl PreviewEntry {
             available_printings,
             selected_printing: None,
             grid_positions: Vec::new(),
+            printing_split: Vec::new(),
+            is_filler: false,
+            filler_copies: 0,
+            has_more_printings: false,
+        }
+    }
+
+    /// Entry for a filler card (see [`PdfOptions::filler_cards`]), printed front face only
+    pub fn filler(name: &str, available_printings: Vec<Card>) -> Self {
+        let mut decklist_entry = DecklistEntry::new(NonZeroU32::MIN, name, None, None);
+        decklist_entry.face_mode = DoubleFaceMode::FrontOnly;
+        Self {
+            selected_printing: (!available_printings.is_empty()).then_some(0),
+            is_filler: true,
+            ..Self::new(decklist_entry, available_printings)
         }
     }
 
@@ -38,15 +78,97 @@ impl PreviewEntry {
         }
     }
 
+    /// Use a single printing for all copies (clears any split)
     pub fn select_printing(&mut self, index: usize) -> bool {
         if index < self.available_printings.len() {
             self.selected_printing = Some(index);
+            self.printing_split.clear();
             true
         } else {
             false
         }
     }
 
+    /// Copies of this entry across all its printings
+    pub fn copies(&self) -> u32 {
+        if self.is_filler {
+            self.filler_copies
+        } else {
+            self.decklist_entry.multiple.get()
+        }
+    }
+
+    /// Copies per printing as (index into available_printings, copies), in layout order
+    pub fn printing_quantities(&self) -> Vec<(usize, u32)> {
+        if !self.printing_split.is_empty() {
+            return self.printing_split.clone();
+        }
+        if self.available_printings.is_empty() {
+            return Vec::new();
+        }
+        vec![(self.selected_printing.unwrap_or(0), self.copies())]
+    }
+
+    /// Number of copies that use the given printing
+    pub fn quantity_for_printing(&self, index: usize) -> u32 {
+        self.printing_quantities()
+            .iter()
+            .find(|(printing, _)| *printing == index)
+            .map_or(0, |(_, copies)| *copies)
+    }
+
+    /// Set how many copies use a printing; the entry's total becomes the sum over all printings
+    ///
+    /// The printing with the most copies becomes the selected one. Removing the
+    /// last remaining copy is rejected, so an entry always keeps at least one.
+    pub fn set_printing_quantity(&mut self, index: usize, copies: u32) -> bool {
+        if index >= self.available_printings.len() {
+            return false;
+        }
+
+        let mut split = self.printing_quantities();
+        match split.iter_mut().find(|(printing, _)| *printing == index) {
+            Some(entry) => entry.1 = copies,
+            None => split.push((index, copies)),
+        }
+        split.retain(|(_, copies)| *copies > 0);
+        let Some(total) = NonZeroU32::new(split.iter().map(|(_, copies)| copies).sum()) else {
+            return false;
+        };
+
+        // max_by_key returns the last maximum; reverse so ties go to the earlier printing
+        self.selected_printing = split
+            .iter()
+            .rev()
+            .max_by_key(|(_, copies)| *copies)
+            .map(|(printing, _)| *printing);
+        self.decklist_entry.multiple = total;
+        self.printing_split = if split.len() > 1 { split } else { Vec::new() };
+        true
+    }
+
+    /// Print this entry with its own face mode instead of the global one, e.g. both sides of
+    /// just a few double-faced cards; returns whether it changed
+    ///
+    /// Call [`GridPreview::relayout_entry`] afterwards.
+    pub fn set_face_mode(&mut self, face_mode: DoubleFaceMode) -> bool {
+        if self.decklist_entry.face_mode == face_mode {
+            return false;
+        }
+        self.decklist_entry.face_mode = face_mode;
+        true
+    }
+
+    /// Whether the printing shown for this entry has a back side, so its face mode matters
+    pub fn has_back_side(&self) -> bool {
+        self.get_selected_card().is_some_and(Card::has_back_side)
+    }
+
+    /// Whether the copies of this entry use more than one printing
+    pub fn is_split(&self) -> bool {
+        !self.printing_split.is_empty()
+    }
+
     /// Alias for select_printing for backwards compatibility
     pub fn set_selected_printing(&mut self, index: usize) {
         self.select_printing(index);
@@ -59,8 +181,14 @@ pub struct GridPreview {
     pub entries: Vec<PreviewEntry>,
     pub current_page: usize,
     pub total_pages: usize,
+    pub cards_per_page: usize, // Slots per page of the most recent layout
     pub selected_entry_index: Option<usize>, // For print selection modal
     pub print_selection_grid: Option<PaginatedGrid>, // Pagination for print selection modal
+    /// Print order of the entries as rearranged by [`GridPreview::move_entry`]; empty for
+    /// decklist order. Entries missing from it are printed after the ones in it.
+    pub entry_order: Vec<usize>,
+    /// Divider rows of the most recent layout
+    pub dividers: Vec<GridDivider>,
 }
 
 impl GridPreview {
@@ -69,11 +197,342 @@ impl GridPreview {
             entries,
             current_page: 0,
             total_pages,
+            cards_per_page: PdfOptions::default().cards_per_page(),
             selected_entry_index: None,
             print_selection_grid: None,
+            entry_order: Vec::new(),
+            dividers: Vec::new(),
         }
     }
 
+    /// Lay out all entries on pages, honoring per-copy printing splits and commander placement
+    ///
+    /// Uses the same order as [`GridPreview::to_card_groups`], so positions match the generated PDF.
+    /// Filler entries get as many copies as there are empty slots on the last page.
+    pub fn layout(&mut self, options: &PdfOptions) {
+        let cards_per_page = options.cards_per_page().max(1);
+        let mut position = 0;
+        self.dividers.clear();
+
+        for group in self.print_groups(options) {
+            // Every group starts on a fresh page
+            position = position.next_multiple_of(cards_per_page);
+            self.place_group(&group, options.copy_order, options, &mut position);
+        }
+        self.place_fillers(options, &mut position);
+
+        self.cards_per_page = cards_per_page;
+        self.total_pages = position.div_ceil(cards_per_page);
+        self.current_page = self.current_page.min(self.total_pages.saturating_sub(1));
+    }
+
+    /// Lay out the pages again after the printings of one entry changed, e.g. in the print
+    /// selection
+    ///
+    /// Print groups before the entry's keep their positions, so only the pages from its group
+    /// on are recomputed. The current page follows the card that was shown first on it, so the
+    /// user keeps their place when the entry now has more or fewer images (e.g. a double-faced
+    /// printing). Nothing but this entry may have changed since the last layout; a changed
+    /// grid size falls back to [`GridPreview::layout`].
+    pub fn relayout_entry(&mut self, entry_index: usize, options: &PdfOptions) {
+        let cards_per_page = options.cards_per_page().max(1);
+        if cards_per_page != self.cards_per_page {
+            self.layout(options);
+            return;
+        }
+        let anchor = self.first_card_on_page(self.current_page);
+
+        // Fillers come after all groups, so a changed filler only moves the fillers
+        let groups = self.print_groups(options);
+        let affected = groups
+            .iter()
+            .position(|group| group.contains(&entry_index))
+            .unwrap_or(groups.len());
+        let mut position = groups[..affected]
+            .iter()
+            .flatten()
+            .flat_map(|&index| &self.entries[index].grid_positions)
+            .map(|p| p.page * cards_per_page + p.position_in_page + 1)
+            .max()
+            .unwrap_or(0);
+        self.dividers
+            .retain(|divider| divider.page * cards_per_page + divider.position_in_page < position);
+
+        for group in &groups[affected..] {
+            position = position.next_multiple_of(cards_per_page);
+            self.place_group(group, options.copy_order, options, &mut position);
+        }
+        self.place_fillers(options, &mut position);
+
+        self.total_pages = position.div_ceil(cards_per_page);
+        let anchor_page = anchor.and_then(|(index, copy_number)| {
+            let positions = &self.entries[index].grid_positions;
+            positions
+                .iter()
+                .find(|p| p.copy_number == copy_number)
+                .or(positions.last())
+                .map(|p| p.page)
+        });
+        self.current_page = anchor_page
+            .unwrap_or(self.current_page)
+            .min(self.total_pages.saturating_sub(1));
+    }
+
+    /// Entry and copy of the first card on `page`
+    fn first_card_on_page(&self, page: usize) -> Option<(usize, usize)> {
+        self.entries
+            .iter()
+            .flat_map(|entry| &entry.grid_positions)
+            .filter(|p| p.page == page)
+            .min_by_key(|p| p.position_in_page)
+            .map(|p| (p.entry_index, p.copy_number))
+    }
+
+    /// Give the filler entries the empty slots on the last page, placing them from `position`
+    fn place_fillers(&mut self, options: &PdfOptions, position: &mut usize) {
+        let fillers = self.filler_indices();
+        let filler_copies = options.filler_copies(*position, fillers.len());
+        for (entry_index, copies) in fillers.into_iter().zip(filler_copies) {
+            let entry = &mut self.entries[entry_index];
+            entry.filler_copies = copies;
+            entry.printing_split.clear();
+            self.place_group(&[entry_index], CopyOrder::Adjacent, options, position);
+        }
+    }
+
+    /// Place the images of every copy of the entries of a group in `copy_order`, starting at
+    /// `position`, with divider rows before its labeled groups
+    ///
+    /// Like [`GridPreview::to_card_groups`], every printing of a split entry is a card of its own.
+    fn place_group(
+        &mut self,
+        group: &[usize],
+        copy_order: CopyOrder,
+        options: &PdfOptions,
+        position: &mut usize,
+    ) {
+        let cards_per_page = options.cards_per_page().max(1);
+
+        // (entry, printing, images per copy) of every card, and its copies
+        let mut cards = Vec::new();
+        let mut copies = Vec::new();
+        for &entry_index in group {
+            let entry = &mut self.entries[entry_index];
+            entry.grid_positions.clear();
+            let section = entry.decklist_entry.section;
+            for (printing_index, printing_copies) in entry.printing_quantities() {
+                let Some(card) = entry.available_printings.get(printing_index) else {
+                    continue;
+                };
+                let images_per_copy = card
+                    .get_images_for_face_mode(&entry.decklist_entry.face_mode)
+                    .len();
+                cards.push((entry_index, printing_index, images_per_copy));
+                copies.push(options.print_copies(section, printing_copies));
+            }
+        }
+
+        // Dividers keep the copies of each card together, so a card's images follow those of
+        // the cards before it
+        let card_images: Vec<usize> = cards
+            .iter()
+            .zip(&copies)
+            .map(|(&(_, _, images_per_copy), &copies)| images_per_copy * copies as usize)
+            .collect();
+        let mut dividers: Vec<(usize, String)> = Vec::new();
+        if options.shows_group_dividers() {
+            let labels = cards
+                .iter()
+                .map(|(entry_index, _, _)| self.entries[*entry_index].decklist_entry.group_label());
+            for (card, label) in crate::pdf::divider_labels(labels) {
+                dividers.push((card_images[..card].iter().sum(), label));
+            }
+        }
+        let total_images: usize = card_images.iter().sum();
+        let divider_images: Vec<usize> = dividers.iter().map(|(image, _)| *image).collect();
+        let (image_slots, divider_slots) = options.group_slots(total_images, &divider_images);
+
+        let start = *position;
+        for (slot, (_, label)) in divider_slots.into_iter().zip(dividers) {
+            self.dividers.push(GridDivider {
+                page: (start + slot) / cards_per_page,
+                position_in_page: (start + slot) % cards_per_page,
+                label,
+            });
+        }
+
+        let mut image_slots = image_slots.into_iter();
+        let mut copy_numbers = vec![0; self.entries.len()];
+        for (card, _) in copy_order.sequence(&copies) {
+            let (entry_index, printing_index, images_per_copy) = cards[card];
+            let copy_number = copy_numbers[entry_index];
+            for image_index in 0..images_per_copy {
+                let slot = start + image_slots.next().unwrap_or_default();
+                self.entries[entry_index].grid_positions.push(GridPosition {
+                    page: slot / cards_per_page,
+                    position_in_page: slot % cards_per_page,
+                    entry_index,
+                    copy_number,
+                    printing_index,
+                    image_index,
+                });
+                *position = slot + 1;
+            }
+            copy_numbers[entry_index] += 1;
+        }
+    }
+
+    /// Groups of cards with copy counts and face modes in print order, ready for PDF generation
+    ///
+    /// Filler cards join the last group with the copies of the most recent layout.
+    pub fn to_card_groups(&self, options: &PdfOptions) -> Vec<Vec<(Card, u32, DoubleFaceMode)>> {
+        let mut groups: Vec<Vec<_>> = self
+            .print_groups(options)
+            .into_iter()
+            .map(|group| {
+                let cards = group
+                    .into_iter()
+                    .flat_map(|entry_index| self.entry_cards(entry_index, options))
+                    .collect();
+                options.copy_order.arrange(cards)
+            })
+            .collect();
+        if let Some(last_group) = groups.last_mut() {
+            for entry_index in self.filler_indices() {
+                let cards = self.entry_cards(entry_index, options);
+                last_group.extend(cards.filter(|(_, copies, _)| *copies > 0));
+            }
+        }
+        groups
+    }
+
+    /// Labels of the divider rows of the groups of [`GridPreview::to_card_groups`], for
+    /// [`PdfOptions::group_labels`] (empty without group dividers)
+    pub fn group_labels(&self, options: &PdfOptions) -> Vec<Vec<(usize, String)>> {
+        if !options.shows_group_dividers() {
+            return Vec::new();
+        }
+        self.print_groups(options)
+            .into_iter()
+            .map(|group| {
+                let labels = group.into_iter().flat_map(|entry_index| {
+                    let label = self.entries[entry_index].decklist_entry.group_label();
+                    let cards = self.entry_cards(entry_index, options).count();
+                    std::iter::repeat_n(label, cards)
+                });
+                crate::pdf::divider_labels(labels)
+            })
+            .collect()
+    }
+
+    /// Divider row starting at `position_in_page` on the current page, if any
+    pub fn divider_at(&self, position_in_page: usize) -> Option<&GridDivider> {
+        self.dividers.iter().find(|divider| {
+            divider.page == self.current_page && divider.position_in_page == position_in_page
+        })
+    }
+
+    fn entry_cards<'a>(
+        &'a self,
+        entry_index: usize,
+        options: &'a PdfOptions,
+    ) -> impl Iterator<Item = (Card, u32, DoubleFaceMode)> + 'a {
+        let entry = &self.entries[entry_index];
+        let section = entry.decklist_entry.section;
+        entry
+            .printing_quantities()
+            .into_iter()
+            .filter_map(move |(printing_index, copies)| {
+                entry.available_printings.get(printing_index).map(|card| {
+                    (
+                        card.clone(),
+                        options.print_copies(section, copies),
+                        entry.decklist_entry.face_mode.clone(),
+                    )
+                })
+            })
+    }
+
+    /// Move an entry to where `target` is printed, e.g. after dragging it there in the preview
+    ///
+    /// The entry takes the place of `target`, which moves back one place when the entry came
+    /// from before it and forward otherwise. The current print order, including any sort order,
+    /// becomes the new [`GridPreview::entry_order`]; sort orders other than
+    /// [`SortOrder::AsEntered`](crate::SortOrder::AsEntered) still apply on top of it, so switch
+    /// to that to print in exactly this order. Call [`GridPreview::layout`] afterwards.
+    /// Filler entries can't be moved.
+    pub fn move_entry(&mut self, entry_index: usize, target: usize, options: &PdfOptions) -> bool {
+        let mut order: Vec<usize> = self.print_groups(options).concat();
+        let (Some(from), Some(to)) = (
+            order.iter().position(|&index| index == entry_index),
+            order.iter().position(|&index| index == target),
+        ) else {
+            return false;
+        };
+        if from == to {
+            return false;
+        }
+
+        order.remove(from);
+        order.insert(to, entry_index);
+        self.entry_order = order;
+        true
+    }
+
+    /// Print groups of the decklist entries in the configured order, leaving out filler entries
+    fn print_groups(&self, options: &PdfOptions) -> Vec<Vec<usize>> {
+        let sections: Vec<DeckSection> = self
+            .entries
+            .iter()
+            .map(|entry| entry.decklist_entry.section)
+            .collect();
+        options
+            .print_groups(&sections)
+            .into_iter()
+            .map(|group| {
+                let mut group: Vec<usize> = group
+                    .into_iter()
+                    .filter(|&entry_index| !self.entries[entry_index].is_filler)
+                    .collect();
+                if !self.entry_order.is_empty() {
+                    group.sort_by_key(|&entry_index| {
+                        self.entry_order
+                            .iter()
+                            .position(|&index| index == entry_index)
+                            .unwrap_or(usize::MAX)
+                    });
+                }
+                // Labeled groups (comments like "// lands", sections) are kept together, sorted
+                // among themselves
+                options.sort_order.sort_by_card_within_groups(
+                    &mut group,
+                    |&entry_index| self.entries[entry_index].decklist_entry.group_label(),
+                    |&entry_index| self.entries[entry_index].get_selected_card(),
+                );
+                group
+            })
+            .filter(|group| !group.is_empty())
+            .collect()
+    }
+
+    /// Filler entries that can be printed (cards that weren't found are left out)
+    fn filler_indices(&self) -> Vec<usize> {
+        (0..self.entries.len())
+            .filter(|&entry_index| {
+                let entry = &self.entries[entry_index];
+                entry.is_filler && !entry.available_printings.is_empty()
+            })
+            .collect()
+    }
+
+    /// Replace the filler entries, e.g. after the filler cards were changed in the options
+    ///
+    /// Call [`GridPreview::layout`] afterwards to give them their copies.
+    pub fn set_filler_entries(&mut self, fillers: Vec<PreviewEntry>) {
+        self.entries.retain(|entry| !entry.is_filler);
+        self.entries.extend(fillers);
+    }
+
     /// Get all grid positions for the current page
     pub fn get_current_page_positions(&self) -> Vec<(usize, &GridPosition, &PreviewEntry)> {
         let mut positions = Vec::new();
@@ -88,6 +547,17 @@ impl GridPreview {
         positions
     }
 
+    /// Every slot of the current page in row-by-row order, None where the slot is empty
+    pub fn get_current_page_slots(&self) -> Vec<Option<(usize, &GridPosition, &PreviewEntry)>> {
+        let mut slots = vec![None; self.cards_per_page];
+        for slot in self.get_current_page_positions() {
+            if let Some(empty) = slots.get_mut(slot.1.position_in_page) {
+                *empty = Some(slot);
+            }
+        }
+        slots
+    }
+
     /// Navigate to next page if possible
     pub fn next_page(&mut self) -> bool {
         if self.current_page + 1 < self.total_pages {
@@ -208,5 +678,470 @@ pub struct GridImage {
     pub entry_index: usize,      // Which decklist entry this came from
     pub copy_number: usize,      // Which copy of that entry (0-based)
     pub page: usize,             // Which page this appears on
-    pub position_in_page: usize, // Position within the page grid (0-8)
+    pub position_in_page: usize, // Position within the page grid (row by row)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scryfall::models::BackSide;
+
+    fn printing(set: &str) -> Card {
+        Card {
+            name: "plains".to_string(),
+            set: set.to_string(),
+            language: "en".to_string(),
+            border_crop: format!("https://example.com/{}.jpg", set),
+            back_side: None,
+            frame: None,
+            digital: false,
+            collector_number: None,
+            prices: None,
+            legalities: Default::default(),
+            layout: None,
+            scryfall_id: None,
+            oracle_id: None,
+            mana_cost: None,
+            type_line: None,
+            oracle_text: None,
+            power_toughness: None,
+        }
+    }
+
+    fn preview_entry(multiple: u32, sets: &[&str]) -> PreviewEntry {
+        let mut entry = PreviewEntry::new(
+            DecklistEntry::new(NonZeroU32::new(multiple).unwrap(), "plains", None, None),
+            sets.iter().map(|set| printing(set)).collect(),
+        );
+        entry.selected_printing = Some(0);
+        entry
+    }
+
+    #[test]
+    fn test_printing_split_keeps_primary_and_total() {
+        let mut entry = preview_entry(4, &["m21", "lea", "znr"]);
+        assert_eq!(entry.printing_quantities(), vec![(0, 4)]);
+
+        assert!(entry.set_printing_quantity(0, 2));
+        assert!(entry.set_printing_quantity(1, 2));
+        assert!(entry.is_split());
+        assert_eq!(entry.printing_quantities(), vec![(0, 2), (1, 2)]);
+        assert_eq!(entry.decklist_entry.multiple.get(), 4);
+        assert_eq!(entry.selected_printing, Some(0)); // Tie goes to the earlier printing
+
+        assert!(entry.set_printing_quantity(1, 3));
+        assert_eq!(entry.selected_printing, Some(1));
+        assert_eq!(entry.decklist_entry.multiple.get(), 5);
+
+        // Dropping back to one printing collapses the split
+        assert!(entry.set_printing_quantity(0, 0));
+        assert!(!entry.is_split());
+        assert_eq!(entry.printing_quantities(), vec![(1, 3)]);
+
+        // The last copy cannot be removed, and unknown printings are rejected
+        assert!(!entry.set_printing_quantity(1, 0));
+        assert!(!entry.set_printing_quantity(7, 1));
+        assert_eq!(entry.quantity_for_printing(1), 3);
+
+        // Selecting a single printing clears the split
+        entry.set_printing_quantity(2, 1);
+        assert!(entry.select_printing(2));
+        assert!(!entry.is_split());
+        assert_eq!(entry.printing_quantities(), vec![(2, 4)]);
+    }
+
+    #[test]
+    fn test_layout_follows_split_and_page_size() {
+        let mut split_entry = preview_entry(3, &["m21", "lea"]);
+        split_entry.set_printing_quantity(0, 1);
+        split_entry.set_printing_quantity(1, 2);
+
+        let mut dfc = printing("khm");
+        dfc.back_side = Some(BackSide::DfcBack {
+            image_url: "https://example.com/khm-back.jpg".to_string(),
+            name: "back".to_string(),
+        });
+        let mut dfc_entry = preview_entry(1, &[]);
+        dfc_entry.available_printings = vec![dfc];
+
+        let options = PdfOptions {
+            cards_per_row: 2,
+            cards_per_column: 2,
+            ..Default::default()
+        };
+        let mut preview = GridPreview::new(vec![split_entry, dfc_entry], 0);
+        preview.layout(&options);
+
+        assert_eq!(preview.total_pages, 2);
+        let positions = &preview.entries[0].grid_positions;
+        let printings: Vec<usize> = positions.iter().map(|p| p.printing_index).collect();
+        assert_eq!(printings, vec![0, 1, 1]);
+        let copies: Vec<usize> = positions.iter().map(|p| p.copy_number).collect();
+        assert_eq!(copies, vec![0, 1, 2]);
+
+        // Both faces of the double-faced card, wrapping onto the second page
+        let dfc_positions = &preview.entries[1].grid_positions;
+        assert_eq!(dfc_positions.len(), 2);
+        assert_eq!(
+            (dfc_positions[0].page, dfc_positions[0].position_in_page),
+            (0, 3)
+        );
+        assert_eq!(
+            (dfc_positions[1].page, dfc_positions[1].position_in_page),
+            (1, 0)
+        );
+        assert_eq!(dfc_positions[1].image_index, 1);
+
+        let groups = preview.to_card_groups(&options);
+        assert_eq!(groups.len(), 1);
+        let summary: Vec<(&str, u32)> = groups[0]
+            .iter()
+            .map(|(card, copies, _)| (card.set.as_str(), *copies))
+            .collect();
+        assert_eq!(summary, vec![("m21", 1), ("lea", 2), ("khm", 1)]);
+    }
+
+    #[test]
+    fn test_layout_isolates_and_duplicates_commanders() {
+        let mut commander = preview_entry(1, &["cmr"]);
+        commander.decklist_entry.section = DeckSection::Commander;
+        let main = preview_entry(2, &["m21"]);
+
+        let options = PdfOptions {
+            commanders_on_own_page: true,
+            duplicate_commanders: true,
+            ..Default::default()
+        };
+        let mut preview = GridPreview::new(vec![commander, main], 0);
+        preview.layout(&options);
+
+        // The main deck stays on the first page, the duplicated commander gets the second
+        assert_eq!(preview.total_pages, 2);
+        let commander_positions: Vec<(usize, usize)> = preview.entries[0]
+            .grid_positions
+            .iter()
+            .map(|p| (p.page, p.position_in_page))
+            .collect();
+        assert_eq!(commander_positions, vec![(1, 0), (1, 1)]);
+        assert_eq!(preview.entries[1].grid_positions[0].page, 0);
+
+        let groups = preview.to_card_groups(&options);
+        let summary: Vec<Vec<(&str, u32)>> = groups
+            .iter()
+            .map(|group| {
+                group
+                    .iter()
+                    .map(|(card, copies, _)| (card.set.as_str(), *copies))
+                    .collect()
+            })
+            .collect();
+        assert_eq!(summary, vec![vec![("m21", 2)], vec![("cmr", 2)]]);
+    }
+
+    #[test]
+    fn test_layout_uses_grid_size_of_options() {
+        let entries = || vec![preview_entry(10, &["m21"]), preview_entry(10, &["lea"])];
+        let large_grid = PdfOptions {
+            cards_per_row: 4,
+            cards_per_column: 4,
+            ..Default::default()
+        };
+        let mut preview = GridPreview::new(entries(), 0);
+        preview.layout(&large_grid);
+        assert_eq!(preview.cards_per_page, 16);
+        assert_eq!(preview.total_pages, 2);
+        let last = preview.entries[1].grid_positions.last().unwrap();
+        assert_eq!((last.page, last.position_in_page), (1, 3));
+
+        preview.go_to_page(1);
+        let slots = preview.get_current_page_slots();
+        assert_eq!(slots.len(), 16);
+        assert_eq!(slots.iter().filter(|slot| slot.is_some()).count(), 4);
+
+        let small_grid = PdfOptions {
+            cards_per_row: 2,
+            cards_per_column: 2,
+            ..Default::default()
+        };
+        preview.layout(&small_grid);
+        assert_eq!(preview.total_pages, 5);
+        // The current page stays where it was as long as it still exists
+        assert_eq!(preview.current_page, 1);
+        assert_eq!(preview.get_current_page_slots().len(), 4);
+    }
+
+    #[test]
+    fn test_entry_face_mode_override() {
+        let mut dfc = printing("khm");
+        dfc.back_side = Some(BackSide::DfcBack {
+            image_url: "https://example.com/khm-back.jpg".to_string(),
+            name: "back".to_string(),
+        });
+        let mut dfc_entry = preview_entry(2, &[]);
+        dfc_entry.available_printings = vec![dfc];
+        let options = PdfOptions::default();
+        let mut preview = GridPreview::new(vec![dfc_entry, preview_entry(1, &["lea"])], 0);
+        preview.layout(&options);
+        assert!(preview.entries[0].has_back_side());
+        assert!(!preview.entries[1].has_back_side());
+        assert_eq!(preview.entries[0].grid_positions.len(), 4);
+        assert_eq!(preview.entries[1].grid_positions[0].position_in_page, 4);
+
+        // Only this entry loses its back faces, in the preview and in the PDF
+        assert!(preview.entries[0].set_face_mode(DoubleFaceMode::FrontOnly));
+        assert!(!preview.entries[0].set_face_mode(DoubleFaceMode::FrontOnly));
+        preview.relayout_entry(0, &options);
+        assert_eq!(preview.entries[0].grid_positions.len(), 2);
+        assert_eq!(preview.entries[1].grid_positions[0].position_in_page, 2);
+        let face_modes: Vec<DoubleFaceMode> = preview.to_card_groups(&options)[0]
+            .iter()
+            .map(|(_, _, face_mode)| face_mode.clone())
+            .collect();
+        assert_eq!(
+            face_modes,
+            vec![DoubleFaceMode::FrontOnly, DoubleFaceMode::BothSides]
+        );
+        assert_eq!(DoubleFaceMode::FrontOnly.next(), DoubleFaceMode::BothSides);
+    }
+
+    #[test]
+    fn test_relayout_entry_keeps_place() {
+        let mut dfc = printing("khm");
+        dfc.back_side = Some(BackSide::DfcBack {
+            image_url: "https://example.com/khm-back.jpg".to_string(),
+            name: "back".to_string(),
+        });
+        let mut changed = preview_entry(4, &["m21"]);
+        changed.available_printings.push(dfc);
+        let mut commander = preview_entry(1, &["cmr"]);
+        commander.decklist_entry.section = DeckSection::Commander;
+        let options = PdfOptions {
+            cards_per_row: 2,
+            cards_per_column: 2,
+            commanders_on_own_page: true,
+            ..Default::default()
+        };
+
+        let mut preview = GridPreview::new(
+            vec![
+                changed,
+                preview_entry(4, &["lea"]),
+                preview_entry(4, &["znr"]),
+                commander,
+            ],
+            0,
+        );
+        preview.layout(&options);
+        assert_eq!(preview.total_pages, 4);
+        preview.go_to_page(2); // The znr copies
+
+        // Both faces of four copies push the later cards back a page
+        assert!(preview.entries[0].select_printing(1));
+        preview.relayout_entry(0, &options);
+        assert_eq!(preview.total_pages, 5);
+        assert_eq!(preview.current_page, 3);
+        assert_eq!(preview.get_current_page_positions()[0].1.entry_index, 2);
+
+        // The same positions as laying out everything again
+        let mut full = preview.clone();
+        full.layout(&options);
+        let positions = |preview: &GridPreview| -> Vec<(usize, usize, usize)> {
+            preview
+                .entries
+                .iter()
+                .flat_map(|entry| &entry.grid_positions)
+                .map(|p| (p.entry_index, p.page, p.position_in_page))
+                .collect()
+        };
+        assert_eq!(positions(&preview), positions(&full));
+
+        // Changing the commander keeps the deck pages and the current page
+        preview.go_to_page(1);
+        preview.relayout_entry(3, &options);
+        assert_eq!(preview.current_page, 1);
+        assert_eq!(positions(&preview), positions(&full));
+    }
+
+    #[test]
+    fn test_move_entry_reorders_layout() {
+        let entries = ["m21", "lea", "znr", "khm"]
+            .iter()
+            .map(|set| preview_entry(1, &[set]))
+            .collect();
+        let options = PdfOptions::default();
+        let mut preview = GridPreview::new(entries, 0);
+        preview.set_filler_entries(vec![PreviewEntry::filler("island", vec![printing("woe")])]);
+
+        let printed_sets = |preview: &GridPreview| -> Vec<String> {
+            preview.to_card_groups(&options)[0]
+                .iter()
+                .map(|(card, _, _)| card.set.clone())
+                .collect()
+        };
+
+        // Moving forward puts the entry after the target, moving back puts it before
+        assert!(preview.move_entry(0, 2, &options));
+        assert!(preview.move_entry(3, 1, &options));
+        preview.layout(&options);
+        assert_eq!(printed_sets(&preview), ["khm", "lea", "znr", "m21", "woe"]);
+        assert_eq!(preview.entries[3].grid_positions[0].position_in_page, 0);
+        assert_eq!(preview.entries[0].grid_positions[0].position_in_page, 3);
+
+        // Fillers stay at the end, and moving onto itself changes nothing
+        assert!(!preview.move_entry(4, 0, &options));
+        assert!(!preview.move_entry(1, 1, &options));
+
+        // The sorted order becomes the base of the next move
+        let alphabetical = PdfOptions {
+            sort_order: crate::SortOrder::Alphabetical,
+            ..Default::default()
+        };
+        preview.entries[2].available_printings[0].name = "a".to_string();
+        assert!(preview.move_entry(0, 1, &alphabetical));
+        assert_eq!(preview.entry_order, vec![2, 3, 0, 1]);
+    }
+
+    #[test]
+    fn test_layout_interleaves_copies() {
+        let options = PdfOptions {
+            copy_order: CopyOrder::Interleaved,
+            ..Default::default()
+        };
+        let mut preview = GridPreview::new(
+            vec![preview_entry(3, &["m21"]), preview_entry(1, &["lea"])],
+            0,
+        );
+        preview.set_filler_entries(vec![PreviewEntry::filler("island", vec![printing("woe")])]);
+        preview.layout(&options);
+
+        let slots = |entry: &PreviewEntry| -> Vec<(usize, usize)> {
+            entry
+                .grid_positions
+                .iter()
+                .map(|p| (p.position_in_page, p.copy_number))
+                .collect()
+        };
+        assert_eq!(slots(&preview.entries[0]), vec![(0, 0), (2, 1), (3, 2)]);
+        assert_eq!(slots(&preview.entries[1]), vec![(1, 0)]);
+        // Fillers keep their copies together after the deck
+        assert_eq!(
+            slots(&preview.entries[2]),
+            vec![(4, 0), (5, 1), (6, 2), (7, 3), (8, 4)]
+        );
+
+        let summary: Vec<(&str, u32)> = preview.to_card_groups(&options)[0]
+            .iter()
+            .map(|(card, copies, _)| (card.set.as_str(), *copies))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![("m21", 1), ("lea", 1), ("m21", 1), ("m21", 1), ("woe", 5)]
+        );
+    }
+
+    #[test]
+    fn test_layout_fills_last_page_with_fillers() {
+        let commander = {
+            let mut entry = preview_entry(1, &["cmr"]);
+            entry.decklist_entry.section = DeckSection::Commander;
+            entry
+        };
+        let main = preview_entry(2, &["m21"]);
+        let options = PdfOptions {
+            cards_per_row: 2,
+            cards_per_column: 2,
+            commanders_on_own_page: true,
+            ..Default::default()
+        };
+
+        let mut preview = GridPreview::new(vec![commander, main], 0);
+        preview.set_filler_entries(vec![
+            PreviewEntry::filler("plains", vec![printing("one")]),
+            PreviewEntry::filler("island", vec![printing("woe")]),
+        ]);
+        preview.layout(&options);
+
+        // The fillers follow the commander on its page, not the main deck
+        assert_eq!(preview.total_pages, 2);
+        let filler_positions: Vec<(usize, usize)> = preview.entries[2..]
+            .iter()
+            .flat_map(|entry| &entry.grid_positions)
+            .map(|p| (p.page, p.position_in_page))
+            .collect();
+        assert_eq!(filler_positions, vec![(1, 1), (1, 2), (1, 3)]);
+
+        let groups = preview.to_card_groups(&options);
+        let summary: Vec<Vec<(&str, u32)>> = groups
+            .iter()
+            .map(|group| {
+                group
+                    .iter()
+                    .map(|(card, copies, _)| (card.set.as_str(), *copies))
+                    .collect()
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![vec![("m21", 2)], vec![("cmr", 1), ("one", 2), ("woe", 1)]]
+        );
+
+        // Replacing the fillers drops the old ones
+        preview.set_filler_entries(Vec::new());
+        preview.layout(&options);
+        assert_eq!(preview.entries.len(), 2);
+    }
+
+    #[test]
+    fn test_layout_group_dividers() {
+        let options = PdfOptions {
+            group_dividers: true,
+            ..Default::default()
+        };
+        let mut lands = preview_entry(2, &["m21"]);
+        lands.decklist_entry.annotation = Some("lands".to_string());
+        let mut ramp = preview_entry(1, &["lea"]);
+        ramp.decklist_entry.annotation = Some("ramp".to_string());
+        let mut sideboard = preview_entry(1, &["znr"]);
+        sideboard.decklist_entry.section = DeckSection::Sideboard;
+
+        let mut preview = GridPreview::new(vec![lands, ramp, sideboard], 0);
+        preview.layout(&options);
+
+        // Each divider takes a row; one that would end a page moves to the next page
+        let dividers: Vec<(usize, usize, &str)> = preview
+            .dividers
+            .iter()
+            .map(|d| (d.page, d.position_in_page, d.label.as_str()))
+            .collect();
+        assert_eq!(
+            dividers,
+            vec![(0, 0, "lands"), (1, 0, "ramp"), (2, 0, "Sideboard")]
+        );
+        let first_positions: Vec<(usize, usize)> = preview
+            .entries
+            .iter()
+            .map(|entry| {
+                (
+                    entry.grid_positions[0].page,
+                    entry.grid_positions[0].position_in_page,
+                )
+            })
+            .collect();
+        assert_eq!(first_positions, vec![(0, 3), (1, 3), (2, 3)]);
+        assert_eq!(preview.total_pages, 3);
+        assert_eq!(
+            preview.group_labels(&options),
+            vec![vec![
+                (0, "lands".to_string()),
+                (1, "ramp".to_string()),
+                (2, "Sideboard".to_string())
+            ]]
+        );
+
+        // Without the option the cards fill the slots as before
+        preview.layout(&PdfOptions::default());
+        assert!(preview.dividers.is_empty());
+        assert_eq!(preview.total_pages, 1);
+        assert!(preview.group_labels(&PdfOptions::default()).is_empty());
+    }
 }
@@ -0,0 +1,266 @@
+use crate::i18n::{Text, tr_with};
+use crate::scryfall::models::Card;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+/// Constructed formats that decklists can be checked against
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Format {
+    Standard,
+    Pioneer,
+    Modern,
+    Legacy,
+    Vintage,
+    Pauper,
+    Commander,
+    Premodern,
+}
+
+impl Format {
+    pub fn all() -> Vec<Format> {
+        vec![
+            Format::Standard,
+            Format::Pioneer,
+            Format::Modern,
+            Format::Legacy,
+            Format::Vintage,
+            Format::Pauper,
+            Format::Commander,
+            Format::Premodern,
+        ]
+    }
+
+    /// Key of this format in Scryfall's `legalities` object
+    pub fn scryfall_key(&self) -> &'static str {
+        match self {
+            Format::Standard => "standard",
+            Format::Pioneer => "pioneer",
+            Format::Modern => "modern",
+            Format::Legacy => "legacy",
+            Format::Vintage => "vintage",
+            Format::Pauper => "pauper",
+            Format::Commander => "commander",
+            Format::Premodern => "premodern",
+        }
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Format::Standard => write!(f, "Standard"),
+            Format::Pioneer => write!(f, "Pioneer"),
+            Format::Modern => write!(f, "Modern"),
+            Format::Legacy => write!(f, "Legacy"),
+            Format::Vintage => write!(f, "Vintage"),
+            Format::Pauper => write!(f, "Pauper"),
+            Format::Commander => write!(f, "Commander"),
+            Format::Premodern => write!(f, "Premodern"),
+        }
+    }
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let key = s.trim().to_lowercase();
+        let key = if key == "edh" {
+            "commander"
+        } else {
+            key.as_str()
+        };
+        Format::all()
+            .into_iter()
+            .find(|format| format.scryfall_key() == key)
+            .ok_or_else(|| format!("Unknown format: {}", s))
+    }
+}
+
+/// Legality of a card in a format, as reported by Scryfall
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Legality {
+    Legal,
+    NotLegal,
+    Restricted,
+    Banned,
+}
+
+impl Legality {
+    fn from_scryfall_str(s: &str) -> Option<Legality> {
+        match s {
+            "legal" => Some(Legality::Legal),
+            "not_legal" => Some(Legality::NotLegal),
+            "restricted" => Some(Legality::Restricted),
+            "banned" => Some(Legality::Banned),
+            _ => None,
+        }
+    }
+}
+
+/// Parse the `legalities` object of a Scryfall card, skipping values we don't know
+pub(crate) fn parse_scryfall_legalities(value: &serde_json::Value) -> BTreeMap<String, Legality> {
+    value
+        .as_object()
+        .map(|legalities| {
+            legalities
+                .iter()
+                .filter_map(|(format, legality)| {
+                    let legality = Legality::from_scryfall_str(legality.as_str()?)?;
+                    Some((format.clone(), legality))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// A card in a decklist that may not be played as listed in a format
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LegalityIssue {
+    pub card_name: String,
+    pub legality: Legality,
+    /// Total copies of the card in the decklist
+    pub copies: u32,
+}
+
+impl fmt::Display for LegalityIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let message = match self.legality {
+            Legality::Banned => tr_with(Text::CardBanned, &[&self.card_name]),
+            Legality::NotLegal => tr_with(Text::CardNotLegal, &[&self.card_name]),
+            Legality::Restricted => tr_with(Text::CardRestricted, &[&self.card_name, &self.copies]),
+            Legality::Legal => tr_with(Text::CardLegal, &[&self.card_name]),
+        };
+        f.write_str(&message)
+    }
+}
+
+/// Report the banned, not legal and over-restricted cards of a resolved decklist
+///
+/// Copies of the same card are counted together across entries. Cards without
+/// legality data (e.g. loaded from an older search cache) are skipped.
+pub fn check_legality(cards: &[(Card, u32)], format: Format) -> Vec<LegalityIssue> {
+    let mut copies_by_name: BTreeMap<&str, (u32, Legality)> = BTreeMap::new();
+    for (card, copies) in cards {
+        let Some(legality) = card.legalities.get(format.scryfall_key()) else {
+            continue;
+        };
+        copies_by_name
+            .entry(card.name.as_str())
+            .or_insert((0, *legality))
+            .0 += copies;
+    }
+
+    copies_by_name
+        .into_iter()
+        .filter(|(_, (copies, legality))| match legality {
+            Legality::Legal => false,
+            Legality::Restricted => *copies > 1,
+            Legality::NotLegal | Legality::Banned => true,
+        })
+        .map(|(name, (copies, legality))| LegalityIssue {
+            card_name: name.to_string(),
+            legality,
+            copies,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn card(name: &str, legalities: serde_json::Value) -> Card {
+        Card {
+            name: name.to_string(),
+            set: "lea".to_string(),
+            language: "en".to_string(),
+            border_crop: format!("https://example.com/{}.jpg", name),
+            back_side: None,
+            frame: None,
+            digital: false,
+            collector_number: None,
+            prices: None,
+            legalities: parse_scryfall_legalities(&legalities),
+            layout: None,
+            scryfall_id: None,
+            oracle_id: None,
+            mana_cost: None,
+            type_line: None,
+            oracle_text: None,
+            power_toughness: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_legalities_skips_unknown_values() {
+        let legalities = parse_scryfall_legalities(&serde_json::json!({
+            "modern": "legal",
+            "vintage": "restricted",
+            "future": "something_new",
+        }));
+        assert_eq!(legalities.len(), 2);
+        assert_eq!(legalities.get("vintage"), Some(&Legality::Restricted));
+    }
+
+    #[test]
+    fn test_check_legality() {
+        let cards = vec![
+            (
+                card(
+                    "black lotus",
+                    serde_json::json!({"vintage": "restricted", "modern": "not_legal"}),
+                ),
+                1,
+            ),
+            (
+                card(
+                    "brainstorm",
+                    serde_json::json!({"vintage": "restricted", "modern": "not_legal"}),
+                ),
+                2,
+            ),
+            (
+                card(
+                    "lightning bolt",
+                    serde_json::json!({"vintage": "legal", "modern": "legal"}),
+                ),
+                4,
+            ),
+            (
+                card(
+                    "mental misstep",
+                    serde_json::json!({"vintage": "banned", "modern": "banned"}),
+                ),
+                1,
+            ),
+            (card("unknown card", serde_json::json!({})), 1),
+        ];
+
+        let issues: Vec<String> = check_legality(&cards, Format::Vintage)
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(
+            issues,
+            vec![
+                "brainstorm: restricted (2 copies, at most 1 allowed)",
+                "mental misstep: banned",
+            ]
+        );
+
+        let issues = check_legality(&cards, Format::Modern);
+        assert_eq!(issues.len(), 3);
+        assert_eq!(issues[0].legality, Legality::NotLegal);
+    }
+
+    #[test]
+    fn test_format_from_str() {
+        assert_eq!("Modern".parse::<Format>(), Ok(Format::Modern));
+        assert_eq!("edh".parse::<Format>(), Ok(Format::Commander));
+        assert!("hearthstone".parse::<Format>().is_err());
+    }
+}
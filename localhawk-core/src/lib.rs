@@ -1,36 +1,70 @@
 pub mod background_loading;
 pub mod cache;
+pub mod cache_contents;
+pub mod cache_events;
 pub mod cache_logic;
 pub mod card_name_cache;
+pub mod collection;
 pub mod decklist;
+pub mod diagnostics;
 pub mod error;
 #[cfg(feature = "ios")]
 pub mod ffi;
 pub mod format;
 pub mod globals;
+pub mod history;
+pub mod hooks;
 #[cfg(feature = "ios")]
 pub mod http_client;
+pub mod i18n;
 #[cfg(feature = "ios")]
 pub mod ios_api;
 #[cfg(feature = "ios")]
 pub mod ios_cache;
+#[cfg(feature = "gui-support")]
 pub mod layout;
+pub mod legality;
+pub mod logging;
 pub mod lookup;
+pub mod output;
+#[cfg(feature = "gui-support")]
 pub mod pagination;
 pub mod pdf;
+pub mod pinned_decks;
+pub mod prefetch;
 pub mod scryfall;
+pub mod search_filter;
 pub mod search_results_cache;
 pub mod set_codes_cache;
-
+pub mod set_icon_cache;
+pub mod settings;
+mod single_flight;
+pub mod sorting;
+pub mod stats;
+pub mod tokens;
+
+#[cfg(feature = "background-loading")]
 pub use background_loading::{
-    BackgroundLoadHandle, BackgroundLoadProgress, LoadingPhase, start_background_image_loading,
+    BackgroundLoadConfig, BackgroundLoadHandle, BackgroundLoader, start_background_image_loading,
+    start_background_image_loading_with_config,
 };
-pub use cache::{LruImageCache, LruSearchCache};
+pub use background_loading::{BackgroundLoadProgress, LoadingPhase};
+pub use cache::{EvictionPolicy, ImageArchiveImport, LruImageCache, LruSearchCache};
+pub use cache_contents::{CachedPrinting, ImageCacheContents, image_cache_contents};
+pub use cache_events::{CacheEvent, subscribe_cache_events};
+pub use cache_logic::{CacheRefreshStatus, FreshnessPolicy};
 pub use card_name_cache::CardNameCache;
+pub use collection::Collection;
+pub use diagnostics::{CheckStatus, DiagnosticCheck, DiagnosticsReport, run_diagnostics};
 pub use set_codes_cache::SetCodesCache;
+pub use set_icon_cache::SetIconCache;
+pub use settings::{GuiLayout, Settings};
+pub use sorting::{CopyOrder, SortOrder};
+pub use stats::UsageStats;
+pub use tokens::{TokenDescription, TokenSuggestion, tokens_in_rules_text};
 
 /// Face mode for double-faced cards - moved from pdf module as it's used throughout the codebase
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub enum DoubleFaceMode {
     /// Include only the front face of double-faced cards
     FrontOnly,
@@ -58,27 +92,134 @@ impl DoubleFaceMode {
             DoubleFaceMode::BothSides,
         ]
     }
+
+    /// The mode after this one when cycling through them: front, both sides, back
+    pub fn next(&self) -> DoubleFaceMode {
+        match self {
+            DoubleFaceMode::FrontOnly => DoubleFaceMode::BothSides,
+            DoubleFaceMode::BothSides => DoubleFaceMode::BackOnly,
+            DoubleFaceMode::BackOnly => DoubleFaceMode::FrontOnly,
+        }
+    }
+}
+
+/// What to print for a back face whose image is unavailable, e.g. a meld card whose meld
+/// result could not be resolved
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+pub enum BackFaceFallback {
+    /// Print the front face instead (with both sides, only the front is printed)
+    #[default]
+    Front,
+    /// Leave the back face out, so back-only cards are not printed at all
+    Skip,
+    /// Print a plain placeholder card back
+    Placeholder,
+}
+
+impl std::fmt::Display for BackFaceFallback {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BackFaceFallback::Front => write!(f, "Use front"),
+            BackFaceFallback::Skip => write!(f, "Skip"),
+            BackFaceFallback::Placeholder => write!(f, "Placeholder back"),
+        }
+    }
+}
+
+impl std::str::FromStr for BackFaceFallback {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "front" => Ok(BackFaceFallback::Front),
+            "skip" => Ok(BackFaceFallback::Skip),
+            "placeholder" => Ok(BackFaceFallback::Placeholder),
+            _ => Err(format!(
+                "Unknown back face fallback: {} (expected front, skip or placeholder)",
+                s
+            )),
+        }
+    }
+}
+
+impl BackFaceFallback {
+    pub fn all() -> Vec<BackFaceFallback> {
+        vec![
+            BackFaceFallback::Front,
+            BackFaceFallback::Skip,
+            BackFaceFallback::Placeholder,
+        ]
+    }
 }
-pub use decklist::{DecklistEntry, ParsedDecklistLine, parse_decklist, parse_line};
+pub use decklist::{
+    DeckSection, DecklistEntry, DecklistFormat, DownloadEstimate, LARGE_DOWNLOAD_BYTES,
+    LARGE_PRINT_PAGES, LineIssue, MAX_COLLECTOR_NUMBER_RANGE, ParsedDecklistLine, PrintEstimate,
+    ResolutionReport, attach_comment_annotations, comment_annotation, deck_file_name,
+    parse_deck_name, parse_decklist, parse_decklist_with_format, parse_line,
+    parse_line_with_format,
+};
 pub use error::ProxyError;
-pub use format::{build_aligned_parsed_output, format_decklist_entry, format_entries_summary};
+pub use format::{
+    build_aligned_parsed_output, build_aligned_parsed_output_with_prices,
+    build_aligned_parsed_output_with_report, format_decklist_entry, format_entries_summary,
+    format_normalized_decklist, format_normalized_line, format_price_total,
+};
 pub use globals::{
-    find_card_name, force_update_card_lookup, force_update_set_codes, get_cache_directory_path,
-    get_cached_image_bytes, get_card_lookup, get_card_name_cache_info,
+    ImagePin, clear_decklist_history, count_cached_images, export_image_cache_to, find_card_name,
+    force_update_card_lookup, force_update_set_codes, get_back_face_fallback, get_basic_land_sets,
+    get_cache_dates, get_cache_directory_path, get_cache_refresh_status, get_cached_image_bytes,
+    get_cached_set_icon, get_cached_thumbnail_bytes, get_card_lookup, get_card_name_cache_info,
     get_card_name_cache_info_ref, get_card_names_cache_path, get_card_names_cache_size,
-    get_image_cache, get_image_cache_info, get_image_cache_path, get_or_fetch_image,
-    get_or_fetch_image_bytes, get_or_fetch_search_results, get_scryfall_client,
-    get_search_cache_path, get_search_results_cache_info, get_set_codes_cache,
-    get_set_codes_cache_path, initialize_caches, save_caches, shutdown_caches,
+    get_comment_annotations, get_decklist_history, get_decklist_history_path, get_default_printing,
+    get_download_rate_limit, get_favorite_printings, get_freshness_policy, get_image_cache,
+    get_image_cache_contents, get_image_cache_eviction_policy, get_image_cache_info,
+    get_image_cache_max_size_mb, get_image_cache_path, get_log_directory_path,
+    get_or_fetch_default_printing_id, get_or_fetch_exact_printing, get_or_fetch_image,
+    get_or_fetch_image_bytes, get_or_fetch_query_results, get_or_fetch_search_results,
+    get_or_fetch_set_icon, get_pinned_decks, get_pinned_decks_path, get_preferred_language,
+    get_query_cache_path, get_read_only_caches, get_scryfall_client, get_search_cache_path,
+    get_search_results_cache_info, get_set_codes_cache, get_set_codes_cache_path,
+    get_set_icons_cache_path, get_set_info, get_sets, get_settings, get_settings_path,
+    get_usage_stats, get_usage_stats_path, has_favorite_printing, import_image_cache_from,
+    initialize_caches, is_card_lookup_ready, is_favorite_printing, is_scryfall_unavailable,
+    pin_deck, pin_images, record_recent_decklist, reset_usage_stats, save_caches, save_usage_stats,
+    set_back_face_fallback, set_basic_land_sets, set_cache_directory, set_comment_annotations,
+    set_default_printing, set_display_name, set_download_rate_limit, set_favorite_printings,
+    set_freshness_policy, set_image_cache_eviction_policy, set_image_cache_max_size_mb,
+    set_preferred_language, set_read_only_caches, shutdown_caches, subscribe_cache_refresh_status,
+    suggest_card_names, toggle_favorite_printing, unpin_deck, update_settings,
+    wait_for_cache_refresh, wait_for_card_lookup,
+};
+pub use history::{DecklistHistory, MAX_RECENT_DECKLISTS, RecentDecklist};
+pub use hooks::{PostGenerationHook, command_hook, print_file, save_pdf};
+pub use i18n::{Text, UiLanguage, set_ui_language, tr, tr_with, ui_language};
+#[cfg(feature = "gui-support")]
+pub use layout::{GridDivider, GridImage, GridPosition, GridPreview, PageNavigation, PreviewEntry};
+pub use legality::{Format, Legality, LegalityIssue, check_legality};
+pub use logging::{DEFAULT_LOG_FILTER, export_logs, init_logging};
+pub use lookup::{CardNameLookup, NameLookupResult, NameMatchMode, normalize_name_separators};
+pub use output::{
+    DEFAULT_FILE_NAME_TEMPLATE, OutputOptions, OverwritePolicy, existing_pdfs, write_pdfs,
 };
-pub use layout::{GridImage, GridPosition, GridPreview, PageNavigation, PreviewEntry};
-pub use lookup::{CardNameLookup, NameLookupResult, NameMatchMode};
+#[cfg(feature = "gui-support")]
 pub use pagination::{PaginatedGrid, PaginatedView};
-pub use pdf::{PageSize, PdfOptions, generate_pdf};
+pub use pdf::{
+    CALIBRATION_LENGTH_MM, CaptionedImage, ImageQuality, MAX_SCALE_CORRECTION,
+    MIN_SCALE_CORRECTION, PageImageFormat, PageSize, PdfOptions, RenderOptions, SectionSplit,
+    TextProxy, calibrated_scale_correction, divider_labels, generate_calibration_page,
+    generate_pdf, generate_pdf_from_groups, generate_pdfs_from_groups,
+    generate_text_pdfs_from_groups, pdf_deck_hash, render_pages, render_pages_from_groups,
+};
+pub use pinned_decks::PinnedDecks;
+pub use prefetch::{
+    PREFETCH_SAVE_INTERVAL, PrefetchPlan, PrefetchProgress, estimate_prefetch_time, format_eta,
+    plan_prefetch, prefetch_images,
+};
 pub use scryfall::{
     Card, CardSearchResult, ScryfallCardNames, ScryfallClient,
-    models::{ScryfallSetCodes, get_minimal_scryfall_languages},
+    models::{CardLayout, CardPrices, ScryfallSetCodes, SetInfo, get_minimal_scryfall_languages},
 };
+pub use search_filter::{SearchFilter, available_frames, available_languages, available_sets};
 
 /// Main interface for generating Magic card proxy sheets
 #[derive(Debug)]
@@ -97,6 +238,11 @@ impl ProxyGenerator {
         get_or_fetch_search_results(name).await
     }
 
+    /// Search with raw Scryfall syntax, e.g. "t:goblin e:dom lang:ja" (uses cached results)
+    pub async fn search_with_query(query: &str) -> Result<CardSearchResult, ProxyError> {
+        get_or_fetch_query_results(query).await
+    }
+
     /// Get all card names from Scryfall and initialize fuzzy matching (now uses global state)
     pub async fn initialize_card_lookup() -> Result<(), ProxyError> {
         // This is now handled by initialize_caches() at startup
@@ -118,8 +264,22 @@ impl ProxyGenerator {
         decklist_text: &str,
         global_face_mode: DoubleFaceMode,
     ) -> Result<Vec<DecklistEntry>, ProxyError> {
+        Self::parse_and_resolve_decklist_with_report(decklist_text, global_face_mode)
+            .await
+            .map(|(entries, _)| entries)
+    }
+
+    /// Parse and resolve a decklist like [`ProxyGenerator::parse_and_resolve_decklist`],
+    /// reporting the lines that were skipped or whose card name couldn't be resolved
+    pub async fn parse_and_resolve_decklist_with_report(
+        decklist_text: &str,
+        global_face_mode: DoubleFaceMode,
+    ) -> Result<(Vec<DecklistEntry>, ResolutionReport), ProxyError> {
         use scryfall::models::get_minimal_scryfall_languages;
 
+        // Card names loaded at startup may still be indexed in the background
+        wait_for_card_lookup().await;
+
         // These should already be initialized at startup, just verify
         if get_card_lookup().read().unwrap().is_none() {
             return Err(ProxyError::Cache(
@@ -141,58 +301,117 @@ impl ProxyGenerator {
             codes_guard.as_ref().cloned().unwrap_or_default()
         };
 
-        let parsed_lines = parse_decklist(decklist_text, &languages, &set_codes);
+        let mut parsed_lines = parse_decklist(decklist_text, &languages, &set_codes);
+        if get_comment_annotations() {
+            attach_comment_annotations(&mut parsed_lines);
+        }
 
         let mut resolved_entries = Vec::new();
-        for line in parsed_lines {
-            if let Some(mut entry) = line.as_entry() {
-                log::debug!(
-                    "Processing entry: {}x '{}' [set: {:?}, lang: {:?}]",
-                    entry.multiple,
+        let mut report = ResolutionReport::default();
+        // "1 Plains (UNF) 235-240" asks for every printing in the range
+        let entries = parsed_lines
+            .iter()
+            .filter_map(|line| line.as_entry())
+            .flat_map(DecklistEntry::expand_collector_number_range);
+        for mut entry in entries {
+            let mut unresolved = false;
+            tracing::debug!(
+                "Processing entry: {}x '{}' [set: {:?}, lang: {:?}]",
+                entry.multiple,
+                entry.name,
+                entry.set,
+                entry.lang
+            );
+            // Try to resolve the card name using global fuzzy matching
+            if let Some(lookup_result) = find_card_name(&entry.name) {
+                tracing::debug!(
+                    "Name resolution: '{}' -> '{}' (face mode: {:?})",
                     entry.name,
-                    entry.set,
-                    entry.lang
+                    lookup_result.name,
+                    lookup_result.hit
                 );
-                // Try to resolve the card name using global fuzzy matching
-                if let Some(lookup_result) = find_card_name(&entry.name) {
-                    log::debug!(
-                        "Name resolution: '{}' -> '{}' (face mode: {:?})",
-                        entry.name,
-                        lookup_result.name,
-                        lookup_result.hit
-                    );
-                    entry.name = lookup_result.name;
-                    // Apply face mode resolution logic (matches MagicHawk logic)
-                    entry.face_mode = match lookup_result.hit {
-                        crate::lookup::NameMatchMode::Part(1) => {
-                            log::debug!("Back face input detected, using BackOnly mode");
-                            DoubleFaceMode::BackOnly // Back face: always back only
-                        }
-                        _ => {
-                            log::debug!(
-                                "Front face or full name input, using global setting: {:?}",
-                                global_face_mode
-                            );
-                            global_face_mode.clone() // Front face or full name: use global setting
-                        }
-                    };
-                } else {
-                    log::debug!(
-                        "Name resolution: '{}' -> no match found, using global setting",
-                        entry.name
-                    );
-                    entry.face_mode = global_face_mode.clone(); // No match: use global setting
+                entry.name = lookup_result.name;
+                // Apply face mode resolution logic (matches MagicHawk logic)
+                entry.face_mode = match lookup_result.hit {
+                    crate::lookup::NameMatchMode::Part(1) => {
+                        tracing::debug!("Back face input detected, using BackOnly mode");
+                        DoubleFaceMode::BackOnly // Back face: always back only
+                    }
+                    _ => {
+                        tracing::debug!(
+                            "Front face or full name input, using global setting: {:?}",
+                            global_face_mode
+                        );
+                        global_face_mode.clone() // Front face or full name: use global setting
+                    }
+                };
+            } else {
+                tracing::debug!(
+                    "Name resolution: '{}' -> no match found, using global setting",
+                    entry.name
+                );
+                entry.face_mode = global_face_mode.clone(); // No match: use global setting
+                unresolved = !entry.name.is_empty();
+            }
+            // A normalized export line names its printing exactly; pin it, so the entry
+            // comes back the way it was exported regardless of the printing preferences
+            if entry.is_exact_printing() && entry.scryfall_id.is_none() {
+                match get_or_fetch_exact_printing(&entry).await {
+                    Ok(Some(card)) => entry.scryfall_id = card.scryfall_id,
+                    Ok(None) => tracing::warn!(
+                        card_name = %entry.name,
+                        "Printing {} {} [{}] not found",
+                        entry.set.as_deref().unwrap_or_default(),
+                        entry.collector_number.as_deref().unwrap_or_default(),
+                        entry.lang.as_deref().unwrap_or_default()
+                    ),
+                    Err(e) => tracing::warn!(
+                        card_name = %entry.name,
+                        error = %e,
+                        "Failed to look up exact printing"
+                    ),
                 }
-                resolved_entries.push(entry);
             }
+            // Token names aren't among the card names, but their exact printings resolve. The
+            // entries of a collector number range share their line, which is reported once.
+            let reported = entry
+                .source_line_number
+                .is_some_and(|line_number| report.issues.contains_key(&line_number));
+            if unresolved && entry.scryfall_id.is_none() && !reported {
+                let suggestion = suggest_card_names(&entry.name, 1)
+                    .into_iter()
+                    .next()
+                    .map(|suggestion| suggestion.name);
+                report.add_unresolved(&entry, suggestion);
+            }
+            // Pinning the default printing makes every frontend select it, even over a
+            // favorite printing; it is always English, so it would override a preferred
+            // language too
+            if get_default_printing()
+                && get_preferred_language().is_none_or(|language| language == "en")
+                && entry.set.is_none()
+                && entry.lang.is_none()
+                && entry.scryfall_id.is_none()
+                && !has_favorite_printing(&entry.name).await
+            {
+                match get_or_fetch_default_printing_id(&entry.name).await {
+                    Ok(id) => entry.scryfall_id = id,
+                    Err(e) => tracing::warn!(
+                        card_name = %entry.name,
+                        error = %e,
+                        "Failed to look up default printing"
+                    ),
+                }
+            }
+            resolved_entries.push(entry);
         }
 
-        log::debug!(
+        tracing::debug!(
             "Final resolved decklist: {} entries",
             resolved_entries.len()
         );
         for entry in &resolved_entries {
-            log::debug!(
+            tracing::debug!(
                 "  -> {}x '{}' [set: {:?}, lang: {:?}, face_mode: {:?}]",
                 entry.multiple,
                 entry.name,
@@ -201,7 +420,8 @@ impl ProxyGenerator {
                 entry.face_mode
             );
         }
-        Ok(resolved_entries)
+        report.add_skipped_lines(decklist_text, &resolved_entries);
+        Ok((resolved_entries, report))
     }
 
     /// Add a card to the generation queue
@@ -277,18 +497,103 @@ impl ProxyGenerator {
     /// Expand a list of cards with quantities into a sequential list of image URLs
     /// This is the single source of truth for what images appear in the PDF and in what order
     pub fn expand_cards_to_image_urls(cards: &[(Card, u32, DoubleFaceMode)]) -> Vec<String> {
+        Self::expand_cards_to_captioned_image_urls(cards)
+            .into_iter()
+            .map(|(image_url, _)| image_url)
+            .collect()
+    }
+
+    /// Expand cards like [`ProxyGenerator::expand_cards_to_image_urls`], pairing every image URL
+    /// with the caption of the card it belongs to
+    pub fn expand_cards_to_captioned_image_urls(
+        cards: &[(Card, u32, DoubleFaceMode)],
+    ) -> Vec<(String, String)> {
         let mut image_urls = Vec::new();
 
         for (card, quantity, face_mode) in cards {
             for _ in 0..*quantity {
                 let urls = card.get_images_for_face_mode(face_mode);
-                image_urls.extend(urls);
+                image_urls.extend(urls.into_iter().map(|url| (url, card.caption())));
             }
         }
 
         image_urls
     }
 
+    /// Lay out the image URLs of card groups into pages exactly like PDF generation does,
+    /// without downloading anything
+    pub fn plan_pages_from_card_groups(
+        card_groups: &[Vec<(Card, u32, DoubleFaceMode)>],
+        options: &PdfOptions,
+    ) -> Vec<Vec<String>> {
+        let cards_per_page = options.cards_per_page().max(1);
+        let mut pages = Vec::new();
+        for (group_index, cards) in card_groups.iter().enumerate() {
+            let image_urls = Self::expand_cards_to_image_urls(cards);
+            let divider_images: Vec<usize> = Self::divider_images(cards, options, group_index)
+                .into_iter()
+                .map(|(image_index, _)| image_index)
+                .collect();
+            let (slots, _) = options.group_slots(image_urls.len(), &divider_images);
+
+            let first_page = pages.len();
+            for (slot, image_url) in slots.into_iter().zip(image_urls) {
+                let page = first_page + slot / cards_per_page;
+                if pages.len() <= page {
+                    pages.resize_with(page + 1, Vec::new);
+                }
+                pages[page].push(image_url);
+            }
+        }
+        pages
+    }
+
+    /// Divider rows of the card group at `group_index` (see [`PdfOptions::group_labels`]): the
+    /// index of the image each goes before, and its label
+    fn divider_images(
+        cards: &[(Card, u32, DoubleFaceMode)],
+        options: &PdfOptions,
+        group_index: usize,
+    ) -> Vec<(usize, String)> {
+        let labels = match options.group_labels.get(group_index) {
+            Some(labels) if options.shows_group_dividers() => labels,
+            _ => return Vec::new(),
+        };
+
+        let mut dividers = Vec::new();
+        let mut image_index = 0;
+        for (card_index, (card, quantity, face_mode)) in cards.iter().enumerate() {
+            if let Some((_, label)) = labels.iter().find(|(index, _)| *index == card_index) {
+                dividers.push((image_index, label.clone()));
+            }
+            image_index += *quantity as usize * card.get_images_for_face_mode(face_mode).len();
+        }
+        dividers
+    }
+
+    /// Pick the printing for a decklist entry: the pinned Scryfall id if present, else the first
+    /// one matching its name, set and language, falling back to the first printing (None only
+    /// if there are no printings)
+    pub fn select_printing_for_entry(printings: &[Card], entry: &DecklistEntry) -> Option<usize> {
+        let matching = background_loading::select_card_from_printings(printings, entry);
+        matching.or((!printings.is_empty()).then_some(0))
+    }
+
+    /// Copies per printing for a decklist entry, as (index into `printings`, copies): basic
+    /// lands without a set spread across the arts of the basic land sets (see
+    /// [`set_basic_land_sets`]), all other entries put every copy on
+    /// [`ProxyGenerator::select_printing_for_entry`] (empty only if there are no printings)
+    pub fn printing_quantities_for_entry(
+        printings: &[Card],
+        entry: &DecklistEntry,
+    ) -> Vec<(usize, u32)> {
+        background_loading::spread_basic_land_copies(printings, entry).unwrap_or_else(|| {
+            Self::select_printing_for_entry(printings, entry)
+                .map(|index| vec![(index, entry.multiple.get())])
+                .unwrap_or_default()
+        })
+    }
+
     /// Convert decklist entries to cards ready for PDF generation
     /// This is the shared logic for both PDF generation and grid preview
     pub async fn resolve_decklist_entries_to_cards(
@@ -297,57 +602,37 @@ impl ProxyGenerator {
         let mut card_list = Vec::new();
 
         for entry in entries {
-            log::debug!("Searching for card: '{}'", entry.name);
+            tracing::debug!("Searching for card: '{}'", entry.name);
             match Self::search_card(&entry.name).await {
                 Ok(search_result) => {
-                    log::debug!(
+                    tracing::debug!(
                         "Found {} printings for '{}'",
                         search_result.cards.len(),
                         entry.name
                     );
 
                     // Use the same card selection logic as used in both PDF generation and grid preview
-                    let selected_card = search_result
-                        .cards
-                        .iter()
-                        .position(|c| {
-                            // First check if the card name matches what we're looking for
-                            let name_matches = c.name.to_lowercase() == entry.name.to_lowercase();
-
-                            // Try to match both set and language if specified
-                            let set_matches = if let Some(ref entry_set) = entry.set {
-                                c.set.to_lowercase() == entry_set.to_lowercase()
-                            } else {
-                                true // No set filter
-                            };
-
-                            let lang_matches = if let Some(ref entry_lang) = entry.lang {
-                                c.language.to_lowercase() == entry_lang.to_lowercase()
-                            } else {
-                                true // No language filter
-                            };
-
-                            name_matches && set_matches && lang_matches
-                        })
-                        .and_then(|idx| search_result.cards.get(idx))
-                        .or_else(|| search_result.cards.first())
-                        .cloned();
-
-                    if let Some(card) = selected_card {
-                        log::debug!(
-                            "Selected card: '{}' ({}) [{}] with face mode {:?}",
+                    let quantities =
+                        Self::printing_quantities_for_entry(&search_result.cards, entry);
+                    if quantities.is_empty() {
+                        tracing::warn!("No suitable card found for entry '{}'", entry.name);
+                    }
+                    for (index, copies) in quantities {
+                        let card = search_result.cards[index].clone();
+                        let face_mode = entry.face_mode_for(&card);
+                        tracing::debug!(
+                            "Selected card: {}x '{}' ({}) [{}] with face mode {:?}",
+                            copies,
                             card.name,
                             card.set.to_uppercase(),
                             card.language,
-                            entry.face_mode
+                            face_mode
                         );
-                        card_list.push((card, entry.multiple as u32, entry.face_mode.clone()));
-                    } else {
-                        log::warn!("No suitable card found for entry '{}'", entry.name);
+                        card_list.push((card, copies, face_mode));
                     }
                 }
                 Err(e) => {
-                    log::debug!("Failed to search for card '{}': {:?}", entry.name, e);
+                    tracing::debug!("Failed to search for card '{}': {:?}", entry.name, e);
                     // Skip cards that can't be found - this matches current behavior
                 }
             }
@@ -356,9 +641,287 @@ impl ProxyGenerator {
         Ok(card_list)
     }
 
+    /// Estimate what generating `entries` downloads: the images of their selected printings
+    /// that aren't cached yet (the printings themselves are looked up through the search cache)
+    pub async fn estimate_downloads(
+        entries: &[DecklistEntry],
+    ) -> Result<DownloadEstimate, ProxyError> {
+        let cards = Self::resolve_decklist_entries_to_cards(entries).await?;
+        Ok(Self::estimate_downloads_for_cards(&cards))
+    }
+
+    /// Hash of everything the PDFs of these card groups show: the printing, images and copies
+    /// of every card, the options and the split into files of `pages_per_file` pages
+    ///
+    /// Generated PDFs embed it (see [`pdf_deck_hash`]), so a PDF with the same hash can be
+    /// reused instead of generating the deck again.
+    pub fn deck_hash(
+        card_groups: &[Vec<(Card, u32, DoubleFaceMode)>],
+        options: &PdfOptions,
+        pages_per_file: Option<usize>,
+    ) -> String {
+        use sha2::{Digest, Sha256};
+
+        let mut hasher = Sha256::new();
+        // Output of other versions may differ
+        hasher.update(env!("CARGO_PKG_VERSION"));
+        hasher.update(serde_json::to_vec(options).unwrap_or_default());
+        hasher.update(format!(
+            "{:?} {:?} {:?}",
+            options.title, options.group_labels, pages_per_file
+        ));
+        for (group_index, cards) in card_groups.iter().enumerate() {
+            hasher.update(format!("group {}", group_index));
+            for (card, copies, face_mode) in cards {
+                hasher.update(format!(
+                    "{} {} {} {:?} {:?} {} {:?}",
+                    card.name,
+                    card.set,
+                    card.language,
+                    card.collector_number,
+                    card.scryfall_id,
+                    copies,
+                    card.get_images_for_face_mode(face_mode)
+                ));
+                if options.text_only {
+                    hasher.update(format!("{:?}", TextProxy::from_card(card)));
+                }
+            }
+        }
+        format!("{:x}", hasher.finalize())
+    }
+
+    /// Tokens the rules text of these cards creates (see [`tokens`]), each with the first
+    /// paper printing of a matching token card to offer as an addition to the decklist
+    ///
+    /// Tokens already among the cards and tokens no token card matches are left out.
+    pub async fn suggest_tokens(cards: &[Card]) -> Vec<TokenSuggestion> {
+        let mut suggestions = Vec::new();
+        let mut searched = std::collections::HashSet::new();
+        for card in cards {
+            let Some(oracle_text) = &card.oracle_text else {
+                continue;
+            };
+            for token in tokens::tokens_in_rules_text(oracle_text) {
+                let query = token.query();
+                if !searched.insert(query.clone()) {
+                    continue;
+                }
+                let found = match get_or_fetch_query_results(&query).await {
+                    Ok(results) => results.cards.into_iter().find(|found| !found.digital),
+                    Err(e) => {
+                        tracing::debug!(query = %query, error = %e, "Token search failed");
+                        None
+                    }
+                };
+                let Some(found) = found else {
+                    continue;
+                };
+                if cards
+                    .iter()
+                    .any(|card| card.name.eq_ignore_ascii_case(&found.name))
+                {
+                    continue;
+                }
+                suggestions.push(TokenSuggestion {
+                    source: card.name.clone(),
+                    description: token.describe(),
+                    line: format_normalized_line(&found, 1, &DoubleFaceMode::FrontOnly),
+                });
+            }
+        }
+        suggestions
+    }
+
+    /// Estimate the pages, sheets and ink of the PDF of these card groups, laid out like
+    /// [`ProxyGenerator::plan_pages_from_card_groups`] without downloading anything
+    pub fn estimate_print(
+        card_groups: &[Vec<(Card, u32, DoubleFaceMode)>],
+        options: &PdfOptions,
+    ) -> PrintEstimate {
+        if options.text_only {
+            let proxies = Self::text_proxy_groups(card_groups);
+            let cards_per_page = options.cards_per_page();
+            return PrintEstimate {
+                pages: proxies
+                    .iter()
+                    .map(|group| group.len().div_ceil(cards_per_page))
+                    .sum(),
+                images: proxies.iter().map(Vec::len).sum(),
+                back_faces: 0,
+            };
+        }
+        let pages = Self::plan_pages_from_card_groups(card_groups, options);
+        let back_faces = card_groups
+            .iter()
+            .flatten()
+            .map(|(card, copies, face_mode)| {
+                let backs = card
+                    .get_images_for_face_mode(face_mode)
+                    .iter()
+                    .filter(|url| **url != card.border_crop)
+                    .count();
+                backs * *copies as usize
+            })
+            .sum();
+        PrintEstimate {
+            pages: pages.len(),
+            images: pages.iter().map(Vec::len).sum(),
+            back_faces,
+        }
+    }
+
+    /// Estimate the PDF of decklist entries like [`ProxyGenerator::estimate_print`], looking up
+    /// the printings through the search cache
+    pub async fn estimate_print_for_entries(
+        entries: &[DecklistEntry],
+        options: &PdfOptions,
+    ) -> Result<PrintEstimate, ProxyError> {
+        let (card_groups, group_labels) =
+            Self::resolve_decklist_entries_to_labeled_card_groups(entries, options).await?;
+        let options = PdfOptions {
+            group_labels,
+            ..options.clone()
+        };
+        Ok(Self::estimate_print(&card_groups, &options))
+    }
+
+    /// Estimate what generating these cards downloads: their images that aren't cached yet,
+    /// each counted once
+    pub fn estimate_downloads_for_cards<'a>(
+        cards: impl IntoIterator<Item = &'a (Card, u32, DoubleFaceMode)>,
+    ) -> DownloadEstimate {
+        let urls: std::collections::HashSet<String> = cards
+            .into_iter()
+            .flat_map(|(card, _, face_mode)| card.get_images_for_face_mode(face_mode))
+            .collect();
+        let cached = count_cached_images(urls.iter().map(String::as_str));
+        DownloadEstimate::for_images(urls.len() - cached)
+    }
+
+    /// Convert decklist entries to groups of cards, each starting on a new page
+    ///
+    /// Applies the commander placement and duplication settings from `options`.
+    pub async fn resolve_decklist_entries_to_card_groups(
+        entries: &[DecklistEntry],
+        options: &PdfOptions,
+    ) -> Result<Vec<Vec<(Card, u32, DoubleFaceMode)>>, ProxyError> {
+        let (card_groups, _) =
+            Self::resolve_decklist_entries_to_labeled_card_groups(entries, options).await?;
+        Ok(card_groups)
+    }
+
+    /// Convert decklist entries to groups of cards like
+    /// [`ProxyGenerator::resolve_decklist_entries_to_card_groups`], along with the labels of
+    /// their divider rows for [`PdfOptions::group_labels`] (empty without group dividers)
+    pub async fn resolve_decklist_entries_to_labeled_card_groups(
+        entries: &[DecklistEntry],
+        options: &PdfOptions,
+    ) -> Result<
+        (
+            Vec<Vec<(Card, u32, DoubleFaceMode)>>,
+            Vec<Vec<(usize, String)>>,
+        ),
+        ProxyError,
+    > {
+        let sections: Vec<DeckSection> = entries.iter().map(|entry| entry.section).collect();
+
+        let mut card_groups = Vec::new();
+        let mut group_labels = Vec::new();
+        for group in options.print_groups(&sections) {
+            let group_entries: Vec<DecklistEntry> = group
+                .into_iter()
+                .map(|index| {
+                    let mut entry = entries[index].clone();
+                    let copies = options.print_copies(entry.section, entry.multiple.get());
+                    if let Some(copies) = std::num::NonZeroU32::new(copies) {
+                        entry.multiple = copies;
+                    }
+                    entry
+                })
+                .collect();
+            // Labeled groups (comments like "// lands", sections) are kept together, sorted
+            // among themselves
+            let mut cards = Vec::new();
+            let mut card_labels = Vec::new();
+            for run in
+                group_entries.chunk_by(|left, right| left.group_label() == right.group_label())
+            {
+                let mut run_cards = Self::resolve_decklist_entries_to_cards(run).await?;
+                options.sort_order.sort_cards(&mut run_cards);
+                card_labels.extend(std::iter::repeat_n(run[0].group_label(), run_cards.len()));
+                cards.extend(run_cards);
+            }
+            card_groups.push(options.copy_order.arrange(cards));
+            group_labels.push(if options.shows_group_dividers() {
+                pdf::divider_labels(card_labels)
+            } else {
+                Vec::new()
+            });
+        }
+
+        if !options.filler_cards.is_empty() {
+            let fillers = Self::resolve_filler_cards(&options.filler_cards).await;
+            let labeled = PdfOptions {
+                group_labels: group_labels.clone(),
+                ..options.clone()
+            };
+            Self::fill_last_page(&mut card_groups, &fillers, &labeled);
+        }
+        Ok((card_groups, group_labels))
+    }
+
+    /// Look up the filler cards by name, using the first printing of each; names without a
+    /// match are skipped
+    pub async fn resolve_filler_cards(names: &[String]) -> Vec<Card> {
+        let mut fillers = Vec::new();
+        for name in names {
+            match Self::search_card(name).await {
+                Ok(search_result) => match search_result.cards.into_iter().next() {
+                    Some(card) => fillers.push(card),
+                    None => tracing::warn!(card_name = %name, "No printings found for filler card"),
+                },
+                Err(e) => {
+                    tracing::warn!(card_name = %name, error = %e, "Failed to look up filler card")
+                }
+            }
+        }
+        fillers
+    }
+
+    /// Add copies of `fillers` (front faces only) to the last card group so that its last page
+    /// has no empty slots
+    pub fn fill_last_page(
+        card_groups: &mut [Vec<(Card, u32, DoubleFaceMode)>],
+        fillers: &[Card],
+        options: &PdfOptions,
+    ) {
+        let Some(last_index) = card_groups.len().checked_sub(1) else {
+            return;
+        };
+        let last_group = &mut card_groups[last_index];
+        // Divider rows take slots too
+        let images = Self::expand_cards_to_image_urls(last_group).len();
+        let divider_images: Vec<usize> = Self::divider_images(last_group, options, last_index)
+            .into_iter()
+            .map(|(image_index, _)| image_index)
+            .collect();
+        let (slots, _) = options.group_slots(images, &divider_images);
+        let used_slots = slots.last().map_or(0, |slot| slot + 1);
+        for (card, copies) in fillers
+            .iter()
+            .zip(options.filler_copies(used_slots, fillers.len()))
+        {
+            if copies > 0 {
+                last_group.push((card.clone(), copies, DoubleFaceMode::FrontOnly));
+            }
+        }
+    }
+
     /// Parse decklist and start background image loading (fire and forget)
     /// This function parses the decklist, kicks off background loading for all cards,
     /// and returns immediately. Background loading happens asynchronously.
+    #[cfg(feature = "background-loading")]
     pub async fn parse_and_start_background_loading(
         decklist_text: &str,
         global_face_mode: DoubleFaceMode,
@@ -366,24 +929,12 @@ impl ProxyGenerator {
         // First parse the decklist
         let entries = Self::parse_and_resolve_decklist(decklist_text, global_face_mode).await?;
 
-        // Start background loading for all entries (fire and forget)
-        if !entries.is_empty() {
-            let entries_clone = entries.clone();
-            let entry_count = entries.len();
-            println!("About to spawn background loading task for {} entries", entry_count);
-            tokio::spawn(async move {
-                println!("Background loading task started for {} entries", entry_count);
-                let _handle = start_background_image_loading(entries_clone);
-                println!("Background loading task completed for {} entries", entry_count);
-                // We don't wait for completion - just let it run in the background
-                log::debug!(
-                    "Background image loading started for {} entries",
-                    entry_count
-                );
-            });
-            println!("tokio::spawn called successfully");
+        // Start background loading for all entries (fire and forget); the load logs its own
+        // lifecycle events and keeps running after the handle is dropped
+        if entries.is_empty() {
+            tracing::debug!("No entries to load in background");
         } else {
-            println!("No entries to load in background");
+            start_background_image_loading(entries.clone());
         }
 
         // Return parsed entries immediately
@@ -399,39 +950,196 @@ impl ProxyGenerator {
     where
         F: FnMut(usize, usize) + Send,
     {
-        let cards = Self::resolve_decklist_entries_to_cards(entries).await?;
-        Self::generate_pdf_from_cards_with_face_modes(&cards, options, progress_callback).await
+        let (card_groups, group_labels) =
+            Self::resolve_decklist_entries_to_labeled_card_groups(entries, &options).await?;
+        let options = PdfOptions {
+            group_labels,
+            ..options
+        };
+        Self::generate_pdf_from_card_groups(&card_groups, options, progress_callback).await
     }
 
     /// Generate PDF from a list of cards with per-card face mode (static method using global state)
     pub async fn generate_pdf_from_cards_with_face_modes<F>(
         cards: &[(Card, u32, DoubleFaceMode)],
         options: PdfOptions,
-        mut progress_callback: F,
+        progress_callback: F,
     ) -> Result<Vec<u8>, ProxyError>
     where
         F: FnMut(usize, usize) + Send,
     {
-        if cards.is_empty() {
+        Self::generate_pdf_from_card_groups(&[cards.to_vec()], options, progress_callback).await
+    }
+
+    /// Generate PDF from groups of cards with per-card face mode, starting each group on a new page
+    pub async fn generate_pdf_from_card_groups<F>(
+        card_groups: &[Vec<(Card, u32, DoubleFaceMode)>],
+        options: PdfOptions,
+        progress_callback: F,
+    ) -> Result<Vec<u8>, ProxyError>
+    where
+        F: FnMut(usize, usize) + Send,
+    {
+        let mut pdfs =
+            Self::generate_pdfs_from_card_groups(card_groups, options, None, progress_callback)
+                .await?;
+        Ok(pdfs.remove(0))
+    }
+
+    /// Generate PDFs directly from decklist entries, one per `pages_per_file` pages
+    pub async fn generate_pdfs_from_entries<F>(
+        entries: &[DecklistEntry],
+        options: PdfOptions,
+        pages_per_file: Option<usize>,
+        progress_callback: F,
+    ) -> Result<Vec<Vec<u8>>, ProxyError>
+    where
+        F: FnMut(usize, usize) + Send,
+    {
+        let (card_groups, group_labels) =
+            Self::resolve_decklist_entries_to_labeled_card_groups(entries, &options).await?;
+        let options = PdfOptions {
+            group_labels,
+            ..options
+        };
+        Self::generate_pdfs_from_card_groups(
+            &card_groups,
+            options,
+            pages_per_file,
+            progress_callback,
+        )
+        .await
+    }
+
+    /// Generate PDFs from groups of cards, one per `pages_per_file` pages (a single PDF if None)
+    pub async fn generate_pdfs_from_card_groups<F>(
+        card_groups: &[Vec<(Card, u32, DoubleFaceMode)>],
+        options: PdfOptions,
+        pages_per_file: Option<usize>,
+        mut progress_callback: F,
+    ) -> Result<Vec<Vec<u8>>, ProxyError>
+    where
+        F: FnMut(usize, usize) + Send,
+    {
+        let started = std::time::Instant::now();
+        let options = PdfOptions {
+            deck_hash: Some(Self::deck_hash(card_groups, &options, pages_per_file)),
+            ..options
+        };
+        let pdfs = if options.text_only {
+            if card_groups.iter().all(|cards| cards.is_empty()) {
+                return Err(ProxyError::InvalidCard("No cards to generate".to_string()));
+            }
+            let proxy_groups = Self::text_proxy_groups(card_groups);
+            let total: usize = proxy_groups.iter().map(Vec::len).sum();
+            progress_callback(total, total);
+            generate_text_pdfs_from_groups(proxy_groups, options, pages_per_file)?
+        } else {
+            let image_groups =
+                Self::fetch_captioned_image_groups(card_groups, &options, progress_callback)
+                    .await?;
+            generate_pdfs_from_groups(image_groups, options, pages_per_file)?
+        };
+
+        let cards: u64 = card_groups
+            .iter()
+            .flatten()
+            .map(|(_, quantity, _)| *quantity as u64)
+            .sum();
+        globals::record_usage(|stats| stats.record_pdf(cards, started.elapsed()));
+        if let Err(e) = globals::save_usage_stats() {
+            tracing::warn!(error = %e, "Failed to save usage statistics");
+        }
+        Ok(pdfs)
+    }
+
+    /// Render groups of cards into page images (one per page) laid out like the PDF
+    pub async fn render_pages_from_card_groups<F>(
+        card_groups: &[Vec<(Card, u32, DoubleFaceMode)>],
+        options: PdfOptions,
+        render_options: RenderOptions,
+        progress_callback: F,
+    ) -> Result<Vec<Vec<u8>>, ProxyError>
+    where
+        F: FnMut(usize, usize) + Send,
+    {
+        let image_groups =
+            Self::fetch_captioned_image_groups(card_groups, &options, progress_callback).await?;
+        render_pages_from_groups(image_groups, options, render_options)
+    }
+
+    /// One text-only proxy per copy of every card, keeping the groups apart; double-faced
+    /// cards show both faces on one proxy whatever their face mode
+    fn text_proxy_groups(card_groups: &[Vec<(Card, u32, DoubleFaceMode)>]) -> Vec<Vec<TextProxy>> {
+        card_groups
+            .iter()
+            .map(|cards| {
+                cards
+                    .iter()
+                    .flat_map(|(card, copies, _)| {
+                        std::iter::repeat_n(TextProxy::from_card(card), *copies as usize)
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Download the images of every card group, keeping the groups apart and marking the
+    /// images that follow a divider row
+    async fn fetch_captioned_image_groups<F>(
+        card_groups: &[Vec<(Card, u32, DoubleFaceMode)>],
+        options: &PdfOptions,
+        mut progress_callback: F,
+    ) -> Result<Vec<Vec<CaptionedImage>>, ProxyError>
+    where
+        F: FnMut(usize, usize) + Send,
+    {
+        if card_groups.iter().all(|cards| cards.is_empty()) {
             return Err(ProxyError::InvalidCard("No cards to generate".to_string()));
         }
 
         // Use shared expansion logic to get the exact sequence of image URLs
-        let image_urls = Self::expand_cards_to_image_urls(cards);
-        let total_images = image_urls.len();
+        let url_groups: Vec<Vec<(String, String)>> = card_groups
+            .iter()
+            .map(|cards| Self::expand_cards_to_captioned_image_urls(cards))
+            .collect();
+        let total_images: usize = url_groups.iter().map(Vec::len).sum();
+        // Images fetched early must still be cached when the PDF is assembled
+        let _pin = pin_images(
+            url_groups
+                .iter()
+                .flatten()
+                .map(|(url, _)| url.clone())
+                .collect(),
+        );
 
         // Download all images in sequence
-        let mut images = Vec::new();
-        for (current_progress, image_url) in image_urls.iter().enumerate() {
-            progress_callback(current_progress, total_images);
-            let image = get_or_fetch_image(image_url).await?;
-            images.push(image);
+        let mut image_groups = Vec::new();
+        let mut current_progress = 0;
+        for (group_index, image_urls) in url_groups.into_iter().enumerate() {
+            let mut images = Vec::new();
+            for (image_url, caption) in image_urls {
+                progress_callback(current_progress, total_images);
+                let image = get_or_fetch_image(&image_url).await?;
+                images.push(CaptionedImage {
+                    image,
+                    caption,
+                    divider: None,
+                });
+                current_progress += 1;
+            }
+            for (image_index, label) in
+                Self::divider_images(&card_groups[group_index], options, group_index)
+            {
+                if let Some(captioned) = images.get_mut(image_index) {
+                    captioned.divider = Some(label);
+                }
+            }
+            image_groups.push(images);
         }
 
         progress_callback(total_images, total_images);
-
-        // Generate PDF
-        generate_pdf(images.into_iter(), options)
+        Ok(image_groups)
     }
 
     /// Generate PDF from a list of cards (static method using global state)
@@ -555,6 +1263,18 @@ mod tests {
             language: "en".to_string(),
             border_crop: "http://example.com/test.jpg".to_string(),
             back_side: None,
+            frame: None,
+            digital: false,
+            collector_number: None,
+            prices: None,
+            legalities: Default::default(),
+            layout: None,
+            scryfall_id: None,
+            oracle_id: None,
+            mana_cost: None,
+            type_line: None,
+            oracle_text: None,
+            power_toughness: None,
         };
 
         // Test adding card
@@ -572,6 +1292,106 @@ mod tests {
         assert_eq!(generator.get_cards().len(), 0);
     }
 
+    #[test]
+    fn test_plan_pages_from_card_groups() {
+        let card = |name: &str, back: bool| Card {
+            name: name.to_string(),
+            set: "test".to_string(),
+            language: "en".to_string(),
+            border_crop: format!("http://example.com/{}.jpg", name),
+            back_side: back.then(|| scryfall::models::BackSide::DfcBack {
+                image_url: format!("http://example.com/{}-back.jpg", name),
+                name: format!("{} back", name),
+            }),
+            frame: None,
+            digital: false,
+            collector_number: None,
+            prices: None,
+            legalities: Default::default(),
+            layout: None,
+            scryfall_id: None,
+            oracle_id: None,
+            mana_cost: None,
+            type_line: None,
+            oracle_text: None,
+            power_toughness: None,
+        };
+
+        let card_groups = vec![
+            vec![(card("commander", false), 1, DoubleFaceMode::FrontOnly)],
+            vec![
+                (card("island", false), 8, DoubleFaceMode::FrontOnly),
+                (card("delver", true), 1, DoubleFaceMode::BothSides),
+            ],
+        ];
+        let pages =
+            ProxyGenerator::plan_pages_from_card_groups(&card_groups, &PdfOptions::default());
+
+        // The commander sits alone on its page, the other ten faces fill a page and a bit
+        let page_sizes: Vec<usize> = pages.iter().map(Vec::len).collect();
+        assert_eq!(page_sizes, vec![1, 9, 1]);
+        assert_eq!(pages[1][8], "http://example.com/delver.jpg");
+        assert_eq!(pages[2][0], "http://example.com/delver-back.jpg");
+
+        // Every quality resamples the same cached images, so switching it downloads nothing
+        for image_quality in ImageQuality::all() {
+            let options = PdfOptions {
+                image_quality,
+                ..PdfOptions::default()
+            };
+            assert_eq!(
+                ProxyGenerator::plan_pages_from_card_groups(&card_groups, &options),
+                pages
+            );
+        }
+
+        let estimate = ProxyGenerator::estimate_print(&card_groups, &PdfOptions::default());
+        assert_eq!(
+            estimate,
+            PrintEstimate {
+                pages: 3,
+                images: 11,
+                back_faces: 1,
+            }
+        );
+        assert_eq!(estimate.duplex_sheets(), 2);
+
+        // Text-only proxies show both faces on one card
+        let text_only = PdfOptions {
+            text_only: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            ProxyGenerator::estimate_print(&card_groups, &text_only),
+            PrintEstimate {
+                pages: 3,
+                images: 10,
+                back_faces: 0,
+            }
+        );
+
+        // The deck hash changes with the cards, their copies, the options and the files
+        let hash = ProxyGenerator::deck_hash(&card_groups, &PdfOptions::default(), None);
+        assert_eq!(
+            hash,
+            ProxyGenerator::deck_hash(&card_groups, &PdfOptions::default(), None)
+        );
+        assert_ne!(
+            hash,
+            ProxyGenerator::deck_hash(&card_groups, &text_only, None)
+        );
+        assert_ne!(
+            hash,
+            ProxyGenerator::deck_hash(&card_groups, &PdfOptions::default(), Some(2))
+        );
+        let mut more_islands = card_groups.clone();
+        more_islands[1][0].1 = 9;
+        assert_ne!(
+            hash,
+            ProxyGenerator::deck_hash(&more_islands, &PdfOptions::default(), None)
+        );
+    }
+
     #[test]
     fn test_pdf_options() {
         let options = PdfOptions::default();
@@ -634,6 +1454,18 @@ mod tests {
             language: "en".to_string(),
             border_crop: "http://example.com/test.jpg".to_string(),
             back_side: None,
+            frame: None,
+            digital: false,
+            collector_number: None,
+            prices: None,
+            legalities: Default::default(),
+            layout: None,
+            scryfall_id: None,
+            oracle_id: None,
+            mana_cost: None,
+            type_line: None,
+            oracle_text: None,
+            power_toughness: None,
         };
         generator.add_card(card, 1);
 
@@ -725,12 +1557,18 @@ mod tests {
             for (i, input) in decklist_inputs.iter().enumerate() {
                 // Simulate what the new parse_and_resolve_decklist does
                 let mut entry = DecklistEntry {
-                    multiple: 1,
+                    multiple: std::num::NonZeroU32::MIN,
                     name: input.to_string(),
                     set: None,
                     lang: None,
                     face_mode: DoubleFaceMode::BothSides, // Default before resolution
                     source_line_number: Some(i),
+                    section: DeckSection::Main,
+                    foil: false,
+                    tags: Vec::new(),
+                    scryfall_id: None,
+                    collector_number: None,
+                    annotation: None,
                 };
 
                 // Apply the same logic as in the updated parse_and_resolve_decklist
@@ -0,0 +1,161 @@
+//! Logging setup shared by the frontends
+//!
+//! Core logs through `tracing` with the module path as target, so subsystems can be filtered
+//! separately with `RUST_LOG`, e.g. `localhawk_core::background_loading=debug`,
+//! `localhawk_core::cache=debug`, `localhawk_core::scryfall=debug` or `localhawk_core::pdf=debug`.
+//! Records of crates still using `log` are forwarded to the same output.
+//!
+//! Optionally everything is also written to daily log files in the cache directory, which
+//! [`export_logs`] bundles into a single file to attach to bug reports.
+
+use crate::error::ProxyError;
+use crate::globals::get_log_directory_path;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::EnvFilter;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+
+/// Filter used when RUST_LOG is not set
+pub const DEFAULT_LOG_FILTER: &str =
+    "localhawk_core=info,localhawk_gui=info,localhawk_cli=info,localhawk_server=info";
+
+const LOG_FILE_PREFIX: &str = "localhawk";
+const LOG_FILE_SUFFIX: &str = "log";
+/// Daily log files kept before the oldest is deleted
+const MAX_LOG_FILES: usize = 7;
+
+/// Log to stderr and, if `log_to_file`, to daily log files in the log directory
+///
+/// `default_filter` applies when RUST_LOG is not set. Does nothing if logging was already set
+/// up, so frontends may call it more than once.
+pub fn init_logging(default_filter: &str, log_to_file: bool) {
+    let filter =
+        EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(default_filter));
+    let stderr_layer = tracing_subscriber::fmt::layer().with_writer(std::io::stderr);
+
+    let log_directory = get_log_directory_path();
+    let file_layer = if log_to_file {
+        match log_file_appender(Path::new(&log_directory)) {
+            Ok(appender) => Some(
+                tracing_subscriber::fmt::layer()
+                    .with_ansi(false)
+                    .with_writer(appender),
+            ),
+            Err(e) => {
+                eprintln!("Failed to open log file in {}: {}", log_directory, e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+    let writes_file = file_layer.is_some();
+
+    if tracing_subscriber::registry()
+        .with(filter)
+        .with(stderr_layer)
+        .with(file_layer)
+        .try_init()
+        .is_ok()
+        && writes_file
+    {
+        tracing::info!(directory = %log_directory, "Writing log files");
+    }
+}
+
+fn log_file_appender(log_directory: &Path) -> Result<RollingFileAppender, String> {
+    RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix(LOG_FILE_PREFIX)
+        .filename_suffix(LOG_FILE_SUFFIX)
+        .max_log_files(MAX_LOG_FILES)
+        .build(log_directory)
+        .map_err(|e| e.to_string())
+}
+
+/// Write all log files, oldest first, into a single file at `destination` for a bug report
+///
+/// Returns the number of log files included; the report names the version and platform even
+/// if there are none.
+pub fn export_logs(destination: &Path) -> Result<usize, ProxyError> {
+    export_logs_from(Path::new(&get_log_directory_path()), destination)
+}
+
+fn export_logs_from(log_directory: &Path, destination: &Path) -> Result<usize, ProxyError> {
+    let log_files = log_files_in(log_directory);
+    let mut report = format!(
+        "LocalHawk {} on {}/{}\n",
+        env!("CARGO_PKG_VERSION"),
+        std::env::consts::OS,
+        std::env::consts::ARCH
+    );
+    if log_files.is_empty() {
+        report.push_str("No log files found (writing log files is off in the settings)\n");
+    }
+    for path in &log_files {
+        let content = fs::read(path).map_err(ProxyError::Io)?;
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        report.push_str(&format!("\n===== {} =====\n", name));
+        report.push_str(&String::from_utf8_lossy(&content));
+    }
+
+    fs::write(destination, report).map_err(ProxyError::Io)?;
+    Ok(log_files.len())
+}
+
+fn log_files_in(log_directory: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(log_directory) else {
+        return Vec::new();
+    };
+    let mut log_files: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| {
+                    name.starts_with(LOG_FILE_PREFIX) && name.ends_with(LOG_FILE_SUFFIX)
+                })
+        })
+        .collect();
+    // Files are named like localhawk.2024-05-01.log, so sorting by name sorts by date
+    log_files.sort();
+    log_files
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    #[test]
+    fn test_export_logs_joins_files_oldest_first() {
+        let log_directory =
+            env::temp_dir().join(format!("localhawk-logs-test-{}", std::process::id()));
+        fs::create_dir_all(&log_directory).unwrap();
+        fs::write(
+            log_directory.join("localhawk.2024-05-02.log"),
+            "second day\n",
+        )
+        .unwrap();
+        fs::write(
+            log_directory.join("localhawk.2024-05-01.log"),
+            "first day\n",
+        )
+        .unwrap();
+        fs::write(log_directory.join("settings.json"), "{}").unwrap();
+        let destination = log_directory.join("report.txt");
+
+        let exported = export_logs_from(&log_directory, &destination).unwrap();
+        let report = fs::read_to_string(&destination).unwrap();
+        fs::remove_dir_all(&log_directory).unwrap();
+
+        assert_eq!(exported, 2);
+        assert!(report.starts_with("LocalHawk "));
+        let first = report.find("first day").unwrap();
+        let second = report.find("second day").unwrap();
+        assert!(first < second);
+        assert!(!report.contains("{}"));
+    }
+}
@@ -1,9 +1,11 @@
-use log::debug;
-use ngrammatic::{Corpus, CorpusBuilder};
-use ord_subset::OrdVar;
-use std::collections::HashMap;
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use tracing::debug;
 
-#[derive(Debug, Eq, PartialEq, Hash, Clone, Copy, PartialOrd, Ord)]
+#[derive(Debug, Eq, PartialEq, Hash, Clone, Copy, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum NameMatchMode {
     Full,
     Part(usize),
@@ -15,98 +17,355 @@ pub struct NameLookupResult {
     pub hit: NameMatchMode,
 }
 
-#[derive(Debug, Clone, PartialOrd, Ord, Eq, PartialEq)]
-struct CorpusLookupResult {
-    similarity: OrdVar<f32>,
-    name: String,
-}
-
-#[derive(Debug)]
-struct CardCorpus {
-    corpus: Corpus,
-    to_full: HashMap<String, String>,
-}
+type Trigram = [char; 3];
 
-impl CardCorpus {
-    const THRESHOLD: f32 = 0.25;
-
-    fn new() -> CardCorpus {
-        CardCorpus {
-            corpus: CorpusBuilder::new().finish(),
-            to_full: HashMap::new(),
-        }
+/// Write the separator between the faces of a split or double-faced card the way Scryfall does,
+/// e.g. "Fire / Ice", "Fire/Ice" and "Fire & Ice" all become "Fire // Ice"
+pub fn normalize_name_separators(name: &str) -> String {
+    lazy_static! {
+        static ref SEPARATOR: Regex = Regex::new(r"\s*/+\s*|\s+&\s+").unwrap();
     }
+    SEPARATOR.replace_all(name.trim(), " // ").into_owned()
+}
 
-    pub fn insert(&mut self, partial_name: &str, full_name: &str) {
-        self.corpus.add_text(partial_name);
-        if partial_name != full_name {
-            self.to_full
-                .insert(partial_name.to_string(), full_name.to_string());
-        }
-    }
+/// Something a name can be looked up by: a full name, or one face of a split or double-faced card
+#[derive(Debug, Serialize, Deserialize)]
+struct NameKey {
+    text: String,
+    /// Index of the full name in `CardNameLookup::names`
+    name: usize,
+    mode: NameMatchMode,
+    /// Number of distinct padded trigrams of `text`
+    trigram_count: usize,
+}
 
-    pub fn find(&self, name: &str) -> Option<CorpusLookupResult> {
-        let n = self
-            .corpus
-            .search(name, CardCorpus::THRESHOLD)
-            .into_iter()
-            .next()?;
-        Some(CorpusLookupResult {
-            name: self.to_full.get(n.text.as_str()).unwrap_or(&n.text).clone(),
-            similarity: OrdVar::new_checked(n.similarity)?,
-        })
-    }
+/// How well a key matched a query, best first when sorted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum MatchKind {
+    Exact,
+    Prefix,
+    Substring,
+    /// Within the edit distance allowed for the query's length
+    Typo(usize),
 }
 
-#[derive(Debug)]
+/// Card names indexed by their trigrams, for fuzzy, prefix and substring lookups
+///
+/// Serializable, so the index can be saved next to the card names instead of being rebuilt at
+/// every startup (see `CardNameCache::load_lookup`).
+#[derive(Debug, Serialize, Deserialize)]
 pub struct CardNameLookup {
-    corpora: HashMap<NameMatchMode, CardCorpus>,
+    /// Full names, lowercase
+    names: Vec<String>,
+    keys: Vec<NameKey>,
+    /// Keys with each padded trigram
+    #[serde(with = "trigram_entries")]
+    trigram_index: HashMap<Trigram, Vec<usize>>,
+    /// Keys by their exact text
+    exact_index: HashMap<String, Vec<usize>>,
+    /// Keys sorted by text, for prefix queries
+    sorted_keys: Vec<usize>,
 }
 
 impl CardNameLookup {
-    fn new() -> CardNameLookup {
-        CardNameLookup {
-            corpora: HashMap::new(),
-        }
-    }
+    /// Minimum trigram similarity of a fuzzy match
+    const THRESHOLD: f32 = 0.25;
+    /// Boosts the similarity of short names, which share few trigrams even when they're close
+    const WARP: i32 = 2;
+    /// Candidates from the trigram index whose edit distance is checked by `suggest`
+    const TYPO_CANDIDATES: usize = 50;
 
     pub fn from_card_names(names: &[String]) -> CardNameLookup {
-        let mut lookup = CardNameLookup::new();
+        let mut lookup = CardNameLookup {
+            names: Vec::with_capacity(names.len()),
+            keys: Vec::with_capacity(names.len()),
+            trigram_index: HashMap::new(),
+            exact_index: HashMap::new(),
+            sorted_keys: Vec::new(),
+        };
         for name in names.iter() {
             lookup.insert(name);
         }
+        lookup.sorted_keys = (0..lookup.keys.len()).collect();
+        lookup
+            .sorted_keys
+            .sort_by(|&left, &right| lookup.keys[left].text.cmp(&lookup.keys[right].text));
         lookup
     }
 
     fn insert(&mut self, name_uppercase: &str) {
         let name = name_uppercase.to_lowercase();
-        self.corpora
-            .entry(NameMatchMode::Full)
-            .or_insert_with(CardCorpus::new)
-            .insert(&name, &name);
-
+        let name_index = self.names.len();
         if name.contains("//") {
-            for (i, partial_name) in name.split("//").map(|s| s.trim()).enumerate() {
-                self.corpora
-                    .entry(NameMatchMode::Part(i))
-                    .or_insert_with(CardCorpus::new)
-                    .insert(partial_name, &name);
+            let parts: Vec<String> = name.split("//").map(|s| s.trim().to_string()).collect();
+            for (i, partial_name) in parts.into_iter().enumerate() {
+                self.insert_key(partial_name, name_index, NameMatchMode::Part(i));
             }
         }
+        // Names like "Minsc & Boo" are indexed the way queries are normalized
+        self.insert_key(
+            normalize_name_separators(&name),
+            name_index,
+            NameMatchMode::Full,
+        );
+        self.names.push(name);
+    }
+
+    fn insert_key(&mut self, text: String, name: usize, mode: NameMatchMode) {
+        let key_index = self.keys.len();
+        let trigrams = padded_trigrams(&text);
+        for trigram in &trigrams {
+            self.trigram_index
+                .entry(*trigram)
+                .or_default()
+                .push(key_index);
+        }
+        self.exact_index
+            .entry(text.clone())
+            .or_default()
+            .push(key_index);
+        self.keys.push(NameKey {
+            text,
+            name,
+            mode,
+            trigram_count: trigrams.len(),
+        });
     }
 
+    /// Best match for a (possibly misspelled) name: an exact match if there is one, otherwise
+    /// the most similar name or face
     pub fn find(&self, name_uppercase: &str) -> Option<NameLookupResult> {
-        let name = name_uppercase.to_lowercase();
-        let best_match = self
-            .corpora
+        let name = normalize_name_separators(&name_uppercase.to_lowercase());
+        if let Some(&key_index) = self
+            .exact_index
+            .get(&name)
+            .and_then(|keys| keys.iter().min_by_key(|&&key| self.keys[key].mode))
+        {
+            return Some(self.result(key_index));
+        }
+
+        let (key_index, similarity) = self.similar_keys(&name).into_iter().next()?;
+        debug!("similarity of best match: {:?}", similarity);
+        Some(self.result(key_index))
+    }
+
+    /// Up to `limit` names for what was typed so far, best first: exact matches, names starting
+    /// with `query`, names containing it, then names within a few typos of it
+    ///
+    /// Every card is suggested once, by whichever of its faces matched best.
+    pub fn suggest(&self, query: &str, limit: usize) -> Vec<NameLookupResult> {
+        let query = normalize_name_separators(&query.to_lowercase());
+        if query.is_empty() || limit == 0 {
+            return Vec::new();
+        }
+
+        let mut matches: Vec<(MatchKind, usize)> = Vec::new();
+        let prefix_start = self
+            .sorted_keys
+            .partition_point(|&key| self.keys[key].text.as_str() < query.as_str());
+        for &key_index in &self.sorted_keys[prefix_start..] {
+            let text = &self.keys[key_index].text;
+            if !text.starts_with(&query) {
+                break;
+            }
+            let kind = if *text == query {
+                MatchKind::Exact
+            } else {
+                MatchKind::Prefix
+            };
+            matches.push((kind, key_index));
+        }
+        for key_index in self.keys_containing(&query) {
+            matches.push((MatchKind::Substring, key_index));
+        }
+        let max_distance = max_edit_distance(&query);
+        for (key_index, _) in self
+            .similar_keys(&query)
+            .into_iter()
+            .take(Self::TYPO_CANDIDATES)
+        {
+            if let Some(distance) =
+                bounded_edit_distance(&query, &self.keys[key_index].text, max_distance)
+            {
+                matches.push((MatchKind::Typo(distance), key_index));
+            }
+        }
+
+        // Full names before faces, then shorter and alphabetical names
+        matches.sort_by(|(left_kind, left), (right_kind, right)| {
+            let (left, right) = (&self.keys[*left], &self.keys[*right]);
+            left_kind
+                .cmp(right_kind)
+                .then(left.mode.cmp(&right.mode))
+                .then(left.text.len().cmp(&right.text.len()))
+                .then(left.text.cmp(&right.text))
+        });
+        let mut suggested = HashSet::new();
+        matches
+            .into_iter()
+            .filter(|(_, key_index)| suggested.insert(self.keys[*key_index].name))
+            .take(limit)
+            .map(|(_, key_index)| self.result(key_index))
+            .collect()
+    }
+
+    fn result(&self, key_index: usize) -> NameLookupResult {
+        let key = &self.keys[key_index];
+        NameLookupResult {
+            name: self.names[key.name].clone(),
+            hit: key.mode,
+        }
+    }
+
+    /// Keys at least `THRESHOLD` similar to `query`, most similar first
+    fn similar_keys(&self, query: &str) -> Vec<(usize, f32)> {
+        let query_trigrams = padded_trigrams(query);
+        let mut shared: HashMap<usize, usize> = HashMap::new();
+        for trigram in &query_trigrams {
+            for &key_index in self.trigram_index.get(trigram).into_iter().flatten() {
+                *shared.entry(key_index).or_default() += 1;
+            }
+        }
+
+        let mut similar: Vec<(usize, f32)> = shared
+            .into_iter()
+            .map(|(key_index, shared)| {
+                let union = query_trigrams.len() + self.keys[key_index].trigram_count - shared;
+                (key_index, warped_similarity(shared, union))
+            })
+            .filter(|(_, similarity)| *similarity >= Self::THRESHOLD)
+            .collect();
+        similar.sort_by(|(left, left_similarity), (right, right_similarity)| {
+            right_similarity
+                .total_cmp(left_similarity)
+                .then_with(|| self.tie_break(*left, *right))
+        });
+        similar
+    }
+
+    /// Full names before faces, then in catalog order
+    fn tie_break(&self, left: usize, right: usize) -> Ordering {
+        self.keys[left]
+            .mode
+            .cmp(&self.keys[right].mode)
+            .then(left.cmp(&right))
+    }
+
+    /// Keys that contain `query` somewhere other than at their start
+    fn keys_containing(&self, query: &str) -> Vec<usize> {
+        let chars: Vec<char> = query.chars().collect();
+        // Shorter queries match too many names to be useful
+        if chars.len() < 3 {
+            return Vec::new();
+        }
+        let mut trigrams: Vec<Trigram> = chars
+            .windows(3)
+            .map(|window| [window[0], window[1], window[2]])
+            .collect();
+        trigrams.sort();
+        trigrams.dedup();
+
+        // Every trigram of the query is in the keys containing it; start with the rarest
+        let mut postings = Vec::new();
+        for trigram in &trigrams {
+            match self.trigram_index.get(trigram) {
+                Some(keys) => postings.push(keys),
+                None => return Vec::new(),
+            }
+        }
+        postings.sort_by_key(|keys| keys.len());
+        let rest: Vec<HashSet<usize>> = postings[1..]
+            .iter()
+            .map(|keys| keys.iter().copied().collect())
+            .collect();
+        postings[0]
             .iter()
-            .filter_map(|(mode, c)| Some((c.find(&name)?, *mode)))
-            .max_by(|(leftres, _), (rightres, _)| leftres.similarity.cmp(&rightres.similarity))?;
-        debug!("similarity of best match: {:?}", best_match.0.similarity);
-        Some(NameLookupResult {
-            name: best_match.0.name.clone(),
-            hit: best_match.1,
-        })
+            .copied()
+            .filter(|key_index| rest.iter().all(|keys| keys.contains(key_index)))
+            .filter(|&key_index| {
+                let text = &self.keys[key_index].text;
+                text.contains(query) && !text.starts_with(query)
+            })
+            .collect()
+    }
+}
+
+/// Distinct trigrams of `text` padded with two spaces on each side, so that the start and end
+/// of short names count as well
+fn padded_trigrams(text: &str) -> Vec<Trigram> {
+    let padded: Vec<char> = "  "
+        .chars()
+        .chain(text.chars())
+        .chain("  ".chars())
+        .collect();
+    let mut trigrams: Vec<Trigram> = padded
+        .windows(3)
+        .map(|window| [window[0], window[1], window[2]])
+        .collect();
+    trigrams.sort();
+    trigrams.dedup();
+    trigrams
+}
+
+/// Share of trigrams two texts have in common, warped to favor partial matches of short texts
+fn warped_similarity(shared: usize, union: usize) -> f32 {
+    if union == 0 {
+        return 0.0;
+    }
+    let union = union as f32;
+    let different = union - shared as f32;
+    (union.powi(CardNameLookup::WARP) - different.powi(CardNameLookup::WARP))
+        / union.powi(CardNameLookup::WARP)
+}
+
+/// Typos allowed in a query: one per four characters, at least one and at most three
+fn max_edit_distance(query: &str) -> usize {
+    (query.chars().count() / 4).clamp(1, 3)
+}
+
+/// Levenshtein distance between `left` and `right`, or None if it exceeds `max_distance`
+fn bounded_edit_distance(left: &str, right: &str, max_distance: usize) -> Option<usize> {
+    let left: Vec<char> = left.chars().collect();
+    let right: Vec<char> = right.chars().collect();
+    if left.len().abs_diff(right.len()) > max_distance {
+        return None;
+    }
+
+    let mut previous: Vec<usize> = (0..=right.len()).collect();
+    let mut current = vec![0; right.len() + 1];
+    for (i, left_char) in left.iter().enumerate() {
+        current[0] = i + 1;
+        for (j, right_char) in right.iter().enumerate() {
+            let substitution = previous[j] + usize::from(left_char != right_char);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        // Distances never shrink from one row to the next
+        if current.iter().all(|&distance| distance > max_distance) {
+            return None;
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    Some(previous[right.len()]).filter(|&distance| distance <= max_distance)
+}
+
+/// JSON map keys have to be strings, so the trigram index is saved as a list of entries
+mod trigram_entries {
+    use super::Trigram;
+    use serde::{Deserialize, Deserializer, Serializer};
+    use std::collections::HashMap;
+
+    pub fn serialize<S: Serializer>(
+        index: &HashMap<Trigram, Vec<usize>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(index)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<HashMap<Trigram, Vec<usize>>, D::Error> {
+        let entries = Vec::<(Trigram, Vec<usize>)>::deserialize(deserializer)?;
+        Ok(entries.into_iter().collect())
     }
 }
 
@@ -150,4 +409,103 @@ mod tests {
             })
         );
     }
+
+    #[test]
+    fn name_separator_variants() {
+        let card_names: Vec<String> = vec![
+            "Incubation // Incongruity".to_string(),
+            "Minsc & Boo, Timeless Heroes".to_string(),
+        ];
+        let lookup = CardNameLookup::from_card_names(&card_names);
+        for query in [
+            "Incubation/Incongruity",
+            "incubation / incongruity",
+            "Incubation // Incongruity",
+            "Incubation & Incongruity",
+        ] {
+            assert_eq!(
+                lookup.find(query),
+                Some(NameLookupResult {
+                    name: "incubation // incongruity".to_string(),
+                    hit: NameMatchMode::Full
+                }),
+                "{}",
+                query
+            );
+        }
+        // Ampersands that are part of the name still find the card
+        assert_eq!(
+            lookup.find("Minsc & Boo, Timeless Heroes"),
+            Some(NameLookupResult {
+                name: "minsc & boo, timeless heroes".to_string(),
+                hit: NameMatchMode::Full
+            })
+        );
+        assert_eq!(normalize_name_separators(" Fire/Ice "), "Fire // Ice");
+        assert_eq!(
+            normalize_name_separators("R&D's Secret Lair"),
+            "R&D's Secret Lair"
+        );
+    }
+
+    #[test]
+    fn name_suggestions() {
+        let card_names: Vec<String> = [
+            "Lightning Bolt",
+            "Lightning Helix",
+            "Chain Lightning",
+            "Fire // Ice",
+            "Ice Cauldron",
+        ]
+        .iter()
+        .map(|name| name.to_string())
+        .collect();
+        let lookup = CardNameLookup::from_card_names(&card_names);
+        let names = |query: &str| -> Vec<String> {
+            lookup
+                .suggest(query, 10)
+                .into_iter()
+                .map(|result| result.name)
+                .collect()
+        };
+
+        // Prefix matches before substring matches
+        assert_eq!(
+            names("lightning"),
+            vec!["lightning bolt", "lightning helix", "chain lightning"]
+        );
+        // The exact face first, and every card only once
+        assert_eq!(names("ice"), vec!["fire // ice", "ice cauldron"]);
+        assert_eq!(
+            lookup.suggest("ice", 1),
+            vec![NameLookupResult {
+                name: "fire // ice".to_string(),
+                hit: NameMatchMode::Part(1)
+            }]
+        );
+        // Typos within the allowed edit distance
+        assert_eq!(names("lightnig bolt"), vec!["lightning bolt"]);
+        assert!(names("xyz").is_empty());
+
+        assert_eq!(bounded_edit_distance("bolt", "bolt", 1), Some(0));
+        assert_eq!(bounded_edit_distance("bolt", "blot", 2), Some(2));
+        assert_eq!(bounded_edit_distance("bolt", "helix", 2), None);
+    }
+
+    #[test]
+    fn saved_lookup_finds_the_same_names() {
+        let card_names: Vec<String> = vec![
+            "Okaun, Eye of Chaos".to_string(),
+            "Cut // Ribbons".to_string(),
+            "Lightning Bolt".to_string(),
+        ];
+        let lookup = CardNameLookup::from_card_names(&card_names);
+        let saved = serde_json::to_string(&lookup).unwrap();
+        let loaded: CardNameLookup = serde_json::from_str(&saved).unwrap();
+
+        for query in ["okaun", "ribbon", "lightnig bolt", "cut // ribbons"] {
+            assert_eq!(loaded.find(query), lookup.find(query));
+            assert_eq!(loaded.suggest(query, 5), lookup.suggest(query, 5));
+        }
+    }
 }
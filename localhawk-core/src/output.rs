@@ -0,0 +1,315 @@
+//! Where generated PDFs are written: file names from a template, what happens to existing
+//! files, and splitting large decks into several files for printers with small spool limits
+
+use crate::decklist::deck_file_name;
+use crate::error::ProxyError;
+use crate::hooks::{PostGenerationHook, save_pdf};
+use crate::pdf::pdf_deck_hash;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use time::OffsetDateTime;
+use tracing::info;
+
+/// File name template used unless configured otherwise, e.g. "Mono Red.pdf"
+pub const DEFAULT_FILE_NAME_TEMPLATE: &str = "{deck}";
+
+/// Stands in for the deck name when the decklist has none
+const UNNAMED_DECK: &str = "proxies";
+
+/// What to do when a PDF would be written over an existing file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum OverwritePolicy {
+    /// Replace the existing file
+    #[default]
+    Overwrite,
+    /// Keep the existing file and add the current time to the new file's name
+    Timestamp,
+}
+
+impl OverwritePolicy {
+    pub fn all() -> Vec<OverwritePolicy> {
+        vec![OverwritePolicy::Overwrite, OverwritePolicy::Timestamp]
+    }
+}
+
+impl std::fmt::Display for OverwritePolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OverwritePolicy::Overwrite => write!(f, "Overwrite"),
+            OverwritePolicy::Timestamp => write!(f, "Add timestamp"),
+        }
+    }
+}
+
+/// How generated PDFs are named and written, shared by the frontends' save flows
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OutputOptions {
+    /// File name without extension; `{deck}` is the deck name, `{date}` today's date
+    /// (YYYY-MM-DD) and `{part}` the number of the file when splitting
+    pub file_name_template: String,
+    pub overwrite: OverwritePolicy,
+    /// Start a new file after this many pages (None = everything in one file)
+    pub pages_per_file: Option<u32>,
+}
+
+impl Default for OutputOptions {
+    fn default() -> Self {
+        OutputOptions {
+            file_name_template: DEFAULT_FILE_NAME_TEMPLATE.to_string(),
+            overwrite: OverwritePolicy::default(),
+            pages_per_file: None,
+        }
+    }
+}
+
+impl OutputOptions {
+    /// Pages per file to pass to PDF generation, None if output isn't split
+    pub fn split_pages(&self) -> Option<usize> {
+        self.pages_per_file
+            .filter(|pages| *pages > 0)
+            .map(|pages| pages as usize)
+    }
+
+    /// Number of files a PDF of `pages` pages is written as
+    pub fn parts(&self, pages: usize) -> usize {
+        self.split_pages()
+            .map_or(1, |pages_per_file| pages.div_ceil(pages_per_file).max(1))
+    }
+
+    /// Same options, but writing to the file name chosen in a save dialog or on the command
+    /// line; returns the directory to write to along with them
+    pub fn for_path(&self, path: &Path) -> (PathBuf, OutputOptions) {
+        let directory = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|| PathBuf::from("."));
+        let file_name_template = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_else(|| self.file_name_template.clone());
+        let options = OutputOptions {
+            file_name_template,
+            ..self.clone()
+        };
+        (directory, options)
+    }
+
+    /// File name for part `part` (1-based) of `parts`, with the placeholders filled in
+    ///
+    /// When output is split but the template has no `{part}`, the part number is appended so
+    /// the files don't overwrite each other.
+    pub fn file_name(&self, deck_name: Option<&str>, part: usize, parts: usize) -> String {
+        self.file_name_at(deck_name, part, parts, now())
+    }
+
+    fn file_name_at(
+        &self,
+        deck_name: Option<&str>,
+        part: usize,
+        parts: usize,
+        now: OffsetDateTime,
+    ) -> String {
+        let mut stem = self
+            .file_name_template
+            .replace("{deck}", deck_name.unwrap_or(UNNAMED_DECK))
+            .replace("{date}", &date_label(now))
+            .replace("{part}", &part.to_string());
+        if parts > 1 && !self.file_name_template.contains("{part}") {
+            stem.push_str(&format!("-{}", part));
+        }
+        deck_file_name(&stem).unwrap_or_else(|| format!("{}.pdf", UNNAMED_DECK))
+    }
+
+    /// Path to write part `part` of `parts` to, applying the overwrite policy
+    pub fn output_path(
+        &self,
+        directory: &Path,
+        deck_name: Option<&str>,
+        part: usize,
+        parts: usize,
+    ) -> PathBuf {
+        let now = now();
+        let path = directory.join(self.file_name_at(deck_name, part, parts, now));
+        match self.overwrite {
+            OverwritePolicy::Timestamp if path.exists() => timestamped(&path, now),
+            _ => path,
+        }
+    }
+}
+
+/// Write generated PDFs (one per part) to `directory`, running the hooks for each file
+///
+/// Returns the paths written, in order.
+pub fn write_pdfs(
+    pdfs: &[Vec<u8>],
+    directory: &Path,
+    deck_name: Option<&str>,
+    options: &OutputOptions,
+    hooks: &[PostGenerationHook],
+) -> Result<Vec<PathBuf>, ProxyError> {
+    std::fs::create_dir_all(directory)?;
+
+    let mut paths = Vec::new();
+    for (index, pdf) in pdfs.iter().enumerate() {
+        let path = options.output_path(directory, deck_name, index + 1, pdfs.len());
+        save_pdf(pdf, &path, hooks)?;
+        info!(path = %path.display(), bytes = pdf.len(), "Wrote PDF");
+        paths.push(path);
+    }
+    Ok(paths)
+}
+
+/// PDFs written earlier for the same deck: the files [`write_pdfs`] would write `parts` PDFs
+/// to, if every one of them embeds `deck_hash` (see [`crate::ProxyGenerator::deck_hash`])
+///
+/// Generating the deck again would give the same PDFs, so these can be kept instead.
+pub fn existing_pdfs(
+    directory: &Path,
+    deck_name: Option<&str>,
+    options: &OutputOptions,
+    parts: usize,
+    deck_hash: &str,
+) -> Option<Vec<PathBuf>> {
+    (1..=parts)
+        .map(|part| {
+            let path = directory.join(options.file_name(deck_name, part, parts));
+            let pdf = std::fs::read(&path).ok()?;
+            (pdf_deck_hash(&pdf)? == deck_hash).then_some(path)
+        })
+        .collect()
+}
+
+fn now() -> OffsetDateTime {
+    // Falls back to UTC where the local offset can't be determined
+    OffsetDateTime::now_local().unwrap_or_else(|_| OffsetDateTime::now_utc())
+}
+
+fn date_label(now: OffsetDateTime) -> String {
+    format!(
+        "{:04}-{:02}-{:02}",
+        now.year(),
+        now.month() as u8,
+        now.day()
+    )
+}
+
+/// `path` with the date and time added before the extension, e.g. "deck-20240501-143005.pdf"
+fn timestamped(path: &Path, now: OffsetDateTime) -> PathBuf {
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let timestamp = format!(
+        "{:04}{:02}{:02}-{:02}{:02}{:02}",
+        now.year(),
+        now.month() as u8,
+        now.day(),
+        now.hour(),
+        now.minute(),
+        now.second()
+    );
+    path.with_file_name(format!("{}-{}.pdf", stem, timestamp))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    #[test]
+    fn test_file_name_template() {
+        // 2024-05-01 14:30:05 UTC
+        let now = OffsetDateTime::from_unix_timestamp(1_714_573_805).unwrap();
+        let options = OutputOptions {
+            file_name_template: "{deck}-{date}".to_string(),
+            ..OutputOptions::default()
+        };
+        assert_eq!(
+            options.file_name_at(Some("Mono Red"), 1, 1, now),
+            "Mono Red-2024-05-01.pdf"
+        );
+        assert_eq!(
+            options.file_name_at(None, 2, 3, now),
+            "proxies-2024-05-01-2.pdf"
+        );
+
+        let options = OutputOptions {
+            file_name_template: "{deck} part {part}".to_string(),
+            ..OutputOptions::default()
+        };
+        assert_eq!(
+            options.file_name_at(Some("Elves: Legacy"), 2, 2, now),
+            "Elves_ Legacy part 2.pdf"
+        );
+    }
+
+    #[test]
+    fn test_write_pdfs_splits_and_keeps_existing_files() {
+        let directory =
+            env::temp_dir().join(format!("localhawk-output-test-{}", std::process::id()));
+        let options = OutputOptions {
+            overwrite: OverwritePolicy::Timestamp,
+            ..OutputOptions::default()
+        };
+        let pdfs = vec![b"%PDF-1".to_vec(), b"%PDF-2".to_vec()];
+
+        let paths = write_pdfs(&pdfs, &directory, Some("Deck"), &options, &[]).unwrap();
+        assert_eq!(
+            paths,
+            vec![directory.join("Deck-1.pdf"), directory.join("Deck-2.pdf")]
+        );
+
+        let first = write_pdfs(&pdfs[..1], &directory, Some("Deck"), &options, &[]).unwrap();
+        assert_eq!(first, vec![directory.join("Deck.pdf")]);
+        let second = write_pdfs(&pdfs[1..], &directory, Some("Deck"), &options, &[]).unwrap();
+        assert_ne!(second, first);
+        assert_eq!(std::fs::read(&first[0]).unwrap(), b"%PDF-1");
+        assert_eq!(std::fs::read(&second[0]).unwrap(), b"%PDF-2");
+
+        std::fs::remove_dir_all(&directory).ok();
+    }
+
+    #[test]
+    fn test_existing_pdfs() {
+        let directory =
+            env::temp_dir().join(format!("localhawk-existing-test-{}", std::process::id()));
+        let options = OutputOptions {
+            pages_per_file: Some(2),
+            ..OutputOptions::default()
+        };
+        assert_eq!(options.parts(3), 2);
+        assert_eq!(OutputOptions::default().parts(3), 1);
+
+        let pdf = |hash: &str| {
+            let options = crate::PdfOptions {
+                deck_hash: Some(hash.to_string()),
+                ..Default::default()
+            };
+            let image = printpdf::image_crate::DynamicImage::new_rgb8(10, 14);
+            crate::generate_pdf(std::iter::once(image), options).unwrap()
+        };
+        let pdfs = vec![pdf("same"), pdf("same")];
+        write_pdfs(&pdfs, &directory, Some("Deck"), &options, &[]).unwrap();
+
+        assert_eq!(
+            existing_pdfs(&directory, Some("Deck"), &options, 2, "same"),
+            Some(vec![
+                directory.join("Deck-1.pdf"),
+                directory.join("Deck-2.pdf")
+            ])
+        );
+        assert_eq!(
+            existing_pdfs(&directory, Some("Deck"), &options, 2, "other"),
+            None
+        );
+        // A missing part means generating again
+        assert_eq!(
+            existing_pdfs(&directory, Some("Deck"), &options, 3, "same"),
+            None
+        );
+
+        std::fs::remove_dir_all(&directory).ok();
+    }
+}
@@ -19,7 +19,7 @@ impl<T> PaginatedView<T> {
         if self.items.is_empty() {
             1
         } else {
-            (self.items.len() + self.items_per_page - 1) / self.items_per_page
+            self.items.len().div_ceil(self.items_per_page.max(1))
         }
     }
 
@@ -83,7 +83,7 @@ impl PaginatedGrid {
         if self.total_items == 0 {
             1
         } else {
-            (self.total_items + self.items_per_page - 1) / self.items_per_page
+            self.total_items.div_ceil(self.items_per_page.max(1))
         }
     }
 
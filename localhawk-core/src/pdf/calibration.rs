@@ -0,0 +1,220 @@
+//! Calibration page for printers that scale pages slightly, so that printed cards still fit
+//! their sleeves
+//!
+//! The page is printed with the current scale correction; measuring its ruler gives the
+//! correction to store in [`PdfOptions::scale_correction`].
+
+use super::{CUT_MARKER_THICKNESS, PdfOptions, cut_marker_line};
+use crate::error::ProxyError;
+use printpdf::{BuiltinFont, Color, IndirectFontRef, Mm, PdfDocument, PdfLayerReference, Rgb};
+
+/// Length of the calibration ruler when printed without any scaling
+pub const CALIBRATION_LENGTH_MM: f32 = 100.0;
+/// Printers that are off by more than this are misconfigured rather than slightly scaling
+pub const MIN_SCALE_CORRECTION: f32 = 0.9;
+pub const MAX_SCALE_CORRECTION: f32 = 1.1;
+
+const PAGE_MARGIN_MM: f64 = 20.0;
+const SQUARE_MM: f64 = 50.0;
+/// Room for the ticks and labels of the vertical ruler
+const RULER_CLEARANCE_MM: f64 = 15.0;
+const TEXT_SIZE: f64 = 10.0;
+const LABEL_SIZE: f64 = 7.0;
+
+/// Scale correction that makes the ruler exactly [`CALIBRATION_LENGTH_MM`] long, given its
+/// length `measured_mm` on a calibration page printed with the correction `current`
+pub fn calibrated_scale_correction(current: f32, measured_mm: f32) -> Result<f32, ProxyError> {
+    let correction = current * CALIBRATION_LENGTH_MM / measured_mm;
+    if measured_mm > 0.0 && (MIN_SCALE_CORRECTION..=MAX_SCALE_CORRECTION).contains(&correction) {
+        Ok(correction)
+    } else {
+        Err(ProxyError::Pdf(format!(
+            "A ruler measuring {} mm needs a scale correction outside {} to {}; \
+             check that the page was printed at 100% (\"Actual size\")",
+            measured_mm, MIN_SCALE_CORRECTION, MAX_SCALE_CORRECTION
+        )))
+    }
+}
+
+/// One-page PDF with rulers, a square and a card outline, drawn with the scale correction of
+/// `options` on its page size
+pub fn generate_calibration_page(options: &PdfOptions) -> Result<Vec<u8>, ProxyError> {
+    let scale = options.scale_correction as f64;
+    let ruler_mm = CALIBRATION_LENGTH_MM as f64 * scale;
+    let card_width_mm = options.card_width_mm() as f64;
+    let card_height_mm = options.card_height_mm() as f64;
+
+    let (page_width_mm, page_height_mm) = options.page_size.dimensions_mm();
+    let (page_width_mm, page_height_mm) = (page_width_mm as f64, page_height_mm as f64);
+    let needed_width =
+        2.0 * PAGE_MARGIN_MM + RULER_CLEARANCE_MM + card_width_mm + 10.0 + SQUARE_MM * scale;
+    let needed_height = 2.0 * PAGE_MARGIN_MM + 50.0 + ruler_mm.max(card_height_mm);
+    if page_width_mm < needed_width.max(ruler_mm + 2.0 * PAGE_MARGIN_MM)
+        || page_height_mm < needed_height
+    {
+        return Err(ProxyError::Pdf(format!(
+            "The calibration page doesn't fit on {}",
+            options.page_size
+        )));
+    }
+
+    let (doc, page, layer) = PdfDocument::new(
+        "LocalHawk Calibration",
+        Mm(page_width_mm),
+        Mm(page_height_mm),
+        "Layer 1",
+    );
+    let font = doc
+        .add_builtin_font(BuiltinFont::Helvetica)
+        .map_err(|e| ProxyError::Pdf(format!("Failed to add font: {}", e)))?;
+    let layer = doc.get_page(page).get_layer(layer);
+    layer.set_outline_color(Color::Rgb(Rgb::new(0.0, 0.0, 0.0, None)));
+    layer.set_outline_thickness(CUT_MARKER_THICKNESS);
+
+    let left = PAGE_MARGIN_MM;
+    let mut y = page_height_mm - PAGE_MARGIN_MM;
+    let instructions = [
+        "LocalHawk calibration page".to_string(),
+        "1. Print this page at 100% (\"Actual size\"), not \"Fit to page\".".to_string(),
+        format!(
+            "2. Measure the horizontal ruler from 0 to {} mm and enter the measured length.",
+            CALIBRATION_LENGTH_MM
+        ),
+        format!(
+            "Current scale correction: {:.4} (the square and card outline should measure {} mm and {:.1} x {:.1} mm)",
+            options.scale_correction,
+            SQUARE_MM,
+            card_width_mm / scale,
+            card_height_mm / scale
+        ),
+    ];
+    for line in instructions {
+        layer.use_text(line, TEXT_SIZE, Mm(left), Mm(y), &font);
+        y -= 6.0;
+    }
+
+    // Horizontal ruler, with the vertical one hanging from its start
+    y -= 12.0;
+    draw_ruler(&layer, &font, (left, y), ruler_mm, scale, true);
+    draw_ruler(&layer, &font, (left, y), ruler_mm, scale, false);
+
+    // Card outline and square right of the vertical ruler
+    let top = y - 10.0;
+    let card_left = left + RULER_CLEARANCE_MM;
+    draw_rectangle(
+        &layer,
+        (card_left, top - card_height_mm),
+        card_width_mm,
+        card_height_mm,
+    );
+    let square_left = card_left + card_width_mm + 10.0;
+    let square_mm = SQUARE_MM * scale;
+    draw_rectangle(&layer, (square_left, top - square_mm), square_mm, square_mm);
+    layer.use_text(
+        format!("{} mm", SQUARE_MM),
+        LABEL_SIZE,
+        Mm(square_left + 2.0),
+        Mm(top - square_mm - 5.0),
+        &font,
+    );
+
+    doc.save_to_bytes()
+        .map_err(|e| ProxyError::Pdf(format!("Failed to save calibration page: {}", e)))
+}
+
+/// Ruler with mm ticks, long ones every 5 mm and labels every 10 mm, starting at `origin`
+/// and going right (`horizontal`) or down
+fn draw_ruler(
+    layer: &PdfLayerReference,
+    font: &IndirectFontRef,
+    origin: (f64, f64),
+    length_mm: f64,
+    scale: f64,
+    horizontal: bool,
+) {
+    let (x, y) = origin;
+    let along = |distance: f64| {
+        if horizontal {
+            (x + distance, y)
+        } else {
+            (x, y - distance)
+        }
+    };
+    layer.add_shape(cut_marker_line(along(0.0), along(length_mm)));
+
+    let ticks = CALIBRATION_LENGTH_MM as u32;
+    for tick in 0..=ticks {
+        let tick_length = match tick {
+            t if t % 10 == 0 => 5.0,
+            t if t % 5 == 0 => 3.5,
+            _ => 2.0,
+        };
+        let (tick_x, tick_y) = along(tick as f64 * scale);
+        let end = if horizontal {
+            (tick_x, tick_y + tick_length)
+        } else {
+            (tick_x + tick_length, tick_y)
+        };
+        layer.add_shape(cut_marker_line((tick_x, tick_y), end));
+
+        if tick % 10 == 0 && (horizontal || tick > 0) {
+            let (label_x, label_y) = if horizontal {
+                (tick_x - 1.0, tick_y + 6.5)
+            } else {
+                (tick_x + 6.0, tick_y - 1.0)
+            };
+            layer.use_text(tick.to_string(), LABEL_SIZE, Mm(label_x), Mm(label_y), font);
+        }
+    }
+}
+
+fn draw_rectangle(layer: &PdfLayerReference, bottom_left: (f64, f64), width: f64, height: f64) {
+    let (left, bottom) = bottom_left;
+    let (right, top) = (left + width, bottom + height);
+    for (from, to) in [
+        ((left, bottom), (right, bottom)),
+        ((right, bottom), (right, top)),
+        ((right, top), (left, top)),
+        ((left, top), (left, bottom)),
+    ] {
+        layer.add_shape(cut_marker_line(from, to));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::PageSize;
+
+    #[test]
+    fn test_calibrated_scale_correction() {
+        // Printer shrinks by 2%: cards have to be drawn 2% larger
+        let correction = calibrated_scale_correction(1.0, 98.0).unwrap();
+        assert!((correction - 1.0204).abs() < 1e-3);
+        // Measuring the corrected page again refines the existing correction
+        let refined = calibrated_scale_correction(correction, 99.5).unwrap();
+        assert!(refined > correction);
+        assert!(calibrated_scale_correction(1.0, 50.0).is_err());
+        assert!(calibrated_scale_correction(1.0, 0.0).is_err());
+    }
+
+    #[test]
+    fn test_calibration_page_applies_correction() {
+        let options = PdfOptions {
+            scale_correction: 1.02,
+            ..PdfOptions::default()
+        };
+        let pdf = generate_calibration_page(&options).unwrap();
+        assert_eq!(&pdf[0..4], b"%PDF");
+        assert!((options.card_height_mm() - 87.0 * 1.02).abs() < 1e-3);
+
+        let tiny = PdfOptions {
+            page_size: PageSize::Custom {
+                width_mm: 100.0,
+                height_mm: 100.0,
+            },
+            ..PdfOptions::default()
+        };
+        assert!(generate_calibration_page(&tiny).is_err());
+    }
+}
@@ -1,7 +1,28 @@
 use crate::DoubleFaceMode;
+use crate::decklist::DeckSection;
 use crate::error::ProxyError;
+use crate::sorting::{CopyOrder, SortOrder};
 use printpdf::image_crate::DynamicImage;
-use printpdf::{Image, ImageTransform, Mm, PdfDocument};
+use printpdf::image_crate::imageops::FilterType;
+use printpdf::lopdf::{Document, Object};
+use printpdf::{
+    BuiltinFont, Color, Image, ImageTransform, IndirectFontRef, Line, Mm, PdfDocument,
+    PdfDocumentReference, PdfLayerReference, Point, Rgb,
+};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, debug_span, info};
+
+mod calibration;
+mod pdf_a;
+mod render;
+mod text_proxy;
+
+pub use calibration::{
+    CALIBRATION_LENGTH_MM, MAX_SCALE_CORRECTION, MIN_SCALE_CORRECTION, calibrated_scale_correction,
+    generate_calibration_page,
+};
+pub use render::{PageImageFormat, RenderOptions, render_pages, render_pages_from_groups};
+pub use text_proxy::{TextProxy, generate_text_pdfs_from_groups};
 
 // Constants from MagicHawk
 pub const IMAGE_WIDTH: u32 = 480;
@@ -11,28 +32,210 @@ pub const PAGE_HEIGHT: u32 = 3 * IMAGE_HEIGHT;
 pub const IMAGE_HEIGHT_CM: f32 = 8.7;
 pub const IMAGE_WIDTH_CM: f32 = IMAGE_HEIGHT_CM * IMAGE_WIDTH as f32 / IMAGE_HEIGHT as f32;
 
-const A4_WIDTH: Mm = Mm(210.0);
-const A4_HEIGHT: Mm = Mm(297.0);
+const A4_WIDTH_MM: f32 = 210.0;
+const A4_HEIGHT_MM: f32 = 297.0;
+const LETTER_WIDTH_MM: f32 = 215.9;
+const LETTER_HEIGHT_MM: f32 = 279.4;
 const INCH_DIV_CM: f32 = 2.54;
 const DPI: f32 = 300.0;
 const DPCM: f32 = DPI / INCH_DIV_CM;
 
-#[derive(Debug, Clone)]
+// Cut markers are drawn outside the card grid, starting a small gap away from it
+const CUT_MARKER_LENGTH_MM: f64 = 5.0;
+const CUT_MARKER_GAP_MM: f64 = 1.0;
+const CUT_MARKER_THICKNESS: f64 = 0.5;
+
+// Caption strips sit below each card; longer captions are cut off to stay within the card width
+const CAPTION_HEIGHT_MM: f32 = 3.0;
+const CAPTION_FONT_SIZE: f64 = 6.0;
+const CAPTION_INSET_MM: f64 = 1.0;
+const CAPTION_MAX_CHARS: usize = 48;
+
+// Divider rows hold a rule across the grid halfway down the row, with the group label above it
+const DIVIDER_FONT_SIZE: f64 = 9.0;
+const DIVIDER_LABEL_GAP_MM: f64 = 1.5;
+const DIVIDER_RULE_THICKNESS: f64 = 0.5;
+const DIVIDER_MAX_CHARS: usize = 80;
+
+const PDF_TITLE: &str = "Magic Card Proxies";
+/// Marks the deck hash among the document keywords
+const DECK_HASH_KEYWORD_PREFIX: &str = "localhawk-deck:";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct PdfOptions {
     pub page_size: PageSize,
     pub cards_per_row: u32,
     pub cards_per_column: u32,
+    /// Minimum distance between the card grid and the page edge (in mm)
     pub margin: f32,
     pub double_face_mode: DoubleFaceMode,
+    /// Draw cut markers along the card edges outside the grid
+    pub cut_markers: bool,
+    pub image_quality: ImageQuality,
+    /// Print a caption strip (card name, set code, collector number) beneath each card
+    pub card_captions: bool,
+    /// Start commanders and companions on their own page(s) after the rest of the deck
+    pub commanders_on_own_page: bool,
+    /// Print every commander and companion twice
+    pub duplicate_commanders: bool,
+    /// Separate the main deck, the sideboard and the tokens onto pages or into files of their
+    /// own, e.g. to print them on different paper
+    pub section_split: SectionSplit,
+    /// Produce PDF/A-1b output with an embedded sRGB profile, for printers that reject plain PDFs
+    pub pdf_a: bool,
+    /// Factor applied to card sizes and positions to compensate for a printer that scales
+    /// pages (1.0 = none), measured with the calibration page
+    pub scale_correction: f32,
+    /// Cards (by name) that fill the empty slots on the last page, taking turns, e.g. basic
+    /// lands or tokens; empty leaves the slots blank
+    pub filler_cards: Vec<String>,
+    /// Order of the cards within each print group
+    pub sort_order: SortOrder,
+    /// Whether the copies of a card are printed together or spread over its print group
+    pub copy_order: CopyOrder,
+    /// Document title, e.g. the deck name (not persisted; defaults to "Magic Card Proxies")
+    #[serde(skip)]
+    pub title: Option<String>,
+    /// Print the card text in plain frames instead of the card images (nothing is downloaded)
+    pub text_only: bool,
+    /// Leave a labeled divider row before each group of cards sharing a comment annotation or
+    /// deck section (only with `CopyOrder::Adjacent`)
+    pub group_dividers: bool,
+    /// Labels of the divider rows: per print group, the index of the first card of each
+    /// labeled group and its label (not persisted; filled in from the decklist entries)
+    #[serde(skip)]
+    pub group_labels: Vec<Vec<(usize, String)>>,
+    /// Hash of the cards and options, embedded in the document keywords to recognize an
+    /// unchanged deck (not persisted; see [`crate::ProxyGenerator::deck_hash`])
+    #[serde(skip)]
+    pub deck_hash: Option<String>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum PageSize {
     A4,
     Letter,
     Custom { width_mm: f32, height_mm: f32 },
 }
 
+impl PageSize {
+    /// Standard page sizes offered in frontends
+    pub fn presets() -> Vec<PageSize> {
+        vec![PageSize::A4, PageSize::Letter]
+    }
+
+    /// Page dimensions (width, height) in mm
+    pub fn dimensions_mm(&self) -> (f32, f32) {
+        match self {
+            PageSize::A4 => (A4_WIDTH_MM, A4_HEIGHT_MM),
+            PageSize::Letter => (LETTER_WIDTH_MM, LETTER_HEIGHT_MM),
+            PageSize::Custom {
+                width_mm,
+                height_mm,
+            } => (*width_mm, *height_mm),
+        }
+    }
+}
+
+impl std::fmt::Display for PageSize {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PageSize::A4 => write!(f, "A4"),
+            PageSize::Letter => write!(f, "Letter"),
+            PageSize::Custom {
+                width_mm,
+                height_mm,
+            } => write!(f, "Custom ({}x{} mm)", width_mm, height_mm),
+        }
+    }
+}
+
+/// Resolution at which card images are embedded into the PDF
+///
+/// Every quality starts from the same cached `border_crop` image and only resamples it while
+/// embedding, so switching quality never downloads images again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ImageQuality {
+    /// Embed images at their original resolution
+    High,
+    /// Downscale images to 75% (smaller files, still fine for most printers)
+    Medium,
+    /// Downscale images to 50% (quick test prints)
+    Draft,
+}
+
+impl ImageQuality {
+    pub fn all() -> Vec<ImageQuality> {
+        vec![
+            ImageQuality::High,
+            ImageQuality::Medium,
+            ImageQuality::Draft,
+        ]
+    }
+
+    fn scale_factor(&self) -> f32 {
+        match self {
+            ImageQuality::High => 1.0,
+            ImageQuality::Medium => 0.75,
+            ImageQuality::Draft => 0.5,
+        }
+    }
+
+    /// Resample an image according to this quality level
+    pub fn apply(&self, image: DynamicImage) -> DynamicImage {
+        let factor = self.scale_factor();
+        if factor >= 1.0 {
+            return image;
+        }
+        let width = ((image.width() as f32 * factor).round() as u32).max(1);
+        let height = ((image.height() as f32 * factor).round() as u32).max(1);
+        image.resize_exact(width, height, FilterType::Triangle)
+    }
+}
+
+impl std::fmt::Display for ImageQuality {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ImageQuality::High => write!(f, "High"),
+            ImageQuality::Medium => write!(f, "Medium"),
+            ImageQuality::Draft => write!(f, "Draft"),
+        }
+    }
+}
+
+/// How the deck sections are separated, see [`PdfOptions::print_groups`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum SectionSplit {
+    /// All sections share pages
+    #[default]
+    Together,
+    /// Each section starts on a new page
+    OwnPages,
+    /// Each section is written to files of its own; a single PDF has them on their own pages
+    OwnFiles,
+}
+
+impl SectionSplit {
+    pub fn all() -> Vec<SectionSplit> {
+        vec![
+            SectionSplit::Together,
+            SectionSplit::OwnPages,
+            SectionSplit::OwnFiles,
+        ]
+    }
+}
+
+impl std::fmt::Display for SectionSplit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SectionSplit::Together => write!(f, "Together"),
+            SectionSplit::OwnPages => write!(f, "Own pages"),
+            SectionSplit::OwnFiles => write!(f, "Own files"),
+        }
+    }
+}
+
 impl Default for PdfOptions {
     fn default() -> Self {
         PdfOptions {
@@ -41,43 +244,444 @@ impl Default for PdfOptions {
             cards_per_column: 3,
             margin: 3.0,
             double_face_mode: DoubleFaceMode::BothSides, // Keep current behavior as default
+            cut_markers: false,
+            image_quality: ImageQuality::High,
+            card_captions: false,
+            commanders_on_own_page: false,
+            duplicate_commanders: false,
+            section_split: SectionSplit::default(),
+            pdf_a: false,
+            scale_correction: 1.0,
+            filler_cards: Vec::new(),
+            sort_order: SortOrder::default(),
+            copy_order: CopyOrder::default(),
+            title: None,
+            text_only: false,
+            group_dividers: false,
+            group_labels: Vec::new(),
+            deck_hash: None,
         }
     }
 }
 
+impl PdfOptions {
+    /// Number of card slots on a single page
+    pub fn cards_per_page(&self) -> usize {
+        (self.cards_per_row * self.cards_per_column) as usize
+    }
+
+    /// Printed card width (in mm), including the scale correction
+    pub fn card_width_mm(&self) -> f32 {
+        IMAGE_WIDTH_CM * 10.0 * self.scale_correction
+    }
+
+    /// Printed card height (in mm), including the scale correction
+    pub fn card_height_mm(&self) -> f32 {
+        IMAGE_HEIGHT_CM * 10.0 * self.scale_correction
+    }
+
+    /// Height of the caption strip below each card (0 if captions are off)
+    fn caption_height_mm(&self) -> f32 {
+        if self.card_captions {
+            CAPTION_HEIGHT_MM
+        } else {
+            0.0
+        }
+    }
+
+    /// Vertical space taken by one row of cards, including its caption strip
+    fn row_height_mm(&self) -> f32 {
+        self.card_height_mm() + self.caption_height_mm()
+    }
+
+    /// Bottom left corner of the card grid, which is centered on the page (in mm)
+    ///
+    /// The margin doesn't move the grid; [`PdfOptions::validate`] checks that the centered grid
+    /// leaves at least the margin on every side.
+    fn grid_origin_mm(&self) -> (f32, f32) {
+        let (page_width_mm, page_height_mm) = self.page_size.dimensions_mm();
+        let grid_width_mm = self.cards_per_row as f32 * self.card_width_mm();
+        let grid_height_mm = self.cards_per_column as f32 * self.row_height_mm();
+        (
+            (page_width_mm - grid_width_mm) / 2.0,
+            (page_height_mm - grid_height_mm) / 2.0,
+        )
+    }
+
+    /// Where the card at `index` within a page goes; pages fill left to right, top to bottom
+    fn card_slot(&self, index: usize) -> CardSlot {
+        let (grid_left, grid_bottom) = self.grid_origin_mm();
+        let row = index as u32 / self.cards_per_row;
+        let col = index as u32 % self.cards_per_row;
+        let row_bottom = (self.cards_per_column - 1 - row) as f32 * self.row_height_mm();
+        CardSlot {
+            left_mm: grid_left + col as f32 * self.card_width_mm(),
+            bottom_mm: grid_bottom + row_bottom + self.caption_height_mm(),
+        }
+    }
+
+    /// Start and end point (in mm from the bottom left page corner) of the rule across the
+    /// divider row starting at `index` within a page
+    fn divider_rule(&self, index: usize) -> ((f64, f64), (f64, f64)) {
+        let slot = self.card_slot(index);
+        let y = (slot.bottom_mm + self.card_height_mm() / 2.0) as f64;
+        let right = slot.left_mm + self.cards_per_row as f32 * self.card_width_mm();
+        ((slot.left_mm as f64, y), (right as f64, y))
+    }
+
+    /// Whether divider rows separate the labeled groups; copies spread over the print group
+    /// don't stay within their group
+    pub fn shows_group_dividers(&self) -> bool {
+        self.group_dividers && self.copy_order == CopyOrder::Adjacent
+    }
+
+    /// Slots (counted from the first slot of a print group, which starts a page) of the
+    /// `count` items of the group, and of the divider rows before the items at `divider_items`
+    ///
+    /// A divider row starts on a new row and takes all of it. One that would be the last row of
+    /// a page moves to the next page, so it is never cut apart from its first cards. Grids of a
+    /// single row have no room for dividers.
+    pub(crate) fn group_slots(
+        &self,
+        count: usize,
+        divider_items: &[usize],
+    ) -> (Vec<usize>, Vec<usize>) {
+        let cards_per_row = self.cards_per_row.max(1) as usize;
+        let rows_per_page = self.cards_per_column as usize;
+        let mut item_slots = Vec::with_capacity(count);
+        let mut divider_slots = Vec::new();
+        let mut next = 0;
+        for item in 0..count {
+            if rows_per_page > 1 && divider_items.contains(&item) {
+                next = next.div_ceil(cards_per_row) * cards_per_row;
+                if (next / cards_per_row) % rows_per_page == rows_per_page - 1 {
+                    next += cards_per_row;
+                }
+                divider_slots.push(next);
+                next += cards_per_row;
+            }
+            item_slots.push(next);
+            next += 1;
+        }
+        (item_slots, divider_slots)
+    }
+
+    /// Check that the card grid fits on the page within the margins
+    pub fn validate(&self) -> Result<(), ProxyError> {
+        if self.cards_per_row == 0 || self.cards_per_column == 0 {
+            return Err(ProxyError::Pdf(
+                "Cards per row and per column must be at least 1".to_string(),
+            ));
+        }
+        if self.margin < 0.0 {
+            return Err(ProxyError::Pdf("Margin must not be negative".to_string()));
+        }
+        if !(MIN_SCALE_CORRECTION..=MAX_SCALE_CORRECTION).contains(&self.scale_correction) {
+            return Err(ProxyError::Pdf(format!(
+                "Scale correction must be between {} and {}",
+                MIN_SCALE_CORRECTION, MAX_SCALE_CORRECTION
+            )));
+        }
+        // Captions use a built-in font, and PDF/A requires every font to be embedded
+        if self.pdf_a && self.card_captions {
+            return Err(ProxyError::Pdf(
+                "Card captions can't be combined with PDF/A output".to_string(),
+            ));
+        }
+        if self.pdf_a && self.group_dividers {
+            return Err(ProxyError::Pdf(
+                "Group dividers can't be combined with PDF/A output".to_string(),
+            ));
+        }
+        if self.pdf_a && self.text_only {
+            return Err(ProxyError::Pdf(
+                "Text-only proxies can't be combined with PDF/A output".to_string(),
+            ));
+        }
+
+        let (page_width_mm, page_height_mm) = self.page_size.dimensions_mm();
+        let grid_width_mm = self.cards_per_row as f32 * self.card_width_mm();
+        let grid_height_mm = self.cards_per_column as f32 * self.row_height_mm();
+
+        if grid_width_mm + 2.0 * self.margin > page_width_mm
+            || grid_height_mm + 2.0 * self.margin > page_height_mm
+        {
+            return Err(ProxyError::Pdf(format!(
+                "{}x{} cards do not fit on {} with {:.1} mm margins",
+                self.cards_per_row, self.cards_per_column, self.page_size, self.margin
+            )));
+        }
+        Ok(())
+    }
+
+    /// Number of copies to print for an entry of `copies` copies in `section`
+    pub fn print_copies(&self, section: DeckSection, copies: u32) -> u32 {
+        if self.duplicate_commanders && section.is_command_zone() {
+            copies * 2
+        } else {
+            copies
+        }
+    }
+
+    /// Copies of each of `fillers` filler cards (one image each) needed to fill the last page
+    /// after `images` images; all zero when that page is already full or empty
+    pub fn filler_copies(&self, images: usize, fillers: usize) -> Vec<u32> {
+        let mut copies = vec![0; fillers];
+        if fillers == 0 || images == 0 {
+            return copies;
+        }
+
+        let cards_per_page = self.cards_per_page().max(1);
+        let empty_slots = (cards_per_page - images % cards_per_page) % cards_per_page;
+        for slot in 0..empty_slots {
+            copies[slot % fillers] += 1;
+        }
+        copies
+    }
+
+    /// Split entry indices (given the section of each entry) into print groups,
+    /// each of which starts on a new page
+    ///
+    /// Entries keep their decklist order. Unless `section_split` is [`SectionSplit::Together`],
+    /// the sideboard and then the tokens follow the rest of the deck in groups of their own;
+    /// commanders and companions form a separate final group when `commanders_on_own_page` is
+    /// set.
+    pub fn print_groups(&self, sections: &[DeckSection]) -> Vec<Vec<usize>> {
+        let split_sections = self.section_split != SectionSplit::Together;
+        if !self.commanders_on_own_page && !split_sections {
+            return vec![(0..sections.len()).collect()];
+        }
+
+        let mut groups: [Vec<usize>; 4] = Default::default();
+        for (index, section) in sections.iter().enumerate() {
+            let group = match section {
+                DeckSection::Sideboard if split_sections => 1,
+                DeckSection::Tokens if split_sections => 2,
+                section if section.is_command_zone() && self.commanders_on_own_page => 3,
+                _ => 0,
+            };
+            groups[group].push(index);
+        }
+        groups
+            .into_iter()
+            .filter(|group| !group.is_empty())
+            .collect()
+    }
+}
+
+/// Position of a card image on its page, in mm from the bottom left page corner
+///
+/// The caption strip (if any) lies directly below `bottom_mm`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct CardSlot {
+    left_mm: f32,
+    bottom_mm: f32,
+}
+
+/// A card image with the caption printed beneath it when `PdfOptions::card_captions` is set
+pub struct CaptionedImage {
+    pub image: DynamicImage,
+    pub caption: String,
+    /// Label of the divider row before this image when `PdfOptions::group_dividers` is set
+    pub divider: Option<String>,
+}
+
+/// Divider rows for the cards of a print group, given the group label of each card: the index
+/// of the first card of each labeled group and its label
+pub fn divider_labels(
+    card_labels: impl IntoIterator<Item = Option<String>>,
+) -> Vec<(usize, String)> {
+    let mut dividers = Vec::new();
+    let mut previous = None;
+    for (index, label) in card_labels.into_iter().enumerate() {
+        if let Some(text) = label.as_ref().filter(|_| label != previous) {
+            dividers.push((index, text.clone()));
+        }
+        previous = label;
+    }
+    dividers
+}
+
+/// A page of a print group: its images with the slot each goes in, and its divider rows
+pub(crate) struct GroupPage<T> {
+    pub items: Vec<(usize, T)>,
+    pub dividers: Vec<(usize, String)>,
+}
+
+/// Split a print group into pages like [`images_to_pages`], leaving the divider rows of
+/// [`PdfOptions::group_slots`] before the images that carry a divider label
+pub(crate) fn group_pages(
+    group: Vec<CaptionedImage>,
+    options: &PdfOptions,
+) -> Vec<GroupPage<CaptionedImage>> {
+    let dividers: Vec<(usize, String)> = if options.shows_group_dividers() {
+        group
+            .iter()
+            .enumerate()
+            .filter_map(|(index, captioned)| Some((index, captioned.divider.clone()?)))
+            .collect()
+    } else {
+        Vec::new()
+    };
+    let divider_items: Vec<usize> = dividers.iter().map(|(index, _)| *index).collect();
+    let (item_slots, divider_slots) = options.group_slots(group.len(), &divider_items);
+
+    let cards_per_page = options.cards_per_page().max(1);
+    let page_count = item_slots
+        .last()
+        .map_or(0, |slot| slot / cards_per_page + 1);
+    let mut pages: Vec<GroupPage<CaptionedImage>> = (0..page_count)
+        .map(|_| GroupPage {
+            items: Vec::new(),
+            dividers: Vec::new(),
+        })
+        .collect();
+    for (slot, (_, label)) in divider_slots.into_iter().zip(dividers) {
+        pages[slot / cards_per_page]
+            .dividers
+            .push((slot % cards_per_page, label));
+    }
+    for (slot, captioned) in item_slots.into_iter().zip(group) {
+        pages[slot / cards_per_page]
+            .items
+            .push((slot % cards_per_page, captioned));
+    }
+    pages
+}
+
+/// Encoded image of the plain card back printed for back faces whose image is unavailable
+pub fn placeholder_back_image_bytes() -> Result<Vec<u8>, ProxyError> {
+    use printpdf::image_crate::{ImageFormat, Rgb as Pixel, RgbImage};
+
+    const BORDER: u32 = 24;
+    let back = RgbImage::from_fn(IMAGE_WIDTH, IMAGE_HEIGHT, |x, y| {
+        let in_border =
+            x < BORDER || y < BORDER || x >= IMAGE_WIDTH - BORDER || y >= IMAGE_HEIGHT - BORDER;
+        if in_border {
+            Pixel([20, 20, 20])
+        } else {
+            Pixel([92, 64, 51])
+        }
+    });
+
+    let mut bytes = Vec::new();
+    DynamicImage::ImageRgb8(back)
+        .write_to(&mut std::io::Cursor::new(&mut bytes), ImageFormat::Png)
+        .map_err(|e| ProxyError::Pdf(format!("Failed to encode placeholder back: {}", e)))?;
+    Ok(bytes)
+}
+
 pub fn generate_pdf<I>(images: I, options: PdfOptions) -> Result<Vec<u8>, ProxyError>
 where
     I: Iterator<Item = DynamicImage>,
 {
-    let (page_width, page_height) = match options.page_size {
-        PageSize::A4 => (A4_WIDTH, A4_HEIGHT),
-        PageSize::Letter => (Mm(215.9), Mm(279.4)),
-        PageSize::Custom {
-            width_mm,
-            height_mm,
-        } => (Mm(width_mm as f64), Mm(height_mm as f64)),
-    };
+    let images = images.map(|image| CaptionedImage {
+        image,
+        caption: String::new(),
+        divider: None,
+    });
+    let pages = group_pages(images.collect(), &options);
+    generate_pdf_from_pages(pages.into_iter(), options)
+}
 
-    let (doc, page1, layer1) =
-        PdfDocument::new("Magic Card Proxies", page_width, page_height, "Layer 1");
-
-    let transform = ImageTransform {
-        dpi: Some(DPI as f64),
-        translate_x: Some(
-            (page_width - Mm((options.cards_per_row as f32 * IMAGE_WIDTH_CM * 10.0) as f64)) / 2.0,
-        ),
-        translate_y: Some(
-            (page_height - Mm((options.cards_per_column as f32 * IMAGE_HEIGHT_CM * 10.0) as f64))
-                / 2.0,
-        ),
-        scale_x: Some((IMAGE_WIDTH_CM / (IMAGE_WIDTH as f32) * DPCM) as f64),
-        scale_y: Some((IMAGE_HEIGHT_CM / (IMAGE_HEIGHT as f32) * DPCM) as f64),
-        rotate: None,
+/// Generate a PDF from groups of captioned images, starting each group on a new page
+pub fn generate_pdf_from_groups<G>(groups: G, options: PdfOptions) -> Result<Vec<u8>, ProxyError>
+where
+    G: IntoIterator<Item = Vec<CaptionedImage>>,
+{
+    let pages: Vec<_> = groups
+        .into_iter()
+        .flat_map(|group| group_pages(group, &options))
+        .collect();
+    generate_pdf_from_pages(pages.into_iter(), options)
+}
+
+/// Generate one PDF per `pages_per_file` pages from groups of captioned images, starting each
+/// group on a new page (a single PDF if `pages_per_file` is None)
+///
+/// With [`SectionSplit::OwnFiles`] each group also starts a new file.
+pub fn generate_pdfs_from_groups<G>(
+    groups: G,
+    options: PdfOptions,
+    pages_per_file: Option<usize>,
+) -> Result<Vec<Vec<u8>>, ProxyError>
+where
+    G: IntoIterator<Item = Vec<CaptionedImage>>,
+{
+    if options.section_split == SectionSplit::OwnFiles {
+        let options = PdfOptions {
+            section_split: SectionSplit::OwnPages,
+            ..options
+        };
+        let mut pdfs = Vec::new();
+        for group in groups {
+            pdfs.extend(generate_pdfs_from_groups(
+                [group],
+                options.clone(),
+                pages_per_file,
+            )?);
+        }
+        if pdfs.is_empty() {
+            pdfs.push(generate_pdf_from_groups(Vec::new(), options)?);
+        }
+        return Ok(pdfs);
+    }
+
+    let Some(pages_per_file) = pages_per_file.filter(|pages| *pages > 0) else {
+        return Ok(vec![generate_pdf_from_groups(groups, options)?]);
     };
 
-    let pages_iter = images_to_pages(images, options.cards_per_row * options.cards_per_column);
+    let pages: Vec<_> = groups
+        .into_iter()
+        .flat_map(|group| group_pages(group, &options))
+        .collect();
+    let mut pages = pages.into_iter().peekable();
+    let mut pdfs = Vec::new();
+    // Always at least one file, like a single PDF without cards
+    while pdfs.is_empty() || pages.peek().is_some() {
+        let file_pages: Vec<_> = pages.by_ref().take(pages_per_file).collect();
+        pdfs.push(generate_pdf_from_pages(
+            file_pages.into_iter(),
+            options.clone(),
+        )?);
+    }
+    Ok(pdfs)
+}
+
+fn generate_pdf_from_pages<P>(pages: P, options: PdfOptions) -> Result<Vec<u8>, ProxyError>
+where
+    P: Iterator<Item = GroupPage<CaptionedImage>>,
+{
+    options.validate()?;
+    let _span = debug_span!("generate_pdf", page_size = ?options.page_size, pdf_a = options.pdf_a)
+        .entered();
 
-    for (page_index, page_images) in pages_iter.enumerate() {
+    let (page_width_mm, page_height_mm) = options.page_size.dimensions_mm();
+    let (page_width, page_height) = (Mm(page_width_mm as f64), Mm(page_height_mm as f64));
+
+    let title = options.title.as_deref().unwrap_or(PDF_TITLE);
+    let (doc, page1, layer1) = PdfDocument::new(title, page_width, page_height, "Layer 1");
+    let doc = with_deck_hash(doc, &options);
+    let label_font = if options.card_captions || options.group_dividers {
+        Some(
+            doc.add_builtin_font(BuiltinFont::Helvetica)
+                .map_err(|e| ProxyError::Pdf(format!("Failed to add caption font: {}", e)))?,
+        )
+    } else {
+        None
+    };
+    let caption_font = label_font.as_ref().filter(|_| options.card_captions);
+    let caption_height_mm = options.caption_height_mm();
+
+    let mut page_count = 0;
+    for (page_index, page) in pages.enumerate() {
+        debug!(
+            page = page_index + 1,
+            cards = page.items.len(),
+            dividers = page.dividers.len(),
+            "Adding page"
+        );
+        page_count += 1;
         let (current_page, current_layer) = if page_index == 0 {
             (page1, layer1)
         } else {
@@ -86,30 +690,184 @@ where
 
         let layer = doc.get_page(current_page).get_layer(current_layer);
 
-        for (card_index, image) in page_images.into_iter().enumerate() {
-            let row = card_index as u32 / options.cards_per_row;
-            let col = card_index as u32 % options.cards_per_row;
+        if let Some(font) = label_font.as_ref() {
+            for (index, label) in page.dividers {
+                add_divider(&layer, &options, index, &label, font);
+            }
+        }
+
+        for (card_index, captioned) in page.items {
+            let slot = options.card_slot(card_index);
 
-            let x_offset = col as f32 * IMAGE_WIDTH_CM * 10.0;
-            let y_offset = (options.cards_per_column - 1 - row) as f32 * IMAGE_HEIGHT_CM * 10.0;
+            let caption_font = caption_font.filter(|_| !captioned.caption.is_empty());
+            if let Some(font) = caption_font {
+                let caption: String = captioned.caption.chars().take(CAPTION_MAX_CHARS).collect();
+                layer.use_text(
+                    caption,
+                    CAPTION_FONT_SIZE,
+                    Mm(slot.left_mm as f64 + CAPTION_INSET_MM),
+                    Mm((slot.bottom_mm - caption_height_mm) as f64 + CAPTION_INSET_MM),
+                    font,
+                );
+            }
 
+            // Scale from the actual pixel size so reduced-quality images keep the physical card size
+            let mut image = options.image_quality.apply(captioned.image);
+            if options.pdf_a {
+                image = pdf_a::flatten_transparency(image);
+            }
             let card_transform = ImageTransform {
-                translate_x: Some(transform.translate_x.unwrap() + Mm(x_offset as f64)),
-                translate_y: Some(transform.translate_y.unwrap() + Mm(y_offset as f64)),
-                ..transform
+                dpi: Some(DPI as f64),
+                translate_x: Some(Mm(slot.left_mm as f64)),
+                translate_y: Some(Mm(slot.bottom_mm as f64)),
+                scale_x: Some(
+                    (options.card_width_mm() / 10.0 / (image.width() as f32) * DPCM) as f64,
+                ),
+                scale_y: Some(
+                    (options.card_height_mm() / 10.0 / (image.height() as f32) * DPCM) as f64,
+                ),
+                rotate: None,
             };
 
             Image::from_dynamic_image(&image).add_to_layer(layer.clone(), card_transform);
         }
+
+        if options.cut_markers {
+            add_cut_markers(&layer, &options);
+        }
     }
 
-    doc.save_to_bytes()
-        .map_err(|e| ProxyError::Pdf(format!("Failed to save PDF: {}", e)))
+    let pdf = doc
+        .save_to_bytes()
+        .map_err(|e| ProxyError::Pdf(format!("Failed to save PDF: {}", e)))?;
+    info!(pages = page_count, bytes = pdf.len(), "Generated PDF");
+    if options.pdf_a {
+        pdf_a::convert_to_pdf_a(&pdf, title)
+    } else {
+        Ok(pdf)
+    }
+}
+
+/// Add the deck hash of `options`, if any, to the document keywords
+fn with_deck_hash(doc: PdfDocumentReference, options: &PdfOptions) -> PdfDocumentReference {
+    match &options.deck_hash {
+        Some(hash) => doc.with_keywords(vec![format!("{}{}", DECK_HASH_KEYWORD_PREFIX, hash)]),
+        None => doc,
+    }
+}
+
+/// The deck hash embedded in a generated PDF (see [`PdfOptions::deck_hash`]), None for PDFs
+/// without one
+pub fn pdf_deck_hash(pdf: &[u8]) -> Option<String> {
+    let doc = Document::load_mem(pdf).ok()?;
+    let info_id = doc
+        .trailer
+        .get(b"Info")
+        .and_then(Object::as_reference)
+        .ok()?;
+    let info = doc.get_object(info_id).and_then(Object::as_dict).ok()?;
+    let keywords = info.get(b"Keywords").and_then(Object::as_str).ok()?;
+    String::from_utf8_lossy(keywords)
+        .split(',')
+        .find_map(|keyword| keyword.trim().strip_prefix(DECK_HASH_KEYWORD_PREFIX))
+        .map(str::to_string)
+}
+
+/// Draw the rule and label of the divider row starting at `index` within the page
+fn add_divider(
+    layer: &PdfLayerReference,
+    options: &PdfOptions,
+    index: usize,
+    label: &str,
+    font: &IndirectFontRef,
+) {
+    let (from, to) = options.divider_rule(index);
+    layer.set_outline_color(Color::Rgb(Rgb::new(0.0, 0.0, 0.0, None)));
+    layer.set_outline_thickness(DIVIDER_RULE_THICKNESS);
+    layer.add_shape(cut_marker_line(from, to));
+
+    let label: String = label.chars().take(DIVIDER_MAX_CHARS).collect();
+    layer.use_text(
+        label,
+        DIVIDER_FONT_SIZE,
+        Mm(from.0 + CAPTION_INSET_MM),
+        Mm(from.1 + DIVIDER_LABEL_GAP_MM),
+        font,
+    );
+}
+
+/// Draw short lines outside the card grid marking where to cut along each card edge
+fn add_cut_markers(layer: &PdfLayerReference, options: &PdfOptions) {
+    layer.set_outline_color(Color::Rgb(Rgb::new(0.0, 0.0, 0.0, None)));
+    layer.set_outline_thickness(CUT_MARKER_THICKNESS);
+
+    for (from, to) in cut_marker_segments(options) {
+        layer.add_shape(cut_marker_line(from, to));
+    }
 }
 
-fn images_to_pages<I>(images: I, cards_per_page: u32) -> impl Iterator<Item = Vec<DynamicImage>>
+/// Start and end point (in mm from the bottom left page corner) of every cut marker
+fn cut_marker_segments(options: &PdfOptions) -> Vec<((f64, f64), (f64, f64))> {
+    let (left, bottom) = options.grid_origin_mm();
+    let (left, bottom) = (left as f64, bottom as f64);
+    let card_width = options.card_width_mm() as f64;
+    let card_height = options.card_height_mm() as f64;
+    let caption_height = options.caption_height_mm() as f64;
+    let row_height = options.row_height_mm() as f64;
+    let right = left + options.cards_per_row as f64 * card_width;
+    let top = bottom + options.cards_per_column as f64 * row_height;
+
+    // Bottom and top edge of every card; adjacent rows share an edge when there are no captions
+    let mut horizontal_edges: Vec<f64> = (0..options.cards_per_column)
+        .flat_map(|row| {
+            let card_bottom = bottom + row as f64 * row_height + caption_height;
+            [card_bottom, card_bottom + card_height]
+        })
+        .collect();
+    horizontal_edges.dedup_by(|a, b| (*a - *b).abs() < 1e-6);
+
+    let mut segments = Vec::new();
+    for col in 0..=options.cards_per_row {
+        let x = left + col as f64 * card_width;
+        segments.push((
+            (x, bottom - CUT_MARKER_GAP_MM - CUT_MARKER_LENGTH_MM),
+            (x, bottom - CUT_MARKER_GAP_MM),
+        ));
+        segments.push((
+            (x, top + CUT_MARKER_GAP_MM),
+            (x, top + CUT_MARKER_GAP_MM + CUT_MARKER_LENGTH_MM),
+        ));
+    }
+
+    for y in horizontal_edges {
+        segments.push((
+            (left - CUT_MARKER_GAP_MM - CUT_MARKER_LENGTH_MM, y),
+            (left - CUT_MARKER_GAP_MM, y),
+        ));
+        segments.push((
+            (right + CUT_MARKER_GAP_MM, y),
+            (right + CUT_MARKER_GAP_MM + CUT_MARKER_LENGTH_MM, y),
+        ));
+    }
+    segments
+}
+
+fn cut_marker_line(from: (f64, f64), to: (f64, f64)) -> Line {
+    Line {
+        points: vec![
+            (Point::new(Mm(from.0), Mm(from.1)), false),
+            (Point::new(Mm(to.0), Mm(to.1)), false),
+        ],
+        is_closed: false,
+        has_fill: false,
+        has_stroke: true,
+        is_clipping_path: false,
+    }
+}
+
+pub(crate) fn images_to_pages<I, T>(images: I, cards_per_page: u32) -> impl Iterator<Item = Vec<T>>
 where
-    I: Iterator<Item = DynamicImage>,
+    I: Iterator<Item = T>,
 {
     let mut current_page = Vec::new();
     let mut pages = Vec::new();
@@ -187,6 +945,13 @@ mod tests {
         assert_eq!(pages[1].len(), 2);
     }
 
+    #[test]
+    fn test_placeholder_back_image() {
+        let bytes = placeholder_back_image_bytes().unwrap();
+        let image = printpdf::image_crate::load_from_memory(&bytes).unwrap();
+        assert_eq!((image.width(), image.height()), (IMAGE_WIDTH, IMAGE_HEIGHT));
+    }
+
     #[test]
     fn test_generate_pdf_basic() {
         let images = vec![create_test_image()];
@@ -248,4 +1013,380 @@ mod tests {
         );
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_validate_rejects_grid_larger_than_page() {
+        let options = PdfOptions {
+            cards_per_row: 4,
+            ..Default::default()
+        };
+        assert!(options.validate().is_err());
+        assert!(generate_pdf(vec![create_test_image()].into_iter(), options).is_err());
+
+        let options = PdfOptions {
+            margin: 20.0,
+            ..Default::default()
+        };
+        assert!(options.validate().is_err());
+
+        let options = PdfOptions {
+            cards_per_column: 0,
+            ..Default::default()
+        };
+        assert!(options.validate().is_err());
+    }
+
+    #[test]
+    fn test_smaller_grids_are_valid() {
+        let options = PdfOptions {
+            cards_per_row: 2,
+            cards_per_column: 2,
+            ..Default::default()
+        };
+        assert!(options.validate().is_ok());
+        assert_eq!(options.cards_per_page(), 4);
+    }
+
+    #[test]
+    fn test_image_quality_scaling() {
+        let image = create_test_image();
+        let high = ImageQuality::High.apply(image.clone());
+        assert_eq!((high.width(), high.height()), (IMAGE_WIDTH, IMAGE_HEIGHT));
+
+        let draft = ImageQuality::Draft.apply(image);
+        assert_eq!(
+            (draft.width(), draft.height()),
+            (IMAGE_WIDTH / 2, IMAGE_HEIGHT / 2)
+        );
+    }
+
+    #[test]
+    fn test_generate_pdf_with_cut_markers_and_draft_quality() {
+        let images = vec![create_test_image(), create_test_image()];
+        let options = PdfOptions {
+            cut_markers: true,
+            image_quality: ImageQuality::Draft,
+            ..Default::default()
+        };
+
+        let pdf_data = generate_pdf(images.into_iter(), options).unwrap();
+        assert_eq!(&pdf_data[0..4], b"%PDF");
+    }
+
+    #[test]
+    fn test_pdf_options_deserialize_fills_missing_fields() {
+        let options: PdfOptions =
+            serde_json::from_str(r#"{"page_size":"Letter","cut_markers":true}"#).unwrap();
+        assert_eq!(options.page_size, PageSize::Letter);
+        assert!(options.cut_markers);
+        assert_eq!(options.cards_per_row, 3);
+        assert_eq!(options.image_quality, ImageQuality::High);
+    }
+
+    #[test]
+    fn test_commander_print_groups_and_copies() {
+        let sections = [
+            DeckSection::Commander,
+            DeckSection::Main,
+            DeckSection::Companion,
+            DeckSection::Sideboard,
+        ];
+
+        let options = PdfOptions::default();
+        assert_eq!(options.print_groups(&sections), vec![vec![0, 1, 2, 3]]);
+        assert_eq!(options.print_copies(DeckSection::Commander, 1), 1);
+
+        let options = PdfOptions {
+            commanders_on_own_page: true,
+            duplicate_commanders: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            options.print_groups(&sections),
+            vec![vec![1, 3], vec![0, 2]]
+        );
+        assert_eq!(
+            options.print_groups(&[DeckSection::Commander]),
+            vec![vec![0]]
+        );
+        assert_eq!(options.print_copies(DeckSection::Commander, 1), 2);
+        assert_eq!(options.print_copies(DeckSection::Main, 4), 4);
+    }
+
+    #[test]
+    fn test_section_print_groups() {
+        let sections = [
+            DeckSection::Tokens,
+            DeckSection::Main,
+            DeckSection::Commander,
+            DeckSection::Sideboard,
+            DeckSection::Main,
+        ];
+
+        let options = PdfOptions {
+            section_split: SectionSplit::OwnPages,
+            ..Default::default()
+        };
+        assert_eq!(
+            options.print_groups(&sections),
+            vec![vec![1, 2, 4], vec![3], vec![0]]
+        );
+
+        let options = PdfOptions {
+            section_split: SectionSplit::OwnFiles,
+            commanders_on_own_page: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            options.print_groups(&sections),
+            vec![vec![1, 4], vec![3], vec![0], vec![2]]
+        );
+    }
+
+    #[test]
+    fn test_filler_copies_fill_last_page() {
+        let options = PdfOptions::default();
+        // 11 cards leave 7 empty slots on the second 3x3 page
+        assert_eq!(options.filler_copies(11, 2), vec![4, 3]);
+        assert_eq!(options.filler_copies(11, 1), vec![7]);
+        assert_eq!(options.filler_copies(9, 2), vec![0, 0]);
+        assert_eq!(options.filler_copies(0, 2), vec![0, 0]);
+        assert!(options.filler_copies(11, 0).is_empty());
+    }
+
+    #[test]
+    fn test_generate_pdf_from_groups() {
+        let captioned = |caption: &str| CaptionedImage {
+            image: create_test_image(),
+            caption: caption.to_string(),
+            divider: None,
+        };
+        let groups = vec![
+            vec![
+                captioned("plains (M21 #260)"),
+                captioned("island (M21 #261)"),
+            ],
+            vec![captioned("")],
+        ];
+        let options = PdfOptions {
+            card_captions: true,
+            cut_markers: true,
+            ..Default::default()
+        };
+        let pdf_data = generate_pdf_from_groups(groups, options).unwrap();
+        assert_eq!(&pdf_data[0..4], b"%PDF");
+    }
+
+    #[test]
+    fn test_generate_pdfs_split_by_pages() {
+        let group = |cards: usize| -> Vec<CaptionedImage> {
+            (0..cards)
+                .map(|_| CaptionedImage {
+                    image: create_test_image(),
+                    caption: String::new(),
+                    divider: None,
+                })
+                .collect()
+        };
+        let options = PdfOptions {
+            cards_per_row: 1,
+            cards_per_column: 1,
+            ..Default::default()
+        };
+
+        // Five pages, two per file
+        let pdfs =
+            generate_pdfs_from_groups(vec![group(3), group(2)], options.clone(), Some(2)).unwrap();
+        assert_eq!(pdfs.len(), 3);
+        assert!(pdfs.iter().all(|pdf| pdf.starts_with(b"%PDF")));
+
+        let pdfs = generate_pdfs_from_groups(vec![group(3)], options.clone(), None).unwrap();
+        assert_eq!(pdfs.len(), 1);
+
+        // A file per group, each still split by pages
+        let options = PdfOptions {
+            section_split: SectionSplit::OwnFiles,
+            ..options
+        };
+        let pdfs =
+            generate_pdfs_from_groups(vec![group(3), group(1)], options.clone(), Some(2)).unwrap();
+        assert_eq!(pdfs.len(), 3);
+        let pdfs = generate_pdfs_from_groups(Vec::new(), options, None).unwrap();
+        assert_eq!(pdfs.len(), 1);
+    }
+
+    #[test]
+    fn test_captions_take_vertical_space() {
+        let options = PdfOptions {
+            page_size: PageSize::Letter,
+            margin: 5.0,
+            ..Default::default()
+        };
+        assert!(options.validate().is_ok());
+
+        let options = PdfOptions {
+            card_captions: true,
+            ..options
+        };
+        assert!(options.validate().is_err());
+    }
+
+    #[test]
+    fn test_generate_pdf_a() {
+        use printpdf::image_crate::RgbaImage;
+        use printpdf::lopdf::{Document, Object};
+
+        let transparent = DynamicImage::ImageRgba8(RgbaImage::new(IMAGE_WIDTH, IMAGE_HEIGHT));
+        let options = PdfOptions {
+            pdf_a: true,
+            cut_markers: true,
+            ..Default::default()
+        };
+        let pdf_data = generate_pdf(vec![transparent].into_iter(), options).unwrap();
+
+        let doc = Document::load_mem(&pdf_data).unwrap();
+        assert_eq!(doc.version, "1.4");
+        assert!(doc.trailer.get(b"ID").is_ok());
+
+        let root = doc.trailer.get(b"Root").unwrap().as_reference().unwrap();
+        let catalog = doc.get_object(root).unwrap().as_dict().unwrap();
+        assert!(catalog.get(b"OCProperties").is_err());
+
+        let metadata_id = catalog.get(b"Metadata").unwrap().as_reference().unwrap();
+        let metadata = doc.get_object(metadata_id).unwrap().as_stream().unwrap();
+        let xmp = String::from_utf8_lossy(&metadata.content);
+        assert!(xmp.contains("<pdfaid:part>1</pdfaid:part>"));
+        assert!(xmp.contains(PDF_TITLE));
+
+        let intents = catalog.get(b"OutputIntents").unwrap().as_array().unwrap();
+        assert_eq!(intents.len(), 1);
+        let intent = doc
+            .get_object(intents[0].as_reference().unwrap())
+            .unwrap()
+            .as_dict()
+            .unwrap();
+        assert_eq!(intent.get(b"S").unwrap().as_name().unwrap(), b"GTS_PDFA1");
+        let profile_id = intent
+            .get(b"DestOutputProfile")
+            .unwrap()
+            .as_reference()
+            .unwrap();
+        let profile = doc.get_object(profile_id).unwrap().as_stream().unwrap();
+        assert_eq!(profile.dict.get(b"N").unwrap().as_i64().unwrap(), 3);
+        assert_eq!(&profile.content[36..40], b"acsp");
+
+        // Transparency was flattened before embedding, so no image has a soft mask
+        for object in doc.objects.values() {
+            if let Object::Stream(stream) = object {
+                assert!(stream.dict.get(b"SMask").is_err());
+            }
+        }
+    }
+
+    #[test]
+    fn test_pdf_deck_hash() {
+        let image = || DynamicImage::new_rgb8(IMAGE_WIDTH, IMAGE_HEIGHT);
+        let pdf = generate_pdf(std::iter::once(image()), PdfOptions::default()).unwrap();
+        assert_eq!(pdf_deck_hash(&pdf), None);
+
+        for pdf_a in [false, true] {
+            let options = PdfOptions {
+                pdf_a,
+                deck_hash: Some("0123abcd".to_string()),
+                ..Default::default()
+            };
+            let pdf = generate_pdf(std::iter::once(image()), options).unwrap();
+            assert_eq!(pdf_deck_hash(&pdf).as_deref(), Some("0123abcd"));
+        }
+        assert_eq!(pdf_deck_hash(b"not a pdf"), None);
+    }
+
+    #[test]
+    fn test_group_slots_and_pages() {
+        let options = PdfOptions {
+            group_dividers: true,
+            ..Default::default()
+        };
+        assert_eq!(options.group_slots(3, &[]), (vec![0, 1, 2], vec![]));
+        // A divider starts a new row and takes all of it
+        assert_eq!(options.group_slots(3, &[1]), (vec![0, 6, 7], vec![3]));
+        // One that would take the last row of a page moves to the next page
+        assert_eq!(
+            options.group_slots(5, &[0, 2]),
+            (vec![3, 4, 12, 13, 14], vec![0, 9])
+        );
+        let single_row = PdfOptions {
+            cards_per_column: 1,
+            ..options.clone()
+        };
+        assert_eq!(single_row.group_slots(2, &[0, 1]), (vec![0, 1], vec![]));
+
+        let labels = [
+            None,
+            Some("ramp"),
+            Some("ramp"),
+            Some("lands"),
+            None,
+            Some("lands"),
+        ];
+        assert_eq!(
+            divider_labels(labels.map(|label| label.map(String::from))),
+            vec![
+                (1, "ramp".to_string()),
+                (3, "lands".to_string()),
+                (5, "lands".to_string())
+            ]
+        );
+
+        let group: Vec<CaptionedImage> = (0..5)
+            .map(|index| CaptionedImage {
+                image: create_test_image(),
+                caption: String::new(),
+                divider: match index {
+                    0 => Some("lands".to_string()),
+                    2 => Some("ramp".to_string()),
+                    _ => None,
+                },
+            })
+            .collect();
+        let pages = group_pages(group, &options);
+        let layout: Vec<(Vec<usize>, Vec<(usize, String)>)> = pages
+            .iter()
+            .map(|page| {
+                let slots = page.items.iter().map(|(slot, _)| *slot).collect();
+                (slots, page.dividers.clone())
+            })
+            .collect();
+        assert_eq!(
+            layout,
+            vec![
+                (vec![3, 4], vec![(0, "lands".to_string())]),
+                (vec![3, 4, 5], vec![(0, "ramp".to_string())])
+            ]
+        );
+
+        let pdf = generate_pdf_from_pages(pages.into_iter(), options.clone()).unwrap();
+        assert_eq!(&pdf[0..4], b"%PDF");
+        let pdf_a = PdfOptions {
+            pdf_a: true,
+            ..options
+        };
+        assert!(pdf_a.validate().is_err());
+    }
+
+    #[test]
+    fn test_pdf_a_rejects_captions() {
+        let options = PdfOptions {
+            card_captions: true,
+            cards_per_column: 2,
+            ..Default::default()
+        };
+        assert!(options.validate().is_ok());
+
+        let options = PdfOptions {
+            pdf_a: true,
+            ..options
+        };
+        assert!(options.validate().is_err());
+    }
 }
@@ -0,0 +1,338 @@
+use crate::error::ProxyError;
+use printpdf::image_crate::{DynamicImage, Rgb, RgbImage};
+use printpdf::lopdf::{Dictionary, Document, Object, ObjectId, Stream, StringFormat};
+use sha2::{Digest, Sha256};
+use time::OffsetDateTime;
+
+const SRGB_PROFILE_NAME: &str = "sRGB IEC61966-2.1";
+const PRODUCER: &str = "LocalHawk";
+
+// Entries in the sampled sRGB tone curve of the generated ICC profile
+const TRC_SAMPLES: usize = 1024;
+
+/// Composite images with an alpha channel onto white, since PDF/A-1 forbids transparency
+pub(crate) fn flatten_transparency(image: DynamicImage) -> DynamicImage {
+    if !image.color().has_alpha() {
+        return image;
+    }
+    let rgba = image.to_rgba8();
+    let flattened = RgbImage::from_fn(rgba.width(), rgba.height(), |x, y| {
+        let pixel = rgba.get_pixel(x, y);
+        let alpha = pixel[3] as u32;
+        Rgb([0, 1, 2].map(|channel| {
+            ((pixel[channel] as u32 * alpha + 255 * (255 - alpha) + 127) / 255) as u8
+        }))
+    });
+    DynamicImage::ImageRgb8(flattened)
+}
+
+/// Turn a generated PDF into a PDF/A-1b compatible one
+///
+/// Adds an sRGB output intent with an embedded ICC profile, XMP metadata matching the
+/// document info, and a file identifier. The keywords, which hold the deck hash, are kept;
+/// optional content (printpdf's layers) and image interpolation, which PDF/A-1 doesn't allow,
+/// are removed.
+pub(crate) fn convert_to_pdf_a(pdf: &[u8], title: &str) -> Result<Vec<u8>, ProxyError> {
+    let mut doc = Document::load_mem(pdf).map_err(pdf_error)?;
+    doc.version = "1.4".to_string();
+
+    let keywords = doc
+        .trailer
+        .get(b"Info")
+        .and_then(Object::as_reference)
+        .and_then(|info| doc.get_object(info))
+        .and_then(Object::as_dict)
+        .and_then(|info| info.get(b"Keywords"))
+        .and_then(Object::as_str)
+        .ok()
+        .map(|keywords| String::from_utf8_lossy(keywords).to_string());
+
+    let now = OffsetDateTime::now_utc();
+    let info = doc.add_object(info_dictionary(title, keywords.as_deref(), now));
+    let metadata = doc.add_object(Stream::new(
+        metadata_dictionary(),
+        xmp_metadata(title, keywords.as_deref(), now).into_bytes(),
+    ));
+    let profile = doc.add_object(Stream::new(icc_dictionary(), srgb_icc_profile()));
+    let output_intent = doc.add_object(output_intent_dictionary(profile));
+
+    let root = doc
+        .trailer
+        .get(b"Root")
+        .and_then(Object::as_reference)
+        .map_err(pdf_error)?;
+    let catalog = doc
+        .get_object_mut(root)
+        .and_then(Object::as_dict_mut)
+        .map_err(pdf_error)?;
+    catalog.set("Metadata", Object::Reference(metadata));
+    catalog.set(
+        "OutputIntents",
+        Object::Array(vec![Object::Reference(output_intent)]),
+    );
+    catalog.remove(b"OCProperties");
+
+    let images = doc.objects.values_mut().filter_map(|object| match object {
+        Object::Stream(stream) if is_name(stream.dict.get(b"Subtype"), b"Image") => Some(stream),
+        _ => None,
+    });
+    for image in images {
+        image.dict.remove(b"Interpolate");
+    }
+
+    let id = Sha256::digest(pdf)[..16].to_vec();
+    doc.trailer.set("Info", Object::Reference(info));
+    doc.trailer.set(
+        "ID",
+        Object::Array(vec![
+            Object::String(id.clone(), StringFormat::Hexadecimal),
+            Object::String(id, StringFormat::Hexadecimal),
+        ]),
+    );
+
+    let mut bytes = Vec::new();
+    doc.save_to(&mut bytes)
+        .map_err(|e| ProxyError::Pdf(format!("Failed to save PDF/A: {}", e)))?;
+    Ok(bytes)
+}
+
+fn pdf_error(e: printpdf::lopdf::Error) -> ProxyError {
+    ProxyError::Pdf(format!("Failed to convert PDF to PDF/A: {}", e))
+}
+
+fn is_name(object: Result<&Object, printpdf::lopdf::Error>, name: &[u8]) -> bool {
+    matches!(object, Ok(Object::Name(value)) if value == name)
+}
+
+fn name(value: &str) -> Object {
+    Object::Name(value.as_bytes().to_vec())
+}
+
+fn text(value: &str) -> Object {
+    Object::String(value.as_bytes().to_vec(), StringFormat::Literal)
+}
+
+fn info_dictionary(title: &str, keywords: Option<&str>, now: OffsetDateTime) -> Dictionary {
+    let date = format!(
+        "D:{:04}{:02}{:02}{:02}{:02}{:02}+00'00'",
+        now.year(),
+        u8::from(now.month()),
+        now.day(),
+        now.hour(),
+        now.minute(),
+        now.second()
+    );
+    let mut info = Dictionary::new();
+    info.set("Title", text(title));
+    info.set("Producer", text(PRODUCER));
+    info.set("Creator", text(PRODUCER));
+    info.set("CreationDate", text(&date));
+    info.set("ModDate", text(&date));
+    if let Some(keywords) = keywords {
+        info.set("Keywords", text(keywords));
+    }
+    info
+}
+
+fn metadata_dictionary() -> Dictionary {
+    let mut dict = Dictionary::new();
+    dict.set("Type", name("Metadata"));
+    dict.set("Subtype", name("XML"));
+    dict
+}
+
+fn icc_dictionary() -> Dictionary {
+    let mut dict = Dictionary::new();
+    dict.set("N", Object::Integer(3));
+    dict.set("Alternate", name("DeviceRGB"));
+    dict
+}
+
+fn output_intent_dictionary(profile: ObjectId) -> Dictionary {
+    let mut dict = Dictionary::new();
+    dict.set("Type", name("OutputIntent"));
+    dict.set("S", name("GTS_PDFA1"));
+    dict.set("OutputConditionIdentifier", text(SRGB_PROFILE_NAME));
+    dict.set("Info", text(SRGB_PROFILE_NAME));
+    dict.set("DestOutputProfile", Object::Reference(profile));
+    dict
+}
+
+/// XMP packet declaring PDF/A-1b conformance, with the same title, producer, dates and
+/// keywords as the document info dictionary
+fn xmp_metadata(title: &str, keywords: Option<&str>, now: OffsetDateTime) -> String {
+    let date = format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}+00:00",
+        now.year(),
+        u8::from(now.month()),
+        now.day(),
+        now.hour(),
+        now.minute(),
+        now.second()
+    );
+    let escape = |value: &str| {
+        value
+            .replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+    };
+    let title = escape(title);
+    let keywords = keywords
+        .map(|keywords| format!("\n<pdf:Keywords>{}</pdf:Keywords>", escape(keywords)))
+        .unwrap_or_default();
+    format!(
+        r#"<?xpacket begin="" id="W5M0MpCehiHzreSzNTczkc9d"?>
+<x:xmpmeta xmlns:x="adobe:ns:meta/">
+<rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#">
+<rdf:Description rdf:about="" xmlns:pdfaid="http://www.aiim.org/pdfa/ns/id/">
+<pdfaid:part>1</pdfaid:part>
+<pdfaid:conformance>B</pdfaid:conformance>
+</rdf:Description>
+<rdf:Description rdf:about="" xmlns:dc="http://purl.org/dc/elements/1.1/">
+<dc:format>application/pdf</dc:format>
+<dc:title><rdf:Alt><rdf:li xml:lang="x-default">{title}</rdf:li></rdf:Alt></dc:title>
+</rdf:Description>
+<rdf:Description rdf:about="" xmlns:xmp="http://ns.adobe.com/xap/1.0/">
+<xmp:CreatorTool>{PRODUCER}</xmp:CreatorTool>
+<xmp:CreateDate>{date}</xmp:CreateDate>
+<xmp:ModifyDate>{date}</xmp:ModifyDate>
+</rdf:Description>
+<rdf:Description rdf:about="" xmlns:pdf="http://ns.adobe.com/pdf/1.3/">
+<pdf:Producer>{PRODUCER}</pdf:Producer>{keywords}
+</rdf:Description>
+</rdf:RDF>
+</x:xmpmeta>
+<?xpacket end="w"?>"#
+    )
+}
+
+/// Build an ICC v2 display profile for sRGB (D50-adapted primaries, sampled tone curve)
+fn srgb_icc_profile() -> Vec<u8> {
+    let s15_fixed16 = |value: f64| ((value * 65536.0).round() as i32).to_be_bytes();
+    let xyz = |x: f64, y: f64, z: f64| {
+        let mut data = b"XYZ \0\0\0\0".to_vec();
+        for value in [x, y, z] {
+            data.extend_from_slice(&s15_fixed16(value));
+        }
+        data
+    };
+
+    let mut description = b"desc\0\0\0\0".to_vec();
+    description.extend_from_slice(&(SRGB_PROFILE_NAME.len() as u32 + 1).to_be_bytes());
+    description.extend_from_slice(SRGB_PROFILE_NAME.as_bytes());
+    description.push(0);
+    // Empty Unicode and ScriptCode descriptions
+    description.extend_from_slice(&[0; 8]);
+    description.extend_from_slice(&[0; 3]);
+    description.extend_from_slice(&[0; 67]);
+
+    let mut copyright = b"text\0\0\0\0".to_vec();
+    copyright.extend_from_slice(b"No copyright, use freely\0");
+
+    let mut tone_curve = b"curv\0\0\0\0".to_vec();
+    tone_curve.extend_from_slice(&(TRC_SAMPLES as u32).to_be_bytes());
+    for sample in 0..TRC_SAMPLES {
+        let encoded = sample as f64 / (TRC_SAMPLES - 1) as f64;
+        let linear = if encoded <= 0.04045 {
+            encoded / 12.92
+        } else {
+            ((encoded + 0.055) / 1.055).powf(2.4)
+        };
+        tone_curve.extend_from_slice(&((linear * 65535.0).round() as u16).to_be_bytes());
+    }
+
+    // The three channels share one tone curve
+    let tags: [(&[u8; 4], usize); 9] = [
+        (b"desc", 0),
+        (b"cprt", 1),
+        (b"wtpt", 2),
+        (b"rXYZ", 3),
+        (b"gXYZ", 4),
+        (b"bXYZ", 5),
+        (b"rTRC", 6),
+        (b"gTRC", 6),
+        (b"bTRC", 6),
+    ];
+    let tag_data = [
+        description,
+        copyright,
+        xyz(0.9642, 1.0, 0.8249),
+        xyz(0.4360747, 0.2225045, 0.0139322),
+        xyz(0.3850649, 0.7168786, 0.0971045),
+        xyz(0.1430804, 0.0606169, 0.7141733),
+        tone_curve,
+    ];
+
+    // Tag data follows the header and tag table, each element starting on a 4-byte boundary
+    let mut offsets = Vec::new();
+    let mut offset = 128 + 4 + 12 * tags.len();
+    for data in &tag_data {
+        offsets.push(offset);
+        offset += data.len().next_multiple_of(4);
+    }
+    let profile_size = offset;
+
+    let mut profile = Vec::with_capacity(profile_size);
+    profile.extend_from_slice(&(profile_size as u32).to_be_bytes());
+    profile.extend_from_slice(&[0; 4]); // Preferred CMM
+    profile.extend_from_slice(&[2, 0x10, 0, 0]); // Version 2.1
+    profile.extend_from_slice(b"mntrRGB XYZ ");
+    for part in [2024u16, 1, 1, 0, 0, 0] {
+        profile.extend_from_slice(&part.to_be_bytes());
+    }
+    profile.extend_from_slice(b"acsp");
+    profile.extend_from_slice(&[0; 24]); // Platform, flags, manufacturer, model, attributes
+    profile.extend_from_slice(&[0; 4]); // Perceptual rendering intent
+    for value in [0.9642, 1.0, 0.8249] {
+        profile.extend_from_slice(&s15_fixed16(value)); // D50 illuminant
+    }
+    profile.resize(128, 0); // Creator, profile ID and reserved bytes
+
+    profile.extend_from_slice(&(tags.len() as u32).to_be_bytes());
+    for (signature, data_index) in tags {
+        profile.extend_from_slice(signature);
+        profile.extend_from_slice(&(offsets[data_index] as u32).to_be_bytes());
+        profile.extend_from_slice(&(tag_data[data_index].len() as u32).to_be_bytes());
+    }
+    for data in &tag_data {
+        profile.extend_from_slice(data);
+        profile.resize(profile.len().next_multiple_of(4), 0);
+    }
+    profile
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use printpdf::image_crate::RgbaImage;
+
+    #[test]
+    fn test_srgb_icc_profile_structure() {
+        let profile = srgb_icc_profile();
+        let read_u32 = |at: usize| u32::from_be_bytes(profile[at..at + 4].try_into().unwrap());
+
+        assert_eq!(read_u32(0) as usize, profile.len());
+        assert_eq!(&profile[12..24], b"mntrRGB XYZ ");
+        assert_eq!(&profile[36..40], b"acsp");
+
+        let tag_count = read_u32(128) as usize;
+        assert_eq!(tag_count, 9);
+        for tag in 0..tag_count {
+            let entry = 132 + 12 * tag;
+            let (offset, size) = (read_u32(entry + 4) as usize, read_u32(entry + 8) as usize);
+            assert_eq!(offset % 4, 0);
+            assert!(offset + size <= profile.len());
+        }
+    }
+
+    #[test]
+    fn test_flatten_transparency_composites_onto_white() {
+        let mut image = RgbaImage::new(2, 1);
+        image.put_pixel(0, 0, printpdf::image_crate::Rgba([0, 0, 0, 0]));
+        image.put_pixel(1, 0, printpdf::image_crate::Rgba([10, 20, 30, 255]));
+
+        let flattened = flatten_transparency(DynamicImage::ImageRgba8(image)).to_rgb8();
+        assert_eq!(flattened.get_pixel(0, 0), &Rgb([255, 255, 255]));
+        assert_eq!(flattened.get_pixel(1, 0), &Rgb([10, 20, 30]));
+    }
+}
@@ -0,0 +1,351 @@
+use super::{
+    CUT_MARKER_THICKNESS, CaptionedImage, DIVIDER_RULE_THICKNESS, GroupPage, PdfOptions,
+    cut_marker_segments, group_pages, pdf_a,
+};
+use crate::error::ProxyError;
+use printpdf::image_crate::imageops::{self, FilterType};
+use printpdf::image_crate::{DynamicImage, ImageOutputFormat, Rgb, RgbImage};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, debug_span};
+
+const MIN_RENDER_DPI: f32 = 10.0;
+const MAX_RENDER_DPI: f32 = 600.0;
+const MM_PER_INCH: f64 = 25.4;
+const POINTS_PER_INCH: f64 = 72.0;
+
+/// File format of rendered page images
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PageImageFormat {
+    Png,
+    /// JPEG with the given quality (1-100); much smaller files for sharing
+    Jpeg {
+        quality: u8,
+    },
+}
+
+/// Resolution and file format of rendered page images
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RenderOptions {
+    pub dpi: f32,
+    pub format: PageImageFormat,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        RenderOptions {
+            dpi: 150.0,
+            format: PageImageFormat::Png,
+        }
+    }
+}
+
+impl RenderOptions {
+    pub fn validate(&self) -> Result<(), ProxyError> {
+        if !(MIN_RENDER_DPI..=MAX_RENDER_DPI).contains(&self.dpi) {
+            return Err(ProxyError::Pdf(format!(
+                "Render resolution must be between {} and {} DPI",
+                MIN_RENDER_DPI, MAX_RENDER_DPI
+            )));
+        }
+        if let PageImageFormat::Jpeg { quality: 0 | 101.. } = self.format {
+            return Err(ProxyError::Pdf(
+                "JPEG quality must be between 1 and 100".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    fn px_per_mm(&self) -> f64 {
+        self.dpi as f64 / MM_PER_INCH
+    }
+}
+
+/// Render card images into page images laid out exactly like `generate_pdf` would,
+/// one encoded image per page
+pub fn render_pages<I>(
+    images: I,
+    options: PdfOptions,
+    render_options: RenderOptions,
+) -> Result<Vec<Vec<u8>>, ProxyError>
+where
+    I: Iterator<Item = DynamicImage>,
+{
+    let images = images.map(|image| CaptionedImage {
+        image,
+        caption: String::new(),
+        divider: None,
+    });
+    let pages = group_pages(images.collect(), &options);
+    render_pages_from_pages(pages.into_iter(), options, render_options)
+}
+
+/// Render groups of captioned images into page images, starting each group on a new page
+///
+/// Caption strips and divider labels are left blank: there is no font to rasterize them with.
+pub fn render_pages_from_groups<G>(
+    groups: G,
+    options: PdfOptions,
+    render_options: RenderOptions,
+) -> Result<Vec<Vec<u8>>, ProxyError>
+where
+    G: IntoIterator<Item = Vec<CaptionedImage>>,
+{
+    let pages: Vec<_> = groups
+        .into_iter()
+        .flat_map(|group| group_pages(group, &options))
+        .collect();
+    render_pages_from_pages(pages.into_iter(), options, render_options)
+}
+
+fn render_pages_from_pages<P>(
+    pages: P,
+    options: PdfOptions,
+    render_options: RenderOptions,
+) -> Result<Vec<Vec<u8>>, ProxyError>
+where
+    P: Iterator<Item = GroupPage<CaptionedImage>>,
+{
+    options.validate()?;
+    render_options.validate()?;
+    let _span = debug_span!("render_pages", dpi = render_options.dpi).entered();
+
+    pages
+        .enumerate()
+        .map(|(page_index, page)| {
+            debug!(
+                page = page_index + 1,
+                cards = page.items.len(),
+                "Rendering page"
+            );
+            let canvas = render_page(page, &options, &render_options);
+            encode_page(canvas, render_options.format)
+        })
+        .collect()
+}
+
+fn render_page(
+    page: GroupPage<CaptionedImage>,
+    options: &PdfOptions,
+    render_options: &RenderOptions,
+) -> RgbImage {
+    let px_per_mm = render_options.px_per_mm();
+    let (page_width_mm, page_height_mm) = options.page_size.dimensions_mm();
+    let (page_width_mm, page_height_mm) = (page_width_mm as f64, page_height_mm as f64);
+    let to_px = |mm: f64| (mm * px_per_mm).round();
+
+    let mut canvas = RgbImage::from_pixel(
+        to_px(page_width_mm) as u32,
+        to_px(page_height_mm) as u32,
+        Rgb([255, 255, 255]),
+    );
+
+    let card_width_px = to_px(options.card_width_mm() as f64) as u32;
+    let card_height_px = to_px(options.card_height_mm() as f64) as u32;
+    for (card_index, captioned) in page.items {
+        let slot = options.card_slot(card_index);
+        let card_top_mm = slot.bottom_mm as f64 + options.card_height_mm() as f64;
+
+        // Image quality only matters for the embedded PDF images; pages are resampled anyway
+        let card = pdf_a::flatten_transparency(captioned.image)
+            .resize_exact(card_width_px, card_height_px, FilterType::Triangle)
+            .to_rgb8();
+        imageops::replace(
+            &mut canvas,
+            &card,
+            to_px(slot.left_mm as f64) as i64,
+            to_px(page_height_mm - card_top_mm) as i64,
+        );
+    }
+
+    let half_divider_px =
+        DIVIDER_RULE_THICKNESS / POINTS_PER_INCH * render_options.dpi as f64 / 2.0;
+    for (index, _label) in page.dividers {
+        let ((x0, y), (x1, _)) = options.divider_rule(index);
+        fill_black(
+            &mut canvas,
+            (x0 * px_per_mm, x1 * px_per_mm),
+            (
+                (page_height_mm - y) * px_per_mm - half_divider_px,
+                (page_height_mm - y) * px_per_mm + half_divider_px,
+            ),
+        );
+    }
+
+    if options.cut_markers {
+        let half_thickness_px =
+            CUT_MARKER_THICKNESS / POINTS_PER_INCH * render_options.dpi as f64 / 2.0;
+        for ((x0, y0), (x1, y1)) in cut_marker_segments(options) {
+            fill_black(
+                &mut canvas,
+                (
+                    x0.min(x1) * px_per_mm - half_thickness_px,
+                    x0.max(x1) * px_per_mm + half_thickness_px,
+                ),
+                (
+                    (page_height_mm - y0.max(y1)) * px_per_mm - half_thickness_px,
+                    (page_height_mm - y0.min(y1)) * px_per_mm + half_thickness_px,
+                ),
+            );
+        }
+    }
+
+    canvas
+}
+
+/// Fill the pixels covered by the given x and y ranges, keeping thin lines at least a pixel wide
+fn fill_black(canvas: &mut RgbImage, x: (f64, f64), y: (f64, f64)) {
+    let to_range = |(from, to): (f64, f64), limit: u32| {
+        let start = from.round().clamp(0.0, limit as f64);
+        let end = to.round().max(from.round() + 1.0).clamp(0.0, limit as f64);
+        start as u32..end as u32
+    };
+
+    for py in to_range(y, canvas.height()) {
+        for px in to_range(x, canvas.width()) {
+            canvas.put_pixel(px, py, Rgb([0, 0, 0]));
+        }
+    }
+}
+
+fn encode_page(canvas: RgbImage, format: PageImageFormat) -> Result<Vec<u8>, ProxyError> {
+    let output_format = match format {
+        PageImageFormat::Png => ImageOutputFormat::Png,
+        PageImageFormat::Jpeg { quality } => ImageOutputFormat::Jpeg(quality),
+    };
+
+    let mut bytes = Vec::new();
+    DynamicImage::ImageRgb8(canvas)
+        .write_to(&mut std::io::Cursor::new(&mut bytes), output_format)
+        .map_err(|e| ProxyError::Pdf(format!("Failed to encode page image: {}", e)))?;
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::{IMAGE_HEIGHT, IMAGE_WIDTH};
+
+    fn red_card() -> DynamicImage {
+        DynamicImage::ImageRgb8(RgbImage::from_pixel(
+            IMAGE_WIDTH,
+            IMAGE_HEIGHT,
+            Rgb([200, 0, 0]),
+        ))
+    }
+
+    fn low_res() -> RenderOptions {
+        RenderOptions {
+            dpi: 50.0,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_render_pages_places_cards_like_the_pdf() {
+        let options = PdfOptions::default();
+        let pages = render_pages(
+            vec![red_card(), red_card()].into_iter(),
+            options.clone(),
+            low_res(),
+        )
+        .unwrap();
+        assert_eq!(pages.len(), 1);
+        assert_eq!(&pages[0][1..4], b"PNG");
+
+        let page = printpdf::image_crate::load_from_memory(&pages[0])
+            .unwrap()
+            .to_rgb8();
+        // A4 at 50 DPI
+        assert_eq!(page.dimensions(), (413, 585));
+        assert_eq!(*page.get_pixel(0, 0), Rgb([255, 255, 255]));
+
+        let px_per_mm = low_res().px_per_mm();
+        let card_center = |index: usize| {
+            let slot = options.card_slot(index);
+            let x = slot.left_mm as f64 + options.card_width_mm() as f64 / 2.0;
+            let y = 297.0 - (slot.bottom_mm as f64 + options.card_height_mm() as f64 / 2.0);
+            ((x * px_per_mm) as u32, (y * px_per_mm) as u32)
+        };
+        let (x, y) = card_center(1);
+        assert_eq!(*page.get_pixel(x, y), Rgb([200, 0, 0]));
+        // The third slot stays empty
+        let (x, y) = card_center(2);
+        assert_eq!(*page.get_pixel(x, y), Rgb([255, 255, 255]));
+    }
+
+    #[test]
+    fn test_render_pages_draws_cut_markers() {
+        let options = PdfOptions {
+            cut_markers: true,
+            ..Default::default()
+        };
+        let render_options = RenderOptions {
+            dpi: 100.0,
+            ..Default::default()
+        };
+        let pages = render_pages(
+            vec![red_card()].into_iter(),
+            options.clone(),
+            render_options,
+        )
+        .unwrap();
+        let page = printpdf::image_crate::load_from_memory(&pages[0])
+            .unwrap()
+            .to_rgb8();
+
+        // Middle of the marker below the left grid edge
+        let ((x, y0), (_, y1)) = cut_marker_segments(&options)[0];
+        let px_per_mm = render_options.px_per_mm();
+        let (x, y) = (x * px_per_mm, (297.0 - (y0 + y1) / 2.0) * px_per_mm);
+        let has_black_pixel = [-1.0, 0.0, 1.0].into_iter().any(|dx: f64| {
+            *page.get_pixel((x + dx).round() as u32, y.round() as u32) == Rgb([0, 0, 0])
+        });
+        assert!(has_black_pixel);
+    }
+
+    #[test]
+    fn test_render_groups_start_new_pages() {
+        let captioned = || CaptionedImage {
+            image: red_card(),
+            caption: "Lightning Bolt (LEA) 161".to_string(),
+            divider: None,
+        };
+        let pages = render_pages_from_groups(
+            vec![vec![captioned(), captioned()], vec![captioned()]],
+            PdfOptions {
+                card_captions: true,
+                ..Default::default()
+            },
+            RenderOptions {
+                dpi: 30.0,
+                format: PageImageFormat::Jpeg { quality: 80 },
+            },
+        )
+        .unwrap();
+        assert_eq!(pages.len(), 2);
+        assert!(pages.iter().all(|page| page.starts_with(&[0xFF, 0xD8])));
+    }
+
+    #[test]
+    fn test_render_options_validation() {
+        assert!(RenderOptions::default().validate().is_ok());
+        let too_fine = RenderOptions {
+            dpi: 2400.0,
+            ..Default::default()
+        };
+        assert!(
+            render_pages(
+                vec![red_card()].into_iter(),
+                PdfOptions::default(),
+                too_fine
+            )
+            .is_err()
+        );
+        let bad_jpeg = RenderOptions {
+            format: PageImageFormat::Jpeg { quality: 0 },
+            ..Default::default()
+        };
+        assert!(bad_jpeg.validate().is_err());
+    }
+}
@@ -0,0 +1,374 @@
+//! Text-only proxies: the card text in a plain frame, for playtesting without any card images
+
+use super::{
+    PDF_TITLE, PdfOptions, SectionSplit, add_cut_markers, images_to_pages, with_deck_hash,
+};
+use crate::error::ProxyError;
+use crate::scryfall::models::Card;
+use printpdf::{
+    BuiltinFont, Color, IndirectFontRef, Line, Mm, PdfDocument, PdfLayerReference, Point, Rgb,
+};
+use tracing::{debug_span, info};
+
+const FRAME_INSET_MM: f64 = 1.5;
+const FRAME_THICKNESS: f64 = 1.0;
+const TEXT_INSET_MM: f64 = 4.0;
+const NAME_FONT_SIZE: f64 = 9.0;
+const TYPE_FONT_SIZE: f64 = 7.0;
+const TEXT_FONT_SIZE: f64 = 6.5;
+const LINE_SPACING: f64 = 1.3;
+const PT_TO_MM: f64 = 25.4 / 72.0;
+/// Average Helvetica character width relative to the font size, to wrap lines without metrics
+const AVERAGE_CHAR_WIDTH: f64 = 0.5;
+
+/// Words kept lowercase inside card names, e.g. "Sword of Fire and Ice"
+const LOWERCASE_WORDS: [&str; 12] = [
+    "a", "an", "and", "at", "for", "from", "in", "into", "of", "on", "the", "to",
+];
+
+/// What a text-only proxy shows of a card
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextProxy {
+    pub name: String,
+    pub mana_cost: Option<String>,
+    pub type_line: Option<String>,
+    pub oracle_text: Option<String>,
+    pub power_toughness: Option<String>,
+}
+
+impl TextProxy {
+    /// The text of every face of `card`; double-faced cards get both faces on one proxy
+    pub fn from_card(card: &Card) -> Self {
+        TextProxy {
+            name: title_case(&card.name),
+            mana_cost: card.mana_cost.clone(),
+            type_line: card.type_line.clone(),
+            oracle_text: card.oracle_text.clone(),
+            power_toughness: card.power_toughness.clone(),
+        }
+    }
+}
+
+/// Capitalize a lowercased card name, e.g. "sword of fire and ice" -> "Sword of Fire and Ice"
+fn title_case(name: &str) -> String {
+    let mut words = Vec::new();
+    for (index, word) in name.split(' ').enumerate() {
+        // Every face name starts with a capital, e.g. "Fire // Ice"
+        let starts_name = index == 0 || words.last().map(String::as_str) == Some("//");
+        if !starts_name && LOWERCASE_WORDS.contains(&word) {
+            words.push(word.to_string());
+            continue;
+        }
+        let mut chars = word.chars();
+        words.push(match chars.next() {
+            Some(first) => first.to_uppercase().chain(chars).collect(),
+            None => String::new(),
+        });
+    }
+    words.join(" ")
+}
+
+/// Break `text` into lines of at most `max_chars` characters at spaces, keeping its line breaks
+///
+/// Words longer than a line get a line of their own.
+pub(crate) fn wrap_text(text: &str, max_chars: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    for paragraph in text.lines() {
+        let mut line = String::new();
+        for word in paragraph.split_whitespace() {
+            let fits = line.chars().count() + 1 + word.chars().count() <= max_chars;
+            if !line.is_empty() && !fits {
+                lines.push(std::mem::take(&mut line));
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(word);
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+/// Characters of `font_size` that fit in `width_mm`
+fn chars_per_line(width_mm: f64, font_size: f64) -> usize {
+    (width_mm / (font_size * AVERAGE_CHAR_WIDTH * PT_TO_MM)).floor() as usize
+}
+
+fn line_height_mm(font_size: f64) -> f64 {
+    font_size * LINE_SPACING * PT_TO_MM
+}
+
+/// Generate one PDF per `pages_per_file` pages of text-only proxies, starting each group on a
+/// new page (a single PDF if `pages_per_file` is None); downloads nothing
+///
+/// With [`SectionSplit::OwnFiles`] each group also starts a new file.
+pub fn generate_text_pdfs_from_groups<G>(
+    groups: G,
+    options: PdfOptions,
+    pages_per_file: Option<usize>,
+) -> Result<Vec<Vec<u8>>, ProxyError>
+where
+    G: IntoIterator<Item = Vec<TextProxy>>,
+{
+    let cards_per_page = options.cards_per_page() as u32;
+    let group_pages = groups
+        .into_iter()
+        .map(|group| images_to_pages(group.into_iter(), cards_per_page).collect::<Vec<_>>());
+    let file_groups: Vec<Vec<Vec<TextProxy>>> = match options.section_split {
+        SectionSplit::OwnFiles => group_pages.collect(),
+        _ => vec![group_pages.flatten().collect()],
+    };
+    let pages_per_file = pages_per_file.filter(|pages| *pages > 0);
+
+    let mut pdfs = Vec::new();
+    for pages in file_groups {
+        let mut pages = pages.into_iter().peekable();
+        while pages.peek().is_some() {
+            let file_pages: Vec<_> = match pages_per_file {
+                Some(pages_per_file) => pages.by_ref().take(pages_per_file).collect(),
+                None => pages.by_ref().collect(),
+            };
+            pdfs.push(generate_text_pdf_from_pages(file_pages, &options)?);
+        }
+    }
+    // Always at least one file, like a single PDF without cards
+    if pdfs.is_empty() {
+        pdfs.push(generate_text_pdf_from_pages(Vec::new(), &options)?);
+    }
+    Ok(pdfs)
+}
+
+fn generate_text_pdf_from_pages(
+    pages: Vec<Vec<TextProxy>>,
+    options: &PdfOptions,
+) -> Result<Vec<u8>, ProxyError> {
+    options.validate()?;
+    let _span = debug_span!("generate_text_pdf", pages = pages.len()).entered();
+
+    let (page_width_mm, page_height_mm) = options.page_size.dimensions_mm();
+    let (page_width, page_height) = (Mm(page_width_mm as f64), Mm(page_height_mm as f64));
+    let title = options.title.as_deref().unwrap_or(PDF_TITLE);
+    let (doc, page1, layer1) = PdfDocument::new(title, page_width, page_height, "Layer 1");
+    let doc = with_deck_hash(doc, options);
+    let font_error = |e| ProxyError::Pdf(format!("Failed to add font: {}", e));
+    let fonts = Fonts {
+        bold: doc
+            .add_builtin_font(BuiltinFont::HelveticaBold)
+            .map_err(font_error)?,
+        regular: doc
+            .add_builtin_font(BuiltinFont::Helvetica)
+            .map_err(font_error)?,
+        italic: doc
+            .add_builtin_font(BuiltinFont::HelveticaOblique)
+            .map_err(font_error)?,
+    };
+
+    let page_count = pages.len();
+    for (page_index, proxies) in pages.into_iter().enumerate() {
+        let (current_page, current_layer) = if page_index == 0 {
+            (page1, layer1)
+        } else {
+            doc.add_page(page_width, page_height, "Layer 1")
+        };
+        let layer = doc.get_page(current_page).get_layer(current_layer);
+
+        for (card_index, proxy) in proxies.iter().enumerate() {
+            let slot = options.card_slot(card_index);
+            draw_text_proxy(
+                &layer,
+                &fonts,
+                proxy,
+                (slot.left_mm as f64, slot.bottom_mm as f64),
+                options,
+            );
+        }
+
+        if options.cut_markers {
+            add_cut_markers(&layer, options);
+        }
+    }
+
+    let pdf = doc
+        .save_to_bytes()
+        .map_err(|e| ProxyError::Pdf(format!("Failed to save PDF: {}", e)))?;
+    info!(
+        pages = page_count,
+        bytes = pdf.len(),
+        "Generated text-only PDF"
+    );
+    Ok(pdf)
+}
+
+struct Fonts {
+    bold: IndirectFontRef,
+    regular: IndirectFontRef,
+    italic: IndirectFontRef,
+}
+
+/// Draw the frame and text of one proxy with its bottom left corner at `origin` (in mm)
+fn draw_text_proxy(
+    layer: &PdfLayerReference,
+    fonts: &Fonts,
+    proxy: &TextProxy,
+    origin: (f64, f64),
+    options: &PdfOptions,
+) {
+    let (left, bottom) = origin;
+    let width = options.card_width_mm() as f64;
+    let height = options.card_height_mm() as f64;
+    let (right, top) = (left + width, bottom + height);
+
+    layer.set_outline_color(Color::Rgb(Rgb::new(0.0, 0.0, 0.0, None)));
+    layer.set_outline_thickness(FRAME_THICKNESS);
+    layer.add_shape(frame(
+        (left + FRAME_INSET_MM, bottom + FRAME_INSET_MM),
+        (right - FRAME_INSET_MM, top - FRAME_INSET_MM),
+    ));
+
+    let text_left = left + TEXT_INSET_MM;
+    let text_width = width - 2.0 * TEXT_INSET_MM;
+    let mut baseline = top - TEXT_INSET_MM - NAME_FONT_SIZE * PT_TO_MM;
+
+    // Name on the left, mana cost on the right of the first line
+    let cost = proxy.mana_cost.as_deref().unwrap_or_default();
+    let cost_chars = cost.chars().count();
+    let name_chars = chars_per_line(text_width, NAME_FONT_SIZE).saturating_sub(cost_chars + 1);
+    for (index, line) in wrap_text(&proxy.name, name_chars).into_iter().enumerate() {
+        if index == 0 && !cost.is_empty() {
+            let cost_width = cost_chars as f64 * NAME_FONT_SIZE * AVERAGE_CHAR_WIDTH * PT_TO_MM;
+            let cost_left = left + width - TEXT_INSET_MM - cost_width;
+            layer.use_text(
+                cost,
+                NAME_FONT_SIZE,
+                Mm(cost_left),
+                Mm(baseline),
+                &fonts.regular,
+            );
+        }
+        layer.use_text(
+            line,
+            NAME_FONT_SIZE,
+            Mm(text_left),
+            Mm(baseline),
+            &fonts.bold,
+        );
+        baseline -= line_height_mm(NAME_FONT_SIZE);
+    }
+
+    let write = |text: &str, font_size: f64, font: &IndirectFontRef, baseline: &mut f64| {
+        for line in wrap_text(text, chars_per_line(text_width, font_size)) {
+            // Text that doesn't fit above the power/toughness line is cut off
+            if *baseline < bottom + TEXT_INSET_MM + line_height_mm(NAME_FONT_SIZE) {
+                return;
+            }
+            layer.use_text(line, font_size, Mm(text_left), Mm(*baseline), font);
+            *baseline -= line_height_mm(font_size);
+        }
+    };
+
+    if let Some(type_line) = &proxy.type_line {
+        baseline -= line_height_mm(TYPE_FONT_SIZE) / 2.0;
+        write(type_line, TYPE_FONT_SIZE, &fonts.italic, &mut baseline);
+    }
+    if let Some(oracle_text) = &proxy.oracle_text {
+        baseline -= line_height_mm(TEXT_FONT_SIZE) / 2.0;
+        write(oracle_text, TEXT_FONT_SIZE, &fonts.regular, &mut baseline);
+    }
+
+    if let Some(power_toughness) = &proxy.power_toughness {
+        let stats_width =
+            power_toughness.chars().count() as f64 * NAME_FONT_SIZE * AVERAGE_CHAR_WIDTH * PT_TO_MM;
+        layer.use_text(
+            power_toughness.as_str(),
+            NAME_FONT_SIZE,
+            Mm(right - TEXT_INSET_MM - stats_width),
+            Mm(bottom + TEXT_INSET_MM),
+            &fonts.bold,
+        );
+    }
+}
+
+fn frame(bottom_left: (f64, f64), top_right: (f64, f64)) -> Line {
+    let corners = [
+        bottom_left,
+        (top_right.0, bottom_left.1),
+        top_right,
+        (bottom_left.0, top_right.1),
+    ];
+    Line {
+        points: corners
+            .iter()
+            .map(|&(x, y)| (Point::new(Mm(x), Mm(y)), false))
+            .collect(),
+        is_closed: true,
+        has_fill: false,
+        has_stroke: true,
+        is_clipping_path: false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use printpdf::lopdf::Document;
+
+    fn proxy(name: &str) -> TextProxy {
+        TextProxy {
+            name: name.to_string(),
+            mana_cost: Some("{1}{W}{U}".to_string()),
+            type_line: Some("Legendary Creature — Human Artificer".to_string()),
+            oracle_text: Some(
+                "Artifact, instant, and sorcery spells you cast cost {1} less to cast.".to_string(),
+            ),
+            power_toughness: Some("2/4".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_wrap_text() {
+        assert_eq!(
+            wrap_text("Flying\nWhen this enters, draw a card.", 12),
+            vec!["Flying", "When this", "enters, draw", "a card."]
+        );
+        // Words longer than a line aren't split
+        assert_eq!(wrap_text("Indestructible", 5), vec!["Indestructible"]);
+        assert_eq!(wrap_text("", 10), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_title_case() {
+        assert_eq!(title_case("sword of fire and ice"), "Sword of Fire and Ice");
+        assert_eq!(title_case("fire // ice"), "Fire // Ice");
+        assert_eq!(title_case("the one ring"), "The One Ring");
+    }
+
+    #[test]
+    fn test_generate_text_pdfs() {
+        let groups = vec![
+            (0..10).map(|_| proxy("urza, lord protector")).collect(),
+            vec![proxy("the mightstone and weakstone")],
+        ];
+        let pdfs = generate_text_pdfs_from_groups(groups, PdfOptions::default(), None).unwrap();
+        assert_eq!(pdfs.len(), 1);
+        assert_eq!(&pdfs[0][0..4], b"%PDF");
+        // Ten proxies take two pages, the second group starts a third
+        let doc = Document::load_mem(&pdfs[0]).unwrap();
+        assert_eq!(doc.get_pages().len(), 3);
+
+        let own_files = PdfOptions {
+            section_split: SectionSplit::OwnFiles,
+            ..Default::default()
+        };
+        let groups = vec![vec![proxy("plains")], vec![proxy("island")]];
+        let pdfs = generate_text_pdfs_from_groups(groups, own_files, None).unwrap();
+        assert_eq!(pdfs.len(), 2);
+
+        let pdf_a = PdfOptions {
+            pdf_a: true,
+            text_only: true,
+            ..Default::default()
+        };
+        assert!(generate_text_pdfs_from_groups(vec![vec![proxy("x")]], pdf_a, None).is_err());
+    }
+}
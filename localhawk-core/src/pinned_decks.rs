@@ -0,0 +1,134 @@
+//! Decks whose images are kept in the image cache under any eviction policy
+//!
+//! Browsing printings of alternative cards fills the image cache quickly, and once it is full
+//! the images of the decks printed most often are evicted like any other. A pinned deck's image
+//! URLs are pinned in the image cache whenever it is loaded, so no eviction policy touches them
+//! until the deck is unpinned.
+
+use crate::error::ProxyError;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::Path;
+use tracing::{debug, warn};
+
+/// Pinned decks by name, each with the image URLs of its cards
+///
+/// Stored next to the settings, so clearing the caches keeps it. Missing fields fall back to
+/// their defaults so files written by older versions keep loading.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PinnedDecks {
+    pub decks: BTreeMap<String, Vec<String>>,
+}
+
+impl PinnedDecks {
+    /// Pin the deck `name` with the images at `urls`, replacing its images if it was pinned
+    ///
+    /// Returns the images it had before, which are no longer pinned for this deck.
+    pub fn pin(&mut self, name: &str, urls: Vec<String>) -> Vec<String> {
+        let urls: Vec<String> = urls
+            .into_iter()
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+        self.decks
+            .insert(name.to_string(), urls)
+            .unwrap_or_default()
+    }
+
+    /// Unpin the deck `name`, returning its images if it was pinned
+    pub fn unpin(&mut self, name: &str) -> Option<Vec<String>> {
+        self.decks.remove(name)
+    }
+
+    /// Image URLs of all pinned decks, an image shared by several decks once per deck
+    pub fn urls(&self) -> impl Iterator<Item = &String> {
+        self.decks.values().flatten()
+    }
+
+    /// Load the pinned decks from a JSON file, starting empty if the file is missing or
+    /// unreadable
+    pub fn load_from(path: &Path) -> Self {
+        if !path.exists() {
+            return PinnedDecks::default();
+        }
+
+        let loaded = fs::read_to_string(path)
+            .map_err(|e| ProxyError::Cache(format!("Failed to read pinned decks: {}", e)))
+            .and_then(|content| {
+                serde_json::from_str(&content).map_err(|e| {
+                    ProxyError::Serialization(format!("Failed to parse pinned decks: {}", e))
+                })
+            });
+        match loaded {
+            Ok(pinned_decks) => pinned_decks,
+            Err(e) => {
+                warn!(path = %path.display(), error = %e, "Failed to load pinned decks, starting empty");
+                PinnedDecks::default()
+            }
+        }
+    }
+
+    /// Save the pinned decks to a JSON file, creating the parent directory if needed
+    pub fn save_to(&self, path: &Path) -> Result<(), ProxyError> {
+        if let Some(parent_dir) = path.parent() {
+            fs::create_dir_all(parent_dir).map_err(|e| {
+                ProxyError::Cache(format!("Failed to create pinned decks directory: {}", e))
+            })?;
+        }
+
+        let content = serde_json::to_string_pretty(self).map_err(|e| {
+            ProxyError::Serialization(format!("Failed to serialize pinned decks: {}", e))
+        })?;
+        fs::write(path, content)
+            .map_err(|e| ProxyError::Cache(format!("Failed to write pinned decks: {}", e)))?;
+
+        debug!(path = %path.display(), "Saved pinned decks to disk");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn urls(names: &[&str]) -> Vec<String> {
+        names
+            .iter()
+            .map(|name| format!("https://example.com/{}.jpg", name))
+            .collect()
+    }
+
+    #[test]
+    fn test_pinned_decks() {
+        let mut pinned_decks = PinnedDecks::default();
+        assert!(
+            pinned_decks
+                .pin("Mono Red", urls(&["bolt", "bolt", "mountain"]))
+                .is_empty()
+        );
+        assert!(pinned_decks.pin("Burn", urls(&["bolt"])).is_empty());
+        assert_eq!(pinned_decks.decks["Mono Red"], urls(&["bolt", "mountain"]));
+        assert_eq!(pinned_decks.urls().count(), 3);
+
+        // Pinning a deck again replaces its images
+        let previous = pinned_decks.pin("Mono Red", urls(&["goblin guide"]));
+        assert_eq!(previous, urls(&["bolt", "mountain"]));
+        assert_eq!(pinned_decks.unpin("Burn"), Some(urls(&["bolt"])));
+        assert_eq!(pinned_decks.unpin("Burn"), None);
+
+        let path = std::env::temp_dir()
+            .join(format!(
+                "localhawk-pinned-decks-test-{}",
+                std::process::id()
+            ))
+            .join("pinned_decks.json");
+        pinned_decks.save_to(&path).unwrap();
+        assert_eq!(PinnedDecks::load_from(&path), pinned_decks);
+
+        if let Some(parent) = path.parent() {
+            std::fs::remove_dir_all(parent).ok();
+        }
+    }
+}
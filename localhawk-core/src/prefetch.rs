@@ -0,0 +1,234 @@
+//! Downloading the images of a deck ahead of time, e.g. overnight before an event without a
+//! connection
+//!
+//! Entries are searched one at a time through the rate-limited API, and images are downloaded
+//! one at a time, so the download rate limit applies as it does everywhere else. Images that
+//! are already cached are skipped and the image cache is saved every
+//! [`PREFETCH_SAVE_INTERVAL`] images, so prefetching again after an interruption picks up
+//! where it stopped.
+
+use crate::DecklistEntry;
+use crate::background_loading::{select_card_from_printings, spread_basic_land_copies};
+use crate::decklist::DownloadEstimate;
+use crate::globals::{
+    count_cached_images, get_or_fetch_image_bytes, get_or_fetch_search_results, save_caches,
+};
+use std::collections::HashSet;
+use std::time::{Duration, Instant};
+use tracing::{debug, info, warn};
+
+/// Images downloaded between saves of the image cache
+pub const PREFETCH_SAVE_INTERVAL: usize = 25;
+
+/// Images a prefetch downloads, see [`plan_prefetch`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PrefetchPlan {
+    /// Images that aren't cached yet, those of the selected printings first
+    pub urls: Vec<String>,
+    /// Images that are cached already and are skipped
+    pub cached: usize,
+    /// Entries that couldn't be searched or have no matching printing
+    pub errors: Vec<String>,
+}
+
+impl PrefetchPlan {
+    /// Estimated size of the images to download
+    pub fn estimate(&self) -> DownloadEstimate {
+        DownloadEstimate::for_images(self.urls.len())
+    }
+}
+
+/// How far a prefetch has come, see [`prefetch_images`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PrefetchProgress {
+    pub downloaded: usize,
+    pub total: usize,
+    /// Time spent downloading so far
+    pub elapsed: Duration,
+    /// Images that failed to download, with the reason
+    pub errors: Vec<String>,
+}
+
+impl PrefetchProgress {
+    /// Images neither downloaded nor failed yet
+    pub fn remaining(&self) -> usize {
+        self.total - self.downloaded - self.errors.len()
+    }
+
+    /// Estimated time left, from the average time per image so far; None before the first
+    /// image is done
+    pub fn eta(&self) -> Option<Duration> {
+        let done = self.downloaded + self.errors.len();
+        if done == 0 {
+            return None;
+        }
+        Some(self.elapsed.mul_f64(self.remaining() as f64 / done as f64))
+    }
+}
+
+/// Time downloading `images` takes at a download rate limit of `kb_per_second`, if there is one
+///
+/// Gives an ETA before the first image is downloaded; without a limit the time depends on the
+/// connection alone.
+pub fn estimate_prefetch_time(images: usize, kb_per_second: Option<u64>) -> Option<Duration> {
+    let bytes_per_second = kb_per_second? * 1024;
+    let bytes = DownloadEstimate::for_images(images).bytes;
+    Some(Duration::from_secs_f64(
+        bytes as f64 / bytes_per_second.max(1) as f64,
+    ))
+}
+
+/// Rounded for progress lines, e.g. "2h 05m", "4m 10s" or "12s"
+pub fn format_eta(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    if hours > 0 {
+        format!("{}h {:02}m", hours, minutes)
+    } else if minutes > 0 {
+        format!("{}m {:02}s", minutes, seconds)
+    } else {
+        format!("{}s", seconds)
+    }
+}
+
+/// Search `entries` and list the images they need that aren't cached yet
+///
+/// These are the images of each entry's selected printing, and with `alternatives` the front
+/// images of all its other printings, so another printing can be picked without a connection.
+pub async fn plan_prefetch(entries: &[DecklistEntry], alternatives: bool) -> PrefetchPlan {
+    let mut selected_urls = Vec::new();
+    let mut alternative_urls = Vec::new();
+    let mut errors = Vec::new();
+
+    for entry in entries {
+        let search_result = match get_or_fetch_search_results(&entry.name).await {
+            Ok(search_result) => search_result,
+            Err(e) => {
+                errors.push(format!("Search failed for '{}': {}", entry.name, e));
+                continue;
+            }
+        };
+
+        // Basic lands spread across several arts need all of them
+        let selected_indices: Vec<usize> =
+            match spread_basic_land_copies(&search_result.cards, entry) {
+                Some(spread) => spread.into_iter().map(|(index, _)| index).collect(),
+                None => select_card_from_printings(&search_result.cards, entry)
+                    .into_iter()
+                    .collect(),
+            };
+        if selected_indices.is_empty() {
+            errors.push(format!("No suitable printing found for '{}'", entry.name));
+        }
+        for (index, card) in search_result.cards.iter().enumerate() {
+            if selected_indices.contains(&index) {
+                selected_urls.extend(card.get_images_for_face_mode(&entry.face_mode));
+            } else if alternatives {
+                alternative_urls.push(card.border_crop.clone());
+            }
+        }
+    }
+
+    let mut seen = HashSet::new();
+    let mut plan = PrefetchPlan {
+        errors,
+        ..Default::default()
+    };
+    for url in selected_urls.into_iter().chain(alternative_urls) {
+        if !seen.insert(url.clone()) {
+            continue;
+        }
+        if count_cached_images([url.as_str()]) > 0 {
+            plan.cached += 1;
+        } else {
+            plan.urls.push(url);
+        }
+    }
+
+    debug!(
+        entries = entries.len(),
+        alternatives,
+        to_download = plan.urls.len(),
+        cached = plan.cached,
+        "Planned prefetch"
+    );
+    plan
+}
+
+/// Download the images at `urls` one after another, reporting progress after each
+///
+/// The image cache is saved every [`PREFETCH_SAVE_INTERVAL`] images and at the end.
+pub async fn prefetch_images(
+    urls: &[String],
+    mut on_progress: impl FnMut(&PrefetchProgress),
+) -> PrefetchProgress {
+    let started = Instant::now();
+    let mut progress = PrefetchProgress {
+        total: urls.len(),
+        ..Default::default()
+    };
+
+    for url in urls {
+        match get_or_fetch_image_bytes(url).await {
+            Ok(_) => {
+                progress.downloaded += 1;
+                if progress.downloaded.is_multiple_of(PREFETCH_SAVE_INTERVAL) {
+                    if let Err(e) = save_caches() {
+                        warn!(error = %e, "Failed to save caches while prefetching");
+                    }
+                }
+            }
+            Err(e) => {
+                warn!(url = %url, error = %e, "Failed to prefetch image");
+                progress.errors.push(format!("{}: {}", url, e));
+            }
+        }
+        progress.elapsed = started.elapsed();
+        on_progress(&progress);
+    }
+
+    if let Err(e) = save_caches() {
+        warn!(error = %e, "Failed to save caches after prefetching");
+    }
+    info!(
+        downloaded = progress.downloaded,
+        failed = progress.errors.len(),
+        elapsed_secs = progress.elapsed.as_secs(),
+        "Prefetch finished"
+    );
+    progress
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prefetch_eta() {
+        let mut progress = PrefetchProgress {
+            total: 10,
+            ..Default::default()
+        };
+        assert_eq!(progress.eta(), None);
+
+        progress.downloaded = 3;
+        progress.errors.push("failed".to_string());
+        progress.elapsed = Duration::from_secs(8);
+        assert_eq!(progress.remaining(), 6);
+        assert_eq!(progress.eta(), Some(Duration::from_secs(12)));
+
+        // 150 KB per image at 300 KB/s
+        assert_eq!(
+            estimate_prefetch_time(4, Some(300)),
+            Some(Duration::from_secs(2))
+        );
+        assert_eq!(estimate_prefetch_time(4, None), None);
+    }
+
+    #[test]
+    fn test_format_eta() {
+        assert_eq!(format_eta(Duration::from_secs(12)), "12s");
+        assert_eq!(format_eta(Duration::from_secs(250)), "4m 10s");
+        assert_eq!(format_eta(Duration::from_secs(7500)), "2h 05m");
+    }
+}
@@ -1,9 +1,25 @@
 use super::{client::ScryfallClient, models::*};
 use crate::error::ProxyError;
-use log::info;
+use tracing::{debug, info, instrument};
 
 const SCRYFALL_CARD_NAMES: &str = "https://api.scryfall.com/catalog/card-names";
 const SCRYFALL_SETS: &str = "https://api.scryfall.com/sets";
+const SCRYFALL_SEARCH: &str = "https://api.scryfall.com/cards/search";
+const SCRYFALL_NAMED: &str = "https://api.scryfall.com/cards/named";
+const SCRYFALL_CARDS: &str = "https://api.scryfall.com/cards";
+/// Result pages fetched for a raw query (Scryfall returns up to 175 cards per page)
+const MAX_QUERY_PAGES: usize = 4;
+/// Result pages fetched for the printings of a card, enough for every basic land
+pub const MAX_PRINTING_PAGES: usize = 12;
+
+/// Cards of the fetched pages of a search
+#[derive(Default)]
+struct SearchPages {
+    data: Vec<serde_json::Map<String, serde_json::Value>>,
+    total_cards: usize,
+    /// Whether the page limit left further pages unfetched
+    has_more: bool,
+}
 
 impl ScryfallClient {
     pub async fn get_card_names(&self) -> Result<ScryfallCardNames, ProxyError> {
@@ -22,6 +38,16 @@ impl ScryfallClient {
         let response = self.call(SCRYFALL_SETS).await?;
         let sets_response: ScryfallSetsResponse = response.json().await?;
 
+        let icon_uris = sets_response
+            .data
+            .iter()
+            .filter_map(|set| Some((set.code.to_lowercase(), set.icon_svg_uri.clone()?)))
+            .collect();
+        let sets = sets_response
+            .data
+            .iter()
+            .map(|set| (set.code.to_lowercase(), SetInfo::from(set)))
+            .collect();
         let codes = sets_response
             .data
             .into_iter()
@@ -31,9 +57,74 @@ impl ScryfallClient {
         Ok(ScryfallSetCodes {
             date: Some(time::OffsetDateTime::now_utc()),
             codes,
+            icon_uris,
+            sets,
         })
     }
 
+    /// Scryfall id of the printing scryfall.com shows for a card name, None if no card has
+    /// exactly this name
+    #[instrument(level = "debug", skip(self))]
+    pub async fn get_default_printing_id(&self, name: &str) -> Result<Option<String>, ProxyError> {
+        let card = self.get_named_card(name).await?;
+        Ok(card
+            .as_ref()
+            .and_then(|card| card.get("id"))
+            .and_then(|id| id.as_str())
+            .map(String::from))
+    }
+
+    /// Full name of the card with this name or face name, e.g. "Kabira Takedown // Kabira
+    /// Plateau" for "Kabira Plateau"; None if no card has it
+    #[instrument(level = "debug", skip(self))]
+    pub async fn get_full_card_name(&self, name: &str) -> Result<Option<String>, ProxyError> {
+        let card = self.get_named_card(name).await?;
+        Ok(card
+            .as_ref()
+            .and_then(|card| card.get("name"))
+            .and_then(|name| name.as_str())
+            .map(String::from))
+    }
+
+    /// The card scryfall.com shows for an exact card or face name, None if there is none
+    async fn get_named_card(
+        &self,
+        name: &str,
+    ) -> Result<Option<serde_json::Map<String, serde_json::Value>>, ProxyError> {
+        let uri = format!("{}?exact={}", SCRYFALL_NAMED, encode_card_name(name));
+        let response = self.call(&uri).await?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+
+        Ok(Some(response.error_for_status()?.json().await?))
+    }
+
+    /// The printing with this set, collector number and language, None if there is none
+    #[instrument(level = "debug", skip(self))]
+    pub async fn get_printing(
+        &self,
+        set: &str,
+        collector_number: &str,
+        lang: &str,
+    ) -> Result<Option<Card>, ProxyError> {
+        let uri = format!(
+            "{}/{}/{}/{}",
+            SCRYFALL_CARDS,
+            encode_query(set),
+            encode_query(collector_number),
+            encode_query(lang)
+        );
+        let response = self.call(&uri).await?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+
+        let card: serde_json::Map<String, serde_json::Value> =
+            response.error_for_status()?.json().await?;
+        Ok(Card::from_scryfall_object(&card).ok())
+    }
+
     async fn get_exact_name_matches(&self, name: &str) -> Result<CardSearchResult, ProxyError> {
         let encoded_name = encode_card_name(name);
         let uri = format!(
@@ -41,56 +132,129 @@ impl ScryfallClient {
             encoded_name
         );
 
-        log::debug!("Searching Scryfall with URI: {}", uri);
-        let response = self.call(&uri).await?;
+        // Nothing matching (e.g. some back faces) is an empty result
+        let pages = self
+            .search_pages(name, uri, Some(MAX_PRINTING_PAGES))
+            .await?;
+        let mut results = Vec::new();
+        for card_data in &pages.data {
+            match Card::from_scryfall_object(card_data) {
+                Ok(card) => results.push(card),
+                Err(e) => info!("Skipping invalid card: {}", e),
+            }
+        }
+        let cards = filter_name_matches(results, name);
+
+        debug!(
+            "Filtered {} cards from {} total results",
+            cards.len(),
+            pages.total_cards
+        );
+        Ok(CardSearchResult {
+            total_found: cards.len(),
+            cards,
+            has_more: pages.has_more,
+        })
+    }
+
+    /// Fetch the result pages of a search starting at `uri`, following `next_page` for at
+    /// most `max_pages` pages (None = all of them)
+    ///
+    /// Nothing matching is an empty result; an invalid `query` is an error with Scryfall's
+    /// explanation.
+    async fn search_pages(
+        &self,
+        query: &str,
+        uri: String,
+        max_pages: Option<usize>,
+    ) -> Result<SearchPages, ProxyError> {
+        let mut uri = uri;
+        let mut pages = SearchPages::default();
+        let mut fetched = 0;
+
+        loop {
+            debug!("Searching Scryfall with URI: {}", uri);
+            let response = self.call(&uri).await?;
+            let status = response.status();
+            if status == reqwest::StatusCode::NOT_FOUND {
+                // Scryfall answers 404 when nothing matches
+                debug!("Nothing matches '{}'", query);
+                break;
+            }
+            if status.is_client_error() {
+                // Invalid syntax: Scryfall explains what is wrong in "details"
+                let error: serde_json::Value = response.json().await?;
+                let details = error["details"].as_str().unwrap_or("invalid query");
+                return Err(ProxyError::InvalidCard(format!("'{}': {}", query, details)));
+            }
 
-        match response.json::<ScryfallSearchAnswer>().await {
-            Ok(answer) => {
-                let mut cards = Vec::new();
-                let search_name_lower = name.to_lowercase();
-
-                for card_data in answer.data {
-                    match Card::from_scryfall_object(&card_data) {
-                        Ok(card) => {
-                            // Filter results to only include cards that exactly match our search name
-                            let card_name_lower = card.name.to_lowercase();
-                            if card_name_lower == search_name_lower {
-                                log::debug!("Adding exact match: '{}' ({})", card.name, card.set);
-                                cards.push(card);
-                            } else {
-                                log::debug!(
-                                    "Skipping non-exact match: '{}' != '{}'",
-                                    card.name,
-                                    name
-                                );
-                            }
-                        }
-                        Err(e) => {
-                            info!("Skipping invalid card: {}", e);
-                            continue;
-                        }
+            let answer: ScryfallSearchAnswer = response.error_for_status()?.json().await?;
+            pages.total_cards = answer.total_cards.max(0) as usize;
+            pages.data.extend(answer.data);
+            fetched += 1;
+
+            match answer.next_page {
+                Some(next_page) if answer.has_more => {
+                    if max_pages.is_some_and(|max_pages| fetched >= max_pages) {
+                        debug!("Stopping after {} result pages of '{}'", fetched, query);
+                        pages.has_more = true;
+                        break;
                     }
+                    uri = next_page;
                 }
-
-                log::debug!(
-                    "Filtered {} cards from {} total results",
-                    cards.len(),
-                    answer.total_cards
-                );
-                Ok(CardSearchResult {
-                    total_found: cards.len(),
-                    cards: cards,
-                })
+                _ => break,
             }
-            Err(e) => {
-                info!("Error deserializing Scryfall search: {}", e);
-                Err(ProxyError::Network(e))
+        }
+
+        Ok(pages)
+    }
+
+    /// Search with a raw Scryfall query such as "t:goblin e:dom lang:ja"
+    ///
+    /// Unlike [`ScryfallClient::search_card`] this returns whatever matches the query, one
+    /// printing per card unless the query says otherwise (e.g. "unique:prints"). Meld
+    /// results are not resolved. `total_found` counts all matches, even beyond the pages
+    /// fetched, and `has_more` tells whether there are such matches.
+    #[instrument(level = "debug", skip(self))]
+    pub async fn search_with_query(&self, query: &str) -> Result<CardSearchResult, ProxyError> {
+        let uri = format!("{}?q={}", SCRYFALL_SEARCH, encode_query(query));
+        let pages = self.search_pages(query, uri, Some(MAX_QUERY_PAGES)).await?;
+
+        let mut cards = Vec::new();
+        for card_data in &pages.data {
+            match Card::from_scryfall_object(card_data) {
+                Ok(card) => cards.push(card),
+                Err(e) => info!("Skipping invalid card: {}", e),
             }
         }
+
+        Ok(CardSearchResult {
+            cards,
+            total_found: pages.total_cards,
+            has_more: pages.has_more,
+        })
     }
 
+    /// All printings of the card with this name
+    ///
+    /// A face name such as "Kabira Plateau" finds the printings of its double-faced card,
+    /// named "Kabira Takedown // Kabira Plateau"; see [`Card::face_index`] for which face it
+    /// names. Cards with more than [`MAX_PRINTING_PAGES`] pages of printings are cut off
+    /// there, with `has_more` set.
+    #[instrument(level = "debug", skip(self))]
     pub async fn search_card(&self, name: &str) -> Result<CardSearchResult, ProxyError> {
-        let name_matches = self.get_exact_name_matches(name).await?;
+        let mut name_matches = self.get_exact_name_matches(name).await?;
+        if name_matches.cards.is_empty() && !name.contains("//") {
+            // Some back faces aren't found by the name search; look the full name up instead
+            let full_name = self
+                .get_full_card_name(name)
+                .await?
+                .filter(|full_name| !full_name.eq_ignore_ascii_case(name));
+            if let Some(full_name) = full_name {
+                debug!("Searching '{}' for face '{}'", full_name, name);
+                name_matches = self.get_exact_name_matches(&full_name).await?;
+            }
+        }
         let mut cards = name_matches.cards;
 
         for card in &mut cards {
@@ -101,10 +265,9 @@ impl ScryfallClient {
             }) = &mut card.back_side
             {
                 if meld_result_image_url.is_empty() {
-                    log::debug!(
+                    debug!(
                         "Resolving meld result '{}' for card '{}'",
-                        meld_result_name,
-                        card.name
+                        meld_result_name, card.name
                     );
 
                     // Search for the meld result card (without recursively resolving meld results)
@@ -118,18 +281,15 @@ impl ScryfallClient {
                     }
 
                     // Debug: Log all search results for the meld result
-                    log::debug!(
+                    debug!(
                         "Meld search for '{}' returned {} cards:",
                         meld_result_name,
                         meld_search_result.cards.len()
                     );
                     for (i, result_card) in meld_search_result.cards.iter().enumerate() {
-                        log::debug!(
+                        debug!(
                             "  [{}] '{}' (set: {}) - URL: {}",
-                            i,
-                            result_card.name,
-                            result_card.set,
-                            result_card.border_crop
+                            i, result_card.name, result_card.set, result_card.border_crop
                         );
                     }
 
@@ -143,12 +303,9 @@ impl ScryfallClient {
                             ProxyError::InvalidCard("No meld result card available".to_string())
                         })?;
 
-                    log::debug!(
+                    debug!(
                         "Found meld result '{}' (set: {}) for card '{}' (set: {})",
-                        meld_card.name,
-                        meld_card.set,
-                        card.name,
-                        card.set
+                        meld_card.name, meld_card.set, card.name, card.set
                     );
                     *meld_result_image_url = meld_card.border_crop.clone();
                 }
@@ -158,10 +315,39 @@ impl ScryfallClient {
         Ok(CardSearchResult {
             cards,
             total_found: name_matches.total_found,
+            has_more: name_matches.has_more,
         })
     }
 }
 
+/// The cards named exactly `name`, or if there are none the cards with a face of that name
+fn filter_name_matches(cards: Vec<Card>, name: &str) -> Vec<Card> {
+    let name = name.trim();
+    let (exact, others): (Vec<Card>, Vec<Card>) = cards
+        .into_iter()
+        .partition(|card| card.name.eq_ignore_ascii_case(name));
+    if !exact.is_empty() {
+        return exact;
+    }
+    others
+        .into_iter()
+        .filter(|card| card.face_index(name).is_some())
+        .collect()
+}
+
+/// Percent-encode a raw query for the `q` parameter, keeping its UTF-8 intact
+fn encode_query(query: &str) -> String {
+    query
+        .trim()
+        .bytes()
+        .map(|b| match b {
+            b' ' => "+".to_string(),
+            b if b.is_ascii_alphanumeric() || b"-_.~".contains(&b) => (b as char).to_string(),
+            b => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
 fn encode_card_name(name: &str) -> String {
     // Proper URL encoding for card names
     // Handle spaces, slashes, and other special characters
@@ -177,3 +363,54 @@ fn encode_card_name(name: &str) -> String {
         })
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RECORDED_CARDS: &str =
+        include_str!("../../test_data/default-cards-20200910090351_truncated.json");
+
+    fn recorded_cards() -> Vec<Card> {
+        let objects: Vec<serde_json::Map<String, serde_json::Value>> =
+            serde_json::from_str(RECORDED_CARDS).unwrap();
+        objects
+            .iter()
+            .filter_map(|object| Card::from_scryfall_object(object).ok())
+            .collect()
+    }
+
+    #[test]
+    fn test_filter_name_matches_finds_faces() {
+        let cards = recorded_cards();
+        let names = |name: &str| -> Vec<String> {
+            filter_name_matches(cards.clone(), name)
+                .into_iter()
+                .map(|card| card.name)
+                .collect()
+        };
+        let pathway = "branchloft pathway // boulderloft pathway";
+
+        assert!(!names(pathway).is_empty());
+        assert!(names(pathway).iter().all(|name| name == pathway));
+        // The back face of the MDFC land finds the same printings, and so does the front
+        assert_eq!(names("Boulderloft Pathway"), names(pathway));
+        assert_eq!(names("Branchloft Pathway"), names(pathway));
+        assert!(names("Pathway").is_empty());
+
+        let fury_sliver = names("Fury Sliver");
+        assert!(!fury_sliver.is_empty());
+        assert!(fury_sliver.iter().all(|name| name == "fury sliver"));
+    }
+
+    #[test]
+    fn test_encode_query() {
+        assert_eq!(encode_query(" t:goblin e:dom "), "t%3Agoblin+e%3Adom");
+        assert_eq!(encode_query("o:\"draw a card\""), "o%3A%22draw+a+card%22");
+        // Non-ASCII text is encoded byte by byte
+        assert_eq!(
+            encode_query("name:ゴブリン"),
+            "name%3A%E3%82%B4%E3%83%96%E3%83%AA%E3%83%B3"
+        );
+    }
+}
@@ -1,7 +1,9 @@
+use crate::cache::HttpValidators;
 use crate::error::ProxyError;
+use crate::scryfall::throttle::throttle_download;
 use lazy_static::lazy_static;
-use log::debug;
 use tokio::time::{Duration, Instant};
+use tracing::{debug, error, warn};
 
 // Headers required according to https://scryfall.com/docs/api/
 const USER_AGENT: &str = "localhawk-core/0.1";
@@ -39,6 +41,15 @@ impl ScryfallClient {
     }
 
     pub async fn call(&self, uri: &str) -> Result<reqwest::Response, ProxyError> {
+        self.call_with_headers(uri, reqwest::header::HeaderMap::new())
+            .await
+    }
+
+    async fn call_with_headers(
+        &self,
+        uri: &str,
+        headers: reqwest::header::HeaderMap,
+    ) -> Result<reqwest::Response, ProxyError> {
         if !uri.contains(".scryfall.io") {
             let next_call = {
                 let mut l = *LAST_SCRYFALL_CALL.lock().unwrap();
@@ -51,11 +62,33 @@ impl ScryfallClient {
             debug!("calling scryfall API (not rate-limited): {}", uri);
         }
 
-        match self.client.get(uri).send().await {
-            Ok(response) => Ok(response),
+        match self.client.get(uri).headers(headers).send().await {
+            Ok(response) => {
+                let content_type = response
+                    .headers()
+                    .get(reqwest::header::CONTENT_TYPE)
+                    .and_then(|value| value.to_str().ok());
+                if !is_maintenance_response(response.status(), content_type) {
+                    // Only an API answer means Scryfall is back, its image CDN may be up anyway
+                    if !uri.contains(".scryfall.io") {
+                        crate::globals::set_scryfall_unavailable(false);
+                    }
+                    return Ok(response);
+                }
+
+                crate::globals::set_scryfall_unavailable(true);
+                warn!(
+                    status = %response.status(),
+                    "scryfall is unavailable (maintenance or overload): {}",
+                    uri
+                );
+                Err(ProxyError::ScryfallUnavailable(
+                    response.status().to_string(),
+                ))
+            }
             Err(e) => {
                 if e.status() == Some(reqwest::StatusCode::TOO_MANY_REQUESTS) {
-                    log::error!(
+                    error!(
                         "scryfall API has returned status code 429 (too many requests): {}",
                         e
                     );
@@ -78,8 +111,106 @@ impl ScryfallClient {
     /// Get raw image bytes from URL (uses same rate limiting as get_image)
     pub async fn get_image_bytes(&self, url: &str) -> Result<Vec<u8>, ProxyError> {
         let response = self.call(url).await?;
-        let bytes = response.bytes().await?;
-        Ok(bytes.to_vec())
+        read_body(response).await
+    }
+
+    /// Get raw image bytes together with the validators to revalidate them later
+    pub async fn get_image_bytes_with_validators(
+        &self,
+        url: &str,
+    ) -> Result<(Vec<u8>, HttpValidators), ProxyError> {
+        let response = self.call(url).await?;
+        let validators = response_validators(&response);
+        let bytes = read_body(response).await?;
+        Ok((bytes, validators))
+    }
+
+    /// Download an image only if it changed since the response `validators` came from
+    ///
+    /// A cache refresh of an unchanged image then costs only a 304 round trip.
+    pub async fn get_image_bytes_if_modified(
+        &self,
+        url: &str,
+        validators: &HttpValidators,
+    ) -> Result<ConditionalImage, ProxyError> {
+        let mut headers = reqwest::header::HeaderMap::new();
+        let conditions = [
+            (reqwest::header::IF_NONE_MATCH, &validators.etag),
+            (
+                reqwest::header::IF_MODIFIED_SINCE,
+                &validators.last_modified,
+            ),
+        ];
+        for (name, value) in conditions {
+            if let Some(Ok(value)) = value.as_deref().map(reqwest::header::HeaderValue::from_str) {
+                headers.insert(name, value);
+            }
+        }
+
+        let response = self.call_with_headers(url, headers).await?;
+        if response.status() == reqwest::StatusCode::NOT_MODIFIED {
+            debug!("image not modified: {}", url);
+            return Ok(ConditionalImage::NotModified);
+        }
+
+        // Never replace a cached image with an error page
+        let response = response.error_for_status()?;
+        let validators = response_validators(&response);
+        let bytes = read_body(response).await?;
+        Ok(ConditionalImage::Modified { bytes, validators })
+    }
+}
+
+/// Whether a response is one of the pages Scryfall serves while it is down
+///
+/// During maintenance or overload the API answers 503 (502 or 504 from the proxy in front of
+/// it), or sometimes a plain HTML page with status 200 where JSON or an image belongs.
+fn is_maintenance_response(status: reqwest::StatusCode, content_type: Option<&str>) -> bool {
+    match status {
+        reqwest::StatusCode::BAD_GATEWAY
+        | reqwest::StatusCode::SERVICE_UNAVAILABLE
+        | reqwest::StatusCode::GATEWAY_TIMEOUT => true,
+        status if status.is_success() => {
+            content_type.is_some_and(|content_type| content_type.starts_with("text/html"))
+        }
+        _ => false,
+    }
+}
+
+/// Read a response body chunk by chunk, keeping to the download rate limit
+async fn read_body(mut response: reqwest::Response) -> Result<Vec<u8>, ProxyError> {
+    let mut body = Vec::with_capacity(response.content_length().unwrap_or(0) as usize);
+    while let Some(chunk) = response.chunk().await? {
+        throttle_download(chunk.len()).await;
+        body.extend_from_slice(&chunk);
+    }
+    Ok(body)
+}
+
+/// Outcome of a conditional image download
+#[derive(Debug)]
+pub enum ConditionalImage {
+    /// The cached image is still current
+    NotModified,
+    /// The image changed (or the server ignored the validators)
+    Modified {
+        bytes: Vec<u8>,
+        validators: HttpValidators,
+    },
+}
+
+/// The ETag and Last-Modified headers of a response
+fn response_validators(response: &reqwest::Response) -> HttpValidators {
+    let header = |name: reqwest::header::HeaderName| {
+        response
+            .headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(String::from)
+    };
+    HttpValidators {
+        etag: header(reqwest::header::ETAG),
+        last_modified: header(reqwest::header::LAST_MODIFIED),
     }
 }
 
@@ -88,3 +219,38 @@ impl Default for ScryfallClient {
         Self::new().expect("Failed to create ScryfallClient")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::StatusCode;
+
+    #[test]
+    fn test_is_maintenance_response() {
+        assert!(is_maintenance_response(
+            StatusCode::SERVICE_UNAVAILABLE,
+            Some("application/json")
+        ));
+        assert!(is_maintenance_response(StatusCode::BAD_GATEWAY, None));
+        assert!(is_maintenance_response(
+            StatusCode::OK,
+            Some("text/html; charset=utf-8")
+        ));
+
+        assert!(!is_maintenance_response(
+            StatusCode::OK,
+            Some("application/json; charset=utf-8")
+        ));
+        assert!(!is_maintenance_response(StatusCode::OK, Some("image/jpeg")));
+        assert!(!is_maintenance_response(StatusCode::NOT_MODIFIED, None));
+        // Scryfall's own errors (e.g. nothing found) are answers, not downtime
+        assert!(!is_maintenance_response(
+            StatusCode::NOT_FOUND,
+            Some("application/json")
+        ));
+        assert!(!is_maintenance_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Some("application/json")
+        ));
+    }
+}
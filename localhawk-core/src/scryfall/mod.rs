@@ -1,6 +1,7 @@
 pub mod api;
 pub mod client;
 pub mod models;
+pub(crate) mod throttle;
 
 pub use client::ScryfallClient;
 pub use models::{Card, CardSearchResult, ScryfallCardNames, get_minimal_scryfall_languages};
@@ -1,6 +1,8 @@
 use crate::error::ProxyError;
+use crate::i18n::{Text, tr_with};
+use crate::legality::{Legality, parse_scryfall_legalities};
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 use time::OffsetDateTime;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -17,6 +19,33 @@ pub struct ScryfallCardNames {
 pub struct ScryfallSetCodes {
     pub date: Option<OffsetDateTime>,
     pub codes: Vec<String>,
+    /// Set symbol SVG URL by lowercase set code (missing in caches written before icons were kept)
+    #[serde(default)]
+    pub icon_uris: BTreeMap<String, String>,
+    /// Name, release date and type by lowercase set code (missing in caches written before they
+    /// were kept)
+    #[serde(default)]
+    pub sets: BTreeMap<String, SetInfo>,
+}
+
+/// What is shown about a set next to its code
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SetInfo {
+    pub name: String,
+    /// Release date as YYYY-MM-DD
+    pub released_at: Option<String>,
+    /// Scryfall's set type, e.g. "expansion", "commander" or "promo"
+    pub set_type: String,
+}
+
+impl From<&ScryfallSet> for SetInfo {
+    fn from(set: &ScryfallSet) -> Self {
+        SetInfo {
+            name: set.name.clone(),
+            released_at: set.released_at.clone(),
+            set_type: set.set_type.clone(),
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -28,6 +57,8 @@ pub struct ScryfallSet {
     pub released_at: Option<String>,
     pub set_type: String,
     pub card_count: i32,
+    #[serde(default)]
+    pub icon_svg_uri: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -53,8 +84,78 @@ pub struct Card {
     pub language: String,
     pub border_crop: String,         // Front face (always exists)
     pub back_side: Option<BackSide>, // What's on the physical back / meld contribution
+    #[serde(default)]
+    pub frame: Option<String>, // Frame edition, e.g. "1993", "2015", "future"
+    #[serde(default)]
+    pub digital: bool, // Only released digitally (MTGO/Arena), never printed on paper
+    #[serde(default)]
+    pub collector_number: Option<String>, // Number within the set, e.g. "225" or "15a"
+    #[serde(default)]
+    pub prices: Option<CardPrices>, // Market prices at the time of the search, if Scryfall has any
+    #[serde(default)]
+    pub legalities: BTreeMap<String, Legality>, // Legality per format, keyed by Scryfall format name
+    #[serde(default)]
+    pub layout: Option<CardLayout>, // Scryfall layout; None for cards cached before it was kept
+    #[serde(default)]
+    pub scryfall_id: Option<String>, // Unique id of this printing; None for cards cached before it was kept
+    #[serde(default)]
+    pub oracle_id: Option<String>, // Id shared by all printings of the same card
+    #[serde(default)]
+    pub mana_cost: Option<String>, // e.g. "{1}{W}{U}", faces joined with " // "
+    #[serde(default)]
+    pub type_line: Option<String>, // e.g. "Legendary Creature — Human Artificer"
+    #[serde(default)]
+    pub oracle_text: Option<String>, // Rules text, faces separated by a "//" line
+    #[serde(default)]
+    pub power_toughness: Option<String>, // e.g. "2/4", faces joined with " // "
+}
+
+/// Market prices reported by Scryfall, kept as the decimal strings Scryfall returns
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct CardPrices {
+    pub usd: Option<String>,
+    pub usd_foil: Option<String>,
+    pub eur: Option<String>,
+    pub tix: Option<String>,
 }
 
+impl CardPrices {
+    /// Parse the `prices` object of a Scryfall card, None if no price is known
+    fn from_scryfall_value(value: &serde_json::Value) -> Option<CardPrices> {
+        let prices: CardPrices = serde_json::from_value(value.clone()).ok()?;
+        let has_any = prices.usd.is_some()
+            || prices.usd_foil.is_some()
+            || prices.eur.is_some()
+            || prices.tix.is_some();
+        has_any.then_some(prices)
+    }
+
+    /// Short label for display, preferring USD, then EUR, then MTGO tickets
+    pub fn label(&self) -> Option<String> {
+        if let Some(usd) = self.usd.as_ref().or(self.usd_foil.as_ref()) {
+            Some(format!("${}", usd))
+        } else if let Some(eur) = &self.eur {
+            Some(format!("€{}", eur))
+        } else {
+            self.tix.as_ref().map(|tix| format!("{} tix", tix))
+        }
+    }
+
+    /// USD price as a number (nonfoil, falling back to foil)
+    pub fn usd_value(&self) -> Option<f64> {
+        self.usd
+            .as_ref()
+            .or(self.usd_foil.as_ref())
+            .and_then(|usd| usd.parse().ok())
+    }
+}
+
+/// Image URL standing in for back faces printed as a placeholder card back
+///
+/// Never fetched from the network: the image caches generate the placeholder image instead.
+pub const PLACEHOLDER_BACK_URL: &str = "localhawk://placeholder-back";
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum BackSide {
     /// Double-faced card back (e.g., Kabira Plateau)
@@ -73,6 +174,107 @@ pub enum BackSide {
     },
 }
 
+/// Image of the back side to print
+enum BackImage {
+    Available(String),
+    Missing,
+    NoBack,
+}
+
+/// Scryfall card layout, which decides whether a card's faces are separate physical sides
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CardLayout {
+    Normal,
+    /// Two halves on the front (Fire // Ice)
+    Split,
+    /// Two halves on the front, one upside down (Kamigawa flip cards)
+    Flip,
+    /// Creature with an adventure spell inset in its text box
+    Adventure,
+    Transform,
+    ModalDfc,
+    Meld,
+    DoubleFacedToken,
+    ReversibleCard,
+    ArtSeries,
+    /// Any other layout (sagas, classes, tokens, ...), all printed on a single side
+    Other,
+}
+
+impl CardLayout {
+    /// Parse Scryfall's `layout` field
+    pub fn from_scryfall(layout: &str) -> CardLayout {
+        match layout {
+            "normal" => CardLayout::Normal,
+            "split" => CardLayout::Split,
+            "flip" => CardLayout::Flip,
+            "adventure" => CardLayout::Adventure,
+            "transform" => CardLayout::Transform,
+            "modal_dfc" => CardLayout::ModalDfc,
+            "meld" => CardLayout::Meld,
+            "double_faced_token" => CardLayout::DoubleFacedToken,
+            "reversible_card" => CardLayout::ReversibleCard,
+            "art_series" => CardLayout::ArtSeries,
+            _ => CardLayout::Other,
+        }
+    }
+
+    /// Whether cards of this layout have a second physical side (or meld result) to print
+    pub fn is_double_faced(&self) -> bool {
+        matches!(
+            self,
+            CardLayout::Transform
+                | CardLayout::ModalDfc
+                | CardLayout::Meld
+                | CardLayout::DoubleFacedToken
+                | CardLayout::ReversibleCard
+                | CardLayout::ArtSeries
+        )
+    }
+}
+
+/// A text field of a Scryfall card object, joining the faces' values for multi-face cards
+///
+/// Scryfall leaves fields like the oracle text out of the top level when they differ
+/// between faces. Empty values (e.g. lands' mana cost) count as missing.
+fn card_text(
+    d: &serde_json::Map<String, serde_json::Value>,
+    key: &str,
+    separator: &str,
+) -> Option<String> {
+    if let Some(text) = d.get(key).and_then(|value| value.as_str()) {
+        return (!text.is_empty()).then(|| text.to_string());
+    }
+    let faces: Vec<&str> = d
+        .get("card_faces")?
+        .as_array()?
+        .iter()
+        .filter_map(|face| face.get(key)?.as_str())
+        .filter(|text| !text.is_empty())
+        .collect();
+    (!faces.is_empty()).then(|| faces.join(separator))
+}
+
+/// Power and toughness of a Scryfall card object like "2/4", or of each face that has them
+fn power_toughness(d: &serde_json::Map<String, serde_json::Value>) -> Option<String> {
+    let of = |object: &serde_json::Map<String, serde_json::Value>| {
+        let power = object.get("power")?.as_str()?;
+        let toughness = object.get("toughness")?.as_str()?;
+        Some(format!("{}/{}", power, toughness))
+    };
+    if let Some(stats) = of(d) {
+        return Some(stats);
+    }
+    let faces: Vec<String> = d
+        .get("card_faces")?
+        .as_array()?
+        .iter()
+        .filter_map(|face| of(face.as_object()?))
+        .collect();
+    (!faces.is_empty()).then(|| faces.join(" // "))
+}
+
 impl Card {
     pub fn from_scryfall_object(
         d: &serde_json::Map<String, serde_json::Value>,
@@ -186,63 +388,188 @@ impl Card {
             None
         };
 
+        let frame = d
+            .get("frame")
+            .and_then(|frame| frame.as_str())
+            .map(|frame| frame.to_lowercase());
+        let digital = d
+            .get("digital")
+            .and_then(|digital| digital.as_bool())
+            .unwrap_or(false);
+        let collector_number = d
+            .get("collector_number")
+            .and_then(|number| number.as_str())
+            .map(String::from);
+        let prices = d.get("prices").and_then(CardPrices::from_scryfall_value);
+        let legalities = d
+            .get("legalities")
+            .map(parse_scryfall_legalities)
+            .unwrap_or_default();
+        let layout = d
+            .get("layout")
+            .and_then(|layout| layout.as_str())
+            .map(CardLayout::from_scryfall);
+        let scryfall_id = d.get("id").and_then(|id| id.as_str()).map(String::from);
+        let oracle_id = d
+            .get("oracle_id")
+            .and_then(|id| id.as_str())
+            .map(String::from);
+        let mana_cost = card_text(d, "mana_cost", " // ");
+        let type_line = card_text(d, "type_line", " // ");
+        let oracle_text = card_text(d, "oracle_text", "\n//\n");
+        let power_toughness = power_toughness(d);
+
         Ok(Card {
             name,
             set,
             language,
             border_crop,
             back_side,
+            frame,
+            digital,
+            collector_number,
+            prices,
+            legalities,
+            layout,
+            scryfall_id,
+            oracle_id,
+            mana_cost,
+            type_line,
+            oracle_text,
+            power_toughness,
         })
     }
 
+    /// The back side to print, if any
+    ///
+    /// Split, flip and adventure cards are a single physical card, so whatever Scryfall
+    /// lists as their second face is never printed. Cards cached before the layout was
+    /// kept fall back to whether a back side was found.
+    fn printed_back_side(&self) -> Option<&BackSide> {
+        match self.layout {
+            Some(layout) if !layout.is_double_faced() => None,
+            _ => self.back_side.as_ref(),
+        }
+    }
+
     /// Get all available images for this card for the given face mode
+    ///
+    /// Only double-faced and meld cards ever yield two images, whatever their Scryfall faces.
+    /// Back faces without an image are handled according to the current
+    /// [`BackFaceFallback`](crate::BackFaceFallback).
     pub fn get_images_for_face_mode(&self, mode: &crate::DoubleFaceMode) -> Vec<String> {
+        self.get_images_for_face_mode_with_fallback(mode, crate::globals::get_back_face_fallback())
+    }
+
+    /// Get all available images for this card for the given face mode, printing `fallback`
+    /// for a back face whose image is unavailable
+    pub fn get_images_for_face_mode_with_fallback(
+        &self,
+        mode: &crate::DoubleFaceMode,
+        fallback: crate::BackFaceFallback,
+    ) -> Vec<String> {
+        let back_image = match self.printed_back_side() {
+            Some(_) => match self.back_image_url() {
+                Some(url) => BackImage::Available(url.to_string()),
+                None => BackImage::Missing,
+            },
+            None => BackImage::NoBack,
+        };
+
         match mode {
             crate::DoubleFaceMode::FrontOnly => {
                 vec![self.border_crop.clone()]
             }
 
-            crate::DoubleFaceMode::BackOnly => {
-                match &self.back_side {
-                    Some(BackSide::DfcBack { image_url, .. }) => vec![image_url.clone()],
-                    Some(BackSide::ContributesToMeld {
-                        meld_result_image_url,
-                        ..
-                    }) => {
-                        if !meld_result_image_url.is_empty() {
-                            vec![meld_result_image_url.clone()]
-                        } else {
-                            vec![self.border_crop.clone()] // Fallback to front if meld not resolved
-                        }
-                    }
-                    None => vec![self.border_crop.clone()], // No back, show front
-                }
-            }
+            crate::DoubleFaceMode::BackOnly => match back_image {
+                BackImage::Available(url) => vec![url],
+                BackImage::Missing => match fallback {
+                    crate::BackFaceFallback::Front => vec![self.border_crop.clone()],
+                    crate::BackFaceFallback::Skip => Vec::new(),
+                    crate::BackFaceFallback::Placeholder => vec![PLACEHOLDER_BACK_URL.to_string()],
+                },
+                BackImage::NoBack => vec![self.border_crop.clone()], // No back, show front
+            },
 
             crate::DoubleFaceMode::BothSides => {
                 let mut images = vec![self.border_crop.clone()];
-                match &self.back_side {
-                    Some(BackSide::DfcBack { image_url, .. }) => {
-                        images.push(image_url.clone());
-                    }
-                    Some(BackSide::ContributesToMeld {
-                        meld_result_image_url,
-                        ..
-                    }) => {
-                        if !meld_result_image_url.is_empty() {
-                            images.push(meld_result_image_url.clone());
+                match back_image {
+                    BackImage::Available(url) => images.push(url),
+                    BackImage::Missing => {
+                        if fallback == crate::BackFaceFallback::Placeholder {
+                            images.push(PLACEHOLDER_BACK_URL.to_string());
                         }
                     }
-                    None => {} // No back side to add
+                    BackImage::NoBack => {} // No back side to add
                 }
                 images
             }
         }
     }
 
-    /// Check if this card has a back side (either DFC back or contributes to meld)
+    /// Image URL of the back side, if it has one (meld results may be unresolved)
+    fn back_image_url(&self) -> Option<&str> {
+        let url = match self.printed_back_side()? {
+            BackSide::DfcBack { image_url, .. } => image_url,
+            BackSide::ContributesToMeld {
+                meld_result_image_url,
+                ..
+            } => meld_result_image_url,
+        };
+        (!url.is_empty()).then_some(url.as_str())
+    }
+
+    /// Warning for the resolution report if this card's back face would be printed in `mode`
+    /// but its image is unavailable, saying what is printed instead
+    pub fn missing_back_face_warning(&self, mode: &crate::DoubleFaceMode) -> Option<String> {
+        if *mode == crate::DoubleFaceMode::FrontOnly
+            || !self.has_back_side()
+            || self.back_image_url().is_some()
+        {
+            return None;
+        }
+
+        let key = match (crate::globals::get_back_face_fallback(), mode) {
+            (crate::BackFaceFallback::Skip, crate::DoubleFaceMode::BackOnly) => {
+                Text::BackFaceSkipped
+            }
+            (crate::BackFaceFallback::Placeholder, _) => Text::BackFacePlaceholder,
+            (crate::BackFaceFallback::Front, crate::DoubleFaceMode::BackOnly) => {
+                Text::BackFaceUsingFront
+            }
+            _ => Text::BackFaceFrontOnly,
+        };
+        Some(tr_with(key, &[&self.name]))
+    }
+
+    /// Short caption identifying this printing, e.g. "lightning bolt (M21 #125)"
+    pub fn caption(&self) -> String {
+        match &self.collector_number {
+            Some(number) => format!("{} ({} #{})", self.name, self.set.to_uppercase(), number),
+            None => format!("{} ({})", self.name, self.set.to_uppercase()),
+        }
+    }
+
+    /// Mana cost, type line and rules text for display, None if none of them is known
+    ///
+    /// Cards cached before the text was kept have none of it until they are searched again.
+    pub fn details(&self) -> Option<String> {
+        let header = match (&self.mana_cost, &self.type_line) {
+            (Some(cost), Some(type_line)) => Some(format!("{}  {}", type_line, cost)),
+            (cost, type_line) => type_line.as_ref().or(cost.as_ref()).cloned(),
+        };
+        let parts: Vec<String> = header.into_iter().chain(self.oracle_text.clone()).collect();
+        (!parts.is_empty()).then(|| parts.join("\n"))
+    }
+
+    /// Short price label for this printing (e.g. "$0.25"), if Scryfall reported a price
+    pub fn price_label(&self) -> Option<String> {
+        self.prices.as_ref().and_then(CardPrices::label)
+    }
+
+    /// Check if this card has a back side to print (either DFC back or contributes to meld)
     pub fn has_back_side(&self) -> bool {
-        self.back_side.is_some()
+        self.printed_back_side().is_some()
     }
 
     /// Get the name of the back side (DFC back name or meld result name)
@@ -256,6 +583,18 @@ impl Card {
         }
     }
 
+    /// Index of the face named `name` (0 for the front), None if the card has no face of that
+    /// name; only cards named after several faces, e.g. "Kabira Takedown // Kabira Plateau",
+    /// have faces to match
+    pub fn face_index(&self, name: &str) -> Option<usize> {
+        if !self.name.contains(" // ") {
+            return None;
+        }
+        self.name
+            .split(" // ")
+            .position(|face| face.eq_ignore_ascii_case(name.trim()))
+    }
+
     /// Check if this card contributes to a meld
     pub fn is_meld_card(&self) -> bool {
         matches!(self.back_side, Some(BackSide::ContributesToMeld { .. }))
@@ -266,6 +605,9 @@ impl Card {
 pub struct CardSearchResult {
     pub cards: Vec<Card>,
     pub total_found: usize,
+    /// Whether Scryfall has more matches than `cards`, left out by the limit on result pages
+    #[serde(default)]
+    pub has_more: bool,
 }
 
 /// Returns all language codes supported by Scryfall (18 languages as of 2025)
@@ -295,6 +637,30 @@ mod tests {
         assert_eq!(card.name, "urza, lord protector");
         assert_eq!(card.set, "bro");
         assert_eq!(card.language, "en");
+        assert_eq!(
+            card.scryfall_id.as_deref(),
+            Some("8aefe8bd-216a-4ec1-9362-3f9dbf7fd083")
+        );
+        assert_eq!(
+            card.oracle_id.as_deref(),
+            Some("df2af646-3e5b-43a3-8f3e-50565889f456")
+        );
+        assert_eq!(card.mana_cost.as_deref(), Some("{1}{W}{U}"));
+        assert_eq!(card.power_toughness.as_deref(), Some("2/4"));
+        assert_eq!(
+            card.type_line.as_deref(),
+            Some("Legendary Creature — Human Artificer")
+        );
+        let details = card.details().unwrap();
+        assert!(details.starts_with("Legendary Creature — Human Artificer  {1}{W}{U}\nArtifact"));
+        assert_eq!(card.frame.as_deref(), Some("2015"));
+        assert!(!card.digital);
+        assert_eq!(card.caption(), "urza, lord protector (BRO #225)");
+        assert_eq!(card.price_label().as_deref(), Some("$26.65"));
+        assert_eq!(
+            card.prices.as_ref().and_then(CardPrices::usd_value),
+            Some(26.65)
+        );
         // Check that this card contributes to a meld
         assert!(card.is_meld_card());
 
@@ -311,6 +677,48 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_price_label_fallbacks() {
+        let eur_only = CardPrices {
+            eur: Some("1.50".to_string()),
+            tix: Some("0.02".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(eur_only.label().as_deref(), Some("€1.50"));
+        assert_eq!(eur_only.usd_value(), None);
+
+        let foil_only = CardPrices {
+            usd_foil: Some("3.00".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(foil_only.label().as_deref(), Some("$3.00"));
+
+        let no_prices = serde_json::json!({"usd": null, "eur": null, "tix": null});
+        assert_eq!(CardPrices::from_scryfall_value(&no_prices), None);
+    }
+
+    #[test]
+    fn test_set_infos() {
+        // Caches written before set names were kept still load
+        let old: ScryfallSetCodes =
+            serde_json::from_str(r#"{"date": null, "codes": ["rna"]}"#).unwrap();
+        assert!(old.sets.is_empty());
+
+        let set: ScryfallSet = serde_json::from_str(
+            r#"{"object": "set", "id": "1", "code": "RNA", "name": "Ravnica Allegiance",
+                "released_at": "2019-01-25", "set_type": "expansion", "card_count": 273}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            SetInfo::from(&set),
+            SetInfo {
+                name: "Ravnica Allegiance".to_string(),
+                released_at: Some("2019-01-25".to_string()),
+                set_type: "expansion".to_string(),
+            }
+        );
+    }
+
     #[test]
     fn test_split_card_parsing() {
         // Test using actual file data - Consecrate // Consume is a split card
@@ -352,6 +760,191 @@ mod tests {
         assert_eq!(card.name, "erayo, soratami ascendant // erayo's essence");
     }
 
+    fn load_test_card(input: &str, index: usize) -> Card {
+        let list: Vec<serde_json::Map<String, serde_json::Value>> =
+            serde_json::from_str(input).unwrap();
+        Card::from_scryfall_object(&list[index]).unwrap()
+    }
+
+    #[test]
+    fn test_single_card_layouts_print_one_image() {
+        let cards = [
+            (
+                load_test_card(include_str!("../../test_data/card_data_consecrate.json"), 0),
+                CardLayout::Split,
+            ),
+            (
+                load_test_card(include_str!("../../test_data/card_data_erayo.json"), 0),
+                CardLayout::Flip,
+            ),
+            (
+                load_test_card(include_str!("../../test_data/card_data_illithid.json"), 0),
+                CardLayout::Adventure,
+            ),
+        ];
+
+        for (card, layout) in cards {
+            assert_eq!(card.layout, Some(layout), "layout of {}", card.name);
+            for mode in crate::DoubleFaceMode::all() {
+                assert_eq!(
+                    card.get_images_for_face_mode(&mode),
+                    vec![card.border_crop.clone()],
+                    "{} in {:?}",
+                    card.name,
+                    mode
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_single_card_layout_ignores_listed_back_face() {
+        let mut card = load_test_card(include_str!("../../test_data/card_data_consecrate.json"), 0);
+        card.back_side = Some(BackSide::DfcBack {
+            image_url: "https://example.com/consume.jpg".to_string(),
+            name: "consume".to_string(),
+        });
+        assert!(!card.has_back_side());
+        assert_eq!(
+            card.get_images_for_face_mode(&crate::DoubleFaceMode::BothSides),
+            vec![card.border_crop.clone()]
+        );
+
+        // Cards cached before the layout was kept still trust their back side
+        card.layout = None;
+        assert!(card.has_back_side());
+        assert_eq!(
+            card.get_images_for_face_mode(&crate::DoubleFaceMode::BothSides)
+                .len(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_modal_dfc_prints_both_faces() {
+        let card = load_test_card(
+            include_str!("../../test_data/default-cards-20200910090351_truncated.json"),
+            3,
+        );
+        assert_eq!(card.name, "branchloft pathway // boulderloft pathway");
+        assert_eq!(card.layout, Some(CardLayout::ModalDfc));
+        // Text only listed per face is joined, lands have no mana cost
+        assert_eq!(card.mana_cost, None);
+        assert_eq!(card.type_line.as_deref(), Some("Land // Land"));
+        assert_eq!(card.power_toughness, None);
+        assert_eq!(
+            card.oracle_text.as_deref(),
+            Some("{T}: Add {G}.\n//\n{T}: Add {W}.")
+        );
+        assert_eq!(
+            card.details().as_deref(),
+            Some("Land // Land\n{T}: Add {G}.\n//\n{T}: Add {W}.")
+        );
+
+        let Some(BackSide::DfcBack { image_url, .. }) = card.back_side.clone() else {
+            panic!("Expected DfcBack back side");
+        };
+        assert_eq!(
+            card.get_images_for_face_mode(&crate::DoubleFaceMode::BothSides),
+            vec![card.border_crop.clone(), image_url.clone()]
+        );
+        assert_eq!(
+            card.get_images_for_face_mode(&crate::DoubleFaceMode::BackOnly),
+            vec![image_url]
+        );
+        assert_eq!(
+            card.get_images_for_face_mode(&crate::DoubleFaceMode::FrontOnly),
+            vec![card.border_crop.clone()]
+        );
+    }
+
+    #[test]
+    fn test_unresolved_meld_back_face_fallback() {
+        use crate::{BackFaceFallback, DoubleFaceMode};
+
+        let card = Card {
+            name: "bruna, the fading light".to_string(),
+            set: "emn".to_string(),
+            language: "en".to_string(),
+            border_crop: "front_url".to_string(),
+            back_side: Some(BackSide::ContributesToMeld {
+                meld_result_name: "brisela, voice of nightmares".to_string(),
+                meld_result_image_url: String::new(),
+                meld_partner: "gisela, the broken blade".to_string(),
+                set: "emn".to_string(),
+            }),
+            frame: None,
+            digital: false,
+            collector_number: None,
+            prices: None,
+            legalities: Default::default(),
+            layout: Some(CardLayout::Meld),
+            scryfall_id: None,
+            oracle_id: None,
+            mana_cost: None,
+            type_line: None,
+            oracle_text: None,
+            power_toughness: None,
+        };
+        let images = |mode, fallback| card.get_images_for_face_mode_with_fallback(&mode, fallback);
+
+        assert_eq!(
+            images(DoubleFaceMode::BackOnly, BackFaceFallback::Front),
+            vec!["front_url"]
+        );
+        assert!(images(DoubleFaceMode::BackOnly, BackFaceFallback::Skip).is_empty());
+        assert_eq!(
+            images(DoubleFaceMode::BackOnly, BackFaceFallback::Placeholder),
+            vec![PLACEHOLDER_BACK_URL]
+        );
+        assert_eq!(
+            images(DoubleFaceMode::BothSides, BackFaceFallback::Skip),
+            vec!["front_url"]
+        );
+        assert_eq!(
+            images(DoubleFaceMode::BothSides, BackFaceFallback::Placeholder),
+            vec!["front_url", PLACEHOLDER_BACK_URL]
+        );
+        assert!(
+            card.missing_back_face_warning(&DoubleFaceMode::FrontOnly)
+                .is_none()
+        );
+        assert!(
+            card.missing_back_face_warning(&DoubleFaceMode::BackOnly)
+                .is_some()
+        );
+
+        // Resolved meld results are printed whatever the fallback
+        let mut resolved = card.clone();
+        resolved.back_side = Some(BackSide::ContributesToMeld {
+            meld_result_name: "brisela, voice of nightmares".to_string(),
+            meld_result_image_url: "meld_url".to_string(),
+            meld_partner: "gisela, the broken blade".to_string(),
+            set: "emn".to_string(),
+        });
+        assert_eq!(
+            resolved.get_images_for_face_mode_with_fallback(
+                &DoubleFaceMode::BackOnly,
+                BackFaceFallback::Skip
+            ),
+            vec!["meld_url"]
+        );
+        assert!(
+            resolved
+                .missing_back_face_warning(&DoubleFaceMode::BackOnly)
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_layout_parsing() {
+        assert_eq!(CardLayout::from_scryfall("modal_dfc"), CardLayout::ModalDfc);
+        assert_eq!(CardLayout::from_scryfall("saga"), CardLayout::Other);
+        assert!(CardLayout::Meld.is_double_faced());
+        assert!(!CardLayout::Adventure.is_double_faced());
+        assert!(!CardLayout::Other.is_double_faced());
+    }
+
     #[test]
     fn test_supported_languages() {
         let languages = get_minimal_scryfall_languages();
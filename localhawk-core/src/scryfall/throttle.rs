@@ -0,0 +1,94 @@
+use crate::globals::get_download_rate_limit;
+use lazy_static::lazy_static;
+use tokio::time::{Duration, Instant};
+
+// Shared by all downloads, so the limit holds however many images load in parallel
+lazy_static! {
+    static ref DOWNLOAD_BUCKET: std::sync::Mutex<TokenBucket> =
+        std::sync::Mutex::new(TokenBucket::new(Instant::now()));
+}
+
+/// Wait until `bytes` more bytes may be downloaded under the download rate limit
+pub(crate) async fn throttle_download(bytes: usize) {
+    let wait = download_wait(bytes);
+    if !wait.is_zero() {
+        tokio::time::sleep(wait).await;
+    }
+}
+
+/// Blocking version of [`throttle_download`] for the sync HTTP client (iOS)
+#[cfg(feature = "ios")]
+pub(crate) fn throttle_download_blocking(bytes: usize) {
+    let wait = download_wait(bytes);
+    if !wait.is_zero() {
+        std::thread::sleep(wait);
+    }
+}
+
+/// Take `bytes` from the shared bucket, returning how long to wait before using them
+fn download_wait(bytes: usize) -> Duration {
+    let Some(kb_per_second) = get_download_rate_limit() else {
+        return Duration::ZERO;
+    };
+    DOWNLOAD_BUCKET
+        .lock()
+        .unwrap()
+        .take(bytes as u64, kb_per_second * 1024, Instant::now())
+}
+
+/// Token bucket on bytes, holding at most one second worth of tokens
+///
+/// Taking more than the bucket holds leaves it in debt; the caller waits until the debt is paid
+/// off, and later callers wait for theirs on top, so parallel downloads share the rate.
+#[derive(Debug)]
+struct TokenBucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(now: Instant) -> Self {
+        TokenBucket {
+            tokens: 0.0,
+            last_refill: now,
+        }
+    }
+
+    /// Take `bytes` tokens at `bytes_per_second`, returning how long to wait before using them
+    fn take(&mut self, bytes: u64, bytes_per_second: u64, now: Instant) -> Duration {
+        let rate = bytes_per_second.max(1) as f64;
+        let elapsed = now
+            .saturating_duration_since(self.last_refill)
+            .as_secs_f64();
+        self.tokens = (self.tokens + elapsed * rate).min(rate);
+        self.last_refill = now;
+
+        self.tokens -= bytes as f64;
+        if self.tokens < 0.0 {
+            Duration::from_secs_f64(-self.tokens / rate)
+        } else {
+            Duration::ZERO
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_bucket_paces_downloads() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket::new(start);
+
+        // Empty at first: 500 bytes at 1000 bytes/s take half a second
+        assert_eq!(bucket.take(500, 1000, start), Duration::from_millis(500));
+        // A second download right away waits for the first one's debt too
+        assert_eq!(bucket.take(500, 1000, start), Duration::from_secs(1));
+
+        // After the debt is paid off and the bucket refilled, a second's worth is free
+        let later = start + Duration::from_secs(3);
+        assert_eq!(bucket.take(1000, 1000, later), Duration::ZERO);
+        assert_eq!(bucket.take(250, 1000, later), Duration::from_millis(250));
+    }
+}
@@ -0,0 +1,148 @@
+use crate::scryfall::Card;
+
+/// Criteria for narrowing down the printings returned by a card search
+///
+/// Filters work on indices into the original result list, so selections that
+/// refer to `available_printings` positions stay valid while a filter is active.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SearchFilter {
+    /// Only printings from this set (lowercase set code)
+    pub set: Option<String>,
+    /// Only printings in this language (lowercase language code)
+    pub language: Option<String>,
+    /// Only printings with this frame edition (e.g. "1997", "2015")
+    pub frame: Option<String>,
+    /// Hide digital-only printings (MTGO/Arena)
+    pub paper_only: bool,
+}
+
+impl SearchFilter {
+    /// Whether any criterion is set
+    pub fn is_active(&self) -> bool {
+        self.set.is_some() || self.language.is_some() || self.frame.is_some() || self.paper_only
+    }
+
+    pub fn matches(&self, card: &Card) -> bool {
+        self.set
+            .as_ref()
+            .is_none_or(|set| card.set.eq_ignore_ascii_case(set))
+            && self
+                .language
+                .as_ref()
+                .is_none_or(|language| card.language.eq_ignore_ascii_case(language))
+            && self
+                .frame
+                .as_ref()
+                .is_none_or(|frame| card.frame.as_deref() == Some(frame.as_str()))
+            && !(self.paper_only && card.digital)
+    }
+
+    /// Indices of the printings matching this filter, in their original order
+    pub fn matching_indices(&self, cards: &[Card]) -> Vec<usize> {
+        cards
+            .iter()
+            .enumerate()
+            .filter(|(_, card)| self.matches(card))
+            .map(|(index, _)| index)
+            .collect()
+    }
+}
+
+/// Distinct set codes among the printings, sorted alphabetically
+pub fn available_sets(cards: &[Card]) -> Vec<String> {
+    distinct_sorted(cards.iter().map(|card| card.set.clone()))
+}
+
+/// Distinct language codes among the printings, sorted alphabetically
+pub fn available_languages(cards: &[Card]) -> Vec<String> {
+    distinct_sorted(cards.iter().map(|card| card.language.clone()))
+}
+
+/// Distinct frame editions among the printings, sorted alphabetically
+pub fn available_frames(cards: &[Card]) -> Vec<String> {
+    distinct_sorted(cards.iter().filter_map(|card| card.frame.clone()))
+}
+
+fn distinct_sorted(values: impl Iterator<Item = String>) -> Vec<String> {
+    let mut values: Vec<String> = values.collect();
+    values.sort();
+    values.dedup();
+    values
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn printing(set: &str, language: &str, frame: &str, digital: bool) -> Card {
+        Card {
+            name: "plains".to_string(),
+            set: set.to_string(),
+            language: language.to_string(),
+            border_crop: format!("https://example.com/{}-{}.jpg", set, language),
+            back_side: None,
+            frame: Some(frame.to_string()),
+            digital,
+            collector_number: None,
+            prices: None,
+            legalities: Default::default(),
+            layout: None,
+            scryfall_id: None,
+            oracle_id: None,
+            mana_cost: None,
+            type_line: None,
+            oracle_text: None,
+            power_toughness: None,
+        }
+    }
+
+    fn printings() -> Vec<Card> {
+        vec![
+            printing("lea", "en", "1993", false),
+            printing("m21", "en", "2015", false),
+            printing("m21", "ja", "2015", false),
+            printing("prm", "en", "2015", true),
+        ]
+    }
+
+    #[test]
+    fn test_default_filter_matches_everything() {
+        let filter = SearchFilter::default();
+        assert!(!filter.is_active());
+        assert_eq!(filter.matching_indices(&printings()), vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn test_filter_criteria_combine() {
+        let cards = printings();
+
+        let filter = SearchFilter {
+            set: Some("M21".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(filter.matching_indices(&cards), vec![1, 2]);
+
+        let filter = SearchFilter {
+            set: Some("m21".to_string()),
+            language: Some("ja".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(filter.matching_indices(&cards), vec![2]);
+
+        let filter = SearchFilter {
+            frame: Some("2015".to_string()),
+            paper_only: true,
+            ..Default::default()
+        };
+        assert!(filter.is_active());
+        assert_eq!(filter.matching_indices(&cards), vec![1, 2]);
+    }
+
+    #[test]
+    fn test_available_options() {
+        let cards = printings();
+        assert_eq!(available_sets(&cards), vec!["lea", "m21", "prm"]);
+        assert_eq!(available_languages(&cards), vec!["en", "ja"]);
+        assert_eq!(available_frames(&cards), vec!["1993", "2015"]);
+    }
+}
@@ -194,12 +194,25 @@ mod tests {
                 language: "en".to_string(),
                 border_crop: format!("https://example.com/image{}.jpg", i),
                 back_side: None,
+                frame: None,
+                digital: false,
+                collector_number: None,
+                prices: None,
+                legalities: Default::default(),
+                layout: None,
+                scryfall_id: None,
+                oracle_id: None,
+                mana_cost: None,
+                type_line: None,
+                oracle_text: None,
+                power_toughness: None,
             })
             .collect();
 
         CardSearchResult {
             cards,
             total_found: count,
+            has_more: false,
         }
     }
 
@@ -1,13 +1,12 @@
 use crate::error::ProxyError;
 use crate::scryfall::{ScryfallClient, models::ScryfallSetCodes};
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use std::fs;
 use std::path::PathBuf;
-use time::{Duration, OffsetDateTime};
+use time::{Date, Month, OffsetDateTime};
 use tracing::{debug, info, warn};
 
-const CACHE_DURATION_DAYS: i64 = 1; // Same as card names cache for consistency
-
 #[derive(Serialize, Deserialize, Debug)]
 struct CachedSetCodes {
     data: ScryfallSetCodes,
@@ -55,7 +54,7 @@ impl SetCodesCache {
                     return Ok(cached.data);
                 } else {
                     warn!(
-                        max_age_days = CACHE_DURATION_DAYS,
+                        max_age_hours = crate::globals::get_freshness_policy().max_age_hours,
                         "Set codes disk cache expired"
                     );
                 }
@@ -68,7 +67,16 @@ impl SetCodesCache {
 
         // Cache miss or expired - fetch from API
         info!("Fetching fresh set codes from Scryfall API");
-        let set_codes = client.get_set_codes().await?;
+        let set_codes = match client.get_set_codes().await {
+            Ok(set_codes) => set_codes,
+            // Expired set codes beat none while Scryfall is down
+            Err(e) if e.is_scryfall_unavailable() && !force_update => {
+                let cached = self.load_from_cache().map_err(|_| e)?;
+                warn!("Scryfall unavailable, using expired set codes from disk cache");
+                return Ok(cached.data);
+            }
+            Err(e) => return Err(e),
+        };
 
         // Save to cache
         self.save_to_cache(&set_codes)?;
@@ -127,7 +135,7 @@ impl SetCodesCache {
             ProxyError::Cache(format!("Failed to write set codes cache file: {}", e))
         })?;
 
-        log::info!(
+        info!(
             "Saved set codes to cache: {}",
             self.cache_file_path.display()
         );
@@ -135,8 +143,14 @@ impl SetCodesCache {
     }
 
     fn is_cache_valid(&self, cached: &CachedSetCodes) -> bool {
-        let age = OffsetDateTime::now_utc() - cached.cached_at;
-        age < Duration::days(CACHE_DURATION_DAYS)
+        !crate::globals::get_freshness_policy().is_stale(cached.cached_at)
+    }
+
+    /// Load set codes from disk regardless of their age, together with when they were cached
+    pub fn load_cached_set_codes(&self) -> Option<(ScryfallSetCodes, OffsetDateTime)> {
+        self.load_from_cache()
+            .ok()
+            .map(|cached| (cached.data, cached.cached_at))
     }
 
     pub fn get_cache_info(&self) -> Option<(OffsetDateTime, usize)> {
@@ -166,8 +180,94 @@ impl SetCodesCache {
     }
 }
 
+/// Release date of the newest set in `fresh` that `previous` didn't know about
+///
+/// Searches cached before that date can't contain the set's cards, so they are outdated.
+pub fn newest_new_set_release(
+    previous: &ScryfallSetCodes,
+    fresh: &ScryfallSetCodes,
+) -> Option<OffsetDateTime> {
+    let known: HashSet<String> = previous
+        .codes
+        .iter()
+        .map(|code| code.to_lowercase())
+        .collect();
+    fresh
+        .codes
+        .iter()
+        .map(|code| code.to_lowercase())
+        .filter(|code| !known.contains(code))
+        .filter_map(|code| {
+            fresh
+                .sets
+                .get(&code)?
+                .released_at
+                .as_deref()
+                .and_then(parse_release_date)
+        })
+        .max()
+}
+
+/// Midnight UTC of a YYYY-MM-DD release date
+fn parse_release_date(released_at: &str) -> Option<OffsetDateTime> {
+    let mut parts = released_at.splitn(3, '-');
+    let year = parts.next()?.parse().ok()?;
+    let month = Month::try_from(parts.next()?.parse::<u8>().ok()?).ok()?;
+    let day = parts.next()?.parse().ok()?;
+    let date = Date::from_calendar_date(year, month, day).ok()?;
+    Some(date.midnight().assume_utc())
+}
+
 impl Default for SetCodesCache {
     fn default() -> Self {
         Self::new().expect("Failed to create SetCodesCache")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scryfall::models::SetInfo;
+
+    fn set_codes(sets: &[(&str, Option<&str>)]) -> ScryfallSetCodes {
+        ScryfallSetCodes {
+            date: None,
+            codes: sets.iter().map(|(code, _)| code.to_string()).collect(),
+            icon_uris: Default::default(),
+            sets: sets
+                .iter()
+                .map(|(code, released_at)| {
+                    let info = SetInfo {
+                        name: code.to_uppercase(),
+                        released_at: released_at.map(str::to_string),
+                        set_type: "expansion".to_string(),
+                    };
+                    (code.to_string(), info)
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_newest_new_set_release() {
+        let previous = set_codes(&[("dsk", Some("2024-09-27"))]);
+        let fresh = set_codes(&[
+            ("dsk", Some("2024-09-27")),
+            ("fdn", Some("2024-11-15")),
+            ("j25", Some("2024-11-15")),
+            ("pfdn", None),
+            ("dft", Some("2025-02-14")),
+        ]);
+
+        let released = newest_new_set_release(&previous, &fresh).unwrap();
+        assert_eq!(
+            released.date(),
+            Date::from_calendar_date(2025, Month::February, 14).unwrap()
+        );
+        assert_eq!(released.hour(), 0);
+
+        // No new sets, nothing is outdated
+        assert_eq!(newest_new_set_release(&fresh, &fresh), None);
+        assert_eq!(parse_release_date("not a date"), None);
+    }
+}
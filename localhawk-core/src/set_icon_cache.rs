@@ -0,0 +1,85 @@
+use crate::error::ProxyError;
+use std::fs;
+use std::path::PathBuf;
+use tracing::debug;
+
+/// Disk cache of set symbol SVGs, one file per set code
+///
+/// Set symbols practically never change, so unlike the set codes list they are
+/// kept until the cache is cleared.
+#[derive(Debug)]
+pub struct SetIconCache {
+    cache_dir: PathBuf,
+}
+
+impl SetIconCache {
+    pub fn new() -> Result<Self, ProxyError> {
+        let cache_dir = PathBuf::from(crate::get_set_icons_cache_path());
+        fs::create_dir_all(&cache_dir).map_err(|e| {
+            ProxyError::Cache(format!("Failed to create set icon cache directory: {}", e))
+        })?;
+
+        Ok(SetIconCache { cache_dir })
+    }
+
+    fn icon_path(&self, set_code: &str) -> Option<PathBuf> {
+        // Set codes are short alphanumeric strings; refuse anything that could escape the directory
+        let is_valid = !set_code.is_empty() && set_code.chars().all(|c| c.is_ascii_alphanumeric());
+        is_valid.then(|| {
+            self.cache_dir
+                .join(format!("{}.svg", set_code.to_lowercase()))
+        })
+    }
+
+    /// Load the cached symbol of a set
+    pub fn load(&self, set_code: &str) -> Option<Vec<u8>> {
+        let icon = fs::read(self.icon_path(set_code)?).ok()?;
+        debug!(set_code = %set_code, bytes = icon.len(), "Loaded set icon from disk cache");
+        Some(icon)
+    }
+
+    pub fn save(&self, set_code: &str, icon: &[u8]) -> Result<(), ProxyError> {
+        let path = self
+            .icon_path(set_code)
+            .ok_or_else(|| ProxyError::Cache(format!("Invalid set code '{}'", set_code)))?;
+        fs::write(&path, icon)
+            .map_err(|e| ProxyError::Cache(format!("Failed to write set icon cache file: {}", e)))
+    }
+
+    pub fn clear_cache(&self) -> Result<(), ProxyError> {
+        if self.cache_dir.exists() {
+            fs::remove_dir_all(&self.cache_dir).map_err(|e| {
+                ProxyError::Cache(format!("Failed to remove set icon cache directory: {}", e))
+            })?;
+        }
+        Ok(())
+    }
+
+    pub fn get_cache_path(&self) -> &PathBuf {
+        &self.cache_dir
+    }
+}
+
+impl Default for SetIconCache {
+    fn default() -> Self {
+        Self::new().expect("Failed to create SetIconCache")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_icon_path_rejects_unsafe_codes() {
+        let cache = SetIconCache {
+            cache_dir: PathBuf::from("/tmp/localhawk-test-set-icons"),
+        };
+        assert_eq!(
+            cache.icon_path("BRO"),
+            Some(PathBuf::from("/tmp/localhawk-test-set-icons/bro.svg"))
+        );
+        assert_eq!(cache.icon_path("../settings"), None);
+        assert_eq!(cache.icon_path(""), None);
+    }
+}
@@ -0,0 +1,186 @@
+use crate::BackFaceFallback;
+use crate::cache::EvictionPolicy;
+use crate::error::ProxyError;
+use crate::i18n::UiLanguage;
+use crate::legality::Format;
+use crate::output::OutputOptions;
+use crate::pdf::PdfOptions;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::{debug, info, warn};
+
+/// User settings persisted between sessions and shared by all frontends
+///
+/// Missing fields fall back to their defaults, so settings files written by
+/// older versions keep loading after new options are added.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub pdf_options: PdfOptions,
+    /// File names, overwrite policy and splitting of saved PDFs
+    pub output: OutputOptions,
+    /// Show the market price of the real cards next to parsed entries and in the preview
+    pub show_prices: bool,
+    /// Format to check parsed decklists against, if any
+    pub legality_format: Option<Format>,
+    /// Language of the user interface and of messages generated in core
+    pub ui_language: UiLanguage,
+    /// Memory budget of the search results cache in MB (None = built-in default)
+    pub search_cache_max_mb: Option<u64>,
+    /// What to print for back faces whose image is unavailable
+    pub back_face_fallback: BackFaceFallback,
+    /// Use the printing scryfall.com shows for cards without a set or language, instead of
+    /// the first one the printings search returns
+    pub default_printing: bool,
+    /// Keep comment lines like "// lands" as headers of the entries after them, and sort only
+    /// within those groups
+    pub comment_annotations: bool,
+    /// Scryfall code of the language whose printings are preferred for cards without a
+    /// language, e.g. "ja" (None = English)
+    pub preferred_language: Option<String>,
+    /// Scryfall ids of favorite printings, keyed by the oracle id of their card; preferred for
+    /// cards without a set and listed first when choosing a printing
+    pub favorite_printings: BTreeMap<String, Vec<String>>,
+    /// Set codes whose different arts the copies of basic lands without a set are spread
+    /// across, e.g. ["unf"] (empty = all copies use one printing)
+    pub basic_land_sets: Vec<String>,
+    /// Directory of the image, search and card name caches (None = platform cache directory;
+    /// applies after a restart)
+    pub cache_directory: Option<PathBuf>,
+    /// Which images make room when the image cache is full; images of pinned decks are kept
+    /// under any policy
+    pub image_cache_eviction: EvictionPolicy,
+    /// Bandwidth cap for image downloads in KB/s (None = unlimited)
+    pub max_download_rate_kb: Option<u64>,
+    /// Also write logs to daily files in the cache directory (applies after a restart)
+    pub log_to_file: bool,
+    /// Keep local usage statistics (PDFs generated, cache hit rates); never uploaded
+    pub collect_usage_stats: bool,
+    /// Window and panel layout of the GUI
+    pub gui_layout: GuiLayout,
+}
+
+/// Window size, open panels and last used directory of the GUI, restored at startup
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GuiLayout {
+    /// Window size in logical pixels (None = built-in default)
+    pub window_size: Option<(f32, f32)>,
+    pub show_extended_panel: bool,
+    pub show_pdf_options: bool,
+    pub show_search_panel: bool,
+    /// Directory the last file was saved to, where save dialogs start
+    pub last_directory: Option<PathBuf>,
+}
+
+impl Settings {
+    /// Load settings from a JSON file, using defaults if the file is missing or unreadable
+    pub fn load_from(path: &Path) -> Self {
+        if !path.exists() {
+            debug!(path = %path.display(), "No settings file found, using defaults");
+            return Settings::default();
+        }
+
+        match Self::try_load_from(path) {
+            Ok(settings) => {
+                info!(path = %path.display(), "Loaded settings from disk");
+                settings
+            }
+            Err(e) => {
+                warn!(path = %path.display(), error = %e, "Failed to load settings, using defaults");
+                Settings::default()
+            }
+        }
+    }
+
+    pub(crate) fn try_load_from(path: &Path) -> Result<Self, ProxyError> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| ProxyError::Cache(format!("Failed to read settings file: {}", e)))?;
+        serde_json::from_str(&content)
+            .map_err(|e| ProxyError::Serialization(format!("Failed to parse settings: {}", e)))
+    }
+
+    /// Save settings to a JSON file, creating the parent directory if needed
+    pub fn save_to(&self, path: &Path) -> Result<(), ProxyError> {
+        if let Some(parent_dir) = path.parent() {
+            fs::create_dir_all(parent_dir).map_err(|e| {
+                ProxyError::Cache(format!("Failed to create settings directory: {}", e))
+            })?;
+        }
+
+        let content = serde_json::to_string_pretty(self).map_err(|e| {
+            ProxyError::Serialization(format!("Failed to serialize settings: {}", e))
+        })?;
+        fs::write(path, content)
+            .map_err(|e| ProxyError::Cache(format!("Failed to write settings file: {}", e)))?;
+
+        debug!(path = %path.display(), "Saved settings to disk");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::{ImageQuality, PageSize};
+
+    #[test]
+    fn test_settings_round_trip() {
+        let path = std::env::temp_dir()
+            .join(format!("localhawk-settings-test-{}", std::process::id()))
+            .join("settings.json");
+
+        let mut settings = Settings::default();
+        settings.pdf_options.page_size = PageSize::Letter;
+        settings.pdf_options.cards_per_row = 2;
+        settings.pdf_options.cut_markers = true;
+        settings.pdf_options.image_quality = ImageQuality::Medium;
+        settings.show_prices = true;
+        settings.image_cache_eviction = EvictionPolicy::LeastFrequentlyUsed;
+        settings.favorite_printings.insert(
+            "df2af646-3e5b-43a3-8f3e-50565889f456".to_string(),
+            vec!["8aefe8bd-216a-4ec1-9362-3f9dbf7fd083".to_string()],
+        );
+        settings.gui_layout.window_size = Some((1280.0, 900.0));
+        settings.gui_layout.last_directory = Some(PathBuf::from("/tmp/proxies"));
+        settings.save_to(&path).unwrap();
+
+        let loaded = Settings::load_from(&path);
+        assert_eq!(loaded.pdf_options.page_size, PageSize::Letter);
+        assert_eq!(loaded.pdf_options.cards_per_row, 2);
+        assert!(loaded.pdf_options.cut_markers);
+        assert_eq!(loaded.pdf_options.image_quality, ImageQuality::Medium);
+        assert!(loaded.show_prices);
+        assert_eq!(
+            loaded.image_cache_eviction,
+            EvictionPolicy::LeastFrequentlyUsed
+        );
+        assert_eq!(loaded.favorite_printings, settings.favorite_printings);
+        assert_eq!(loaded.gui_layout, settings.gui_layout);
+
+        if let Some(parent) = path.parent() {
+            std::fs::remove_dir_all(parent).ok();
+        }
+    }
+
+    #[test]
+    fn test_missing_or_corrupt_settings_use_defaults() {
+        let dir = std::env::temp_dir().join(format!(
+            "localhawk-settings-corrupt-test-{}",
+            std::process::id()
+        ));
+        let path = dir.join("settings.json");
+
+        let settings = Settings::load_from(&path);
+        assert_eq!(settings.pdf_options.cards_per_row, 3);
+
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(&path, "not json").unwrap();
+        let settings = Settings::load_from(&path);
+        assert_eq!(settings.pdf_options.page_size, PageSize::A4);
+
+        std::fs::remove_dir_all(dir).ok();
+    }
+}
@@ -0,0 +1,160 @@
+use crate::error::ProxyError;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use tokio::sync::OnceCell;
+
+/// Outcome of a fetch shared with every caller that asked for the same key while it ran
+type SharedFetch<T> = Arc<OnceCell<Result<T, String>>>;
+
+/// Coalesces concurrent fetches of the same key into one
+///
+/// The first caller for a key runs the fetch; callers arriving while it is running await
+/// its outcome instead of fetching again. If the first caller is cancelled, one of the
+/// waiting callers runs its own fetch instead.
+pub(crate) struct SingleFlight<T> {
+    pending: Mutex<HashMap<String, SharedFetch<T>>>,
+}
+
+impl<T: Clone> SingleFlight<T> {
+    pub(crate) fn new() -> Self {
+        Self {
+            pending: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Whether a fetch for this key is currently running
+    #[cfg(test)]
+    pub(crate) fn is_pending(&self, key: &str) -> bool {
+        self.pending.lock().unwrap().contains_key(key)
+    }
+
+    /// Run `fetch` for `key`, or wait for the fetch another caller already started
+    ///
+    /// The caller whose fetch ran gets its own error back; waiting callers get it as
+    /// `ProxyError::Cache`, since errors can't be cloned.
+    pub(crate) async fn run<F, Fut>(&self, key: &str, fetch: F) -> Result<T, ProxyError>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<T, ProxyError>>,
+    {
+        let shared = self
+            .pending
+            .lock()
+            .unwrap()
+            .entry(key.to_string())
+            .or_default()
+            .clone();
+        let _guard = PendingGuard {
+            flight: self,
+            key,
+            shared: shared.clone(),
+        };
+
+        let mut own_error = None;
+        let outcome = shared
+            .get_or_init(|| async {
+                fetch().await.map_err(|e| {
+                    let message = e.to_string();
+                    own_error = Some(e);
+                    message
+                })
+            })
+            .await
+            .clone();
+
+        match own_error {
+            Some(e) => Err(e),
+            None => outcome.map_err(ProxyError::Cache),
+        }
+    }
+}
+
+/// Forgets a finished fetch, so later calls check the caches and fetch again if needed
+struct PendingGuard<'a, T> {
+    flight: &'a SingleFlight<T>,
+    key: &'a str,
+    shared: SharedFetch<T>,
+}
+
+impl<T> Drop for PendingGuard<'_, T> {
+    fn drop(&mut self) {
+        // A cancelled fetch stays registered for its waiters to take over, and a newer
+        // fetch registered for the same key must not be removed
+        if !self.shared.initialized() {
+            return;
+        }
+        let mut pending = self.flight.pending.lock().unwrap();
+        if pending
+            .get(self.key)
+            .is_some_and(|shared| Arc::ptr_eq(shared, &self.shared))
+        {
+            pending.remove(self.key);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tokio::sync::oneshot;
+
+    #[tokio::test]
+    async fn test_concurrent_fetches_are_coalesced() {
+        let flight = Arc::new(SingleFlight::<u32>::new());
+        let fetches = Arc::new(AtomicUsize::new(0));
+        let (release_tx, release_rx) = oneshot::channel::<()>();
+
+        let leader = {
+            let flight = flight.clone();
+            let fetches = fetches.clone();
+            tokio::spawn(async move {
+                flight
+                    .run("bolt", || async move {
+                        fetches.fetch_add(1, Ordering::SeqCst);
+                        release_rx.await.unwrap();
+                        Ok(42)
+                    })
+                    .await
+            })
+        };
+        while !flight.is_pending("bolt") {
+            tokio::task::yield_now().await;
+        }
+
+        let follower = {
+            let flight = flight.clone();
+            let fetches = fetches.clone();
+            tokio::spawn(async move {
+                flight
+                    .run("bolt", || async move {
+                        fetches.fetch_add(1, Ordering::SeqCst);
+                        Ok(7)
+                    })
+                    .await
+            })
+        };
+        tokio::task::yield_now().await;
+        release_tx.send(()).unwrap();
+
+        assert_eq!(leader.await.unwrap().unwrap(), 42);
+        assert_eq!(follower.await.unwrap().unwrap(), 42);
+        assert_eq!(fetches.load(Ordering::SeqCst), 1);
+        assert!(!flight.is_pending("bolt"));
+    }
+
+    #[tokio::test]
+    async fn test_finished_fetch_is_not_reused() {
+        let flight = SingleFlight::<u32>::new();
+        let first = flight
+            .run("bolt", || async {
+                Err(ProxyError::Cache("offline".to_string()))
+            })
+            .await;
+        assert!(matches!(first, Err(ProxyError::Cache(message)) if message == "offline"));
+
+        let second = flight.run("bolt", || async { Ok(1) }).await;
+        assert_eq!(second.unwrap(), 1);
+    }
+}
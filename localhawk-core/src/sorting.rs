@@ -0,0 +1,374 @@
+use crate::DoubleFaceMode;
+use crate::scryfall::models::Card;
+use serde::{Deserialize, Serialize};
+
+/// Cards with copy counts and face modes, as used for PDF generation
+type CardCopies = (Card, u32, DoubleFaceMode);
+
+/// Card types in the order used by [`SortOrder::ByType`]; lands come last
+const TYPE_ORDER: [&str; 7] = [
+    "Creature",
+    "Planeswalker",
+    "Battle",
+    "Instant",
+    "Sorcery",
+    "Artifact",
+    "Enchantment",
+];
+
+/// Order of the cards within each print group of the PDF
+///
+/// Cards that sort equal keep their decklist order; where their copies go is up to
+/// [`CopyOrder`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum SortOrder {
+    /// Decklist order
+    #[default]
+    AsEntered,
+    /// By card name
+    Alphabetical,
+    /// Creatures, planeswalkers, battles, instants, sorceries, artifacts, enchantments, lands
+    ByType,
+    /// Decklist order, but lands after everything else
+    LandsLast,
+}
+
+impl std::fmt::Display for SortOrder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SortOrder::AsEntered => write!(f, "As entered"),
+            SortOrder::Alphabetical => write!(f, "Alphabetical"),
+            SortOrder::ByType => write!(f, "By type"),
+            SortOrder::LandsLast => write!(f, "Lands last"),
+        }
+    }
+}
+
+impl std::str::FromStr for SortOrder {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "entered" | "as-entered" => Ok(SortOrder::AsEntered),
+            "alphabetical" | "name" => Ok(SortOrder::Alphabetical),
+            "type" | "by-type" => Ok(SortOrder::ByType),
+            "lands-last" => Ok(SortOrder::LandsLast),
+            _ => Err(format!(
+                "Unknown sort order: {} (expected entered, alphabetical, type or lands-last)",
+                s
+            )),
+        }
+    }
+}
+
+impl SortOrder {
+    pub fn all() -> Vec<SortOrder> {
+        vec![
+            SortOrder::AsEntered,
+            SortOrder::Alphabetical,
+            SortOrder::ByType,
+            SortOrder::LandsLast,
+        ]
+    }
+
+    /// Sort cards with copy counts and face modes, as used for PDF generation
+    pub fn sort_cards(&self, cards: &mut [CardCopies]) {
+        self.sort_by_card(cards, |(card, _, _)| Some(card));
+    }
+
+    /// Sort anything that has a card, e.g. preview entries; items without a card sort first
+    pub fn sort_by_card<T>(&self, items: &mut [T], card: impl Fn(&T) -> Option<&Card>) {
+        if *self == SortOrder::AsEntered {
+            return;
+        }
+        // Stable, so equal keys keep their order
+        items.sort_by_cached_key(|item| card(item).map(|card| self.key(card)));
+    }
+
+    /// Sort like [`SortOrder::sort_by_card`], but only within runs of neighbouring items in the
+    /// same group, e.g. the entries under one "// lands" comment, so the groups stay in place
+    pub fn sort_by_card_within_groups<T, G: PartialEq>(
+        &self,
+        items: &mut [T],
+        group: impl Fn(&T) -> G,
+        card: impl Fn(&T) -> Option<&Card>,
+    ) {
+        for run in items.chunk_by_mut(|left, right| group(left) == group(right)) {
+            self.sort_by_card(run, &card);
+        }
+    }
+
+    fn key(&self, card: &Card) -> (usize, String) {
+        match self {
+            SortOrder::AsEntered => (0, String::new()),
+            SortOrder::Alphabetical => (0, card.name.to_lowercase()),
+            SortOrder::ByType => (type_rank(card), String::new()),
+            SortOrder::LandsLast => (is_land(card) as usize, String::new()),
+        }
+    }
+}
+
+/// Where the copies of a card go within its print group
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum CopyOrder {
+    /// All copies of a card next to each other
+    #[default]
+    Adjacent,
+    /// One copy of every card, then another copy of every card that has more, and so on,
+    /// e.g. to spread the cards of a draft set over the pages
+    Interleaved,
+}
+
+impl std::fmt::Display for CopyOrder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CopyOrder::Adjacent => write!(f, "Together"),
+            CopyOrder::Interleaved => write!(f, "Interleaved"),
+        }
+    }
+}
+
+impl std::str::FromStr for CopyOrder {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "adjacent" => Ok(CopyOrder::Adjacent),
+            "interleaved" | "round-robin" => Ok(CopyOrder::Interleaved),
+            _ => Err(format!(
+                "Unknown copy order: {} (expected adjacent or interleaved)",
+                s
+            )),
+        }
+    }
+}
+
+impl CopyOrder {
+    pub fn all() -> Vec<CopyOrder> {
+        vec![CopyOrder::Adjacent, CopyOrder::Interleaved]
+    }
+
+    /// Order in which to print the copies of items with these copy counts, as
+    /// (item index, copy index) pairs
+    pub fn sequence(&self, copies: &[u32]) -> Vec<(usize, u32)> {
+        match self {
+            CopyOrder::Adjacent => copies
+                .iter()
+                .enumerate()
+                .flat_map(|(item, &count)| (0..count).map(move |copy| (item, copy)))
+                .collect(),
+            CopyOrder::Interleaved => {
+                let rounds = copies.iter().copied().max().unwrap_or(0);
+                (0..rounds)
+                    .flat_map(|round| {
+                        copies
+                            .iter()
+                            .enumerate()
+                            .filter(move |&(_, &count)| round < count)
+                            .map(move |(item, _)| (item, round))
+                    })
+                    .collect()
+            }
+        }
+    }
+
+    /// Arrange cards with copy counts in this order; interleaved, every copy becomes an item of
+    /// its own, so expanding the cards to image URLs keeps the order
+    pub fn arrange(&self, cards: Vec<CardCopies>) -> Vec<CardCopies> {
+        if *self == CopyOrder::Adjacent {
+            return cards;
+        }
+        let copies: Vec<u32> = cards.iter().map(|(_, copies, _)| *copies).collect();
+        self.sequence(&copies)
+            .into_iter()
+            .map(|(item, _)| {
+                let (card, _, face_mode) = &cards[item];
+                (card.clone(), 1, face_mode.clone())
+            })
+            .collect()
+    }
+}
+
+/// Type line of the front face, without subtypes
+fn front_types(card: &Card) -> &str {
+    let type_line = card.type_line.as_deref().unwrap_or_default();
+    let front = type_line.split("//").next().unwrap_or_default();
+    front.split('—').next().unwrap_or_default()
+}
+
+fn is_land(card: &Card) -> bool {
+    front_types(card).contains("Land")
+}
+
+/// Position in [`TYPE_ORDER`], lands and then unknown types last
+fn type_rank(card: &Card) -> usize {
+    if is_land(card) {
+        return TYPE_ORDER.len();
+    }
+    let types = front_types(card);
+    TYPE_ORDER
+        .iter()
+        .position(|kind| types.contains(kind))
+        .unwrap_or(TYPE_ORDER.len() + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn card(name: &str, type_line: Option<&str>) -> CardCopies {
+        let card = Card {
+            name: name.to_string(),
+            set: "tst".to_string(),
+            language: "en".to_string(),
+            border_crop: format!("https://example.com/{}.jpg", name),
+            back_side: None,
+            frame: None,
+            digital: false,
+            collector_number: None,
+            prices: None,
+            legalities: Default::default(),
+            layout: None,
+            scryfall_id: None,
+            oracle_id: None,
+            mana_cost: None,
+            type_line: type_line.map(String::from),
+            oracle_text: None,
+            power_toughness: None,
+        };
+        (card, 2, DoubleFaceMode::BothSides)
+    }
+
+    fn names(cards: &[CardCopies]) -> Vec<&str> {
+        cards
+            .iter()
+            .map(|(card, _, _)| card.name.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_sort_orders() {
+        let deck = vec![
+            card("mountain", Some("Basic Land — Mountain")),
+            card("lightning bolt", Some("Instant")),
+            card("dryad arbor", Some("Land Creature — Forest Dryad")),
+            card("goblin guide", Some("Creature — Goblin Scout")),
+            card("mystery", None),
+            card(
+                "bonecrusher giant",
+                Some("Creature — Giant // Instant — Adventure"),
+            ),
+        ];
+
+        let mut cards = deck.clone();
+        SortOrder::AsEntered.sort_cards(&mut cards);
+        assert_eq!(names(&cards), names(&deck));
+
+        SortOrder::Alphabetical.sort_cards(&mut cards);
+        assert_eq!(
+            names(&cards),
+            vec![
+                "bonecrusher giant",
+                "dryad arbor",
+                "goblin guide",
+                "lightning bolt",
+                "mountain",
+                "mystery"
+            ]
+        );
+
+        let mut cards = deck.clone();
+        SortOrder::ByType.sort_cards(&mut cards);
+        assert_eq!(
+            names(&cards),
+            vec![
+                "goblin guide",
+                "bonecrusher giant",
+                "lightning bolt",
+                "mountain",
+                "dryad arbor",
+                "mystery"
+            ]
+        );
+
+        let mut cards = deck.clone();
+        SortOrder::LandsLast.sort_cards(&mut cards);
+        assert_eq!(
+            names(&cards),
+            vec![
+                "lightning bolt",
+                "goblin guide",
+                "mystery",
+                "bonecrusher giant",
+                "mountain",
+                "dryad arbor"
+            ]
+        );
+
+        assert_eq!("lands-last".parse::<SortOrder>(), Ok(SortOrder::LandsLast));
+        assert!("random".parse::<SortOrder>().is_err());
+    }
+
+    #[test]
+    fn test_sort_within_groups() {
+        // Two comment groups, each sorted on its own
+        let mut cards = vec![
+            (card("shock", Some("Instant")), "burn"),
+            (
+                card("goblin guide", Some("Creature — Goblin Scout")),
+                "burn",
+            ),
+            (card("mountain", Some("Basic Land — Mountain")), "lands"),
+            (card("fiery islet", Some("Land")), "lands"),
+        ];
+        SortOrder::Alphabetical.sort_by_card_within_groups(
+            &mut cards,
+            |(_, group)| *group,
+            |((card, _, _), _)| Some(card),
+        );
+        let names: Vec<&str> = cards
+            .iter()
+            .map(|((card, _, _), _)| card.name.as_str())
+            .collect();
+        assert_eq!(
+            names,
+            vec!["goblin guide", "shock", "fiery islet", "mountain"]
+        );
+    }
+
+    #[test]
+    fn test_copy_orders() {
+        let mut deck = vec![
+            card("lightning bolt", Some("Instant")),
+            card("goblin guide", Some("Creature — Goblin Scout")),
+            card("mountain", Some("Basic Land — Mountain")),
+        ];
+        deck[0].1 = 3;
+        deck[1].1 = 1;
+
+        assert_eq!(CopyOrder::Adjacent.arrange(deck.clone()), deck);
+
+        let interleaved = CopyOrder::Interleaved.arrange(deck.clone());
+        assert_eq!(
+            names(&interleaved),
+            vec![
+                "lightning bolt",
+                "goblin guide",
+                "mountain",
+                "lightning bolt",
+                "mountain",
+                "lightning bolt"
+            ]
+        );
+        assert!(interleaved.iter().all(|(_, copies, _)| *copies == 1));
+
+        assert_eq!(
+            CopyOrder::Interleaved.sequence(&[2, 0, 1]),
+            vec![(0, 0), (2, 0), (0, 1)]
+        );
+        assert_eq!(
+            "round-robin".parse::<CopyOrder>(),
+            Ok(CopyOrder::Interleaved)
+        );
+    }
+}
@@ -0,0 +1,198 @@
+use crate::error::ProxyError;
+use crate::i18n::{Text, tr_with};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+use time::OffsetDateTime;
+use tracing::{debug, warn};
+
+/// Usage statistics kept on this machine to help tune the cache sizes
+///
+/// Only collected when enabled in the settings, and never sent anywhere. Like the settings,
+/// missing fields fall back to zero so files written by older versions keep loading.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UsageStats {
+    /// When collecting started (or the statistics were last reset)
+    pub since: Option<OffsetDateTime>,
+    pub pdfs_generated: u64,
+    /// Cards (copies, not images) in all generated PDFs
+    pub cards_generated: u64,
+    /// Time spent generating PDFs, image downloads included
+    pub pdf_time_ms: u64,
+    pub image_cache_hits: u64,
+    pub image_cache_misses: u64,
+    pub search_cache_hits: u64,
+    pub search_cache_misses: u64,
+}
+
+impl UsageStats {
+    /// Statistics starting now
+    pub fn new() -> Self {
+        UsageStats {
+            since: Some(OffsetDateTime::now_utc()),
+            ..UsageStats::default()
+        }
+    }
+
+    pub fn record_pdf(&mut self, cards: u64, duration: Duration) {
+        self.pdfs_generated += 1;
+        self.cards_generated += cards;
+        self.pdf_time_ms += duration.as_millis() as u64;
+    }
+
+    pub fn record_image_lookup(&mut self, hit: bool) {
+        if hit {
+            self.image_cache_hits += 1;
+        } else {
+            self.image_cache_misses += 1;
+        }
+    }
+
+    pub fn record_search_lookup(&mut self, hit: bool) {
+        if hit {
+            self.search_cache_hits += 1;
+        } else {
+            self.search_cache_misses += 1;
+        }
+    }
+
+    /// Average time to generate a PDF, if any were generated
+    pub fn average_pdf_time(&self) -> Option<Duration> {
+        (self.pdfs_generated > 0)
+            .then(|| Duration::from_millis(self.pdf_time_ms / self.pdfs_generated))
+    }
+
+    /// Share of image lookups answered from the cache (0.0 to 1.0)
+    pub fn image_cache_hit_rate(&self) -> Option<f64> {
+        hit_rate(self.image_cache_hits, self.image_cache_misses)
+    }
+
+    /// Share of card searches answered from the cache (0.0 to 1.0)
+    pub fn search_cache_hit_rate(&self) -> Option<f64> {
+        hit_rate(self.search_cache_hits, self.search_cache_misses)
+    }
+
+    /// Human-readable summary in the UI language, one statistic per line
+    pub fn summary(&self) -> Vec<String> {
+        let percent = |rate: Option<f64>| match rate {
+            Some(rate) => format!("{:.0}%", rate * 100.0),
+            None => "-".to_string(),
+        };
+        let since = self
+            .since
+            .map(|since| since.date().to_string())
+            .unwrap_or_else(|| "-".to_string());
+        let average = self
+            .average_pdf_time()
+            .map(|duration| format!("{:.1} s", duration.as_secs_f64()))
+            .unwrap_or_else(|| "-".to_string());
+
+        vec![
+            tr_with(Text::StatsSince, &[&since]),
+            tr_with(Text::StatsPdfsGenerated, &[&self.pdfs_generated]),
+            tr_with(Text::StatsCardsGenerated, &[&self.cards_generated]),
+            tr_with(Text::StatsAveragePdfTime, &[&average]),
+            tr_with(
+                Text::StatsImageCacheHitRate,
+                &[
+                    &percent(self.image_cache_hit_rate()),
+                    &self.image_cache_hits,
+                    &self.image_cache_misses,
+                ],
+            ),
+            tr_with(
+                Text::StatsSearchCacheHitRate,
+                &[
+                    &percent(self.search_cache_hit_rate()),
+                    &self.search_cache_hits,
+                    &self.search_cache_misses,
+                ],
+            ),
+        ]
+    }
+
+    /// Load statistics from a JSON file, starting over if the file is missing or unreadable
+    pub fn load_from(path: &Path) -> Self {
+        if !path.exists() {
+            return UsageStats::new();
+        }
+
+        let loaded = fs::read_to_string(path)
+            .map_err(|e| ProxyError::Cache(format!("Failed to read usage statistics: {}", e)))
+            .and_then(|content| {
+                serde_json::from_str(&content).map_err(|e| {
+                    ProxyError::Serialization(format!("Failed to parse usage statistics: {}", e))
+                })
+            });
+        match loaded {
+            Ok(stats) => stats,
+            Err(e) => {
+                warn!(path = %path.display(), error = %e, "Failed to load usage statistics, starting over");
+                UsageStats::new()
+            }
+        }
+    }
+
+    /// Save statistics to a JSON file, creating the parent directory if needed
+    pub fn save_to(&self, path: &Path) -> Result<(), ProxyError> {
+        if let Some(parent_dir) = path.parent() {
+            fs::create_dir_all(parent_dir).map_err(|e| {
+                ProxyError::Cache(format!("Failed to create statistics directory: {}", e))
+            })?;
+        }
+
+        let content = serde_json::to_string_pretty(self).map_err(|e| {
+            ProxyError::Serialization(format!("Failed to serialize usage statistics: {}", e))
+        })?;
+        fs::write(path, content)
+            .map_err(|e| ProxyError::Cache(format!("Failed to write usage statistics: {}", e)))?;
+
+        debug!(path = %path.display(), "Saved usage statistics to disk");
+        Ok(())
+    }
+}
+
+fn hit_rate(hits: u64, misses: u64) -> Option<f64> {
+    let lookups = hits + misses;
+    (lookups > 0).then(|| hits as f64 / lookups as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_usage_stats_rates_and_round_trip() {
+        let mut stats = UsageStats::new();
+        assert_eq!(stats.average_pdf_time(), None);
+        assert_eq!(stats.image_cache_hit_rate(), None);
+
+        stats.record_pdf(60, Duration::from_secs(3));
+        stats.record_pdf(40, Duration::from_secs(1));
+        for hit in [true, true, true, false] {
+            stats.record_image_lookup(hit);
+        }
+        stats.record_search_lookup(false);
+        assert_eq!(stats.cards_generated, 100);
+        assert_eq!(stats.average_pdf_time(), Some(Duration::from_secs(2)));
+        assert_eq!(stats.image_cache_hit_rate(), Some(0.75));
+        assert_eq!(stats.search_cache_hit_rate(), Some(0.0));
+        assert!(
+            stats
+                .summary()
+                .contains(&"Cards generated: 100".to_string())
+        );
+
+        let path = std::env::temp_dir()
+            .join(format!("localhawk-stats-test-{}", std::process::id()))
+            .join("usage_stats.json");
+        stats.save_to(&path).unwrap();
+        assert_eq!(UsageStats::load_from(&path), stats);
+
+        if let Some(parent) = path.parent() {
+            std::fs::remove_dir_all(parent).ok();
+        }
+    }
+}
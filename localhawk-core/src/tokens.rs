@@ -0,0 +1,205 @@
+//! Tokens a card's rules text says it creates, e.g. "create a 1/1 white Soldier creature
+//! token", so a deck can be proxied together with its tokens
+//!
+//! The rules text is scanned for token descriptions, which become Scryfall searches for
+//! matching token cards. The matches are only suggestions: a description rarely pins a single
+//! token printing, and the decklist may already have its own tokens.
+
+use crate::i18n::{Text, tr_with};
+use lazy_static::lazy_static;
+use regex::Regex;
+
+lazy_static! {
+    // "create" (a count) (tapped) (P/T) (colors) (Capitalized Subtypes) (card types) "token"
+    static ref RE_CREATE_TOKEN: Regex = Regex::new(
+        r"\b[Cc]reates? (?:a|an|one|two|three|four|five|six|seven|eight|nine|ten|X|that many|a number of)(?: tapped)?(?: legendary)?(?: ([0-9X*]+)/([0-9X*]+))?((?:,? (?:and )?(?:white|blue|black|red|green|colorless))*)((?: [A-Z][A-Za-z'-]*)+)((?: artifact| enchantment| creature)*) tokens?\b"
+    )
+    .unwrap();
+}
+
+/// A token described in rules text, e.g. "a 1/1 white Soldier creature token"
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenDescription {
+    /// Power and toughness as written, e.g. ("1", "1") or ("X", "X"); None for noncreature
+    /// tokens like Treasure
+    pub power_toughness: Option<(String, String)>,
+    /// Color words, e.g. ["white", "black"]; empty when the text names none
+    pub colors: Vec<String>,
+    /// Subtypes naming the token, e.g. "Soldier", "Treasure" or "Eldrazi Spawn"
+    pub name: String,
+    /// Card types before "token", e.g. "artifact creature"; empty for e.g. Treasure
+    pub card_types: String,
+}
+
+impl TokenDescription {
+    /// The description as rules text puts it, e.g. "1/1 white Soldier creature token"
+    pub fn describe(&self) -> String {
+        let mut words = Vec::new();
+        if let Some((power, toughness)) = &self.power_toughness {
+            words.push(format!("{}/{}", power, toughness));
+        }
+        if !self.colors.is_empty() {
+            words.push(self.colors.join(" and "));
+        }
+        words.push(self.name.clone());
+        if !self.card_types.is_empty() {
+            words.push(self.card_types.clone());
+        }
+        words.push("token".to_string());
+        words.join(" ")
+    }
+
+    /// Scryfall search for the token cards matching this description, e.g.
+    /// `t:token t:soldier pow=1 tou=1 c=w`
+    pub fn query(&self) -> String {
+        let mut terms = vec!["t:token".to_string()];
+        terms.extend(
+            self.name
+                .split_whitespace()
+                .map(|subtype| format!("t:{}", subtype.to_lowercase())),
+        );
+        // Variable power and toughness are printed as stars or zero, so they can't be searched
+        if let Some((power, toughness)) = &self.power_toughness {
+            if power.chars().all(|c| c.is_ascii_digit()) {
+                terms.push(format!("pow={}", power));
+            }
+            if toughness.chars().all(|c| c.is_ascii_digit()) {
+                terms.push(format!("tou={}", toughness));
+            }
+        }
+        let colors: String = self
+            .colors
+            .iter()
+            .filter_map(|color| match color.as_str() {
+                "white" => Some('w'),
+                "blue" => Some('u'),
+                "black" => Some('b'),
+                "red" => Some('r'),
+                "green" => Some('g'),
+                "colorless" => Some('c'),
+                _ => None,
+            })
+            .collect();
+        if !colors.is_empty() {
+            terms.push(format!("c={}", colors));
+        }
+        terms.join(" ")
+    }
+}
+
+/// Every token `oracle_text` creates, in order and without repeats
+pub fn tokens_in_rules_text(oracle_text: &str) -> Vec<TokenDescription> {
+    let mut tokens: Vec<TokenDescription> = Vec::new();
+    for captures in RE_CREATE_TOKEN.captures_iter(oracle_text) {
+        let power_toughness = captures
+            .get(1)
+            .zip(captures.get(2))
+            .map(|(power, toughness)| (power.as_str().to_string(), toughness.as_str().to_string()));
+        let colors = captures[3]
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|word| !word.is_empty() && *word != "and")
+            .map(str::to_string)
+            .collect();
+        let token = TokenDescription {
+            power_toughness,
+            colors,
+            name: captures[4].trim().to_string(),
+            card_types: captures[5].trim().to_string(),
+        };
+        if !tokens.contains(&token) {
+            tokens.push(token);
+        }
+    }
+    tokens
+}
+
+/// A token card matching a token some card of the deck creates, offered as an addition
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenSuggestion {
+    /// Name of the card whose rules text creates the token
+    pub source: String,
+    /// The token as the rules text describes it, see [`TokenDescription::describe`]
+    pub description: String,
+    /// Decklist line naming the matching token printing exactly, e.g.
+    /// "1 Soldier (TMH2) 2 [en]"
+    pub line: String,
+}
+
+impl TokenSuggestion {
+    /// e.g. "Suggested token for Raise the Alarm: 1/1 white Soldier creature token"
+    pub fn annotation(&self) -> String {
+        tr_with(Text::SuggestedToken, &[&self.source, &self.description])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token(
+        power_toughness: Option<(&str, &str)>,
+        colors: &[&str],
+        name: &str,
+    ) -> TokenDescription {
+        TokenDescription {
+            power_toughness: power_toughness
+                .map(|(power, toughness)| (power.to_string(), toughness.to_string())),
+            colors: colors.iter().map(|color| color.to_string()).collect(),
+            name: name.to_string(),
+            card_types: if power_toughness.is_some() {
+                "creature".to_string()
+            } else {
+                String::new()
+            },
+        }
+    }
+
+    #[test]
+    fn test_tokens_in_rules_text() {
+        assert_eq!(
+            tokens_in_rules_text("Create two 1/1 white Soldier creature tokens."),
+            vec![token(Some(("1", "1")), &["white"], "Soldier")]
+        );
+        assert_eq!(
+            tokens_in_rules_text(
+                "When this enters, create a Treasure token.\nWhenever you attack, create a \
+                 Treasure token."
+            ),
+            vec![token(None, &[], "Treasure")]
+        );
+        assert_eq!(
+            tokens_in_rules_text(
+                "Create a 1/1 white and black Spirit creature token with flying. Then create \
+                 a 0/1 colorless Eldrazi Spawn creature token."
+            ),
+            vec![
+                token(Some(("1", "1")), &["white", "black"], "Spirit"),
+                token(Some(("0", "1")), &["colorless"], "Eldrazi Spawn"),
+            ]
+        );
+        // Copies of other permanents aren't described well enough to search for
+        assert!(
+            tokens_in_rules_text("Create a token that's a copy of target creature.").is_empty()
+        );
+        assert!(tokens_in_rules_text("Flying").is_empty());
+    }
+
+    #[test]
+    fn test_token_query_and_description() {
+        let golem =
+            tokens_in_rules_text("create a tapped 3/3 colorless Golem artifact creature token");
+        assert_eq!(golem[0].card_types, "artifact creature");
+        assert_eq!(
+            golem[0].describe(),
+            "3/3 colorless Golem artifact creature token"
+        );
+        assert_eq!(golem[0].query(), "t:token t:golem pow=3 tou=3 c=c");
+
+        let ooze = tokens_in_rules_text("create an X/X green Ooze creature token");
+        assert_eq!(ooze[0].query(), "t:token t:ooze c=g");
+        assert_eq!(
+            tokens_in_rules_text("create a Food token")[0].query(),
+            "t:token t:food"
+        );
+    }
+}
@@ -0,0 +1,239 @@
+//! End-to-end proxy generation: parse a decklist, resolve it against recorded Scryfall cards and
+//! generate the PDF
+//!
+//! The card names, set codes and printings searches are seeded from the card objects in
+//! `test_data` and card images are generated here, so these tests need neither network access
+//! nor populated caches.
+
+use localhawk_core::globals::get_search_results_cache;
+use localhawk_core::{
+    CaptionedImage, Card, CardNameLookup, CardSearchResult, DoubleFaceMode, LineIssue, PdfOptions,
+    ProxyGenerator, generate_pdf_from_groups, get_card_lookup, get_set_codes_cache,
+    set_cache_directory, set_read_only_caches,
+};
+use printpdf::image_crate::{DynamicImage, Rgb, RgbImage};
+use printpdf::lopdf::Document;
+use std::sync::Once;
+
+const RECORDED_CARDS: &str =
+    include_str!("../test_data/default-cards-20200910090351_truncated.json");
+
+const DECKLIST: &str = "4 Fury Slivr
+2 Branchloft Pathway
+1 Boulderloft Pathway
+1 Consecrate/Consume
+3 Kor Outfitter (zen)
+1 Not A Real Card";
+
+/// Not among the recorded cards, so Scryfall finds no printings of it
+const UNKNOWN_CARD: &str = "Not A Real Card";
+
+/// Cards of the recorded Scryfall responses, in the order Scryfall returned them
+fn recorded_cards() -> Vec<Card> {
+    let objects: Vec<serde_json::Map<String, serde_json::Value>> =
+        serde_json::from_str(RECORDED_CARDS).unwrap();
+    objects
+        .iter()
+        .filter_map(|object| Card::from_scryfall_object(object).ok())
+        .collect()
+}
+
+/// Replays a printings search: the recorded cards with exactly this name
+fn search(cards: &[Card], name: &str) -> CardSearchResult {
+    let cards: Vec<Card> = cards
+        .iter()
+        .filter(|card| card.name.to_lowercase() == name.to_lowercase())
+        .cloned()
+        .collect();
+    CardSearchResult {
+        total_found: cards.len(),
+        cards,
+        has_more: false,
+    }
+}
+
+/// Fill the global card name lookup, set codes and printings searches from the recorded cards,
+/// the way `initialize_caches` and the first searches would
+fn seed_recorded_caches() {
+    static SEEDED: Once = Once::new();
+    SEEDED.call_once(|| {
+        // Start from empty caches and keep them in memory
+        set_cache_directory(Some(
+            std::env::temp_dir().join(format!("localhawk-end-to-end-{}", std::process::id())),
+        ));
+        set_read_only_caches(true);
+
+        let cards = recorded_cards();
+        let mut names: Vec<String> = cards.iter().map(|card| card.name.clone()).collect();
+        *get_card_lookup().write().unwrap() = Some(CardNameLookup::from_card_names(&names));
+        *get_set_codes_cache().write().unwrap() =
+            Some(cards.iter().map(|card| card.set.clone()).collect());
+
+        names.push(UNKNOWN_CARD.to_string());
+        let mut search_cache = get_search_results_cache().write().unwrap();
+        for name in names {
+            search_cache
+                .insert(name.to_lowercase(), search(&cards, &name))
+                .unwrap();
+        }
+    });
+}
+
+/// Parse, resolve and pick the printings of the decklist like every frontend does
+async fn resolve(decklist: &str) -> Vec<(Card, u32, DoubleFaceMode)> {
+    seed_recorded_caches();
+    let entries = ProxyGenerator::parse_and_resolve_decklist(decklist, DoubleFaceMode::BothSides)
+        .await
+        .unwrap();
+    ProxyGenerator::resolve_decklist_entries_to_cards(&entries)
+        .await
+        .unwrap()
+}
+
+/// Stands in for a downloaded card image, colored by its position in the PDF
+fn fixture_image(index: usize) -> DynamicImage {
+    let shade = (index * 17 % 256) as u8;
+    DynamicImage::ImageRgb8(RgbImage::from_pixel(63, 88, Rgb([shade, 128, 255 - shade])))
+}
+
+/// Number of images drawn on each page of a PDF
+fn images_per_page(pdf: &[u8]) -> Vec<usize> {
+    let document = Document::load_mem(pdf).unwrap();
+    document
+        .get_pages()
+        .into_values()
+        .map(|page_id| {
+            // Every image is drawn with its own "Do" operator
+            let content = document.get_page_content(page_id).unwrap();
+            String::from_utf8_lossy(&content)
+                .split_whitespace()
+                .filter(|token| *token == "Do")
+                .count()
+        })
+        .collect()
+}
+
+fn image_url(card: &Card, face_mode: DoubleFaceMode) -> String {
+    let urls = card.get_images_for_face_mode(&face_mode);
+    assert_eq!(urls.len(), 1);
+    urls[0].clone()
+}
+
+#[tokio::test]
+async fn test_decklist_resolves_to_recorded_printings() {
+    let cards = resolve(DECKLIST).await;
+
+    let resolved: Vec<(&str, &str, u32, DoubleFaceMode)> = cards
+        .iter()
+        .map(|(card, copies, face_mode)| {
+            (
+                card.name.as_str(),
+                card.set.as_str(),
+                *copies,
+                face_mode.clone(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        resolved,
+        vec![
+            ("Fury Sliver", "tsp", 4, DoubleFaceMode::BothSides),
+            (
+                "Branchloft Pathway // Boulderloft Pathway",
+                "znr",
+                2,
+                DoubleFaceMode::BothSides
+            ),
+            (
+                "Branchloft Pathway // Boulderloft Pathway",
+                "znr",
+                1,
+                DoubleFaceMode::BackOnly
+            ),
+            ("Consecrate // Consume", "rna", 1, DoubleFaceMode::BothSides),
+            ("Kor Outfitter", "zen", 3, DoubleFaceMode::BothSides),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_unknown_card_is_reported() {
+    seed_recorded_caches();
+    let (entries, report) =
+        ProxyGenerator::parse_and_resolve_decklist_with_report(DECKLIST, DoubleFaceMode::BothSides)
+            .await
+            .unwrap();
+
+    assert_eq!(entries.len(), 6);
+    assert_eq!(report.issues.keys().copied().collect::<Vec<_>>(), vec![5]);
+    assert!(matches!(
+        &report.issues[&5],
+        LineIssue::Unresolved { name, .. } if name == UNKNOWN_CARD
+    ));
+}
+
+#[tokio::test]
+async fn test_unknown_collector_number_range_is_reported_once() {
+    seed_recorded_caches();
+    let decklist = format!("4 {} (zen) 1-50\n1 {}", UNKNOWN_CARD, UNKNOWN_CARD);
+    let (entries, report) = ProxyGenerator::parse_and_resolve_decklist_with_report(
+        &decklist,
+        DoubleFaceMode::BothSides,
+    )
+    .await
+    .unwrap();
+
+    // One entry per collector number, but one issue per line
+    assert_eq!(entries.len(), 51);
+    assert_eq!(
+        report.issues.keys().copied().collect::<Vec<_>>(),
+        vec![0, 1]
+    );
+}
+
+#[tokio::test]
+async fn test_dry_run_pages_keep_decklist_order() {
+    let cards = resolve(DECKLIST).await;
+    let options = PdfOptions::default();
+
+    let pages = ProxyGenerator::plan_pages_from_card_groups(&[cards.clone()], &options);
+    let page_sizes: Vec<usize> = pages.iter().map(Vec::len).collect();
+    assert_eq!(page_sizes, vec![9, 4]);
+
+    let fury_sliver = image_url(&cards[0].0, DoubleFaceMode::FrontOnly);
+    let branchloft = image_url(&cards[1].0, DoubleFaceMode::FrontOnly);
+    let boulderloft = image_url(&cards[1].0, DoubleFaceMode::BackOnly);
+    let consecrate = image_url(&cards[3].0, DoubleFaceMode::FrontOnly);
+    let kor_outfitter = image_url(&cards[4].0, DoubleFaceMode::FrontOnly);
+    assert_ne!(branchloft, boulderloft);
+
+    let mut expected = vec![fury_sliver; 4];
+    for _ in 0..2 {
+        expected.push(branchloft.clone());
+        expected.push(boulderloft.clone());
+    }
+    expected.push(boulderloft);
+    expected.push(consecrate);
+    expected.extend(vec![kor_outfitter; 3]);
+    assert_eq!(pages.concat(), expected);
+}
+
+#[tokio::test]
+async fn test_generated_pdf_has_every_image() {
+    let cards = resolve(DECKLIST).await;
+    let options = PdfOptions::default();
+
+    let images: Vec<CaptionedImage> = ProxyGenerator::expand_cards_to_captioned_image_urls(&cards)
+        .into_iter()
+        .enumerate()
+        .map(|(index, (_url, caption))| CaptionedImage {
+            image: fixture_image(index),
+            caption,
+            divider: None,
+        })
+        .collect();
+    let pdf = generate_pdf_from_groups(vec![images], options).unwrap();
+
+    assert_eq!(&pdf[0..4], b"%PDF");
+    assert_eq!(images_per_page(&pdf), vec![9, 4]);
+}
@@ -1,33 +1,126 @@
+use crate::notifications::{NotificationLevel, Notifications};
+use iced::keyboard::{self, Key, Modifiers, key::Named};
 use iced::widget::{
-    button, column, container, image, pick_list, row, scrollable, text, text_editor,
+    button, checkbox, column, container, image, mouse_area, pick_list, progress_bar, rich_text,
+    row, scrollable, slider, span, stack, svg, text, text_editor, text_input, tooltip,
 };
 use iced::widget::{horizontal_space, rule};
-use iced::{Element, Length, Task};
+use iced::{Element, Length, Size, Subscription, Task, event, mouse, window};
 use localhawk_core::{
-    BackgroundLoadHandle,
+    BackFaceFallback,
     BackgroundLoadProgress,
+    BackgroundLoader,
+    CacheEvent,
+    CacheRefreshStatus,
+    Card,
+    CardSearchResult,
+    Collection,
+    CopyOrder,
+    DEFAULT_FILE_NAME_TEMPLATE,
+    DeckSection,
     DecklistEntry,
+    DecklistFormat,
     DoubleFaceMode,
-    GridImage,
-    GridPosition,
+    DownloadEstimate,
+    EvictionPolicy,
+    Format,
     GridPreview,
+    GuiLayout,
+    ImageCacheContents,
+    ImagePin,
+    ImageQuality,
     LoadingPhase,
+    OutputOptions,
+    OverwritePolicy,
     PageNavigation,
+    PageSize,
     PaginatedGrid,
     PdfOptions,
+    PinnedDecks,
     PreviewEntry,
+    PrintEstimate,
     ProxyGenerator,
+    RecentDecklist,
+    ResolutionReport,
+    SearchFilter,
+    SectionSplit,
+    SortOrder,
+    Text,
+    TokenSuggestion,
+    UiLanguage,
+    available_frames,
+    available_languages,
+    available_sets,
     // Import the new modules
-    build_aligned_parsed_output,
+    build_aligned_parsed_output_with_report,
+    calibrated_scale_correction,
+    check_legality,
+    count_cached_images,
+    export_logs,
     force_update_card_lookup,
+    format_normalized_decklist,
+    format_price_total,
+    generate_calibration_page,
+    get_back_face_fallback,
+    get_basic_land_sets,
+    get_cache_refresh_status,
     get_cached_image_bytes,
+    get_cached_set_icon,
+    get_cached_thumbnail_bytes,
     get_card_name_cache_info,
     get_card_names_cache_size,
+    get_comment_annotations,
+    get_decklist_history,
+    get_default_printing,
+    get_image_cache_contents,
+    get_image_cache_eviction_policy,
     get_image_cache_info,
+    get_log_directory_path,
+    get_minimal_scryfall_languages,
+    get_or_fetch_image_bytes,
+    get_or_fetch_set_icon,
+    get_pinned_decks,
+    get_preferred_language,
     get_search_results_cache_info,
-    start_background_image_loading,
+    get_set_info,
+    get_settings,
+    get_usage_stats,
+    is_favorite_printing,
+    is_scryfall_unavailable,
+    parse_deck_name,
+    pin_deck,
+    pin_images,
+    print_file,
+    record_recent_decklist,
+    reset_usage_stats,
+    run_diagnostics,
+    set_back_face_fallback,
+    set_basic_land_sets,
+    set_comment_annotations,
+    set_default_printing,
+    set_display_name,
+    set_download_rate_limit,
+    set_favorite_printings,
+    set_image_cache_eviction_policy,
+    set_preferred_language,
+    set_ui_language,
+    subscribe_cache_events,
+    subscribe_cache_refresh_status,
+    toggle_favorite_printing,
+    tr,
+    tr_with,
+    ui_language,
+    unpin_deck,
+    update_settings,
+    write_pdfs,
 };
 use rfd::AsyncFileDialog;
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+use tokio::sync::broadcast::error::RecvError;
 
 // Constants for grid preview card dimensions (maintaining Magic card aspect ratio: 480:680 = ~0.706)
 const GRID_CARD_WIDTH: f32 = 200.0;
@@ -41,10 +134,34 @@ const THUMBNAIL_HEIGHT: f32 = GRID_CARD_HEIGHT;
 const PRINT_SELECTION_COLUMNS: usize = 5;
 const PRINT_SELECTION_ROWS: usize = 3;
 const PRINTS_PER_PAGE: usize = PRINT_SELECTION_COLUMNS * PRINT_SELECTION_ROWS;
+// Printings compared side by side are shown at the size of Scryfall's border crop images
+const COMPARE_CARD_WIDTH: f32 = 480.0;
+const COMPARE_CARD_HEIGHT: f32 = 680.0;
+// Size of the set symbol shown next to each printing's set code
+const SET_ICON_SIZE: f32 = 14.0;
 // Font size constant for UI consistency
 const UI_FONT_SIZE: u16 = 14;
 // Advanced options sidebar width
 const ADVANCED_SIDEBAR_WIDTH: f32 = 480.0;
+// Notification toasts in the bottom right corner
+const NOTIFICATION_WIDTH: f32 = 420.0;
+const NOTIFICATION_DETAILS_MAX_HEIGHT: f32 = 200.0;
+// Network-bound tasks give up after this long, since requests have no timeout of their own;
+// generating a PDF downloads all of its images first, so it gets much longer
+const NETWORK_TASK_TIMEOUT: Duration = Duration::from_secs(120);
+const PDF_GENERATION_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+// Results listed in the Scryfall search panel
+const MAX_LISTED_SEARCH_RESULTS: usize = 100;
+// Choices offered in the PDF options panel
+const MAX_CARDS_PER_ROW_OR_COLUMN: u32 = 4;
+const MAX_MARGIN_MM: f32 = 15.0;
+// Border color marking commanders and companions in the grid preview
+const COMMAND_ZONE_HIGHLIGHT: iced::Color = iced::Color {
+    r: 0.85,
+    g: 0.65,
+    b: 0.1,
+    a: 1.0,
+};
 
 /// Create navigation controls for a paginated grid (GUI helper)
 fn create_navigation_controls_for_grid(
@@ -87,23 +204,181 @@ fn create_navigation_controls_for_grid(
 #[derive(Debug, Clone, PartialEq)]
 pub enum PreviewMode {
     Hidden,         // Traditional workflow (parse → generate)
-    GridPreview,    // Show 3x3 grid preview
+    GridPreview,    // Show grid preview laid out like the PDF pages
     PrintSelection, // Modal for selecting prints
 }
 
+/// Keyboard shortcuts; what they do depends on the current preview mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shortcut {
+    /// PageUp: previous preview page (or print selection page)
+    PreviousPage,
+    /// PageDown: next preview page (or print selection page)
+    NextPage,
+    /// Ctrl+Enter (Cmd+Enter on macOS): same as "PDF from Decklist"
+    GenerateAndSave,
+    /// Esc: close the print selection modal
+    Close,
+    /// Arrow keys: move the highlighted printing in the print selection modal
+    MoveLeft,
+    MoveRight,
+    MoveUp,
+    MoveDown,
+    /// Enter: select the highlighted printing
+    Confirm,
+}
+
+/// Pick list entry for an optional print selection filter value
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FilterChoice {
+    Any,
+    Only(String),
+}
+
+impl FilterChoice {
+    fn options(values: Vec<String>) -> Vec<FilterChoice> {
+        std::iter::once(FilterChoice::Any)
+            .chain(values.into_iter().map(FilterChoice::Only))
+            .collect()
+    }
+
+    fn from_filter(value: &Option<String>) -> FilterChoice {
+        value.clone().map_or(FilterChoice::Any, FilterChoice::Only)
+    }
+
+    fn into_filter(self) -> Option<String> {
+        match self {
+            FilterChoice::Any => None,
+            FilterChoice::Only(value) => Some(value),
+        }
+    }
+}
+
+impl std::fmt::Display for FilterChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FilterChoice::Any => write!(f, "Any"),
+            FilterChoice::Only(value) => write!(f, "{}", value.to_uppercase()),
+        }
+    }
+}
+
+/// Pick list entry for the format decklists are checked against
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FormatChoice {
+    NoCheck,
+    Check(Format),
+}
+
+impl FormatChoice {
+    fn options() -> Vec<FormatChoice> {
+        std::iter::once(FormatChoice::NoCheck)
+            .chain(Format::all().into_iter().map(FormatChoice::Check))
+            .collect()
+    }
+}
+
+impl std::fmt::Display for FormatChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FormatChoice::NoCheck => write!(f, "No format check"),
+            FormatChoice::Check(format) => write!(f, "{}", format),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum Message {
     DecklistAction(text_editor::Action),
+    RecentDecklistSelected(RecentDecklist),
     ParseDecklist,
-    DecklistParsed(Vec<DecklistEntry>),
+    DecklistParsed(Result<(Vec<DecklistEntry>, ResolutionReport), String>),
+    DownloadEstimated(Result<DownloadEstimate, String>),
+    TokensSuggested(Vec<TokenSuggestion>),
+    AddSuggestedToken(usize), // Index into the report's token suggestions
+    ConfirmDownload,          // Load the images of a large download after all
+    CancelDownload,
     GenerateAll, // New: Parse + Generate + Save in one step
     GeneratePdf,
-    PdfGenerated(Result<Vec<u8>, String>),
+    PrintEstimated(Result<PrintEstimate, String>),
+    ConfirmPrint, // Generate a large PDF after all
+    CancelPrint,
+    PdfGenerated(Result<Vec<Vec<u8>>, String>), // One PDF per part when splitting output
     FileSaved(Option<String>),
+    PrintPdf, // Generate the PDF and send it to the system print spooler instead of saving it
+    PdfPrinted(Result<String, String>),
     ForceUpdateCardNames,
     CardNamesUpdated(Result<String, String>),
     DoubleFaceModeChanged(DoubleFaceMode),
+    BackFaceFallbackChanged(BackFaceFallback),
+    DefaultPrintingToggled(bool),
+    CommentGroupsToggled(bool),
+    PreferredLanguageChanged(String),
+    DownloadRateChanged(String),
     ToggleExtendedPanel,
+    WindowResized(Size),
+    WindowCloseRequested(window::Id), // Saves the layout before closing
+
+    // PDF options panel (persisted in settings)
+    TogglePdfOptions,
+    PageSizeChanged(PageSize),
+    CardsPerRowChanged(u32),
+    FileNameTemplateChanged(String),
+    OverwritePolicyChanged(OverwritePolicy),
+    PagesPerFileChanged(String),
+    SaveCalibrationPage,
+    CalibrationPageSaved(Option<Result<String, String>>), // None if the dialog was cancelled
+    CalibrationMeasurementChanged(String),
+    ApplyCalibration,
+    ResetCalibration,
+    FillerCardsChanged(String),
+    SortOrderChanged(SortOrder),
+    CopyOrderChanged(CopyOrder),
+    ApplyFillerCards,
+    BasicLandSetsChanged(String),
+    ApplyBasicLandSets,
+    FillerEntriesLoaded(Vec<PreviewEntry>),
+    CardsPerColumnChanged(u32),
+    MarginChanged(f32),
+    CutMarkersToggled(bool),
+    CardCaptionsToggled(bool),
+    GroupDividersToggled(bool),
+    CommandersOnOwnPageToggled(bool),
+    SectionSplitChanged(SectionSplit),
+    DuplicateCommandersToggled(bool),
+    PdfAToggled(bool),
+    TextOnlyToggled(bool),
+    ShowPricesToggled(bool),
+    UiLanguageChanged(UiLanguage),
+
+    // Diagnostics
+    LogToFileToggled(bool),
+    ExportLogs,
+    CopyDecklist,
+    LogsExported(Option<Result<(usize, String), String>>), // None if the dialog was cancelled
+    RunDiagnostics,
+    DiagnosticsFinished(String),
+    CopyDiagnostics,
+    CollectUsageStatsToggled(bool),
+    ResetUsageStats,
+    ToggleCachedCards,
+    ImageCacheEvictionChanged(EvictionPolicy),
+    PinDeck,
+    UnpinDeck(String),
+
+    // Scryfall search panel (raw Scryfall query syntax)
+    ToggleSearchPanel,
+    SearchQueryChanged(String),
+    RunSearchQuery,
+    SearchQueryResults(Result<CardSearchResult, String>),
+    AddSearchResult(usize), // Index into the search results
+
+    // Collection comparison
+    ImportCollection,
+    CollectionLoaded(Option<Result<(String, Collection), String>>),
+    MissingOnlyToggled(bool),
+    LegalityFormatChanged(FormatChoice),
+    ImageQualityChanged(ImageQuality),
 
     // Grid preview lifecycle
     BuildGridPreview,
@@ -113,236 +388,457 @@ pub enum Message {
     NextPage,
     PrevPage,
 
+    // Dragging preview cards to reorder the entries (a press and release in place is a click)
+    CardPressed(usize), // Entry index
+    CardDraggedOver(usize),
+    CardReleased,
+
     // Print selection
-    ShowPrintSelection(usize), // Entry index
+    ShowPrintSelection(usize),   // Entry index
+    EntryFaceModeToggled(usize), // Entry index
     SelectPrint {
         entry_index: usize,
         print_index: usize,
     },
+    // Split an entry's copies across printings (adds delta copies of one printing)
+    ChangePrintingQuantity {
+        entry_index: usize,
+        print_index: usize,
+        delta: i32,
+    },
     ClosePrintSelection,
+    SetIconsLoaded,
+    ToggleFavoritePrinting(usize), // Print index of the entry in the print selection
+
+    // Comparing two printings side by side in the print selection
+    ToggleCompareMode,
+    ComparePrint(usize), // Print index
+    ClearComparedPrintings,
+    ComparedImagesLoaded,
 
     // Print selection pagination
     PrintSelectionPrevPage,
     PrintSelectionNextPage,
 
+    // Print selection filters
+    PrintFilterSetChanged(FilterChoice),
+    PrintFilterLanguageChanged(FilterChoice),
+    PrintFilterFrameChanged(FilterChoice),
+    PrintFilterPaperOnlyToggled(bool),
+    ClearPrintFilters,
+
     // Background image loading (now using core library)
-    PollBackgroundProgress,
+    PollBackgroundProgress(u64), // Generation of the load it polls, see BackgroundLoader
+    // An image landed in or left the image cache, so thumbnails showing it are redrawn
+    ImageCacheChanged(CacheEvent),
+
+    // Image download progress of the PDF being generated
+    PollPdfProgress,
+
+    // Automatic refresh of stale card names / set codes started at startup
+    CacheRefreshStatusChanged(CacheRefreshStatus),
+
+    // Keyboard navigation
+    Shortcut(Shortcut),
+
+    // Notifications (error and warning toasts)
+    DismissNotification(u64),
+    DismissAllNotifications,
+    ToggleNotificationDetails(u64),
+    RetryNotification(u64), // Dismisses it and runs its failed operation again
+}
+
+/// Images fetched so far for the PDF being generated, updated by the generation task
+#[derive(Debug, Default)]
+struct PdfProgress {
+    current: AtomicUsize,
+    total: AtomicUsize,
+}
+
+/// A preview card being dragged to another place in the print order
+#[derive(Debug, Clone, Copy)]
+struct CardDrag {
+    entry_index: usize,
+    moved: bool, // Released without having moved, the press was a click on the card
 }
 
 pub struct AppState {
     display_text: String,
     decklist_content: text_editor::Content,
+    recent_decklists: Vec<RecentDecklist>, // Newest first, offered in the "Recent" menu
     parsed_cards: Vec<DecklistEntry>,
     parsed_cards_aligned_text: text_editor::Content, // Line-by-line aligned output
+    resolution_report: ResolutionReport, // Skipped and unresolved lines of the last parse
+    pending_download: Option<DownloadEstimate>, // Large download waiting for confirmation
+    pending_print: Option<PrintEstimate>, // Large PDF waiting for confirmation
+    decklist_format: DecklistFormat,     // Detected when the decklist was parsed
+    deck_name: Option<String>,           // From a leading "Deck: NAME" line; names the saved PDF
     is_parsing: bool,
-    error_message: Option<String>,
+    notifications: Notifications,
     is_generating_pdf: bool,
-    generated_pdf: Option<Vec<u8>>,
+    pdf_progress: Arc<PdfProgress>,
+    generated_pdfs: Vec<Vec<u8>>, // More than one when output is split by pages
     is_updating_card_names: bool,
-    double_face_mode: DoubleFaceMode,
+    pdf_options: PdfOptions, // Includes the face mode; loaded from and saved to settings
+    output_options: OutputOptions, // Loaded from and saved to settings
+    calibration_measurement: String, // Ruler length typed in after printing a calibration page
+    filler_cards_text: String, // Comma-separated filler card names, applied on submit
+    basic_land_sets_text: String, // Comma-separated basic land set codes, applied on submit
+    download_rate_text: String, // Download cap in KB/s, empty for unlimited
+    show_pdf_options: bool,
+    show_prices: bool,         // Loaded from and saved to settings
+    log_to_file: bool,         // Loaded from and saved to settings, used at startup
+    collect_usage_stats: bool, // Loaded from and saved to settings
+    running_diagnostics: bool,
+    diagnostics_report: Option<String>, // Report of the last diagnostics run
+    cached_cards: Option<ImageCacheContents>, // Shown in the image cache section while Some
+    pinned_decks: PinnedDecks,          // Reloaded after pinning or unpinning a deck
+    legality_format: Option<Format>,    // Loaded from and saved to settings
+
+    // Imported collection (with the file name it came from) and whether to proxy only missing cards
+    collection: Option<(String, Collection)>,
+    missing_only: bool,
     show_extended_panel: bool,
+    window_size: Option<Size>, // Saved with the layout when the window closes
+    last_directory: Option<PathBuf>, // Where save dialogs start
 
     // New preview-related fields
     grid_preview: Option<GridPreview>,
+    card_drag: Option<CardDrag>,
+    /// Keeps the preview's images in the image cache while it is shown
+    preview_pin: Option<ImagePin>,
     page_navigation: Option<PageNavigation>,
     preview_mode: PreviewMode,
     is_building_preview: bool,
 
     // Background image loading (now using core library)
-    background_load_handle: Option<BackgroundLoadHandle>,
+    background_loader: BackgroundLoader,
     latest_background_progress: Option<BackgroundLoadProgress>,
+    reported_load_errors: usize, // Loading errors already turned into a notification
 
     // Auto-continue to PDF generation after parsing
     auto_generate_after_parse: bool,
+
+    // Print the next generated PDF instead of opening the save dialog
+    print_after_generation: bool,
+
+    // Print selection filters and the printings they leave visible (indices into available_printings)
+    print_selection_filter: SearchFilter,
+    filtered_printings: Vec<usize>,
+
+    // Printing highlighted for keyboard navigation (position within filtered_printings)
+    print_selection_focus: Option<usize>,
+
+    // Clicking a printing picks it for comparison instead of selecting it
+    comparing_printings: bool,
+    // Printings picked for comparison (indices into available_printings, at most two)
+    compared_printings: Vec<usize>,
+
+    // Scryfall search panel
+    show_search_panel: bool,
+    search_query: String,
+    is_searching: bool,
+    search_results: Option<CardSearchResult>,
 }
 
 impl AppState {
     pub fn new() -> Self {
+        let settings = get_settings();
+        set_ui_language(settings.ui_language);
+        set_back_face_fallback(settings.back_face_fallback);
+        set_default_printing(settings.default_printing);
+        set_comment_annotations(settings.comment_annotations);
+        set_preferred_language(settings.preferred_language.clone());
+        set_basic_land_sets(settings.basic_land_sets.clone());
+        set_favorite_printings(settings.favorite_printings.clone());
+        set_download_rate_limit(settings.max_download_rate_kb);
+        set_image_cache_eviction_policy(settings.image_cache_eviction);
         Self {
             display_text: "Welcome to LocalHawk!\nParsing includes fuzzy matching, set/language awareness, and card name resolution.".to_string(),
             decklist_content: text_editor::Content::with_text(
                 "\n1 Gisela, the Broken Blade\n1 Bruna, the Fading Light\n1 Counterspell [7ED]\n// comments are ignored\n1 Memory Lapse [ja]\n1 kabira takedown\n1 kabira plateau\n1 cut // ribbons (pakh)",
                 // "1 Bruna, the Fading Light\n[V17] 1 Bruna, the Fading Light [V17] \n1 Bruna, the Fading Light [V17] \n1 Bruna, the Fading Light [V17] \n1 Bruna, the Fading Light [V17] \n1 Bruna, the Fading Light [V17]
             ),
+            recent_decklists: get_decklist_history().decklists,
             parsed_cards: Vec::new(),
             parsed_cards_aligned_text: text_editor::Content::new(),
+            resolution_report: ResolutionReport::default(),
+            pending_download: None,
+            pending_print: None,
+            decklist_format: DecklistFormat::default(),
+            deck_name: None,
             is_parsing: false,
-            error_message: None,
+            notifications: Notifications::default(),
             is_generating_pdf: false,
-            generated_pdf: None,
+            pdf_progress: Arc::default(),
+            generated_pdfs: Vec::new(),
             is_updating_card_names: false,
-            double_face_mode: DoubleFaceMode::BothSides,
-            show_extended_panel: false,
+            pdf_options: get_settings().pdf_options,
+            output_options: get_settings().output,
+            calibration_measurement: String::new(),
+            filler_cards_text: get_settings().pdf_options.filler_cards.join(", "),
+            basic_land_sets_text: get_basic_land_sets().join(", ").to_uppercase(),
+            download_rate_text: settings
+                .max_download_rate_kb
+                .map(|limit| limit.to_string())
+                .unwrap_or_default(),
+            show_pdf_options: settings.gui_layout.show_pdf_options,
+            show_prices: get_settings().show_prices,
+            log_to_file: get_settings().log_to_file,
+            collect_usage_stats: get_settings().collect_usage_stats,
+            running_diagnostics: false,
+            diagnostics_report: None,
+            cached_cards: None,
+            pinned_decks: get_pinned_decks(),
+            legality_format: get_settings().legality_format,
+            collection: None,
+            missing_only: false,
+            show_extended_panel: settings.gui_layout.show_extended_panel,
+            window_size: settings
+                .gui_layout
+                .window_size
+                .map(|(width, height)| Size::new(width, height)),
+            last_directory: settings.gui_layout.last_directory.clone(),
 
             // Initialize new preview fields
             grid_preview: None,
+            card_drag: None,
+            preview_pin: None,
             page_navigation: None,
             preview_mode: PreviewMode::Hidden,
             is_building_preview: false,
 
             // Initialize background loading fields
-            background_load_handle: None,
+            background_loader: BackgroundLoader::default(),
             latest_background_progress: None,
+            reported_load_errors: 0,
 
             // Initialize auto-continue flag
             auto_generate_after_parse: false,
+            print_after_generation: false,
+
+            print_selection_filter: SearchFilter::default(),
+            filtered_printings: Vec::new(),
+            print_selection_focus: None,
+            comparing_printings: false,
+            compared_printings: Vec::new(),
+
+            show_search_panel: settings.gui_layout.show_search_panel,
+            search_query: String::new(),
+            is_searching: false,
+            search_results: None,
         }
     }
 }
 
-/// Build grid preview using the exact same logic as PDF generation
+/// Preview entries for the filler cards, with all their printings to choose from
+async fn load_filler_entries(names: Vec<String>) -> Vec<PreviewEntry> {
+    let mut fillers = Vec::new();
+    for name in names {
+        let available_printings = match ProxyGenerator::search_card(&name).await {
+            Ok(search_result) => search_result.cards,
+            Err(_) => Vec::new(),
+        };
+        fillers.push(PreviewEntry::filler(&name, available_printings));
+    }
+    fillers
+}
+
 /// Build grid preview using the exact same logic as PDF generation
 /// This ensures 100% consistency between what you see and what you get
 async fn build_grid_preview_from_entries_unified(
     entries: Vec<DecklistEntry>,
+    pdf_options: PdfOptions,
 ) -> Result<GridPreview, String> {
-    // Use the same card resolution logic as PDF generation
-    let cards = ProxyGenerator::resolve_decklist_entries_to_cards(&entries)
-        .await
-        .map_err(|e| format!("Failed to resolve cards: {}", e))?;
-
-    // Use the same expansion logic as PDF generation
-    let image_urls = ProxyGenerator::expand_cards_to_image_urls(&cards);
-
-    // Convert to grid format with page/position information
-    let mut grid_images = Vec::new();
-    for (position, _image_url) in image_urls.into_iter().enumerate() {
-        let page = position / 9; // 9 cards per page
-        let position_in_page = position % 9;
-
-        // Find which entry and copy this image belongs to
-        let mut current_position = 0;
-        let mut entry_index = 0;
-        let mut copy_number = 0;
-        let mut _image_index = 0;
-
-        'outer: for (idx, (card, quantity, face_mode)) in cards.iter().enumerate() {
-            for copy in 0..*quantity {
-                let urls = card.get_images_for_face_mode(face_mode);
-                for (img_idx, _) in urls.iter().enumerate() {
-                    if current_position == position {
-                        entry_index = idx;
-                        copy_number = copy as usize;
-                        _image_index = img_idx;
-                        break 'outer;
-                    }
-                    current_position += 1;
-                }
-            }
-        }
-
-        grid_images.push(GridImage {
-            entry_index,
-            copy_number,
-            page,
-            position_in_page,
-        });
-    }
-
-    // Build preview entries for print selection, using the same resolved cards
+    // Build preview entries for print selection, selecting printings like PDF generation does
     let mut preview_entries = Vec::new();
-    for (entry_idx, entry) in entries.iter().enumerate() {
-        // Find all grid positions for this entry
-        let positions: Vec<GridPosition> = grid_images
-            .iter()
-            .filter(|img| img.entry_index == entry_idx)
-            .map(|img| GridPosition {
-                page: img.page,
-                position_in_page: img.position_in_page,
-                entry_index: img.entry_index,
-                copy_number: img.copy_number,
-            })
-            .collect();
-
+    for entry in &entries {
         // Get all available printings (search results)
-        let (available_printings, selected_printing_index) =
+        let (available_printings, has_more_printings) =
             match ProxyGenerator::search_card(&entry.name).await {
-                Ok(search_result) => {
-                    // Find which card was selected by resolve_decklist_entries_to_cards()
-                    let selected_card = &cards.get(entry_idx).map(|(card, _, _)| card);
-                    let selected_index = if let Some(selected_card) = selected_card {
-                        // Find the index of the selected card in the search results
-                        search_result.cards.iter().position(|c| {
-                            c.name == selected_card.name
-                                && c.set == selected_card.set
-                                && c.language == selected_card.language
-                                && c.border_crop == selected_card.border_crop
-                        })
-                    } else {
-                        None
-                    };
-                    (search_result.cards, selected_index)
-                }
-                Err(_) => (Vec::new(), None), // Fallback to empty if search fails
+                Ok(search_result) => (search_result.cards, search_result.has_more),
+                Err(_) => (Vec::new(), false), // Fallback to empty if search fails
             };
 
-        preview_entries.push(PreviewEntry {
-            decklist_entry: entry.clone(),
-            available_printings,
-            selected_printing: selected_printing_index, // Use the same selection as PDF generation
-            grid_positions: positions,
-        });
+        let mut preview_entry = PreviewEntry::new(entry.clone(), available_printings);
+        preview_entry.has_more_printings = has_more_printings;
+        // Basic lands may be spread across the arts of the chosen sets
+        let quantities = ProxyGenerator::printing_quantities_for_entry(
+            &preview_entry.available_printings,
+            entry,
+        );
+        preview_entry.selected_printing = quantities.first().map(|(index, _)| *index);
+        if quantities.len() > 1 {
+            preview_entry.printing_split = quantities;
+        }
+        // A back face name the lookup didn't resolve still prints the back face
+        if let Some(card) = preview_entry
+            .selected_printing
+            .and_then(|index| preview_entry.available_printings.get(index))
+        {
+            preview_entry.decklist_entry.face_mode = entry.face_mode_for(card);
+        }
+        preview_entries.push(preview_entry);
     }
+    preview_entries.extend(load_filler_entries(pdf_options.filler_cards.clone()).await);
 
-    let total_pages = if grid_images.is_empty() {
-        0
-    } else {
-        grid_images.iter().map(|img| img.page).max().unwrap_or(0) + 1
-    };
+    // Use the same expansion order as PDF generation to place every image on the pages
+    let mut grid_preview = GridPreview::new(preview_entries, 0);
+    grid_preview.layout(&pdf_options);
 
-    Ok(GridPreview {
-        entries: preview_entries,
-        current_page: 0,
-        total_pages,
-        selected_entry_index: None,
-        print_selection_grid: None,
-    })
+    Ok(grid_preview)
 }
 
 pub fn initialize() -> (AppState, Task<Message>) {
     (AppState::new(), Task::none())
 }
 
+/// Start loading the images of the parsed cards in the background and build the preview,
+/// continuing to PDF generation if "Generate" started the parse
+fn start_loading_images(state: &mut AppState) -> Task<Message> {
+    // Replaces the load of the previous decklist once that has stopped
+    let generation = state.background_loader.start(state.parsed_cards.clone());
+    state.reported_load_errors = 0;
+
+    let mut tasks = vec![
+        Task::perform(async { () }, move |_| {
+            Message::PollBackgroundProgress(generation)
+        }),
+        Task::perform(async { () }, |_| Message::BuildGridPreview),
+    ];
+
+    // If GenerateAll was triggered, auto-continue to PDF generation
+    if state.auto_generate_after_parse {
+        state.auto_generate_after_parse = false; // Reset flag
+        tasks.push(Task::perform(async { () }, |_| Message::GeneratePdf));
+    }
+
+    Task::batch(tasks)
+}
+
+/// Generate the PDF from the preview if there is one, else from the parsed cards
+fn start_pdf_generation(state: &mut AppState) -> Task<Message> {
+    state.is_generating_pdf = true;
+    state.generated_pdfs.clear();
+    let progress = Arc::new(PdfProgress::default());
+    state.pdf_progress = progress.clone();
+    let report_progress = move |current: usize, total: usize| {
+        progress.current.store(current, Ordering::Relaxed);
+        progress.total.store(total, Ordering::Relaxed);
+    };
+
+    let cards = state.parsed_cards.clone();
+    // Once the preview exists it holds the printing choices (including per-copy splits)
+    let preview_cards = state
+        .grid_preview
+        .as_ref()
+        .map(|grid_preview| grid_preview.to_card_groups(&state.pdf_options));
+    let group_labels = state
+        .grid_preview
+        .as_ref()
+        .map(|grid_preview| grid_preview.group_labels(&state.pdf_options))
+        .unwrap_or_default();
+    let pdf_options = PdfOptions {
+        title: state.deck_name.clone(),
+        group_labels,
+        ..state.pdf_options.clone()
+    };
+    let pages_per_file = state.output_options.split_pages();
+    let generation = Task::perform(
+        with_timeout(PDF_GENERATION_TIMEOUT, async move {
+            // Generate PDF using the new unified logic (same as grid preview)
+            let result = match preview_cards {
+                Some(preview_cards) => {
+                    ProxyGenerator::generate_pdfs_from_card_groups(
+                        &preview_cards,
+                        pdf_options,
+                        pages_per_file,
+                        report_progress,
+                    )
+                    .await
+                }
+                None => {
+                    ProxyGenerator::generate_pdfs_from_entries(
+                        &cards,
+                        pdf_options,
+                        pages_per_file,
+                        report_progress,
+                    )
+                    .await
+                }
+            };
+            result.map_err(|e| format!("PDF generation failed: {}", e))
+        }),
+        Message::PdfGenerated,
+    );
+    Task::batch([
+        generation,
+        Task::perform(async {}, |_| Message::PollPdfProgress),
+    ])
+}
+
 pub fn update(state: &mut AppState, message: Message) -> Task<Message> {
     match message {
         Message::DecklistAction(action) => {
             state.decklist_content.perform(action);
         }
+        Message::RecentDecklistSelected(recent) => {
+            state.decklist_content = text_editor::Content::with_text(&recent.decklist);
+        }
         Message::ParseDecklist => {
             let decklist_text = state.decklist_content.text();
             if decklist_text.trim().is_empty() {
-                state.error_message = Some("Please enter a decklist first!".to_string());
+                state
+                    .notifications
+                    .warning(tr(Text::EnterDecklistFirst), None);
                 return Task::none();
             }
 
             state.is_parsing = true;
-            state.error_message = None;
+            state.decklist_format = DecklistFormat::detect(&decklist_text);
+            state.deck_name = parse_deck_name(&decklist_text);
+            if let Err(e) = record_recent_decklist(&decklist_text) {
+                tracing::warn!("Failed to save decklist history: {}", e);
+            }
+            state.recent_decklists = get_decklist_history().decklists;
+            // Stop loading the previous decklist's images; the next load waits for it to stop
+            state.background_loader.cancel();
+            state.latest_background_progress = None;
 
             // Parse and resolve decklist with global caches and current face mode setting
-            let current_face_mode = state.double_face_mode.clone();
+            let current_face_mode = state.pdf_options.double_face_mode.clone();
             return Task::perform(
-                async move {
-                    match ProxyGenerator::parse_and_resolve_decklist(
+                with_timeout(NETWORK_TASK_TIMEOUT, async move {
+                    ProxyGenerator::parse_and_resolve_decklist_with_report(
                         &decklist_text,
                         current_face_mode,
                     )
                     .await
-                    {
-                        Ok(cards) => cards,
-                        Err(e) => {
-                            log::error!("Failed to parse decklist: {}", e);
-                            Vec::new() // Return empty list on error
-                        }
-                    }
-                },
+                    .map_err(|e| {
+                        tracing::error!("Failed to parse decklist: {}", e);
+                        e.to_string()
+                    })
+                }),
                 Message::DecklistParsed,
             );
         }
-        Message::DecklistParsed(cards) => {
+        Message::DecklistParsed(result) => {
             state.is_parsing = false;
-            log::debug!("GUI received parsed cards: {}", cards.len());
+            let (cards, report) = match result {
+                Ok(parsed) => parsed,
+                Err(error) => {
+                    state.auto_generate_after_parse = false;
+                    state.notifications.error_with_retry(
+                        tr(Text::DecklistParseFailed),
+                        Some(error),
+                        Message::ParseDecklist,
+                    );
+                    return Task::none();
+                }
+            };
+            tracing::debug!("GUI received parsed cards: {}", cards.len());
             for card in &cards {
-                log::debug!(
+                tracing::debug!(
                     "  GUI card: {}x '{}' [set: {:?}, lang: {:?}, face_mode: {:?}]",
                     card.multiple,
                     card.name,
@@ -351,109 +847,175 @@ pub fn update(state: &mut AppState, message: Message) -> Task<Message> {
                     card.face_mode
                 );
             }
+            // Leave out owned cards when only the missing ones should be proxied
+            let cards = match (&state.collection, state.missing_only) {
+                (Some(collection), true) => collection.missing_entries(&cards),
+                _ => cards,
+            };
             state.parsed_cards = cards.clone();
-            state.error_message = None;
+            state.resolution_report = report;
+            state.pending_download = None;
+            state.pending_print = None;
+            // The previous preview (and any printing splits in it) belongs to the old decklist
+            state.grid_preview = None;
+            state.preview_pin = None;
+            state.page_navigation = None;
+            state.preview_mode = PreviewMode::Hidden;
             state.display_text = format!(
                 "Parsed {} cards successfully! Loading images and building preview...",
                 state.parsed_cards.len()
             );
 
             // Build aligned text output for the right panel
-            let aligned_text =
-                build_aligned_parsed_output(&state.decklist_content.text(), &state.parsed_cards);
-            state.parsed_cards_aligned_text = text_editor::Content::with_text(&aligned_text);
+            refresh_aligned_output(state);
 
-            // Start background image loading immediately after parsing (now using core library)
+            // Images are loaded once it is clear how much has to be downloaded
             if !cards.is_empty() {
-                // Start background loading in core library
-                let handle = start_background_image_loading(cards.clone());
-                state.background_load_handle = Some(handle);
-
-                let mut tasks = vec![
-                    Task::perform(async { () }, |_| Message::PollBackgroundProgress),
-                    Task::perform(async { () }, |_| Message::BuildGridPreview),
-                ];
-
-                // If GenerateAll was triggered, auto-continue to PDF generation
-                if state.auto_generate_after_parse {
-                    state.auto_generate_after_parse = false; // Reset flag
-                    tasks.push(Task::perform(async { () }, |_| Message::GeneratePdf));
+                let token_entries = cards.clone();
+                return Task::batch([
+                    Task::perform(
+                        with_timeout(NETWORK_TASK_TIMEOUT, async move {
+                            ProxyGenerator::estimate_downloads(&cards)
+                                .await
+                                .map_err(|e| e.to_string())
+                        }),
+                        Message::DownloadEstimated,
+                    ),
+                    Task::perform(
+                        with_timeout(NETWORK_TASK_TIMEOUT, async move {
+                            match ProxyGenerator::resolve_decklist_entries_to_cards(&token_entries)
+                                .await
+                            {
+                                Ok(cards) => {
+                                    let cards: Vec<Card> =
+                                        cards.into_iter().map(|(card, _, _)| card).collect();
+                                    Ok(ProxyGenerator::suggest_tokens(&cards).await)
+                                }
+                                Err(e) => Err(e.to_string()),
+                            }
+                        }),
+                        |tokens| Message::TokensSuggested(tokens.unwrap_or_default()),
+                    ),
+                ]);
+            }
+        }
+        Message::DownloadEstimated(result) => match result {
+            Ok(estimate) => {
+                state.resolution_report.download = Some(estimate);
+                if estimate.images > 0 {
+                    state.display_text = format!("{}\n{}", state.display_text, estimate.describe());
                 }
-
-                return Task::batch(tasks);
+                // Large downloads wait for the user, e.g. on a metered connection
+                if estimate.is_large() {
+                    state.pending_download = Some(estimate);
+                } else {
+                    return start_loading_images(state);
+                }
+            }
+            Err(error) => {
+                tracing::warn!("Failed to estimate the download size: {}", error);
+                return start_loading_images(state);
+            }
+        },
+        Message::TokensSuggested(tokens) => {
+            state.resolution_report.tokens = tokens;
+        }
+        Message::AddSuggestedToken(index) => {
+            if index < state.resolution_report.tokens.len() {
+                let suggestion = state.resolution_report.tokens.remove(index);
+                let mut decklist = state.decklist_content.text();
+                if !decklist.is_empty() && !decklist.ends_with('\n') {
+                    decklist.push('\n');
+                }
+                // Tokens go in a section of their own, so they can be printed separately
+                let last_section = decklist
+                    .lines()
+                    .rev()
+                    .find_map(|line| DeckSection::from_header(line.trim()));
+                if last_section != Some(DeckSection::Tokens) {
+                    decklist.push('\n');
+                    decklist.push_str(DeckSection::Tokens.header());
+                    decklist.push('\n');
+                }
+                decklist.push_str(&suggestion.line);
+                state.decklist_content = text_editor::Content::with_text(&decklist);
             }
         }
-        Message::PollBackgroundProgress => {
-            if let Some(handle) = state.background_load_handle.as_mut() {
+        Message::ConfirmDownload => {
+            state.pending_download = None;
+            return start_loading_images(state);
+        }
+        Message::CancelDownload => {
+            state.pending_download = None;
+            state.auto_generate_after_parse = false;
+            state.display_text = tr(Text::DownloadCancelled).to_string();
+        }
+        Message::PollBackgroundProgress(generation) => {
+            // Polling for a load that another one replaced stops here
+            if generation != state.background_loader.generation() {
+                return Task::none();
+            }
+            if let Some(handle) = state.background_loader.handle_mut() {
                 if let Some(progress) = handle.try_get_progress() {
-                    log::debug!("Background progress update: {:?}", progress);
+                    tracing::debug!("Background progress update: {:?}", progress);
                     state.latest_background_progress = Some(progress.clone());
 
-                    // Update display text with progress
-                    let progress_text = match progress.phase {
-                        LoadingPhase::Selected => {
-                            format!(
-                                "Loading selected images: {}/{} entries, {} images cached...",
-                                progress.current_entry,
-                                progress.total_entries,
-                                progress.selected_loaded
-                            )
-                        }
-                        LoadingPhase::Alternatives => {
-                            format!(
-                                "Loading alternative images: {}/{} alternatives cached...",
-                                progress.alternatives_loaded, progress.total_alternatives
-                            )
-                        }
-                        LoadingPhase::Completed => {
-                            format!(
-                                "All images loaded! {} selected + {} alternatives = {} total images.",
-                                progress.selected_loaded,
-                                progress.alternatives_loaded,
-                                progress.selected_loaded + progress.alternatives_loaded
-                            )
-                        }
-                    };
-                    state.display_text = progress_text;
-
-                    // Show any errors
-                    if !progress.errors.is_empty() {
-                        let error_msg = format!(
-                            "Loading completed with {} error(s): {}",
-                            progress.errors.len(),
-                            progress.errors.join("; ")
+                    // Progress bars show the loading phases; only the outcome goes to the status text
+                    if progress.phase == LoadingPhase::Completed {
+                        state.display_text = format!(
+                            "All images loaded! {} selected + {} alternatives = {} total images.",
+                            progress.selected_loaded,
+                            progress.alternatives_loaded,
+                            progress.selected_loaded + progress.alternatives_loaded
+                        );
+                    }
+
+                    // Errors accumulate over the whole load; notify about the new ones only
+                    let new_errors = progress.errors.get(state.reported_load_errors..);
+                    if let Some(new_errors) = new_errors.filter(|errors| !errors.is_empty()) {
+                        state.notifications.error(
+                            tr_with(Text::ImagesFailedToLoad, &[&new_errors.len()]),
+                            Some(new_errors.join("\n")),
                         );
-                        state.error_message = Some(error_msg);
+                        state.reported_load_errors = progress.errors.len();
                     }
                 }
 
                 // Check if loading is finished
                 if handle.is_finished() {
-                    log::debug!("Background loading task finished");
-                    state.background_load_handle = None;
+                    tracing::debug!("Background loading task finished");
                 } else {
-                    // Continue polling
+                    // Continue polling for the progress bars (thumbnails follow cache events)
                     return Task::perform(
                         async {
-                            tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+                            tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
                         },
-                        |_| Message::PollBackgroundProgress,
+                        move |_| Message::PollBackgroundProgress(generation),
                     );
                 }
             }
         }
+        Message::ImageCacheChanged(event) => {
+            // The view reads thumbnails from the image cache, so the redraw after this shows them
+            tracing::trace!("Image cache changed: {:?}", event);
+        }
         Message::BuildGridPreview => {
             if state.parsed_cards.is_empty() {
-                state.error_message = Some("No cards parsed to build preview".to_string());
+                state
+                    .notifications
+                    .warning(tr(Text::NoCardsToPreview), None);
                 return Task::none();
             }
 
             state.is_building_preview = true;
-            state.error_message = None;
 
             let cards = state.parsed_cards.clone();
+            let pdf_options = state.pdf_options.clone();
             return Task::perform(
-                build_grid_preview_from_entries_unified(cards),
+                with_timeout(
+                    NETWORK_TASK_TIMEOUT,
+                    build_grid_preview_from_entries_unified(cards, pdf_options),
+                ),
                 Message::GridPreviewBuilt,
             );
         }
@@ -462,15 +1024,36 @@ pub fn update(state: &mut AppState, message: Message) -> Task<Message> {
 
             match result {
                 Ok(grid_preview) => {
+                    // Names that matched no card on Scryfall can't be proxied
+                    for entry in &grid_preview.entries {
+                        if entry.available_printings.is_empty() {
+                            state.notifications.warning(
+                                tr_with(Text::NoPrintingsFound, &[&entry.decklist_entry.name]),
+                                None,
+                            );
+                        }
+                    }
                     let total_pages = grid_preview.total_pages;
+                    let cards = grid_preview.to_card_groups(&state.pdf_options).concat();
+                    state.preview_pin = Some(pin_images(
+                        ProxyGenerator::expand_cards_to_image_urls(&cards),
+                    ));
                     state.page_navigation = Some(PageNavigation::new(total_pages));
                     state.grid_preview = Some(grid_preview);
                     state.preview_mode = PreviewMode::GridPreview;
-                    state.display_text = format!("Grid preview built with {} pages", total_pages);
+                    state.display_text = tr_with(Text::GridPreviewBuilt, &[&total_pages]);
+                    // Prices are only known once the printings are resolved
+                    if state.show_prices {
+                        refresh_aligned_output(state);
+                    }
                 }
                 Err(error) => {
-                    state.error_message = Some(error);
-                    state.display_text = "Failed to build grid preview".to_string();
+                    state.notifications.error_with_retry(
+                        tr(Text::GridPreviewFailed),
+                        Some(error),
+                        Message::BuildGridPreview,
+                    );
+                    state.display_text = tr(Text::GridPreviewFailed).to_string();
                 }
             }
         }
@@ -492,20 +1075,74 @@ pub fn update(state: &mut AppState, message: Message) -> Task<Message> {
                 }
             }
         }
+        Message::CardPressed(entry_index) => {
+            state.card_drag = Some(CardDrag {
+                entry_index,
+                moved: false,
+            });
+        }
+        Message::CardDraggedOver(target) => {
+            let Some(drag) = state.card_drag.as_mut() else {
+                return Task::none();
+            };
+            let moved = state.grid_preview.as_mut().is_some_and(|grid_preview| {
+                grid_preview.move_entry(drag.entry_index, target, &state.pdf_options)
+            });
+            if moved {
+                drag.moved = true;
+                // The moved order already includes the sorting, print it as arranged from now on
+                if state.pdf_options.sort_order == SortOrder::AsEntered {
+                    relayout_grid_preview(state);
+                } else {
+                    update_pdf_options(state, |options| options.sort_order = SortOrder::AsEntered);
+                }
+            }
+        }
+        Message::CardReleased => {
+            let click = state.card_drag.take().filter(|drag| !drag.moved);
+            if let Some(drag) = click {
+                return update(state, Message::ShowPrintSelection(drag.entry_index));
+            }
+        }
+        Message::EntryFaceModeToggled(entry_index) => {
+            let Some(entry) = state
+                .grid_preview
+                .as_mut()
+                .and_then(|grid_preview| grid_preview.entries.get_mut(entry_index))
+            else {
+                return Task::none();
+            };
+            let previous = entry.decklist_entry.face_mode.clone();
+            let face_mode = previous.next();
+            entry.set_face_mode(face_mode.clone());
+
+            // The parsed entry is matched by its face mode, so it has to follow first
+            if let Some(card) = entry.get_selected_card().cloned() {
+                let name = entry.decklist_entry.name.clone();
+                if let Some(parsed_entry) = state.parsed_cards.iter_mut().find(|parsed| {
+                    parsed.name.eq_ignore_ascii_case(&name)
+                        && parsed.face_mode_for(&card) == previous
+                }) {
+                    parsed_entry.face_mode = face_mode;
+                }
+            }
+            apply_printing_change(state, entry_index);
+        }
         Message::ShowPrintSelection(entry_index) => {
             if let Some(ref mut grid_preview) = state.grid_preview {
                 if entry_index < grid_preview.entries.len() {
                     grid_preview.selected_entry_index = Some(entry_index);
-
-                    // Initialize pagination grid for print selection
-                    let entry = &grid_preview.entries[entry_index];
-                    let total_printings = entry.available_printings.len();
-                    grid_preview.print_selection_grid =
-                        Some(PaginatedGrid::new(total_printings, PRINTS_PER_PAGE));
-
                     state.preview_mode = PreviewMode::PrintSelection;
                 }
             }
+
+            // Each entry starts unfiltered; this also initializes pagination for print selection
+            state.print_selection_filter = SearchFilter::default();
+            state.comparing_printings = false;
+            state.compared_printings.clear();
+            apply_print_selection_filter(state);
+
+            return load_set_icons(state, entry_index);
         }
         Message::SelectPrint {
             entry_index,
@@ -514,61 +1151,107 @@ pub fn update(state: &mut AppState, message: Message) -> Task<Message> {
             if let Some(ref mut grid_preview) = state.grid_preview {
                 if let Some(entry) = grid_preview.entries.get_mut(entry_index) {
                     entry.set_selected_printing(print_index);
-                    log::debug!(
+                    tracing::debug!(
                         "Selected printing {} for entry {}",
                         print_index,
                         entry_index
                     );
-
-                    // Update the corresponding DecklistEntry in parsed_cards with selected printing info
-                    if let Some(selected_card) = entry.get_selected_card() {
-                        // Find the matching entry in parsed_cards by name
-                        log::debug!(
-                            "Looking for match: grid entry name='{}', checking against {} parsed entries",
-                            entry.decklist_entry.name,
-                            state.parsed_cards.len()
-                        );
-
-                        for parsed in &state.parsed_cards {
-                            log::debug!("  Parsed entry: '{}'", parsed.name);
-                        }
-
-                        if let Some(parsed_entry) = state.parsed_cards.iter_mut().find(|parsed| {
-                            parsed.name.to_lowercase() == entry.decklist_entry.name.to_lowercase()
-                                && parsed.face_mode == entry.decklist_entry.face_mode
-                        }) {
-                            // Update the parsed entry with the selected printing's set and language
-                            parsed_entry.set = Some(selected_card.set.clone());
-                            parsed_entry.lang = Some(selected_card.language.clone());
-
-                            log::debug!(
-                                "Updated parsed entry '{}' with selected printing: set='{}', lang='{}'",
-                                parsed_entry.name,
-                                selected_card.set,
-                                selected_card.language
-                            );
-                        } else {
-                            log::warn!(
-                                "Could not find matching parsed entry for grid entry '{}'",
-                                entry.decklist_entry.name
-                            );
-                        }
+                    // Images still loading in the background should be this printing's
+                    if let (Some(handle), Some(card)) = (
+                        state.background_loader.handle(),
+                        entry.available_printings.get(print_index),
+                    ) {
+                        handle.select_printing(entry_index, card.clone());
                     }
                 }
             }
-
-            // Rebuild aligned text output after print selection
-            let aligned_text =
-                build_aligned_parsed_output(&state.decklist_content.text(), &state.parsed_cards);
-            state.parsed_cards_aligned_text = text_editor::Content::with_text(&aligned_text);
+            apply_printing_change(state, entry_index);
 
             state.preview_mode = PreviewMode::GridPreview;
+            state.print_selection_focus = None;
+            state.comparing_printings = false;
+            state.compared_printings.clear();
             if let Some(ref mut grid_preview) = state.grid_preview {
                 grid_preview.selected_entry_index = None;
             }
         }
+        Message::ChangePrintingQuantity {
+            entry_index,
+            print_index,
+            delta,
+        } => {
+            if let Some(ref mut grid_preview) = state.grid_preview {
+                if let Some(entry) = grid_preview.entries.get_mut(entry_index) {
+                    let copies = entry.quantity_for_printing(print_index) as i32 + delta;
+                    if entry.set_printing_quantity(print_index, copies.max(0) as u32) {
+                        tracing::debug!(
+                            "Entry {} now uses {} copies of printing {}",
+                            entry_index,
+                            copies,
+                            print_index
+                        );
+                    }
+                }
+            }
+            apply_printing_change(state, entry_index);
+        }
+        Message::SetIconsLoaded => {
+            // Icons are read from the core's memory cache while rendering; nothing to store
+        }
+        Message::ToggleFavoritePrinting(print_index) => {
+            let card = state
+                .grid_preview
+                .as_ref()
+                .and_then(|grid_preview| {
+                    grid_preview
+                        .selected_entry_index
+                        .and_then(|entry_index| grid_preview.entries.get(entry_index))
+                })
+                .and_then(|entry| entry.available_printings.get(print_index));
+            if let Some(card) = card {
+                // Applies to automatic selection from the next time the decklist is parsed
+                let favorites = toggle_favorite_printing(card);
+                if let Err(e) = update_settings(|settings| settings.favorite_printings = favorites)
+                {
+                    tracing::warn!("Failed to save settings: {}", e);
+                }
+                // Favorites are listed first
+                apply_print_selection_filter(state);
+            }
+        }
+        Message::ToggleCompareMode => {
+            state.comparing_printings = !state.comparing_printings;
+            state.compared_printings.clear();
+        }
+        Message::ComparePrint(print_index) => {
+            if let Some(position) = state
+                .compared_printings
+                .iter()
+                .position(|&compared| compared == print_index)
+            {
+                state.compared_printings.remove(position);
+            } else {
+                // A third pick replaces the oldest one
+                if state.compared_printings.len() == 2 {
+                    state.compared_printings.remove(0);
+                }
+                state.compared_printings.push(print_index);
+                if state.compared_printings.len() == 2 {
+                    return load_compared_images(state);
+                }
+            }
+        }
+        Message::ClearComparedPrintings => {
+            state.compared_printings.clear();
+        }
+        Message::ComparedImagesLoaded => {
+            // The comparison reads the images from the image cache; the redraw shows them
+        }
         Message::ClosePrintSelection => {
             state.preview_mode = PreviewMode::GridPreview;
+            state.print_selection_focus = None;
+            state.comparing_printings = false;
+            state.compared_printings.clear();
             if let Some(ref mut grid_preview) = state.grid_preview {
                 grid_preview.selected_entry_index = None;
                 grid_preview.print_selection_grid = None;
@@ -577,17 +1260,41 @@ pub fn update(state: &mut AppState, message: Message) -> Task<Message> {
         Message::PrintSelectionPrevPage => {
             if let Some(ref mut grid_preview) = state.grid_preview {
                 if let Some(ref mut print_grid) = grid_preview.print_selection_grid {
-                    print_grid.prev_page();
+                    if print_grid.prev_page() {
+                        state.print_selection_focus = Some(print_grid.get_current_page_range().0);
+                    }
                 }
             }
         }
         Message::PrintSelectionNextPage => {
             if let Some(ref mut grid_preview) = state.grid_preview {
                 if let Some(ref mut print_grid) = grid_preview.print_selection_grid {
-                    print_grid.next_page();
+                    if print_grid.next_page() {
+                        state.print_selection_focus = Some(print_grid.get_current_page_range().0);
+                    }
                 }
             }
         }
+        Message::PrintFilterSetChanged(choice) => {
+            state.print_selection_filter.set = choice.into_filter();
+            apply_print_selection_filter(state);
+        }
+        Message::PrintFilterLanguageChanged(choice) => {
+            state.print_selection_filter.language = choice.into_filter();
+            apply_print_selection_filter(state);
+        }
+        Message::PrintFilterFrameChanged(choice) => {
+            state.print_selection_filter.frame = choice.into_filter();
+            apply_print_selection_filter(state);
+        }
+        Message::PrintFilterPaperOnlyToggled(paper_only) => {
+            state.print_selection_filter.paper_only = paper_only;
+            apply_print_selection_filter(state);
+        }
+        Message::ClearPrintFilters => {
+            state.print_selection_filter = SearchFilter::default();
+            apply_print_selection_filter(state);
+        }
         Message::GenerateAll => {
             // Set flag to auto-continue to PDF generation after parsing
             state.auto_generate_after_parse = true;
@@ -597,55 +1304,111 @@ pub fn update(state: &mut AppState, message: Message) -> Task<Message> {
         }
         Message::GeneratePdf => {
             if state.parsed_cards.is_empty() {
-                state.error_message = Some("Please parse a decklist first!".to_string());
+                state
+                    .notifications
+                    .warning(tr(Text::ParseDecklistFirst), None);
                 return Task::none();
             }
 
-            state.is_generating_pdf = true;
-            state.error_message = None;
-            state.generated_pdf = None;
-
+            // Check the size first, so a malformed decklist doesn't turn into a huge print
+            if let Some(grid_preview) = state.grid_preview.as_ref() {
+                let card_groups = grid_preview.to_card_groups(&state.pdf_options);
+                let pdf_options = PdfOptions {
+                    group_labels: grid_preview.group_labels(&state.pdf_options),
+                    ..state.pdf_options.clone()
+                };
+                let estimate = ProxyGenerator::estimate_print(&card_groups, &pdf_options);
+                return update(state, Message::PrintEstimated(Ok(estimate)));
+            }
             let cards = state.parsed_cards.clone();
-            let double_face_mode = state.double_face_mode.clone();
+            let pdf_options = state.pdf_options.clone();
             return Task::perform(
-                async move {
-                    // Generate PDF using the new unified logic (same as grid preview)
-                    let pdf_options = PdfOptions {
-                        double_face_mode: double_face_mode,
-                        ..PdfOptions::default()
-                    };
-                    match ProxyGenerator::generate_pdf_from_entries(
-                        &cards,
-                        pdf_options,
-                        |_current, _total| {
-                            // No progress reporting for now
-                        },
-                    )
-                    .await
-                    {
-                        Ok(pdf_data) => Ok(pdf_data),
-                        Err(e) => Err(format!("PDF generation failed: {}", e)),
-                    }
-                },
-                Message::PdfGenerated,
+                with_timeout(NETWORK_TASK_TIMEOUT, async move {
+                    ProxyGenerator::estimate_print_for_entries(&cards, &pdf_options)
+                        .await
+                        .map_err(|e| e.to_string())
+                }),
+                Message::PrintEstimated,
             );
         }
+        Message::PrintEstimated(result) => match result {
+            Ok(estimate) => {
+                state.display_text = estimate.describe();
+                if estimate.is_large() {
+                    state.pending_print = Some(estimate);
+                } else {
+                    return start_pdf_generation(state);
+                }
+            }
+            Err(error) => {
+                tracing::warn!("Failed to estimate the PDF size: {}", error);
+                return start_pdf_generation(state);
+            }
+        },
+        Message::ConfirmPrint => {
+            state.pending_print = None;
+            return start_pdf_generation(state);
+        }
+        Message::CancelPrint => {
+            state.pending_print = None;
+            state.print_after_generation = false;
+            state.display_text = tr(Text::GenerationCancelled).to_string();
+        }
+        Message::PollPdfProgress => {
+            // Redraw the progress bar until the PDF is done
+            if state.is_generating_pdf {
+                return Task::perform(
+                    async {
+                        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+                    },
+                    |_| Message::PollPdfProgress,
+                );
+            }
+        }
         Message::PdfGenerated(result) => {
             state.is_generating_pdf = false;
+            let print = std::mem::take(&mut state.print_after_generation);
 
             match result {
-                Ok(pdf_data) => {
-                    state.generated_pdf = Some(pdf_data.clone());
-                    state.display_text = format!(
-                        "PDF generated successfully! {} bytes - Opening save dialog...",
-                        pdf_data.len()
+                Ok(pdfs) if print => {
+                    state.display_text = tr(Text::SendingToPrinter).to_string();
+                    state.generated_pdfs = pdfs.clone();
+                    return Task::perform(
+                        async move {
+                            // Parts are spooled one by one, in order
+                            let mut printed = Vec::new();
+                            for (index, pdf) in pdfs.iter().enumerate() {
+                                let path = std::env::temp_dir()
+                                    .join(format!("localhawk_proxy_sheet_{}.pdf", index + 1));
+                                if let Err(e) = std::fs::write(&path, pdf) {
+                                    return Err(format!("Failed to write PDF for printing: {}", e));
+                                }
+                                if let Err(e) = print_file(&path) {
+                                    return Err(format!("Printing failed: {}", e));
+                                }
+                                printed.push(path.display().to_string());
+                            }
+                            Ok(printed.join(", "))
+                        },
+                        Message::PdfPrinted,
                     );
+                }
+                Ok(pdfs) => {
+                    let size: usize = pdfs.iter().map(Vec::len).sum();
+                    state.generated_pdfs = pdfs;
+                    state.display_text = tr_with(Text::PdfGeneratedOpeningSaveDialog, &[&size]);
 
                     // Auto-trigger save dialog after successful PDF generation
+                    let file_name = state.output_options.file_name(
+                        state.deck_name.as_deref(),
+                        1,
+                        state.generated_pdfs.len(),
+                    );
+                    let directory = state.last_directory.clone();
                     return Task::perform(
-                        async {
-                            match AsyncFileDialog::new()
-                                .set_file_name("proxy_sheet.pdf")
+                        async move {
+                            match save_dialog(directory)
+                                .set_file_name(file_name)
                                 .add_filter("PDF Files", &["pdf"])
                                 .save_file()
                                 .await
@@ -658,37 +1421,82 @@ pub fn update(state: &mut AppState, message: Message) -> Task<Message> {
                     );
                 }
                 Err(error) => {
-                    state.error_message = Some(error);
-                    state.display_text = "PDF generation failed!".to_string();
+                    let retry = if print {
+                        Message::PrintPdf
+                    } else {
+                        Message::GeneratePdf
+                    };
+                    state.notifications.error_with_retry(
+                        tr(Text::PdfGenerationFailed),
+                        Some(error),
+                        retry,
+                    );
+                    state.display_text = tr(Text::PdfGenerationFailed).to_string();
                 }
             }
         }
         Message::FileSaved(file_path) => {
             if let Some(path) = file_path {
-                if let Some(pdf_data) = &state.generated_pdf {
-                    match std::fs::write(&path, pdf_data) {
-                        Ok(_) => {
-                            state.display_text = format!("PDF saved successfully to: {}", path);
-                            state.error_message = None;
+                remember_directory(state, Path::new(&path));
+                if !state.generated_pdfs.is_empty() {
+                    // The chosen name replaces the template; parts are numbered after it
+                    let (directory, output_options) =
+                        state.output_options.for_path(Path::new(&path));
+                    let written = write_pdfs(
+                        &state.generated_pdfs,
+                        &directory,
+                        state.deck_name.as_deref(),
+                        &output_options,
+                        &[],
+                    );
+                    match written {
+                        Ok(paths) if paths.len() == 1 => {
+                            state.display_text = tr_with(Text::PdfSaved, &[&paths[0].display()]);
+                        }
+                        Ok(paths) => {
+                            state.display_text =
+                                tr_with(Text::PdfPartsSaved, &[&paths.len(), &directory.display()]);
                         }
                         Err(e) => {
-                            state.error_message = Some(format!("Failed to save PDF: {}", e));
+                            state
+                                .notifications
+                                .error(format!("Failed to save PDF: {}", e), None);
                         }
                     }
                 } else {
-                    state.error_message = Some("No PDF data to save!".to_string());
+                    state.notifications.error(tr(Text::NoPdfToSave), None);
                 }
             } else {
                 // User cancelled the dialog
-                state.display_text = "Save cancelled.".to_string();
+                state.display_text = tr(Text::SaveCancelled).to_string();
+            }
+        }
+        Message::PrintPdf => {
+            if state.parsed_cards.is_empty() {
+                state
+                    .notifications
+                    .warning(tr(Text::ParseDecklistFirst), None);
+                return Task::none();
             }
+            state.print_after_generation = true;
+            return update(state, Message::GeneratePdf);
         }
+        Message::PdfPrinted(result) => match result {
+            Ok(path) => {
+                state.display_text = tr_with(Text::SentToPrinter, &[&path]);
+            }
+            Err(error) => {
+                state
+                    .notifications
+                    .error(tr(Text::PrintingFailed), Some(error));
+                state.display_text = tr(Text::PrintingFailed).to_string();
+            }
+        },
         Message::ForceUpdateCardNames => {
             state.is_updating_card_names = true;
-            state.error_message = None;
 
             return Task::perform(
-                async {
+                with_timeout(NETWORK_TASK_TIMEOUT, async {
                     match force_update_card_lookup().await {
                         Ok(_) => {
                             // Get cache info after update
@@ -706,44 +1514,1824 @@ pub fn update(state: &mut AppState, message: Message) -> Task<Message> {
                         }
                         Err(e) => Err(format!("Failed to update card names: {}", e)),
                     }
-                },
+                }),
                 Message::CardNamesUpdated,
             );
         }
         Message::CardNamesUpdated(result) => {
             state.is_updating_card_names = false;
 
-            match result {
-                Ok(_) => {
-                    state.display_text = "Card names updated successfully!".to_string();
-                    state.error_message = None;
-                }
-                Err(error) => {
-                    state.error_message = Some(error);
-                    state.display_text = "Card name update failed!".to_string();
-                }
-            }
-        }
-        Message::DoubleFaceModeChanged(mode) => {
-            state.double_face_mode = mode;
-        }
-        Message::ToggleExtendedPanel => {
-            state.show_extended_panel = !state.show_extended_panel;
-        }
+            match result {
+                Ok(_) => {
+                    state.display_text = tr(Text::CardNamesUpdated).to_string();
+                }
+                Err(error) => {
+                    state.notifications.error_with_retry(
+                        tr(Text::CardNameUpdateFailed),
+                        Some(error),
+                        Message::ForceUpdateCardNames,
+                    );
+                    state.display_text = tr(Text::CardNameUpdateFailed).to_string();
+                }
+            }
+        }
+        Message::CacheRefreshStatusChanged(status) => match status {
+            CacheRefreshStatus::Refreshing { .. } => {}
+            CacheRefreshStatus::Completed { .. } => {
+                tracing::info!("Background refresh of card names and set codes finished");
+            }
+            CacheRefreshStatus::Failed(error) => {
+                state
+                    .notifications
+                    .error(tr(Text::CardDataRefreshFailed), Some(error));
+            }
+            CacheRefreshStatus::Idle => {}
+        },
+        Message::DoubleFaceModeChanged(mode) => {
+            update_pdf_options(state, |options| options.double_face_mode = mode);
+        }
+        Message::BackFaceFallbackChanged(fallback) => {
+            set_back_face_fallback(fallback);
+            if let Err(e) = update_settings(|settings| settings.back_face_fallback = fallback) {
+                tracing::warn!("Failed to save settings: {}", e);
+            }
+            // Skipped back faces change the number of images to lay out
+            relayout_grid_preview(state);
+        }
+        Message::DefaultPrintingToggled(enabled) => {
+            // Applies from the next time the decklist is parsed
+            set_default_printing(enabled);
+            if let Err(e) = update_settings(|settings| settings.default_printing = enabled) {
+                tracing::warn!("Failed to save settings: {}", e);
+            }
+        }
+        Message::CommentGroupsToggled(enabled) => {
+            // Applies from the next time the decklist is parsed
+            set_comment_annotations(enabled);
+            if let Err(e) = update_settings(|settings| settings.comment_annotations = enabled) {
+                tracing::warn!("Failed to save settings: {}", e);
+            }
+        }
+        Message::PreferredLanguageChanged(language) => {
+            // Applies from the next time the decklist is parsed; English is the default
+            let language = (language != "en").then_some(language);
+            set_preferred_language(language.clone());
+            if let Err(e) = update_settings(|settings| settings.preferred_language = language) {
+                tracing::warn!("Failed to save settings: {}", e);
+            }
+        }
+        Message::DownloadRateChanged(rate) => {
+            // Keep what was typed, but only apply numbers (or nothing, for unlimited)
+            let limit = match rate.trim() {
+                "" => Some(None),
+                digits => digits.parse::<u64>().ok().map(Some),
+            };
+            state.download_rate_text = rate;
+            if let Some(limit) = limit {
+                set_download_rate_limit(limit);
+                if let Err(e) = update_settings(|settings| settings.max_download_rate_kb = limit) {
+                    tracing::warn!("Failed to save settings: {}", e);
+                }
+            }
+        }
+        Message::ToggleExtendedPanel => {
+            state.show_extended_panel = !state.show_extended_panel;
+        }
+        Message::WindowResized(size) => {
+            state.window_size = Some(size);
+        }
+        Message::WindowCloseRequested(id) => {
+            save_layout(state);
+            return window::close(id);
+        }
+        Message::Shortcut(shortcut) => return handle_shortcut(state, shortcut),
+        Message::TogglePdfOptions => {
+            state.show_pdf_options = !state.show_pdf_options;
+        }
+        Message::ToggleSearchPanel => {
+            state.show_search_panel = !state.show_search_panel;
+        }
+        Message::SearchQueryChanged(query) => {
+            state.search_query = query;
+        }
+        Message::RunSearchQuery => {
+            let query = state.search_query.trim().to_string();
+            if query.is_empty() || state.is_searching {
+                return Task::none();
+            }
+            state.is_searching = true;
+            return Task::perform(
+                with_timeout(NETWORK_TASK_TIMEOUT, async move {
+                    ProxyGenerator::search_with_query(&query)
+                        .await
+                        .map_err(|e| e.to_string())
+                }),
+                Message::SearchQueryResults,
+            );
+        }
+        Message::SearchQueryResults(result) => {
+            state.is_searching = false;
+            match result {
+                Ok(results) => state.search_results = Some(results),
+                Err(error) => {
+                    state.search_results = None;
+                    state
+                        .notifications
+                        .error_with_retry(error, None, Message::RunSearchQuery);
+                }
+            }
+        }
+        Message::AddSearchResult(index) => {
+            let Some(card) = state
+                .search_results
+                .as_ref()
+                .and_then(|results| results.cards.get(index))
+            else {
+                return Task::none();
+            };
+            // Pin the set so the listed printing is the one that gets resolved
+            let line = format!("1 {} [{}]", card.name, card.set.to_uppercase());
+            let mut decklist = state.decklist_content.text();
+            if !decklist.is_empty() && !decklist.ends_with('\n') {
+                decklist.push('\n');
+            }
+            decklist.push_str(&line);
+            state.decklist_content = text_editor::Content::with_text(&decklist);
+        }
+        Message::PageSizeChanged(page_size) => {
+            update_pdf_options(state, |options| options.page_size = page_size);
+        }
+        Message::CardsPerRowChanged(cards_per_row) => {
+            update_pdf_options(state, |options| options.cards_per_row = cards_per_row);
+        }
+        Message::FileNameTemplateChanged(template) => {
+            update_output_options(state, |options| options.file_name_template = template);
+        }
+        Message::OverwritePolicyChanged(overwrite) => {
+            update_output_options(state, |options| options.overwrite = overwrite);
+        }
+        Message::PagesPerFileChanged(pages) => {
+            // Empty means everything in one file; anything else that isn't a number is ignored
+            let pages_per_file = if pages.trim().is_empty() {
+                Some(None)
+            } else {
+                pages.trim().parse::<u32>().ok().map(Some)
+            };
+            if let Some(pages_per_file) = pages_per_file {
+                update_output_options(state, |options| options.pages_per_file = pages_per_file);
+            }
+        }
+        Message::SaveCalibrationPage => {
+            let options = state.pdf_options.clone();
+            let directory = state.last_directory.clone();
+            return Task::perform(
+                async move {
+                    let handle = save_dialog(directory)
+                        .set_file_name("calibration.pdf")
+                        .add_filter("PDF Files", &["pdf"])
+                        .save_file()
+                        .await?;
+                    let path = handle.path().to_path_buf();
+                    let written = generate_calibration_page(&options)
+                        .and_then(|pdf| Ok(std::fs::write(&path, pdf)?));
+                    Some(
+                        written
+                            .map(|()| path.display().to_string())
+                            .map_err(|e| e.to_string()),
+                    )
+                },
+                Message::CalibrationPageSaved,
+            );
+        }
+        Message::CalibrationPageSaved(result) => match result {
+            Some(Ok(path)) => {
+                remember_directory(state, Path::new(&path));
+                state.display_text = tr_with(Text::CalibrationPageSaved, &[&path]);
+            }
+            Some(Err(error)) => {
+                state
+                    .notifications
+                    .error(tr(Text::CalibrationFailed), Some(error));
+            }
+            None => state.display_text = tr(Text::SaveCancelled).to_string(),
+        },
+        Message::CalibrationMeasurementChanged(measurement) => {
+            state.calibration_measurement = measurement;
+        }
+        Message::ApplyCalibration => {
+            let Ok(measured) = state.calibration_measurement.trim().parse::<f32>() else {
+                return Task::none();
+            };
+            match calibrated_scale_correction(state.pdf_options.scale_correction, measured) {
+                Ok(correction) => {
+                    update_pdf_options(state, |options| options.scale_correction = correction);
+                    state.calibration_measurement.clear();
+                }
+                Err(e) => {
+                    state
+                        .notifications
+                        .error(tr(Text::CalibrationFailed), Some(e.to_string()));
+                }
+            }
+        }
+        Message::ResetCalibration => {
+            update_pdf_options(state, |options| options.scale_correction = 1.0);
+        }
+        Message::SortOrderChanged(sort_order) => {
+            // Choosing a sort order drops the order cards were dragged into
+            if let Some(grid_preview) = state.grid_preview.as_mut() {
+                grid_preview.entry_order.clear();
+            }
+            update_pdf_options(state, |options| options.sort_order = sort_order);
+        }
+        Message::CopyOrderChanged(copy_order) => {
+            update_pdf_options(state, |options| options.copy_order = copy_order);
+        }
+        Message::FillerCardsChanged(filler_cards_text) => {
+            state.filler_cards_text = filler_cards_text;
+        }
+        Message::ApplyFillerCards => {
+            let names: Vec<String> = state
+                .filler_cards_text
+                .split(',')
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+                .map(String::from)
+                .collect();
+            state.filler_cards_text = names.join(", ");
+            update_pdf_options(state, |options| options.filler_cards = names.clone());
+
+            // An existing preview needs the printings of the new filler cards
+            if state.grid_preview.is_some() {
+                return Task::perform(
+                    with_timeout(NETWORK_TASK_TIMEOUT, async move {
+                        Ok(load_filler_entries(names).await)
+                    }),
+                    |fillers| Message::FillerEntriesLoaded(fillers.unwrap_or_default()),
+                );
+            }
+        }
+        Message::BasicLandSetsChanged(basic_land_sets_text) => {
+            state.basic_land_sets_text = basic_land_sets_text;
+        }
+        Message::ApplyBasicLandSets => {
+            // Applies from the next time the decklist is parsed
+            set_basic_land_sets(
+                state
+                    .basic_land_sets_text
+                    .split(',')
+                    .map(String::from)
+                    .collect(),
+            );
+            let sets = get_basic_land_sets();
+            state.basic_land_sets_text = sets.join(", ").to_uppercase();
+            if let Err(e) = update_settings(|settings| settings.basic_land_sets = sets) {
+                tracing::warn!("Failed to save settings: {}", e);
+            }
+        }
+        Message::FillerEntriesLoaded(fillers) => {
+            for filler in &fillers {
+                if filler.available_printings.is_empty() {
+                    state.notifications.warning(
+                        tr_with(Text::NoPrintingsFound, &[&filler.decklist_entry.name]),
+                        None,
+                    );
+                }
+            }
+            if let Some(grid_preview) = state.grid_preview.as_mut() {
+                grid_preview.set_filler_entries(fillers);
+            }
+            relayout_grid_preview(state);
+        }
+        Message::CardsPerColumnChanged(cards_per_column) => {
+            update_pdf_options(state, |options| options.cards_per_column = cards_per_column);
+        }
+        Message::MarginChanged(margin) => {
+            update_pdf_options(state, |options| options.margin = margin);
+        }
+        Message::CutMarkersToggled(cut_markers) => {
+            update_pdf_options(state, |options| options.cut_markers = cut_markers);
+        }
+        Message::CardCaptionsToggled(card_captions) => {
+            update_pdf_options(state, |options| options.card_captions = card_captions);
+        }
+        Message::GroupDividersToggled(group_dividers) => {
+            update_pdf_options(state, |options| options.group_dividers = group_dividers);
+        }
+        Message::CommandersOnOwnPageToggled(own_page) => {
+            update_pdf_options(state, |options| options.commanders_on_own_page = own_page);
+        }
+        Message::SectionSplitChanged(section_split) => {
+            update_pdf_options(state, |options| options.section_split = section_split);
+        }
+        Message::DuplicateCommandersToggled(duplicate) => {
+            update_pdf_options(state, |options| options.duplicate_commanders = duplicate);
+        }
+        Message::PdfAToggled(pdf_a) => {
+            update_pdf_options(state, |options| options.pdf_a = pdf_a);
+        }
+        Message::TextOnlyToggled(text_only) => {
+            update_pdf_options(state, |options| options.text_only = text_only);
+        }
+        Message::ShowPricesToggled(show_prices) => {
+            state.show_prices = show_prices;
+            if let Err(e) = update_settings(|settings| settings.show_prices = show_prices) {
+                tracing::warn!("Failed to save settings: {}", e);
+            }
+            refresh_aligned_output(state);
+        }
+        Message::UiLanguageChanged(language) => {
+            set_ui_language(language);
+            if let Err(e) = update_settings(|settings| settings.ui_language = language) {
+                tracing::warn!("Failed to save settings: {}", e);
+            }
+        }
+        Message::LogToFileToggled(log_to_file) => {
+            state.log_to_file = log_to_file;
+            if let Err(e) = update_settings(|settings| settings.log_to_file = log_to_file) {
+                tracing::warn!("Failed to save settings: {}", e);
+            }
+        }
+        Message::CopyDecklist => {
+            let Some(grid_preview) = state.grid_preview.as_ref() else {
+                return Task::none();
+            };
+            let cards = normalized_decklist_cards(grid_preview);
+            let total: u32 = cards.iter().map(|(_, _, copies, _)| copies).sum();
+            state.display_text = tr_with(Text::DecklistCopied, &[&total]);
+            return iced::clipboard::write(format_normalized_decklist(&cards));
+        }
+        Message::ExportLogs => {
+            let directory = state.last_directory.clone();
+            return Task::perform(
+                async move {
+                    let handle = save_dialog(directory)
+                        .set_file_name("localhawk-logs.txt")
+                        .add_filter("Text Files", &["txt"])
+                        .save_file()
+                        .await?;
+                    let path = handle.path().to_path_buf();
+                    Some(
+                        export_logs(&path)
+                            .map(|count| (count, path.display().to_string()))
+                            .map_err(|e| e.to_string()),
+                    )
+                },
+                Message::LogsExported,
+            );
+        }
+        Message::LogsExported(result) => match result {
+            Some(Ok((count, path))) => {
+                remember_directory(state, Path::new(&path));
+                state.display_text = tr_with(Text::LogsExported, &[&count, &path]);
+            }
+            Some(Err(error)) => {
+                state
+                    .notifications
+                    .error(tr(Text::LogExportFailed), Some(error));
+            }
+            None => state.display_text = tr(Text::SaveCancelled).to_string(),
+        },
+        Message::RunDiagnostics => {
+            state.running_diagnostics = true;
+            return Task::perform(
+                with_timeout(NETWORK_TASK_TIMEOUT, async {
+                    Ok(run_diagnostics().await.to_string())
+                }),
+                |report| Message::DiagnosticsFinished(report.unwrap_or_else(|error| error)),
+            );
+        }
+        Message::DiagnosticsFinished(report) => {
+            state.running_diagnostics = false;
+            state.diagnostics_report = Some(report);
+        }
+        Message::CopyDiagnostics => {
+            if let Some(report) = &state.diagnostics_report {
+                state.display_text = tr(Text::DiagnosticsCopied).to_string();
+                return iced::clipboard::write(report.clone());
+            }
+        }
+        Message::CollectUsageStatsToggled(collect_usage_stats) => {
+            state.collect_usage_stats = collect_usage_stats;
+            if let Err(e) =
+                update_settings(|settings| settings.collect_usage_stats = collect_usage_stats)
+            {
+                tracing::warn!("Failed to save settings: {}", e);
+            }
+        }
+        Message::ResetUsageStats => {
+            if let Err(e) = reset_usage_stats() {
+                state
+                    .notifications
+                    .error(tr(Text::UsageStatsResetFailed), Some(e.to_string()));
+            }
+        }
+        Message::ToggleCachedCards => {
+            state.cached_cards = match state.cached_cards {
+                Some(_) => None,
+                None => Some(get_image_cache_contents()),
+            };
+        }
+        Message::ImageCacheEvictionChanged(policy) => {
+            set_image_cache_eviction_policy(policy);
+            if let Err(e) = update_settings(|settings| settings.image_cache_eviction = policy) {
+                tracing::warn!("Failed to save settings: {}", e);
+            }
+        }
+        Message::PinDeck => {
+            let Some(grid_preview) = &state.grid_preview else {
+                return Task::none();
+            };
+            let Some(deck_name) = &state.deck_name else {
+                state
+                    .notifications
+                    .warning(tr(Text::PinDeckNeedsName), None);
+                return Task::none();
+            };
+            let cards = grid_preview.to_card_groups(&state.pdf_options).concat();
+            let urls = ProxyGenerator::expand_cards_to_image_urls(&cards);
+            if let Err(e) = pin_deck(deck_name, urls) {
+                state
+                    .notifications
+                    .error(tr(Text::PinDeckFailed), Some(e.to_string()));
+            }
+            state.pinned_decks = get_pinned_decks();
+        }
+        Message::UnpinDeck(name) => {
+            if let Err(e) = unpin_deck(&name) {
+                tracing::warn!("Failed to unpin deck '{}': {}", name, e);
+            }
+            state.pinned_decks = get_pinned_decks();
+        }
+        Message::ImportCollection => {
+            return Task::perform(
+                async {
+                    let handle = AsyncFileDialog::new()
+                        .add_filter("Collection exports", &["csv", "txt"])
+                        .pick_file()
+                        .await?;
+                    let file_name = handle.file_name();
+                    Some(
+                        Collection::load_from(handle.path())
+                            .map(|collection| (file_name, collection))
+                            .map_err(|e| format!("Failed to load collection: {}", e)),
+                    )
+                },
+                Message::CollectionLoaded,
+            );
+        }
+        Message::CollectionLoaded(result) => match result {
+            Some(Ok((file_name, collection))) => {
+                state.display_text = format!(
+                    "Imported collection {} ({} cards)",
+                    file_name,
+                    collection.total_cards()
+                );
+                state.collection = Some((file_name, collection));
+                state.missing_only = true;
+                return reparse_if_parsed(state);
+            }
+            Some(Err(error)) => {
+                state.notifications.error(error, None);
+            }
+            None => {
+                state.display_text = "Collection import cancelled.".to_string();
+            }
+        },
+        Message::MissingOnlyToggled(missing_only) => {
+            state.missing_only = missing_only;
+            return reparse_if_parsed(state);
+        }
+        Message::LegalityFormatChanged(choice) => {
+            let legality_format = match choice {
+                FormatChoice::NoCheck => None,
+                FormatChoice::Check(format) => Some(format),
+            };
+            state.legality_format = legality_format;
+            if let Err(e) = update_settings(|settings| settings.legality_format = legality_format) {
+                tracing::warn!("Failed to save settings: {}", e);
+            }
+        }
+        Message::ImageQualityChanged(image_quality) => {
+            update_pdf_options(state, |options| options.image_quality = image_quality);
+        }
+        Message::DismissNotification(id) => state.notifications.dismiss(id),
+        Message::DismissAllNotifications => state.notifications.dismiss_all(),
+        Message::ToggleNotificationDetails(id) => state.notifications.toggle_details(id),
+        Message::RetryNotification(id) => {
+            if let Some(retry) = state.notifications.take_retry(id) {
+                return update(state, retry);
+            }
+        }
+    }
+    Task::none()
+}
+
+/// Map key presses that no widget handled to shortcuts
+fn shortcut_for_key(key: Key, modifiers: Modifiers) -> Option<Shortcut> {
+    match key.as_ref() {
+        Key::Named(Named::Enter) if modifiers.command() => Some(Shortcut::GenerateAndSave),
+        Key::Named(Named::Enter) => Some(Shortcut::Confirm),
+        Key::Named(Named::PageUp) => Some(Shortcut::PreviousPage),
+        Key::Named(Named::PageDown) => Some(Shortcut::NextPage),
+        Key::Named(Named::Escape) => Some(Shortcut::Close),
+        Key::Named(Named::ArrowLeft) => Some(Shortcut::MoveLeft),
+        Key::Named(Named::ArrowRight) => Some(Shortcut::MoveRight),
+        Key::Named(Named::ArrowUp) => Some(Shortcut::MoveUp),
+        Key::Named(Named::ArrowDown) => Some(Shortcut::MoveDown),
+        _ => None,
+    }
+}
+
+pub fn subscription(_state: &AppState) -> Subscription<Message> {
+    Subscription::batch([
+        keyboard::on_key_press(|key, modifiers| {
+            shortcut_for_key(key, modifiers).map(Message::Shortcut)
+        }),
+        window::resize_events().map(|(_id, size)| Message::WindowResized(size)),
+        window::close_requests().map(Message::WindowCloseRequested),
+        Subscription::run(image_cache_changes),
+        Subscription::run(cache_refresh_status_changes),
+        // Cards can be dropped anywhere, so releases are picked up outside the preview too
+        event::listen_with(|event, _status, _window| match event {
+            iced::Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) => {
+                Some(Message::CardReleased)
+            }
+            _ => None,
+        }),
+    ])
+}
+
+/// Image cache events as messages, for as long as the app runs
+fn image_cache_changes() -> impl iced::futures::Stream<Item = Message> {
+    iced::futures::stream::unfold(subscribe_cache_events(), |mut events| async move {
+        loop {
+            match events.recv().await {
+                Ok(event) => return Some((Message::ImageCacheChanged(event), events)),
+                // The next event redraws the thumbnails of the skipped ones as well
+                Err(RecvError::Lagged(skipped)) => {
+                    tracing::debug!("Skipped {} image cache events", skipped);
+                }
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    })
+}
+
+/// Status changes of the automatic background refresh as messages, starting with the current
+/// status in case the refresh finished before the app subscribed
+fn cache_refresh_status_changes() -> impl iced::futures::Stream<Item = Message> {
+    let mut statuses = subscribe_cache_refresh_status();
+    statuses.mark_changed();
+    iced::futures::stream::unfold(statuses, |mut statuses| async move {
+        statuses.changed().await.ok()?;
+        let status = statuses.borrow_and_update().clone();
+        Some((Message::CacheRefreshStatusChanged(status), statuses))
+    })
+}
+
+/// Run a network-bound task, failing with a timeout error once it takes longer than `limit`
+///
+/// A stalled connection would otherwise leave the GUI waiting forever, e.g. showing
+/// "Building..." for a preview. The task is dropped on timeout, which cancels its requests.
+async fn with_timeout<T>(
+    limit: Duration,
+    task: impl Future<Output = Result<T, String>>,
+) -> Result<T, String> {
+    tokio::time::timeout(limit, task)
+        .await
+        .unwrap_or_else(|_| Err(tr_with(Text::TaskTimedOut, &[&limit.as_secs()])))
+}
+
+/// Save dialog starting in the directory the last file was saved to
+fn save_dialog(directory: Option<PathBuf>) -> AsyncFileDialog {
+    match directory {
+        Some(directory) => AsyncFileDialog::new().set_directory(directory),
+        None => AsyncFileDialog::new(),
+    }
+}
+
+/// Remember the directory of a saved file for the next save dialog
+fn remember_directory(state: &mut AppState, path: &Path) {
+    if let Some(directory) = path.parent() {
+        state.last_directory = Some(directory.to_path_buf());
+        save_layout(state);
+    }
+}
+
+/// Persist the window size, open panels and last directory so the next launch restores them
+fn save_layout(state: &AppState) {
+    let layout = GuiLayout {
+        window_size: state.window_size.map(|size| (size.width, size.height)),
+        show_extended_panel: state.show_extended_panel,
+        show_pdf_options: state.show_pdf_options,
+        show_search_panel: state.show_search_panel,
+        last_directory: state.last_directory.clone(),
+    };
+    if let Err(e) = update_settings(|settings| settings.gui_layout = layout) {
+        tracing::warn!("Failed to save settings: {}", e);
+    }
+}
+
+fn handle_shortcut(state: &mut AppState, shortcut: Shortcut) -> Task<Message> {
+    let in_print_selection = state.preview_mode == PreviewMode::PrintSelection;
+
+    match shortcut {
+        Shortcut::PreviousPage if in_print_selection => {
+            update(state, Message::PrintSelectionPrevPage)
+        }
+        Shortcut::NextPage if in_print_selection => update(state, Message::PrintSelectionNextPage),
+        Shortcut::PreviousPage => update(state, Message::PrevPage),
+        Shortcut::NextPage => update(state, Message::NextPage),
+        Shortcut::GenerateAndSave => {
+            if state.is_generating_pdf || state.is_parsing {
+                Task::none()
+            } else {
+                update(state, Message::GenerateAll)
+            }
+        }
+        Shortcut::Close if in_print_selection => update(state, Message::ClosePrintSelection),
+        Shortcut::Confirm if in_print_selection => {
+            let entry_index = state
+                .grid_preview
+                .as_ref()
+                .and_then(|grid_preview| grid_preview.selected_entry_index);
+            let print_index = state
+                .print_selection_focus
+                .and_then(|position| state.filtered_printings.get(position).copied());
+            match (entry_index, print_index) {
+                (Some(entry_index), Some(print_index)) => update(
+                    state,
+                    Message::SelectPrint {
+                        entry_index,
+                        print_index,
+                    },
+                ),
+                _ => Task::none(),
+            }
+        }
+        Shortcut::MoveLeft | Shortcut::MoveRight | Shortcut::MoveUp | Shortcut::MoveDown
+            if in_print_selection =>
+        {
+            move_print_selection_focus(state, shortcut);
+            Task::none()
+        }
+        _ => Task::none(),
+    }
+}
+
+/// After the printings of a preview entry changed: re-layout the pages and mirror the
+/// selection into parsed_cards so the aligned output and re-resolution stay in sync
+fn apply_printing_change(state: &mut AppState, entry_index: usize) {
+    relayout_grid_preview_entry(state, entry_index);
+
+    let Some(entry) = state
+        .grid_preview
+        .as_ref()
+        .and_then(|grid_preview| grid_preview.entries.get(entry_index))
+    else {
+        return;
+    };
+
+    // Update the corresponding DecklistEntry in parsed_cards with selected printing info
+    if let Some(selected_card) = entry.get_selected_card() {
+        // Find the matching entry in parsed_cards by name
+        tracing::debug!(
+            "Looking for match: grid entry name='{}', checking against {} parsed entries",
+            entry.decklist_entry.name,
+            state.parsed_cards.len()
+        );
+
+        if let Some(parsed_entry) = state.parsed_cards.iter_mut().find(|parsed| {
+            parsed.name.to_lowercase() == entry.decklist_entry.name.to_lowercase()
+                && parsed.face_mode_for(selected_card) == entry.decklist_entry.face_mode
+        }) {
+            // Update the parsed entry with the selected printing's id, set and language;
+            // for split entries this is the printing with the most copies
+            parsed_entry.set = Some(selected_card.set.clone());
+            parsed_entry.lang = Some(selected_card.language.clone());
+            parsed_entry.scryfall_id = selected_card.scryfall_id.clone();
+            parsed_entry.multiple = entry.decklist_entry.multiple;
+
+            tracing::debug!(
+                "Updated parsed entry '{}' with selected printing: set='{}', lang='{}'",
+                parsed_entry.name,
+                selected_card.set,
+                selected_card.language
+            );
+        } else {
+            tracing::warn!(
+                "Could not find matching parsed entry for grid entry '{}'",
+                entry.decklist_entry.name
+            );
+        }
+    }
+
+    // Rebuild aligned text output after print selection
+    refresh_aligned_output(state);
+}
+
+/// Fetch the full-size images of the printings being compared that aren't cached yet
+///
+/// A running background load downloads them before anything else; otherwise they are fetched
+/// here.
+fn load_compared_images(state: &AppState) -> Task<Message> {
+    let Some(entry) = state.grid_preview.as_ref().and_then(|grid_preview| {
+        grid_preview
+            .selected_entry_index
+            .and_then(|entry_index| grid_preview.entries.get(entry_index))
+    }) else {
+        return Task::none();
+    };
+
+    let urls: Vec<String> = state
+        .compared_printings
+        .iter()
+        .filter_map(|&print_index| entry.available_printings.get(print_index))
+        .map(|card| card.border_crop.clone())
+        .filter(|url| get_cached_image_bytes(url).is_none())
+        .collect();
+    if urls.is_empty() {
+        return Task::none();
+    }
+    if let Some(handle) = state.background_loader.handle() {
+        if handle.prioritize_images(urls.clone()) {
+            return Task::none();
+        }
+    }
+
+    Task::perform(
+        with_timeout(NETWORK_TASK_TIMEOUT, async move {
+            for url in urls {
+                if let Err(e) = get_or_fetch_image_bytes(&url).await {
+                    tracing::warn!("Failed to load {} for comparison: {}", url, e);
+                }
+            }
+            Ok(())
+        }),
+        |result| {
+            if let Err(error) = result {
+                tracing::warn!("Loading the compared printings stopped: {}", error);
+            }
+            Message::ComparedImagesLoaded
+        },
+    )
+}
+
+/// One of the printings compared side by side: the full-size image, a caption naming the
+/// printing and a button selecting it
+fn compared_printing<'a>(
+    card: &Card,
+    entry_index: usize,
+    print_index: usize,
+) -> Element<'a, Message> {
+    // The full-size image may still be downloading; the thumbnail stands in meanwhile
+    let image_view: Element<'a, Message> = match get_cached_image_bytes(&card.border_crop)
+        .or_else(|| get_cached_thumbnail_bytes(&card.border_crop))
+    {
+        Some(image_bytes) => {
+            image::Image::<image::Handle>::new(image::Handle::from_bytes(image_bytes))
+                .width(Length::Fixed(COMPARE_CARD_WIDTH))
+                .height(Length::Fixed(COMPARE_CARD_HEIGHT))
+                .into()
+        }
+        None => container(text("...").size(12))
+            .width(Length::Fixed(COMPARE_CARD_WIDTH))
+            .height(Length::Fixed(COMPARE_CARD_HEIGHT))
+            .center_x(Length::Fill)
+            .center_y(Length::Fill)
+            .into(),
+    };
+
+    let collector_number = card
+        .collector_number
+        .as_deref()
+        .map(|number| format!("#{} · ", number))
+        .unwrap_or_default();
+    column![
+        image_view,
+        row![
+            set_label(&card.set),
+            text(format!("{}{}", collector_number, card.language)).size(UI_FONT_SIZE),
+        ]
+        .spacing(6)
+        .align_y(iced::Alignment::Center),
+        button(text(tr(Text::UseThisPrinting)).size(UI_FONT_SIZE))
+            .on_press(Message::SelectPrint {
+                entry_index,
+                print_index,
+            })
+            .padding(5),
+    ]
+    .spacing(6)
+    .align_x(iced::Alignment::Center)
+    .into()
+}
+
+/// Fetch the set symbols of an entry's printings that aren't in memory yet
+fn load_set_icons(state: &AppState, entry_index: usize) -> Task<Message> {
+    let Some(entry) = state
+        .grid_preview
+        .as_ref()
+        .and_then(|grid_preview| grid_preview.entries.get(entry_index))
+    else {
+        return Task::none();
+    };
+
+    let set_codes: BTreeSet<String> = entry
+        .available_printings
+        .iter()
+        .map(|card| card.set.clone())
+        .filter(|set_code| get_cached_set_icon(set_code).is_none())
+        .collect();
+    if set_codes.is_empty() {
+        return Task::none();
+    }
+
+    Task::perform(
+        with_timeout(NETWORK_TASK_TIMEOUT, async move {
+            for set_code in set_codes {
+                if let Err(e) = get_or_fetch_set_icon(&set_code).await {
+                    tracing::debug!("No set icon for {}: {}", set_code, e);
+                }
+            }
+            Ok(())
+        }),
+        |result| {
+            if let Err(error) = result {
+                tracing::warn!("Loading set icons stopped: {}", error);
+            }
+            Message::SetIconsLoaded
+        },
+    )
+}
+
+/// Set symbol next to the set code, falling back to the code alone until the symbol is loaded,
+/// with the set's name on hover
+fn set_label<'a>(set_code: &str) -> Element<'a, Message> {
+    let code = text(set_code.to_uppercase()).size(12);
+    let label: Element<'a, Message> = match get_cached_set_icon(set_code) {
+        Some(icon) => row![
+            svg(svg::Handle::from_memory(icon))
+                .width(Length::Fixed(SET_ICON_SIZE))
+                .height(Length::Fixed(SET_ICON_SIZE))
+                .style(|theme: &iced::Theme, _status| svg::Style {
+                    color: Some(theme.palette().text),
+                }),
+            code,
+        ]
+        .spacing(4)
+        .align_y(iced::Alignment::Center)
+        .into(),
+        None => code.into(),
+    };
+
+    // Name and release date of the set on hover, once the set list has them
+    match get_set_info(set_code) {
+        Some(info) => {
+            let description = match &info.released_at {
+                Some(released_at) => format!("{} ({})", set_display_name(set_code), released_at),
+                None => set_display_name(set_code),
+            };
+            tooltip(
+                label,
+                container(text(description).size(12))
+                    .padding(6)
+                    .style(container::rounded_box),
+                tooltip::Position::Bottom,
+            )
+            .into()
+        }
+        None => label,
+    }
+}
+
+/// Parse the decklist again so a changed collection filter applies to the parsed cards and preview
+fn reparse_if_parsed(state: &mut AppState) -> Task<Message> {
+    if state.parsed_cards.is_empty() && state.grid_preview.is_none() {
+        Task::none()
+    } else {
+        update(state, Message::ParseDecklist)
+    }
+}
+
+/// Rebuild the aligned parsed output, with the selected printings' prices if enabled
+fn refresh_aligned_output(state: &mut AppState) {
+    let prices: Vec<Option<String>> = match (&state.grid_preview, state.show_prices) {
+        (Some(grid_preview), true) => grid_preview
+            .entries
+            .iter()
+            .map(|entry| entry.get_selected_card().and_then(Card::price_label))
+            .collect(),
+        _ => Vec::new(),
+    };
+    let aligned_text = build_aligned_parsed_output_with_report(
+        &state.decklist_content.text(),
+        &state.parsed_cards,
+        &prices,
+        &state.resolution_report,
+    );
+    state.parsed_cards_aligned_text = text_editor::Content::with_text(&aligned_text);
+}
+
+/// Lines of the aligned output, with skipped and unresolved lines in red
+fn aligned_output_spans(aligned_text: &str) -> Vec<text::Span<'static, Message>> {
+    aligned_text
+        .lines()
+        .map(|line| {
+            let line_span = span(format!("{}\n", line));
+            if line.starts_with('✗') {
+                line_span.color(iced::Color::from_rgb(0.8, 0.2, 0.2))
+            } else {
+                line_span
+            }
+        })
+        .collect()
+}
+
+/// Badge in the top right corner of a preview card: cached printings out of all printings
+fn printings_badge<'a>(cached: usize, total: usize, card_width: f32) -> Element<'a, Message> {
+    let all_cached = cached == total;
+    container(
+        container(
+            text(format!("{}/{}", cached, total))
+                .size(10)
+                .color(iced::Color::WHITE),
+        )
+        .padding([1, 4])
+        .style(move |_theme| container::Style {
+            background: Some(
+                if all_cached {
+                    iced::Color::from_rgb(0.2, 0.6, 0.2)
+                } else {
+                    iced::Color::from_rgba(0.0, 0.0, 0.0, 0.6)
+                }
+                .into(),
+            ),
+            border: iced::Border {
+                radius: 3.0.into(),
+                ..Default::default()
+            },
+            ..Default::default()
+        }),
+    )
+    .align_right(Length::Fixed(card_width))
+    .padding(4)
+    .into()
+}
+
+/// Toggle in the top left corner of a double-faced preview card that cycles the faces printed
+/// for just this entry
+fn face_mode_toggle<'a>(entry_index: usize, face_mode: &DoubleFaceMode) -> Element<'a, Message> {
+    let label = match face_mode {
+        DoubleFaceMode::FrontOnly => tr(Text::FaceFront),
+        DoubleFaceMode::BackOnly => tr(Text::FaceBack),
+        DoubleFaceMode::BothSides => tr(Text::FaceBoth),
+    };
+    container(
+        button(text(label).size(10))
+            .padding([1, 4])
+            .on_press(Message::EntryFaceModeToggled(entry_index)),
+    )
+    .padding(4)
+    .into()
+}
+
+/// Progress bar for `done` out of `total` items, with a label in front
+fn labelled_progress_bar<'a>(label: String, done: usize, total: usize) -> Element<'a, Message> {
+    row![
+        text(label).size(14).width(Length::Fixed(260.0)),
+        progress_bar(0.0..=total.max(1) as f32, done as f32)
+            .width(Length::Fixed(300.0))
+            .height(Length::Fixed(10.0)),
+    ]
+    .spacing(10)
+    .align_y(iced::Alignment::Center)
+    .into()
+}
+
+/// Estimated cost of the real cards for everything the preview would print
+fn price_total_text(grid_preview: &GridPreview, pdf_options: &PdfOptions) -> String {
+    let cards: Vec<(Card, u32)> = grid_preview
+        .to_card_groups(pdf_options)
+        .into_iter()
+        .flatten()
+        .map(|(card, copies, _)| (card, copies))
+        .collect();
+    format_price_total(&cards)
+}
+
+/// Warnings for cards that are banned or not legal in the selected format
+///
+/// Legality comes from the resolved printings, so there is nothing to report before the preview exists.
+fn legality_warnings(state: &AppState) -> Option<String> {
+    let format = state.legality_format?;
+    let grid_preview = state.grid_preview.as_ref()?;
+    let cards: Vec<(Card, u32)> = grid_preview
+        .entries
+        .iter()
+        .filter_map(|entry| {
+            let card = entry.get_selected_card()?.clone();
+            Some((card, entry.copies()))
+        })
+        .collect();
+
+    let issues = check_legality(&cards, format);
+    if issues.is_empty() {
+        return Some(format!("✓ All cards are legal in {}", format));
+    }
+    let lines: Vec<String> = issues.iter().map(|issue| format!("⚠ {}", issue)).collect();
+    Some(format!("Not legal in {}:\n{}", format, lines.join("\n")))
+}
+
+/// Warnings for cards whose back face should be printed but has no image
+fn back_face_warnings(state: &AppState) -> Option<String> {
+    let grid_preview = state.grid_preview.as_ref()?;
+    let lines: Vec<String> = grid_preview
+        .entries
+        .iter()
+        .filter_map(|entry| {
+            entry
+                .get_selected_card()?
+                .missing_back_face_warning(&entry.decklist_entry.face_mode)
+        })
+        .map(|warning| format!("⚠ {}", warning))
+        .collect();
+    (!lines.is_empty()).then(|| lines.join("\n"))
+}
+
+/// Toasts for the current errors and warnings, newest first
+fn notification_center(notifications: &Notifications) -> Element<'_, Message> {
+    let mut toasts = column![]
+        .spacing(8)
+        .width(Length::Fixed(NOTIFICATION_WIDTH));
+    if notifications.len() > 1 {
+        toasts = toasts.push(
+            row![
+                horizontal_space(),
+                button(text(tr(Text::DismissAll)).size(12))
+                    .on_press(Message::DismissAllNotifications)
+                    .padding([2, 8]),
+            ]
+            .width(Length::Fill),
+        );
+    }
+
+    for notification in notifications.iter() {
+        let (label, color) = match notification.level {
+            NotificationLevel::Error => {
+                (tr(Text::ErrorLabel), iced::Color::from_rgb(0.8, 0.2, 0.2))
+            }
+            NotificationLevel::Warning => {
+                (tr(Text::WarningLabel), iced::Color::from_rgb(0.8, 0.5, 0.1))
+            }
+        };
+
+        let mut header = row![
+            text(notification.time_label())
+                .size(12)
+                .color(iced::Color::from_rgb(0.5, 0.5, 0.5)),
+            text(label).size(12).color(color),
+            text(&notification.summary)
+                .size(UI_FONT_SIZE)
+                .width(Length::Fill),
+        ]
+        .spacing(8)
+        .align_y(iced::Alignment::Center);
+        if notification.details.is_some() {
+            let toggle_label = if notification.expanded {
+                Text::HideDetails
+            } else {
+                Text::ShowDetails
+            };
+            header = header.push(
+                button(text(tr(toggle_label)).size(12))
+                    .on_press(Message::ToggleNotificationDetails(notification.id))
+                    .padding([2, 8]),
+            );
+        }
+        if notification.retry.is_some() {
+            header = header.push(
+                button(text(tr(Text::Retry)).size(12))
+                    .on_press(Message::RetryNotification(notification.id))
+                    .padding([2, 8]),
+            );
+        }
+        header = header.push(
+            button(text("×").size(12))
+                .on_press(Message::DismissNotification(notification.id))
+                .padding([2, 8]),
+        );
+
+        let mut toast = column![header].spacing(6);
+        if let Some(details) = notification
+            .details
+            .as_ref()
+            .filter(|_| notification.expanded)
+        {
+            toast = toast.push(
+                container(scrollable(text(details).size(12)))
+                    .max_height(NOTIFICATION_DETAILS_MAX_HEIGHT),
+            );
+        }
+
+        toasts = toasts.push(
+            container(toast)
+                .style(move |_theme| container::Style {
+                    background: Some(iced::Color::from_rgb(1.0, 0.98, 0.96).into()),
+                    border: iced::Border {
+                        color,
+                        width: 1.0,
+                        radius: 4.0.into(),
+                    },
+                    ..Default::default()
+                })
+                .padding(10)
+                .width(Length::Fill),
+        );
+    }
+
+    toasts.into()
+}
+
+/// Collection import controls shown below the decklist input
+fn collection_row(state: &AppState) -> Element<'_, Message> {
+    let status = match &state.collection {
+        Some((file_name, collection)) => {
+            format!("{}: {} cards", file_name, collection.total_cards())
+        }
+        None => "No collection imported".to_string(),
+    };
+
+    row![
+        button(text("Import Collection...").size(UI_FONT_SIZE))
+            .on_press(Message::ImportCollection)
+            .padding(5),
+        text(status).size(12),
+        checkbox("Missing cards only", state.missing_only)
+            .on_toggle_maybe(
+                state
+                    .collection
+                    .is_some()
+                    .then_some(Message::MissingOnlyToggled)
+            )
+            .text_size(UI_FONT_SIZE),
+    ]
+    .spacing(10)
+    .align_y(iced::Alignment::Center)
+    .into()
+}
+
+/// "Run Diagnostics" button with the report of the last run and a button to copy it
+fn diagnostics_section(state: &AppState) -> Element<'_, Message> {
+    let label = if state.running_diagnostics {
+        tr(Text::RunningDiagnostics)
+    } else {
+        tr(Text::RunDiagnostics)
+    };
+    let mut section = column![
+        row![
+            button(text(label).size(12))
+                .on_press_maybe((!state.running_diagnostics).then_some(Message::RunDiagnostics))
+                .padding(8),
+            button(text(tr(Text::CopyReport)).size(12))
+                .on_press_maybe(
+                    state
+                        .diagnostics_report
+                        .as_ref()
+                        .map(|_| Message::CopyDiagnostics)
+                )
+                .padding(8),
+        ]
+        .spacing(10),
+    ]
+    .spacing(8);
+    if let Some(report) = &state.diagnostics_report {
+        section = section.push(text(report).size(12).font(iced::Font::MONOSPACE));
+    }
+    section.into()
+}
+
+/// Size of a card in the grid preview; grids with more than three cards per row or column
+/// are shrunk to take the space of a 3x3 grid
+fn preview_card_size(options: &PdfOptions) -> (f32, f32) {
+    let cards = options.cards_per_row.max(options.cards_per_column).max(3) as f32;
+    let scale = 3.0 / cards;
+    (GRID_CARD_WIDTH * scale, GRID_CARD_HEIGHT * scale)
+}
+
+/// Put a small price line under a grid preview cell
+fn with_price_label(cell: Element<'_, Message>, price: Option<String>) -> Element<'_, Message> {
+    column![cell, text(price.unwrap_or_default()).size(10)]
+        .align_x(iced::Alignment::Center)
+        .into()
+}
+
+/// Grid preview cell that opens the print selection when clicked and can be dragged onto
+/// another card to print it there instead
+fn draggable_card<'a>(
+    content: impl Into<Element<'a, Message>>,
+    entry_index: usize,
+) -> Element<'a, Message> {
+    mouse_area(content)
+        .on_press(Message::CardPressed(entry_index))
+        .on_enter(Message::CardDraggedOver(entry_index))
+        .interaction(mouse::Interaction::Grab)
+        .into()
+}
+
+/// Card-sized box with a text, standing in for a card image that isn't there (yet)
+fn placeholder_card<'a>(
+    label: String,
+    size: u16,
+    width: f32,
+    height: f32,
+) -> container::Container<'a, Message> {
+    container(text(label).size(size))
+        .width(Length::Fixed(width))
+        .height(Length::Fixed(height))
+        .style(container::rounded_box)
+}
+
+/// Divider row of the preview grid: the group label above a thin rule across the row
+fn divider_row<'a>(label: &str, width: f32, height: f32) -> Element<'a, Message> {
+    container(
+        column![
+            text(label.to_string()).size(UI_FONT_SIZE),
+            rule::Rule::horizontal(1.0)
+        ]
+        .spacing(2),
+    )
+    .width(Length::Fixed(width))
+    .height(Length::Fixed(height))
+    .padding(4)
+    .center_y(Length::Fixed(height))
+    .into()
+}
+
+/// The printings chosen in the preview with their copies, for the normalized decklist
+fn normalized_decklist_cards(
+    grid_preview: &GridPreview,
+) -> Vec<(DeckSection, Card, u32, DoubleFaceMode)> {
+    grid_preview
+        .entries
+        .iter()
+        .filter(|entry| !entry.is_filler)
+        .flat_map(|entry| {
+            let decklist_entry = &entry.decklist_entry;
+            entry
+                .printing_quantities()
+                .into_iter()
+                .filter_map(move |(printing_index, copies)| {
+                    let card = entry.available_printings.get(printing_index)?;
+                    Some((
+                        decklist_entry.section,
+                        card.clone(),
+                        copies,
+                        decklist_entry.face_mode.clone(),
+                    ))
+                })
+        })
+        .collect()
+}
+
+/// Recompute grid positions of the current preview for the current page layout
+fn relayout_grid_preview(state: &mut AppState) {
+    if let Some(grid_preview) = state.grid_preview.as_mut() {
+        grid_preview.layout(&state.pdf_options);
+    }
+    sync_page_navigation(state);
+}
+
+/// Recompute the pages from the entry whose printings changed on, staying with the cards the
+/// user was looking at
+fn relayout_grid_preview_entry(state: &mut AppState, entry_index: usize) {
+    if let Some(grid_preview) = state.grid_preview.as_mut() {
+        grid_preview.relayout_entry(entry_index, &state.pdf_options);
+    }
+    sync_page_navigation(state);
+}
+
+/// Show the page count and current page of the re-laid out preview
+fn sync_page_navigation(state: &mut AppState) {
+    if let (Some(grid_preview), Some(page_nav)) =
+        (state.grid_preview.as_ref(), state.page_navigation.as_mut())
+    {
+        page_nav.total_pages = grid_preview.total_pages;
+        page_nav.update_navigation_state(grid_preview.current_page);
+    }
+}
+
+/// Re-apply the print selection filter to the open entry and paginate the matching printings
+fn apply_print_selection_filter(state: &mut AppState) {
+    let Some(grid_preview) = state.grid_preview.as_mut() else {
+        return;
+    };
+    let Some(entry) = grid_preview
+        .selected_entry_index
+        .and_then(|entry_index| grid_preview.entries.get(entry_index))
+    else {
+        return;
+    };
+
+    state.filtered_printings = state
+        .print_selection_filter
+        .matching_indices(&entry.available_printings);
+    // Favorite printings come first, otherwise in Scryfall's order
+    state
+        .filtered_printings
+        .sort_by_key(|&index| !is_favorite_printing(&entry.available_printings[index]));
+
+    // Start keyboard navigation at the current selection if it is still visible, on its page
+    let focus = entry
+        .selected_printing
+        .and_then(|selected| {
+            state
+                .filtered_printings
+                .iter()
+                .position(|&index| index == selected)
+        })
+        .unwrap_or(0);
+
+    let mut print_grid = PaginatedGrid::new(state.filtered_printings.len(), PRINTS_PER_PAGE);
+    print_grid.current_page = focus / PRINTS_PER_PAGE;
+    grid_preview.print_selection_grid = Some(print_grid);
+    state.print_selection_focus = (!state.filtered_printings.is_empty()).then_some(focus);
+}
+
+/// Filter controls shown above the print selection grid
+fn print_filter_controls<'a>(filter: &SearchFilter, entry: &PreviewEntry) -> Element<'a, Message> {
+    let printings = &entry.available_printings;
+
+    row![
+        text("Set:").size(UI_FONT_SIZE),
+        pick_list(
+            FilterChoice::options(available_sets(printings)),
+            Some(FilterChoice::from_filter(&filter.set)),
+            Message::PrintFilterSetChanged,
+        )
+        .text_size(UI_FONT_SIZE),
+        text("Language:").size(UI_FONT_SIZE),
+        pick_list(
+            FilterChoice::options(available_languages(printings)),
+            Some(FilterChoice::from_filter(&filter.language)),
+            Message::PrintFilterLanguageChanged,
+        )
+        .text_size(UI_FONT_SIZE),
+        text("Frame:").size(UI_FONT_SIZE),
+        pick_list(
+            FilterChoice::options(available_frames(printings)),
+            Some(FilterChoice::from_filter(&filter.frame)),
+            Message::PrintFilterFrameChanged,
+        )
+        .text_size(UI_FONT_SIZE),
+        checkbox("Paper only", filter.paper_only)
+            .on_toggle(Message::PrintFilterPaperOnlyToggled)
+            .text_size(UI_FONT_SIZE),
+        button(text("Clear filters").size(UI_FONT_SIZE))
+            .on_press_maybe(filter.is_active().then_some(Message::ClearPrintFilters))
+            .padding(5),
+    ]
+    .spacing(8)
+    .align_y(iced::Alignment::Center)
+    .into()
+}
+
+/// Move the highlighted printing within the print selection grid, following it across pages
+fn move_print_selection_focus(state: &mut AppState, direction: Shortcut) {
+    let Some(print_grid) = state
+        .grid_preview
+        .as_mut()
+        .and_then(|grid_preview| grid_preview.print_selection_grid.as_mut())
+    else {
+        return;
+    };
+    if print_grid.total_items == 0 {
+        return;
+    }
+
+    let focus = state.print_selection_focus.unwrap_or(0);
+    let last = print_grid.total_items - 1;
+    let new_focus = match direction {
+        Shortcut::MoveLeft => focus.saturating_sub(1),
+        Shortcut::MoveRight => (focus + 1).min(last),
+        Shortcut::MoveUp => focus.checked_sub(PRINT_SELECTION_COLUMNS).unwrap_or(focus),
+        Shortcut::MoveDown if focus + PRINT_SELECTION_COLUMNS <= last => {
+            focus + PRINT_SELECTION_COLUMNS
+        }
+        _ => focus,
+    };
+
+    state.print_selection_focus = Some(new_focus);
+    print_grid.current_page = new_focus / print_grid.items_per_page;
+}
+
+/// Apply a change to the PDF options, persist it, and re-layout the preview to match
+fn update_pdf_options(state: &mut AppState, change: impl FnOnce(&mut PdfOptions)) {
+    change(&mut state.pdf_options);
+
+    let pdf_options = state.pdf_options.clone();
+    if let Err(e) = update_settings(|settings| settings.pdf_options = pdf_options) {
+        tracing::warn!("Failed to save settings: {}", e);
+    }
+
+    relayout_grid_preview(state);
+}
+
+/// Scryfall language codes to prefer printings in, English first
+fn card_languages() -> Vec<String> {
+    let mut languages: Vec<String> = get_minimal_scryfall_languages().into_iter().collect();
+    languages.sort_by_key(|language| (language != "en", language.clone()));
+    languages
+}
+
+fn update_output_options(state: &mut AppState, change: impl FnOnce(&mut OutputOptions)) {
+    change(&mut state.output_options);
+
+    let output_options = state.output_options.clone();
+    if let Err(e) = update_settings(|settings| settings.output = output_options) {
+        tracing::warn!("Failed to save settings: {}", e);
+    }
+}
+
+/// Collapsible panel searching Scryfall with its own query syntax; results can be added to
+/// the decklist
+fn search_panel(state: &AppState) -> Element<'_, Message> {
+    let query_row = row![
+        text_input(tr(Text::SearchQueryPlaceholder), &state.search_query)
+            .on_input(Message::SearchQueryChanged)
+            .on_submit(Message::RunSearchQuery)
+            .size(UI_FONT_SIZE)
+            .width(Length::Fixed(500.0)),
+        button(
+            text(if state.is_searching {
+                tr(Text::Searching)
+            } else {
+                tr(Text::Search)
+            })
+            .size(UI_FONT_SIZE)
+        )
+        .on_press_maybe((!state.is_searching).then_some(Message::RunSearchQuery))
+        .padding(8),
+    ]
+    .spacing(10)
+    .align_y(iced::Alignment::Center);
+
+    let mut panel = column![text(tr(Text::ScryfallSearch)).size(16), query_row].spacing(10);
+
+    if let Some(results) = &state.search_results {
+        let listed = results.cards.len().min(MAX_LISTED_SEARCH_RESULTS);
+        let rows: Vec<Element<Message>> = results.cards[..listed]
+            .iter()
+            .enumerate()
+            .map(|(index, card)| {
+                let line = match &card.type_line {
+                    Some(type_line) => format!("{} — {}", card.caption(), type_line),
+                    None => card.caption(),
+                };
+                let label: Element<Message> = match card.details() {
+                    Some(details) => tooltip(
+                        text(line).size(UI_FONT_SIZE),
+                        container(text(details).size(12))
+                            .padding(8)
+                            .max_width(320)
+                            .style(container::rounded_box),
+                        tooltip::Position::Bottom,
+                    )
+                    .into(),
+                    None => text(line).size(UI_FONT_SIZE).into(),
+                };
+                row![
+                    button(text(tr(Text::AddToDecklist)).size(12))
+                        .on_press(Message::AddSearchResult(index))
+                        .padding([2, 8]),
+                    label,
+                ]
+                .spacing(10)
+                .align_y(iced::Alignment::Center)
+                .into()
+            })
+            .collect();
+
+        panel = panel
+            .push(
+                text(tr_with(
+                    Text::SearchResultsFound,
+                    &[&results.total_found, &listed],
+                ))
+                .size(12),
+            )
+            .push(scrollable(column(rows).spacing(4)).height(Length::Fixed(250.0)));
     }
-    Task::none()
+
+    container(panel)
+        .style(|_theme| container::Style {
+            background: Some(iced::Color::from_rgb(0.97, 0.97, 0.99).into()),
+            border: iced::Border {
+                color: iced::Color::from_rgb(0.85, 0.85, 0.9),
+                width: 1.0,
+                radius: 4.0.into(),
+            },
+            ..Default::default()
+        })
+        .padding(12)
+        .into()
+}
+
+/// The printings with images in the image cache, ✓ marking those with all their images
+fn cached_cards_list(contents: &ImageCacheContents) -> Element<'_, Message> {
+    let mut summary = tr_with(
+        Text::CachedCardsSummary,
+        &[
+            &contents.printings.len(),
+            &contents.cards().len(),
+            &contents.offline_ready_cards().len(),
+        ],
+    );
+    if contents.unknown_images > 0 {
+        summary.push('\n');
+        summary.push_str(&tr_with(
+            Text::UnknownCachedImages,
+            &[&contents.unknown_images],
+        ));
+    }
+    let printings = contents.printings.iter().map(|printing| {
+        let marker = if printing.is_complete() { "✓" } else { "  " };
+        text(format!("{} {}", marker, printing)).size(12).into()
+    });
+    column![
+        text(summary).size(12),
+        scrollable(column(printings).spacing(2)).height(Length::Fixed(200.0)),
+    ]
+    .spacing(6)
+    .into()
+}
+
+/// The pinned decks with how many of their images are cached, each with a button to unpin it
+fn pinned_decks_list(pinned_decks: &PinnedDecks) -> Element<'_, Message> {
+    let decks = pinned_decks.decks.iter().map(|(name, urls)| {
+        let cached = count_cached_images(urls.iter().map(String::as_str));
+        row![
+            text(tr_with(
+                Text::PinnedDeckImages,
+                &[name, &cached, &urls.len()]
+            ))
+            .size(12),
+            button(text(tr(Text::Unpin)).size(12))
+                .on_press(Message::UnpinDeck(name.clone()))
+                .padding(4),
+        ]
+        .spacing(10)
+        .align_y(iced::Alignment::Center)
+        .into()
+    });
+    column(decks).spacing(4).into()
+}
+
+/// Collapsible panel with the PDF layout options, applied to both the preview and generated PDFs
+fn pdf_options_panel<'a>(
+    options: &'a PdfOptions,
+    output: &'a OutputOptions,
+    calibration_measurement: &'a str,
+    filler_cards_text: &'a str,
+    basic_land_sets_text: &'a str,
+) -> Element<'a, Message> {
+    let counts: Vec<u32> = (1..=MAX_CARDS_PER_ROW_OR_COLUMN).collect();
+
+    let validation_text = match options.validate() {
+        Ok(()) => text(format!(
+            "{} cards per page ({}x{})",
+            options.cards_per_page(),
+            options.cards_per_row,
+            options.cards_per_column
+        ))
+        .size(12),
+        Err(e) => text(e.to_string())
+            .size(12)
+            .color(iced::Color::from_rgb(0.8, 0.2, 0.2)),
+    };
+
+    container(
+        column![
+            row![
+                text(tr(Text::PageSize)).size(UI_FONT_SIZE),
+                pick_list(
+                    PageSize::presets(),
+                    Some(options.page_size.clone()),
+                    Message::PageSizeChanged,
+                )
+                .text_size(UI_FONT_SIZE)
+                .width(Length::Fixed(100.0)),
+                text(tr(Text::Rows)).size(UI_FONT_SIZE),
+                pick_list(
+                    counts.clone(),
+                    Some(options.cards_per_column),
+                    Message::CardsPerColumnChanged,
+                )
+                .text_size(UI_FONT_SIZE),
+                text(tr(Text::Columns)).size(UI_FONT_SIZE),
+                pick_list(
+                    counts,
+                    Some(options.cards_per_row),
+                    Message::CardsPerRowChanged,
+                )
+                .text_size(UI_FONT_SIZE),
+                text(tr(Text::ImageQuality)).size(UI_FONT_SIZE),
+                pick_list(
+                    ImageQuality::all(),
+                    Some(options.image_quality),
+                    Message::ImageQualityChanged,
+                )
+                .text_size(UI_FONT_SIZE)
+                .width(Length::Fixed(100.0)),
+            ]
+            .spacing(8)
+            .align_y(iced::Alignment::Center),
+            row![
+                // The grid is always centered; the margin is the least it may leave
+                text(format!("Minimum margin: {:.1} mm", options.margin)).size(UI_FONT_SIZE),
+                slider(0.0..=MAX_MARGIN_MM, options.margin, Message::MarginChanged)
+                    .step(0.5_f32)
+                    .width(Length::Fixed(200.0)),
+                checkbox("Cut markers", options.cut_markers)
+                    .on_toggle(Message::CutMarkersToggled)
+                    .text_size(UI_FONT_SIZE),
+                checkbox("Card captions", options.card_captions)
+                    .on_toggle(Message::CardCaptionsToggled)
+                    .text_size(UI_FONT_SIZE),
+                checkbox("Group dividers", options.group_dividers)
+                    .on_toggle(Message::GroupDividersToggled)
+                    .text_size(UI_FONT_SIZE),
+                checkbox("Text only (no images)", options.text_only)
+                    .on_toggle(Message::TextOnlyToggled)
+                    .text_size(UI_FONT_SIZE),
+            ]
+            .spacing(8)
+            .align_y(iced::Alignment::Center),
+            row![
+                checkbox("Commanders on own page", options.commanders_on_own_page)
+                    .on_toggle(Message::CommandersOnOwnPageToggled)
+                    .text_size(UI_FONT_SIZE),
+                text(tr(Text::Sections)).size(UI_FONT_SIZE),
+                pick_list(
+                    SectionSplit::all(),
+                    Some(options.section_split),
+                    Message::SectionSplitChanged,
+                )
+                .text_size(UI_FONT_SIZE),
+                checkbox("Duplicate commanders", options.duplicate_commanders)
+                    .on_toggle(Message::DuplicateCommandersToggled)
+                    .text_size(UI_FONT_SIZE),
+                checkbox("PDF/A (for picky printers)", options.pdf_a)
+                    .on_toggle(Message::PdfAToggled)
+                    .text_size(UI_FONT_SIZE),
+                text(tr(Text::CardOrder)).size(UI_FONT_SIZE),
+                pick_list(
+                    SortOrder::all(),
+                    Some(options.sort_order),
+                    Message::SortOrderChanged,
+                )
+                .text_size(UI_FONT_SIZE),
+                text(tr(Text::Copies)).size(UI_FONT_SIZE),
+                pick_list(
+                    CopyOrder::all(),
+                    Some(options.copy_order),
+                    Message::CopyOrderChanged,
+                )
+                .text_size(UI_FONT_SIZE),
+            ]
+            .spacing(8)
+            .align_y(iced::Alignment::Center),
+            row![
+                text(tr(Text::FileName)).size(UI_FONT_SIZE),
+                tooltip(
+                    text_input(DEFAULT_FILE_NAME_TEMPLATE, &output.file_name_template)
+                        .on_input(Message::FileNameTemplateChanged)
+                        .size(UI_FONT_SIZE)
+                        .width(Length::Fixed(180.0)),
+                    container(text(tr(Text::FileNameTemplateHelp)).size(12))
+                        .padding(6)
+                        .style(container::rounded_box),
+                    tooltip::Position::Bottom,
+                ),
+                text(tr(Text::ExistingFiles)).size(UI_FONT_SIZE),
+                pick_list(
+                    OverwritePolicy::all(),
+                    Some(output.overwrite),
+                    Message::OverwritePolicyChanged,
+                )
+                .text_size(UI_FONT_SIZE),
+                text(tr(Text::PagesPerFile)).size(UI_FONT_SIZE),
+                text_input(
+                    tr(Text::AllPages),
+                    &output
+                        .pages_per_file
+                        .map(|pages| pages.to_string())
+                        .unwrap_or_default(),
+                )
+                .on_input(Message::PagesPerFileChanged)
+                .size(UI_FONT_SIZE)
+                .width(Length::Fixed(60.0)),
+            ]
+            .spacing(8)
+            .align_y(iced::Alignment::Center),
+            row![
+                text(tr_with(
+                    Text::ScaleCorrection,
+                    &[&format!("{:.4}", options.scale_correction)]
+                ))
+                .size(UI_FONT_SIZE),
+                button(text(tr(Text::CalibrationPage)).size(12))
+                    .on_press(Message::SaveCalibrationPage),
+                text_input(tr(Text::MeasuredRuler), calibration_measurement)
+                    .on_input(Message::CalibrationMeasurementChanged)
+                    .on_submit(Message::ApplyCalibration)
+                    .size(UI_FONT_SIZE)
+                    .width(Length::Fixed(140.0)),
+                button(text(tr(Text::ApplyCalibration)).size(12))
+                    .on_press(Message::ApplyCalibration),
+                button(text(tr(Text::ResetCalibration)).size(12))
+                    .on_press(Message::ResetCalibration),
+            ]
+            .spacing(8)
+            .align_y(iced::Alignment::Center),
+            row![
+                text(tr(Text::FillerCards)).size(UI_FONT_SIZE),
+                text_input(tr(Text::FillerCardsPlaceholder), filler_cards_text)
+                    .on_input(Message::FillerCardsChanged)
+                    .on_submit(Message::ApplyFillerCards)
+                    .size(UI_FONT_SIZE)
+                    .width(Length::Fixed(300.0)),
+                button(text(tr(Text::ApplyFillerCards)).size(12))
+                    .on_press(Message::ApplyFillerCards),
+            ]
+            .spacing(8)
+            .align_y(iced::Alignment::Center),
+            row![
+                text(tr(Text::BasicLandSets)).size(UI_FONT_SIZE),
+                text_input(tr(Text::BasicLandSetsPlaceholder), basic_land_sets_text)
+                    .on_input(Message::BasicLandSetsChanged)
+                    .on_submit(Message::ApplyBasicLandSets)
+                    .size(UI_FONT_SIZE)
+                    .width(Length::Fixed(300.0)),
+                button(text(tr(Text::ApplyFillerCards)).size(12))
+                    .on_press(Message::ApplyBasicLandSets),
+            ]
+            .spacing(8)
+            .align_y(iced::Alignment::Center),
+            validation_text,
+        ]
+        .spacing(8),
+    )
+    .style(|_theme| container::Style {
+        background: Some(iced::Color::from_rgb(0.95, 0.95, 0.95).into()),
+        border: iced::Border {
+            color: iced::Color::from_rgb(0.8, 0.8, 0.8),
+            width: 1.0,
+            radius: 4.0.into(),
+        },
+        ..Default::default()
+    })
+    .padding(8)
+    .into()
 }
 
 pub fn view(state: &AppState) -> Element<'_, Message> {
     // Left side: Decklist input (text field only)
     let decklist_input_section = column![
-        text("Decklist Parser:").size(18),
-        text("Paste your decklist below (supports various formats):").size(14),
+        text(tr(Text::DecklistParser)).size(18),
+        row![
+            text(tr(Text::PasteDecklist)).size(14),
+            horizontal_space(),
+            pick_list(
+                state.recent_decklists.as_slice(),
+                None::<RecentDecklist>,
+                Message::RecentDecklistSelected,
+            )
+            .placeholder(tr(Text::RecentDecklists))
+            .text_size(UI_FONT_SIZE)
+            .width(Length::Fixed(220.0)),
+        ]
+        .width(Length::Fixed(600.0))
+        .align_y(iced::Alignment::Center),
         text_editor(&state.decklist_content)
             .on_action(Message::DecklistAction)
+            // Ctrl+Enter generates instead of inserting a newline while editing the decklist
+            .key_binding(|key_press| {
+                if matches!(key_press.key, Key::Named(Named::Enter))
+                    && key_press.modifiers.command()
+                {
+                    Some(text_editor::Binding::Custom(Message::Shortcut(
+                        Shortcut::GenerateAndSave,
+                    )))
+                } else {
+                    text_editor::Binding::from_key_press(key_press)
+                }
+            })
             .height(Length::Fixed(400.0))
             .width(600.0) // Increased width to accommodate longer parsed entries
             .font(iced::Font::MONOSPACE), // Use monospace font for better alignment with parsed output
+        collection_row(state),
     ]
     .spacing(10)
     .width(Length::Fixed(650.0)); // Container width slightly larger than text field
@@ -751,7 +3339,7 @@ pub fn view(state: &AppState) -> Element<'_, Message> {
     // Button row: action buttons and settings with better visual grouping
     let button_row = row![
         // Action buttons group
-        button(text("PDF from Decklist").size(UI_FONT_SIZE))
+        button(text(tr(Text::PdfFromDecklist)).size(UI_FONT_SIZE))
             .on_press_maybe(if state.is_generating_pdf || state.is_parsing {
                 None
             } else {
@@ -759,7 +3347,7 @@ pub fn view(state: &AppState) -> Element<'_, Message> {
             })
             .padding(10)
             .width(Length::Fixed(140.0)),
-        button(text("Preview").size(UI_FONT_SIZE))
+        button(text(tr(Text::Preview)).size(UI_FONT_SIZE))
             .on_press_maybe(if state.is_parsing {
                 None
             } else {
@@ -767,7 +3355,7 @@ pub fn view(state: &AppState) -> Element<'_, Message> {
             })
             .padding(10)
             .width(Length::Fixed(100.0)),
-        button(text("PDF from Preview").size(UI_FONT_SIZE))
+        button(text(tr(Text::PdfFromPreview)).size(UI_FONT_SIZE))
             .on_press_maybe(
                 if state.is_generating_pdf || state.parsed_cards.is_empty() {
                     None
@@ -777,19 +3365,60 @@ pub fn view(state: &AppState) -> Element<'_, Message> {
             )
             .padding(10)
             .width(Length::Fixed(140.0)),
+        button(text(tr(Text::CopyDecklist)).size(UI_FONT_SIZE))
+            .on_press_maybe(state.grid_preview.as_ref().map(|_| Message::CopyDecklist))
+            .padding(10)
+            .width(Length::Fixed(140.0)),
+        button(text(tr(Text::Print)).size(UI_FONT_SIZE))
+            .on_press_maybe(
+                if state.is_generating_pdf || state.parsed_cards.is_empty() {
+                    None
+                } else {
+                    Some(Message::PrintPdf)
+                }
+            )
+            .padding(10)
+            .width(Length::Fixed(100.0)),
         // Visual separator
         container(text("")).width(Length::Fixed(20.0)),
         // Settings group
         container(
             row![
-                text("Face Mode:").size(UI_FONT_SIZE),
+                text(tr(Text::FaceMode)).size(UI_FONT_SIZE),
                 pick_list(
                     DoubleFaceMode::all(),
-                    Some(state.double_face_mode.clone()),
+                    Some(state.pdf_options.double_face_mode.clone()),
                     Message::DoubleFaceModeChanged,
                 )
                 .text_size(UI_FONT_SIZE)
                 .width(Length::Fixed(120.0)),
+                text(tr(Text::MissingBackFace)).size(UI_FONT_SIZE),
+                pick_list(
+                    BackFaceFallback::all(),
+                    Some(get_back_face_fallback()),
+                    Message::BackFaceFallbackChanged,
+                )
+                .text_size(UI_FONT_SIZE)
+                .width(Length::Fixed(140.0)),
+                checkbox(tr(Text::DefaultPrinting), get_default_printing())
+                    .on_toggle(Message::DefaultPrintingToggled)
+                    .text_size(UI_FONT_SIZE),
+                checkbox(tr(Text::CommentGroups), get_comment_annotations())
+                    .on_toggle(Message::CommentGroupsToggled)
+                    .text_size(UI_FONT_SIZE),
+                text(tr(Text::PreferredLanguage)).size(UI_FONT_SIZE),
+                pick_list(
+                    card_languages(),
+                    Some(get_preferred_language().unwrap_or_else(|| "en".to_string())),
+                    Message::PreferredLanguageChanged,
+                )
+                .text_size(UI_FONT_SIZE)
+                .width(Length::Fixed(70.0)),
+                text(tr(Text::MaxDownloadRate)).size(UI_FONT_SIZE),
+                text_input("∞", &state.download_rate_text)
+                    .on_input(Message::DownloadRateChanged)
+                    .size(UI_FONT_SIZE)
+                    .width(Length::Fixed(70.0)),
             ]
             .spacing(8)
             .align_y(iced::Alignment::Center)
@@ -804,6 +3433,30 @@ pub fn view(state: &AppState) -> Element<'_, Message> {
             ..Default::default()
         })
         .padding(8),
+        button(
+            text(if state.show_pdf_options {
+                "PDF Options ▾"
+            } else {
+                "PDF Options ▸"
+            })
+            .size(UI_FONT_SIZE)
+        )
+        .on_press(Message::TogglePdfOptions)
+        .padding(10),
+        button(
+            text(format!(
+                "{} {}",
+                tr(Text::ScryfallSearch),
+                if state.show_search_panel {
+                    "▾"
+                } else {
+                    "▸"
+                }
+            ))
+            .size(UI_FONT_SIZE)
+        )
+        .on_press(Message::ToggleSearchPanel)
+        .padding(10),
     ]
     .spacing(10);
 
@@ -811,12 +3464,39 @@ pub fn view(state: &AppState) -> Element<'_, Message> {
     let parsed_cards_section = if !state.parsed_cards.is_empty() {
         let parsed_text = state.parsed_cards_aligned_text.text();
         column![
-            text(format!("Parsed Cards ({}):", state.parsed_cards.len())).size(18),
-            text("Resolved names, sets, languages, and face modes:").size(14),
+            text(format!(
+                "Parsed Cards ({}, {} format):",
+                state.parsed_cards.len(),
+                state.decklist_format
+            ))
+            .size(18),
+            row![
+                text("Resolved names, sets, languages, and face modes:").size(14),
+                checkbox("Show prices", state.show_prices)
+                    .on_toggle(Message::ShowPricesToggled)
+                    .text_size(UI_FONT_SIZE),
+            ]
+            .spacing(10)
+            .align_y(iced::Alignment::Center),
+            row![
+                text("Check legality:").size(UI_FONT_SIZE),
+                pick_list(
+                    FormatChoice::options(),
+                    Some(
+                        state
+                            .legality_format
+                            .map_or(FormatChoice::NoCheck, FormatChoice::Check)
+                    ),
+                    Message::LegalityFormatChanged,
+                )
+                .text_size(UI_FONT_SIZE),
+            ]
+            .spacing(10)
+            .align_y(iced::Alignment::Center),
             // Container styled to match text_editor appearance but using text widget to avoid greyed-out look
             container(
                 scrollable(
-                    text(parsed_text)
+                    rich_text(aligned_output_spans(&parsed_text))
                         .font(iced::Font::MONOSPACE) // Use monospace font for better alignment
                         .size(16)
                         .line_height(iced::widget::text::LineHeight::Absolute(iced::Pixels(20.0))) // Match text_editor line height
@@ -835,16 +3515,27 @@ pub fn view(state: &AppState) -> Element<'_, Message> {
             .padding(8)
             .height(Length::Fixed(400.0))
             .width(600.0), // Same width as input text field
+            text(match (&state.grid_preview, state.show_prices) {
+                (Some(grid_preview), true) => price_total_text(grid_preview, &state.pdf_options),
+                _ => String::new(),
+            })
+            .size(14),
+            text(legality_warnings(state).unwrap_or_default())
+                .size(14)
+                .color(iced::Color::from_rgb(0.8, 0.2, 0.2)),
+            text(back_face_warnings(state).unwrap_or_default())
+                .size(14)
+                .color(iced::Color::from_rgb(0.8, 0.5, 0.1)),
         ]
         .spacing(10)
         .width(Length::Fixed(650.0)) // Same container width as input section
     } else {
         column![
-            text("Parsed Cards:").size(18),
-            text("Resolved cards will appear here after parsing:").size(14),
+            text(tr(Text::ParsedCards)).size(18),
+            text(tr(Text::ResolvedCardsHint)).size(14),
             // Empty placeholder with same styling
             container(
-                text("Resolved cards will appear here after parsing...")
+                text(tr(Text::ResolvedCardsPlaceholder))
                     .font(iced::Font::MONOSPACE)
                     .size(14)
                     .color(iced::Color::from_rgb(0.6, 0.6, 0.6))
@@ -869,36 +3560,146 @@ pub fn view(state: &AppState) -> Element<'_, Message> {
     // Input section: side-by-side decklist input and parsed cards with minimal gap for visual alignment
     let input_section = row![decklist_input_section, parsed_cards_section,].spacing(5);
 
-    // Combined top section: input + button row below
-    let top_section = column![input_section, button_row,].spacing(15);
+    // Combined top section: input + button row below (+ PDF options and search when expanded)
+    let mut top_section = column![input_section, button_row].spacing(15);
+    if state.show_pdf_options {
+        top_section = top_section.push(pdf_options_panel(
+            &state.pdf_options,
+            &state.output_options,
+            &state.calibration_measurement,
+            &state.filler_cards_text,
+            &state.basic_land_sets_text,
+        ));
+    }
+    if state.show_search_panel {
+        top_section = top_section.push(search_panel(state));
+    }
 
     let pdf_status_section = if state.is_generating_pdf {
-        column![text("Generating PDF and opening save dialog...").size(16),].spacing(5)
-    } else if let Some(pdf_data) = &state.generated_pdf {
+        let status = if state.print_after_generation {
+            tr(Text::GeneratingPdfForPrinting)
+        } else {
+            tr(Text::GeneratingPdf)
+        };
+        let current = state.pdf_progress.current.load(Ordering::Relaxed);
+        let total = state.pdf_progress.total.load(Ordering::Relaxed);
+        column![
+            text(status).size(16),
+            labelled_progress_bar(
+                tr_with(Text::PdfImagesProgress, &[&current, &total]),
+                current,
+                total
+            ),
+        ]
+        .spacing(5)
+    } else if !state.generated_pdfs.is_empty() {
+        let size: usize = state.generated_pdfs.iter().map(Vec::len).sum();
         column![
-            text("PDF Generated!").size(16),
-            text(format!("Size: {} KB", pdf_data.len() / 1024)).size(14),
+            text(tr(Text::PdfGenerated)).size(16),
+            text(tr_with(Text::PdfSize, &[&(size / 1024)])).size(14),
         ]
         .spacing(5)
     } else {
         column![]
     };
 
-    let error_section = if let Some(error) = &state.error_message {
-        column![text("Error:").size(16), text(error).size(14),].spacing(5)
-    } else {
-        column![]
-    };
+    let mut display_section = column![text(&state.display_text).size(16),].spacing(10);
+    if is_scryfall_unavailable() {
+        display_section = display_section.push(
+            text(tr(Text::ScryfallUnavailableUsingCache))
+                .size(14)
+                .color(iced::Color::from_rgb(0.8, 0.5, 0.1)),
+        );
+    }
+    if let Some(estimate) = state.pending_download {
+        display_section = display_section.push(
+            row![
+                text(tr_with(
+                    Text::ConfirmLargeDownload,
+                    &[&estimate.megabytes(), &estimate.images]
+                ))
+                .size(14),
+                button(tr(Text::Download))
+                    .on_press(Message::ConfirmDownload)
+                    .padding(5),
+                button(tr(Text::Cancel))
+                    .on_press(Message::CancelDownload)
+                    .padding(5),
+            ]
+            .spacing(10)
+            .align_y(iced::Alignment::Center),
+        );
+    }
+    // Tokens the cards create are only added when the user asks for them
+    for (index, suggestion) in state.resolution_report.tokens.iter().enumerate() {
+        display_section = display_section.push(
+            row![
+                button(text(tr(Text::AddToDecklist)).size(12))
+                    .on_press(Message::AddSuggestedToken(index))
+                    .padding([2, 8]),
+                text(format!("{} ({})", suggestion.annotation(), suggestion.line)).size(14),
+            ]
+            .spacing(10)
+            .align_y(iced::Alignment::Center),
+        );
+    }
+    if let Some(estimate) = state.pending_print {
+        display_section = display_section.push(
+            row![
+                text(tr_with(Text::ConfirmLargePrint, &[&estimate.pages])).size(14),
+                button(tr(Text::GenerateAnyway))
+                    .on_press(Message::ConfirmPrint)
+                    .padding(5),
+                button(tr(Text::Cancel))
+                    .on_press(Message::CancelPrint)
+                    .padding(5),
+            ]
+            .spacing(10)
+            .align_y(iced::Alignment::Center),
+        );
+    }
 
-    let display_section = column![text(&state.display_text).size(16),].spacing(10);
+    // Background image loading: selected printings first, then the alternatives
+    let loading_section = match (
+        state.background_loader.is_running(),
+        &state.latest_background_progress,
+    ) {
+        (true, Some(progress)) => {
+            let entries_done = if progress.phase == LoadingPhase::Selected {
+                progress.current_entry
+            } else {
+                progress.total_entries
+            };
+            column![
+                labelled_progress_bar(
+                    tr_with(
+                        Text::SelectedImagesProgress,
+                        &[&entries_done, &progress.total_entries]
+                    ),
+                    entries_done,
+                    progress.total_entries,
+                ),
+                labelled_progress_bar(
+                    tr_with(
+                        Text::AlternativeImagesProgress,
+                        &[&progress.alternatives_loaded, &progress.total_alternatives]
+                    ),
+                    progress.alternatives_loaded,
+                    progress.total_alternatives,
+                ),
+            ]
+            .spacing(5)
+        }
+        _ => column![],
+    };
 
     // Note: update_section content moved to sidebar sections
 
-    // Always-visible 3x3 grid section - shows empty placeholders before parsing, gets populated as cards are processed
+    // Always-visible grid section (rows x columns from the PDF options) - shows empty placeholders before parsing, gets populated as cards are processed
     let grid_preview_section = {
         match state.preview_mode {
             PreviewMode::GridPreview | PreviewMode::Hidden => {
-                // Always show 3x3 grid layout - Default mode (both GridPreview and Hidden show grid)
+                // Always show the page grid layout - Default mode (both GridPreview and Hidden show grid)
 
                 // Page navigation controls (only show if we have parsed cards and multiple pages)
                 let page_nav = if let Some(ref _grid_preview) = state.grid_preview {
@@ -933,142 +3734,174 @@ pub fn view(state: &AppState) -> Element<'_, Message> {
                     row![]
                 };
 
-                // Get current page positions or use empty state
-                let current_positions = if let Some(ref grid_preview) = state.grid_preview {
-                    grid_preview.get_current_page_positions()
+                // Get the slots of the current page or use empty state
+                let current_slots = if let Some(ref grid_preview) = state.grid_preview {
+                    grid_preview.get_current_page_slots()
                 } else {
                     Vec::new() // Empty state - will show all empty placeholders
                 };
+                let filled_slots = current_slots.iter().flatten().count();
 
-                // Create a 3x3 grid of cards
+                // Create a grid of cards matching the PDF page layout
+                let cards_per_row = state.pdf_options.cards_per_row as usize;
+                let cards_per_column = state.pdf_options.cards_per_column as usize;
+                let (card_width, card_height) = preview_card_size(&state.pdf_options);
                 let mut grid_rows = Vec::new();
-                for row_idx in 0..3 {
+                let mut images_ready = 0;
+                for row_idx in 0..cards_per_column {
+                    // A divider row takes the whole row, like in the PDF
+                    let divider = state
+                        .grid_preview
+                        .as_ref()
+                        .and_then(|grid_preview| grid_preview.divider_at(row_idx * cards_per_row));
+                    if let Some(divider) = divider {
+                        grid_rows.push(divider_row(
+                            &divider.label,
+                            cards_per_row as f32 * card_width,
+                            card_height,
+                        ));
+                        continue;
+                    }
+
                     let mut grid_row = Vec::new();
-                    for col_idx in 0..3 {
-                        let position_idx = row_idx * 3 + col_idx;
+                    for col_idx in 0..cards_per_row {
+                        let position_idx = row_idx * cards_per_row + col_idx;
 
-                        if let Some((entry_idx, _position, entry)) =
-                            current_positions.get(position_idx)
+                        if let Some((entry_idx, grid_position, entry)) =
+                            current_slots.get(position_idx).and_then(Option::as_ref)
                         {
-                            // Try to get cached image, fallback to text if not available
-                            let card_widget = if let Some(selected_card) = entry.get_selected_card()
+                            // Each position knows its printing (copies may be split across
+                            // printings) and which image of that printing it shows
+                            let card_widget = if let Some(card) =
+                                entry.available_printings.get(grid_position.printing_index)
                             {
                                 // Get all image URLs that would be generated for this entry's face mode
                                 let image_urls = ProxyGenerator::get_image_urls_for_face_mode(
-                                    selected_card,
+                                    card,
                                     &entry.decklist_entry.face_mode,
                                 );
+                                let image_url = image_urls
+                                    .get(grid_position.image_index)
+                                    .unwrap_or(&card.border_crop);
 
-                                // Find which copy and which image within that copy this position represents
-
-                                // Find the position of this grid slot relative to this entry's images
-                                let entry_positions: Vec<&GridPosition> = entry
-                                    .grid_positions
-                                    .iter()
-                                    .filter(|pos| {
-                                        if let Some(ref grid_preview) = state.grid_preview {
-                                            pos.page == grid_preview.current_page
-                                        } else {
-                                            pos.page == 0 // Default to page 0 if no grid preview yet
-                                        }
-                                    })
-                                    .collect();
-
-                                if let Some(grid_position) = entry_positions
-                                    .iter()
-                                    .find(|pos| pos.position_in_page == position_idx)
-                                {
-                                    // Calculate which image this should be based on copy_number and position within that copy
-
-                                    // Find which image within the copy this position represents
-                                    // by looking at all positions for this copy on all pages
-                                    let copy_positions: Vec<&GridPosition> = entry
-                                        .grid_positions
-                                        .iter()
-                                        .filter(|pos| pos.copy_number == grid_position.copy_number)
-                                        .collect();
-
-                                    let image_index_within_copy = copy_positions
-                                        .iter()
-                                        .position(|pos| {
-                                            pos.page == grid_position.page
-                                                && pos.position_in_page
-                                                    == grid_position.position_in_page
-                                        })
-                                        .unwrap_or(0);
-
-                                    let image_url = image_urls
-                                        .get(image_index_within_copy)
-                                        .unwrap_or(&selected_card.border_crop);
-
-                                    if let Some(image_bytes) = get_cached_image_bytes(image_url) {
-                                        // Display the correct image based on face mode and position
-                                        let image_handle = image::Handle::from_bytes(image_bytes);
-                                        button(
-                                            image::Image::<image::Handle>::new(image_handle)
-                                                .width(Length::Fixed(GRID_CARD_WIDTH))
-                                                .height(Length::Fixed(GRID_CARD_HEIGHT)),
-                                        )
-                                        .on_press(Message::ShowPrintSelection(*entry_idx))
-                                        .width(Length::Fixed(GRID_CARD_WIDTH))
-                                        .height(Length::Fixed(GRID_CARD_HEIGHT))
-                                        .padding(0) // No padding for seamless grid
+                                if let Some(image_bytes) = get_cached_thumbnail_bytes(image_url) {
+                                    images_ready += 1;
+                                    // Display the correct image based on face mode and position
+                                    let image_handle = image::Handle::from_bytes(image_bytes);
+                                    draggable_card(
+                                        image::Image::<image::Handle>::new(image_handle)
+                                            .width(Length::Fixed(card_width))
+                                            .height(Length::Fixed(card_height)),
+                                        *entry_idx,
+                                    )
+                                } else {
+                                    // Fallback to text while image loads
+                                    let face_info = if image_url == &card.border_crop {
+                                        "Front"
                                     } else {
-                                        // Fallback to text while image loads
-                                        let face_info = if image_url == &selected_card.border_crop {
-                                            "Front"
-                                        } else {
-                                            "Back"
-                                        };
-                                        button(
-                                            text(format!(
+                                        "Back"
+                                    };
+                                    draggable_card(
+                                        placeholder_card(
+                                            format!(
                                                 "{}\n[{}]\n{}\nLoading...",
-                                                selected_card.name,
-                                                selected_card.set.to_uppercase(),
+                                                card.name,
+                                                card.set.to_uppercase(),
                                                 face_info
-                                            ))
-                                            .size(8),
-                                        )
-                                        .on_press(Message::ShowPrintSelection(*entry_idx))
-                                        .width(Length::Fixed(GRID_CARD_WIDTH))
-                                        .height(Length::Fixed(GRID_CARD_HEIGHT))
-                                        .padding(0)
-                                    }
-                                } else {
-                                    // Position not found, show fallback
-                                    button(
-                                        text(format!("Error\n{}", entry.decklist_entry.name))
-                                            .size(9),
+                                            ),
+                                            8,
+                                            card_width,
+                                            card_height,
+                                        ),
+                                        *entry_idx,
                                     )
-                                    .on_press(Message::ShowPrintSelection(*entry_idx))
-                                    .width(Length::Fixed(GRID_CARD_WIDTH))
-                                    .height(Length::Fixed(GRID_CARD_HEIGHT))
-                                    .padding(0)
                                 }
                             } else {
                                 // No card selected, show entry name
-                                button(text(entry.decklist_entry.name.clone()).size(10))
-                                    .on_press(Message::ShowPrintSelection(*entry_idx))
-                                    .width(Length::Fixed(GRID_CARD_WIDTH))
-                                    .height(Length::Fixed(GRID_CARD_HEIGHT))
-                                    .padding(0)
+                                draggable_card(
+                                    placeholder_card(
+                                        entry.decklist_entry.name.clone(),
+                                        10,
+                                        card_width,
+                                        card_height,
+                                    ),
+                                    *entry_idx,
+                                )
                             };
 
-                            grid_row.push(container(card_widget).into());
+                            // Show how much of the print selection is ready to browse
+                            let printings = &entry.available_printings;
+                            let card_widget: Element<'_, Message> = if printings.len() > 1 {
+                                let cached = count_cached_images(
+                                    printings.iter().map(|card| card.border_crop.as_str()),
+                                );
+                                stack![
+                                    card_widget,
+                                    printings_badge(cached, printings.len(), card_width)
+                                ]
+                                .into()
+                            } else {
+                                card_widget
+                            };
+                            let card_widget: Element<'_, Message> = if entry.has_back_side() {
+                                stack![
+                                    card_widget,
+                                    face_mode_toggle(*entry_idx, &entry.decklist_entry.face_mode)
+                                ]
+                                .into()
+                            } else {
+                                card_widget
+                            };
+
+                            // Highlight commanders and companions so they stand out in the preview
+                            let is_command_zone = entry.decklist_entry.section.is_command_zone();
+                            let price = entry
+                                .available_printings
+                                .get(grid_position.printing_index)
+                                .and_then(|card| card.price_label());
+                            let cell: Element<'_, Message> = container(card_widget)
+                                .style(move |_theme| container::Style {
+                                    border: iced::Border {
+                                        color: if is_command_zone {
+                                            COMMAND_ZONE_HIGHLIGHT
+                                        } else {
+                                            iced::Color::TRANSPARENT
+                                        },
+                                        width: 2.0,
+                                        radius: 0.0.into(),
+                                    },
+                                    ..Default::default()
+                                })
+                                .into();
+                            grid_row.push(if state.show_prices {
+                                with_price_label(cell, price)
+                            } else {
+                                cell
+                            });
                         } else {
                             // Empty slot - show visual placeholder only (no text)
                             let empty_slot = container(text(""))
-                                .width(Length::Fixed(GRID_CARD_WIDTH))
-                                .height(Length::Fixed(GRID_CARD_HEIGHT))
-                                .center_x(Length::Fixed(GRID_CARD_WIDTH))
-                                .center_y(Length::Fixed(GRID_CARD_HEIGHT));
+                                .width(Length::Fixed(card_width))
+                                .height(Length::Fixed(card_height))
+                                .center_x(Length::Fixed(card_width))
+                                .center_y(Length::Fixed(card_height));
 
-                            grid_row.push(empty_slot.into());
+                            grid_row.push(if state.show_prices {
+                                with_price_label(empty_slot.into(), None)
+                            } else {
+                                empty_slot.into()
+                            });
                         }
                     }
                     grid_rows.push(row(grid_row).spacing(0).into()); // No spacing between cards
                 }
 
+                let page_images = labelled_progress_bar(
+                    tr_with(Text::PageImagesReady, &[&images_ready, &filled_slots]),
+                    images_ready,
+                    filled_slots,
+                );
+
                 if state.is_building_preview {
                     column![
                         text("Grid Preview: Building...").size(16),
@@ -1080,6 +3913,7 @@ pub fn view(state: &AppState) -> Element<'_, Message> {
                     column![
                         text("Grid Preview:").size(16),
                         page_nav,
+                        page_images,
                         column(grid_rows).spacing(0),
                     ]
                     .spacing(10)
@@ -1095,7 +3929,8 @@ pub fn view(state: &AppState) -> Element<'_, Message> {
                         if let Some(entry) = grid_preview.entries.get(selected_entry_idx) {
                             let modal_title = format!(
                                 "Select printing for {}x {}",
-                                entry.decklist_entry.multiple, entry.decklist_entry.name
+                                entry.copies(),
+                                entry.decklist_entry.name
                             );
 
                             // Get pagination info from the grid
@@ -1110,21 +3945,24 @@ pub fn view(state: &AppState) -> Element<'_, Message> {
                             );
 
                             // Create buttons only for current page printings (much faster!)
-                            let print_buttons: Vec<Element<Message>> = entry
-                                .available_printings
+                            let print_buttons: Vec<Element<Message>> = state.filtered_printings
+                                [start_idx..end_idx]
                                 .iter()
-                                .skip(start_idx)
-                                .take(end_idx - start_idx)
                                 .enumerate()
-                                .map(|(page_relative_idx, card)| {
-                                    let actual_print_idx = start_idx + page_relative_idx; // Convert back to global index
+                                .filter_map(|(page_relative_idx, &actual_print_idx)| {
+                                    // Filtered positions map back to indices into available_printings
+                                    let card = entry.available_printings.get(actual_print_idx)?;
                                     let is_selected =
                                         entry.selected_printing == Some(actual_print_idx);
+                                    let is_focused = state.print_selection_focus
+                                        == Some(start_idx + page_relative_idx);
+                                    let is_compared =
+                                        state.compared_printings.contains(&actual_print_idx);
 
                                     // Show only the image - cleaner and more space-efficient
                                     let button_content: Element<Message> =
                                         if let Some(image_bytes) =
-                                            get_cached_image_bytes(&card.border_crop)
+                                            get_cached_thumbnail_bytes(&card.border_crop)
                                         {
                                             // Show actual card image thumbnail only
                                             let image_handle =
@@ -1145,13 +3983,78 @@ pub fn view(state: &AppState) -> Element<'_, Message> {
 
                                     // Use different style for selected printing with tooltip
                                     let btn = button(button_content)
-                                        .on_press(Message::SelectPrint {
-                                            entry_index: selected_entry_idx,
-                                            print_index: actual_print_idx,
+                                        .on_press(if state.comparing_printings {
+                                            Message::ComparePrint(actual_print_idx)
+                                        } else {
+                                            Message::SelectPrint {
+                                                entry_index: selected_entry_idx,
+                                                print_index: actual_print_idx,
+                                            }
                                         })
-                                        .padding(if is_selected { 3 } else { 0 }); // Minimal padding, selected gets slight border
+                                        .padding(if is_selected || is_compared { 3 } else { 0 }); // Minimal padding, selected gets slight border
 
-                                    btn.into()
+                                    // Outline the printing highlighted by keyboard navigation
+                                    let outlined =
+                                        container(btn).style(move |_theme| container::Style {
+                                            border: iced::Border {
+                                                color: if is_focused {
+                                                    iced::Color::from_rgb(0.2, 0.4, 0.9)
+                                                } else {
+                                                    iced::Color::TRANSPARENT
+                                                },
+                                                width: 2.0,
+                                                radius: 0.0.into(),
+                                            },
+                                            ..Default::default()
+                                        });
+
+                                    // Mana cost, type line and rules text on hover
+                                    let thumbnail: Element<Message> = match card.details() {
+                                        Some(details) => tooltip(
+                                            outlined,
+                                            container(text(details).size(12))
+                                                .padding(8)
+                                                .max_width(320)
+                                                .style(container::rounded_box),
+                                            tooltip::Position::Right,
+                                        )
+                                        .into(),
+                                        None => outlined.into(),
+                                    };
+
+                                    // Stepper for splitting the entry's copies across printings
+                                    let copies = entry.quantity_for_printing(actual_print_idx);
+                                    let change_copies = |delta| Message::ChangePrintingQuantity {
+                                        entry_index: selected_entry_idx,
+                                        print_index: actual_print_idx,
+                                        delta,
+                                    };
+                                    let favorite = is_favorite_printing(card);
+                                    let stepper = row![
+                                        button(
+                                            text(if favorite { "★" } else { "☆" })
+                                                .size(UI_FONT_SIZE)
+                                        )
+                                        .on_press(Message::ToggleFavoritePrinting(actual_print_idx))
+                                        .padding([0, 4]),
+                                        set_label(&card.set),
+                                        button(text("-").size(UI_FONT_SIZE))
+                                            .on_press_maybe((copies > 0).then(|| change_copies(-1)))
+                                            .padding([0, 8]),
+                                        text(format!("{}x", copies)).size(UI_FONT_SIZE),
+                                        button(text("+").size(UI_FONT_SIZE))
+                                            .on_press(change_copies(1))
+                                            .padding([0, 8]),
+                                    ]
+                                    .spacing(6)
+                                    .align_y(iced::Alignment::Center);
+
+                                    Some(
+                                        column![thumbnail, stepper]
+                                            .spacing(2)
+                                            .align_x(iced::Alignment::Center)
+                                            .into(),
+                                    )
                                 })
                                 .collect();
 
@@ -1171,14 +4074,76 @@ pub fn view(state: &AppState) -> Element<'_, Message> {
                                 }
                             }
 
+                            let selected_details = entry
+                                .get_selected_card()
+                                .and_then(Card::details)
+                                .unwrap_or_default();
+
+                            // Two picked printings replace the grid until the user goes back
+                            let printings_view: Element<Message> = match state.compared_printings[..]
+                            {
+                                [first, second] => column![
+                                    row([first, second].into_iter().filter_map(|print_index| {
+                                        let card = entry.available_printings.get(print_index)?;
+                                        Some(compared_printing(
+                                            card,
+                                            selected_entry_idx,
+                                            print_index,
+                                        ))
+                                    }))
+                                    .spacing(20),
+                                    button(text(tr(Text::BackToPrintings)).size(UI_FONT_SIZE))
+                                        .on_press(Message::ClearComparedPrintings)
+                                        .padding(5),
+                                ]
+                                .spacing(10)
+                                .into(),
+                                _ => column(print_rows).spacing(0).into(),
+                            };
+
                             column![
                             text(modal_title).size(16),
-                            button("Close")
-                                .on_press(Message::ClosePrintSelection)
-                                .padding(5),
+                            text(selected_details).size(12),
+                            row![
+                                button("Close")
+                                    .on_press(Message::ClosePrintSelection)
+                                    .padding(5),
+                                checkbox(tr(Text::ComparePrintings), state.comparing_printings)
+                                    .on_toggle(|_| Message::ToggleCompareMode)
+                                    .text_size(UI_FONT_SIZE),
+                                text(if state.comparing_printings {
+                                    tr(Text::ComparePrintingsHint)
+                                } else {
+                                    ""
+                                })
+                                .size(12),
+                            ]
+                            .spacing(15)
+                            .align_y(iced::Alignment::Center),
+                            print_filter_controls(&state.print_selection_filter, entry),
                             page_nav,
-                            text(format!("Click on a card image to select that printing ({} total printings):", entry.available_printings.len())).size(12),
-                            column(print_rows).spacing(0),
+                            text(if entry.is_split() {
+                                format!("{} copies split across {} printings; use - / + under a printing to change its copies", entry.decklist_entry.multiple, entry.printing_quantities().len())
+                            } else {
+                                "Use - / + under a printing to split copies across several printings".to_string()
+                            }).size(12),
+                            row![
+                                text(if state.print_selection_filter.is_active() {
+                                    format!("Click on a card image to select that printing ({} of {} printings match the filters):", state.filtered_printings.len(), entry.available_printings.len())
+                                } else {
+                                    format!("Click on a card image to select that printing ({} total printings):", entry.available_printings.len())
+                                }).size(12),
+                                // Cards with very many printings are cut off after a few result pages
+                                text(if entry.has_more_printings {
+                                    tr(Text::MorePrintingsOnScryfall)
+                                } else {
+                                    ""
+                                })
+                                .size(12)
+                                .color(iced::Color::from_rgb(0.8, 0.5, 0.1)),
+                            ]
+                            .spacing(5),
+                            printings_view,
                         ]
                         .spacing(10)
                         } else {
@@ -1198,9 +4163,9 @@ pub fn view(state: &AppState) -> Element<'_, Message> {
     let main_content = column![
         top_section,
         display_section,
+        loading_section,
         grid_preview_section,
         pdf_status_section,
-        error_section,
     ]
     .spacing(20);
 
@@ -1210,13 +4175,24 @@ pub fn view(state: &AppState) -> Element<'_, Message> {
             container(
                 column![
                     row![
-                        text("Advanced Options").size(16),
+                        text(tr(Text::AdvancedOptions)).size(16),
                         horizontal_space(),
                         button("×")
                             .on_press(Message::ToggleExtendedPanel)
                             .padding(5)
                     ],
                     rule::Rule::horizontal(1.0),
+                    row![
+                        text(tr(Text::InterfaceLanguage)).size(UI_FONT_SIZE),
+                        pick_list(
+                            UiLanguage::all(),
+                            Some(ui_language()),
+                            Message::UiLanguageChanged,
+                        )
+                        .text_size(UI_FONT_SIZE),
+                    ]
+                    .spacing(10)
+                    .align_y(iced::Alignment::Center),
                     // Card Name Database Section
                     container(
                         column![
@@ -1257,6 +4233,11 @@ pub fn view(state: &AppState) -> Element<'_, Message> {
                                     .unwrap_or_else(|| "• No card name cache found".to_string())
                             )
                             .size(12),
+                            text(format!(
+                                "• Automatic refresh: {}",
+                                get_cache_refresh_status()
+                            ))
+                            .size(12),
                         ]
                         .spacing(8)
                     )
@@ -1304,7 +4285,39 @@ pub fn view(state: &AppState) -> Element<'_, Message> {
                                 format!("• {} images cached\n• {:.1} MB total size", count, size_mb)
                             })
                             .size(12),
+                            button(
+                                text(tr(if state.cached_cards.is_some() {
+                                    Text::HideCachedCards
+                                } else {
+                                    Text::ShowCachedCards
+                                }))
+                                .size(12)
+                            )
+                            .on_press(Message::ToggleCachedCards)
+                            .padding(8),
                         ]
+                        .push_maybe(state.cached_cards.as_ref().map(cached_cards_list))
+                        .push(
+                            row![
+                                text(tr(Text::ImageCacheEviction)).size(12),
+                                pick_list(
+                                    EvictionPolicy::all(),
+                                    Some(get_image_cache_eviction_policy()),
+                                    Message::ImageCacheEvictionChanged,
+                                )
+                                .text_size(12),
+                            ]
+                            .spacing(10)
+                            .align_y(iced::Alignment::Center),
+                        )
+                        .push(
+                            button(text(tr(Text::PinDeck)).size(12))
+                                .on_press_maybe(
+                                    state.grid_preview.as_ref().map(|_| Message::PinDeck)
+                                )
+                                .padding(8),
+                        )
+                        .push(pinned_decks_list(&state.pinned_decks))
                         .spacing(8)
                     )
                     .style(|_theme| container::Style {
@@ -1317,6 +4330,63 @@ pub fn view(state: &AppState) -> Element<'_, Message> {
                         ..Default::default()
                     })
                     .padding(12),
+                    // Diagnostics Section
+                    container(
+                        column![
+                            text(tr(Text::Diagnostics)).size(16),
+                            checkbox(tr(Text::WriteLogFiles), state.log_to_file)
+                                .on_toggle(Message::LogToFileToggled)
+                                .text_size(12),
+                            button(text(tr(Text::ExportLogs)).size(12))
+                                .on_press(Message::ExportLogs)
+                                .padding(8),
+                            text(format!("• {}", get_log_directory_path())).size(12),
+                            diagnostics_section(state),
+                        ]
+                        .spacing(8)
+                    )
+                    .style(|_theme| container::Style {
+                        background: Some(iced::Color::from_rgb(0.97, 0.96, 0.94).into()),
+                        border: iced::Border {
+                            color: iced::Color::from_rgb(0.9, 0.87, 0.82),
+                            width: 1.0,
+                            radius: 3.0.into(),
+                        },
+                        ..Default::default()
+                    })
+                    .padding(12),
+                    // Usage Statistics Section (local only, helps tuning the cache sizes)
+                    container(
+                        column![
+                            text(tr(Text::UsageStatistics)).size(16),
+                            checkbox(tr(Text::CollectUsageStats), state.collect_usage_stats)
+                                .on_toggle(Message::CollectUsageStatsToggled)
+                                .text_size(12),
+                            text(
+                                get_usage_stats()
+                                    .summary()
+                                    .iter()
+                                    .map(|line| format!("• {}", line))
+                                    .collect::<Vec<_>>()
+                                    .join("\n")
+                            )
+                            .size(12),
+                            button(text(tr(Text::ResetUsageStats)).size(12))
+                                .on_press(Message::ResetUsageStats)
+                                .padding(8),
+                        ]
+                        .spacing(8)
+                    )
+                    .style(|_theme| container::Style {
+                        background: Some(iced::Color::from_rgb(0.95, 0.96, 0.98).into()),
+                        border: iced::Border {
+                            color: iced::Color::from_rgb(0.84, 0.86, 0.92),
+                            width: 1.0,
+                            radius: 3.0.into(),
+                        },
+                        ..Default::default()
+                    })
+                    .padding(12),
                 ]
                 .spacing(10),
             )
@@ -1340,7 +4410,7 @@ pub fn view(state: &AppState) -> Element<'_, Message> {
     let sidebar_toggle = if !state.show_extended_panel {
         Some(
             container(
-                button(text("Advanced Options").size(UI_FONT_SIZE))
+                button(text(tr(Text::AdvancedOptions)).size(UI_FONT_SIZE))
                     .on_press(Message::ToggleExtendedPanel)
                     .padding(8)
                     .style(|_theme, _status| button::Style {
@@ -1370,8 +4440,23 @@ pub fn view(state: &AppState) -> Element<'_, Message> {
         row![main_content] // Fallback (shouldn't happen)
     };
 
-    scrollable(container(layout).padding(20))
+    let content = scrollable(container(layout).padding(20))
         .width(Length::Fill)
-        .height(Length::Fill)
+        .height(Length::Fill);
+
+    // Notifications float over the bottom right corner without blocking the rest of the window
+    if state.notifications.is_empty() {
+        content.into()
+    } else {
+        stack![
+            content,
+            container(notification_center(&state.notifications))
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .align_x(iced::alignment::Horizontal::Right)
+                .align_y(iced::alignment::Vertical::Bottom)
+                .padding(20),
+        ]
         .into()
+    }
 }
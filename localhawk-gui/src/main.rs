@@ -1,19 +1,16 @@
 mod app;
-
-fn init_logging() {
-    // Initialize tracing with configurable filtering
-    tracing_subscriber::fmt()
-        .with_env_filter(
-            // Default to info level, but allow override via RUST_LOG
-            // Example: RUST_LOG=localhawk_core::globals=debug,localhawk_core::card_name_cache=debug
-            tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| "localhawk_core=info,localhawk_gui=info".into()),
-        )
-        .init();
-}
+mod notifications;
 
 fn main() -> iced::Result {
-    init_logging();
+    // Before logging starts, since the log files live in the cache directory
+    localhawk_core::set_cache_directory(localhawk_core::get_settings().cache_directory);
+
+    // Default to info level, but allow override via RUST_LOG
+    // Example: RUST_LOG=localhawk_core::globals=debug,localhawk_core::card_name_cache=debug
+    localhawk_core::init_logging(
+        localhawk_core::DEFAULT_LOG_FILTER,
+        localhawk_core::get_settings().log_to_file,
+    );
 
     // Initialize caches at startup
     let rt = tokio::runtime::Runtime::new().unwrap();
@@ -23,8 +20,15 @@ fn main() -> iced::Result {
         std::process::exit(1);
     }
 
-    // Run the GUI application
-    let result = iced::application("LocalHawk", app::update, app::view).run_with(app::initialize);
+    // Run the GUI application, restoring the window size of the last session
+    let mut application = iced::application("LocalHawk", app::update, app::view)
+        .subscription(app::subscription)
+        // The layout is saved when the window closes, then the app closes it
+        .exit_on_close_request(false);
+    if let Some((width, height)) = localhawk_core::get_settings().gui_layout.window_size {
+        application = application.window_size((width, height));
+    }
+    let result = application.run_with(app::initialize);
 
     // Application has exited (user closed window), save caches before returning
     if let Err(e) = rt.block_on(localhawk_core::shutdown_caches()) {
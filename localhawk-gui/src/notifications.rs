@@ -0,0 +1,133 @@
+//! Non-blocking notifications (toasts) for errors and warnings
+//!
+//! Problems from background loading, parsing and PDF generation are collected here instead of
+//! overwriting each other, and stay visible until the user dismisses them.
+//! Errors of operations that may succeed when run again, like a request that timed out, offer
+//! to retry them.
+
+use crate::app::Message;
+use time::OffsetDateTime;
+
+/// Oldest notifications are dropped beyond this
+const MAX_NOTIFICATIONS: usize = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationLevel {
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Clone)]
+pub struct Notification {
+    pub id: u64,
+    pub level: NotificationLevel,
+    pub summary: String,
+    /// Longer explanation, shown when the notification is expanded
+    pub details: Option<String>,
+    pub time: OffsetDateTime,
+    pub expanded: bool,
+    /// Runs the failed operation again, offered as a "Retry" button
+    pub retry: Option<Message>,
+}
+
+impl Notification {
+    /// Local time the notification was raised, e.g. "14:05:32"
+    pub fn time_label(&self) -> String {
+        format!(
+            "{:02}:{:02}:{:02}",
+            self.time.hour(),
+            self.time.minute(),
+            self.time.second()
+        )
+    }
+}
+
+/// Notifications currently shown, newest first
+#[derive(Debug, Default)]
+pub struct Notifications {
+    items: Vec<Notification>,
+    next_id: u64,
+}
+
+impl Notifications {
+    pub fn error(&mut self, summary: impl Into<String>, details: Option<String>) {
+        self.push(NotificationLevel::Error, summary.into(), details, None);
+    }
+
+    /// An error of an operation that may succeed when run again, e.g. after a timeout
+    pub fn error_with_retry(
+        &mut self,
+        summary: impl Into<String>,
+        details: Option<String>,
+        retry: Message,
+    ) {
+        self.push(
+            NotificationLevel::Error,
+            summary.into(),
+            details,
+            Some(retry),
+        );
+    }
+
+    pub fn warning(&mut self, summary: impl Into<String>, details: Option<String>) {
+        self.push(NotificationLevel::Warning, summary.into(), details, None);
+    }
+
+    fn push(
+        &mut self,
+        level: NotificationLevel,
+        summary: String,
+        details: Option<String>,
+        retry: Option<Message>,
+    ) {
+        tracing::debug!("Notification ({:?}): {}", level, summary);
+        // Falls back to UTC where the local offset can't be determined
+        let time = OffsetDateTime::now_local().unwrap_or_else(|_| OffsetDateTime::now_utc());
+        self.items.insert(
+            0,
+            Notification {
+                id: self.next_id,
+                level,
+                summary,
+                details,
+                time,
+                expanded: false,
+                retry,
+            },
+        );
+        self.next_id += 1;
+        self.items.truncate(MAX_NOTIFICATIONS);
+    }
+
+    pub fn dismiss(&mut self, id: u64) {
+        self.items.retain(|notification| notification.id != id);
+    }
+
+    pub fn dismiss_all(&mut self) {
+        self.items.clear();
+    }
+
+    /// Dismiss the notification, returning the message that retries its operation
+    pub fn take_retry(&mut self, id: u64) -> Option<Message> {
+        let position = self.items.iter().position(|n| n.id == id)?;
+        self.items.remove(position).retry
+    }
+
+    pub fn toggle_details(&mut self, id: u64) {
+        if let Some(notification) = self.items.iter_mut().find(|n| n.id == id) {
+            notification.expanded = !notification.expanded;
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Notification> {
+        self.items.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+}
@@ -0,0 +1,323 @@
+//! Headless LocalHawk: a small HTTP API over the core library
+//!
+//! Shares the caches and settings of the GUI and CLI, so a machine on the home network can
+//! resolve decklists and generate proxy PDFs for any device that can send an HTTP request:
+//!
+//! - `POST /decklist` with the decklist as body: how each line was resolved and the tokens
+//!   its cards create (JSON)
+//! - `POST /generate` with `{"decklist": "...", "options": {...}}`: the PDF; `options` are
+//!   PDF options as in the settings file and default to the saved ones. The ETag is the deck
+//!   hash: sending it back as If-None-Match gets 304 Not Modified while the resolved cards
+//!   and options are unchanged, without generating the PDF again
+//! - `GET /cache/stats`: cache sizes and the local usage statistics (JSON)
+
+use axum::Json;
+use axum::Router;
+use axum::http::{HeaderMap, StatusCode, header};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use clap::Parser;
+use localhawk_core::{
+    DEFAULT_LOG_FILTER, FreshnessPolicy, PdfOptions, ProxyError, ProxyGenerator, UsageStats,
+    deck_file_name, get_card_names_cache_size, get_image_cache_info, get_search_results_cache_info,
+    get_settings, get_usage_stats, init_logging, parse_deck_name, set_back_face_fallback,
+    set_basic_land_sets, set_cache_directory, set_comment_annotations, set_default_printing,
+    set_download_rate_limit, set_favorite_printings, set_freshness_policy,
+    set_image_cache_eviction_policy, set_preferred_language, set_ui_language,
+};
+use serde::{Deserialize, Serialize};
+use std::net::SocketAddr;
+use tracing::{info, warn};
+
+#[derive(Parser)]
+#[command(name = "localhawk-server")]
+#[command(about = "An HTTP API for generating card proxy sheets")]
+struct Cli {
+    /// Address to listen on; use 0.0.0.0:8787 to accept requests from other devices
+    #[arg(long, default_value = "127.0.0.1:8787")]
+    bind: SocketAddr,
+    /// Refresh card names and set codes when older than this many days
+    #[arg(long, default_value = "7")]
+    max_cache_age_days: i64,
+    /// Never refresh stale card names and set codes automatically
+    #[arg(long)]
+    no_auto_refresh: bool,
+    /// Also write logs to daily files in the cache directory (always on if enabled in the settings)
+    #[arg(long)]
+    log_file: bool,
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let cli = Cli::parse();
+    let settings = get_settings();
+    // Before logging starts, since the log files live in the cache directory
+    set_cache_directory(settings.cache_directory.clone());
+    init_logging(DEFAULT_LOG_FILTER, cli.log_file || settings.log_to_file);
+    set_ui_language(settings.ui_language);
+    set_back_face_fallback(settings.back_face_fallback);
+    set_default_printing(settings.default_printing);
+    set_comment_annotations(settings.comment_annotations);
+    set_preferred_language(settings.preferred_language.clone());
+    set_basic_land_sets(settings.basic_land_sets.clone());
+    set_favorite_printings(settings.favorite_printings.clone());
+    set_download_rate_limit(settings.max_download_rate_kb);
+    set_image_cache_eviction_policy(settings.image_cache_eviction);
+    set_freshness_policy(FreshnessPolicy {
+        auto_refresh: !cli.no_auto_refresh,
+        ..FreshnessPolicy::from_days(cli.max_cache_age_days)
+    });
+
+    if let Err(e) = localhawk_core::initialize_caches().await {
+        eprintln!("Failed to initialize caches: {}", e);
+        std::process::exit(1);
+    }
+
+    let app = Router::new()
+        .route("/decklist", post(resolve_decklist))
+        .route("/generate", post(generate))
+        .route("/cache/stats", get(cache_stats));
+
+    let listener = tokio::net::TcpListener::bind(cli.bind).await?;
+    info!(address = %cli.bind, "Listening for requests");
+    axum::serve(listener, app)
+        .with_graceful_shutdown(shutdown_signal())
+        .await?;
+
+    info!("Shutting down, saving caches");
+    if let Err(e) = localhawk_core::shutdown_caches().await {
+        warn!(error = %e, "Failed to save caches");
+    }
+    Ok(())
+}
+
+async fn shutdown_signal() {
+    if let Err(e) = tokio::signal::ctrl_c().await {
+        warn!(error = %e, "Failed to listen for Ctrl-C");
+        std::future::pending::<()>().await;
+    }
+}
+
+/// How one decklist entry was resolved
+#[derive(Serialize)]
+struct ResolvedEntry {
+    /// Line in the decklist (1-based)
+    line: Option<usize>,
+    quantity: u32,
+    name: String,
+    section: String,
+    set: Option<String>,
+    lang: Option<String>,
+    /// Printing that would be printed, None if the card wasn't found
+    printing: Option<Printing>,
+}
+
+#[derive(Serialize)]
+struct Printing {
+    name: String,
+    set: String,
+    collector_number: Option<String>,
+    language: String,
+    scryfall_id: Option<String>,
+    price: Option<String>,
+}
+
+/// A token some card of the decklist creates, see ProxyGenerator::suggest_tokens
+#[derive(Serialize)]
+struct SuggestedToken {
+    /// Card whose rules text creates the token
+    source: String,
+    description: String,
+    /// Decklist line to add for the token
+    line: String,
+}
+
+#[derive(Serialize)]
+struct ResolutionReport {
+    entries: Vec<ResolvedEntry>,
+    resolved: usize,
+    unresolved: usize,
+    tokens: Vec<SuggestedToken>,
+}
+
+async fn resolve_decklist(decklist: String) -> Result<Json<ResolutionReport>, ApiError> {
+    let face_mode = get_settings().pdf_options.double_face_mode;
+    let entries = ProxyGenerator::parse_and_resolve_decklist(&decklist, face_mode).await?;
+    info!(entries = entries.len(), "Resolving decklist");
+
+    let mut resolved_entries = Vec::new();
+    let mut selected_cards = Vec::new();
+    for entry in entries {
+        let printings = match ProxyGenerator::search_card(&entry.name).await {
+            Ok(results) => results.cards,
+            Err(e) => {
+                warn!(card_name = %entry.name, error = %e, "Search failed");
+                Vec::new()
+            }
+        };
+        let selected = ProxyGenerator::select_printing_for_entry(&printings, &entry)
+            .and_then(|index| printings.get(index));
+        let printing = selected.map(|card| Printing {
+            name: card.name.clone(),
+            set: card.set.clone(),
+            collector_number: card.collector_number.clone(),
+            language: card.language.clone(),
+            scryfall_id: card.scryfall_id.clone(),
+            price: card.price_label(),
+        });
+        selected_cards.extend(selected.cloned());
+        resolved_entries.push(ResolvedEntry {
+            line: entry.source_line_number.map(|line| line + 1),
+            quantity: entry.multiple.get(),
+            name: entry.name,
+            section: format!("{:?}", entry.section),
+            set: entry.set,
+            lang: entry.lang,
+            printing,
+        });
+    }
+
+    let resolved = resolved_entries
+        .iter()
+        .filter(|entry| entry.printing.is_some())
+        .count();
+    let tokens = ProxyGenerator::suggest_tokens(&selected_cards)
+        .await
+        .into_iter()
+        .map(|suggestion| SuggestedToken {
+            source: suggestion.source,
+            description: suggestion.description,
+            line: suggestion.line,
+        })
+        .collect();
+    Ok(Json(ResolutionReport {
+        unresolved: resolved_entries.len() - resolved,
+        resolved,
+        entries: resolved_entries,
+        tokens,
+    }))
+}
+
+#[derive(Deserialize)]
+struct GenerateRequest {
+    decklist: String,
+    /// PDF options, the saved ones if missing
+    options: Option<PdfOptions>,
+}
+
+async fn generate(
+    headers: HeaderMap,
+    Json(request): Json<GenerateRequest>,
+) -> Result<Response, ApiError> {
+    let mut options = request
+        .options
+        .unwrap_or_else(|| get_settings().pdf_options);
+    let deck_name = parse_deck_name(&request.decklist);
+    if options.title.is_none() {
+        options.title = deck_name.clone();
+    }
+
+    let entries = ProxyGenerator::parse_and_resolve_decklist(
+        &request.decklist,
+        options.double_face_mode.clone(),
+    )
+    .await?;
+    let (card_groups, group_labels) =
+        ProxyGenerator::resolve_decklist_entries_to_labeled_card_groups(&entries, &options).await?;
+    let options = PdfOptions {
+        group_labels,
+        ..options
+    };
+
+    // The client still has the PDF of an unchanged deck
+    let etag = format!(
+        "\"{}\"",
+        ProxyGenerator::deck_hash(&card_groups, &options, None)
+    );
+    let unchanged = headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|tags| tags.split(',').any(|tag| tag.trim() == etag));
+    if unchanged {
+        info!(
+            entries = entries.len(),
+            "Deck unchanged, not generating the PDF again"
+        );
+        return Ok((StatusCode::NOT_MODIFIED, [(header::ETAG, etag)]).into_response());
+    }
+
+    info!(entries = entries.len(), "Generating PDF");
+    let pdf =
+        ProxyGenerator::generate_pdf_from_card_groups(&card_groups, options, |_, _| {}).await?;
+
+    let file_name = deck_name
+        .as_deref()
+        .and_then(deck_file_name)
+        // Plain header values must be ASCII
+        .filter(|name| name.is_ascii())
+        .unwrap_or_else(|| "proxies.pdf".to_string());
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/pdf".to_string()),
+            (header::ETAG, etag),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", file_name),
+            ),
+        ],
+        pdf,
+    )
+        .into_response())
+}
+
+#[derive(Serialize)]
+struct CacheSize {
+    count: usize,
+    size_mb: f64,
+}
+
+impl From<(usize, f64)> for CacheSize {
+    fn from((count, size_mb): (usize, f64)) -> Self {
+        CacheSize { count, size_mb }
+    }
+}
+
+#[derive(Serialize)]
+struct CacheStats {
+    images: CacheSize,
+    search_results: CacheSize,
+    card_names: Option<CacheSize>,
+    /// Only counted while collecting usage statistics is enabled in the settings
+    usage: UsageStats,
+}
+
+async fn cache_stats() -> Json<CacheStats> {
+    Json(CacheStats {
+        images: get_image_cache_info().into(),
+        search_results: get_search_results_cache_info().into(),
+        card_names: get_card_names_cache_size().map(CacheSize::from),
+        usage: get_usage_stats(),
+    })
+}
+
+/// Error response: the message as JSON, with a status matching the kind of error
+struct ApiError(StatusCode, String);
+
+impl From<ProxyError> for ApiError {
+    fn from(error: ProxyError) -> Self {
+        let status = match error {
+            ProxyError::InvalidCard(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            ProxyError::Network(_) => StatusCode::BAD_GATEWAY,
+            ProxyError::ScryfallUnavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        ApiError(status, error.to_string())
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        warn!(status = %self.0, error = %self.1, "Request failed");
+        (self.0, Json(serde_json::json!({ "error": self.1 }))).into_response()
+    }
+}